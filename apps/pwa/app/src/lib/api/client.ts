@@ -2,6 +2,8 @@ const API_BASE = (import.meta.env.VITE_API_BASE || '/api').replace(/\/+$/, '');
 
 const STREAM_ACTIVITY_TIMEOUT = 30_000; // 30 seconds
 const STREAM_CHECK_INTERVAL = 5_000; // Check every 5 seconds
+const STREAM_RESUME_ATTEMPTS = 5; // Reconnects per dropped stream
+const STREAM_RESUME_DELAY = 1_000; // Backoff step between reconnects
 
 // ============================================================================
 // Type Definitions
@@ -14,6 +16,8 @@ export interface SessionResponse {
 	token_count?: number | null;
 	working_dir: string | null;
 	parent_session_id: string | null;
+	/** Parent messages the session was forked with (null unless forked) */
+	fork_point?: number | null;
 	mode: 'build' | 'plan';
 	updated_at: string;
 	model?: string | null;
@@ -44,6 +48,28 @@ export interface SessionWithMessagesResponse {
 	messages: MessageResponse[];
 }
 
+/** Message matching a session search */
+export interface SearchHit {
+	session_id: string;
+	session_title: string;
+	working_dir: string | null;
+	message_id: number;
+	/** Position of the message in its session (0-based) */
+	message_index: number;
+	role: string;
+	snippet: string;
+	created_at: string;
+}
+
+/** A project memory kept across sessions */
+export interface Memory {
+	id: number;
+	working_dir: string;
+	content: string;
+	created_at: string;
+	updated_at: string;
+}
+
 /** Model info */
 export interface ModelInfo {
 	id: string;
@@ -126,6 +152,27 @@ export interface ProviderStatus {
 	supports_oauth: boolean;
 }
 
+/** Signed-in user account */
+export interface UserAccount {
+	id: string;
+	email: string;
+	display_name: string | null;
+	created_at: string;
+}
+
+/** Current authentication state */
+export interface AuthStatusResponse {
+	authenticated: boolean;
+	user: UserAccount | null;
+	setup_required: boolean;
+}
+
+/** Short-lived code for signing in another device */
+export interface PairingCodeResponse {
+	code: string;
+	expires_at: string;
+}
+
 /** OAuth start response */
 export interface OAuthStartResponse {
 	auth_url: string;
@@ -207,6 +254,32 @@ export interface PortListResponse {
 	discovery_error?: string | null;
 }
 
+/** MCP resource or resource template, for @ mentions */
+export interface McpResource {
+	server: string;
+	uri: string;
+	name: string;
+	description: string | null;
+	mime_type: string | null;
+	is_template: boolean;
+}
+
+/** MCP prompt argument */
+export interface McpPromptArgument {
+	name: string;
+	description: string | null;
+	required: boolean;
+}
+
+/** MCP prompt, offered as a slash command */
+export interface McpPrompt {
+	server: string;
+	name: string;
+	command: string;
+	description: string | null;
+	arguments: McpPromptArgument[];
+}
+
 /** SSE stream event types */
 export type StreamEvent =
 	| { type: 'text_delta'; delta: string }
@@ -221,6 +294,14 @@ export type StreamEvent =
 	| { type: 'plan_complete'; tool_call_id: string; title: string; task_count: number }
 	| { type: 'usage'; prompt_tokens: number; completion_tokens: number }
 	| { type: 'title_update'; title: string }
+	| { type: 'context_near_limit'; tokens_used: number; context_window: number }
+	| { type: 'session_pinched'; previous_session_id: string; session_id: string; title: string }
+	| {
+			type: 'conversation_compacted';
+			summarized_messages: number;
+			elided_results: number;
+			tokens_before: number;
+	  }
 	| { type: 'tool_approval_required'; id: string; name: string; arguments: Record<string, unknown> }
 	| { type: 'tool_approved'; id: string }
 	| { type: 'tool_denied'; id: string }
@@ -232,15 +313,16 @@ export type StreamEvent =
 // API Client
 // ============================================================================
 
-// Store for current user ID (set from auth session)
-let currentUserId: string | null = null;
-
-export function setCurrentUserId(userId: string | null) {
-	currentUserId = userId;
-}
+// Pages that must stay reachable while signed out
+const AUTH_ROUTES = ['/login', '/pair'];
+const SIGN_IN_ENDPOINTS = ['/auth/status', '/auth/setup', '/auth/login', '/auth/pair/claim'];
 
-export function getCurrentUserId(): string | null {
-	return currentUserId;
+/** Send the browser to the sign-in page after the server refuses a request */
+function redirectToLogin() {
+	if (typeof window === 'undefined') return;
+	if (AUTH_ROUTES.some((route) => window.location.pathname.startsWith(route))) return;
+	const next = encodeURIComponent(window.location.pathname + window.location.search);
+	window.location.href = `/login?next=${next}`;
 }
 
 export function getApiUrl(path: string): string {
@@ -259,21 +341,23 @@ class ApiError extends Error {
 }
 
 async function request<T>(path: string, options: RequestInit = {}): Promise<T> {
-	// Build headers with optional X-User-Id for multi-tenant auth
 	const headers: Record<string, string> = {
 		'Content-Type': 'application/json',
 		...(options.headers as Record<string, string>)
 	};
 
-	if (currentUserId) {
-		headers['X-User-Id'] = currentUserId;
-	}
-
+	// The session cookie authenticates remote browsers
 	const response = await fetch(getApiUrl(path), {
 		...options,
-		headers
+		headers,
+		credentials: 'include'
 	});
 
+	// Sign-in endpoints report bad credentials with 401 themselves
+	if (response.status === 401 && !SIGN_IN_ENDPOINTS.includes(path)) {
+		redirectToLogin();
+	}
+
 	if (!response.ok) {
 		const error = await response.json().catch(() => ({ error: 'Unknown error' }));
 		throw new ApiError(response.status, error.error || error.message || 'Request failed');
@@ -296,6 +380,13 @@ export const apiClient = {
 
 	getDirectories: () => request<string[]>('/sessions/directories'),
 
+	searchSessions: (q: string, options: { working_dir?: string; limit?: number } = {}) => {
+		const params = new URLSearchParams({ q });
+		if (options.working_dir) params.set('working_dir', options.working_dir);
+		if (options.limit) params.set('limit', String(options.limit));
+		return request<SearchHit[]>(`/sessions/search?${params}`);
+	},
+
 	browseDirectories: (path?: string) =>
 		request<{
 			current: string;
@@ -332,6 +423,22 @@ export const apiClient = {
 			})
 		}),
 
+	forkSession: (id: string, data: { message_count?: number; title?: string; model?: string } = {}) =>
+		request<SessionResponse>(`/sessions/${id}/fork`, {
+			method: 'POST',
+			body: JSON.stringify(data)
+		}),
+
+	/** Download URL for a session export; the session cookie authenticates it */
+	exportSessionUrl: (id: string, format: 'json' | 'markdown' | 'html' = 'json') =>
+		getApiUrl(`/sessions/${id}/export?format=${format}`),
+
+	importSession: (bundle: string, workingDir?: string) =>
+		request<SessionResponse>(
+			`/sessions/import${workingDir ? `?working_dir=${encodeURIComponent(workingDir)}` : ''}`,
+			{ method: 'POST', body: bundle }
+		),
+
 	getSessionState: (id: string) =>
 		request<{
 			id: string;
@@ -395,10 +502,20 @@ export const apiClient = {
 			body: JSON.stringify({ session_id: sessionId, tool_call_id: toolCallId, result })
 		}),
 
-	submitToolApproval: (sessionId: string, toolCallId: string, approved: boolean) =>
+	submitToolApproval: (
+		sessionId: string,
+		toolCallId: string,
+		approved: boolean,
+		alwaysAllow = false
+	) =>
 		request<{ status: string }>('/chat/tool-approval', {
 			method: 'POST',
-			body: JSON.stringify({ session_id: sessionId, tool_call_id: toolCallId, approved })
+			body: JSON.stringify({
+				session_id: sessionId,
+				tool_call_id: toolCallId,
+				approved,
+				always_allow: alwaysAllow
+			})
 		}),
 
 	// Credentials
@@ -413,6 +530,51 @@ export const apiClient = {
 	deleteCredential: (providerId: string) =>
 		request<void>(`/credentials/${providerId}`, { method: 'DELETE' }),
 
+	// Project memory
+	getMemories: (workingDir?: string) =>
+		request<Memory[]>(
+			`/memories${workingDir ? `?working_dir=${encodeURIComponent(workingDir)}` : ''}`
+		),
+
+	createMemory: (workingDir: string, content: string) =>
+		request<Memory>('/memories', {
+			method: 'POST',
+			body: JSON.stringify({ working_dir: workingDir, content })
+		}),
+
+	updateMemory: (id: number, content: string) =>
+		request<Memory>(`/memories/${id}`, {
+			method: 'PATCH',
+			body: JSON.stringify({ content })
+		}),
+
+	deleteMemory: (id: number) => request<void>(`/memories/${id}`, { method: 'DELETE' }),
+
+	// Account auth
+	getAuthStatus: () => request<AuthStatusResponse>('/auth/status'),
+
+	setup: (email: string, password: string, displayName?: string) =>
+		request<UserAccount>('/auth/setup', {
+			method: 'POST',
+			body: JSON.stringify({ email, password, display_name: displayName })
+		}),
+
+	login: (email: string, password: string) =>
+		request<UserAccount>('/auth/login', {
+			method: 'POST',
+			body: JSON.stringify({ email, password })
+		}),
+
+	logout: () => request<void>('/auth/logout', { method: 'POST' }),
+
+	createPairingCode: () => request<PairingCodeResponse>('/auth/pair', { method: 'POST' }),
+
+	claimPairingCode: (code: string, deviceName?: string) =>
+		request<UserAccount>('/auth/pair/claim', {
+			method: 'POST',
+			body: JSON.stringify({ code, device_name: deviceName })
+		}),
+
 	// OAuth
 	startOAuth: (provider: string) =>
 		request<OAuthStartResponse>('/auth/oauth/start', {
@@ -490,6 +652,23 @@ export const apiClient = {
 		request<PreviewSettings>(`/settings/preview/hidden/${port}`, {
 			method: 'DELETE'
 		}),
+
+	// MCP resources and prompts
+	getMcpResources: () => request<McpResource[]>('/mcp/resources'),
+
+	readMcpResource: (server: string, uri: string) =>
+		request<{ text: string }>('/mcp/resources/read', {
+			method: 'POST',
+			body: JSON.stringify({ server, uri })
+		}),
+
+	getMcpPrompts: () => request<McpPrompt[]>('/mcp/prompts'),
+
+	getMcpPrompt: (server: string, name: string, input: string) =>
+		request<{ text: string }>('/mcp/prompts/get', {
+			method: 'POST',
+			body: JSON.stringify({ server, name, input })
+		}),
 };
 
 // Chat streaming
@@ -540,6 +719,7 @@ export interface StreamCallbacks {
 	onPlanComplete: (toolCallId: string, title: string, taskCount: number) => void;
 	onUsage: (promptTokens: number, completionTokens: number) => void;
 	onTitleUpdate: (title: string) => void;
+	onSessionPinched?: (sessionId: string, title: string) => void;
 	onFinish: (sessionId: string) => void;
 	onError: (error: string) => void;
 }
@@ -550,45 +730,37 @@ interface ToolResultRequest {
 	result: string;
 }
 
-async function streamSSE(
-	url: string,
-	body: object,
-	callbacks: StreamCallbacks,
-	signal?: AbortSignal
-): Promise<void> {
-	const headers: Record<string, string> = { 'Content-Type': 'application/json' };
-	if (currentUserId) {
-		headers['X-User-Id'] = currentUserId;
-	}
-
-	const response = await fetch(getApiUrl(url), {
-		method: 'POST',
-		headers,
-		body: JSON.stringify(body),
-		signal
-	});
-
-	if (!response.ok) {
-		const error = await response.json().catch(() => ({ error: 'Unknown error' }));
-		callbacks.onError(error.error || 'Request failed');
-		return;
-	}
+interface StreamCursor {
+	/** Sequence number of the last event received (server `id:` field) */
+	lastEventId: number;
+	/** Whether the run's `finish` event has been seen */
+	finished: boolean;
+}
 
+/**
+ * Read an SSE response until it ends. Returns an error message if the
+ * connection dropped before the run finished, or null otherwise.
+ */
+async function readSSE(
+	response: Response,
+	callbacks: StreamCallbacks,
+	cursor: StreamCursor
+): Promise<string | null> {
 	const reader = response.body?.getReader();
 	if (!reader) {
-		callbacks.onError('No response body');
-		return;
+		return 'No response body';
 	}
 
 	const decoder = new TextDecoder();
 	let buffer = '';
 	let lastActivity = Date.now();
+	let timedOut = false;
 
 	const timeoutInterval = setInterval(() => {
 		if (Date.now() - lastActivity > STREAM_ACTIVITY_TIMEOUT) {
 			clearInterval(timeoutInterval);
+			timedOut = true;
 			reader.cancel().catch(() => {});
-			callbacks.onError('Stream timeout: no data received for 30 seconds');
 		}
 	}, STREAM_CHECK_INTERVAL);
 
@@ -603,12 +775,16 @@ async function streamSSE(
 			buffer = lines.pop() || '';
 
 			for (const line of lines) {
-				if (line.startsWith('data: ')) {
+				if (line.startsWith('id: ')) {
+					const id = Number(line.slice(4));
+					if (Number.isFinite(id)) cursor.lastEventId = id;
+				} else if (line.startsWith('data: ')) {
 					const data = line.slice(6);
 					if (data === '[DONE]') continue;
 
 					try {
 						const event = JSON.parse(data);
+						if (event.type === 'finish') cursor.finished = true;
 						handleEvent(event, callbacks);
 					} catch (e) {
 						console.warn('[SSE] Parse error:', data, e);
@@ -618,14 +794,86 @@ async function streamSSE(
 		}
 	} catch (err) {
 		if (err instanceof Error && err.name === 'AbortError') {
-			// User cancelled
-		} else {
-			callbacks.onError(err instanceof Error ? err.message : 'Stream error');
+			throw err;
 		}
+		return err instanceof Error ? err.message : 'Stream error';
 	} finally {
 		clearInterval(timeoutInterval);
 		reader.cancel().catch(() => {});
 	}
+
+	if (timedOut) {
+		return 'Stream timeout: no data received for 30 seconds';
+	}
+	return cursor.finished ? null : 'Stream connection lost';
+}
+
+/**
+ * POST a chat request and stream the agent's events. If the connection
+ * drops mid-run, reattach via `/chat/:session_id/events?after=N` and replay
+ * whatever was missed.
+ */
+async function streamSSE(
+	url: string,
+	body: object,
+	callbacks: StreamCallbacks,
+	signal?: AbortSignal
+): Promise<void> {
+	const headers: Record<string, string> = { 'Content-Type': 'application/json' };
+
+	try {
+		const response = await fetch(getApiUrl(url), {
+			method: 'POST',
+			headers,
+			body: JSON.stringify(body),
+			credentials: 'include',
+			signal
+		});
+
+		if (response.status === 401) {
+			redirectToLogin();
+		}
+
+		if (!response.ok) {
+			const error = await response.json().catch(() => ({ error: 'Unknown error' }));
+			callbacks.onError(error.error || 'Request failed');
+			return;
+		}
+
+		const sessionId = response.headers.get('X-Session-Id');
+		const cursor: StreamCursor = { lastEventId: 0, finished: false };
+		let error = await readSSE(response, callbacks, cursor);
+
+		for (let attempt = 1; error && sessionId && attempt <= STREAM_RESUME_ATTEMPTS; attempt++) {
+			await new Promise((resolve) => setTimeout(resolve, STREAM_RESUME_DELAY * attempt));
+			if (signal?.aborted) return;
+
+			const resumed = await fetch(
+				getApiUrl(`/chat/${encodeURIComponent(sessionId)}/events?after=${cursor.lastEventId}`),
+				{ headers, credentials: 'include', signal }
+			).catch((err) => {
+				if (err instanceof Error && err.name === 'AbortError') throw err;
+				return null;
+			});
+			if (!resumed) continue;
+			if (!resumed.ok) break;
+
+			const before = cursor.lastEventId;
+			error = await readSSE(resumed, callbacks, cursor);
+			// Made progress: give the next drop a fresh set of attempts
+			if (cursor.lastEventId > before) attempt = 0;
+		}
+
+		if (error) {
+			callbacks.onError(error);
+		}
+	} catch (err) {
+		if (err instanceof Error && err.name === 'AbortError') {
+			// User cancelled
+		} else {
+			callbacks.onError(err instanceof Error ? err.message : 'Stream error');
+		}
+	}
 }
 
 export async function streamToolResult(
@@ -682,6 +930,15 @@ function handleEvent(event: StreamEvent, callbacks: StreamCallbacks) {
 		case 'title_update':
 			callbacks.onTitleUpdate(event.title);
 			break;
+		case 'context_near_limit':
+			// The server pinches on its own; nothing to show yet
+			break;
+		case 'session_pinched':
+			callbacks.onSessionPinched?.(event.session_id, event.title);
+			break;
+		case 'conversation_compacted':
+			// Same session and stored history; the next usage update shows the smaller context
+			break;
 		case 'tool_approval_required':
 			callbacks.onToolApprovalRequired?.(event.id, event.name, event.arguments);
 			break;
@@ -320,6 +320,11 @@ function createStreamCallbacks(ref: AssistantMessageRef): StreamCallbacks {
 			sessionStore.update((s) => ({ ...s, title }));
 			loadSessions();
 		},
+		onSessionPinched: (sessionId, title) => {
+			// The run continues in a linked session with fresh context
+			sessionStore.update((s) => ({ ...s, sessionId, title, tokenCount: 0 }));
+			loadSessions();
+		},
 		onFinish: (sessionId) => {
 			const currentState = get(sessionStore);
 			const queued = currentState.queuedMessages;
@@ -776,10 +781,10 @@ if (typeof document !== 'undefined') {
 	});
 }
 
-export async function approveToolCall(toolCallId: string) {
+export async function approveToolCall(toolCallId: string, alwaysAllow = false) {
 	const state = get(sessionStore);
 	if (!state.sessionId) return;
-	await apiClient.submitToolApproval(state.sessionId, toolCallId, true);
+	await apiClient.submitToolApproval(state.sessionId, toolCallId, true, alwaysAllow);
 }
 
 export async function denyToolCall(toolCallId: string) {
@@ -8,6 +8,7 @@ export interface Session {
 	updated_at: string;
 	token_count?: number | null;
 	parent_session_id?: string | null;
+	fork_point?: number | null;
 	working_dir?: string | null;
 	target_branch?: string | null;
 }
@@ -4,6 +4,8 @@
 //! - Multi-provider AI with API key authentication
 //! - Single-mode Chat UI with slash commands
 //! - `krusty serve` — unified server + PWA + Tailscale
+//! - `krusty run` — headless agent runs for scripts and CI
+//! - `krusty session` — session export and import
 //! - Clean architecture from day one
 
 use anyhow::Result;
@@ -14,7 +16,9 @@ use krusty_core::{
     acp, agent, ai, constants, extensions, paths, plan, plugins, process, storage, tools,
 };
 
+mod run;
 mod serve;
+mod session;
 mod tui;
 
 /// Krusty - AI Coding Assistant
@@ -48,6 +52,32 @@ enum Commands {
         /// Port to listen on
         #[arg(short, long, default_value_t = 3000)]
         port: u16,
+
+        /// Print a one-time code (and QR) for signing in a new device
+        #[arg(long)]
+        pair: bool,
+
+        /// Serve remote requests without signing in (trusted networks only)
+        #[arg(long)]
+        allow_unauthenticated_remote: bool,
+    },
+
+    /// Run the agent headlessly on a single prompt
+    ///
+    /// Takes the prompt from an argument or stdin, runs the agent to
+    /// completion and streams events to stdout as text or JSON lines.
+    ///
+    /// Exit codes: 0 success, 1 error, 2 waiting for user input,
+    /// 3 iteration limit reached.
+    Run(run::RunArgs),
+
+    /// Export or import sessions
+    ///
+    /// Exports a session as a JSON bundle (re-importable on another
+    /// machine), a Markdown transcript or a static HTML page.
+    Session {
+        #[command(subcommand)]
+        command: session::SessionCommand,
     },
 }
 
@@ -71,17 +101,27 @@ async fn main() -> Result<()> {
 
     // Serve mode has its own logging (stdout), skip TUI logging setup
     if matches!(cli.command, Some(Commands::Serve { .. })) {
-        if let Some(Commands::Serve { port }) = cli.command {
-            return serve::run(port).await;
+        if let Some(Commands::Serve {
+            port,
+            pair,
+            allow_unauthenticated_remote,
+        }) = cli.command
+        {
+            return serve::run(port, pair, allow_unauthenticated_remote).await;
         }
     }
 
     // Set up panic hook to restore terminal state (TUI/ACP modes)
-    let original_hook = std::panic::take_hook();
-    std::panic::set_hook(Box::new(move |panic_info| {
-        restore_terminal();
-        original_hook(panic_info);
-    }));
+    if !matches!(
+        cli.command,
+        Some(Commands::Run(_)) | Some(Commands::Session { .. })
+    ) {
+        let original_hook = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |panic_info| {
+            restore_terminal();
+            original_hook(panic_info);
+        }));
+    }
 
     // Initialize logging to file (not stdout/stderr which would mess up TUI)
     let log_dir = paths::logs_dir();
@@ -134,6 +174,13 @@ async fn main() -> Result<()> {
             let server = acp::AcpServer::new()?;
             server.run().await?;
         }
+        Some(Commands::Run(args)) => {
+            let code = run::run(args).await?;
+            if code != run::EXIT_SUCCESS {
+                std::process::exit(code);
+            }
+        }
+        Some(Commands::Session { command }) => session::run(command)?,
         Some(Commands::Serve { .. }) => unreachable!(),
         None => {
             let mut app = tui::App::new().await;
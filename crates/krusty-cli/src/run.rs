@@ -0,0 +1,684 @@
+//! `krusty run` — headless, non-interactive agent runs
+//!
+//! Drives the core `AgenticOrchestrator` to completion without the TUI so
+//! Krusty can be scripted from CI jobs and shell pipelines. The prompt comes
+//! from an argument or stdin; `LoopEvent`s are streamed to stdout as plain
+//! text or newline-delimited JSON, and the process exit code reflects how the
+//! run ended.
+
+use std::io::{IsTerminal, Read, Write};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Args, ValueEnum};
+use tokio::sync::RwLock;
+
+use krusty_core::agent::{
+    AgentCancellation, AgenticOrchestrator, LoggingHook, LoopEvent, LoopInput, OrchestratorConfig,
+    OrchestratorServices, PlanModeHook, SafetyHook, UserHookManager, UserPostToolHook,
+    UserPreToolHook,
+};
+use krusty_core::ai::client::{AiClient, CallOptions};
+use krusty_core::ai::models::create_model_registry;
+use krusty_core::ai::providers::{get_provider, ProviderId};
+use krusty_core::ai::types::{Content, ModelMessage, Role};
+use krusty_core::lsp::LspManager;
+use krusty_core::mcp::McpManager;
+use krusty_core::process::ProcessRegistry;
+use krusty_core::skills::SkillsManager;
+use krusty_core::storage::credentials::ActiveProviderStore;
+use krusty_core::storage::{CredentialStore, Database, Preferences, SessionManager, WorkMode};
+use krusty_core::tools::registry::PermissionMode;
+use krusty_core::tools::{
+    register_all_tools, register_build_tool, register_explore_tool, register_lsp_tools,
+    register_memory_tools, register_session_search_tool, register_task_tool, ToolRegistry,
+};
+
+use crate::paths;
+
+/// Run finished normally.
+pub const EXIT_SUCCESS: i32 = 0;
+/// The agent or provider reported an error, or the session hit its hard budget.
+pub const EXIT_ERROR: i32 = 1;
+/// The agent stopped to wait for user input (AskUser or plan confirmation).
+pub const EXIT_AWAITING_INPUT: i32 = 2;
+/// The iteration limit was reached while the agent still had work to do.
+pub const EXIT_MAX_ITERATIONS: i32 = 3;
+
+/// Arguments for `krusty run`.
+#[derive(Args, Debug)]
+pub struct RunArgs {
+    /// Prompt to send. Reads from stdin when omitted or set to `-`.
+    pub prompt: Option<String>,
+
+    /// Model ID to use (defaults to the last model selected in the TUI)
+    #[arg(short, long)]
+    pub model: Option<String>,
+
+    /// Provider to use (defaults to the active provider)
+    #[arg(long)]
+    pub provider: Option<String>,
+
+    /// Working directory for tools (defaults to the current directory)
+    #[arg(short = 'C', long = "cwd")]
+    pub working_dir: Option<PathBuf>,
+
+    /// Maximum agentic iterations before stopping
+    #[arg(long, default_value_t = 50)]
+    pub max_iterations: usize,
+
+    /// Resume an existing session by ID instead of starting a new one
+    #[arg(short, long)]
+    pub session: Option<String>,
+
+    /// Tool permission mode. Supervised runs deny every write/execute tool.
+    #[arg(long, value_enum, default_value_t = RunPermission::Autonomous)]
+    pub permission_mode: RunPermission,
+
+    /// Start in plan mode instead of build mode
+    #[arg(long)]
+    pub plan: bool,
+
+    /// Output format for streamed events
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+}
+
+/// Permission mode selectable from the command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum RunPermission {
+    Supervised,
+    Autonomous,
+}
+
+impl From<RunPermission> for PermissionMode {
+    fn from(mode: RunPermission) -> Self {
+        match mode {
+            RunPermission::Supervised => PermissionMode::Supervised,
+            RunPermission::Autonomous => PermissionMode::Autonomous,
+        }
+    }
+}
+
+/// How `LoopEvent`s are written to stdout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Assistant text on stdout, tool activity on stderr
+    Text,
+    /// One JSON-serialized `LoopEvent` per line
+    Json,
+}
+
+/// Run the agent headlessly and return the process exit code.
+pub async fn run(args: RunArgs) -> Result<i32> {
+    let stdin = std::io::stdin();
+    let prompt = read_prompt(args.prompt.as_deref(), stdin.is_terminal(), stdin)?;
+
+    let working_dir = match args.working_dir {
+        Some(dir) => dir
+            .canonicalize()
+            .with_context(|| format!("Invalid working directory: {}", dir.display()))?,
+        None => std::env::current_dir()?,
+    };
+
+    let db_path = paths::config_dir().join("krusty.db");
+    let session_manager = SessionManager::new(Database::new(&db_path)?);
+
+    // Resolve provider + model, then authenticate
+    let provider = match args.provider.as_deref() {
+        Some(name) => krusty_server::utils::providers::parse_provider(name)
+            .ok_or_else(|| anyhow!("Unknown provider: {}", name))?,
+        None => ActiveProviderStore::load(),
+    };
+
+    let resumed = match args.session.as_deref() {
+        Some(id) => Some(
+            session_manager
+                .get_session(id)?
+                .ok_or_else(|| anyhow!("Session {} not found", id))?,
+        ),
+        None => None,
+    };
+
+    let model = args
+        .model
+        .clone()
+        .or_else(|| resumed.as_ref().and_then(|s| s.model.clone()))
+        .or_else(|| Preferences::new(Database::new(&db_path).ok()?).get_current_model())
+        .unwrap_or_default();
+    let (model, _) = crate::tui::auth::validate_model_for_provider(&model, provider);
+
+    let ai_client = Arc::new(create_ai_client(provider, &model).await?);
+    let provider_model =
+        get_provider(provider).and_then(|p| p.models.iter().find(|m| m.id == model));
+    let pricing = provider_model.and_then(|m| m.pricing());
+    let context_window = provider_model.map(|m| m.context_window).unwrap_or(0);
+    let auto_pinch = Database::new(&db_path)
+        .map(|db| Preferences::new(db).get_auto_pinch_policy())
+        .unwrap_or_default();
+
+    // Session: resume or create
+    let (session_id, mut conversation, is_new_session) = match resumed {
+        Some(session) => {
+            let conversation = load_conversation(&session_manager, &session.id)?;
+            let is_new = conversation.is_empty();
+            (session.id, conversation, is_new)
+        }
+        None => {
+            let title = SessionManager::generate_title_from_content(&prompt);
+            let working_dir_str = working_dir.to_string_lossy().to_string();
+            let id = session_manager.create_session(
+                &title,
+                Some(model.as_str()),
+                Some(working_dir_str.as_str()),
+            )?;
+            (id, Vec::new(), true)
+        }
+    };
+
+    let work_mode = if args.plan {
+        session_manager.update_session_work_mode(&session_id, WorkMode::Plan)?;
+        WorkMode::Plan
+    } else {
+        session_manager
+            .get_session(&session_id)?
+            .map(|s| s.work_mode)
+            .unwrap_or_default()
+    };
+
+    let user_content = vec![Content::Text { text: prompt }];
+    session_manager.save_message(&session_id, "user", &serde_json::to_string(&user_content)?)?;
+    conversation.push(ModelMessage {
+        role: Role::User,
+        content: user_content,
+    });
+
+    let lsp_manager = Arc::new(LspManager::new());
+    let tool_registry = init_tool_registry(&db_path, &working_dir, &ai_client, &lsp_manager).await;
+    let options = CallOptions {
+        tools: Some(tool_registry.get_ai_tools().await),
+        session_id: Some(session_id.clone()),
+        codex_parallel_tool_calls: true,
+        ..Default::default()
+    };
+
+    let services = OrchestratorServices {
+        ai_client,
+        tool_registry,
+        process_registry: Arc::new(ProcessRegistry::new()),
+        db_path,
+        skills_manager: Arc::new(RwLock::new(SkillsManager::with_defaults(&working_dir))),
+        lsp_manager: Some(lsp_manager),
+        editor: None,
+    };
+
+    let config = OrchestratorConfig {
+        session_id,
+        working_dir,
+        permission_mode: args.permission_mode.into(),
+        max_iterations: args.max_iterations.max(1),
+        user_id: None,
+        initial_work_mode: work_mode,
+        generate_title: is_new_session,
+        pricing,
+        context_window,
+        auto_pinch,
+    };
+
+    let orchestrator = AgenticOrchestrator::new(services, config);
+    let (mut event_rx, input_tx) = orchestrator.run(conversation, options);
+
+    let mut output = EventPrinter::new(args.format, std::io::stdout(), std::io::stderr());
+    let mut outcome = RunOutcome::new();
+
+    while let Some(event) = event_rx.recv().await {
+        output.print(&event)?;
+        outcome.observe(&event);
+
+        match &event {
+            LoopEvent::ToolApprovalRequired { id, name, .. } => {
+                // Nobody can answer an approval prompt in a headless run
+                tracing::info!(tool = %name, "Denying tool in supervised headless run");
+                let _ = input_tx.send(LoopInput::ToolApproval {
+                    tool_call_id: id.clone(),
+                    approved: false,
+                    always_allow: false,
+                });
+            }
+            LoopEvent::Finished { .. } => break,
+            _ => {}
+        }
+    }
+
+    output.finish()?;
+    Ok(outcome.exit_code())
+}
+
+/// Read the prompt from the argument, or from `stdin` when absent or `-`.
+///
+/// An interactive `stdin` is refused rather than waited on.
+fn read_prompt(arg: Option<&str>, interactive: bool, mut stdin: impl Read) -> Result<String> {
+    let prompt = match arg {
+        Some(text) if text != "-" => text.to_string(),
+        _ => {
+            if interactive {
+                return Err(anyhow!(
+                    "No prompt given. Pass it as an argument or pipe it on stdin."
+                ));
+            }
+            let mut buf = String::new();
+            stdin.read_to_string(&mut buf)?;
+            buf
+        }
+    };
+
+    let prompt = prompt.trim().to_string();
+    if prompt.is_empty() {
+        return Err(anyhow!("Prompt is empty"));
+    }
+    Ok(prompt)
+}
+
+/// Build an authenticated client for a provider/model pair.
+async fn create_ai_client(provider: ProviderId, model: &str) -> Result<AiClient> {
+    if get_provider(provider).is_none() {
+        return Err(anyhow!("Provider {} is not available", provider));
+    }
+
+    if provider.supports_oauth() {
+        if let Err(e) = krusty_core::auth::refresh_oauth_token(provider).await {
+            tracing::debug!("OAuth token refresh skipped: {}", e);
+        }
+    }
+
+    let credentials = CredentialStore::load().unwrap_or_default();
+    let key = match provider {
+        ProviderId::Anthropic => krusty_core::auth::resolve_anthropic_auth(&credentials).credential,
+        ProviderId::OpenAI => {
+            krusty_core::auth::resolve_openai_auth(&credentials, model).credential
+        }
+        _ => credentials.get_auth(&provider),
+    }
+    .ok_or_else(|| {
+        anyhow!(
+            "No credentials configured for {}. Run `krusty` and use /auth first.",
+            provider
+        )
+    })?;
+
+    let config = crate::tui::auth::create_client_config(
+        provider,
+        model,
+        &credentials,
+        &create_model_registry(),
+    );
+    Ok(AiClient::with_api_key(config, key))
+}
+
+/// Build the tool registry with the same hook chain as the TUI and server.
+async fn init_tool_registry(
+    db_path: &std::path::Path,
+    working_dir: &std::path::Path,
+    ai_client: &Arc<AiClient>,
+    lsp_manager: &Arc<LspManager>,
+) -> Arc<ToolRegistry> {
+    let mut hook_manager = UserHookManager::new();
+    if let Ok(db) = Database::new(db_path) {
+        if let Err(e) = hook_manager.load(&db) {
+            tracing::warn!("Failed to load hooks: {}", e);
+        }
+    }
+    let hook_manager = Arc::new(RwLock::new(hook_manager));
+
+    let mut registry = ToolRegistry::new();
+    registry.add_pre_hook(Arc::new(SafetyHook::new()));
+    registry.add_pre_hook(Arc::new(PlanModeHook::new()));
+    registry.add_post_hook(Arc::new(LoggingHook::new()));
+    registry.add_pre_hook(Arc::new(UserPreToolHook::new(hook_manager.clone())));
+    registry.add_post_hook(Arc::new(UserPostToolHook::new(hook_manager)));
+    let registry = Arc::new(registry);
+    register_all_tools(&registry).await;
+    register_lsp_tools(&registry, lsp_manager.clone()).await;
+    register_session_search_tool(&registry, db_path.to_path_buf()).await;
+    register_memory_tools(&registry, db_path.to_path_buf()).await;
+
+    let cancellation = AgentCancellation::new();
+    register_explore_tool(&registry, ai_client.clone(), cancellation.clone()).await;
+    register_build_tool(&registry, ai_client.clone(), cancellation.clone()).await;
+    register_task_tool(&registry, ai_client.clone(), cancellation).await;
+
+    let mcp_manager = Arc::new(McpManager::new(working_dir.to_path_buf()));
+    if let Err(e) = mcp_manager.load_config().await {
+        tracing::warn!("Failed to load MCP config: {}", e);
+    } else if let Err(e) = mcp_manager.connect_all().await {
+        tracing::warn!("Failed to connect MCP servers: {}", e);
+    }
+    krusty_core::mcp::tool::register_mcp_tools(mcp_manager.clone(), &registry).await;
+    krusty_core::mcp::tool::spawn_tool_sync(mcp_manager, registry.clone());
+
+    registry
+}
+
+fn load_conversation(
+    session_manager: &SessionManager,
+    session_id: &str,
+) -> Result<Vec<ModelMessage>> {
+    let messages = session_manager
+        .load_session_messages(session_id)?
+        .into_iter()
+        .filter_map(|(role, content_json)| {
+            let role = match role.as_str() {
+                "user" => Role::User,
+                "assistant" => Role::Assistant,
+                // Pinch context; also keeps indices aligned with compactions
+                "system" => Role::System,
+                _ => return None,
+            };
+            serde_json::from_str(&content_json)
+                .ok()
+                .map(|content| ModelMessage { role, content })
+        })
+        .collect();
+    Ok(messages)
+}
+
+/// Tracks how a run ended to pick its exit code.
+struct RunOutcome {
+    exit_code: i32,
+    /// Whether the last turn stopped with tool calls still to run
+    last_turn_has_more: bool,
+}
+
+impl RunOutcome {
+    fn new() -> Self {
+        Self {
+            exit_code: EXIT_SUCCESS,
+            last_turn_has_more: false,
+        }
+    }
+
+    fn observe(&mut self, event: &LoopEvent) {
+        match event {
+            LoopEvent::AwaitingInput { .. } => self.exit_code = EXIT_AWAITING_INPUT,
+            LoopEvent::Error { .. } | LoopEvent::BudgetExceeded { .. } => {
+                self.exit_code = EXIT_ERROR
+            }
+            LoopEvent::TurnComplete { has_more, .. } => self.last_turn_has_more = *has_more,
+            _ => {}
+        }
+    }
+
+    fn exit_code(&self) -> i32 {
+        if self.exit_code == EXIT_SUCCESS && self.last_turn_has_more {
+            EXIT_MAX_ITERATIONS
+        } else {
+            self.exit_code
+        }
+    }
+}
+
+/// Writes `LoopEvent`s in the selected output format.
+///
+/// Assistant text and JSON go to `out`; text-mode tool activity goes to `err`.
+struct EventPrinter<O: Write, E: Write> {
+    format: OutputFormat,
+    out: O,
+    err: E,
+    /// Whether the last text written to `out` ended mid-line
+    mid_line: bool,
+}
+
+impl<O: Write, E: Write> EventPrinter<O, E> {
+    fn new(format: OutputFormat, out: O, err: E) -> Self {
+        Self {
+            format,
+            out,
+            err,
+            mid_line: false,
+        }
+    }
+
+    fn print(&mut self, event: &LoopEvent) -> Result<()> {
+        match self.format {
+            OutputFormat::Json => {
+                serde_json::to_writer(&mut self.out, event)?;
+                writeln!(self.out)?;
+                self.out.flush()?;
+            }
+            OutputFormat::Text => self.print_text(event)?,
+        }
+        Ok(())
+    }
+
+    fn print_text(&mut self, event: &LoopEvent) -> Result<()> {
+        match event {
+            LoopEvent::TextDelta { delta } | LoopEvent::TextDeltaWithCitations { delta, .. } => {
+                self.out.write_all(delta.as_bytes())?;
+                self.out.flush()?;
+                self.mid_line = !delta.ends_with('\n');
+            }
+            LoopEvent::ToolCallComplete { name, .. } => {
+                self.end_line()?;
+                writeln!(self.err, "→ {}", name)?;
+            }
+            LoopEvent::ToolResult {
+                output, is_error, ..
+            } if *is_error => {
+                let first_line = output.lines().next().unwrap_or_default();
+                writeln!(self.err, "  ✗ {}", first_line)?;
+            }
+            LoopEvent::ToolDenied { id } => writeln!(self.err, "  ✗ denied ({})", id)?,
+            LoopEvent::PermissionRuleAdded { rule } => {
+                writeln!(self.err, "rule added: {}", rule.spec())?
+            }
+            LoopEvent::ModeChange { mode, .. } => writeln!(self.err, "mode: {}", mode)?,
+            LoopEvent::AwaitingInput { tool_name, .. } => {
+                self.end_line()?;
+                writeln!(self.err, "Stopped: waiting for user input ({})", tool_name)?;
+            }
+            LoopEvent::Error { error } => {
+                self.end_line()?;
+                writeln!(self.err, "Error: {}", error)?;
+            }
+            LoopEvent::BudgetWarning {
+                spent_usd,
+                limit_usd,
+            } => {
+                self.end_line()?;
+                writeln!(
+                    self.err,
+                    "Warning: session spend ${:.2} passed soft budget ${:.2}",
+                    spent_usd, limit_usd
+                )?;
+            }
+            LoopEvent::BudgetExceeded {
+                spent_usd,
+                limit_usd,
+            } => {
+                self.end_line()?;
+                writeln!(
+                    self.err,
+                    "Stopped: session spend ${:.2} reached hard budget ${:.2}",
+                    spent_usd, limit_usd
+                )?;
+            }
+            LoopEvent::ContextNearLimit {
+                tokens_used,
+                context_window,
+            } => {
+                self.end_line()?;
+                writeln!(
+                    self.err,
+                    "Warning: context at {} / {} tokens",
+                    tokens_used, context_window
+                )?;
+            }
+            LoopEvent::SessionPinched { session_id, .. } => {
+                self.end_line()?;
+                writeln!(self.err, "pinched: continuing in session {}", session_id)?;
+            }
+            LoopEvent::ConversationCompacted {
+                summarized_messages,
+                elided_results,
+                ..
+            } => {
+                self.end_line()?;
+                writeln!(
+                    self.err,
+                    "compacted: summarized {} messages, elided {} tool results",
+                    summarized_messages, elided_results
+                )?;
+            }
+            LoopEvent::Finished { session_id } => {
+                self.end_line()?;
+                writeln!(self.err, "session: {}", session_id)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> Result<()> {
+        if self.mid_line {
+            writeln!(self.out)?;
+            self.out.flush()?;
+            self.mid_line = false;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> Result<()> {
+        if self.format == OutputFormat::Text {
+            self.end_line()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn printed(format: OutputFormat, events: &[LoopEvent]) -> (String, String) {
+        let mut printer = EventPrinter::new(format, Vec::new(), Vec::new());
+        for event in events {
+            printer.print(event).unwrap();
+        }
+        printer.finish().unwrap();
+        (
+            String::from_utf8(printer.out).unwrap(),
+            String::from_utf8(printer.err).unwrap(),
+        )
+    }
+
+    fn exit_code(events: &[LoopEvent]) -> i32 {
+        let mut outcome = RunOutcome::new();
+        for event in events {
+            outcome.observe(event);
+        }
+        outcome.exit_code()
+    }
+
+    fn text(delta: &str) -> LoopEvent {
+        LoopEvent::TextDelta {
+            delta: delta.to_string(),
+        }
+    }
+
+    fn turn(has_more: bool) -> LoopEvent {
+        LoopEvent::TurnComplete { turn: 1, has_more }
+    }
+
+    #[test]
+    fn test_read_prompt_prefers_the_argument() {
+        let prompt = read_prompt(Some("  fix the build \n"), true, "ignored".as_bytes()).unwrap();
+        assert_eq!(prompt, "fix the build");
+    }
+
+    #[test]
+    fn test_read_prompt_reads_piped_stdin() {
+        let stdin = "explain main.rs\n".as_bytes();
+        assert_eq!(read_prompt(None, false, stdin).unwrap(), "explain main.rs");
+        assert_eq!(
+            read_prompt(Some("-"), false, "from a pipe".as_bytes()).unwrap(),
+            "from a pipe"
+        );
+    }
+
+    #[test]
+    fn test_read_prompt_rejects_terminal_and_empty_input() {
+        assert!(read_prompt(None, true, "typed".as_bytes()).is_err());
+        assert!(read_prompt(None, false, " \n".as_bytes()).is_err());
+        assert!(read_prompt(Some(""), false, "unused".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_text_output_splits_answer_from_tool_activity() {
+        let (out, err) = printed(
+            OutputFormat::Text,
+            &[
+                text("Let me check"),
+                LoopEvent::ToolCallComplete {
+                    id: "call_1".to_string(),
+                    name: "read".to_string(),
+                    arguments: serde_json::json!({}),
+                },
+                text("Done."),
+            ],
+        );
+        assert_eq!(out, "Let me check\nDone.\n");
+        assert_eq!(err, "→ read\n");
+    }
+
+    #[test]
+    fn test_json_output_writes_one_event_per_line() {
+        let (out, err) = printed(
+            OutputFormat::Json,
+            &[
+                text("hi"),
+                LoopEvent::Finished {
+                    session_id: "s1".to_string(),
+                },
+            ],
+        );
+        let lines: Vec<serde_json::Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["type"], "text_delta");
+        assert_eq!(lines[0]["delta"], "hi");
+        assert_eq!(lines[1]["type"], "finished");
+        assert!(err.is_empty());
+    }
+
+    #[test]
+    fn test_exit_code_mapping() {
+        assert_eq!(exit_code(&[text("ok"), turn(false)]), EXIT_SUCCESS);
+        assert_eq!(exit_code(&[turn(true)]), EXIT_MAX_ITERATIONS);
+        assert_eq!(
+            exit_code(&[LoopEvent::AwaitingInput {
+                tool_call_id: "call_1".to_string(),
+                tool_name: "AskUserQuestion".to_string(),
+            }]),
+            EXIT_AWAITING_INPUT
+        );
+        assert_eq!(
+            exit_code(&[
+                turn(true),
+                LoopEvent::Error {
+                    error: "rate limited".to_string(),
+                },
+            ]),
+            EXIT_ERROR
+        );
+        assert_eq!(
+            exit_code(&[LoopEvent::BudgetExceeded {
+                spent_usd: 5.0,
+                limit_usd: 5.0,
+            }]),
+            EXIT_ERROR
+        );
+    }
+}
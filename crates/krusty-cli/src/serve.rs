@@ -1,19 +1,25 @@
 //! `krusty serve` — unified server with embedded PWA and Tailscale integration
 //!
 //! Starts the Krusty API server with the PWA frontend embedded in the binary.
-//! On first run, prompts for provider and API key configuration.
+//! On first run, prompts for provider and API key configuration and offers
+//! to create a login for remote devices.
 //! Detects and reuses an already-running instance if present.
 
 use anyhow::{Context, Result};
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
 
 use krusty_core::ai::providers::ProviderId;
+use krusty_core::paths;
 use krusty_core::server_instance;
 use krusty_core::storage::credentials::CredentialStore;
+use krusty_core::storage::{AccountStore, Database, TokenScope, MIN_PASSWORD_LEN};
 use krusty_core::tailscale;
 
+/// How long a `--pair` code stays valid
+const PAIRING_CODE_MINUTES: i64 = 10;
+
 /// Run the serve command.
-pub async fn run(port: u16) -> Result<()> {
+pub async fn run(port: u16, pair: bool, allow_unauthenticated_remote: bool) -> Result<()> {
     // Check for existing running server
     if let Some(instance) = server_instance::detect_running_server().await {
         print_banner(instance.port, false);
@@ -22,9 +28,13 @@ pub async fn run(port: u16) -> Result<()> {
             instance.pid
         );
         // Don't start a new server — just print the URLs and exit
-        if let Some(url) = tailscale::setup_tailscale_serve(instance.port) {
+        let tailscale_url = tailscale::setup_tailscale_serve(instance.port);
+        if let Some(url) = &tailscale_url {
             println!("  Tailscale: {}", url);
         }
+        if pair {
+            print_pairing_code(tailscale_url.as_deref(), instance.port)?;
+        }
         return Ok(());
     }
 
@@ -34,6 +44,12 @@ pub async fn run(port: u16) -> Result<()> {
         run_setup_wizard()?;
     }
 
+    let db = Database::new(&paths::config_dir().join("krusty.db"))?;
+    if AccountStore::new(&db).login_count()? == 0 && io::stdin().is_terminal() {
+        run_account_wizard(&db)?;
+    }
+    drop(db);
+
     // Write PID file
     server_instance::write_pid_file(port)?;
 
@@ -47,7 +63,8 @@ pub async fn run(port: u16) -> Result<()> {
     print_banner(port, true);
 
     // Setup Tailscale serve (non-blocking, best-effort)
-    if let Some(url) = tailscale::setup_tailscale_serve(port) {
+    let tailscale_url = tailscale::setup_tailscale_serve(port);
+    if let Some(url) = &tailscale_url {
         println!("  Tailscale: {}\n", url);
     } else if !tailscale::is_installed() {
         println!("  Tip: Install Tailscale to access Krusty from any device.");
@@ -64,8 +81,16 @@ pub async fn run(port: u16) -> Result<()> {
         )
         .init();
 
+    if allow_unauthenticated_remote {
+        println!("  \x1b[33mWarning:\x1b[0m remote devices can connect without signing in.\n");
+    }
+    if pair {
+        print_pairing_code(tailscale_url.as_deref(), port)?;
+    }
+
     let config = krusty_server::ServerConfig {
         port,
+        allow_unauthenticated_remote,
         ..Default::default()
     };
 
@@ -150,3 +175,73 @@ fn run_setup_wizard() -> Result<()> {
 
     Ok(())
 }
+
+/// Offer to create the login remote devices sign in with.
+fn run_account_wizard(db: &Database) -> Result<()> {
+    println!("  Remote devices need a login to use this server.");
+    print!("  Email (leave blank to skip): ");
+    io::stdout().flush()?;
+
+    let mut email = String::new();
+    io::stdin().read_line(&mut email)?;
+    let email = email.trim();
+    if email.is_empty() {
+        println!("  Skipped. Only this machine can connect until a login exists.\n");
+        return Ok(());
+    }
+
+    print!("  Password ({}+ characters): ", MIN_PASSWORD_LEN);
+    io::stdout().flush()?;
+    let mut password = String::new();
+    io::stdin().read_line(&mut password)?;
+
+    let account = AccountStore::new(db)
+        .create_user(email, password.trim_end_matches(['\r', '\n']), None)
+        .context("Failed to create login")?;
+
+    println!();
+    println!("  \x1b[32m✓\x1b[0m Login created for {}", account.email);
+    println!("    Pair a phone with: krusty serve --pair");
+    println!();
+
+    Ok(())
+}
+
+/// Print a one-time pairing code and a QR code linking to it.
+fn print_pairing_code(tailscale_url: Option<&str>, port: u16) -> Result<()> {
+    let db = Database::new(&paths::config_dir().join("krusty.db"))?;
+    let store = AccountStore::new(&db);
+    let Some(account) = store.list_logins()?.into_iter().next() else {
+        println!("  No login exists yet; restart `krusty serve` in a terminal to create one.\n");
+        return Ok(());
+    };
+
+    let (code, _) = store.create_pairing_code(
+        &account.id,
+        &TokenScope::ALL,
+        chrono::Duration::minutes(PAIRING_CODE_MINUTES),
+    )?;
+    let base = tailscale_url
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| format!("http://localhost:{}", port));
+    let url = format!("{}/pair?code={}", base, code);
+
+    println!(
+        "  Pair a device as {} (valid {} minutes):",
+        account.email, PAIRING_CODE_MINUTES
+    );
+    println!("    Code: \x1b[1m{}-{}\x1b[0m", &code[..4], &code[4..]);
+    println!("    Link: {}", url);
+    if let Ok(qr) = qrcode::QrCode::new(url.as_bytes()) {
+        let rendered = qr
+            .render::<qrcode::render::unicode::Dense1x2>()
+            .quiet_zone(true)
+            .build();
+        for line in rendered.lines() {
+            println!("    {}", line);
+        }
+    }
+    println!();
+
+    Ok(())
+}
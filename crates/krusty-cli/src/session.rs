@@ -0,0 +1,132 @@
+//! `krusty session` — export and import sessions
+//!
+//! Exports a session as a re-importable JSON bundle, a Markdown transcript or
+//! a static HTML page, and imports JSON bundles as new sessions.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use clap::{Subcommand, ValueEnum};
+
+use krusty_core::storage::export::parse_bundle;
+use krusty_core::storage::{Database, ExportFormat, SessionManager};
+
+use crate::paths;
+
+/// Subcommands of `krusty session`.
+#[derive(Subcommand, Debug)]
+pub enum SessionCommand {
+    /// Export a session to stdout or a file
+    Export {
+        /// Session ID
+        id: String,
+
+        /// Output format (defaults to the output file's extension, else json)
+        #[arg(short, long, value_enum)]
+        format: Option<SessionFormat>,
+
+        /// File to write instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+
+    /// Import a JSON session bundle and print the new session ID
+    Import {
+        /// Bundle file to import. Reads from stdin when set to `-`.
+        path: PathBuf,
+
+        /// Working directory for the imported session (defaults to the
+        /// exported one)
+        #[arg(short = 'C', long = "cwd")]
+        working_dir: Option<PathBuf>,
+    },
+}
+
+/// Export format selectable from the command line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SessionFormat {
+    /// Re-importable JSON bundle
+    Json,
+    /// Readable Markdown transcript
+    Markdown,
+    /// Static HTML page
+    Html,
+}
+
+impl From<SessionFormat> for ExportFormat {
+    fn from(format: SessionFormat) -> Self {
+        match format {
+            SessionFormat::Json => ExportFormat::Json,
+            SessionFormat::Markdown => ExportFormat::Markdown,
+            SessionFormat::Html => ExportFormat::Html,
+        }
+    }
+}
+
+/// Run a `krusty session` subcommand.
+pub fn run(command: SessionCommand) -> Result<()> {
+    let db_path = paths::config_dir().join("krusty.db");
+    let session_manager = SessionManager::new(Database::new(&db_path)?);
+
+    match command {
+        SessionCommand::Export { id, format, output } => {
+            let format = match format {
+                Some(format) => format.into(),
+                None => output
+                    .as_deref()
+                    .and_then(format_from_extension)
+                    .unwrap_or(ExportFormat::Json),
+            };
+            let bundle = session_manager.export_session(&id)?;
+            let rendered = format.render(&bundle)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    eprintln!(
+                        "Exported '{}' ({} messages) to {}",
+                        bundle.session.title,
+                        bundle.messages.len(),
+                        path.display()
+                    );
+                }
+                None => print!("{}", rendered),
+            }
+        }
+        SessionCommand::Import { path, working_dir } => {
+            let json = if path.as_os_str() == "-" {
+                let mut buf = String::new();
+                std::io::stdin().read_to_string(&mut buf)?;
+                buf
+            } else {
+                std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?
+            };
+            let working_dir = working_dir
+                .map(|dir| {
+                    dir.canonicalize()
+                        .map_err(|_| anyhow!("Invalid working directory: {}", dir.display()))
+                })
+                .transpose()?;
+
+            let bundle = parse_bundle(&json)?;
+            let id = session_manager.import_session(
+                &bundle,
+                working_dir.as_deref().and_then(Path::to_str),
+                None,
+            )?;
+            eprintln!(
+                "Imported '{}' ({} messages)",
+                bundle.session.title,
+                bundle.messages.len()
+            );
+            println!("{}", id);
+        }
+    }
+    Ok(())
+}
+
+fn format_from_extension(path: &Path) -> Option<ExportFormat> {
+    path.extension()?.to_str()?.parse().ok()
+}
@@ -29,7 +29,8 @@ use crate::ai::client::AiClient;
 use crate::ai::models::SharedModelRegistry;
 use crate::ai::providers::ProviderId;
 use crate::ai::types::{AiTool, AiToolCall};
-use crate::extensions::WasmHost;
+use crate::extensions::wasm_host::SlashCommandArgumentCompletion;
+use crate::extensions::{ExtensionSlashCommands, WasmHost};
 use crate::plan::{PlanFile, PlanManager};
 use crate::plugins::PluginManager;
 use crate::process::ProcessRegistry;
@@ -64,6 +65,7 @@ pub enum Popup {
     ThemeSelect,
     Help,
     SessionList,
+    SessionSearch,
     McpBrowser,
     ProcessList,
     PluginsBrowser,
@@ -71,6 +73,8 @@ pub enum Popup {
     FilePreview,
     SkillsBrowser,
     Hooks,
+    Permissions,
+    Memories,
 }
 
 /// Work mode - BUILD (coding) or PLAN (planning)
@@ -181,10 +185,12 @@ pub struct AppServices {
     #[allow(dead_code)]
     pub wasm_host: Option<Arc<WasmHost>>,
     pub plugin_manager: Option<Arc<PluginManager>>,
+    pub slash_commands: Arc<ExtensionSlashCommands>,
 
     // Skills/MCP
     pub skills_manager: Arc<RwLock<SkillsManager>>,
     pub mcp_manager: Arc<krusty_core::mcp::McpManager>,
+    pub lsp_manager: Arc<krusty_core::lsp::LspManager>,
     pub mcp_status_tx: tokio::sync::mpsc::UnboundedSender<crate::tui::utils::McpStatusUpdate>,
     pub oauth_status_tx: tokio::sync::mpsc::UnboundedSender<crate::tui::utils::OAuthStatusUpdate>,
 }
@@ -271,10 +277,11 @@ pub struct AppRuntime {
     pub current_model: String,
     /// Token usage tracking
     pub context_tokens_used: usize,
-    /// Flag to trigger auto-pinch after response completes
+    /// Session spend in USD from the usage ledger (None until a priced call)
+    pub session_cost_usd: Option<f64>,
+    /// Flag to open the pinch popup once the orchestrator reports the
+    /// context nearing its limit and the AI goes idle
     pub pending_auto_pinch: bool,
-    /// Auto-pinch in progress (bypasses popup when AI is busy)
-    pub auto_pinch_in_progress: bool,
     /// AI client
     pub ai_client: Option<AiClient>,
     /// API key
@@ -303,6 +310,8 @@ pub struct AppRuntime {
     pub title_editor: TitleEditor,
     /// Async channel receivers
     pub channels: AsyncChannels,
+    /// Extension argument completions by command name and arguments
+    pub argument_completions: HashMap<(String, Vec<String>), Vec<SlashCommandArgumentCompletion>>,
     /// /init exploration ID
     pub init_explore_id: Option<String>,
     /// Cached languages for /init
@@ -353,8 +362,8 @@ impl AppRuntime {
             chat: ChatState::new(),
             current_model,
             context_tokens_used: 0,
+            session_cost_usd: None,
             pending_auto_pinch: false,
-            auto_pinch_in_progress: false,
             ai_client: None,
             api_key: None,
             active_provider,
@@ -369,6 +378,7 @@ impl AppRuntime {
             session_title: None,
             title_editor: TitleEditor::new(),
             channels: AsyncChannels::new(),
+            argument_completions: HashMap::new(),
             init_explore_id: None,
             cached_init_languages: None,
             event_bus: AgentEventBus::new(),
@@ -442,6 +452,24 @@ impl App {
         app
     }
 
+    /// Get pricing for the current model (None if unknown)
+    pub fn model_pricing(&self) -> Option<krusty_core::ai::models::ModelPricing> {
+        // Dynamic registry first (OpenRouter and custom provider prices live here)
+        if let Some(metadata) = self
+            .services
+            .model_registry
+            .try_get_model(&self.runtime.current_model)
+        {
+            return metadata.pricing();
+        }
+
+        crate::ai::providers::get_provider(self.runtime.active_provider)?
+            .models
+            .iter()
+            .find(|m| m.id == self.runtime.current_model)?
+            .pricing()
+    }
+
     /// Get max context window size for current model
     pub fn max_context_tokens(&self) -> usize {
         // First check dynamic ModelRegistry (OpenRouter models live here)
@@ -535,42 +563,11 @@ impl App {
         self.runtime.active_plan = Some(plan);
     }
 
-    /// Context usage threshold for auto-pinch (80%)
-    const AUTO_PINCH_THRESHOLD: f32 = 0.80;
-
-    /// Check if context usage warrants auto-pinch and set the pending flag
-    ///
-    /// Called after AI response completes. If context is at threshold,
-    /// sets `pending_auto_pinch` which triggers the pinch popup when idle.
-    pub fn check_auto_pinch(&mut self) {
-        // Don't trigger if already pending or no session
-        if self.runtime.pending_auto_pinch || self.runtime.current_session_id.is_none() {
-            return;
-        }
-
-        let max_tokens = self.max_context_tokens();
-        if max_tokens == 0 {
-            return;
-        }
-
-        let usage_ratio = self.runtime.context_tokens_used as f32 / max_tokens as f32;
-
-        if usage_ratio >= Self::AUTO_PINCH_THRESHOLD {
-            tracing::info!(
-                "Context at {:.0}% ({}/{}) - will trigger auto-pinch after idle",
-                usage_ratio * 100.0,
-                self.runtime.context_tokens_used,
-                max_tokens
-            );
-            self.runtime.pending_auto_pinch = true;
-        }
-    }
-
-    /// Trigger auto-pinch if pending and conditions are right
+    /// Open the pinch popup if the context neared its limit
     ///
-    /// Called from main loop. When AI is busy (autonomous work), bypasses the popup
-    /// entirely and runs pinch in the background. When idle, shows the popup for
-    /// manual interaction.
+    /// Called from main loop. The orchestrator pinches on its own while the
+    /// AI works autonomously; this covers the interactive case, once the AI
+    /// is idle.
     pub fn trigger_pending_auto_pinch(&mut self) {
         if !self.runtime.pending_auto_pinch {
             return;
@@ -581,8 +578,8 @@ impl App {
             return;
         }
 
-        // Don't trigger if already in a popup or auto-pinch is running
-        if self.ui.popup != crate::tui::app::Popup::None || self.runtime.auto_pinch_in_progress {
+        // Don't trigger if already in a popup
+        if self.ui.popup != crate::tui::app::Popup::None {
             return;
         }
 
@@ -606,34 +603,16 @@ impl App {
         self.runtime.chat.messages.push((
             "system".to_string(),
             format!(
-                "Context is at {}% capacity ({} / {} tokens). Starting pinch to continue conversation with fresh context...",
+                "Context is at {}% capacity ({} / {} tokens). Pinch to continue the conversation with fresh context.",
                 usage_percent,
                 self.runtime.context_tokens_used,
                 max_tokens
             ),
         ));
 
-        // Check if conversation has pending AI work (multi-turn tool loop).
-        // If the last message is a tool result or assistant message with tool calls,
-        // the AI was mid-flow — bypass popup and auto-pinch silently.
-        let was_autonomous = self.runtime.chat.conversation.last().is_some_and(|msg| {
-            msg.role == crate::ai::types::Role::User
-                && msg
-                    .content
-                    .iter()
-                    .any(|c| matches!(c, crate::ai::types::Content::ToolResult { .. }))
-        });
-
-        if was_autonomous {
-            // AI was working autonomously — bypass popup
-            tracing::info!("Auto-pinch: AI was autonomous, bypassing popup");
-            self.start_auto_pinch();
-        } else {
-            // User is interactive — show popup as before
-            let top_files = self.get_top_files_preview(5);
-            self.ui.popups.pinch.start(usage_percent, top_files);
-            self.ui.popup = crate::tui::app::Popup::Pinch;
-        }
+        let top_files = self.get_top_files_preview(5);
+        self.ui.popups.pinch.start(usage_percent, top_files);
+        self.ui.popup = crate::tui::app::Popup::Pinch;
     }
 
     /// Show a toast notification
@@ -757,6 +736,9 @@ impl App {
             }
         }
 
+        // Discover models from user-defined providers (local servers, gateways)
+        self.start_custom_model_fetch();
+
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(
@@ -863,13 +845,12 @@ impl App {
 
             // Poll async operations
             self.poll_openrouter_fetch();
+            self.poll_custom_model_fetch();
             self.poll_title_generation();
             self.poll_summarization();
-
-            // Poll auto-pinch (background pinch without popup)
-            if self.runtime.auto_pinch_in_progress {
-                self.poll_auto_pinch();
-            }
+            self.poll_compaction();
+            self.poll_argument_completion();
+            self.poll_extension_command();
 
             // Update menu animations (only when on start menu for efficiency)
             if self.ui.view == View::StartMenu {
@@ -8,17 +8,21 @@ use tokio::sync::RwLock;
 
 use crate::agent::{UserHookManager, UserPostToolHook, UserPreToolHook};
 use crate::ai::models::{create_model_registry, ModelMetadata, SharedModelRegistry};
-use crate::ai::providers::{builtin_providers, ProviderId};
-use crate::extensions::WasmHost;
+use crate::ai::providers::{all_providers, ProviderId};
+use crate::extensions::{ExtensionSlashCommands, WasmHost};
 use crate::paths;
 use crate::plan::PlanManager;
 use crate::plugins::PluginManager;
 use crate::process::ProcessRegistry;
 use crate::storage::{CredentialStore, Database, Preferences, SessionManager};
-use crate::tools::{register_all_tools, ToolRegistry};
+use crate::tools::{
+    register_all_tools, register_lsp_tools, register_memory_tools, register_session_search_tool,
+    ToolRegistry,
+};
 use crate::tui::app::AppServices;
 use crate::tui::themes::{Theme, THEME_REGISTRY};
 use crate::tui::utils::{AsyncChannels, McpStatusUpdate};
+use krusty_core::lsp::LspManager;
 use krusty_core::skills::SkillsManager;
 
 /// Initialize core services (tools, extensions, etc.)
@@ -70,6 +74,23 @@ pub async fn init_services(
 
     // Tool registry with hooks
     let tool_registry = init_tool_registry(&user_hook_manager).await;
+
+    // Language servers (started lazily, also from Zed extensions)
+    let mut lsp_manager = LspManager::new();
+    if let Some(host) = &wasm_host {
+        lsp_manager = lsp_manager.with_wasm_host(host.clone());
+    }
+    let lsp_manager = Arc::new(lsp_manager);
+
+    // Slash commands from Zed extensions, run on the shared host
+    let mut slash_commands = ExtensionSlashCommands::new();
+    if let Some(host) = &wasm_host {
+        slash_commands = slash_commands.with_wasm_host(host.clone());
+    }
+    let slash_commands = Arc::new(slash_commands);
+    register_lsp_tools(&tool_registry, lsp_manager.clone()).await;
+    register_session_search_tool(&tool_registry, db_path.clone()).await;
+    register_memory_tools(&tool_registry, db_path.clone()).await;
     let cached_ai_tools = tool_registry.get_ai_tools().await;
 
     // Preferences and theme
@@ -130,8 +151,10 @@ pub async fn init_services(
         user_hook_manager,
         wasm_host,
         plugin_manager,
+        slash_commands,
         skills_manager,
         mcp_manager,
+        lsp_manager,
         mcp_status_tx,
         oauth_status_tx,
     };
@@ -239,8 +262,8 @@ fn init_plan_manager(db_path: &Path) -> Option<PlanManager> {
 fn init_model_registry(preferences: &Option<Preferences>) -> SharedModelRegistry {
     let model_registry = create_model_registry();
 
-    // Load static models from builtin providers
-    for provider in builtin_providers() {
+    // Load static models from builtin and user-defined providers
+    for provider in all_providers() {
         if provider.models.is_empty() {
             continue;
         }
@@ -249,7 +272,8 @@ fn init_model_registry(preferences: &Option<Preferences>) -> SharedModelRegistry
             .iter()
             .map(|m| {
                 let mut meta = ModelMetadata::new(&m.id, &m.display_name, provider.id)
-                    .with_context(m.context_window, m.max_output);
+                    .with_context(m.context_window, m.max_output)
+                    .with_pricing(m.input_price, m.output_price);
                 if let Some(format) = m.reasoning {
                     meta = meta.with_thinking(format);
                 }
@@ -309,6 +333,7 @@ async fn spawn_mcp_connections(
             tracing::warn!("MCP server connection errors: {}", e);
         }
         krusty_core::mcp::tool::register_mcp_tools(mcp.clone(), &registry).await;
+        krusty_core::mcp::tool::spawn_tool_sync(mcp.clone(), registry.clone());
 
         let tool_count = mcp.get_all_tools().await.len();
         if tool_count > 0 {
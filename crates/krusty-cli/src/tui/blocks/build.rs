@@ -39,6 +39,9 @@ const WAVE_INTERVAL: Duration = Duration::from_millis(150);
 /// Spiral spinner interval (agent rows)
 const SPIRAL_INTERVAL: Duration = Duration::from_millis(180);
 
+/// Per-builder diff summary line in the build tool's output
+const DIFF_PREFIX: &str = "**Diff**: ";
+
 /// State of a single builder agent
 #[derive(Debug, Clone)]
 struct BuilderEntry {
@@ -56,6 +59,8 @@ struct BuilderEntry {
     spinner_idx: usize,
     /// Output text (populated on completion)
     output: String,
+    /// Diff summary of the builder's merged worktree (populated on completion)
+    diff: Option<String>,
     /// Whether output is expanded
     expanded: bool,
     /// When this agent started
@@ -82,6 +87,7 @@ impl BuilderEntry {
             current_action: progress.current_action.clone(),
             spinner_idx: 0,
             output: String::new(),
+            diff: None,
             expanded: false,
             started_at: Instant::now(),
             final_elapsed_ms: None,
@@ -282,7 +288,13 @@ impl BuildBlock {
                         builder.status = AgentProgressStatus::Complete;
                     }
                 }
-            } else if let Some((_, ref mut text)) = current_builder {
+            } else if let Some((ref id, ref mut text)) = current_builder {
+                if let Some(diff) = line.strip_prefix(DIFF_PREFIX) {
+                    if let Some(builder) = self.builders.get_mut(id) {
+                        builder.diff = Some(diff.to_string());
+                    }
+                    continue;
+                }
                 text.push_str(line);
                 text.push('\n');
             }
@@ -414,7 +426,10 @@ impl BuildBlock {
             let tools_str = format!("{:>2} tools", builder.displayed_tools());
             let tokens_str = format!("{:>5}", builder.format_tokens());
 
-            let action = builder.current_action.as_deref().unwrap_or("");
+            let action = match (&builder.status, &builder.diff) {
+                (AgentProgressStatus::Complete, Some(diff)) => diff.as_str(),
+                _ => builder.current_action.as_deref().unwrap_or(""),
+            };
             let action_max = (area.width as usize).saturating_sub(38);
             // Truncate action safely at char boundary
             let action_display = if action.len() > action_max && action_max > 3 {
@@ -24,8 +24,6 @@ pub enum PromptType {
     AskUserQuestion,
     /// Tool approval in supervised permission mode
     ToolApproval,
-    /// Permission mode selection
-    PermissionSelect,
 }
 
 /// A single option in a question
@@ -178,6 +176,12 @@ impl DecisionPrompt {
                     label: "Approve".to_string(),
                     description: Some("Execute the tool(s)".to_string()),
                 },
+                PromptOption {
+                    label: "Always allow".to_string(),
+                    description: Some(
+                        "Execute and save an allow rule for this project".to_string(),
+                    ),
+                },
                 PromptOption {
                     label: "Deny".to_string(),
                     description: Some("Block execution".to_string()),
@@ -198,35 +202,6 @@ impl DecisionPrompt {
         self.visible = true;
     }
 
-    /// Show permission mode selection prompt
-    pub fn show_permission_select(&mut self, current_is_supervised: bool) {
-        let question = PromptQuestion {
-            question: "Select permission mode".to_string(),
-            header: "Permissions".to_string(),
-            options: vec![
-                PromptOption {
-                    label: "Supervised".to_string(),
-                    description: Some("Approve write tools before execution".to_string()),
-                },
-                PromptOption {
-                    label: "Autonomous".to_string(),
-                    description: Some("Auto-execute all tools".to_string()),
-                },
-            ],
-            multi_select: false,
-        };
-        self.questions = vec![question];
-        self.current_index = 0;
-        self.selected_option = if current_is_supervised { 0 } else { 1 };
-        self.scroll_offset = 0;
-        self.toggled_options.clear();
-        self.answers.clear();
-        self.prompt_type = PromptType::PermissionSelect;
-        self.tool_use_id = None;
-        self.custom_input_mode = false;
-        self.visible = true;
-    }
-
     /// Hide the prompt
     pub fn hide(&mut self) {
         self.visible = false;
@@ -19,6 +19,8 @@ pub struct StatusBarProps<'a> {
     pub cwd: &'a Path,
     pub git_status: Option<&'a krusty_core::git::GitStatusSummary>,
     pub context_tokens: Option<(usize, usize)>,
+    /// Session spend in USD, from the usage ledger
+    pub session_cost: Option<f64>,
     pub running_processes: usize,
     pub process_elapsed: Option<Duration>,
 }
@@ -31,6 +33,7 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, props: StatusBarProps<'_>) {
         cwd,
         git_status,
         context_tokens,
+        session_cost,
         running_processes,
         process_elapsed,
     } = props;
@@ -107,6 +110,17 @@ pub fn render_status_bar(f: &mut Frame, area: Rect, props: StatusBarProps<'_>) {
         ));
     }
 
+    // Session spend
+    if let Some(cost) = session_cost {
+        let cost_text = format!("${:.2}", cost);
+        left_width += 3 + cost_text.width() as u16;
+        left_spans.push(Span::styled(" │ ", Style::default().fg(theme.dim_color)));
+        left_spans.push(Span::styled(
+            cost_text,
+            Style::default().fg(theme.dim_color),
+        ));
+    }
+
     // Running processes indicator with elapsed time
     if running_processes > 0 {
         let elapsed_str = process_elapsed
@@ -2,7 +2,9 @@
 //!
 //! Handles /command parsing and execution.
 
+use crate::extensions::slash_commands::parse_invocation;
 use crate::tui::app::{App, Popup, View};
+use crate::tui::utils::ExtensionCommandUpdate;
 
 impl App {
     /// Handle slash commands
@@ -13,6 +15,7 @@ impl App {
         match command.as_str() {
             "/home" => {
                 self.runtime.current_session_id = None;
+                self.runtime.session_cost_usd = None;
                 self.runtime.chat.messages.clear();
                 self.runtime.chat.streaming_assistant_idx = None;
                 self.runtime.chat.conversation.clear();
@@ -24,20 +27,23 @@ impl App {
                 let current_dir = self.runtime.working_dir.to_string_lossy().into_owned();
                 self.ui.popups.session.set_current_directory(&current_dir);
 
-                // Get sessions for current directory only
-                let sessions: Vec<_> = self
-                    .list_sessions_for_directory(&current_dir)
-                    .into_iter()
-                    .map(|s| crate::tui::popups::session_list::SessionInfo {
-                        id: s.id,
-                        title: s.title,
-                        updated_at: s.updated_at.format("%Y-%m-%d %H:%M").to_string(),
-                    })
-                    .collect();
+                // Get sessions for current directory only, forks under their parents
+                let sessions = crate::tui::popups::session_list::session_tree(
+                    self.list_sessions_for_directory(&current_dir),
+                );
 
                 self.ui.popups.session.set_sessions(sessions);
                 self.ui.popup = Popup::SessionList;
             }
+            "/search" => {
+                let current_dir = self.runtime.working_dir.to_string_lossy().into_owned();
+                self.ui
+                    .popups
+                    .session_search
+                    .open(&current_dir, &parts[1..].join(" "));
+                self.run_session_search();
+                self.ui.popup = Popup::SessionSearch;
+            }
             "/model" => {
                 // Populate model list from registry (non-blocking)
                 let configured = self.configured_providers();
@@ -101,6 +107,9 @@ impl App {
             "/pinch" => {
                 self.handle_pinch_command();
             }
+            "/compact" => {
+                self.start_compaction();
+            }
             "/terminal" | "/term" | "/shell" => {
                 self.handle_terminal_command(parts.get(1).copied());
             }
@@ -120,11 +129,28 @@ impl App {
                 self.open_hooks_popup();
             }
             "/permissions" | "/perm" => {
-                self.show_permission_select();
+                self.open_permissions_popup();
+            }
+            "/memory" | "/memories" => {
+                self.open_memories_popup();
             }
             "/update" => {
                 self.start_update_check();
             }
+            "/budget" => {
+                self.handle_budget_command(&parts[1..]);
+            }
+            "/undo" => {
+                self.handle_rewind_command(&["last"]);
+            }
+            "/rewind" => {
+                self.handle_rewind_command(&parts[1..]);
+            }
+            "/fork" => {
+                self.handle_fork_command(&parts[1..]);
+            }
+            _ if command.starts_with("/mcp__") && self.handle_mcp_prompt_command(cmd) => {}
+            _ if self.handle_extension_command(cmd) => {}
             _ => {
                 self.runtime
                     .chat
@@ -134,6 +160,337 @@ impl App {
         }
     }
 
+    /// Handle `/mcp__{server}__{prompt} [args]` by sending the rendered prompt
+    ///
+    /// Returns false if no connected server offers the prompt.
+    fn handle_mcp_prompt_command(&mut self, cmd: &str) -> bool {
+        let (name, input) = cmd
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((cmd, ""));
+        let mcp = self.services.mcp_manager.clone();
+        let prompts = futures::executor::block_on(mcp.get_all_prompts());
+        let Some((server, prompt)) = prompts.into_iter().find(|(server, prompt)| {
+            format!("/mcp__{}__{}", server, prompt.name).eq_ignore_ascii_case(name)
+        }) else {
+            return false;
+        };
+
+        let result = prompt
+            .bind_arguments(input)
+            .map_err(anyhow::Error::msg)
+            .and_then(|args| {
+                tokio::task::block_in_place(|| {
+                    tokio::runtime::Handle::current().block_on(mcp.get_prompt(
+                        &server,
+                        &prompt.name,
+                        args,
+                    ))
+                })
+            });
+        match result {
+            Ok(rendered) => self.handle_input_submit(rendered.to_text()),
+            Err(e) => self.runtime.chat.messages.push((
+                "system".to_string(),
+                format!("MCP prompt {} failed: {}", prompt.name, e),
+            )),
+        }
+        true
+    }
+
+    /// Handle an extension's slash command by sending its output as context
+    ///
+    /// The command runs in the background; `poll_extension_command` sends
+    /// its output once it arrives. Returns false if no installed extension
+    /// provides the command.
+    fn handle_extension_command(&mut self, cmd: &str) -> bool {
+        let slash_commands = self.services.slash_commands.clone();
+        let Some((name, _)) = parse_invocation(cmd) else {
+            return false;
+        };
+        if slash_commands.get(name).is_none() {
+            return false;
+        }
+        if self.runtime.channels.extension_command.is_some() {
+            self.runtime.chat.messages.push((
+                "system".to_string(),
+                "Another slash command is still running".to_string(),
+            ));
+            return true;
+        }
+
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.runtime.channels.extension_command = Some(rx);
+        let command = cmd.to_string();
+        let root = self.runtime.working_dir.clone();
+        tokio::spawn(async move {
+            let result = match slash_commands.expand(&command, &root).await {
+                Some(Ok(context)) => Ok(context),
+                Some(Err(e)) => Err(e.to_string()),
+                None => Err("Unknown extension command".to_string()),
+            };
+            let _ = tx.send(ExtensionCommandUpdate { command, result });
+        });
+        true
+    }
+
+    /// Poll for an extension slash command run in the background
+    pub fn poll_extension_command(&mut self) {
+        let Some(rx) = self.runtime.channels.extension_command.as_mut() else {
+            return;
+        };
+        let update = match rx.try_recv() {
+            Ok(update) => update,
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return,
+            Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                self.runtime.channels.extension_command = None;
+                return;
+            }
+        };
+        self.runtime.channels.extension_command = None;
+        match update.result {
+            Ok(context) => self.handle_input_submit(context),
+            Err(e) => self.runtime.chat.messages.push((
+                "system".to_string(),
+                format!("Slash command {} failed: {}", update.command.trim(), e),
+            )),
+        }
+        self.ui.needs_redraw = true;
+    }
+
+    /// Handle /budget command - show or set the session's spending limits
+    ///
+    /// `/budget` shows spend and limits, `/budget soft|hard <usd>` sets a
+    /// limit and `/budget off` clears both.
+    fn handle_budget_command(&mut self, args: &[&str]) {
+        use crate::storage::{SessionBudget, UsageLedger};
+
+        let message = match (
+            &self.services.session_manager,
+            &self.runtime.current_session_id,
+        ) {
+            (Some(sm), Some(session_id)) => {
+                let ledger = UsageLedger::new(sm.db());
+                let mut budget = ledger.get_budget(session_id).unwrap_or_default();
+                let update = match args {
+                    [] => Ok(false),
+                    ["off"] => {
+                        budget = SessionBudget::default();
+                        Ok(true)
+                    }
+                    [kind @ ("soft" | "hard"), amount] => {
+                        match amount.trim_start_matches('$').parse::<f64>() {
+                            Ok(usd) if usd > 0.0 => {
+                                if *kind == "soft" {
+                                    budget.soft_usd = Some(usd);
+                                } else {
+                                    budget.hard_usd = Some(usd);
+                                }
+                                Ok(true)
+                            }
+                            _ => Err(format!("Invalid amount: {}", amount)),
+                        }
+                    }
+                    _ => Err("Usage: /budget [soft <usd> | hard <usd> | off]".to_string()),
+                };
+
+                match update {
+                    Ok(changed) => {
+                        let saved = if changed {
+                            ledger.set_budget(session_id, &budget)
+                        } else {
+                            Ok(())
+                        };
+                        match (saved, ledger.session_totals(session_id)) {
+                            (Ok(()), Ok(totals)) => {
+                                let limit = |l: Option<f64>| {
+                                    l.map(|usd| format!("${:.2}", usd))
+                                        .unwrap_or_else(|| "none".to_string())
+                                };
+                                format!(
+                                    "Session spend ${:.2} ({} calls) · soft budget {} · hard budget {}",
+                                    totals.cost_usd,
+                                    totals.calls,
+                                    limit(budget.soft_usd),
+                                    limit(budget.hard_usd)
+                                )
+                            }
+                            (Err(e), _) | (_, Err(e)) => format!("Failed to update budget: {}", e),
+                        }
+                    }
+                    Err(message) => message,
+                }
+            }
+            _ => "Start a session before setting a budget".to_string(),
+        };
+
+        self.runtime
+            .chat
+            .messages
+            .push(("system".to_string(), message));
+    }
+
+    /// Handle /undo and /rewind commands - restore files from checkpoints
+    ///
+    /// `/rewind` lists checkpoints, `/rewind last` (`/undo`) reverts the most
+    /// recent turn that changed files, `/rewind diff <turn>` shows changes
+    /// since a turn and `/rewind <turn> [chat]` restores files (and optionally
+    /// the conversation) to before that turn.
+    fn handle_rewind_command(&mut self, args: &[&str]) {
+        use crate::storage::CheckpointStore;
+        use krusty_core::tools::checkpoint::{diff_files, restore_files};
+
+        if self.is_busy() {
+            self.runtime.chat.messages.push((
+                "system".to_string(),
+                "Cannot rewind while the agent is working".to_string(),
+            ));
+            return;
+        }
+
+        let mut reload = None;
+        let message = match (
+            &self.services.session_manager,
+            &self.runtime.current_session_id,
+        ) {
+            (Some(sm), Some(session_id)) => {
+                let db = sm.db();
+                let store = CheckpointStore::new(db);
+                let parse_turn = |arg: &str| {
+                    arg.parse::<usize>()
+                        .map_err(|_| anyhow::anyhow!("Invalid turn: {}", arg))
+                };
+                let result = match args {
+                    ["last"] => match store.latest_with_files(session_id) {
+                        Ok(Some(checkpoint)) => restore_files(db, session_id, checkpoint.turn)
+                            .map(|summary| format_restore_summary(&summary)),
+                        Ok(None) => Ok("No file changes to undo".to_string()),
+                        Err(e) => Err(e),
+                    },
+                    [] | ["list"] => store.list(session_id).map(|checkpoints| {
+                        if checkpoints.is_empty() {
+                            return "No checkpoints in this session".to_string();
+                        }
+                        let mut out = String::from("Checkpoints:");
+                        for c in checkpoints {
+                            let time = chrono::DateTime::parse_from_rfc3339(&c.created_at)
+                                .map(|t| {
+                                    t.with_timezone(&chrono::Local).format("%H:%M").to_string()
+                                })
+                                .unwrap_or_default();
+                            out.push_str(&format!(
+                                "\n  {:>3}  {}  {} ({} file{})",
+                                c.turn,
+                                time,
+                                c.prompt.as_deref().unwrap_or("-"),
+                                c.files.len(),
+                                if c.files.len() == 1 { "" } else { "s" }
+                            ));
+                        }
+                        out
+                    }),
+                    ["diff", turn] => parse_turn(turn)
+                        .and_then(|turn| diff_files(db, session_id, turn))
+                        .map(|diffs| {
+                            if diffs.is_empty() {
+                                "No changes since that checkpoint".to_string()
+                            } else {
+                                let body: String = diffs.iter().map(|d| d.diff.as_str()).collect();
+                                format!("```diff\n{}```", body)
+                            }
+                        }),
+                    [turn] | [turn, "chat"] => {
+                        let rewind_chat = args.len() == 2;
+                        parse_turn(turn)
+                            .and_then(|turn| restore_files(db, session_id, turn))
+                            .and_then(|summary| {
+                                if rewind_chat && summary.failed.is_empty() {
+                                    sm.truncate_messages(session_id, summary.message_count)?;
+                                    reload = Some(session_id.clone());
+                                }
+                                Ok(format_restore_summary(&summary))
+                            })
+                    }
+                    _ => Err(anyhow::anyhow!(
+                        "Usage: /rewind [list | last | diff <turn> | <turn> [chat]]"
+                    )),
+                };
+                result.unwrap_or_else(|e| e.to_string())
+            }
+            _ => "No active session".to_string(),
+        };
+
+        if let Some(session_id) = reload {
+            if let Err(e) = self.load_session(&session_id) {
+                tracing::warn!("Failed to reload session after rewind: {}", e);
+            }
+        }
+        self.runtime
+            .chat
+            .messages
+            .push(("system".to_string(), message));
+    }
+
+    /// Handle /fork command - branch the session into a new one
+    ///
+    /// `/fork` copies the whole conversation; `/fork <turn>` keeps only the
+    /// messages before that turn (see `/rewind` for turn numbers), so the
+    /// turn can be retried with a different prompt. The fork uses the current
+    /// model, so switching with `/model` first retries on another one. The
+    /// original session is left untouched.
+    fn handle_fork_command(&mut self, args: &[&str]) {
+        use crate::storage::CheckpointStore;
+
+        if self.is_busy() {
+            self.runtime.chat.messages.push((
+                "system".to_string(),
+                "Cannot fork while the agent is working".to_string(),
+            ));
+            return;
+        }
+
+        let result = match (
+            &self.services.session_manager,
+            &self.runtime.current_session_id,
+        ) {
+            (Some(sm), Some(session_id)) => {
+                let message_count = match args {
+                    [] => Ok(None),
+                    [turn] => turn
+                        .parse::<usize>()
+                        .map_err(|_| anyhow::anyhow!("Invalid turn: {}", turn))
+                        .and_then(|turn| {
+                            CheckpointStore::new(sm.db())
+                                .get(session_id, turn)?
+                                .map(|c| Some(c.message_count))
+                                .ok_or_else(|| anyhow::anyhow!("No checkpoint for turn {}", turn))
+                        }),
+                    _ => Err(anyhow::anyhow!("Usage: /fork [turn]")),
+                };
+                message_count.and_then(|count| {
+                    let model = Some(self.runtime.current_model.as_str());
+                    sm.fork_session(session_id, count, None, model)
+                })
+            }
+            _ => Err(anyhow::anyhow!("No active session")),
+        };
+
+        let message = match result {
+            Ok(fork_id) => match self.load_session(&fork_id) {
+                Ok(()) => format!(
+                    "Forked into a new session ({} messages). /load to switch back.",
+                    self.runtime.chat.conversation.len()
+                ),
+                Err(e) => format!("Forked, but failed to load the fork: {}", e),
+            },
+            Err(e) => e.to_string(),
+        };
+        self.runtime
+            .chat
+            .messages
+            .push(("system".to_string(), message));
+    }
+
     /// Handle /init command - intelligently analyze codebase and generate KRAB.md
     fn handle_init_command(&mut self) {
         use crate::tui::app::View;
@@ -1035,13 +1392,27 @@ impl App {
         self.ui.popups.mcp.update(servers);
     }
 
-    /// Show permission mode selection popup
-    fn show_permission_select(&mut self) {
-        let is_supervised = self.runtime.permission_mode
-            == krusty_core::tools::registry::PermissionMode::Supervised;
-        self.ui
-            .decision_prompt
-            .show_permission_select(is_supervised);
+    /// Open permission mode and rules popup
+    fn open_permissions_popup(&mut self) {
+        let popup = &mut self.ui.popups.permissions;
+        popup.reset();
+        popup.mode = self.runtime.permission_mode;
+        popup.working_dir = self.runtime.working_dir.to_string_lossy().into_owned();
+        popup.selected_index = 0;
+        popup.scroll_offset = 0;
+        self.refresh_permissions_popup();
+        self.ui.popup = Popup::Permissions;
+    }
+
+    /// Open the project memories popup
+    fn open_memories_popup(&mut self) {
+        let popup = &mut self.ui.popups.memories;
+        popup.reset();
+        popup.working_dir = self.runtime.working_dir.to_string_lossy().into_owned();
+        popup.selected_index = 0;
+        popup.scroll_offset = 0;
+        self.refresh_memories_popup();
+        self.ui.popup = Popup::Memories;
     }
 
     /// Open hooks configuration popup
@@ -1059,6 +1430,21 @@ impl App {
     }
 }
 
+/// Describe the outcome of a checkpoint restore for the chat
+fn format_restore_summary(summary: &krusty_core::tools::checkpoint::RestoreSummary) -> String {
+    let mut out = format!(
+        "Rewound to before turn {}: {} file{} restored, {} removed",
+        summary.turn,
+        summary.restored.len(),
+        if summary.restored.len() == 1 { "" } else { "s" },
+        summary.deleted.len()
+    );
+    for failure in &summary.failed {
+        out.push_str(&format!("\n  failed: {}", failure));
+    }
+    out
+}
+
 /// Generate KRAB.md template content
 fn generate_krab_template(
     project_name: &str,
@@ -2,12 +2,33 @@
 //!
 //! Main keyboard input handling. Popup-specific key handlers are in popup_keys.rs.
 
+use std::time::Duration;
+
 use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
 use crate::agent::{AgentEvent, InterruptReason};
+use crate::extensions::wasm_host::SlashCommandArgumentCompletion;
 use crate::tui::app::{App, Popup, View};
 use crate::tui::input::InputAction;
-use crate::tui::utils::TitleAction;
+use crate::tui::utils::{ArgumentCompletionUpdate, TitleAction};
+
+/// Typing pause before an extension is asked for argument completions
+const ARGUMENT_COMPLETION_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Cached argument lists before the completion cache starts over
+const ARGUMENT_COMPLETION_CACHE_SIZE: usize = 64;
+
+/// Split `name args...` into the command name and its arguments
+///
+/// The argument being typed is empty right after a space.
+fn split_argument_query(query: &str) -> (String, Vec<String>) {
+    let (name, rest) = query.split_once(char::is_whitespace).unwrap_or((query, ""));
+    let mut arguments: Vec<String> = rest.split_whitespace().map(str::to_string).collect();
+    if rest.is_empty() || rest.ends_with(char::is_whitespace) {
+        arguments.push(String::new());
+    }
+    (name.to_string(), arguments)
+}
 
 impl App {
     /// Main keyboard event dispatcher
@@ -112,10 +133,14 @@ impl App {
                 }
                 // Only plain Enter selects autocomplete - Shift+Enter should insert newline
                 KeyCode::Enter if modifiers.is_empty() => {
-                    if let Some(cmd) = self.ui.autocomplete.get_selected() {
-                        self.handle_slash_command(cmd.primary);
+                    if let Some(cmd) = self.ui.autocomplete.get_selected().cloned() {
                         self.ui.input.clear();
                         self.ui.autocomplete.hide();
+                        if cmd.takes_args {
+                            self.ui.input.insert_text(&format!("{} ", cmd.primary));
+                        } else {
+                            self.handle_slash_command(&cmd.primary);
+                        }
                     }
                     return;
                 }
@@ -446,12 +471,19 @@ impl App {
                     .iter()
                     .any(|ext| query.to_lowercase().ends_with(ext));
 
+            // Once arguments follow the command name, Enter submits the line;
+            // extension commands complete their arguments instead
             if is_file_path {
                 self.ui.autocomplete.hide();
-            } else if self.ui.autocomplete.visible {
+            } else if query.contains(char::is_whitespace) {
+                let query = query.to_string();
+                self.complete_extension_argument(&query);
+            } else if self.ui.autocomplete.visible && !self.ui.autocomplete.completing_arguments {
                 self.ui.autocomplete.update(query);
             } else {
-                self.ui.autocomplete.show(query);
+                let query = query.to_string();
+                self.refresh_slash_commands();
+                self.ui.autocomplete.show(&query);
             }
         } else {
             self.ui.autocomplete.hide();
@@ -485,13 +517,171 @@ impl App {
             if self.ui.file_search.visible {
                 self.ui.file_search.update(query);
             } else {
-                self.ui.file_search.show(query);
+                let query = query.to_string();
+                self.refresh_mcp_resources();
+                self.ui.file_search.show(&query);
             }
         } else {
             self.ui.file_search.hide();
         }
     }
 
+    /// Offer cached MCP prompts and extension commands as slash commands
+    fn refresh_slash_commands(&mut self) {
+        use crate::tui::input::autocomplete::CommandSuggestion;
+
+        let mcp = self.services.mcp_manager.clone();
+        let prompts = futures::executor::block_on(mcp.get_all_prompts());
+        let mut commands: Vec<CommandSuggestion> = prompts
+            .iter()
+            .map(|(server, prompt)| CommandSuggestion::mcp_prompt(server, prompt))
+            .collect();
+        commands.extend(
+            self.services
+                .slash_commands
+                .commands()
+                .iter()
+                .map(CommandSuggestion::extension),
+        );
+        self.ui.autocomplete.set_extra_commands(commands);
+    }
+
+    /// Ask the extension behind `/name args` for argument completions
+    ///
+    /// Hides the popup for other commands. Results are cached per argument
+    /// list; misses are fetched in the background once typing pauses and
+    /// picked up by `poll_argument_completion`.
+    fn complete_extension_argument(&mut self, query: &str) {
+        let (name, arguments) = split_argument_query(query);
+        let slash_commands = self.services.slash_commands.clone();
+        if slash_commands.get(&name).is_none() {
+            self.runtime.channels.argument_completion = None;
+            self.ui.autocomplete.hide();
+            return;
+        }
+
+        let key = (name, arguments);
+        if let Some(completions) = self.runtime.argument_completions.get(&key).cloned() {
+            self.runtime.channels.argument_completion = None;
+            self.show_argument_completions(&key.0, &key.1, &completions);
+            return;
+        }
+
+        // Replacing the receiver drops the previous request, which then
+        // skips the extension call when its debounce ends
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.runtime.channels.argument_completion = Some(rx);
+        let (name, arguments) = key;
+        tokio::spawn(async move {
+            tokio::time::sleep(ARGUMENT_COMPLETION_DEBOUNCE).await;
+            if tx.is_closed() {
+                return;
+            }
+            let result = slash_commands
+                .complete_argument(&name, arguments.clone())
+                .await
+                .map_err(|e| e.to_string());
+            let _ = tx.send(ArgumentCompletionUpdate {
+                name,
+                arguments,
+                result,
+            });
+        });
+    }
+
+    /// Poll for extension argument completions fetched in the background
+    pub fn poll_argument_completion(&mut self) {
+        let Some(rx) = self.runtime.channels.argument_completion.as_mut() else {
+            return;
+        };
+        let update = match rx.try_recv() {
+            Ok(update) => update,
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return,
+            Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
+                self.runtime.channels.argument_completion = None;
+                return;
+            }
+        };
+        self.runtime.channels.argument_completion = None;
+
+        let completions = match update.result {
+            Ok(completions) => completions,
+            Err(e) => {
+                tracing::debug!("No completions for /{}: {}", update.name, e);
+                Vec::new()
+            }
+        };
+        if self.runtime.argument_completions.len() >= ARGUMENT_COMPLETION_CACHE_SIZE {
+            self.runtime.argument_completions.clear();
+        }
+        self.runtime.argument_completions.insert(
+            (update.name.clone(), update.arguments.clone()),
+            completions.clone(),
+        );
+
+        // Drop results for a line the user has since changed
+        let content = self.ui.input.content();
+        let current = content
+            .strip_prefix('/')
+            .filter(|query| query.contains(char::is_whitespace))
+            .map(split_argument_query);
+        if current.as_ref() == Some(&(update.name.clone(), update.arguments.clone())) {
+            self.show_argument_completions(&update.name, &update.arguments, &completions);
+            self.ui.needs_redraw = true;
+        }
+    }
+
+    /// Show completions for the last of `arguments`, or hide the popup if none
+    fn show_argument_completions(
+        &mut self,
+        name: &str,
+        arguments: &[String],
+        completions: &[SlashCommandArgumentCompletion],
+    ) {
+        use crate::tui::input::autocomplete::CommandSuggestion;
+
+        if completions.is_empty() {
+            self.ui.autocomplete.hide();
+            return;
+        }
+        let typed = &arguments[..arguments.len() - 1];
+        let suggestions = completions
+            .iter()
+            .map(|completion| {
+                let line = std::iter::once(format!("/{}", name))
+                    .chain(typed.iter().cloned())
+                    .chain(std::iter::once(completion.new_text.clone()))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                CommandSuggestion::argument_completion(line, completion)
+            })
+            .collect();
+        self.ui.autocomplete.show_argument_completions(suggestions);
+    }
+
+    /// Offer cached MCP resources and resource templates in file search
+    fn refresh_mcp_resources(&mut self) {
+        use crate::tui::input::file_search::FileEntry;
+
+        let mcp = self.services.mcp_manager.clone();
+        let (resources, templates) = futures::executor::block_on(async {
+            (
+                mcp.get_all_resources().await,
+                mcp.get_all_resource_templates().await,
+            )
+        });
+        let entries = resources
+            .iter()
+            .map(|(server, r)| FileEntry::mcp_resource(server, &r.uri, &r.name))
+            .chain(
+                templates
+                    .iter()
+                    .map(|(server, t)| FileEntry::mcp_resource(server, &t.uri_template, &t.name)),
+            )
+            .collect();
+        self.ui.file_search.set_mcp_resources(entries);
+    }
+
     /// Insert a file reference into the input, replacing the @query
     pub fn insert_file_reference(&mut self, path: &str) {
         let content = self.ui.input.content().to_string();
@@ -616,15 +806,6 @@ impl App {
             PromptType::ToolApproval => {
                 self.handle_tool_approval_answer(&answers);
             }
-            PromptType::PermissionSelect => {
-                if let Some(crate::tui::components::PromptAnswer::Selected(idx)) = answers.first() {
-                    self.runtime.permission_mode = if *idx == 0 {
-                        krusty_core::tools::registry::PermissionMode::Supervised
-                    } else {
-                        krusty_core::tools::registry::PermissionMode::Autonomous
-                    };
-                }
-            }
         }
     }
 
@@ -739,3 +920,27 @@ impl App {
         self.send_to_ai();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_argument_query_marks_the_argument_being_typed() {
+        assert_eq!(
+            split_argument_query("docs serde der"),
+            (
+                "docs".to_string(),
+                vec!["serde".to_string(), "der".to_string()]
+            )
+        );
+        assert_eq!(
+            split_argument_query("docs serde "),
+            ("docs".to_string(), vec!["serde".to_string(), String::new()])
+        );
+        assert_eq!(
+            split_argument_query("docs "),
+            ("docs".to_string(), vec![String::new()])
+        );
+    }
+}
@@ -1,8 +1,9 @@
 //! Model fetching handlers
 //!
-//! Async model fetching from dynamic providers (OpenRouter).
+//! Async model fetching from dynamic providers (OpenRouter, user-defined).
 
-use crate::ai::providers::ProviderId;
+use crate::ai::custom_providers;
+use crate::ai::providers::{get_provider, ProviderId};
 use crate::tui::app::App;
 
 impl App {
@@ -89,6 +90,53 @@ impl App {
         }
     }
 
+    /// Start async model discovery for configured user-defined providers
+    ///
+    /// Only providers with a `models_url` are queried. Results land in the
+    /// registry; completions are signalled so an open popup can refresh.
+    pub fn start_custom_model_fetch(&mut self) {
+        let providers: Vec<ProviderId> = self
+            .configured_providers()
+            .into_iter()
+            .filter(|p| get_provider(*p).is_some_and(|c| c.models_url.is_some()))
+            .collect();
+        if providers.is_empty() {
+            return;
+        }
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        self.runtime.channels.custom_models = Some(rx);
+
+        for provider in providers {
+            let api_key = self.services.credential_store.get_api_key(&provider);
+            let registry = self.services.model_registry.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match custom_providers::fetch_models(provider, api_key.as_deref()).await {
+                    Ok(models) if !models.is_empty() => {
+                        registry.set_models(provider, models).await;
+                        let _ = tx.send(provider);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to fetch {} models: {}", provider, e),
+                }
+            });
+        }
+    }
+
+    /// Poll for user-defined provider model discovery completions
+    pub fn poll_custom_model_fetch(&mut self) {
+        let mut updated = false;
+        if let Some(rx) = &mut self.runtime.channels.custom_models {
+            while rx.try_recv().is_ok() {
+                updated = true;
+            }
+        }
+        if updated {
+            self.refresh_model_popup();
+        }
+    }
+
     /// Refresh model popup with current registry data
     pub fn refresh_model_popup(&mut self) {
         let configured = self.configured_providers();
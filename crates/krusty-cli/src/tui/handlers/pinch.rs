@@ -5,14 +5,20 @@
 //! 2. AI summarizes conversation (async, using Sonnet 4.5 + extended thinking)
 //! 3. User provides direction for next phase
 //! 4. New linked session is created
-
-use std::path::PathBuf;
-
-use crate::agent::{generate_summary, PinchContext, PinchContextInput, SummarizationResult};
+//!
+//! `/compact` is the in-place alternative: old turns are summarized and stale
+//! tool output elided without leaving the session.
+
+use crate::agent::auto_pinch::read_key_file_contents;
+use crate::agent::compaction::compact_session;
+use crate::agent::{
+    generate_summary, read_project_instructions, PinchContext, PinchContextInput,
+    SummarizationResult,
+};
 use crate::ai::client::AiClient;
 use crate::storage::{FileActivityTracker, RankedFile};
 use crate::tui::app::App;
-use crate::tui::utils::{SummarizationUpdate, TitleUpdate};
+use crate::tui::utils::{CompactionUpdate, SummarizationUpdate, TitleUpdate};
 
 impl App {
     /// Start the summarization phase of pinch
@@ -97,47 +103,15 @@ impl App {
 
     /// Read contents of top-ranked files for summarization context
     fn read_key_file_contents(&self, ranked_files: &[RankedFile]) -> Vec<(String, String)> {
-        ranked_files
-            .iter()
-            .take(10)
-            .filter_map(|file| {
-                let path = if std::path::Path::new(&file.path).is_absolute() {
-                    PathBuf::from(&file.path)
-                } else {
-                    self.runtime.working_dir.join(&file.path)
-                };
-                std::fs::read_to_string(&path)
-                    .ok()
-                    .map(|content| (file.path.clone(), content))
-            })
-            .collect()
+        read_key_file_contents(&self.runtime.working_dir, ranked_files, 10)
     }
 
     /// Read project context from instruction files
     fn read_project_context(&self) -> Option<String> {
-        // Support common AI coding assistant instruction file formats
-        const PROJECT_FILES: &[&str] = &[
-            "KRAB.md",
-            "krab.md",
-            "AGENTS.md",
-            "agents.md",
-            "CLAUDE.md",
-            "claude.md",
-            ".cursorrules",
-            ".windsurfrules",
-            ".clinerules",
-            ".github/copilot-instructions.md",
-            "JULES.md",
-            "gemini.md",
-        ];
-        for filename in PROJECT_FILES {
-            let path = self.runtime.working_dir.join(filename);
-            if let Ok(content) = std::fs::read_to_string(&path) {
-                tracing::debug!("Loaded project context from {}", filename);
-                return Some(content);
-            }
-        }
-        None
+        read_project_instructions(&self.runtime.working_dir).map(|(filename, content)| {
+            tracing::debug!("Loaded project context from {}", filename);
+            content
+        })
     }
 
     /// Create AI client for summarization
@@ -195,231 +169,107 @@ impl App {
         }
     }
 
-    /// Get ranked files for summarization context
-    fn get_ranked_files_for_summarization(&self) -> Vec<RankedFile> {
-        if let (Some(sm), Some(session_id)) = (
-            &self.services.session_manager,
-            &self.runtime.current_session_id,
-        ) {
-            let tracker = FileActivityTracker::new(sm.db(), session_id.clone());
-            return tracker.get_ranked_files(20).unwrap_or_default();
-        }
-        Vec::new()
-    }
-
-    /// Start auto-pinch (bypasses popup, used when AI is working autonomously)
+    /// Compact the current session in place (/compact)
     ///
-    /// Directly starts summarization without popup interaction.
-    pub fn start_auto_pinch(&mut self) {
-        self.runtime.auto_pinch_in_progress = true;
-
-        let ranked_files = self.get_ranked_files_for_summarization();
-        let file_contents = self.read_key_file_contents(&ranked_files);
-        let project_context = self.read_project_context();
-        let conversation = self.runtime.chat.conversation.clone();
-        let current_model = self.runtime.current_model.clone();
+    /// Stored messages are kept; the orchestrator sends the compacted view
+    /// from the next turn on.
+    pub fn start_compaction(&mut self) {
+        let notice = if self.is_busy() {
+            Some("Cannot compact while the agent is working")
+        } else if self.runtime.channels.compaction.is_some() {
+            Some("Compaction already in progress")
+        } else if self.runtime.current_session_id.is_none() {
+            Some("No conversation to compact. Start a chat first.")
+        } else {
+            None
+        };
+        if let Some(notice) = notice {
+            self.runtime
+                .chat
+                .messages
+                .push(("system".to_string(), notice.to_string()));
+            return;
+        }
 
-        let client = match self.create_summarization_client() {
-            Some(c) => c,
-            None => {
-                tracing::error!("Auto-pinch: no AI client for summarization");
-                self.runtime.auto_pinch_in_progress = false;
-                return;
-            }
+        let Some(client) = self.create_summarization_client() else {
+            self.runtime.chat.messages.push((
+                "system".to_string(),
+                "No AI client available for compaction".to_string(),
+            ));
+            return;
         };
+        let session_id = self.runtime.current_session_id.clone().unwrap_or_default();
+        let conversation = self.runtime.chat.conversation.clone();
+        let working_dir = self.runtime.working_dir.clone();
+        let tokens_before = self.runtime.context_tokens_used;
+        let db_path = crate::paths::config_dir().join("krusty.db");
 
         let (tx, rx) = tokio::sync::oneshot::channel();
-        self.runtime.channels.summarization = Some(rx);
-
-        let msg_count = conversation.len();
-        let file_count = file_contents.len();
+        self.runtime.channels.compaction = Some(rx);
+        self.runtime.chat.messages.push((
+            "system".to_string(),
+            "Compacting conversation...".to_string(),
+        ));
 
         tokio::spawn(async move {
-            let result = generate_summary(
+            let result = compact_session(
                 &client,
+                &db_path,
+                &session_id,
+                &working_dir,
                 &conversation,
-                None, // no preservation hints in auto mode
-                &ranked_files,
-                &file_contents,
-                project_context.as_deref(),
-                Some(&current_model),
+                tokens_before,
             )
             .await;
-
-            let update = SummarizationUpdate {
+            let _ = tx.send(CompactionUpdate {
+                session_id,
                 result: result.map_err(|e| e.to_string()),
-            };
-            let _ = tx.send(update);
+            });
         });
-
-        tracing::info!(
-            "Auto-pinch: started summarization with {} messages, {} file contents",
-            msg_count,
-            file_count
-        );
     }
 
-    /// Poll auto-pinch summarization and complete when ready
-    pub fn poll_auto_pinch(&mut self) {
-        if !self.runtime.auto_pinch_in_progress {
+    /// Poll for /compact results
+    pub fn poll_compaction(&mut self) {
+        let Some(rx) = self.runtime.channels.compaction.as_mut() else {
             return;
-        }
-
-        let rx = match self.runtime.channels.summarization.as_mut() {
-            Some(rx) => rx,
-            None => return,
         };
 
-        match rx.try_recv() {
+        let message = match rx.try_recv() {
             Ok(update) => {
-                self.runtime.channels.summarization = None;
+                tracing::info!("Compaction finished for session {}", update.session_id);
                 match update.result {
-                    Ok(summary) => {
-                        tracing::info!(
-                            "Auto-pinch: summarization complete: {}",
-                            &summary.work_summary[..100.min(summary.work_summary.len())]
-                        );
-                        self.complete_auto_pinch(summary);
-                    }
-                    Err(e) => {
-                        tracing::error!("Auto-pinch: summarization failed: {}", e);
-                        self.runtime.auto_pinch_in_progress = false;
-                        self.runtime
-                            .chat
-                            .messages
-                            .push(("system".to_string(), format!("Auto-pinch failed: {}", e)));
+                    Ok(outcome) => {
+                        self.runtime.pending_auto_pinch = false;
+                        format_compaction_notice(
+                            outcome.summarized_messages,
+                            outcome.elided_results,
+                        )
                     }
+                    Err(e) => format!("Compaction failed: {}", e),
                 }
             }
-            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => {
-                // Still summarizing
-            }
+            Err(tokio::sync::oneshot::error::TryRecvError::Empty) => return,
             Err(tokio::sync::oneshot::error::TryRecvError::Closed) => {
-                self.runtime.channels.summarization = None;
-                self.runtime.auto_pinch_in_progress = false;
-                tracing::error!("Auto-pinch: summarization task cancelled");
+                "Compaction task cancelled".to_string()
             }
-        }
-    }
-
-    /// Complete auto-pinch: create linked session and resume AI
-    fn complete_auto_pinch(&mut self, summary_result: SummarizationResult) {
-        let ranked_files = self.get_ranked_files_for_summarization();
-        let project_context = self.read_project_context();
-        let key_file_contents = self
-            .read_key_file_contents(&ranked_files)
-            .into_iter()
-            .take(5)
-            .collect();
-        let active_plan = self.runtime.active_plan.as_ref().map(|p| p.to_markdown());
-
-        // Extract summary text before consuming summary_result
-        let summary_text = summary_result.work_summary.clone();
-
-        let pinch_ctx = PinchContext::from_input(PinchContextInput {
-            source_session_id: self.runtime.current_session_id.clone().unwrap_or_default(),
-            source_session_title: self
-                .runtime
-                .session_title
-                .clone()
-                .unwrap_or_else(|| "Untitled".to_string()),
-            summary: summary_result,
-            ranked_files,
-            preservation_hints: None, // no preservation hints
-            direction: None,          // no direction — auto-continue
-            project_context,
-            key_file_contents,
-            active_plan,
-        });
-
-        let Some(sm) = &self.services.session_manager else {
-            tracing::error!("Auto-pinch: no session manager");
-            self.runtime.auto_pinch_in_progress = false;
-            return;
         };
+        self.runtime.channels.compaction = None;
+        self.runtime
+            .chat
+            .messages
+            .push(("system".to_string(), message));
+    }
 
-        let Some(parent_id) = &self.runtime.current_session_id else {
-            tracing::error!("Auto-pinch: no current session");
-            self.runtime.auto_pinch_in_progress = false;
-            return;
-        };
-
-        let parent_title = self
-            .runtime
-            .session_title
-            .clone()
-            .unwrap_or_else(|| "Session".to_string());
-        let fallback_title = format!(
-            "{} (cont.)",
-            &parent_title.chars().take(45).collect::<String>()
-        );
-
-        match sm.create_linked_session(
-            &fallback_title,
-            parent_id,
-            &pinch_ctx,
-            Some(&self.runtime.current_model),
-            Some(&self.runtime.working_dir.to_string_lossy()),
-            self.runtime
-                .git_status
-                .as_ref()
-                .and_then(|status| status.branch.as_deref()),
+    /// Get ranked files for summarization context
+    fn get_ranked_files_for_summarization(&self) -> Vec<RankedFile> {
+        if let (Some(sm), Some(session_id)) = (
+            &self.services.session_manager,
+            &self.runtime.current_session_id,
         ) {
-            Ok(new_id) => {
-                // Save pinch context as first message
-                let system_msg_text = pinch_ctx.to_system_message();
-                let system_msg_json =
-                    serde_json::json!([{ "type": "text", "text": system_msg_text }]).to_string();
-                if let Err(e) = sm.save_message(&new_id, "system", &system_msg_json) {
-                    tracing::warn!("Auto-pinch: failed to save pinch message: {}", e);
-                }
-
-                // Carry over active plan
-                if let Some(ref plan) = self.runtime.active_plan {
-                    if let Some(ref pm) = self.services.plan_manager {
-                        if let Err(e) = pm.save_plan_for_session(&new_id, plan) {
-                            tracing::warn!("Auto-pinch: failed to carry over plan: {}", e);
-                        }
-                    }
-                }
-
-                // Save "Continue working on the current task." as user message
-                let content_json = serde_json::to_string(&vec![crate::ai::types::Content::Text {
-                    text: "Continue working on the current task.".to_string(),
-                }])
-                .unwrap_or_else(|_| "[\"Continue working on the current task.\"]".to_string());
-
-                if let Err(e) = sm.save_message(&new_id, "user", &content_json) {
-                    tracing::warn!("Auto-pinch: failed to save continue message: {}", e);
-                }
-
-                // Spawn title generation
-                self.spawn_pinch_title_generation(new_id.clone(), parent_title, summary_text, None);
-
-                // Load the new session and resume
-                self.save_block_ui_states();
-                if let Err(e) = self.load_session(&new_id) {
-                    tracing::error!("Auto-pinch: failed to load new session: {}", e);
-                    self.runtime.auto_pinch_in_progress = false;
-                    return;
-                }
-
-                tracing::info!(
-                    "Auto-pinch: complete, resuming AI in new session {}",
-                    new_id
-                );
-                self.runtime.auto_pinch_in_progress = false;
-                self.send_to_ai();
-            }
-            Err(e) => {
-                tracing::error!("Auto-pinch: failed to create session: {}", e);
-                self.runtime.auto_pinch_in_progress = false;
-                self.runtime
-                    .chat
-                    .messages
-                    .push(("system".to_string(), format!("Auto-pinch failed: {}", e)));
-            }
+            let tracker = FileActivityTracker::new(sm.db(), session_id.clone());
+            return tracker.get_ranked_files(20).unwrap_or_default();
         }
+        Vec::new()
     }
 
     /// Complete the pinch by creating a linked session
@@ -643,3 +493,14 @@ impl App {
         self.create_ai_client()
     }
 }
+
+/// Chat notice for an in-place compaction
+pub(crate) fn format_compaction_notice(
+    summarized_messages: usize,
+    elided_results: usize,
+) -> String {
+    format!(
+        "Compacted conversation: {} earlier messages summarized, {} stale tool results elided. Full history is kept.",
+        summarized_messages, elided_results
+    )
+}
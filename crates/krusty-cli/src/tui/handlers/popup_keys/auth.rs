@@ -98,6 +98,8 @@ impl App {
 
                         if provider == ProviderId::OpenRouter {
                             self.start_openrouter_fetch();
+                        } else if provider.is_custom() {
+                            self.start_custom_model_fetch();
                         }
                     }
                 }
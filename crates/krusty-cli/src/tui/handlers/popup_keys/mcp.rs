@@ -35,14 +35,6 @@ impl App {
     /// Connect to selected MCP server
     fn mcp_connect(&mut self) {
         if let Some(server) = self.ui.popups.mcp.get_selected() {
-            if server.server_type == "remote" {
-                self.ui
-                    .popups
-                    .mcp
-                    .set_status("Remote servers handled by API".to_string());
-                return;
-            }
-
             let name = server.name.clone();
             let mcp = self.services.mcp_manager.clone();
             let registry = self.services.tool_registry.clone();
@@ -83,14 +75,6 @@ impl App {
     /// Disconnect from selected MCP server
     fn mcp_disconnect(&mut self) {
         if let Some(server) = self.ui.popups.mcp.get_selected() {
-            if server.server_type == "remote" {
-                self.ui
-                    .popups
-                    .mcp
-                    .set_status("Remote servers handled by API".to_string());
-                return;
-            }
-
             let name = server.name.clone();
             let mcp = self.services.mcp_manager.clone();
             let status_tx = self.services.mcp_status_tx.clone();
@@ -0,0 +1,76 @@
+//! Memories popup keyboard handler
+
+use crossterm::event::KeyCode;
+
+use crate::paths;
+use crate::storage::{Database, MemoryStore};
+use crate::tui::app::{App, Popup};
+use crate::tui::popups::memories::MemoriesStage;
+
+impl App {
+    /// Handle memories popup keyboard events
+    pub fn handle_memories_popup_key(&mut self, code: KeyCode) {
+        match &self.ui.popups.memories.stage {
+            MemoriesStage::List => match code {
+                KeyCode::Esc => self.ui.popup = Popup::None,
+                KeyCode::Up | KeyCode::Char('k') => self.ui.popups.memories.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.ui.popups.memories.next(),
+                KeyCode::Enter | KeyCode::Char('e') => self.ui.popups.memories.start_edit(),
+                KeyCode::Char('d') => self.delete_selected_memory(),
+                _ => {}
+            },
+            MemoriesStage::Edit { .. } => match code {
+                KeyCode::Esc => self.ui.popups.memories.reset(),
+                KeyCode::Enter => self.save_pending_memory(),
+                KeyCode::Backspace => self.ui.popups.memories.backspace(),
+                KeyCode::Char(c) => self.ui.popups.memories.add_char(c),
+                _ => {}
+            },
+        }
+    }
+
+    fn delete_selected_memory(&mut self) {
+        if let Some(id) = self.ui.popups.memories.get_selected_id() {
+            if let Ok(db) = Database::new(&paths::config_dir().join("krusty.db")) {
+                if let Err(e) = MemoryStore::new(&db).delete(id, None) {
+                    self.ui.popups.memories.error = Some(e.to_string());
+                }
+                self.refresh_memories_popup();
+            }
+        }
+    }
+
+    fn save_pending_memory(&mut self) {
+        let Some((id, content)) = self.ui.popups.memories.pending_edit() else {
+            return;
+        };
+        let content = content.to_string();
+        if let Ok(db) = Database::new(&paths::config_dir().join("krusty.db")) {
+            let store = MemoryStore::new(&db);
+            let saved = match id {
+                Some(id) => store.update(id, None, &content).map(|_| ()),
+                None => store
+                    .add(&self.ui.popups.memories.working_dir, None, &content)
+                    .map(|_| ()),
+            };
+            if let Err(e) = saved {
+                self.ui.popups.memories.error = Some(e.to_string());
+                return;
+            }
+            self.ui.popups.memories.reset();
+            self.refresh_memories_popup();
+        }
+    }
+
+    /// Reload the current project's memories into the memories popup
+    pub fn refresh_memories_popup(&mut self) {
+        let working_dir = self.ui.popups.memories.working_dir.clone();
+        let memories = Database::new(&paths::config_dir().join("krusty.db"))
+            .and_then(|db| MemoryStore::new(&db).list(Some(&working_dir), None))
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load memories: {}", e);
+                Vec::new()
+            });
+        self.ui.popups.memories.set_memories(memories);
+    }
+}
@@ -7,6 +7,8 @@ mod auth;
 mod file_preview;
 mod hooks;
 mod mcp;
+mod memories;
+mod permissions;
 mod pinch;
 mod plugins;
 mod process;
@@ -62,6 +64,9 @@ impl App {
             Popup::SessionList => {
                 self.handle_session_list_key(code);
             }
+            Popup::SessionSearch => {
+                self.handle_session_search_key(code);
+            }
             Popup::Auth => {
                 self.handle_auth_popup_key(code, modifiers);
             }
@@ -86,6 +91,12 @@ impl App {
             Popup::Hooks => {
                 self.handle_hooks_popup_key(code);
             }
+            Popup::Permissions => {
+                self.handle_permissions_popup_key(code);
+            }
+            Popup::Memories => {
+                self.handle_memories_popup_key(code);
+            }
             Popup::None => {}
         }
     }
@@ -165,6 +176,46 @@ impl App {
         }
     }
 
+    /// Handle session search popup keys
+    fn handle_session_search_key(&mut self, code: KeyCode) {
+        let popup = &mut self.ui.popups.session_search;
+        match code {
+            KeyCode::Esc => self.ui.popup = Popup::None,
+            KeyCode::Up => popup.prev(),
+            KeyCode::Down => popup.next(),
+            KeyCode::Tab => {
+                popup.toggle_scope();
+                self.run_session_search();
+            }
+            KeyCode::Backspace => {
+                popup.query.pop();
+                self.run_session_search();
+            }
+            KeyCode::Char(c) => {
+                popup.query.push(c);
+                self.run_session_search();
+            }
+            KeyCode::Enter => {
+                if let Some(hit) = popup.get_selected() {
+                    let (session_id, message_index) = (hit.session_id.clone(), hit.message_index);
+                    self.save_block_ui_states();
+                    match self.load_session(&session_id) {
+                        Ok(()) => {
+                            self.jump_to_message(message_index);
+                            self.ui.pending_view_change = Some(crate::tui::app::View::Chat);
+                        }
+                        Err(e) => self.runtime.chat.messages.push((
+                            "system".to_string(),
+                            format!("Failed to load session: {}", e),
+                        )),
+                    }
+                    self.ui.popup = Popup::None;
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Handle session list popup keys
     fn handle_session_list_key(&mut self, code: KeyCode) {
         match code {
@@ -0,0 +1,83 @@
+//! Permissions popup keyboard handler
+
+use crossterm::event::KeyCode;
+
+use crate::agent::permissions;
+use crate::paths;
+use crate::storage::Database;
+use crate::tui::app::{App, Popup};
+use crate::tui::popups::permissions::PermissionsStage;
+use krusty_core::tools::registry::PermissionMode;
+
+impl App {
+    /// Handle permissions popup keyboard events
+    pub fn handle_permissions_popup_key(&mut self, code: KeyCode) {
+        match &self.ui.popups.permissions.stage {
+            PermissionsStage::List => match code {
+                KeyCode::Esc => self.ui.popup = Popup::None,
+                KeyCode::Up | KeyCode::Char('k') => self.ui.popups.permissions.prev(),
+                KeyCode::Down | KeyCode::Char('j') => self.ui.popups.permissions.next(),
+                KeyCode::Enter if self.ui.popups.permissions.is_add_new_selected() => {
+                    self.ui.popups.permissions.start_add();
+                }
+                KeyCode::Char('m') => self.toggle_permission_mode(),
+                KeyCode::Char('d') => self.delete_selected_permission_rule(),
+                _ => {}
+            },
+            PermissionsStage::AddRule { .. } => match code {
+                KeyCode::Esc => self.ui.popups.permissions.reset(),
+                KeyCode::Enter => self.save_pending_permission_rule(),
+                KeyCode::Tab => self.ui.popups.permissions.toggle_scope(),
+                KeyCode::Backspace => self.ui.popups.permissions.backspace(),
+                KeyCode::Char(c) => self.ui.popups.permissions.add_char(c),
+                _ => {}
+            },
+        }
+    }
+
+    fn toggle_permission_mode(&mut self) {
+        self.runtime.permission_mode = match self.runtime.permission_mode {
+            PermissionMode::Supervised => PermissionMode::Autonomous,
+            PermissionMode::Autonomous => PermissionMode::Supervised,
+        };
+        self.ui.popups.permissions.mode = self.runtime.permission_mode;
+    }
+
+    fn delete_selected_permission_rule(&mut self) {
+        if let Some(id) = self.ui.popups.permissions.get_selected_rule_id() {
+            if let Ok(db) = Database::new(&paths::config_dir().join("krusty.db")) {
+                if let Err(e) = permissions::delete_rule(&db, id, None) {
+                    self.ui.popups.permissions.error = Some(e.to_string());
+                }
+                self.refresh_permissions_popup();
+            }
+        }
+    }
+
+    fn save_pending_permission_rule(&mut self) {
+        if let Some(rule) = self.ui.popups.permissions.take_pending_rule() {
+            if let Ok(db) = Database::new(&paths::config_dir().join("krusty.db")) {
+                if let Err(e) = permissions::save_rule(&db, &rule, None) {
+                    self.ui.popups.permissions.error = Some(e.to_string());
+                    return;
+                }
+                self.ui.popups.permissions.reset();
+                self.refresh_permissions_popup();
+            }
+        }
+    }
+
+    /// Reload rules for the current project into the permissions popup
+    pub fn refresh_permissions_popup(&mut self) {
+        let rules = Database::new(&paths::config_dir().join("krusty.db"))
+            .and_then(|db| {
+                permissions::PermissionPolicy::load(&db, &self.runtime.working_dir, None)
+            })
+            .map(|policy| policy.rules().to_vec())
+            .unwrap_or_else(|e| {
+                tracing::warn!("Failed to load permission rules: {}", e);
+                Vec::new()
+            });
+        self.ui.popups.permissions.set_rules(rules);
+    }
+}
@@ -8,7 +8,7 @@ use anyhow::Result;
 
 use crate::ai::client::AiClient;
 use crate::ai::providers::ProviderId;
-use crate::tools::{register_build_tool, register_explore_tool};
+use crate::tools::{register_build_tool, register_explore_tool, register_task_tool};
 use crate::tui::app::App;
 
 impl App {
@@ -55,7 +55,7 @@ impl App {
         Ok(())
     }
 
-    /// Register explore and build tools if client is available
+    /// Register explore, build and task tools if client is available
     pub(crate) async fn register_explore_tool_if_client(&mut self) {
         let client = self.create_ai_client();
 
@@ -72,16 +72,24 @@ impl App {
 
             // Register build tool (The Kraken)
             register_build_tool(
+                &self.services.tool_registry,
+                client.clone(),
+                self.runtime.cancellation.clone(),
+            )
+            .await;
+
+            // Register task tool (user-defined agents)
+            register_task_tool(
                 &self.services.tool_registry,
                 client,
                 self.runtime.cancellation.clone(),
             )
             .await;
 
-            // Update cached tools so API knows about explore and build
+            // Update cached tools so API knows about explore, build and task
             self.services.cached_ai_tools = self.services.tool_registry.get_ai_tools().await;
             tracing::info!(
-                "Registered explore and build tools, total tools: {}",
+                "Registered explore, build and task tools, total tools: {}",
                 self.services.cached_ai_tools.len()
             );
         }
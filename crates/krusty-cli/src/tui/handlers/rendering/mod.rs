@@ -48,6 +48,7 @@ impl App {
                 self.runtime.context_tokens_used,
             ),
             Popup::SessionList => self.ui.popups.session.render(f, &self.ui.theme),
+            Popup::SessionSearch => self.ui.popups.session_search.render(f, &self.ui.theme),
             Popup::Auth => self.ui.popups.auth.render(f, &self.ui.theme),
             Popup::ProcessList => self.ui.popups.process.render(f, &self.ui.theme),
             Popup::PluginsBrowser => self.ui.popups.plugins.render(f, &self.ui.theme),
@@ -56,6 +57,8 @@ impl App {
             Popup::SkillsBrowser => self.ui.popups.skills.render(f, &self.ui.theme),
             Popup::McpBrowser => self.ui.popups.mcp.render(f, &self.ui.theme),
             Popup::Hooks => self.ui.popups.hooks.render(f, &self.ui.theme),
+            Popup::Permissions => self.ui.popups.permissions.render(f, &self.ui.theme),
+            Popup::Memories => self.ui.popups.memories.render(f, &self.ui.theme),
         }
 
         // Render toasts on top of everything
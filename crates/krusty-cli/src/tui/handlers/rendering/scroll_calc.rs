@@ -31,7 +31,11 @@ impl App {
         // Pre-render markdown to cache (same as render_messages) to ensure consistent line counts
         self.ui.markdown_cache.check_width(wrap_width);
 
-        for (role, content) in &self.runtime.chat.messages {
+        let jump_to = self.ui.scroll_system.scroll.jump_to_message;
+        for (i, (role, content)) in self.runtime.chat.messages.iter().enumerate() {
+            if jump_to == Some(i) {
+                self.ui.scroll_system.scroll.jump_line = Some(total);
+            }
             if let Some((block_type, idx)) = indices.get_and_increment(role) {
                 // Handle block types
                 let height = match block_type {
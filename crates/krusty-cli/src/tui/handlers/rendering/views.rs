@@ -150,8 +150,7 @@ impl App {
             if y < inner_area.y + inner_area.height {
                 // Color eyes pink on the second line
                 if i == 1 && (line.contains(" o ") || line.contains("-")) {
-                    let mut x_pos = crab_start_x;
-                    for ch in line.chars() {
+                    for (x_pos, ch) in (crab_start_x..).zip(line.chars()) {
                         if x_pos < inner_area.x + inner_area.width {
                             if let Some(cell) = f.buffer_mut().cell_mut(Position::new(x_pos, y)) {
                                 if ch == 'o' || ch == '-' {
@@ -164,11 +163,9 @@ impl App {
                                 cell.set_bg(self.ui.theme.bg_color);
                             }
                         }
-                        x_pos += 1;
                     }
                 } else {
-                    let mut x_pos = crab_start_x;
-                    for ch in line.chars() {
+                    for (x_pos, ch) in (crab_start_x..).zip(line.chars()) {
                         if x_pos < inner_area.x + inner_area.width {
                             if let Some(cell) = f.buffer_mut().cell_mut(Position::new(x_pos, y)) {
                                 cell.set_char(ch);
@@ -176,14 +173,13 @@ impl App {
                                 cell.set_bg(self.ui.theme.bg_color);
                             }
                         }
-                        x_pos += 1;
                     }
                 }
             }
         }
 
         // Quick Actions section
-        let padding = " ".repeat(((area.width as usize / 2).saturating_sub(25)).max(0));
+        let padding = " ".repeat((area.width as usize / 2).saturating_sub(25));
         let commands_text = vec![
             Line::from(vec![
                 Span::raw(&padding),
@@ -400,6 +396,7 @@ impl App {
                 cwd: &self.runtime.working_dir,
                 git_status: self.runtime.git_status.as_ref(),
                 context_tokens: None,
+                session_cost: None,
                 running_processes: self.runtime.running_process_count,
                 process_elapsed: self.runtime.running_process_elapsed,
             },
@@ -541,6 +538,7 @@ impl App {
             .scroll
             .update_max_scroll(msg_total_lines, msg_visible_height);
         self.ui.scroll_system.scroll.apply_scroll_to_bottom();
+        self.ui.scroll_system.scroll.apply_jump_to_message();
 
         // NOW render messages with correct scroll position
         self.render_messages(f, messages_chunk);
@@ -673,6 +671,7 @@ impl App {
                 cwd: &self.runtime.working_dir,
                 git_status: self.runtime.git_status.as_ref(),
                 context_tokens,
+                session_cost: self.runtime.session_cost_usd,
                 running_processes: self.runtime.running_process_count,
                 process_elapsed: self.runtime.running_process_elapsed,
             },
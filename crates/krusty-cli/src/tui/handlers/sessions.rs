@@ -6,7 +6,7 @@ use anyhow::Result;
 
 use crate::ai::client::AiClient;
 use crate::ai::types::{Content, ModelMessage, Role};
-use crate::storage::SessionManager;
+use crate::storage::{MessageSearch, SessionManager, UsageLedger};
 use crate::tui::app::{App, WorkMode};
 use crate::tui::blocks::{
     BashBlock, EditBlock, ReadBlock, ThinkingBlock, ToolResultBlock, WriteBlock,
@@ -14,6 +14,9 @@ use crate::tui::blocks::{
 use crate::tui::state::{hash_content, BlockManager};
 use crate::tui::utils::TitleUpdate;
 
+/// Maximum results shown in the session search popup
+const SESSION_SEARCH_LIMIT: usize = 50;
+
 impl App {
     /// Create a new session
     pub fn create_session(&mut self, first_message: &str) -> Option<String> {
@@ -34,6 +37,7 @@ impl App {
                 tracing::info!("Created new session: {}", id);
                 self.runtime.current_session_id = Some(id.clone());
                 self.runtime.session_title = Some(fallback_title);
+                self.runtime.session_cost_usd = None;
 
                 // Clear any active plan when starting a new session
                 self.clear_plan();
@@ -123,6 +127,19 @@ impl App {
         }
     }
 
+    /// Load a session's spend from the usage ledger (None if nothing was priced)
+    pub fn load_session_cost(&self, session_id: &str) -> Option<f64> {
+        let sm = self.services.session_manager.as_ref()?;
+        match UsageLedger::new(sm.db()).session_totals(session_id) {
+            Ok(totals) if totals.calls > totals.unpriced_calls => Some(totals.cost_usd),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Failed to load session usage: {}", e);
+                None
+            }
+        }
+    }
+
     /// Save a message to the current session
     /// Content is serialized as JSON for full fidelity (supports tools, images, etc.)
     pub fn save_model_message(&self, message: &ModelMessage) {
@@ -301,6 +318,7 @@ impl App {
         // Use stored token count if available, otherwise estimate
         self.runtime.context_tokens_used = stored_token_count
             .unwrap_or_else(|| Self::estimate_conversation_tokens(&self.runtime.chat.conversation));
+        self.runtime.session_cost_usd = self.load_session_cost(session_id);
 
         tracing::info!(
             "Loaded session {} with {} messages, {} blocks, ~{} tokens",
@@ -346,6 +364,37 @@ impl App {
             .unwrap_or_default()
     }
 
+    /// Re-run the session search popup's query
+    pub fn run_session_search(&mut self) {
+        let popup = &self.ui.popups.session_search;
+        let hits = match &self.services.session_manager {
+            Some(sm) => MessageSearch::new(sm.db())
+                .search(
+                    &popup.query,
+                    popup.working_dir(),
+                    None,
+                    SESSION_SEARCH_LIMIT,
+                )
+                .unwrap_or_else(|e| {
+                    tracing::warn!("Session search failed: {}", e);
+                    Vec::new()
+                }),
+            None => Vec::new(),
+        };
+        self.ui.popups.session_search.set_results(hits);
+    }
+
+    /// Scroll the loaded conversation to a stored message
+    pub fn jump_to_message(&mut self, message_index: usize) {
+        let last = self.runtime.chat.messages.len().saturating_sub(1);
+        if let Some(&start) = self.runtime.chat.loaded_message_starts.get(message_index) {
+            self.ui
+                .scroll_system
+                .scroll
+                .request_jump_to_message(start.min(last));
+        }
+    }
+
     /// Save all block UI states to the database
     pub fn save_block_ui_states(&self) {
         let Some(sm) = &self.services.session_manager else {
@@ -441,10 +490,15 @@ impl App {
     /// - "tool_result" → ToolResultBlock (grep/glob/unknown tools)
     fn build_display_from_conversation(&mut self) {
         self.runtime.chat.messages.clear();
+        self.runtime.chat.loaded_message_starts.clear();
         self.runtime.chat.streaming_assistant_idx = None;
         self.runtime.blocks = BlockManager::new();
 
         for msg in &self.runtime.chat.conversation {
+            self.runtime
+                .chat
+                .loaded_message_starts
+                .push(self.runtime.chat.messages.len());
             let base_role = match msg.role {
                 Role::User => "user",
                 Role::Assistant => "assistant",
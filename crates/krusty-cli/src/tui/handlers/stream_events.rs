@@ -10,6 +10,7 @@ use crate::ai::types::{AiToolCall, Content, ModelMessage, Role};
 use crate::plan::PlanFile;
 use crate::tui::app::{App, WorkMode};
 use crate::tui::blocks::{StreamBlock, WebSearchBlock};
+use crate::tui::handlers::pinch::format_compaction_notice;
 
 impl App {
     fn append_streaming_assistant_delta(&mut self, delta: String) {
@@ -196,6 +197,12 @@ impl App {
             LoopEvent::ToolDenied { id } => {
                 tracing::info!("Tool denied: {}", id);
             }
+            LoopEvent::PermissionRuleAdded { rule } => {
+                self.runtime.chat.messages.push((
+                    "system".to_string(),
+                    format!("Saved permission rule: {}", rule.spec()),
+                ));
+            }
             LoopEvent::AwaitingInput {
                 tool_call_id,
                 tool_name,
@@ -295,6 +302,68 @@ impl App {
                 self.runtime.context_tokens_used = prompt_tokens + completion_tokens;
                 self.save_session_token_count();
             }
+            LoopEvent::Cost {
+                call_cost_usd,
+                session_cost_usd,
+            } => {
+                if call_cost_usd.is_some() || session_cost_usd > 0.0 {
+                    self.runtime.session_cost_usd = Some(session_cost_usd);
+                }
+            }
+            LoopEvent::BudgetWarning {
+                spent_usd,
+                limit_usd,
+            } => {
+                self.runtime.chat.messages.push((
+                    "system".to_string(),
+                    format!(
+                        "Session spend ${:.2} has passed the ${:.2} soft budget",
+                        spent_usd, limit_usd
+                    ),
+                ));
+            }
+            LoopEvent::BudgetExceeded {
+                spent_usd,
+                limit_usd,
+            } => {
+                self.runtime.chat.messages.push((
+                    "system".to_string(),
+                    format!(
+                        "Stopped: session spend ${:.2} reached the ${:.2} hard budget. Raise it with /budget hard <usd>",
+                        spent_usd, limit_usd
+                    ),
+                ));
+            }
+            LoopEvent::ContextNearLimit { .. } => {
+                // Opens the pinch popup once idle, unless the orchestrator
+                // pinches first
+                self.runtime.pending_auto_pinch = true;
+            }
+            LoopEvent::SessionPinched {
+                session_id, title, ..
+            } => {
+                tracing::info!("Auto-pinch: continuing in session {}", session_id);
+                self.runtime.current_session_id = Some(session_id);
+                self.runtime.session_title = Some(title);
+                self.runtime.context_tokens_used = 0;
+                self.runtime.pending_auto_pinch = false;
+                self.runtime.chat.messages.push((
+                    "system".to_string(),
+                    "Context neared its limit. Summarized and continuing in a linked session."
+                        .to_string(),
+                ));
+            }
+            LoopEvent::ConversationCompacted {
+                summarized_messages,
+                elided_results,
+                ..
+            } => {
+                self.runtime.pending_auto_pinch = false;
+                self.runtime.chat.messages.push((
+                    "system".to_string(),
+                    format_compaction_notice(summarized_messages, elided_results),
+                ));
+            }
             LoopEvent::TitleGenerated { title } => {
                 self.runtime.session_title = Some(title);
             }
@@ -306,7 +375,6 @@ impl App {
                 self.runtime.channels.loop_events = None;
                 self.runtime.channels.loop_input = None;
                 self.runtime.pending_ask_user_calls.clear();
-                self.check_auto_pinch();
             }
             LoopEvent::Error { error } => {
                 self.handle_stream_error(error);
@@ -469,7 +537,7 @@ impl App {
                 .push(("write".to_string(), String::new()));
         }
 
-        if name == "Task" || name == "explore" {
+        if name == "Task" || name == "task" || name == "explore" {
             tracing::info!(
                 "handle_tool_start: explore tool '{}' detected, block will be created on execution",
                 name
@@ -487,6 +555,7 @@ impl App {
                 | "write"
                 | "processes"
                 | "Task"
+                | "task"
                 | "explore"
                 | "build"
                 | "AskUserQuestion"
@@ -130,6 +130,24 @@ impl App {
                     content_blocks.push(loaded.content);
                     display_parts.push(format!("[Image: {}]", loaded.display_name));
                 }
+                InputSegment::McpResource { server, uri } => {
+                    if uri.contains('{') {
+                        anyhow::bail!("Fill in the template variables in {} first", uri);
+                    }
+                    let mcp = self.services.mcp_manager.clone();
+                    let contents = tokio::task::block_in_place(|| {
+                        tokio::runtime::Handle::current().block_on(mcp.read_resource(&server, &uri))
+                    })?;
+                    content_blocks.push(Content::Text {
+                        text: format!(
+                            "Resource {} from MCP server {}:\n\n{}",
+                            uri,
+                            server,
+                            krusty_core::mcp::format_resource_contents(&contents)
+                        ),
+                    });
+                    display_parts.push(format!("[Resource: {}]", uri));
+                }
                 InputSegment::ClipboardImage(id) => {
                     // Extract clipboard id (format: "clipboard:uuid")
                     let clipboard_id = id.strip_prefix("clipboard:").unwrap_or(&id);
@@ -298,6 +316,8 @@ impl App {
             process_registry: self.runtime.process_registry.clone(),
             db_path,
             skills_manager: self.services.skills_manager.clone(),
+            lsp_manager: Some(self.services.lsp_manager.clone()),
+            editor: None,
         };
 
         let config = OrchestratorConfig {
@@ -308,6 +328,14 @@ impl App {
             user_id: None,
             initial_work_mode: self.ui.work_mode.into(),
             generate_title: is_new_session,
+            pricing: self.model_pricing(),
+            context_window: self.max_context_tokens(),
+            auto_pinch: self
+                .services
+                .preferences
+                .as_ref()
+                .map(|p| p.get_auto_pinch_policy())
+                .unwrap_or_default(),
         };
 
         let conversation = self.runtime.chat.conversation.clone();
@@ -195,7 +195,7 @@ impl App {
                 }
             }
 
-            if tool_name == "explore" || tool_name == "Task" {
+            if tool_name == "explore" || tool_name == "Task" || tool_name == "task" {
                 let prompt = tool_call
                     .arguments
                     .get("prompt")
@@ -257,7 +257,12 @@ impl App {
         use crate::tui::components::PromptAnswer;
 
         self.runtime.approval_requested_at = None;
-        let approved = matches!(answers.first(), Some(PromptAnswer::Selected(0)));
+        // Options: 0 = Approve, 1 = Always allow, 2 = Deny
+        let (approved, always_allow) = match answers.first() {
+            Some(PromptAnswer::Selected(0)) => (true, false),
+            Some(PromptAnswer::Selected(1)) => (true, true),
+            _ => (false, false),
+        };
 
         // Send approval via orchestrator LoopInput channel
         if let Some(ref tx) = self.runtime.channels.loop_input {
@@ -265,6 +270,7 @@ impl App {
                 let _ = tx.send(crate::agent::loop_events::LoopInput::ToolApproval {
                     tool_call_id: id.clone(),
                     approved,
+                    always_allow,
                 });
             }
         }
@@ -391,6 +397,7 @@ impl App {
                     let _ = tx.send(crate::agent::loop_events::LoopInput::ToolApproval {
                         tool_call_id: id.clone(),
                         approved: false,
+                        always_allow: false,
                     });
                 }
             }
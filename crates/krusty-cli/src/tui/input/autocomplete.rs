@@ -8,13 +8,59 @@ use ratatui::{
     Frame,
 };
 
+use std::borrow::Cow;
+
 use crate::tui::themes::Theme;
 
 #[derive(Debug, Clone)]
 pub struct CommandSuggestion {
-    pub primary: &'static str,
+    pub primary: Cow<'static, str>,
     pub aliases: Vec<&'static str>,
-    pub description: &'static str,
+    pub description: Cow<'static, str>,
+    /// Selecting inserts the command for the user to add arguments
+    pub takes_args: bool,
+}
+
+impl CommandSuggestion {
+    /// Slash command for an MCP prompt: `/mcp__{server}__{prompt}`
+    pub fn mcp_prompt(server: &str, prompt: &krusty_core::mcp::McpPrompt) -> Self {
+        Self {
+            primary: format!("/mcp__{}__{}", server, prompt.name).into(),
+            aliases: vec![],
+            description: prompt
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Prompt from MCP server {}", server))
+                .into(),
+            takes_args: !prompt.arguments.is_empty(),
+        }
+    }
+
+    /// Slash command contributed by a Zed extension
+    pub fn extension(command: &krusty_core::extensions::ExtensionSlashCommand) -> Self {
+        Self {
+            primary: format!("/{}", command.name).into(),
+            aliases: vec![],
+            description: command.description.clone().into(),
+            takes_args: command.requires_argument,
+        }
+    }
+
+    /// Argument completion for an extension command
+    ///
+    /// `line` is the command line with the completion's text in place of the
+    /// argument being typed.
+    pub fn argument_completion(
+        line: String,
+        completion: &krusty_core::extensions::wasm_host::SlashCommandArgumentCompletion,
+    ) -> Self {
+        Self {
+            primary: line.into(),
+            aliases: vec![],
+            description: completion.label.clone().into(),
+            takes_args: !completion.run_command,
+        }
+    }
 }
 
 /// Autocomplete popup for slash commands
@@ -25,6 +71,8 @@ pub struct AutocompletePopup {
     pub selected: usize,
     pub visible: bool,
     pub query: String,
+    /// Showing argument completions rather than command names
+    pub completing_arguments: bool,
 }
 
 impl Default for AutocompletePopup {
@@ -41,6 +89,26 @@ impl AutocompletePopup {
             selected: 0,
             visible: false,
             query: String::new(),
+            completing_arguments: false,
+        }
+    }
+
+    /// Replace the MCP prompt and extension commands offered after the built-in ones
+    pub fn set_extra_commands(&mut self, commands: Vec<CommandSuggestion>) {
+        self.suggestions = get_all_commands();
+        self.suggestions.extend(commands);
+        self.completing_arguments = false;
+    }
+
+    /// Show argument completions in the order the extension returned them
+    pub fn show_argument_completions(&mut self, completions: Vec<CommandSuggestion>) {
+        self.filtered = (0..completions.len()).map(|i| (i, 100)).collect();
+        self.suggestions = completions;
+        self.completing_arguments = true;
+        self.visible = !self.filtered.is_empty();
+        self.query.clear();
+        if self.selected >= self.filtered.len() {
+            self.selected = 0;
         }
     }
 
@@ -130,7 +198,7 @@ impl AutocompletePopup {
             }
         }
 
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.sort_by_key(|s| std::cmp::Reverse(s.1));
         self.filtered = scored;
     }
 
@@ -155,14 +223,14 @@ impl AutocompletePopup {
                 }
 
                 spans.push(Span::styled(
-                    cmd.primary,
+                    cmd.primary.as_ref(),
                     Style::default()
                         .fg(theme.accent_color)
                         .add_modifier(Modifier::BOLD),
                 ));
                 spans.push(Span::raw("  "));
                 spans.push(Span::styled(
-                    cmd.description,
+                    cmd.description.as_ref(),
                     Style::default().fg(theme.text_color),
                 ));
 
@@ -235,89 +303,148 @@ fn fuzzy_match(text: &str, pattern: &str) -> Option<i32> {
 pub fn get_all_commands() -> Vec<CommandSuggestion> {
     vec![
         CommandSuggestion {
-            primary: "/home",
+            primary: "/home".into(),
             aliases: vec![],
-            description: "Return to start menu",
+            description: "Return to start menu".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/load",
+            primary: "/load".into(),
             aliases: vec![],
-            description: "Load previous session",
+            description: "Load previous session".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/model",
+            primary: "/search".into(),
             aliases: vec![],
-            description: "Select AI model",
+            description: "Search all sessions' messages".into(),
+            takes_args: true,
         },
         CommandSuggestion {
-            primary: "/auth",
+            primary: "/model".into(),
             aliases: vec![],
-            description: "Manage API providers",
+            description: "Select AI model".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/init",
+            primary: "/auth".into(),
             aliases: vec![],
-            description: "Initialize project (create KRAB.md)",
+            description: "Manage API providers".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/theme",
+            primary: "/init".into(),
             aliases: vec![],
-            description: "Change color theme",
+            description: "Initialize project (create KRAB.md)".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/clear",
+            primary: "/theme".into(),
             aliases: vec![],
-            description: "Clear chat messages",
+            description: "Change color theme".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/pinch",
+            primary: "/clear".into(),
             aliases: vec![],
-            description: "Continue in new session with context",
+            description: "Clear chat messages".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/cmd",
+            primary: "/pinch".into(),
             aliases: vec![],
-            description: "Show all controls",
+            description: "Continue in new session with context".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/terminal",
+            primary: "/compact".into(),
+            aliases: vec![],
+            description: "Summarize old turns in this session".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/budget".into(),
+            aliases: vec![],
+            description: "Show or set session spend limits".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/undo".into(),
+            aliases: vec![],
+            description: "Revert file changes from the last turn".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/rewind".into(),
+            aliases: vec![],
+            description: "List, diff or restore checkpoints".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/fork".into(),
+            aliases: vec![],
+            description: "Branch the session, optionally before a turn".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/cmd".into(),
+            aliases: vec![],
+            description: "Show all controls".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/terminal".into(),
             aliases: vec!["term", "shell"],
-            description: "Open interactive terminal",
+            description: "Open interactive terminal".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/ps",
+            primary: "/ps".into(),
             aliases: vec!["processes"],
-            description: "View background processes",
+            description: "View background processes".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/skills",
+            primary: "/skills".into(),
             aliases: vec![],
-            description: "Browse and manage skills",
+            description: "Browse and manage skills".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/plugins",
+            primary: "/plugins".into(),
             aliases: vec![],
-            description: "Browse and manage installable plugins",
+            description: "Browse and manage installable plugins".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/plan",
+            primary: "/plan".into(),
             aliases: vec![],
-            description: "View or manage active plan",
+            description: "View or manage active plan".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/mcp",
+            primary: "/mcp".into(),
             aliases: vec![],
-            description: "Browse and manage MCP servers",
+            description: "Browse and manage MCP servers".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/hooks",
+            primary: "/hooks".into(),
             aliases: vec![],
-            description: "Configure tool execution hooks",
+            description: "Configure tool execution hooks".into(),
+            takes_args: false,
         },
         CommandSuggestion {
-            primary: "/permissions",
+            primary: "/permissions".into(),
             aliases: vec!["perm"],
-            description: "Toggle supervised/autonomous mode",
+            description: "Permission mode and allow/deny rules".into(),
+            takes_args: false,
+        },
+        CommandSuggestion {
+            primary: "/memory".into(),
+            aliases: vec!["memories"],
+            description: "View and edit project memories".into(),
+            takes_args: false,
         },
     ]
 }
@@ -342,4 +469,29 @@ mod tests {
         let first = ac.get_selected().unwrap();
         assert_eq!(first.primary, "/model");
     }
+
+    #[test]
+    fn test_argument_completions_replace_commands() {
+        use krusty_core::extensions::wasm_host::SlashCommandArgumentCompletion;
+
+        let mut ac = AutocompletePopup::new();
+        let completion = SlashCommandArgumentCompletion {
+            label: "serde".to_string(),
+            new_text: "serde".to_string(),
+            run_command: true,
+        };
+        ac.show_argument_completions(vec![CommandSuggestion::argument_completion(
+            "/rustdoc serde".to_string(),
+            &completion,
+        )]);
+        assert!(ac.visible && ac.completing_arguments);
+        let selected = ac.get_selected().unwrap();
+        assert_eq!(selected.primary, "/rustdoc serde");
+        assert!(!selected.takes_args);
+
+        ac.set_extra_commands(vec![]);
+        ac.show("mod");
+        assert!(!ac.completing_arguments);
+        assert_eq!(ac.get_selected().unwrap().primary, "/model");
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! Triggered by `@` in input, similar to slash command autocomplete.
 //! Two modes:
-//! - Fuzzy search: type to filter files
+//! - Fuzzy search: type to filter files and MCP resources
 //! - Tree browser: expandable/collapsible directory tree
 
 use ratatui::{
@@ -37,6 +37,17 @@ pub struct FileEntry {
     pub is_dir: bool,
 }
 
+impl FileEntry {
+    /// Entry for an MCP resource or resource template, inserted as `[mcp:server:uri]`
+    pub fn mcp_resource(server: &str, uri: &str, name: &str) -> Self {
+        Self {
+            path: format!("mcp:{}:{}", server, uri),
+            name: name.to_string(),
+            is_dir: false,
+        }
+    }
+}
+
 /// A visible entry in the tree (includes depth for indentation)
 #[derive(Debug, Clone)]
 pub struct TreeEntry {
@@ -83,8 +94,10 @@ pub struct FileSearchPopup {
     pub mode: FileSearchMode,
     /// Search query (fuzzy mode)
     pub query: String,
-    /// All indexed files
+    /// All indexed files, followed by MCP resources
     files: Vec<FileEntry>,
+    /// Resources from connected MCP servers
+    mcp_resources: Vec<FileEntry>,
     /// Filtered results with scores (fuzzy mode)
     filtered: Vec<(usize, i32)>,
     /// Selected index (fuzzy mode)
@@ -107,6 +120,7 @@ impl FileSearchPopup {
             mode: FileSearchMode::Fuzzy,
             query: String::new(),
             files: Vec::new(),
+            mcp_resources: Vec::new(),
             filtered: Vec::new(),
             selected: 0,
             scroll_offset: 0,
@@ -127,6 +141,15 @@ impl FileSearchPopup {
             (false, true) => std::cmp::Ordering::Greater,
             _ => a.path.cmp(&b.path),
         });
+        self.files.extend(self.mcp_resources.iter().cloned());
+    }
+
+    /// Replace the MCP resources offered alongside files
+    pub fn set_mcp_resources(&mut self, resources: Vec<FileEntry>) {
+        let file_count = self.files.len() - self.mcp_resources.len().min(self.files.len());
+        self.files.truncate(file_count);
+        self.files.extend(resources.iter().cloned());
+        self.mcp_resources = resources;
     }
 
     fn index_dir(&mut self, abs_path: &Path, rel_path: &Path) {
@@ -174,7 +197,7 @@ impl FileSearchPopup {
         self.mode = FileSearchMode::Fuzzy;
 
         // Index files if not already done
-        if self.files.is_empty() {
+        if self.files.len() == self.mcp_resources.len() {
             self.index_files();
         }
 
@@ -499,7 +522,7 @@ impl FileSearchPopup {
             }
         }
 
-        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        scored.sort_by_key(|s| std::cmp::Reverse(s.1));
         self.filtered = scored.into_iter().take(50).collect();
     }
 
@@ -5,6 +5,7 @@
 //! - Bracketed paths: [/path/to/file.pdf]
 //! - Raw paths: /path/to/file.pdf (when pasted)
 //! - URLs: https://example.com/image.png
+//! - MCP resources: [mcp:server:uri]
 
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -21,6 +22,8 @@ pub enum InputSegment {
     ImageUrl(String),
     /// Clipboard image reference (from paste)
     ClipboardImage(String),
+    /// Resource on a connected MCP server
+    McpResource { server: String, uri: String },
 }
 
 // Bracketed patterns: [path] or [url]
@@ -40,6 +43,7 @@ static RAW_PATH_PATTERN: Lazy<Regex> = Lazy::new(|| {
 /// - `[/absolute/path/image.jpg]` - bracketed absolute paths
 /// - `[https://example.com/image.png]` - bracketed URLs
 /// - `[clipboard:id]` - clipboard image references
+/// - `[mcp:server:uri]` - MCP server resources
 /// - `/path/to/file.pdf` - raw paths (when pasted)
 /// - `./file.png` - raw relative paths
 pub fn parse_input(text: &str, working_dir: &Path) -> Vec<InputSegment> {
@@ -60,6 +64,11 @@ pub fn parse_input(text: &str, working_dir: &Path) -> Vec<InputSegment> {
             InputSegment::ImageUrl(inner.to_string())
         } else if inner.starts_with("clipboard:") {
             InputSegment::ClipboardImage(inner.to_string())
+        } else if let Some((server, uri)) = parse_mcp_reference(inner) {
+            InputSegment::McpResource {
+                server: server.to_string(),
+                uri: uri.to_string(),
+            }
         } else if has_supported_extension(inner) {
             let path = if Path::new(inner).is_absolute() {
                 PathBuf::from(inner)
@@ -156,6 +165,12 @@ fn has_supported_extension(s: &str) -> bool {
         .unwrap_or(false)
 }
 
+/// Split `mcp:server:uri` into server and URI
+fn parse_mcp_reference(inner: &str) -> Option<(&str, &str)> {
+    let (server, uri) = inner.strip_prefix("mcp:")?.split_once(':')?;
+    (!server.is_empty() && !uri.is_empty()).then_some((server, uri))
+}
+
 /// Check if input contains any file references
 pub fn has_file_references(text: &str) -> bool {
     if RAW_PATH_PATTERN.is_match(text) {
@@ -169,6 +184,7 @@ pub fn has_file_references(text: &str) -> bool {
         inner.starts_with("http://")
             || inner.starts_with("https://")
             || inner.starts_with("clipboard:")
+            || parse_mcp_reference(inner).is_some()
             || has_supported_extension(inner)
     })
 }
@@ -259,4 +275,17 @@ mod tests {
         assert!(matches!(&segments[0], InputSegment::Text(t) if t == "[note] check"));
         assert!(matches!(&segments[1], InputSegment::ImagePath(p) if p.ends_with("photo.jpg")));
     }
+
+    #[test]
+    fn test_parse_mcp_resource() {
+        let text = "summarize [mcp:docs:file:///guide.md]";
+        let segments = parse_input(text, Path::new("/home"));
+        assert_eq!(segments.len(), 2);
+        assert!(matches!(
+            &segments[1],
+            InputSegment::McpResource { server, uri } if server == "docs" && uri == "file:///guide.md"
+        ));
+        assert!(has_file_references(text));
+        assert!(!has_file_references("[mcp:]"));
+    }
 }
@@ -3,7 +3,7 @@
 pub mod animation;
 pub mod app;
 mod app_builder;
-mod auth;
+pub(crate) mod auth;
 pub mod blocks;
 pub mod components;
 pub mod graphics;
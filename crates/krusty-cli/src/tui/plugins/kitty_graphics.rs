@@ -379,6 +379,7 @@ mod tests {
         let frame = PluginFrame::from_arc(pixels.clone(), 100, 100);
         assert_eq!(frame.width, 100);
         assert_eq!(Arc::strong_count(&frame.pixels), 2); // Original + frame
+        assert!(Arc::ptr_eq(&frame.pixels, &pixels));
     }
 
     #[test]
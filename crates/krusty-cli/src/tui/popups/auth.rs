@@ -11,7 +11,7 @@ use ratatui::{
 use super::common::{
     center_rect, popup_block, popup_title, render_popup_background, scroll_indicator, PopupSize,
 };
-use crate::ai::providers::{builtin_providers, ProviderId};
+use crate::ai::providers::{all_providers, get_provider, ProviderId};
 use crate::tui::themes::Theme;
 use krusty_core::auth::AuthMethod;
 
@@ -108,7 +108,7 @@ impl AuthPopup {
     /// Navigate down in provider list
     pub fn next_provider(&mut self) {
         if let AuthState::ProviderSelection { selected_index, .. } = &mut self.state {
-            let providers = builtin_providers();
+            let providers = all_providers();
             if *selected_index < providers.len() - 1 {
                 *selected_index += 1;
                 self.ensure_visible(10); // Use reasonable visible height
@@ -134,7 +134,7 @@ impl AuthPopup {
     /// Confirm provider selection - go to auth method selection or API key input
     pub fn confirm_provider(&mut self) {
         if let AuthState::ProviderSelection { selected_index, .. } = &self.state {
-            let providers = builtin_providers();
+            let providers = all_providers();
             if let Some(provider) = providers.get(*selected_index) {
                 // Check if provider supports OAuth
                 if provider.id.supports_oauth() {
@@ -327,19 +327,12 @@ impl AuthPopup {
                     scroll_offset: 0,
                 };
             }
-            AuthState::ApiKeyInput { provider, .. } => {
-                // If provider supports OAuth, go back to method selection
-                if provider.supports_oauth() {
-                    self.state = AuthState::AuthMethodSelection {
-                        provider: *provider,
-                        selected_index: 0,
-                    };
-                } else {
-                    self.state = AuthState::ProviderSelection {
-                        selected_index: 0,
-                        scroll_offset: 0,
-                    };
-                }
+            // If provider supports OAuth, go back to method selection
+            AuthState::ApiKeyInput { provider, .. } if provider.supports_oauth() => {
+                self.state = AuthState::AuthMethodSelection {
+                    provider: *provider,
+                    selected_index: 0,
+                };
             }
             AuthState::OAuthBrowserWaiting { provider, .. }
             | AuthState::OAuthDeviceCode { provider, .. }
@@ -452,7 +445,7 @@ impl AuthPopup {
         f.render_widget(title, chunks[0]);
 
         // Provider list - simplified to one line per provider
-        let providers = builtin_providers();
+        let providers = all_providers();
         let mut lines = Vec::new();
 
         // Calculate visible height (content area minus potential scroll indicators)
@@ -803,12 +796,25 @@ impl AuthPopup {
         f.render_widget(title, chunks[0]);
 
         // Instructions with provider-specific URL
-        let url = match provider {
-            ProviderId::OpenRouter => "https://openrouter.ai/keys",
-            ProviderId::ZAi => "https://z.ai/",
-            ProviderId::MiniMax => "https://platform.minimax.io/",
-            ProviderId::Anthropic => "https://console.anthropic.com/settings/keys",
-            ProviderId::OpenAI => "https://platform.openai.com/api-keys",
+        let (url_label, url) = match provider {
+            ProviderId::OpenRouter => ("Get your key from: ", "https://openrouter.ai/keys"),
+            ProviderId::ZAi => ("Get your key from: ", "https://z.ai/"),
+            ProviderId::MiniMax => ("Get your key from: ", "https://platform.minimax.io/"),
+            ProviderId::Anthropic => (
+                "Get your key from: ",
+                "https://console.anthropic.com/settings/keys",
+            ),
+            ProviderId::OpenAI => (
+                "Get your key from: ",
+                "https://platform.openai.com/api-keys",
+            ),
+            ProviderId::Gemini => ("Get your key from: ", "https://aistudio.google.com/apikey"),
+            ProviderId::Custom(_) => (
+                "Endpoint: ",
+                get_provider(provider)
+                    .map(|p| p.base_url.as_str())
+                    .unwrap_or_default(),
+            ),
         };
 
         let instructions = Paragraph::new(vec![
@@ -822,7 +828,7 @@ impl AuthPopup {
             ]),
             Line::from(""),
             Line::from(vec![
-                Span::raw("Get your key from: "),
+                Span::raw(url_label),
                 Span::styled(
                     url,
                     Style::default()
@@ -146,19 +146,27 @@ impl HelpPopup {
         let commands = [
             ("/home", "Return to start menu"),
             ("/load", "Load previous session"),
+            ("/search", "Search all sessions' messages"),
             ("/model", "Select AI model"),
             ("/auth", "Manage API providers"),
             ("/theme", "Change color theme"),
             ("/clear", "Clear chat messages"),
             ("/pinch", "Compress context to new session"),
+            ("/compact", "Summarize old turns in this session"),
             ("/plan", "View/manage active plan"),
             ("/mcp", "Browse and manage MCP servers"),
+            ("/mcp__<server>__<prompt>", "Run an MCP server prompt"),
             ("/skills", "Browse skills"),
             ("/plugins", "Browse and manage installable plugins"),
             ("/ps", "View background processes"),
             ("/terminal", "Open interactive terminal"),
             ("/init", "Generate KRAB.md"),
-            ("/permissions", "Toggle supervised/autonomous mode"),
+            ("/permissions", "Permission mode and allow/deny rules"),
+            ("/memory", "View and edit project memories"),
+            ("/budget", "Show or set session spend limits"),
+            ("/undo", "Revert file changes from the last turn"),
+            ("/rewind", "List, diff or restore checkpoints"),
+            ("/fork", "Branch the session, optionally before a turn"),
             ("/cmd", "Show this help"),
         ];
 
@@ -199,7 +207,7 @@ impl HelpPopup {
                     ("Ctrl+V", "Paste text or image"),
                     ("Ctrl+C", "Clear input"),
                     ("Ctrl+W", "Delete word"),
-                    ("@", "Search files to attach"),
+                    ("@", "Search files and MCP resources to attach"),
                 ],
             ),
             (
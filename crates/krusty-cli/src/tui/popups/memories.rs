@@ -0,0 +1,374 @@
+//! Memories popup
+//!
+//! Lists the project memories the agent keeps across sessions and lets the
+//! user add, edit or delete them.
+//! Stages: List → Edit
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
+    Frame,
+};
+
+use super::common::{
+    center_rect, popup_block, popup_title, render_popup_background, scroll_indicator, PopupSize,
+};
+use crate::storage::Memory;
+use crate::tui::themes::Theme;
+use crate::tui::utils::truncate_ellipsis;
+
+/// Stages of the memories popup
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum MemoriesStage {
+    /// Saved memories
+    #[default]
+    List,
+    /// Write a new memory (`id` None) or revise an existing one
+    Edit { id: Option<i64>, input: String },
+}
+
+/// Memories popup
+pub struct MemoriesPopup {
+    pub stage: MemoriesStage,
+    /// Memories of the current project
+    pub memories: Vec<Memory>,
+    /// Project directory the memories belong to
+    pub working_dir: String,
+    /// Selected index in list view (memories, then "Add memory")
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+    pub error: Option<String>,
+}
+
+impl Default for MemoriesPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoriesPopup {
+    pub fn new() -> Self {
+        Self {
+            stage: MemoriesStage::List,
+            memories: Vec::new(),
+            working_dir: String::new(),
+            selected_index: 0,
+            scroll_offset: 0,
+            error: None,
+        }
+    }
+
+    /// Reset to the list view
+    pub fn reset(&mut self) {
+        self.stage = MemoriesStage::List;
+        self.error = None;
+    }
+
+    /// Set the memories to display, keeping the selection in range
+    pub fn set_memories(&mut self, memories: Vec<Memory>) {
+        self.memories = memories;
+        self.selected_index = self.selected_index.min(self.memories.len());
+        self.scroll_offset = self.scroll_offset.min(self.selected_index);
+    }
+
+    // =========================================================================
+    // Navigation
+    // =========================================================================
+
+    pub fn next(&mut self) {
+        if self.stage == MemoriesStage::List && self.selected_index < self.memories.len() {
+            self.selected_index += 1;
+            self.ensure_visible();
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if self.stage == MemoriesStage::List && self.selected_index > 0 {
+            self.selected_index -= 1;
+            self.ensure_visible();
+        }
+    }
+
+    fn ensure_visible(&mut self) {
+        let visible_height = 12;
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + visible_height {
+            self.scroll_offset = self.selected_index - visible_height + 1;
+        }
+    }
+
+    // =========================================================================
+    // Stage transitions
+    // =========================================================================
+
+    /// Edit the selected memory, or start a new one when "Add memory" is selected
+    pub fn start_edit(&mut self) {
+        if self.stage != MemoriesStage::List {
+            return;
+        }
+        self.stage = match self.memories.get(self.selected_index) {
+            Some(memory) => MemoriesStage::Edit {
+                id: Some(memory.id),
+                input: memory.content.clone(),
+            },
+            None => MemoriesStage::Edit {
+                id: None,
+                input: String::new(),
+            },
+        };
+        self.error = None;
+    }
+
+    /// The memory being edited: its ID (None for a new one) and text
+    pub fn pending_edit(&self) -> Option<(Option<i64>, &str)> {
+        match &self.stage {
+            MemoriesStage::Edit { id, input } => Some((*id, input.as_str())),
+            MemoriesStage::List => None,
+        }
+    }
+
+    // =========================================================================
+    // Text input
+    // =========================================================================
+
+    pub fn add_char(&mut self, c: char) {
+        if let MemoriesStage::Edit { input, .. } = &mut self.stage {
+            input.push(c);
+            self.error = None;
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let MemoriesStage::Edit { input, .. } = &mut self.stage {
+            input.pop();
+        }
+    }
+
+    // =========================================================================
+    // List operations
+    // =========================================================================
+
+    /// Get selected memory ID (for delete)
+    pub fn get_selected_id(&self) -> Option<i64> {
+        if self.stage == MemoriesStage::List {
+            self.memories.get(self.selected_index).map(|m| m.id)
+        } else {
+            None
+        }
+    }
+
+    // =========================================================================
+    // Rendering
+    // =========================================================================
+
+    pub fn render(&self, f: &mut Frame, theme: &Theme) {
+        match &self.stage {
+            MemoriesStage::List => self.render_list(f, theme),
+            MemoriesStage::Edit { id, input } => self.render_edit(f, theme, *id, input),
+        }
+    }
+
+    fn render_list(&self, f: &mut Frame, theme: &Theme) {
+        let (w, h) = PopupSize::Large.dimensions();
+        let area = center_rect(w, h, f.area());
+        render_popup_background(f, area, theme);
+
+        let block = popup_block(theme);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Length(2), // Project
+                Constraint::Min(5),    // Memories
+                Constraint::Length(2), // Footer
+            ])
+            .split(inner);
+
+        let title_lines = popup_title("Project Memory", theme);
+        let title = Paragraph::new(title_lines).alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let project_line = Paragraph::new(Line::from(vec![
+            Span::styled("  Project: ", Style::default().fg(theme.dim_color)),
+            Span::styled(
+                truncate_ellipsis(&self.working_dir, 60).into_owned(),
+                Style::default().fg(theme.accent_color),
+            ),
+        ]));
+        f.render_widget(project_line, chunks[1]);
+
+        let mut lines = Vec::new();
+        let visible_height = (chunks[2].height as usize).saturating_sub(4).max(1);
+        let content_width = (chunks[2].width as usize).saturating_sub(18).max(10);
+
+        if self.scroll_offset > 0 {
+            lines.push(scroll_indicator("up", self.scroll_offset, theme));
+        } else {
+            lines.push(Line::from(""));
+        }
+
+        if self.memories.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  Nothing remembered yet. The agent saves memories with the remember tool.",
+                Style::default().fg(theme.dim_color),
+            )));
+        } else {
+            for (i, memory) in self
+                .memories
+                .iter()
+                .enumerate()
+                .skip(self.scroll_offset)
+                .take(visible_height)
+            {
+                let is_selected = i == self.selected_index;
+                let prefix = if is_selected { "› " } else { "  " };
+                let style = if is_selected {
+                    Style::default()
+                        .fg(theme.accent_color)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text_color)
+                };
+                let content = memory.content.split_whitespace().collect::<Vec<_>>();
+                let date = memory.updated_at.get(..10).unwrap_or(&memory.updated_at);
+
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(
+                        truncate_ellipsis(&content.join(" "), content_width).into_owned(),
+                        style,
+                    ),
+                    Span::styled(format!("  {}", date), Style::default().fg(theme.dim_color)),
+                ]));
+            }
+
+            let remaining = self
+                .memories
+                .len()
+                .saturating_sub(self.scroll_offset + visible_height);
+            if remaining > 0 {
+                lines.push(scroll_indicator("down", remaining, theme));
+            }
+        }
+
+        lines.push(Line::from(""));
+        let is_add_selected = self.selected_index == self.memories.len();
+        let add_style = if is_add_selected {
+            Style::default()
+                .fg(theme.accent_color)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_color)
+        };
+        let add_prefix = if is_add_selected { "› " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(add_prefix, add_style),
+            Span::styled("+ Add memory", add_style),
+        ]));
+
+        if let Some(err) = &self.error {
+            lines.push(Line::from(Span::styled(
+                format!("  {}", err),
+                Style::default().fg(theme.error_color),
+            )));
+        }
+
+        f.render_widget(Paragraph::new(lines), chunks[2]);
+
+        let key = Style::default()
+            .fg(theme.accent_color)
+            .add_modifier(Modifier::BOLD);
+        let text = Style::default().fg(theme.text_color);
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled("↑↓", key),
+            Span::styled(": navigate  ", text),
+            Span::styled("Enter", key),
+            Span::styled(": edit  ", text),
+            Span::styled("d", key),
+            Span::styled(": delete  ", text),
+            Span::styled("Esc", key),
+            Span::styled(": close", text),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(footer, chunks[3]);
+    }
+
+    fn render_edit(&self, f: &mut Frame, theme: &Theme, id: Option<i64>, input: &str) {
+        let (w, h) = PopupSize::Large.dimensions();
+        let area = center_rect(w, h, f.area());
+        render_popup_background(f, area, theme);
+
+        let block = popup_block(theme);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Min(5),    // Input
+                Constraint::Length(2), // Error
+                Constraint::Length(2), // Footer
+            ])
+            .split(inner);
+
+        let title_text = if id.is_some() {
+            "Edit Memory"
+        } else {
+            "Add Memory"
+        };
+        let title = Paragraph::new(popup_title(title_text, theme)).alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let input_block = Block::default()
+            .title("One self-contained fact about this project")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border_color));
+        let input_widget = if input.is_empty() {
+            Paragraph::new("Integration tests need `docker compose up db` first")
+                .style(Style::default().fg(theme.dim_color))
+        } else {
+            Paragraph::new(Line::from(vec![
+                Span::styled(input, Style::default().fg(theme.text_color)),
+                Span::styled(
+                    "_",
+                    Style::default()
+                        .fg(theme.accent_color)
+                        .add_modifier(Modifier::SLOW_BLINK),
+                ),
+            ]))
+        };
+        f.render_widget(
+            input_widget.wrap(Wrap { trim: false }).block(input_block),
+            chunks[1],
+        );
+
+        if let Some(err) = &self.error {
+            let error_widget = Paragraph::new(err.as_str())
+                .style(Style::default().fg(theme.error_color))
+                .alignment(Alignment::Center);
+            f.render_widget(error_widget, chunks[2]);
+        }
+
+        let key = Style::default()
+            .fg(theme.accent_color)
+            .add_modifier(Modifier::BOLD);
+        let text = Style::default().fg(theme.text_color);
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled("Enter", key),
+            Span::styled(": save  ", text),
+            Span::styled("Esc", key),
+            Span::styled(": back", text),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(footer, chunks[3]);
+    }
+}
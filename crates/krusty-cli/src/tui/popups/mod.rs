@@ -13,11 +13,14 @@ pub mod file_preview;
 pub mod help;
 pub mod hooks;
 pub mod mcp_browser;
+pub mod memories;
 pub mod model_select;
+pub mod permissions;
 pub mod pinch;
 pub mod plugins;
 pub mod process_list;
 pub mod scroll;
 pub mod session_list;
+pub mod session_search;
 pub mod skills_browser;
 pub mod theme_select;
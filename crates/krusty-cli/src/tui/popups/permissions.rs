@@ -0,0 +1,441 @@
+//! Permissions popup
+//!
+//! Shows the permission mode and the allow/deny/ask rules that apply to the
+//! current project, and lets the user add or delete rules.
+//! Stages: List → AddRule
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, BorderType, Borders, Paragraph},
+    Frame,
+};
+
+use super::common::{
+    center_rect, popup_block, popup_title, render_popup_background, scroll_indicator, PopupSize,
+};
+use crate::agent::{PermissionAction, PermissionRule};
+use crate::tui::themes::Theme;
+use crate::tui::utils::truncate_ellipsis;
+use krusty_core::tools::registry::PermissionMode;
+
+/// Stages of the permissions popup
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum PermissionsStage {
+    /// Mode and existing rules
+    #[default]
+    List,
+    /// Enter a rule as `<action> <tool> [pattern]`
+    AddRule {
+        input: String,
+        /// Apply to every project instead of only the current one
+        global: bool,
+    },
+}
+
+/// Permissions popup
+pub struct PermissionsPopup {
+    pub stage: PermissionsStage,
+    /// Current permission mode (shown in the header)
+    pub mode: PermissionMode,
+    /// Rules that apply to the current project
+    pub rules: Vec<PermissionRule>,
+    /// Project directory new project-scoped rules are tied to
+    pub working_dir: String,
+    /// Selected index in list view (rules, then "Add rule")
+    pub selected_index: usize,
+    pub scroll_offset: usize,
+    pub error: Option<String>,
+}
+
+impl Default for PermissionsPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PermissionsPopup {
+    pub fn new() -> Self {
+        Self {
+            stage: PermissionsStage::List,
+            mode: PermissionMode::Supervised,
+            rules: Vec::new(),
+            working_dir: String::new(),
+            selected_index: 0,
+            scroll_offset: 0,
+            error: None,
+        }
+    }
+
+    /// Reset to the list view
+    pub fn reset(&mut self) {
+        self.stage = PermissionsStage::List;
+        self.error = None;
+    }
+
+    /// Set the rules to display, keeping the selection in range
+    pub fn set_rules(&mut self, rules: Vec<PermissionRule>) {
+        self.rules = rules;
+        self.selected_index = self.selected_index.min(self.rules.len());
+        self.scroll_offset = self.scroll_offset.min(self.selected_index);
+    }
+
+    // =========================================================================
+    // Navigation
+    // =========================================================================
+
+    pub fn next(&mut self) {
+        if self.stage == PermissionsStage::List && self.selected_index < self.rules.len() {
+            self.selected_index += 1;
+            self.ensure_visible();
+        }
+    }
+
+    pub fn prev(&mut self) {
+        if self.stage == PermissionsStage::List && self.selected_index > 0 {
+            self.selected_index -= 1;
+            self.ensure_visible();
+        }
+    }
+
+    fn ensure_visible(&mut self) {
+        let visible_height = 12;
+        if self.selected_index < self.scroll_offset {
+            self.scroll_offset = self.selected_index;
+        } else if self.selected_index >= self.scroll_offset + visible_height {
+            self.scroll_offset = self.selected_index - visible_height + 1;
+        }
+    }
+
+    // =========================================================================
+    // Stage transitions
+    // =========================================================================
+
+    pub fn start_add(&mut self) {
+        self.stage = PermissionsStage::AddRule {
+            input: String::new(),
+            global: false,
+        };
+        self.error = None;
+    }
+
+    /// Toggle between project and global scope while adding
+    pub fn toggle_scope(&mut self) {
+        if let PermissionsStage::AddRule { global, .. } = &mut self.stage {
+            *global = !*global;
+        }
+    }
+
+    /// Parse the rule being added, recording an error on failure
+    pub fn take_pending_rule(&mut self) -> Option<PermissionRule> {
+        let PermissionsStage::AddRule { input, global } = &self.stage else {
+            return None;
+        };
+        let working_dir = (!*global).then(|| self.working_dir.clone());
+        match PermissionRule::parse_spec(input, working_dir) {
+            Ok(rule) => {
+                self.error = None;
+                Some(rule)
+            }
+            Err(e) => {
+                self.error = Some(e.to_string());
+                None
+            }
+        }
+    }
+
+    // =========================================================================
+    // Text input
+    // =========================================================================
+
+    pub fn add_char(&mut self, c: char) {
+        if let PermissionsStage::AddRule { input, .. } = &mut self.stage {
+            input.push(c);
+            self.error = None;
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if let PermissionsStage::AddRule { input, .. } = &mut self.stage {
+            input.pop();
+        }
+    }
+
+    // =========================================================================
+    // List operations
+    // =========================================================================
+
+    /// Get selected rule ID (for delete)
+    pub fn get_selected_rule_id(&self) -> Option<&str> {
+        if self.stage == PermissionsStage::List {
+            self.rules.get(self.selected_index).map(|r| r.id.as_str())
+        } else {
+            None
+        }
+    }
+
+    /// Check if "Add rule" is selected
+    pub fn is_add_new_selected(&self) -> bool {
+        self.stage == PermissionsStage::List && self.selected_index == self.rules.len()
+    }
+
+    // =========================================================================
+    // Rendering
+    // =========================================================================
+
+    pub fn render(&self, f: &mut Frame, theme: &Theme) {
+        match &self.stage {
+            PermissionsStage::List => self.render_list(f, theme),
+            PermissionsStage::AddRule { input, global } => {
+                self.render_add_rule(f, theme, input, *global)
+            }
+        }
+    }
+
+    fn render_list(&self, f: &mut Frame, theme: &Theme) {
+        let (w, h) = PopupSize::Large.dimensions();
+        let area = center_rect(w, h, f.area());
+        render_popup_background(f, area, theme);
+
+        let block = popup_block(theme);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Length(2), // Mode
+                Constraint::Min(5),    // Rules
+                Constraint::Length(2), // Footer
+            ])
+            .split(inner);
+
+        let title_lines = popup_title("Permissions", theme);
+        let title = Paragraph::new(title_lines).alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let (mode_label, mode_desc) = match self.mode {
+            PermissionMode::Supervised => ("Supervised", "ask before write tools"),
+            PermissionMode::Autonomous => ("Autonomous", "run tools unless a rule says otherwise"),
+        };
+        let mode_line = Paragraph::new(Line::from(vec![
+            Span::styled("  Mode: ", Style::default().fg(theme.dim_color)),
+            Span::styled(
+                mode_label,
+                Style::default()
+                    .fg(theme.accent_color)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            Span::styled(
+                format!(" - {}", mode_desc),
+                Style::default().fg(theme.dim_color),
+            ),
+        ]));
+        f.render_widget(mode_line, chunks[1]);
+
+        let mut lines = Vec::new();
+        let visible_height = (chunks[2].height as usize).saturating_sub(4).max(1);
+
+        if self.scroll_offset > 0 {
+            lines.push(scroll_indicator("up", self.scroll_offset, theme));
+        } else {
+            lines.push(Line::from(""));
+        }
+
+        if self.rules.is_empty() {
+            lines.push(Line::from(Span::styled(
+                "  No rules configured",
+                Style::default().fg(theme.dim_color),
+            )));
+        } else {
+            for (i, rule) in self
+                .rules
+                .iter()
+                .enumerate()
+                .skip(self.scroll_offset)
+                .take(visible_height)
+            {
+                let is_selected = i == self.selected_index;
+                let prefix = if is_selected { "› " } else { "  " };
+                let action_color = match rule.action {
+                    PermissionAction::Allow => theme.success_color,
+                    PermissionAction::Deny => theme.error_color,
+                    PermissionAction::Ask => theme.warning_color,
+                };
+                let style = if is_selected {
+                    Style::default()
+                        .fg(theme.accent_color)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text_color)
+                };
+                let scope = if rule.working_dir.is_some() {
+                    "project"
+                } else {
+                    "global"
+                };
+                let target = match &rule.pattern {
+                    Some(pattern) => format!("{} {}", rule.tool, pattern),
+                    None => rule.tool.clone(),
+                };
+
+                lines.push(Line::from(vec![
+                    Span::styled(prefix, style),
+                    Span::styled(
+                        format!("{:<6}", rule.action),
+                        Style::default().fg(action_color),
+                    ),
+                    Span::styled(truncate_ellipsis(&target, 44).into_owned(), style),
+                    Span::styled(
+                        format!("  [{}]", scope),
+                        Style::default().fg(theme.dim_color),
+                    ),
+                ]));
+            }
+
+            let remaining = self
+                .rules
+                .len()
+                .saturating_sub(self.scroll_offset + visible_height);
+            if remaining > 0 {
+                lines.push(scroll_indicator("down", remaining, theme));
+            }
+        }
+
+        lines.push(Line::from(""));
+        let is_add_selected = self.is_add_new_selected();
+        let add_style = if is_add_selected {
+            Style::default()
+                .fg(theme.accent_color)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.text_color)
+        };
+        let add_prefix = if is_add_selected { "› " } else { "  " };
+        lines.push(Line::from(vec![
+            Span::styled(add_prefix, add_style),
+            Span::styled("+ Add rule", add_style),
+        ]));
+
+        f.render_widget(Paragraph::new(lines), chunks[2]);
+
+        let key = Style::default()
+            .fg(theme.accent_color)
+            .add_modifier(Modifier::BOLD);
+        let text = Style::default().fg(theme.text_color);
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled("↑↓", key),
+            Span::styled(": navigate  ", text),
+            Span::styled("Enter", key),
+            Span::styled(": select  ", text),
+            Span::styled("m", key),
+            Span::styled(": mode  ", text),
+            Span::styled("d", key),
+            Span::styled(": delete  ", text),
+            Span::styled("Esc", key),
+            Span::styled(": close", text),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(footer, chunks[3]);
+    }
+
+    fn render_add_rule(&self, f: &mut Frame, theme: &Theme, input: &str, global: bool) {
+        let (w, h) = PopupSize::Large.dimensions();
+        let area = center_rect(w, h, f.area());
+        render_popup_background(f, area, theme);
+
+        let block = popup_block(theme);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(1)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Length(2), // Scope
+                Constraint::Length(3), // Input
+                Constraint::Length(2), // Error
+                Constraint::Min(3),    // Hints
+                Constraint::Length(2), // Footer
+            ])
+            .split(inner);
+
+        let title_lines = popup_title("Add Permission Rule", theme);
+        let title = Paragraph::new(title_lines).alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let scope = if global {
+            "all projects".to_string()
+        } else {
+            truncate_ellipsis(&self.working_dir, 50).into_owned()
+        };
+        let scope_line = Paragraph::new(Line::from(vec![
+            Span::styled("Applies to: ", Style::default().fg(theme.dim_color)),
+            Span::styled(scope, Style::default().fg(theme.accent_color)),
+        ]));
+        f.render_widget(scope_line, chunks[1]);
+
+        let input_block = Block::default()
+            .title("<allow|deny|ask> <tool> [pattern]")
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.border_color));
+        let display_text = if input.is_empty() {
+            "allow bash cargo test*"
+        } else {
+            input
+        };
+        let text_style = if input.is_empty() {
+            Style::default().fg(theme.dim_color)
+        } else {
+            Style::default().fg(theme.text_color)
+        };
+        let input_widget = Paragraph::new(display_text)
+            .style(text_style)
+            .block(input_block);
+        f.render_widget(input_widget, chunks[2]);
+
+        if let Some(err) = &self.error {
+            let error_widget = Paragraph::new(err.as_str())
+                .style(Style::default().fg(theme.error_color))
+                .alignment(Alignment::Center);
+            f.render_widget(error_widget, chunks[3]);
+        }
+
+        let example = |spec: &'static str, desc: &'static str| {
+            Line::from(vec![
+                Span::styled(format!("  {}", spec), Style::default().fg(theme.text_color)),
+                Span::styled(format!(" ({})", desc), Style::default().fg(theme.dim_color)),
+            ])
+        };
+        let hint_lines = vec![
+            Line::from(Span::styled(
+                "Examples (* and ? wildcards, ! negates):",
+                Style::default().fg(theme.dim_color),
+            )),
+            example("allow bash cargo test*", "run tests without asking"),
+            example("deny write !src/*", "no writes outside src/"),
+            example("ask bash git push*", "always confirm pushes"),
+            example("deny * .env", "never touch .env"),
+        ];
+        f.render_widget(Paragraph::new(hint_lines), chunks[4]);
+
+        let key = Style::default()
+            .fg(theme.accent_color)
+            .add_modifier(Modifier::BOLD);
+        let text = Style::default().fg(theme.text_color);
+        let footer = Paragraph::new(Line::from(vec![
+            Span::styled("Enter", key),
+            Span::styled(": save  ", text),
+            Span::styled("Tab", key),
+            Span::styled(": project/global  ", text),
+            Span::styled("Esc", key),
+            Span::styled(": back", text),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(footer, chunks[5]);
+    }
+}
@@ -2,6 +2,7 @@
 //!
 //! TUI shows sessions for the current working directory only.
 //! User already knows where they are (they launched from there).
+//! Forked and pinched sessions are listed under their parent.
 
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout},
@@ -10,6 +11,7 @@ use ratatui::{
     widgets::Paragraph,
     Frame,
 };
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 use super::common::{
@@ -25,6 +27,49 @@ pub struct SessionInfo {
     pub id: String,
     pub title: String,
     pub updated_at: String,
+    /// Nesting level under the parent session (0 for roots)
+    pub depth: usize,
+    /// Parent messages a fork started with (None for roots and pinches)
+    pub fork_point: Option<usize>,
+}
+
+/// Order sessions as a tree: each root (most recent first) followed by its
+/// forks and pinches, depth-first
+///
+/// A session whose parent is not in the list is treated as a root.
+pub fn session_tree(sessions: Vec<crate::storage::SessionInfo>) -> Vec<SessionInfo> {
+    let ids: Vec<String> = sessions.iter().map(|s| s.id.clone()).collect();
+    let mut children: HashMap<Option<String>, Vec<crate::storage::SessionInfo>> = HashMap::new();
+    for session in sessions {
+        let parent = session
+            .parent_session_id
+            .clone()
+            .filter(|parent| ids.contains(parent));
+        children.entry(parent).or_default().push(session);
+    }
+
+    fn visit(
+        parent: Option<String>,
+        depth: usize,
+        children: &mut HashMap<Option<String>, Vec<crate::storage::SessionInfo>>,
+        out: &mut Vec<SessionInfo>,
+    ) {
+        for session in children.remove(&parent).unwrap_or_default() {
+            let id = session.id.clone();
+            out.push(SessionInfo {
+                id: session.id,
+                title: session.title,
+                updated_at: session.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+                depth,
+                fork_point: session.fork_point,
+            });
+            visit(Some(id), depth + 1, children, out);
+        }
+    }
+
+    let mut out = Vec::with_capacity(ids.len());
+    visit(None, 0, &mut children, &mut out);
+    out
 }
 
 /// Session list popup state
@@ -157,9 +202,19 @@ impl SessionListPopup {
                 };
 
                 let prefix = if is_selected { "▶ " } else { "  " };
+                let branch = match session.depth {
+                    0 => String::new(),
+                    depth => format!("{}↳ ", "  ".repeat(depth - 1)),
+                };
+                let fork_point = session
+                    .fork_point
+                    .map(|n| format!("  @{}", n))
+                    .unwrap_or_default();
                 lines.push(Line::from(vec![
                     Span::styled(prefix.to_string(), style),
+                    Span::styled(branch, Style::default().fg(theme.dim_color)),
                     Span::styled(session.title.clone(), style),
+                    Span::styled(fork_point, Style::default().fg(theme.dim_color)),
                     Span::styled(
                         format!("  {}", session.updated_at),
                         Style::default().fg(theme.dim_color),
@@ -228,3 +283,48 @@ fn shorten_path(path: &str) -> String {
     let last_three: PathBuf = components[components.len() - 3..].iter().collect();
     format!(".../{}", last_three.display())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::{SessionInfo as StoredSession, WorkMode};
+
+    fn stored(id: &str, parent: Option<&str>, fork_point: Option<usize>) -> StoredSession {
+        StoredSession {
+            id: id.to_string(),
+            title: id.to_string(),
+            updated_at: chrono::Utc::now(),
+            token_count: None,
+            parent_session_id: parent.map(str::to_string),
+            fork_point,
+            working_dir: None,
+            user_id: None,
+            work_mode: WorkMode::default(),
+            model: None,
+            target_branch: None,
+        }
+    }
+
+    #[test]
+    fn test_session_tree_nests_children_under_parents() {
+        let tree = session_tree(vec![
+            stored("fork", Some("root"), Some(2)),
+            stored("other", None, None),
+            stored("root", None, None),
+            stored("nested", Some("fork"), Some(1)),
+            stored("orphan", Some("deleted"), Some(4)),
+        ]);
+        let order: Vec<(&str, usize)> = tree.iter().map(|s| (s.id.as_str(), s.depth)).collect();
+        assert_eq!(
+            order,
+            vec![
+                ("other", 0),
+                ("root", 0),
+                ("fork", 1),
+                ("nested", 2),
+                ("orphan", 0)
+            ]
+        );
+        assert_eq!(tree[2].fork_point, Some(2));
+    }
+}
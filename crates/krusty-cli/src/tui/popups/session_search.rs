@@ -0,0 +1,220 @@
+//! Session search popup - full-text search across past sessions
+//!
+//! Searches as you type. Results come from the message search index and
+//! Enter opens the session scrolled to the matching message.
+
+use ratatui::{
+    layout::{Alignment, Constraint, Direction, Layout},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::Paragraph,
+    Frame,
+};
+
+use super::common::{
+    center_content, center_rect, popup_block, popup_title, render_popup_background,
+    scroll_indicator, PopupSize,
+};
+use super::scroll::ScrollState;
+use crate::storage::SearchHit;
+use crate::tui::themes::Theme;
+
+/// Session search popup state
+pub struct SessionSearchPopup {
+    /// Scroll state for navigating results
+    scroll: ScrollState,
+    pub query: String,
+    pub results: Vec<SearchHit>,
+    /// Search every directory instead of the current one
+    pub all_directories: bool,
+    /// Current working directory (searched unless `all_directories`)
+    pub current_dir: String,
+}
+
+impl Default for SessionSearchPopup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionSearchPopup {
+    pub fn new() -> Self {
+        Self {
+            scroll: ScrollState::new(0),
+            query: String::new(),
+            results: Vec::new(),
+            all_directories: false,
+            current_dir: String::new(),
+        }
+    }
+
+    /// Reset for a new search in `dir`
+    pub fn open(&mut self, dir: &str, query: &str) {
+        self.current_dir = dir.to_string();
+        self.query = query.to_string();
+        self.all_directories = false;
+        self.set_results(Vec::new());
+    }
+
+    /// Directory filter for the current scope
+    pub fn working_dir(&self) -> Option<&str> {
+        (!self.all_directories).then_some(self.current_dir.as_str())
+    }
+
+    pub fn set_results(&mut self, results: Vec<SearchHit>) {
+        self.scroll = ScrollState::new(results.len());
+        self.results = results;
+    }
+
+    pub fn toggle_scope(&mut self) {
+        self.all_directories = !self.all_directories;
+    }
+
+    pub fn next(&mut self) {
+        self.scroll.next();
+    }
+
+    pub fn prev(&mut self) {
+        self.scroll.prev();
+    }
+
+    /// Get selected result
+    pub fn get_selected(&self) -> Option<&SearchHit> {
+        self.results.get(self.scroll.selected)
+    }
+
+    pub fn render(&mut self, f: &mut Frame, theme: &Theme) {
+        let (w, h) = PopupSize::Large.dimensions();
+        let area = center_rect(w, h, f.area());
+        render_popup_background(f, area, theme);
+
+        let block = popup_block(theme);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Title
+                Constraint::Length(2), // Search bar
+                Constraint::Min(5),    // Results
+                Constraint::Length(2), // Footer
+            ])
+            .split(inner);
+
+        // Each result takes two lines (title, then snippet)
+        let visible_height = (chunks[2].height as usize).saturating_sub(2) / 2;
+        self.scroll.set_visible_height(visible_height);
+
+        let title_text = if self.all_directories {
+            "Search all sessions".to_string()
+        } else {
+            "Search sessions in this directory".to_string()
+        };
+        let title = Paragraph::new(popup_title(&title_text, theme)).alignment(Alignment::Center);
+        f.render_widget(title, chunks[0]);
+
+        let search = Paragraph::new(Line::from(vec![
+            Span::styled("  Search: ", Style::default().fg(theme.accent_color)),
+            Span::styled(&self.query, Style::default().fg(theme.text_color)),
+            Span::styled(
+                "_",
+                Style::default()
+                    .fg(theme.accent_color)
+                    .add_modifier(Modifier::SLOW_BLINK),
+            ),
+        ]));
+        f.render_widget(search, chunks[1]);
+
+        let mut lines: Vec<Line> = Vec::new();
+        if self.results.is_empty() {
+            let hint = if self.query.trim().is_empty() {
+                "  Type to search message text, tools and file paths"
+            } else {
+                "  No matches"
+            };
+            lines.push(Line::from(Span::styled(
+                hint.to_string(),
+                Style::default()
+                    .fg(theme.dim_color)
+                    .add_modifier(Modifier::ITALIC),
+            )));
+        } else {
+            let items_above = self.scroll.items_above();
+            if items_above > 0 {
+                lines.push(scroll_indicator("up", items_above, theme));
+            }
+
+            let snippet_width = (chunks[2].width as usize).saturating_sub(10);
+            for idx in self.scroll.visible_range() {
+                let hit = &self.results[idx];
+                let is_selected = self.scroll.is_selected(idx);
+                let style = if is_selected {
+                    Style::default()
+                        .fg(theme.accent_color)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text_color)
+                };
+
+                let prefix = if is_selected { "▶ " } else { "  " };
+                let date = hit.created_at.get(..10).unwrap_or(&hit.created_at);
+                lines.push(Line::from(vec![
+                    Span::styled(prefix.to_string(), style),
+                    Span::styled(hit.session_title.clone(), style),
+                    Span::styled(
+                        format!("  {} · {}", date, hit.role),
+                        Style::default().fg(theme.dim_color),
+                    ),
+                ]));
+
+                let snippet: String = hit
+                    .snippet
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .chars()
+                    .take(snippet_width)
+                    .collect();
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", snippet),
+                    Style::default().fg(theme.dim_color),
+                )));
+            }
+
+            let items_below = self.scroll.items_below();
+            if items_below > 0 {
+                lines.push(scroll_indicator("down", items_below, theme));
+            }
+        }
+
+        let content = Paragraph::new(lines).style(Style::default().bg(theme.bg_color));
+        f.render_widget(content, center_content(chunks[2], 2));
+
+        let key = |k: &'static str| {
+            Span::styled(
+                k,
+                Style::default()
+                    .fg(theme.accent_color)
+                    .add_modifier(Modifier::BOLD),
+            )
+        };
+        let label = |l: &'static str| Span::styled(l, Style::default().fg(theme.text_color));
+        let footer = Paragraph::new(Line::from(vec![
+            key("↑↓"),
+            label(": navigate  "),
+            key("Enter"),
+            label(": open  "),
+            key("Tab"),
+            label(if self.all_directories {
+                ": this directory  "
+            } else {
+                ": all directories  "
+            }),
+            key("Esc"),
+            label(": cancel"),
+        ]))
+        .alignment(Alignment::Center);
+        f.render_widget(footer, chunks[3]);
+    }
+}
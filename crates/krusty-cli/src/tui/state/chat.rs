@@ -13,6 +13,9 @@ pub struct ChatState {
     pub messages: Vec<(String, String)>,
     /// Full conversation history for API
     pub conversation: Vec<ModelMessage>,
+    /// Index in `messages` where each conversation message starts, as of
+    /// the last session load (used to jump to search results)
+    pub loaded_message_starts: Vec<usize>,
     /// True while streaming response from AI API
     pub is_streaming: bool,
     /// True while tools are executing
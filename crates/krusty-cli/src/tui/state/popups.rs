@@ -4,9 +4,11 @@
 
 use crate::tui::popups::{
     auth::AuthPopup, file_preview::FilePreviewPopup, help::HelpPopup, hooks::HooksPopup,
-    mcp_browser::McpBrowserPopup, model_select::ModelSelectPopup, pinch::PinchPopup,
-    plugins::PluginsBrowserPopup, process_list::ProcessListPopup, session_list::SessionListPopup,
-    skills_browser::SkillsBrowserPopup, theme_select::ThemeSelectPopup,
+    mcp_browser::McpBrowserPopup, memories::MemoriesPopup, model_select::ModelSelectPopup,
+    permissions::PermissionsPopup, pinch::PinchPopup, plugins::PluginsBrowserPopup,
+    process_list::ProcessListPopup, session_list::SessionListPopup,
+    session_search::SessionSearchPopup, skills_browser::SkillsBrowserPopup,
+    theme_select::ThemeSelectPopup,
 };
 
 /// All popup controller states grouped together
@@ -15,6 +17,7 @@ pub struct PopupState {
     pub theme: ThemeSelectPopup,
     pub model: ModelSelectPopup,
     pub session: SessionListPopup,
+    pub session_search: SessionSearchPopup,
     pub auth: AuthPopup,
     pub mcp: McpBrowserPopup,
     pub process: ProcessListPopup,
@@ -23,6 +26,8 @@ pub struct PopupState {
     pub file_preview: FilePreviewPopup,
     pub skills: SkillsBrowserPopup,
     pub hooks: HooksPopup,
+    pub permissions: PermissionsPopup,
+    pub memories: MemoriesPopup,
 }
 
 impl PopupState {
@@ -35,6 +40,7 @@ impl PopupState {
             theme: ThemeSelectPopup::new(),
             model: ModelSelectPopup::new(),
             session: SessionListPopup::new(),
+            session_search: SessionSearchPopup::new(),
             auth: AuthPopup::new(),
             mcp: McpBrowserPopup::new(),
             process: ProcessListPopup::new(),
@@ -43,6 +49,8 @@ impl PopupState {
             file_preview,
             skills: SkillsBrowserPopup::new(),
             hooks: HooksPopup::new(),
+            permissions: PermissionsPopup::new(),
+            memories: MemoriesPopup::new(),
         }
     }
 }
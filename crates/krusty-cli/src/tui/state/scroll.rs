@@ -29,6 +29,10 @@ pub struct ScrollState {
     pub locked_to_messages: bool,
     /// Lock scroll during text selection (prevents block scroll capture)
     pub locked_for_selection: bool,
+    /// Display message to bring to the top on next render
+    pub jump_to_message: Option<usize>,
+    /// First line of `jump_to_message`, found while counting message lines
+    pub jump_line: Option<usize>,
 }
 
 impl ScrollState {
@@ -41,6 +45,8 @@ impl ScrollState {
             scroll_to_bottom: false,
             locked_to_messages: false,
             locked_for_selection: false,
+            jump_to_message: None,
+            jump_line: None,
         }
     }
 
@@ -91,6 +97,21 @@ impl ScrollState {
         }
     }
 
+    /// Request a jump to a display message on next render
+    pub fn request_jump_to_message(&mut self, index: usize) {
+        self.jump_to_message = Some(index);
+        self.jump_line = None;
+        self.scroll_to_bottom = false;
+    }
+
+    /// Apply a pending jump once its line is known
+    pub fn apply_jump_to_message(&mut self) {
+        if let Some(line) = self.jump_line.take() {
+            self.jump_to_message = None;
+            self.scroll_to_line(line);
+        }
+    }
+
     // =========================================================================
     // Max Scroll Updates
     // =========================================================================
@@ -4,9 +4,12 @@
 
 use tokio::sync::{mpsc, oneshot};
 
+use crate::agent::compaction::CompactionOutcome;
 use crate::agent::subagent::AgentProgress;
 use crate::agent::{LoopEvent, LoopInput, SummarizationResult};
 use crate::ai::models::ModelMetadata;
+use crate::ai::providers::ProviderId;
+use crate::extensions::wasm_host::SlashCommandArgumentCompletion;
 use crate::tools::ToolOutputChunk;
 
 /// AI-generated title update
@@ -36,6 +39,25 @@ pub struct SummarizationUpdate {
     pub result: Result<SummarizationResult, String>,
 }
 
+/// Result of /compact
+pub struct CompactionUpdate {
+    pub session_id: String,
+    pub result: Result<CompactionOutcome, String>,
+}
+
+/// Extension slash command argument completions for `/name arguments`
+pub struct ArgumentCompletionUpdate {
+    pub name: String,
+    pub arguments: Vec<String>,
+    pub result: Result<Vec<SlashCommandArgumentCompletion>, String>,
+}
+
+/// Result of running an extension slash command
+pub struct ExtensionCommandUpdate {
+    pub command: String,
+    pub result: Result<String, String>,
+}
+
 /// MCP server status update from background tasks
 pub struct McpStatusUpdate {
     pub success: bool,
@@ -73,12 +95,20 @@ pub struct AsyncChannels {
     pub title_update: Option<oneshot::Receiver<TitleUpdate>>,
     /// AI-generated summarization result for pinch
     pub summarization: Option<oneshot::Receiver<SummarizationUpdate>>,
+    /// In-place compaction result for /compact
+    pub compaction: Option<oneshot::Receiver<CompactionUpdate>>,
     /// Explore tool sub-agent progress updates (bounded for backpressure)
     pub explore_progress: Option<mpsc::Receiver<AgentProgress>>,
     /// Build tool builder agent progress updates (bounded for backpressure)
     pub build_progress: Option<mpsc::Receiver<AgentProgress>>,
     /// OpenRouter model fetch result receiver
     pub openrouter_models: Option<oneshot::Receiver<Result<Vec<ModelMetadata>, String>>>,
+    /// Extension slash command argument completions (debounced)
+    pub argument_completion: Option<oneshot::Receiver<ArgumentCompletionUpdate>>,
+    /// Extension slash command output, sent to the AI once it arrives
+    pub extension_command: Option<oneshot::Receiver<ExtensionCommandUpdate>>,
+    /// User-defined provider model discovery completions
+    pub custom_models: Option<mpsc::UnboundedReceiver<ProviderId>>,
     /// /init codebase exploration result receiver
     pub init_exploration: Option<oneshot::Receiver<InitExplorationResult>>,
     /// /init exploration progress updates
@@ -8,7 +8,8 @@ mod text;
 mod title;
 
 pub use channels::{
-    AsyncChannels, DeviceCodeInfo, InitExplorationResult, McpStatusUpdate, OAuthStatusUpdate,
+    ArgumentCompletionUpdate, AsyncChannels, CompactionUpdate, DeviceCodeInfo,
+    ExtensionCommandUpdate, InitExplorationResult, McpStatusUpdate, OAuthStatusUpdate,
     SummarizationUpdate, TitleUpdate,
 };
 pub use syntax::highlight_code;
@@ -2,31 +2,39 @@
 //!
 //! This is the core ACP agent that handles all protocol methods.
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use agent_client_protocol::{
     Agent, AgentCapabilities, AuthenticateRequest, AuthenticateResponse, AvailableCommand,
-    AvailableCommandsUpdate, CancelNotification, ClientCapabilities, ContentBlock,
-    Error as AcpSchemaError, ExtNotification, ExtRequest, ExtResponse, Implementation,
-    InitializeRequest, InitializeResponse, LoadSessionRequest, LoadSessionResponse,
-    McpCapabilities, ModelId, ModelInfo as AcpModelInfo, NewSessionRequest, NewSessionResponse,
-    PromptCapabilities, PromptRequest, PromptResponse, Result as AcpResult, SessionCapabilities,
-    SessionId, SessionMode, SessionModeState, SessionModelState, SessionNotification,
-    SessionUpdate, SetSessionModeRequest, SetSessionModeResponse, SetSessionModelRequest,
-    SetSessionModelResponse,
+    AvailableCommandInput, AvailableCommandsUpdate, CancelNotification, Client as _,
+    ClientCapabilities, ContentBlock, ContentChunk, Error as AcpSchemaError, ExtNotification,
+    ExtRequest, ExtResponse, Implementation, InitializeRequest, InitializeResponse,
+    LoadSessionRequest, LoadSessionResponse, McpCapabilities, ModelId, ModelInfo as AcpModelInfo,
+    NewSessionRequest, NewSessionResponse, PromptCapabilities, PromptRequest, PromptResponse,
+    Result as AcpResult, SessionCapabilities, SessionId, SessionMode, SessionModeState,
+    SessionModelState, SessionNotification, SessionUpdate, SetSessionModeRequest,
+    SetSessionModeResponse, SetSessionModelRequest, SetSessionModelResponse, StopReason,
+    TextContent, UnstructuredCommandInput,
 };
-use tokio::sync::{mpsc, RwLock};
+use tokio::sync::{mpsc, Mutex, RwLock};
 use tracing::{debug, error, info, warn};
 
-use super::bridge::NotificationBridge;
+use super::bridge::{ClientChannel, NotificationBridge};
 use super::error::AcpError;
 use super::processor::PromptProcessor;
 use super::session::{SessionManager, SessionState};
-use crate::ai::openrouter;
+use super::tools::ClientEditor;
+use crate::agent::{
+    LoggingHook, PlanModeHook, SafetyHook, UserHookManager, UserPostToolHook, UserPreToolHook,
+};
 use crate::ai::providers::{get_provider, ProviderId};
+use crate::ai::{custom_providers, openrouter};
+use crate::extensions::ExtensionSlashCommands;
+use crate::paths;
 use crate::storage::credentials::CredentialStore;
-use crate::tools::ToolRegistry;
+use crate::storage::{Database, SessionManager as StorageSessionManager};
+use crate::tools::{EditorClient, ToolRegistry};
 
 /// ACP protocol version supported by this agent (10 is current)
 #[allow(dead_code)]
@@ -56,44 +64,48 @@ pub struct KrustyAgent {
     processor: RwLock<PromptProcessor>,
     /// Channel for sending notifications to the connection
     notification_tx: RwLock<Option<mpsc::Sender<SessionNotification>>>,
+    /// Channel for sending client requests (permissions, file access, terminals)
+    client_channel: RwLock<Option<ClientChannel>>,
     /// Current model configuration (provider + model)
     current_model: RwLock<Option<ModelConfig>>,
     /// Available model configurations from all providers
     available_models: RwLock<Vec<AvailableModelRecord>>,
+    /// Slash commands contributed by installed extensions
+    slash_commands: Arc<ExtensionSlashCommands>,
 }
 
 impl KrustyAgent {
     /// Create a new Krusty ACP agent
+    ///
+    /// Tools run behind the same hook chain as the TUI and server.
     pub fn new() -> Self {
-        let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
-        let tools = Arc::new(ToolRegistry::new());
-        Self {
-            sessions: Arc::new(SessionManager::new()),
-            tools: tools.clone(),
-            client_capabilities: RwLock::new(None),
-            api_key: RwLock::new(None),
-            processor: RwLock::new(PromptProcessor::new(tools, cwd)),
-            notification_tx: RwLock::new(None),
-            current_model: RwLock::new(None),
-            available_models: RwLock::new(Vec::new()),
-        }
+        let db_path = paths::config_dir().join("krusty.db");
+        Self::with_tools(Arc::new(create_tool_registry(&db_path)))
     }
 
     /// Create with custom tool registry
     pub fn with_tools(tools: Arc<ToolRegistry>) -> Self {
         let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
         Self {
-            sessions: Arc::new(SessionManager::new()),
+            sessions: Arc::new(create_session_manager()),
             tools: tools.clone(),
             client_capabilities: RwLock::new(None),
             api_key: RwLock::new(None),
             processor: RwLock::new(PromptProcessor::new(tools, cwd)),
             notification_tx: RwLock::new(None),
+            client_channel: RwLock::new(None),
             current_model: RwLock::new(None),
             available_models: RwLock::new(Vec::new()),
+            slash_commands: Arc::new(ExtensionSlashCommands::new()),
         }
     }
 
+    /// Register the agent's tools (everything but the sub-agent tools, which
+    /// are registered when an AI client is initialized)
+    pub async fn register_tools(&self) {
+        self.processor.read().await.register_tools().await;
+    }
+
     /// Detect all available models from configured providers
     /// Returns a list of `(model_id, provider, actual_model_id, api_key, display_name)`.
     pub async fn detect_available_models(&self) -> Vec<AvailableModelRecord> {
@@ -108,77 +120,62 @@ impl KrustyAgent {
             }
         };
 
-        // Get configured providers as a set for quick lookup
-        let configured: std::collections::HashSet<_> =
-            store.configured_providers().into_iter().collect();
-        info!("Found {} configured providers", configured.len());
-
         // Iterate in the canonical order: MiniMax first, OpenRouter last
         // This matches the TUI model selection order
         for &provider in ProviderId::all() {
-            if !configured.contains(&provider) {
+            // Stored key, or an env/keyless credential for user-defined providers
+            let Some(api_key) = store.get_api_key(&provider) else {
                 continue;
-            }
+            };
+            let Some(provider_config) = get_provider(provider) else {
+                continue;
+            };
 
-            if let Some(api_key) = store.get(&provider) {
-                match provider {
-                    // Dynamic providers - fetch models from API
-                    ProviderId::OpenRouter => {
-                        info!("Fetching models from OpenRouter...");
-                        match openrouter::fetch_models(api_key).await {
-                            Ok(fetched) => {
-                                let fetched_count = fetched.len();
-                                for model in fetched {
-                                    let model_id =
-                                        format!("{}:{}", provider.storage_key(), model.id);
-                                    models.push((
-                                        model_id,
-                                        provider,
-                                        model.id.clone(),
-                                        api_key.clone(),
-                                        model.display_name.clone(),
-                                    ));
-                                }
-                                info!("Added {} models from OpenRouter", fetched_count);
-                            }
-                            Err(e) => {
-                                warn!("Failed to fetch OpenRouter models: {}", e);
-                                // Fallback to static models if available
-                                if let Some(provider_config) = get_provider(provider) {
-                                    for model_info in &provider_config.models {
-                                        let model_id =
-                                            format!("{}:{}", provider.storage_key(), model_info.id);
-                                        models.push((
-                                            model_id,
-                                            provider,
-                                            model_info.id.clone(),
-                                            api_key.clone(),
-                                            model_info.display_name.clone(),
-                                        ));
-                                    }
-                                }
-                            }
-                        }
+            // Dynamic providers - fetch models from API
+            let fetched = match provider {
+                ProviderId::OpenRouter => {
+                    info!("Fetching models from OpenRouter...");
+                    Some(openrouter::fetch_models(&api_key).await)
+                }
+                ProviderId::Custom(_) if provider_config.models_url.is_some() => {
+                    Some(custom_providers::fetch_models(provider, Some(&api_key)).await)
+                }
+                _ => None,
+            };
+
+            match fetched {
+                Some(Ok(fetched)) => {
+                    let fetched_count = fetched.len();
+                    for model in fetched {
+                        let model_id = format!("{}:{}", provider.storage_key(), model.id);
+                        models.push((
+                            model_id,
+                            provider,
+                            model.id.clone(),
+                            api_key.clone(),
+                            model.display_name.clone(),
+                        ));
+                    }
+                    info!("Added {} models from {}", fetched_count, provider);
+                }
+                // Static providers (or failed fetch) - use configured models
+                fetched => {
+                    if let Some(Err(e)) = fetched {
+                        warn!("Failed to fetch {} models: {}", provider, e);
                     }
-                    // Static providers - use hardcoded models
-                    _ => {
-                        if let Some(provider_config) = get_provider(provider) {
-                            for model_info in &provider_config.models {
-                                let model_id =
-                                    format!("{}:{}", provider.storage_key(), model_info.id);
-                                models.push((
-                                    model_id,
-                                    provider,
-                                    model_info.id.clone(),
-                                    api_key.clone(),
-                                    model_info.display_name.clone(),
-                                ));
-                                debug!(
-                                    "Added model: {} from {:?}",
-                                    model_info.display_name, provider
-                                );
-                            }
-                        }
+                    for model_info in &provider_config.models {
+                        let model_id = format!("{}:{}", provider.storage_key(), model_info.id);
+                        models.push((
+                            model_id,
+                            provider,
+                            model_info.id.clone(),
+                            api_key.clone(),
+                            model_info.display_name.clone(),
+                        ));
+                        debug!(
+                            "Added model: {} from {:?}",
+                            model_info.display_name, provider
+                        );
                     }
                 }
             }
@@ -217,7 +214,8 @@ impl KrustyAgent {
         self.processor
             .write()
             .await
-            .init_ai_client(api_key, provider, Some(actual_model_id));
+            .init_ai_client(api_key, provider, Some(actual_model_id))
+            .await;
 
         Ok(())
     }
@@ -236,12 +234,18 @@ impl KrustyAgent {
         *self.notification_tx.write().await = Some(tx);
     }
 
+    /// Set the client request channel sender
+    pub async fn set_client_channel(&self, client: ClientChannel) {
+        *self.client_channel.write().await = Some(client);
+    }
+
     /// Initialize the AI client with an API key
     pub async fn init_ai_client(&self, api_key: String, provider: ProviderId) {
         self.processor
             .write()
             .await
-            .init_ai_client(api_key, provider, None);
+            .init_ai_client(api_key, provider, None)
+            .await;
     }
 
     /// Initialize the AI client with an API key and optional model override
@@ -254,7 +258,8 @@ impl KrustyAgent {
         self.processor
             .write()
             .await
-            .init_ai_client(api_key, provider, model);
+            .init_ai_client(api_key, provider, model)
+            .await;
     }
 
     /// Get agent capabilities to advertise
@@ -274,8 +279,8 @@ impl KrustyAgent {
 
         // MCP capabilities
         let mut mcp_caps = McpCapabilities::new();
-        mcp_caps.http = false;
-        mcp_caps.sse = false;
+        mcp_caps.http = true;
+        mcp_caps.sse = true;
         caps.mcp_capabilities = mcp_caps;
 
         caps
@@ -311,15 +316,35 @@ impl KrustyAgent {
         self.api_key.read().await.clone()
     }
 
-    /// Get available slash commands
+    /// Get available slash commands, including those of installed extensions
     pub fn get_available_commands(&self) -> Vec<AvailableCommand> {
-        vec![
+        let mut commands = vec![
             AvailableCommand::new("compact", "Summarize the conversation to reduce context"),
             AvailableCommand::new("clear", "Clear the conversation history"),
             AvailableCommand::new("help", "Show available commands and usage"),
             AvailableCommand::new("model", "Show or change the current AI model"),
-            AvailableCommand::new("mode", "Switch between code and plan modes"),
-        ]
+            AvailableCommand::new("mode", "Switch between code, auto and plan modes"),
+            AvailableCommand::new("undo", "Revert file changes from the last turn"),
+            AvailableCommand::new("rewind", "List, diff or restore file checkpoints"),
+        ];
+        commands.extend(
+            self.slash_commands
+                .commands()
+                .iter()
+                .filter(|c| commands.iter().all(|b| b.name != c.name))
+                .map(|c| {
+                    let command = AvailableCommand::new(&c.name, &c.description);
+                    if c.requires_argument {
+                        command.input(AvailableCommandInput::Unstructured(
+                            UnstructuredCommandInput::new("argument"),
+                        ))
+                    } else {
+                        command
+                    }
+                })
+                .collect::<Vec<_>>(),
+        );
+        commands
     }
 
     /// Send available commands notification to the client
@@ -413,6 +438,9 @@ impl Agent for KrustyAgent {
             },
         );
 
+        // Start the editor's MCP servers; their tools join this session's tool set
+        session.connect_mcp_servers().await;
+
         // Build and inject workspace context so the AI understands the codebase
         session.add_system_context(workspace_context).await;
         info!("Injected workspace context for session cwd");
@@ -423,9 +451,12 @@ impl Agent for KrustyAgent {
         // Build the response with model and mode state
         let mut response = NewSessionResponse::new(session.id.clone());
 
-        // Set up available modes (plan and code)
+        // Set up available modes (code, auto and plan)
         let available_modes = vec![
-            SessionMode::new("code", "Code").description("Write and edit code directly"),
+            SessionMode::new("code", "Code")
+                .description("Write and edit code, asking before edits and commands"),
+            SessionMode::new("auto", "Auto")
+                .description("Write and edit code without asking, unless a rule says otherwise"),
             SessionMode::new("plan", "Plan").description("Plan changes before implementing"),
         ];
         let mode_state = SessionModeState::new("code", available_modes);
@@ -457,11 +488,11 @@ impl Agent for KrustyAgent {
 
             // Initialize the processor with the first model
             let (_, provider, actual_model, api_key, _) = &detected_models[0];
-            self.processor.write().await.init_ai_client(
-                api_key.clone(),
-                *provider,
-                Some(actual_model.clone()),
-            );
+            self.processor
+                .write()
+                .await
+                .init_ai_client(api_key.clone(), *provider, Some(actual_model.clone()))
+                .await;
 
             // Store current model config
             *self.current_model.write().await = Some(ModelConfig {
@@ -499,6 +530,12 @@ impl Agent for KrustyAgent {
             return Ok(LoadSessionResponse::new());
         }
 
+        let mcp_servers = if request.mcp_servers.is_empty() {
+            None
+        } else {
+            Some(request.mcp_servers)
+        };
+
         // Try to load from persistent storage if available
         let session_id_str = request.session_id.to_string();
 
@@ -516,10 +553,11 @@ impl Agent for KrustyAgent {
             // Create session and restore from storage
             match self
                 .sessions
-                .create_session_from_storage(&session_id_str, None, None)
+                .create_session_from_storage(&session_id_str, None, mcp_servers.clone())
                 .await
             {
                 Ok(session) => {
+                    session.connect_mcp_servers().await;
                     info!(
                         "Session {} restored from storage with {} messages",
                         session.id,
@@ -538,7 +576,8 @@ impl Agent for KrustyAgent {
             "Session {} not found in memory or storage, creating new session",
             request.session_id
         );
-        let _session = self.sessions.create_session(None, None);
+        let session = self.sessions.create_session(None, mcp_servers);
+        session.connect_mcp_servers().await;
 
         Ok(LoadSessionResponse::new())
     }
@@ -574,12 +613,52 @@ impl Agent for KrustyAgent {
         };
 
         // Create a bridge for this request
-        let bridge = NotificationBridge::new(tx.clone());
+        let mut bridge = NotificationBridge::new(tx.clone());
+        let mut editor: Option<Arc<dyn EditorClient>> = None;
+        if let Some(client) = self.client_channel.read().await.as_ref() {
+            bridge = bridge.with_client_channel(client.clone());
+
+            // Delegate file access and terminals the client advertised
+            if let Some(capabilities) = self.client_capabilities.read().await.clone() {
+                editor = ClientEditor::new(client.clone(), session.id.clone(), capabilities)
+                    .map(|e| Arc::new(e) as Arc<dyn EditorClient>);
+            }
+        }
 
-        // Process the prompt with the PromptProcessor
+        // Checkpoint commands are handled locally, without calling the AI
         let processor = self.processor.read().await;
+        if let Some(stop_reason) = processor
+            .handle_checkpoint_command(&session, &prompt_text, &bridge)
+            .await
+        {
+            return Ok(PromptResponse::new(stop_reason));
+        }
+
+        // Extension commands are replaced by their output before the AI sees them
+        let mut prompt = request.prompt;
+        if let Some(result) = self.slash_commands.expand(&prompt_text, &session.cwd).await {
+            match result {
+                Ok(expanded) => prompt = replace_prompt_text(prompt, expanded),
+                Err(e) => {
+                    let chunk = ContentChunk::new(ContentBlock::Text(TextContent::new(format!(
+                        "Slash command failed: {}",
+                        e
+                    ))));
+                    let notification = SessionNotification::new(
+                        session.id.clone(),
+                        SessionUpdate::AgentMessageChunk(chunk),
+                    );
+                    if let Err(e) = bridge.session_notification(notification).await {
+                        warn!("Failed to send slash command error: {}", e);
+                    }
+                    return Ok(PromptResponse::new(StopReason::EndTurn));
+                }
+            }
+        }
+
+        // Process the prompt with the PromptProcessor
         let stop_reason = processor
-            .process_prompt(&session, request.prompt, &bridge)
+            .process_prompt(&session, prompt, &bridge, editor)
             .await
             .map_err(|e| {
                 error!("Prompt processing error: {}", e);
@@ -663,6 +742,41 @@ impl Agent for KrustyAgent {
     }
 }
 
+/// Build the tool registry with the hook chain the TUI and server use
+fn create_tool_registry(db_path: &Path) -> ToolRegistry {
+    let mut hook_manager = UserHookManager::new();
+    if let Ok(db) = Database::new(db_path) {
+        if let Err(e) = hook_manager.load(&db) {
+            warn!("Failed to load hooks: {}", e);
+        }
+    }
+    let hook_manager = Arc::new(RwLock::new(hook_manager));
+
+    let mut registry = ToolRegistry::new();
+    registry.add_pre_hook(Arc::new(SafetyHook::new()));
+    registry.add_pre_hook(Arc::new(PlanModeHook::new()));
+    registry.add_post_hook(Arc::new(LoggingHook::new()));
+    registry.add_pre_hook(Arc::new(UserPreToolHook::new(hook_manager.clone())));
+    registry.add_post_hook(Arc::new(UserPostToolHook::new(hook_manager)));
+    registry
+}
+
+/// Open the session manager, persisting to Krusty's database
+///
+/// Prompts run through the orchestrator, which stores the conversation, so
+/// sessions without storage can't be prompted.
+fn create_session_manager() -> SessionManager {
+    match Database::new(&paths::config_dir().join("krusty.db")) {
+        Ok(db) => {
+            SessionManager::with_storage(Arc::new(Mutex::new(StorageSessionManager::new(db))))
+        }
+        Err(e) => {
+            warn!("Failed to open session storage: {}", e);
+            SessionManager::new()
+        }
+    }
+}
+
 /// Extract text content from ACP content blocks
 fn extract_prompt_text(content: &[ContentBlock]) -> String {
     let mut prompt_text = String::new();
@@ -677,6 +791,17 @@ fn extract_prompt_text(content: &[ContentBlock]) -> String {
     prompt_text
 }
 
+/// Swap a prompt's text blocks for `text`, keeping images and resources
+fn replace_prompt_text(prompt: Vec<ContentBlock>, text: String) -> Vec<ContentBlock> {
+    std::iter::once(ContentBlock::Text(TextContent::new(text)))
+        .chain(
+            prompt
+                .into_iter()
+                .filter(|block| !matches!(block, ContentBlock::Text(_))),
+        )
+        .collect()
+}
+
 /// Build workspace context for the AI
 ///
 /// Scans the workspace directory to provide the AI with understanding of:
@@ -1,32 +1,88 @@
 //! Notification Bridge for ACP
 //!
 //! Provides a channel-based bridge between the Agent and the Connection,
-//! allowing the Agent to send session notifications without direct access
-//! to the connection.
+//! allowing the Agent to send session notifications and make client requests
+//! without direct access to the connection.
 
+use std::future::Future;
+use std::rc::Rc;
 use std::time::Duration;
 
 use agent_client_protocol::{
-    Client, Error as AcpError, PermissionOptionId, RequestPermissionOutcome,
+    AgentSideConnection, Client, Error as AcpError, PermissionOptionId, RequestPermissionOutcome,
     RequestPermissionRequest, RequestPermissionResponse, Result as AcpResult,
     SelectedPermissionOutcome, SessionNotification,
 };
-use tokio::sync::mpsc;
+use futures::future::LocalBoxFuture;
+use futures::FutureExt;
+use tokio::sync::{mpsc, oneshot};
 use tracing::{info, warn};
 
+/// A client request, run by the server against the real connection
+pub type ClientTask =
+    Box<dyn FnOnce(Rc<AgentSideConnection>) -> LocalBoxFuture<'static, ()> + Send>;
+
+/// Handle for making requests to the client from any thread
+///
+/// The connection lives on the server's `LocalSet`, so requests are sent
+/// there as `ClientTask`s and their results come back over a oneshot.
+#[derive(Clone)]
+pub struct ClientChannel {
+    tx: mpsc::UnboundedSender<ClientTask>,
+}
+
+impl ClientChannel {
+    /// Create a handle that sends tasks to the server through `tx`
+    pub fn new(tx: mpsc::UnboundedSender<ClientTask>) -> Self {
+        Self { tx }
+    }
+
+    /// Run `call` against the real connection and wait for its output
+    ///
+    /// Fails only if the server is no longer running client tasks.
+    pub async fn call<T, F, Fut>(&self, call: F) -> AcpResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Rc<AgentSideConnection>) -> Fut + Send + 'static,
+        Fut: Future<Output = T> + 'static,
+    {
+        let (reply_tx, reply_rx) = oneshot::channel();
+        let task: ClientTask = Box::new(move |connection| {
+            async move {
+                let _ = reply_tx.send(call(connection).await);
+            }
+            .boxed_local()
+        });
+        self.tx
+            .send(task)
+            .map_err(|_| AcpError::new(-32603, "Client channel closed"))?;
+        reply_rx
+            .await
+            .map_err(|_| AcpError::new(-32603, "Client request dropped"))
+    }
+}
+
 /// Bridge that implements Client trait using channels
 ///
 /// This allows the PromptProcessor to send session notifications
 /// through a channel, which are then forwarded to the real connection
-/// by the server.
+/// by the server. Client requests (permissions) go through a
+/// `ClientChannel` when one is attached.
 pub struct NotificationBridge {
     tx: mpsc::Sender<SessionNotification>,
+    client: Option<ClientChannel>,
 }
 
 impl NotificationBridge {
     /// Create a new notification bridge
     pub fn new(tx: mpsc::Sender<SessionNotification>) -> Self {
-        Self { tx }
+        Self { tx, client: None }
+    }
+
+    /// Send client requests to the connection through `client`
+    pub fn with_client_channel(mut self, client: ClientChannel) -> Self {
+        self.client = Some(client);
+        self
     }
 }
 
@@ -39,17 +95,25 @@ impl NotificationBridge {
 ///
 /// # Security Note
 ///
-/// In headless/ACP mode, permissions are auto-approved because there's no UI
-/// to prompt the user. This is expected behavior for background agent execution.
-/// The editor (Zed, etc.) is responsible for user consent before spawning the agent.
+/// Permission requests are forwarded to the editor when a client channel is
+/// attached. Without one (headless use) they are auto-approved, since there's
+/// no UI to prompt the user.
 #[async_trait::async_trait(?Send)]
 impl Client for NotificationBridge {
     async fn request_permission(
         &self,
         request: RequestPermissionRequest,
     ) -> AcpResult<RequestPermissionResponse> {
+        if let Some(client) = &self.client {
+            let forwarded = request.clone();
+            return client
+                .call(
+                    move |connection| async move { connection.request_permission(forwarded).await },
+                )
+                .await?;
+        }
+
         // In headless mode, auto-approve permissions since there's no UI to prompt.
-        // The editor is responsible for user consent before spawning the agent.
         let option_id = request
             .options
             .first()
@@ -41,7 +41,7 @@ mod updates;
 mod workspace_context;
 
 pub use agent::KrustyAgent;
-pub use bridge::{create_notification_channel, NotificationBridge};
+pub use bridge::{create_notification_channel, ClientChannel, ClientTask, NotificationBridge};
 pub use error::AcpError;
 pub use model_manager::{CachedProviderInfo, ModelManager};
 pub use processor::PromptProcessor;
@@ -1,39 +1,61 @@
 //! ACP Prompt Processor
 //!
-//! Connects the ACP agent to Krusty's AI client and tool system.
-//! Handles the core prompt processing loop:
-//! 1. Convert ACP content blocks to Krusty's AI format
-//! 2. Call AI provider with streaming
-//! 3. Stream responses back via ACP session/update notifications
-//! 4. Execute tool calls and stream their results
-
+//! Connects the ACP agent to Krusty's agentic orchestrator.
+//! Each prompt is handled by:
+//! 1. Converting ACP content blocks to Krusty's AI format
+//! 2. Running the conversation through `AgenticOrchestrator`, the same loop
+//!    the TUI and server use (plan mode, sub-agents, hooks, failure detection)
+//! 3. Translating its `LoopEvent`s into ACP session/update notifications
+
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
 
 use agent_client_protocol::{
-    Client as AcpClient, ContentBlock as AcpContent, ContentChunk, EmbeddedResourceResource,
-    SessionNotification, SessionUpdate, StopReason, TextContent, ToolCall, ToolCallId,
+    Client as AcpClient, ContentBlock as AcpContent, ContentChunk, CurrentModeUpdate,
+    EmbeddedResourceResource, PermissionOption, PermissionOptionKind, Plan,
+    RequestPermissionOutcome, RequestPermissionRequest, SessionNotification, SessionUpdate,
+    StopReason, TextContent, ToolCall, ToolCallId, ToolCallStatus,
 };
-use anyhow::Result;
-use tracing::{debug, error, info, warn};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
 
 const ACP_DEFAULT_MAX_TOKENS: usize = 8192;
 
+use crate::agent::plan_handler::parse_plan_confirm_choice;
+use crate::agent::{
+    AgentCancellation, AgenticOrchestrator, LoopEvent, LoopInput, OrchestratorConfig,
+    OrchestratorServices,
+};
 use crate::ai::client::{AiClient, AiClientConfig, CallOptions};
 use crate::ai::format_detection::detect_api_format;
 use crate::ai::providers::{get_provider, AuthHeader, ProviderId};
-use crate::ai::streaming::StreamPart;
-use crate::ai::types::{AiToolCall, Content, FinishReason};
-use crate::tools::git_identity::{GitIdentity, GitIdentityMode};
-use crate::tools::{ToolContext, ToolRegistry, ToolResult};
+use crate::ai::types::Content;
+use crate::lsp::LspManager;
+use crate::mcp::McpManager;
+use crate::paths;
+use crate::plan::PlanManager;
+use crate::process::ProcessRegistry;
+use crate::skills::SkillsManager;
+use crate::storage::{
+    CheckpointStore, Database, Preferences, SessionManager as StorageSessionManager, WorkMode,
+};
+use crate::tools::checkpoint::{self, RestoreSummary};
+use crate::tools::registry::PermissionMode;
+use crate::tools::{
+    register_all_tools, register_build_tool, register_explore_tool, register_lsp_tools,
+    register_memory_tools, register_session_search_tool, register_task_tool, EditorClient,
+    ToolRegistry,
+};
 
 use super::error::AcpError;
 use super::session::SessionState;
 use super::tools::{
-    create_tool_call_complete, create_tool_call_failed, create_tool_call_start,
-    text_to_tool_content, tool_name_to_kind,
+    create_tool_call_complete, create_tool_call_details, create_tool_call_failed,
+    create_tool_call_status, text_to_tool_content, tool_call_diffs, tool_name_to_kind,
 };
+use super::updates::plan_items_to_entries;
 
 /// Prompt processor that connects ACP to Krusty's AI and tools
 pub struct PromptProcessor {
@@ -41,34 +63,58 @@ pub struct PromptProcessor {
     ai_client: Option<Arc<AiClient>>,
     /// Tool registry for executing tools
     tools: Arc<ToolRegistry>,
-    /// Git identity for commit attribution
-    git_identity: Option<GitIdentity>,
+    /// Background processes started by tools
+    process_registry: Arc<ProcessRegistry>,
+    /// Language servers backing the LSP tools
+    lsp_manager: Arc<LspManager>,
+    /// Directory the agent was started in (MCP config lookup)
+    working_dir: PathBuf,
+    /// Database holding sessions, checkpoints and the usage ledger
+    db_path: PathBuf,
 }
 
 impl PromptProcessor {
     /// Create a new prompt processor
-    pub fn new(tools: Arc<ToolRegistry>, _cwd: PathBuf) -> Self {
+    pub fn new(tools: Arc<ToolRegistry>, cwd: PathBuf) -> Self {
         Self {
             ai_client: None,
             tools,
-            git_identity: Some(GitIdentity::default()),
+            process_registry: Arc::new(ProcessRegistry::new()),
+            lsp_manager: Arc::new(LspManager::new()),
+            working_dir: cwd,
+            db_path: paths::config_dir().join("krusty.db"),
         }
     }
 
-    /// Set git identity for commit attribution
-    pub fn set_git_identity(&mut self, identity: GitIdentity) {
-        self.git_identity = Some(identity);
+    /// Register the tools that don't need an AI client
+    ///
+    /// Built-in, language server, session search, memory and configured MCP
+    /// tools, matching what the TUI and server offer.
+    pub async fn register_tools(&self) {
+        register_all_tools(&self.tools).await;
+        register_lsp_tools(&self.tools, self.lsp_manager.clone()).await;
+        register_session_search_tool(&self.tools, self.db_path.clone()).await;
+        register_memory_tools(&self.tools, self.db_path.clone()).await;
+
+        let mcp_manager = Arc::new(McpManager::new(self.working_dir.clone()));
+        if let Err(e) = mcp_manager.load_config().await {
+            warn!("Failed to load MCP config: {}", e);
+        } else if let Err(e) = mcp_manager.connect_all().await {
+            warn!("Failed to connect MCP servers: {}", e);
+        }
+        crate::mcp::tool::register_mcp_tools(mcp_manager.clone(), &self.tools).await;
+        crate::mcp::tool::spawn_tool_sync(mcp_manager, self.tools.clone());
     }
 
     /// Initialize the AI client with an API key and optional model override
-    pub fn init_ai_client(
+    ///
+    /// Also (re)registers the sub-agent tools against the new client.
+    pub async fn init_ai_client(
         &mut self,
         api_key: String,
         provider: ProviderId,
         model_override: Option<String>,
     ) {
-        use std::collections::HashMap;
-
         // Get provider configuration from the registry
         let provider_config = get_provider(provider);
 
@@ -100,6 +146,12 @@ impl PromptProcessor {
         };
 
         let client = Arc::new(AiClient::new(config, api_key));
+
+        let cancellation = AgentCancellation::new();
+        register_explore_tool(&self.tools, client.clone(), cancellation.clone()).await;
+        register_build_tool(&self.tools, client.clone(), cancellation.clone()).await;
+        register_task_tool(&self.tools, client.clone(), cancellation).await;
+
         self.ai_client = Some(client);
 
         info!(
@@ -108,10 +160,113 @@ impl PromptProcessor {
         );
     }
 
+    /// Handle `/undo` and `/rewind` prompts, replying with a message chunk
+    ///
+    /// Returns None when the prompt is not a checkpoint command. Supports
+    /// `/rewind [list]`, `/rewind diff <turn>` and `/rewind <turn> [chat]`.
+    pub async fn handle_checkpoint_command<C: AcpClient>(
+        &self,
+        session: &SessionState,
+        prompt_text: &str,
+        connection: &C,
+    ) -> Option<StopReason> {
+        let parts: Vec<&str> = prompt_text.split_whitespace().collect();
+        let args = match parts.as_slice() {
+            ["/undo"] => vec!["last"],
+            ["/rewind", args @ ..] => args.to_vec(),
+            _ => return None,
+        };
+
+        // Checkpoints belong to the storage session the orchestrator writes
+        let session_id = session.get_storage_session_id().await.unwrap_or_default();
+        let result = Database::new(&self.db_path).and_then(|db| {
+            let store = CheckpointStore::new(&db);
+            let parse_turn = |arg: &str| {
+                arg.parse::<usize>()
+                    .map_err(|_| anyhow::anyhow!("Invalid turn: {}", arg))
+            };
+            match args.as_slice() {
+                ["last"] => match store.latest_with_files(&session_id)? {
+                    Some(c) => checkpoint::restore_files(&db, &session_id, c.turn)
+                        .map(|summary| (describe_restore(&summary), None)),
+                    None => Ok(("No file changes to undo".to_string(), None)),
+                },
+                [] | ["list"] => {
+                    let checkpoints = store.list(&session_id)?;
+                    if checkpoints.is_empty() {
+                        return Ok(("No checkpoints in this session".to_string(), None));
+                    }
+                    let lines: Vec<String> = checkpoints
+                        .iter()
+                        .map(|c| {
+                            format!(
+                                "- turn {}: {} ({} files)",
+                                c.turn,
+                                c.prompt.as_deref().unwrap_or("-"),
+                                c.files.len()
+                            )
+                        })
+                        .collect();
+                    Ok((lines.join("\n"), None))
+                }
+                ["diff", turn] => {
+                    let diffs = checkpoint::diff_files(&db, &session_id, parse_turn(turn)?)?;
+                    if diffs.is_empty() {
+                        return Ok(("No changes since that checkpoint".to_string(), None));
+                    }
+                    let body: String = diffs.iter().map(|d| d.diff.as_str()).collect();
+                    Ok((format!("```diff\n{}```", body), None))
+                }
+                [turn] | [turn, "chat"] => {
+                    let summary = checkpoint::restore_files(&db, &session_id, parse_turn(turn)?)?;
+                    let keep = (args.len() == 2 && summary.failed.is_empty())
+                        .then_some(summary.message_count);
+                    Ok((describe_restore(&summary), keep))
+                }
+                _ => Err(anyhow::anyhow!(
+                    "Usage: /rewind [list | diff <turn> | <turn> [chat]]"
+                )),
+            }
+        });
+
+        let reply = match result {
+            Ok((reply, Some(keep))) => {
+                session.truncate_messages(keep).await;
+                reply
+            }
+            Ok((reply, None)) => reply,
+            Err(e) => e.to_string(),
+        };
+
+        let chunk = ContentChunk::new(AcpContent::Text(TextContent::new(&reply)));
+        let notification =
+            SessionNotification::new(session.id.clone(), SessionUpdate::AgentMessageChunk(chunk));
+        if let Err(e) = connection.session_notification(notification).await {
+            warn!("Failed to send checkpoint reply: {}", e);
+        }
+        Some(StopReason::EndTurn)
+    }
+
+    /// The tools available to a session
+    ///
+    /// Sessions with client-supplied MCP servers get a copy of the shared
+    /// registry with those servers' tools added.
+    async fn session_tools(&self, session: &SessionState) -> Arc<ToolRegistry> {
+        let Some(mcp_manager) = session.mcp_manager().await else {
+            return self.tools.clone();
+        };
+        let tools = self.tools.fork().await;
+        crate::mcp::tool::register_mcp_tools(mcp_manager, &tools).await;
+        Arc::new(tools)
+    }
+
     /// Process a prompt and stream results via the connection
     ///
-    /// Implements an agentic loop: after tool execution, continues calling the AI
-    /// with tool results until the AI responds without requesting more tools.
+    /// Runs the prompt through `AgenticOrchestrator` and relays its events
+    /// until the loop finishes, the session is cancelled, or the agent stops
+    /// to wait for the user. A prompt sent while the agent waits answers its
+    /// question (AskUserQuestion) or plan confirmation. File and terminal
+    /// tools go through `editor` when the client offers them.
     ///
     /// Returns the stop reason when processing completes
     pub async fn process_prompt<C: AcpClient>(
@@ -119,279 +274,488 @@ impl PromptProcessor {
         session: &SessionState,
         prompt: Vec<AcpContent>,
         connection: &C,
+        editor: Option<Arc<dyn EditorClient>>,
     ) -> Result<StopReason, AcpError> {
-        let ai_client = self.ai_client.as_ref().ok_or_else(|| {
+        let ai_client = self.ai_client.clone().ok_or_else(|| {
             AcpError::NotAuthenticated("AI client not initialized - authenticate first".into())
         })?;
 
-        // Convert initial ACP content to Krusty messages and add to history
+        // Convert ACP content to Krusty content
         // Handle Text, Resource (embedded files), and ResourceLink (file references)
-        let initial_content: Vec<Content> =
-            prompt.into_iter().filter_map(convert_acp_content).collect();
+        let content: Vec<Content> = prompt.into_iter().filter_map(convert_acp_content).collect();
+        let prompt_text = content
+            .iter()
+            .filter_map(|c| match c {
+                Content::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // The orchestrator persists the conversation, so the session needs a
+        // storage session before the first run
+        let (session_id, is_new_session) = match session.get_storage_session_id().await {
+            Some(id) => (id, false),
+            None => {
+                let title = StorageSessionManager::generate_title_from_content(&prompt_text);
+                let id = session.init_storage_session(&title).await.ok_or_else(|| {
+                    AcpError::InternalError("Failed to create storage session".into())
+                })?;
+                (id, true)
+            }
+        };
 
-        if !initial_content.is_empty() {
-            session
-                .add_user_message_content(initial_content.clone())
-                .await;
+        let mode = session.get_mode().await;
+        let mut work_mode = match mode.as_deref() {
+            Some("plan") => WorkMode::Plan,
+            _ => WorkMode::Build,
+        };
+        // Only the auto mode runs write and execute tools without asking
+        let permission_mode = match mode.as_deref() {
+            Some("auto") => PermissionMode::Autonomous,
+            _ => PermissionMode::Supervised,
+        };
+
+        match session.take_pending_question().await {
+            Some(id) if id.starts_with("plan-confirm-") => {
+                match parse_plan_confirm_choice(&prompt_text).as_deref() {
+                    Some("execute") => {
+                        work_mode = WorkMode::Build;
+                        self.set_work_mode(session, &session_id, work_mode, connection)
+                            .await;
+                        session
+                            .add_user_message(
+                                "The plan has been approved. Begin executing the plan, \
+                                 starting with Task 1.1."
+                                    .to_string(),
+                            )
+                            .await;
+                    }
+                    Some(_) => {
+                        if let Ok(plan_manager) = PlanManager::new(self.db_path.clone()) {
+                            let _ = plan_manager.abandon_plan(&session_id);
+                        }
+                        session
+                            .add_user_message(
+                                "The plan has been abandoned. What would you like to do instead?"
+                                    .to_string(),
+                            )
+                            .await;
+                    }
+                    // Feedback on the plan rather than a decision
+                    None => session.add_user_message_content(content).await,
+                }
+            }
+            Some(id) if session.answer_tool_call(&id, prompt_text.clone()).await => {}
+            _ => session.add_user_message_content(content).await,
         }
 
-        // Get tool definitions for the AI
-        let tool_defs = self.tools.get_ai_tools().await;
+        let config = ai_client.config();
+        let provider_model = get_provider(config.provider_id())
+            .and_then(|p| p.models.iter().find(|m| m.id == config.model));
+        let pricing = provider_model.and_then(|m| m.pricing());
+        let context_window = provider_model.map(|m| m.context_window).unwrap_or(0);
+        let auto_pinch = Database::new(&self.db_path)
+            .map(|db| Preferences::new(db).get_auto_pinch_policy())
+            .unwrap_or_default();
+
+        let tools = self.session_tools(session).await;
+        let options = CallOptions {
+            tools: Some(tools.get_ai_tools().await),
+            enable_caching: true,
+            session_id: Some(session_id.clone()),
+            codex_parallel_tool_calls: true,
+            ..Default::default()
+        };
 
-        // Agentic loop - continue until AI stops requesting tools
-        const MAX_ITERATIONS: usize = 50; // Safety limit
-        for iteration in 0..MAX_ITERATIONS {
-            if session.is_cancelled() {
-                info!("Session cancelled");
-                return Ok(StopReason::Cancelled);
-            }
+        let services = OrchestratorServices {
+            ai_client,
+            tool_registry: tools,
+            process_registry: self.process_registry.clone(),
+            db_path: self.db_path.clone(),
+            skills_manager: Arc::new(RwLock::new(SkillsManager::with_defaults(&session.cwd))),
+            lsp_manager: Some(self.lsp_manager.clone()),
+            editor,
+        };
 
-            info!("Agentic loop iteration {}", iteration + 1);
+        let config = OrchestratorConfig {
+            session_id: session_id.clone(),
+            working_dir: session.cwd.clone(),
+            permission_mode,
+            initial_work_mode: work_mode,
+            generate_title: is_new_session,
+            pricing,
+            context_window,
+            auto_pinch,
+            ..Default::default()
+        };
 
-            // Get current conversation history
-            let messages = session.history().await;
+        let orchestrator = AgenticOrchestrator::new(services, config);
+        let (mut event_rx, input_tx) = orchestrator.run(session.history().await, options);
 
-            // Set up call options
-            let options = CallOptions {
-                tools: if tool_defs.is_empty() {
-                    None
-                } else {
-                    Some(tool_defs.clone())
-                },
-                enable_caching: true,
-                session_id: Some(session.id.to_string()),
-                codex_parallel_tool_calls: true,
-                ..Default::default()
+        let mut turn = TurnState::new(session_id);
+        let cancelled = loop {
+            let event = tokio::select! {
+                event = event_rx.recv() => event,
+                _ = session.cancelled() => {
+                    let _ = input_tx.send(LoopInput::Cancel);
+                    break true;
+                }
             };
-
-            // Call AI with streaming
-            let mut rx = ai_client
-                .call_streaming(messages, &options)
-                .await
-                .map_err(|e| AcpError::AiClientError(e.to_string()))?;
-
-            // Process stream and send updates
-            let mut accumulated_text = String::new();
-            let mut pending_tool_calls: Vec<AiToolCall> = Vec::new();
-            let mut stop_reason = StopReason::EndTurn;
-
-            // Stream receive timeout: 3 minutes handles slow providers and large responses.
-            // Resets on every received event, so only triggers on true stalls.
-            const STREAM_TIMEOUT: Duration = Duration::from_secs(180);
-
-            loop {
-                let part = match tokio::time::timeout(STREAM_TIMEOUT, rx.recv()).await {
-                    Ok(Some(part)) => part,
-                    Ok(None) => break, // Channel closed, stream ended
-                    Err(_) => {
-                        warn!(
-                            "Stream receive timed out after {}s",
-                            STREAM_TIMEOUT.as_secs()
-                        );
-                        return Err(AcpError::AiClientError(
-                            "Stream timed out waiting for response".into(),
-                        ));
+            let Some(event) = event else {
+                break false;
+            };
+            if let LoopEvent::ToolApprovalRequired {
+                id,
+                name,
+                arguments,
+            } = &event
+            {
+                let approved = tokio::select! {
+                    approved = request_approval(session, id, name, arguments, connection) => approved,
+                    _ = session.cancelled() => {
+                        let _ = input_tx.send(LoopInput::Cancel);
+                        break true;
                     }
                 };
+                let _ = input_tx.send(LoopInput::ToolApproval {
+                    tool_call_id: id.clone(),
+                    approved,
+                    always_allow: false,
+                });
+            }
+            if matches!(event, LoopEvent::Finished { .. }) {
+                break false;
+            }
+            self.relay_event(session, event, &mut turn, connection)
+                .await;
+        };
 
-                if session.is_cancelled() {
-                    info!("Session cancelled, stopping stream processing");
-                    return Ok(StopReason::Cancelled);
-                }
+        // Pick up what the orchestrator stored, in the session it ended in
+        if let Err(e) = session.load_from_storage(&turn.session_id).await {
+            warn!("Failed to reload session messages: {}", e);
+        }
 
-                match part {
-                    StreamPart::Start { model, provider } => {
-                        debug!("Stream started: model={}, provider={}", model, provider);
-                    }
+        if cancelled {
+            info!("Session cancelled");
+            return Ok(StopReason::Cancelled);
+        }
+        if let Some(error) = turn.error {
+            send_update(
+                session,
+                SessionUpdate::AgentMessageChunk(text_chunk(&format!("\n\nError: {}", error))),
+                connection,
+            )
+            .await;
+        }
+        Ok(if turn.has_more {
+            StopReason::MaxTurnRequests
+        } else {
+            StopReason::EndTurn
+        })
+    }
 
-                    StreamPart::TextDelta { delta } => {
-                        accumulated_text.push_str(&delta);
-                        let chunk = ContentChunk::new(AcpContent::Text(TextContent::new(&delta)));
-                        let notification = SessionNotification::new(
-                            session.id.clone(),
-                            SessionUpdate::AgentMessageChunk(chunk),
-                        );
-                        if let Err(e) = connection.session_notification(notification).await {
-                            warn!("Failed to send text chunk: {}", e);
-                        }
-                    }
+    /// Translate one orchestrator event into ACP session updates
+    async fn relay_event<C: AcpClient>(
+        &self,
+        session: &SessionState,
+        event: LoopEvent,
+        turn: &mut TurnState,
+        connection: &C,
+    ) {
+        let update = match event {
+            LoopEvent::TextDelta { delta } | LoopEvent::TextDeltaWithCitations { delta, .. } => {
+                SessionUpdate::AgentMessageChunk(text_chunk(&delta))
+            }
 
-                    StreamPart::ThinkingDelta { thinking, .. } => {
-                        let chunk =
-                            ContentChunk::new(AcpContent::Text(TextContent::new(&thinking)));
-                        let notification = SessionNotification::new(
-                            session.id.clone(),
-                            SessionUpdate::AgentThoughtChunk(chunk),
-                        );
-                        if let Err(e) = connection.session_notification(notification).await {
-                            warn!("Failed to send thought chunk: {}", e);
-                        }
-                    }
+            LoopEvent::ThinkingDelta { thinking } => {
+                SessionUpdate::AgentThoughtChunk(text_chunk(&thinking))
+            }
 
-                    StreamPart::ToolCallStart { id, name } => {
-                        debug!("Tool call starting: {} ({})", name, id);
-                        let kind = tool_name_to_kind(&name);
-                        let title = format!("Running {}", name);
-                        let tool_call =
-                            ToolCall::new(ToolCallId::from(id.clone()), title).kind(kind);
-                        let notification = SessionNotification::new(
-                            session.id.clone(),
-                            SessionUpdate::ToolCall(tool_call),
-                        );
-                        if let Err(e) = connection.session_notification(notification).await {
-                            warn!("Failed to send tool call start: {}", e);
-                        }
-                    }
+            LoopEvent::ToolCallStart { id, name } => {
+                debug!("Tool call starting: {} ({})", name, id);
+                let kind = tool_name_to_kind(&name);
+                let tool_call =
+                    ToolCall::new(ToolCallId::from(id), format!("Running {}", name)).kind(kind);
+                SessionUpdate::ToolCall(tool_call)
+            }
 
-                    StreamPart::ToolCallComplete { tool_call } => {
-                        info!("Tool call complete: {} ({})", tool_call.name, tool_call.id);
-                        pending_tool_calls.push(tool_call);
-                    }
+            LoopEvent::ToolCallComplete {
+                id,
+                name,
+                arguments,
+            } => {
+                let update = create_tool_call_details(&id, &name, arguments.clone());
+                turn.tool_calls.insert(id, (name, arguments));
+                SessionUpdate::ToolCallUpdate(update)
+            }
 
-                    StreamPart::Finish { reason } => {
-                        debug!("Stream finished: {:?}", reason);
-                        stop_reason = convert_finish_reason(reason);
-                    }
+            LoopEvent::ToolExecuting { id, name } => {
+                info!("Executing tool: {} ({})", name, id);
+                SessionUpdate::ToolCallUpdate(create_tool_call_status(
+                    &id,
+                    ToolCallStatus::InProgress,
+                ))
+            }
 
-                    StreamPart::Error { error } => {
-                        error!("Stream error: {}", error);
-                        return Err(AcpError::AiClientError(error));
-                    }
+            LoopEvent::ToolResult {
+                id,
+                output,
+                is_error,
+            } => {
+                if is_error {
+                    warn!("Tool {} failed: {}", id, output);
+                    SessionUpdate::ToolCallUpdate(create_tool_call_failed(&id, &output))
+                } else {
+                    let mut content = turn
+                        .tool_calls
+                        .get(&id)
+                        .map(|(name, arguments)| tool_call_diffs(name, arguments, &session.cwd))
+                        .unwrap_or_default();
+                    content.push(text_to_tool_content(&output));
+                    SessionUpdate::ToolCallUpdate(create_tool_call_complete(&id, content))
+                }
+            }
 
-                    _ => {}
+            LoopEvent::AwaitingInput {
+                tool_call_id,
+                tool_name,
+            } => {
+                let questions = turn
+                    .tool_calls
+                    .get(&tool_call_id)
+                    .filter(|_| tool_name == "AskUserQuestion")
+                    .map(|(_, arguments)| format_questions(arguments));
+                session.set_pending_question(Some(tool_call_id)).await;
+                match questions {
+                    Some(text) => SessionUpdate::AgentMessageChunk(text_chunk(&text)),
+                    None => return,
                 }
             }
 
-            // Add the assistant's text response to conversation history
-            if !accumulated_text.is_empty() {
-                session
-                    .add_assistant_message(accumulated_text.clone())
-                    .await;
+            LoopEvent::PlanUpdate { tasks } => {
+                let items: Vec<(String, bool)> = tasks
+                    .into_iter()
+                    .map(|t| (t.description, t.completed))
+                    .collect();
+                SessionUpdate::Plan(Plan::new(plan_items_to_entries(&items)))
             }
 
-            // If no tool calls, we're done
-            if pending_tool_calls.is_empty() {
-                info!("Agentic loop complete after {} iterations", iteration + 1);
-                return Ok(stop_reason);
+            LoopEvent::PlanComplete {
+                title, task_count, ..
+            } => SessionUpdate::AgentMessageChunk(text_chunk(&format!(
+                "\n\nPlan ready: {} ({} tasks). Reply \"execute\" to start building it \
+                 or \"abandon\" to discard it.",
+                title, task_count
+            ))),
+
+            LoopEvent::ModeChange { mode, .. } => {
+                let mode_id = acp_mode_id(&mode, session.get_mode().await.as_deref());
+                session.set_mode(Some(mode_id.to_string())).await;
+                SessionUpdate::CurrentModeUpdate(CurrentModeUpdate::new(mode_id))
             }
 
-            // Execute tool calls and add results to history
-            self.execute_tool_calls(session, pending_tool_calls, connection)
-                .await?;
+            LoopEvent::BudgetExceeded {
+                spent_usd,
+                limit_usd,
+            } => SessionUpdate::AgentMessageChunk(text_chunk(&format!(
+                "\n\nStopped: session spend ${:.2} reached hard budget ${:.2}",
+                spent_usd, limit_usd
+            ))),
+
+            LoopEvent::SessionPinched { session_id, .. } => {
+                info!("Session pinched, continuing in {}", session_id);
+                turn.session_id = session_id;
+                return;
+            }
 
-            // Loop continues - AI will be called again with tool results in history
-        }
+            LoopEvent::TurnComplete { has_more, .. } => {
+                turn.has_more = has_more;
+                return;
+            }
+
+            LoopEvent::Error { error } => {
+                warn!("Agentic loop error: {}", error);
+                turn.error = Some(error);
+                return;
+            }
 
-        warn!("Agentic loop hit maximum iterations ({})", MAX_ITERATIONS);
-        Ok(StopReason::EndTurn)
+            _ => return,
+        };
+
+        send_update(session, update, connection).await;
     }
 
-    /// Execute tool calls and stream their results
-    async fn execute_tool_calls<C: AcpClient>(
+    /// Switch the session's work mode, in storage and in the client
+    async fn set_work_mode<C: AcpClient>(
         &self,
         session: &SessionState,
-        tool_calls: Vec<AiToolCall>,
+        session_id: &str,
+        work_mode: WorkMode,
         connection: &C,
-    ) -> Result<StopReason, AcpError> {
-        let mut ctx = ToolContext {
-            // Always honor the session-specific working directory to prevent
-            // cross-session cwd bleed when multiple ACP sessions are active.
-            working_dir: session.cwd.clone(),
-            ..Default::default()
-        };
-
-        if let Some(ref identity) = self.git_identity {
-            if identity.mode != GitIdentityMode::Disabled {
-                ctx = ctx.with_git_identity(identity.clone());
+    ) {
+        match Database::new(&self.db_path) {
+            Ok(db) => {
+                if let Err(e) =
+                    StorageSessionManager::new(db).update_session_work_mode(session_id, work_mode)
+                {
+                    warn!("Failed to save work mode: {}", e);
+                }
             }
+            Err(e) => warn!("Failed to open database while saving work mode: {}", e),
         }
+        let mode_id = acp_mode_id(&work_mode.to_string(), session.get_mode().await.as_deref());
+        session.set_mode(Some(mode_id.to_string())).await;
+        send_update(
+            session,
+            SessionUpdate::CurrentModeUpdate(CurrentModeUpdate::new(mode_id)),
+            connection,
+        )
+        .await;
+    }
+}
 
-        for tool_call in tool_calls {
-            if session.is_cancelled() {
-                return Ok(StopReason::Cancelled);
-            }
+/// What a prompt's run has reported so far
+struct TurnState {
+    /// Storage session the orchestrator is writing to (changes on pinch)
+    session_id: String,
+    /// Name and arguments of each tool call, by id
+    tool_calls: HashMap<String, (String, Value)>,
+    /// Whether the last turn ended with work still to do
+    has_more: bool,
+    /// Last error the loop reported
+    error: Option<String>,
+}
 
-            info!("Executing tool: {} ({})", tool_call.name, tool_call.id);
-
-            // Send tool call start update
-            let start_update =
-                create_tool_call_start(&tool_call.id, &tool_call.name, tool_call.arguments.clone());
-            let notification = SessionNotification::new(
-                session.id.clone(),
-                SessionUpdate::ToolCallUpdate(start_update),
-            );
-            if let Err(e) = connection.session_notification(notification).await {
-                warn!("Failed to send tool start: {}", e);
-            }
+impl TurnState {
+    fn new(session_id: String) -> Self {
+        Self {
+            session_id,
+            tool_calls: HashMap::new(),
+            has_more: false,
+            error: None,
+        }
+    }
+}
 
-            // Execute the tool
-            let result = self
-                .tools
-                .execute(&tool_call.name, tool_call.arguments.clone(), &ctx)
-                .await;
+/// Send a session update, logging failures
+async fn send_update<C: AcpClient>(session: &SessionState, update: SessionUpdate, connection: &C) {
+    let notification = SessionNotification::new(session.id.clone(), update);
+    if let Err(e) = connection.session_notification(notification).await {
+        warn!("Failed to send session update: {}", e);
+    }
+}
 
-            // Send tool call result
-            let (update, output_for_history, is_error_for_history) = match &result {
-                Some(ToolResult { output, is_error }) if !*is_error => {
-                    info!("Tool {} completed successfully", tool_call.name);
-                    let content = vec![text_to_tool_content(output)];
-                    (
-                        create_tool_call_complete(&tool_call.id, content),
-                        Some(output.clone()),
-                        false,
-                    )
-                }
-                Some(ToolResult { output, is_error }) => {
-                    warn!("Tool {} failed: {}", tool_call.name, output);
-                    (
-                        create_tool_call_failed(&tool_call.id, output),
-                        Some(output.clone()),
-                        *is_error,
-                    )
-                }
-                None => {
-                    let msg = format!("Tool '{}' not found", tool_call.name);
-                    warn!("{}", msg);
-                    (create_tool_call_failed(&tool_call.id, &msg), None, true)
-                }
-            };
+/// Wrap text in a content chunk
+fn text_chunk(text: &str) -> ContentChunk {
+    ContentChunk::new(AcpContent::Text(TextContent::new(text)))
+}
 
-            let notification =
-                SessionNotification::new(session.id.clone(), SessionUpdate::ToolCallUpdate(update));
-            if let Err(e) = connection.session_notification(notification).await {
-                warn!("Failed to send tool result: {}", e);
-            }
+/// Map a Krusty work mode to the ACP session mode advertised for it
+///
+/// Building keeps an auto session in auto mode.
+fn acp_mode_id(work_mode: &str, current: Option<&str>) -> &'static str {
+    match (work_mode, current) {
+        ("plan", _) => "plan",
+        (_, Some("auto")) => "auto",
+        _ => "code",
+    }
+}
 
-            // Add tool call and result to session history
-            session
-                .add_tool_call(
-                    tool_call.id.clone(),
-                    tool_call.name.clone(),
-                    tool_call.arguments.clone(),
-                )
-                .await;
+/// Permission prompt options, by id
+const ALLOW_ONCE: &str = "allow-once";
+const ALLOW_ALWAYS: &str = "allow-always";
+const REJECT: &str = "reject";
 
-            if let Some(ref output) = output_for_history {
-                session
-                    .add_tool_result(&tool_call.id, output.clone(), is_error_for_history)
-                    .await;
-            }
+/// Ask the client whether a tool may run
+///
+/// "Always allow" answers are remembered for the rest of the session, and
+/// later calls to that tool are approved without asking.
+async fn request_approval<C: AcpClient>(
+    session: &SessionState,
+    id: &str,
+    tool_name: &str,
+    arguments: &Value,
+    connection: &C,
+) -> bool {
+    if session.is_tool_always_allowed(tool_name).await {
+        return true;
+    }
+
+    let options = vec![
+        PermissionOption::new(ALLOW_ONCE, "Allow once", PermissionOptionKind::AllowOnce),
+        PermissionOption::new(
+            ALLOW_ALWAYS,
+            "Always allow",
+            PermissionOptionKind::AllowAlways,
+        ),
+        PermissionOption::new(REJECT, "Reject", PermissionOptionKind::RejectOnce),
+    ];
+    let tool_call = create_tool_call_details(id, tool_name, arguments.clone());
+    let request = RequestPermissionRequest::new(session.id.clone(), tool_call, options);
+
+    let outcome = match connection.request_permission(request).await {
+        Ok(response) => response.outcome,
+        Err(e) => {
+            warn!("Permission request for {} failed: {}", tool_name, e);
+            return false;
         }
+    };
+    match outcome {
+        RequestPermissionOutcome::Selected(selected) => match &*selected.option_id.0 {
+            ALLOW_ONCE => true,
+            ALLOW_ALWAYS => {
+                session.always_allow_tool(tool_name).await;
+                true
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
 
-        // Tool calls completed - model should continue
-        Ok(StopReason::EndTurn)
+/// Render AskUserQuestion arguments as a message the user can answer
+fn format_questions(arguments: &Value) -> String {
+    let mut out = String::new();
+    let questions = arguments.get("questions").and_then(|v| v.as_array());
+    for question in questions.into_iter().flatten() {
+        let field = |value: &Value, name: &str| {
+            value
+                .get(name)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        out.push_str(&format!(
+            "\n\n**{}**: {}",
+            field(question, "header"),
+            field(question, "question")
+        ));
+        let options = question.get("options").and_then(|v| v.as_array());
+        for option in options.into_iter().flatten() {
+            let description = field(option, "description");
+            if description.is_empty() {
+                out.push_str(&format!("\n- {}", field(option, "label")));
+            } else {
+                out.push_str(&format!("\n- {}: {}", field(option, "label"), description));
+            }
+        }
     }
+    out
 }
 
-/// Convert AI finish reason to ACP stop reason
-fn convert_finish_reason(reason: FinishReason) -> StopReason {
-    match reason {
-        FinishReason::Stop => StopReason::EndTurn,
-        FinishReason::Length => StopReason::MaxTokens,
-        FinishReason::ToolCalls => StopReason::EndTurn,
-        FinishReason::ContentFilter => StopReason::EndTurn,
-        FinishReason::Other(_) => StopReason::EndTurn,
+/// Describe the outcome of a checkpoint restore
+fn describe_restore(summary: &RestoreSummary) -> String {
+    let mut out = format!(
+        "Rewound to before turn {}: {} files restored, {} removed",
+        summary.turn,
+        summary.restored.len(),
+        summary.deleted.len()
+    );
+    for failure in &summary.failed {
+        out.push_str(&format!("\nFailed: {}", failure));
     }
+    out
 }
 
 /// Convert ACP content block to Krusty's Content type
@@ -477,19 +841,24 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_convert_finish_reason() {
-        assert!(matches!(
-            convert_finish_reason(FinishReason::Stop),
-            StopReason::EndTurn
-        ));
-        assert!(matches!(
-            convert_finish_reason(FinishReason::ToolCalls),
-            StopReason::EndTurn
-        ));
-        assert!(matches!(
-            convert_finish_reason(FinishReason::Length),
-            StopReason::MaxTokens
-        ));
+    fn test_format_questions() {
+        let arguments = serde_json::json!({
+            "questions": [{
+                "header": "Database",
+                "question": "Which database should we use?",
+                "options": [
+                    {"label": "SQLite", "description": "Embedded"},
+                    {"label": "Postgres"}
+                ]
+            }]
+        });
+        assert_eq!(
+            format_questions(&arguments),
+            "\n\n**Database**: Which database should we use?\n- SQLite: Embedded\n- Postgres"
+        );
+        assert_eq!(acp_mode_id("build", Some("code")), "code");
+        assert_eq!(acp_mode_id("build", Some("auto")), "auto");
+        assert_eq!(acp_mode_id("plan", Some("auto")), "plan");
     }
 
     #[test]
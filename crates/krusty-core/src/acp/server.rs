@@ -4,6 +4,7 @@
 
 use std::future::Future;
 use std::pin::Pin;
+use std::rc::Rc;
 use std::sync::Arc;
 
 use agent_client_protocol::{AgentSideConnection, Client};
@@ -15,9 +16,10 @@ use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 use tracing::{error, info, warn};
 
 use super::agent::KrustyAgent;
+use super::bridge::ClientChannel;
 use crate::ai::providers::ProviderId;
 use crate::storage::credentials::{ActiveProviderStore, CredentialStore};
-use crate::tools::{register_acp_tools, ToolRegistry};
+use crate::tools::ToolRegistry;
 
 /// ACP Server configuration
 #[derive(Debug, Clone, Default)]
@@ -66,8 +68,8 @@ impl AcpServer {
     pub async fn run(self) -> Result<()> {
         info!("Starting Krusty ACP server");
 
-        // Register ACP-compatible tools (excludes TUI-only tools like AskUserQuestion)
-        register_acp_tools(self.agent.tools()).await;
+        // Register the same tools the TUI and server offer
+        self.agent.register_tools().await;
         info!(
             "Registered {} tools",
             self.agent.tools().get_ai_tools().await.len()
@@ -89,7 +91,8 @@ impl AcpServer {
                  - KRUSTY_PROVIDER + KRUSTY_API_KEY (+ optional KRUSTY_MODEL)\n\
                  - MINIMAX_API_KEY\n\
                  - OPENROUTER_API_KEY\n\
-                 - ZAI_API_KEY"
+                 - ZAI_API_KEY\n\
+                 - GEMINI_API_KEY"
             );
         }
 
@@ -105,8 +108,14 @@ impl AcpServer {
                 // Create notification channel
                 let (tx, mut rx) = mpsc::channel(1000);
 
-                // Give the sender to the agent
+                // Channel for client requests (permissions, file access, terminals)
+                let (client_tx, mut client_rx) = mpsc::unbounded_channel();
+
+                // Give the senders to the agent
                 self.agent.set_notification_channel(tx).await;
+                self.agent
+                    .set_client_channel(ClientChannel::new(client_tx))
+                    .await;
 
                 // Get stdin/stdout for transport, wrapped for futures compatibility
                 let stdin = stdin().compat();
@@ -120,34 +129,51 @@ impl AcpServer {
                 // Create connection with our agent
                 let (connection, io_task) =
                     AgentSideConnection::new(self.agent, stdout, stdin, spawn_fn);
+                let connection = Rc::new(connection);
 
                 info!("ACP connection established, waiting for requests...");
 
                 // Spawn task to forward notifications to the connection
+                let notification_connection = Rc::clone(&connection);
                 tokio::task::spawn_local(async move {
                     while let Some(notification) = rx.recv().await {
-                        if let Err(e) = connection.session_notification(notification).await {
+                        if let Err(e) = notification_connection
+                            .session_notification(notification)
+                            .await
+                        {
                             warn!("Failed to forward notification: {}", e);
                         }
                     }
                 });
 
+                // Run client requests concurrently; each waits on the editor
+                tokio::task::spawn_local(async move {
+                    while let Some(task) = client_rx.recv().await {
+                        tokio::task::spawn_local(task(Rc::clone(&connection)));
+                    }
+                });
+
                 // Run the IO task
-                if let Err(e) = io_task.await {
-                    error!("ACP connection error: {}", e);
-                    return Err(anyhow::anyhow!("ACP connection error: {}", e));
-                }
+                let io_result = io_task.await;
 
-                // Clean up sessions on disconnect
+                // Clean up sessions on disconnect, whether or not the
+                // connection failed, so their MCP servers are shut down
                 let session_ids = agent_for_cleanup.sessions().session_ids();
                 let session_count = session_ids.len();
                 for id in session_ids {
-                    agent_for_cleanup.sessions().remove_session(&id);
+                    if let Some(session) = agent_for_cleanup.sessions().remove_session(&id) {
+                        session.close().await;
+                    }
                 }
                 if session_count > 0 {
                     info!("Cleaned up {} sessions on disconnect", session_count);
                 }
 
+                if let Err(e) = io_result {
+                    error!("ACP connection error: {}", e);
+                    return Err(anyhow::anyhow!("ACP connection error: {}", e));
+                }
+
                 info!("ACP connection closed");
                 Ok(())
             })
@@ -176,7 +202,7 @@ pub struct AcpEnvConfig {
 /// 3. Krusty's stored credentials (~/.krusty/tokens/credentials.json)
 ///
 /// Environment variable options:
-/// - KRUSTY_PROVIDER: minimax, openrouter, zai
+/// - KRUSTY_PROVIDER: minimax, openrouter, zai, gemini, or a user-defined provider id
 /// - KRUSTY_MODEL: Override the default model for the provider
 /// - KRUSTY_API_KEY: Generic API key (used with KRUSTY_PROVIDER)
 fn detect_api_key_from_env() -> Option<AcpEnvConfig> {
@@ -188,7 +214,7 @@ fn detect_api_key_from_env() -> Option<AcpEnvConfig> {
             "minimax" => Some(ProviderId::MiniMax),
             "openrouter" => Some(ProviderId::OpenRouter),
             "zai" | "z.ai" => Some(ProviderId::ZAi),
-            _ => None,
+            other => ProviderId::from_storage_key(other),
         };
 
         if let Some(provider) = provider {
@@ -213,6 +239,7 @@ fn detect_api_key_from_env() -> Option<AcpEnvConfig> {
         (ProviderId::MiniMax, "MINIMAX_API_KEY"),
         (ProviderId::OpenRouter, "OPENROUTER_API_KEY"),
         (ProviderId::ZAi, "ZAI_API_KEY"),
+        (ProviderId::Gemini, "GEMINI_API_KEY"),
         // OpenAI key maps to OpenRouter (which supports OpenAI models)
         (ProviderId::OpenRouter, "OPENAI_API_KEY"),
     ];
@@ -252,13 +279,13 @@ fn detect_from_credential_store(model: Option<String>) -> Option<AcpEnvConfig> {
     let active_provider = ActiveProviderStore::load();
 
     // Try active provider first
-    if let Some(api_key) = store.get(&active_provider) {
+    if let Some(api_key) = store.get_api_key(&active_provider) {
         info!(
             "Using active provider {:?} from credential store",
             active_provider
         );
         return Some(AcpEnvConfig {
-            api_key: api_key.clone(),
+            api_key,
             provider: active_provider,
             model,
         });
@@ -291,6 +318,8 @@ fn get_provider_api_key(provider: ProviderId) -> Option<String> {
         ProviderId::ZAi => "ZAI_API_KEY",
         ProviderId::Anthropic => "ANTHROPIC_API_KEY",
         ProviderId::OpenAI => "OPENAI_API_KEY",
+        ProviderId::Gemini => "GEMINI_API_KEY",
+        ProviderId::Custom(_) => return CredentialStore::default().get_api_key(&provider),
     };
     std::env::var(env_var).ok().filter(|s| !s.is_empty())
 }
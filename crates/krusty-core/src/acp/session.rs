@@ -2,22 +2,26 @@
 //!
 //! Manages session state for ACP connections. Each session maintains:
 //! - Working directory context
-//! - MCP server configurations
+//! - MCP servers supplied by the client, connected for the session's lifetime
 //! - Conversation history
 //! - Cancellation state
+//! - A question the agent is waiting on the user to answer
+//! - Tools the user always allowed for the rest of the session
 //! - Optional persistence to SQLite via storage::SessionManager
 
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 
-use agent_client_protocol::{McpServer, SessionId};
+use agent_client_protocol::{HttpHeader, McpServer, SessionId};
 use dashmap::DashMap;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::{Mutex, Notify, RwLock};
 use tracing::{debug, info, warn};
 
 use super::error::AcpError;
 use crate::ai::types::{ModelMessage, Role};
+use crate::mcp::{McpManager, McpServerConfig};
 use crate::storage::SessionManager as StorageSessionManager;
 use crate::tools::ToolContext;
 
@@ -35,12 +39,20 @@ pub struct SessionState {
     pub cwd: PathBuf,
     /// MCP server configurations passed by the client
     pub mcp_servers: Vec<McpServer>,
+    /// Connections to `mcp_servers`, once started
+    mcp_manager: RwLock<Option<Arc<McpManager>>>,
     /// Current session mode (e.g., "code", "architect", "ask")
     pub mode: RwLock<Option<String>>,
     /// Conversation messages
     pub messages: RwLock<Vec<ModelMessage>>,
     /// Whether this session has been cancelled
     cancelled: AtomicBool,
+    /// Wakes prompts waiting in `cancelled()`
+    cancel_notify: Notify,
+    /// AskUserQuestion call whose answer is the next prompt
+    pending_question: RwLock<Option<String>>,
+    /// Tools the user chose "always allow" for in a permission prompt
+    always_allowed_tools: RwLock<HashSet<String>>,
     /// Tool context for this session
     pub tool_context: RwLock<Option<ToolContext>>,
     /// Storage session ID for persistence (links to SQLite storage)
@@ -71,9 +83,13 @@ impl SessionState {
             id,
             cwd: working_dir,
             mcp_servers: mcp_servers.unwrap_or_default(),
+            mcp_manager: RwLock::new(None),
             mode: RwLock::new(None),
             messages: RwLock::new(Vec::new()),
             cancelled: AtomicBool::new(false),
+            cancel_notify: Notify::new(),
+            pending_question: RwLock::new(None),
+            always_allowed_tools: RwLock::new(HashSet::new()),
             tool_context: RwLock::new(None),
             storage_session_id: RwLock::new(None),
             storage,
@@ -84,6 +100,18 @@ impl SessionState {
     pub fn cancel(&self) {
         debug!("Cancelling session {}", self.id);
         self.cancelled.store(true, Ordering::SeqCst);
+        self.cancel_notify.notify_waiters();
+    }
+
+    /// Wait until this session is cancelled
+    pub async fn cancelled(&self) {
+        loop {
+            let notified = self.cancel_notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
     }
 
     /// Check if session is cancelled
@@ -138,7 +166,123 @@ impl SessionState {
         }
     }
 
+    /// Remember the AskUserQuestion call the next prompt answers
+    pub async fn set_pending_question(&self, tool_call_id: Option<String>) {
+        *self.pending_question.write().await = tool_call_id;
+    }
+
+    /// Take the AskUserQuestion call the next prompt answers, if any
+    pub async fn take_pending_question(&self) -> Option<String> {
+        self.pending_question.write().await.take()
+    }
+
+    /// Allow a tool without asking for the rest of the session
+    pub async fn always_allow_tool(&self, tool_name: &str) {
+        self.always_allowed_tools
+            .write()
+            .await
+            .insert(tool_name.to_string());
+    }
+
+    /// Check if the user always allowed a tool in this session
+    pub async fn is_tool_always_allowed(&self, tool_name: &str) -> bool {
+        self.always_allowed_tools.read().await.contains(tool_name)
+    }
+
+    /// Start the MCP servers the client supplied for this session
+    pub async fn connect_mcp_servers(&self) {
+        if self.mcp_servers.is_empty() {
+            return;
+        }
+
+        let manager = McpManager::new(self.cwd.clone());
+        for server in &self.mcp_servers {
+            match mcp_server_config(server) {
+                Some((name, config)) => manager.add_server(name, config).await,
+                None => warn!("Skipping MCP server with unsupported transport"),
+            }
+        }
+        if let Err(e) = manager.connect_all().await {
+            warn!(
+                "Failed to connect MCP servers for session {}: {}",
+                self.id, e
+            );
+        }
+
+        info!(
+            "Session {} connected {} client MCP servers",
+            self.id,
+            manager.list_servers().await.len()
+        );
+        *self.mcp_manager.write().await = Some(Arc::new(manager));
+    }
+
+    /// Connections to the client's MCP servers, if any were supplied
+    pub async fn mcp_manager(&self) -> Option<Arc<McpManager>> {
+        self.mcp_manager.read().await.clone()
+    }
+
+    /// End the session, shutting down its MCP servers
+    pub async fn close(&self) {
+        if let Some(manager) = self.mcp_manager.write().await.take() {
+            manager.disconnect_all().await;
+        }
+    }
+
+    /// Replace the placeholder result of a tool call in the last message
+    ///
+    /// Returns false when the last message holds no result for the call.
+    pub async fn answer_tool_call(&self, tool_call_id: &str, answer: String) -> bool {
+        use crate::ai::types::Content;
+
+        let mut messages = self.messages.write().await;
+        let Some(last) = messages.last_mut().filter(|m| m.role == Role::User) else {
+            return false;
+        };
+        let Some(output) = last.content.iter_mut().find_map(|c| match c {
+            Content::ToolResult {
+                tool_use_id,
+                output,
+                ..
+            } if tool_use_id == tool_call_id => Some(output),
+            _ => None,
+        }) else {
+            return false;
+        };
+        *output = serde_json::Value::String(answer);
+
+        if let (Some(storage), Some(session_id)) =
+            (&self.storage, self.storage_session_id.read().await.as_ref())
+        {
+            match serde_json::to_string(&last.content) {
+                Ok(content_json) => {
+                    let storage = storage.lock().await;
+                    if let Err(e) = storage.update_last_message(session_id, "user", &content_json) {
+                        warn!("Failed to persist answer: {}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to serialize message content: {}", e),
+            }
+        }
+        true
+    }
+
+    /// Keep the first `keep` messages, in memory and in storage (rewind)
+    pub async fn truncate_messages(&self, keep: usize) {
+        self.messages.write().await.truncate(keep);
+        if let Some(ref storage) = self.storage {
+            if let Some(ref session_id) = *self.storage_session_id.read().await {
+                let storage = storage.lock().await;
+                if let Err(e) = storage.truncate_messages(session_id, keep) {
+                    warn!("Failed to truncate stored messages: {}", e);
+                }
+            }
+        }
+    }
+
     /// Initialize storage session (creates a new persistent session)
+    ///
+    /// Messages added before the session was persisted are saved to it.
     pub async fn init_storage_session(&self, title: &str) -> Option<String> {
         if let Some(ref storage) = self.storage {
             let working_dir = self.cwd.to_string_lossy();
@@ -150,6 +294,9 @@ impl SessionState {
                 Ok(id) => {
                     *self.storage_session_id.write().await = Some(id.clone());
                     info!("Created storage session {} for ACP session {}", id, self.id);
+                    for message in self.messages.read().await.iter() {
+                        self.persist_message(message).await;
+                    }
                     Some(id)
                 }
                 Err(e) => {
@@ -439,6 +586,39 @@ impl Default for SessionManager {
     }
 }
 
+/// Convert an MCP server from `session/new` into Krusty's config
+///
+/// Returns None for transports this version doesn't know about.
+fn mcp_server_config(server: &McpServer) -> Option<(String, McpServerConfig)> {
+    let remote = |url: &str, headers: &[HttpHeader], legacy_sse: bool| McpServerConfig::Remote {
+        url: url.to_string(),
+        authorization_token: None,
+        headers: headers
+            .iter()
+            .map(|h| (h.name.clone(), h.value.clone()))
+            .collect(),
+        legacy_sse,
+    };
+
+    match server {
+        McpServer::Stdio(stdio) => Some((
+            stdio.name.clone(),
+            McpServerConfig::Local {
+                command: stdio.command.to_string_lossy().to_string(),
+                args: stdio.args.clone(),
+                env: stdio
+                    .env
+                    .iter()
+                    .map(|v| (v.name.clone(), v.value.clone()))
+                    .collect::<HashMap<_, _>>(),
+            },
+        )),
+        McpServer::Http(http) => Some((http.name.clone(), remote(&http.url, &http.headers, false))),
+        McpServer::Sse(sse) => Some((sse.name.clone(), remote(&sse.url, &sse.headers, true))),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -477,6 +657,45 @@ mod tests {
         assert!(manager.get_session(&fake_id).is_err());
     }
 
+    #[tokio::test]
+    async fn test_always_allowed_tools() {
+        let session = SessionState::new(SessionId::from("1".to_string()), None, None);
+
+        assert!(!session.is_tool_always_allowed("bash").await);
+        session.always_allow_tool("bash").await;
+        assert!(session.is_tool_always_allowed("bash").await);
+        assert!(!session.is_tool_always_allowed("write").await);
+    }
+
+    #[test]
+    fn test_mcp_server_config() {
+        use agent_client_protocol::{EnvVariable, McpServerSse, McpServerStdio};
+
+        let stdio = McpServer::Stdio(
+            McpServerStdio::new("files", "/usr/bin/mcp-files")
+                .args(vec!["--root".to_string(), ".".to_string()])
+                .env(vec![EnvVariable::new("TOKEN", "secret")]),
+        );
+        let (name, config) = mcp_server_config(&stdio).unwrap();
+        assert_eq!(name, "files");
+        match config {
+            McpServerConfig::Local { command, args, env } => {
+                assert_eq!(command, "/usr/bin/mcp-files");
+                assert_eq!(args, vec!["--root", "."]);
+                assert_eq!(env.get("TOKEN").map(String::as_str), Some("secret"));
+            }
+            other => panic!("expected local server, got {:?}", other),
+        }
+
+        let sse = McpServer::Sse(
+            McpServerSse::new("docs", "https://example.com/sse")
+                .headers(vec![HttpHeader::new("Authorization", "Bearer x")]),
+        );
+        let (name, config) = mcp_server_config(&sse).unwrap();
+        assert_eq!(name, "docs");
+        assert_eq!(config.transport_type(), "sse");
+    }
+
     #[tokio::test]
     async fn test_session_with_storage() {
         use crate::storage::Database;
@@ -1,44 +1,38 @@
 //! ACP tool integration
 //!
 //! Bridges Krusty's tool system with ACP's tool call protocol.
-//! Some tools are executed locally, others are delegated to the client.
+//! File and terminal tools are delegated to the client (editor) when it
+//! advertises the capability, and executed locally otherwise.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use agent_client_protocol::{
-    Client, Content, ContentBlock, CreateTerminalRequest, Diff, ReadTextFileRequest,
-    ReleaseTerminalRequest, SessionId, TerminalId, TerminalOutputRequest, TextContent,
+    Client, ClientCapabilities, Content, ContentBlock, CreateTerminalRequest, Diff, EnvVariable,
+    KillTerminalCommandRequest, ReadTextFileRequest, ReleaseTerminalRequest, SessionId,
+    SessionNotification, SessionUpdate, Terminal, TerminalId, TerminalOutputRequest, TextContent,
     ToolCallContent, ToolCallId, ToolCallLocation, ToolCallStatus, ToolCallUpdate,
-    ToolCallUpdateFields, ToolKind, WriteTextFileRequest,
+    ToolCallUpdateFields, ToolKind, WaitForTerminalExitRequest, WriteTextFileRequest,
 };
+use async_trait::async_trait;
 use serde_json::Value;
-use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
+use super::bridge::ClientChannel;
 use super::error::AcpError;
-
-/// Tools that should be delegated to the client (editor)
-#[allow(dead_code)]
-const CLIENT_DELEGATED_TOOLS: &[&str] = &[
-    // File operations - editor has the authoritative view
-    // Note: We may still execute locally if client doesn't support
-];
-
-/// Check if a tool should be delegated to the client
-#[allow(dead_code)]
-pub fn should_delegate_to_client(tool_name: &str) -> bool {
-    CLIENT_DELEGATED_TOOLS.contains(&tool_name)
-}
+use crate::tools::{EditorClient, EditorCommandOutput, TerminalUnavailable};
 
 /// Map tool name to ACP ToolKind for proper UI categorization
 pub fn tool_name_to_kind(tool_name: &str) -> ToolKind {
     match tool_name {
         // Read operations
-        "read" | "Read" | "cat" => ToolKind::Read,
+        "read" | "Read" | "cat" | "hover" | "diagnostics" => ToolKind::Read,
         // Edit operations
         "edit" | "Edit" | "write" | "Write" | "patch" | "apply_patch" | "multiedit"
-        | "multi_edit" => ToolKind::Edit,
+        | "multi_edit" | "rename" => ToolKind::Edit,
         // Search operations
         "grep" | "Grep" | "glob" | "Glob" | "find" | "search" | "ripgrep" | "list" | "ls"
-        | "list_dir" => ToolKind::Search,
+        | "list_dir" | "definition" | "references" => ToolKind::Search,
         // Execute operations
         "bash" | "Bash" | "shell" | "exec" | "run" | "terminal" => ToolKind::Execute,
         // Fetch operations
@@ -50,7 +44,7 @@ pub fn tool_name_to_kind(tool_name: &str) -> ToolKind {
         // Delete operations
         "delete" | "remove" | "rm" => ToolKind::Delete,
         // Move operations
-        "move" | "mv" | "rename" => ToolKind::Move,
+        "move" | "mv" => ToolKind::Move,
         // Default
         _ => ToolKind::Other,
     }
@@ -213,14 +207,13 @@ pub fn create_tool_title(tool_name: &str, input: &Value) -> String {
     }
 }
 
-/// Create an ACP tool call update for a starting tool
-pub fn create_tool_call_start(id: &str, tool_name: &str, input: Value) -> ToolCallUpdate {
+/// Create an ACP tool call update describing a tool call whose arguments are known
+pub fn create_tool_call_details(id: &str, tool_name: &str, input: Value) -> ToolCallUpdate {
     let kind = tool_name_to_kind(tool_name);
     let locations = extract_locations(tool_name, &input);
     let title = create_tool_title(tool_name, &input);
 
     let mut fields = ToolCallUpdateFields::new();
-    fields.status = Some(ToolCallStatus::InProgress);
     fields.kind = Some(kind);
     fields.title = Some(title);
     fields.raw_input = Some(input);
@@ -231,6 +224,14 @@ pub fn create_tool_call_start(id: &str, tool_name: &str, input: Value) -> ToolCa
     ToolCallUpdate::new(ToolCallId::from(id.to_string()), fields)
 }
 
+/// Create an ACP tool call update that only changes the status
+pub fn create_tool_call_status(id: &str, status: ToolCallStatus) -> ToolCallUpdate {
+    let mut fields = ToolCallUpdateFields::new();
+    fields.status = Some(status);
+
+    ToolCallUpdate::new(ToolCallId::from(id.to_string()), fields)
+}
+
 /// Create an ACP tool call update for a completed tool
 pub fn create_tool_call_complete(id: &str, content: Vec<ToolCallContent>) -> ToolCallUpdate {
     let mut fields = ToolCallUpdateFields::new();
@@ -259,17 +260,64 @@ pub fn text_to_tool_content(text: &str) -> ToolCallContent {
 }
 
 /// Convert a file diff to ACP diff content
-/// Note: ACP Diff only stores the new text, not old text (unified diff is computed by client)
-#[allow(dead_code)]
-pub fn diff_to_tool_content(path: &Path, _old_text: &str, new_text: &str) -> ToolCallContent {
-    ToolCallContent::Diff(Diff::new(
-        path.to_string_lossy().to_string(),
-        new_text.to_string(),
-    ))
+///
+/// `old_text` is None for new files; clients compute the unified diff.
+pub fn diff_to_tool_content(
+    path: &Path,
+    old_text: Option<&str>,
+    new_text: &str,
+) -> ToolCallContent {
+    ToolCallContent::Diff(
+        Diff::new(path.to_path_buf(), new_text.to_string()).old_text(old_text.map(String::from)),
+    )
+}
+
+/// Diffs for a successful file-editing tool call, built from its arguments
+///
+/// Returns an empty list for tools that don't edit files. Relative paths are
+/// resolved against `cwd` since ACP requires absolute paths.
+pub fn tool_call_diffs(tool_name: &str, input: &Value, cwd: &Path) -> Vec<ToolCallContent> {
+    let Some(path) = input.get("file_path").and_then(|v| v.as_str()) else {
+        return Vec::new();
+    };
+    let path = cwd.join(path);
+    let str_field = |value: &Value, field: &str| {
+        value
+            .get(field)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+    };
+
+    match tool_name {
+        "write" => str_field(input, "content")
+            .map(|content| vec![diff_to_tool_content(&path, None, &content)])
+            .unwrap_or_default(),
+        "edit" => match (
+            str_field(input, "old_string"),
+            str_field(input, "new_string"),
+        ) {
+            (Some(old), Some(new)) => vec![diff_to_tool_content(&path, Some(&old), &new)],
+            _ => Vec::new(),
+        },
+        "multiedit" => input
+            .get("edits")
+            .and_then(|v| v.as_array())
+            .map(|edits| {
+                edits
+                    .iter()
+                    .filter_map(|edit| {
+                        let old = str_field(edit, "old_string")?;
+                        let new = str_field(edit, "new_string")?;
+                        Some(diff_to_tool_content(&path, Some(&old), &new))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
 }
 
 /// Read a file via the ACP client (if supported)
-#[allow(dead_code)]
 pub async fn read_file_via_client<C: Client>(
     client: &C,
     session_id: &SessionId,
@@ -291,7 +339,6 @@ pub async fn read_file_via_client<C: Client>(
 }
 
 /// Write a file via the ACP client (if supported)
-#[allow(dead_code)]
 pub async fn write_file_via_client<C: Client>(
     client: &C,
     session_id: &SessionId,
@@ -314,17 +361,25 @@ pub async fn write_file_via_client<C: Client>(
     Ok(())
 }
 
-/// Create a terminal via the ACP client
-#[allow(dead_code)]
+/// Create a terminal via the ACP client, running `command` through the shell
 pub async fn create_terminal_via_client<C: Client>(
     client: &C,
     session_id: &SessionId,
     command: &str,
-    _cwd: Option<&Path>,
+    cwd: Option<&Path>,
+    env: Vec<(String, String)>,
 ) -> Result<String, AcpError> {
     debug!("Creating terminal via client: {}", command);
 
-    let request = CreateTerminalRequest::new(session_id.clone(), command.to_string());
+    let env = env
+        .into_iter()
+        .map(|(name, value)| EnvVariable::new(name, value))
+        .collect();
+    let request = CreateTerminalRequest::new(session_id.clone(), "sh")
+        .args(vec!["-c".to_string(), command.to_string()])
+        .env(env)
+        .cwd(cwd.map(Path::to_path_buf))
+        .output_byte_limit(TERMINAL_OUTPUT_LIMIT);
 
     let response = client
         .create_terminal(request)
@@ -334,8 +389,36 @@ pub async fn create_terminal_via_client<C: Client>(
     Ok(response.terminal_id.to_string())
 }
 
+/// Wait for a terminal's command to exit via the ACP client
+///
+/// Kills the command if it runs longer than `timeout`. Returns the exit
+/// code (None if killed by a signal) and whether it timed out.
+pub async fn wait_for_terminal_via_client<C: Client>(
+    client: &C,
+    session_id: &SessionId,
+    terminal_id: &str,
+    timeout: Duration,
+) -> Result<(Option<i32>, bool), AcpError> {
+    let terminal_id = TerminalId::from(terminal_id.to_string());
+    let request = WaitForTerminalExitRequest::new(session_id.clone(), terminal_id.clone());
+
+    match tokio::time::timeout(timeout, client.wait_for_terminal_exit(request)).await {
+        Ok(response) => {
+            let response = response.map_err(|e| AcpError::ToolError(e.to_string()))?;
+            Ok((response.exit_status.exit_code.map(|c| c as i32), false))
+        }
+        Err(_) => {
+            debug!("Terminal {} timed out, killing", terminal_id);
+            let request = KillTerminalCommandRequest::new(session_id.clone(), terminal_id);
+            if let Err(e) = client.kill_terminal_command(request).await {
+                warn!("Failed to kill timed out terminal: {}", e);
+            }
+            Ok((None, true))
+        }
+    }
+}
+
 /// Get terminal output via the ACP client
-#[allow(dead_code)]
 pub async fn get_terminal_output_via_client<C: Client>(
     client: &C,
     session_id: &SessionId,
@@ -358,7 +441,6 @@ pub async fn get_terminal_output_via_client<C: Client>(
 }
 
 /// Release a terminal via the ACP client
-#[allow(dead_code)]
 pub async fn release_terminal_via_client<C: Client>(
     client: &C,
     session_id: &SessionId,
@@ -378,3 +460,138 @@ pub async fn release_terminal_via_client<C: Client>(
 
     Ok(())
 }
+
+/// Output kept by client terminals (matches what the bash tool captures)
+const TERMINAL_OUTPUT_LIMIT: u64 = 2_000_000;
+
+/// Serves Krusty's file and terminal tools from the ACP client
+///
+/// Reads see unsaved buffers, writes land in the editor's undo stack, and
+/// commands run in the editor's terminals. Each operation is only offered
+/// when the client advertised it in `ClientCapabilities`.
+pub struct ClientEditor {
+    client: ClientChannel,
+    session_id: SessionId,
+    capabilities: ClientCapabilities,
+}
+
+impl ClientEditor {
+    /// Create an editor for `session_id`
+    ///
+    /// Returns None when the client offers neither file access nor terminals.
+    pub fn new(
+        client: ClientChannel,
+        session_id: SessionId,
+        capabilities: ClientCapabilities,
+    ) -> Option<Self> {
+        let fs = &capabilities.fs;
+        if !fs.read_text_file && !fs.write_text_file && !capabilities.terminal {
+            return None;
+        }
+        Some(Self {
+            client,
+            session_id,
+            capabilities,
+        })
+    }
+}
+
+#[async_trait]
+impl EditorClient for ClientEditor {
+    fn can_read_files(&self) -> bool {
+        self.capabilities.fs.read_text_file
+    }
+
+    fn can_write_files(&self) -> bool {
+        self.capabilities.fs.write_text_file
+    }
+
+    fn can_run_commands(&self) -> bool {
+        self.capabilities.terminal
+    }
+
+    async fn read_text_file(&self, path: &Path) -> anyhow::Result<String> {
+        let session_id = self.session_id.clone();
+        let path = path.to_path_buf();
+        let content = self
+            .client
+            .call(move |connection| async move {
+                read_file_via_client(&*connection, &session_id, &path, None, None).await
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))??;
+        Ok(content)
+    }
+
+    async fn write_text_file(&self, path: &Path, content: &str) -> anyhow::Result<()> {
+        let session_id = self.session_id.clone();
+        let path = path.to_path_buf();
+        let content = content.to_string();
+        self.client
+            .call(move |connection| async move {
+                write_file_via_client(&*connection, &session_id, &path, &content).await
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))??;
+        Ok(())
+    }
+
+    async fn run_command(
+        &self,
+        command: &str,
+        cwd: &Path,
+        env: Vec<(String, String)>,
+        timeout: Duration,
+        tool_use_id: Option<&str>,
+    ) -> anyhow::Result<EditorCommandOutput> {
+        let session_id = self.session_id.clone();
+        let command = command.to_string();
+        let cwd = cwd.to_path_buf();
+        let tool_use_id = tool_use_id.map(ToString::to_string);
+        let output = self
+            .client
+            .call(move |connection| async move {
+                let client = &*connection;
+                let terminal_id =
+                    create_terminal_via_client(client, &session_id, &command, Some(&cwd), env)
+                        .await
+                        .map_err(|e| TerminalUnavailable(e.to_string()))?;
+
+                // Show the live terminal inside the tool call
+                if let Some(id) = tool_use_id {
+                    let terminal = ToolCallContent::Terminal(Terminal::new(terminal_id.clone()));
+                    let update = ToolCallUpdate::new(
+                        ToolCallId::new(id),
+                        ToolCallUpdateFields::new().content(vec![terminal]),
+                    );
+                    let notification = SessionNotification::new(
+                        session_id.clone(),
+                        SessionUpdate::ToolCallUpdate(update),
+                    );
+                    if let Err(e) = client.session_notification(notification).await {
+                        warn!("Failed to attach terminal to tool call: {}", e);
+                    }
+                }
+
+                let waited =
+                    wait_for_terminal_via_client(client, &session_id, &terminal_id, timeout).await;
+                let output =
+                    get_terminal_output_via_client(client, &session_id, &terminal_id).await;
+                if let Err(e) = release_terminal_via_client(client, &session_id, &terminal_id).await
+                {
+                    warn!("Failed to release terminal: {}", e);
+                }
+
+                let (exit_code, timed_out) = waited?;
+                let (output, _) = output?;
+                Ok::<_, anyhow::Error>(EditorCommandOutput {
+                    output,
+                    exit_code,
+                    timed_out,
+                })
+            })
+            .await
+            .map_err(|e| anyhow::anyhow!("{}", e))??;
+        Ok(output)
+    }
+}
@@ -76,7 +76,6 @@ fn update_type(update: &SessionUpdate) -> &'static str {
 }
 
 /// Convert Krusty plan items to ACP plan entries
-#[allow(dead_code)]
 pub fn plan_items_to_entries(items: &[(String, bool)]) -> Vec<PlanEntry> {
     items
         .iter()
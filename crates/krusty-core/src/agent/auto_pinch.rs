@@ -0,0 +1,316 @@
+//! Automatic pinch
+//!
+//! The orchestrator compares each turn's token usage against the model's
+//! context window. Crossing the policy threshold emits
+//! `LoopEvent::ContextNearLimit`; under `AutoPinchMode::Auto` the
+//! conversation is then summarized into a linked session and the loop
+//! carries on there, or compacted in place under `PinchStrategy::Compact`,
+//! so every front end gets the same behavior.
+
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::context::read_project_instructions;
+use super::pinch_context::{PinchContext, PinchContextInput};
+use super::summarizer::generate_summary;
+use crate::ai::client::AiClient;
+use crate::ai::types::{Content, ModelMessage, Role};
+use crate::plan::PlanManager;
+use crate::storage::{Database, FileActivityTracker, RankedFile, SessionManager};
+
+/// Default share of the context window that triggers a pinch
+pub const DEFAULT_AUTO_PINCH_THRESHOLD: f32 = 0.80;
+
+/// Prompt that resumes autonomous work in the new session
+pub const CONTINUE_PROMPT: &str = "Continue working on the current task.";
+
+/// Files ranked for the summary, and how many of them are read in full
+pub(super) const RANKED_FILE_LIMIT: usize = 20;
+pub(super) const SUMMARY_FILE_CONTENTS: usize = 10;
+/// Key files embedded in the new session's pinch message
+const CARRIED_FILE_CONTENTS: usize = 5;
+
+/// What happens when a session nears its context window
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoPinchMode {
+    /// Never warn or pinch
+    Off,
+    /// Emit `ContextNearLimit` and leave pinching to the user
+    Notify,
+    /// Warn, then free up context using the policy's strategy
+    #[default]
+    Auto,
+}
+
+/// How an automatic pinch frees up context
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PinchStrategy {
+    /// Summarize into a linked session and continue there
+    #[default]
+    LinkedSession,
+    /// Summarize old turns and elide stale tool output in the same session
+    Compact,
+}
+
+/// Auto-pinch policy, stored under the `auto_pinch` preference
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoPinchPolicy {
+    pub mode: AutoPinchMode,
+    /// Share of the context window (0.0-1.0) that counts as near the limit
+    pub threshold: f32,
+    pub strategy: PinchStrategy,
+}
+
+impl Default for AutoPinchPolicy {
+    fn default() -> Self {
+        Self {
+            mode: AutoPinchMode::default(),
+            threshold: DEFAULT_AUTO_PINCH_THRESHOLD,
+            strategy: PinchStrategy::default(),
+        }
+    }
+}
+
+impl AutoPinchPolicy {
+    /// Check that the threshold is a share of the window in (0.0, 1.0]
+    pub fn validate(&self) -> Result<()> {
+        if self.threshold > 0.0 && self.threshold <= 1.0 {
+            Ok(())
+        } else {
+            Err(anyhow!(
+                "Auto-pinch threshold must be above 0 and at most 1, got {}",
+                self.threshold
+            ))
+        }
+    }
+
+    /// Whether `tokens_used` is past the threshold (never for an unknown window)
+    pub fn is_near_limit(&self, tokens_used: usize, context_window: usize) -> bool {
+        self.mode != AutoPinchMode::Off
+            && context_window > 0
+            && tokens_used as f32 / context_window as f32 >= self.threshold
+    }
+
+    /// Whether the orchestrator should pinch on its own
+    pub fn pinches(&self) -> bool {
+        self.mode == AutoPinchMode::Auto
+    }
+}
+
+/// A linked session created by an automatic pinch
+#[derive(Debug, Clone)]
+pub struct PinchOutcome {
+    pub session_id: String,
+    pub title: String,
+    /// Title of the session that was pinched
+    pub source_title: String,
+    /// Work summary, used to generate a better title
+    pub summary: String,
+    /// Conversation to continue with: pinch context, then `next_prompt`
+    pub conversation: Vec<ModelMessage>,
+}
+
+/// Summarize a session into a new linked session
+///
+/// `conversation` is what gets summarized; `next_prompt` becomes the first
+/// user message of the new session. The active plan is carried over.
+pub async fn pinch_session(
+    client: &AiClient,
+    db_path: &Path,
+    session_id: &str,
+    working_dir: &Path,
+    conversation: &[ModelMessage],
+    next_prompt: Vec<Content>,
+) -> Result<PinchOutcome> {
+    // Gather everything from the database up front; it can't cross an await
+    let (ranked_files, parent_title, model, target_branch) = {
+        let db = Database::new(db_path)?;
+        let ranked_files = FileActivityTracker::new(&db, session_id.to_string())
+            .get_ranked_files(RANKED_FILE_LIMIT)
+            .unwrap_or_default();
+        let session = SessionManager::new(db)
+            .get_session(session_id)?
+            .ok_or_else(|| anyhow!("Session {} not found", session_id))?;
+        (
+            ranked_files,
+            session.title,
+            session.model,
+            session.target_branch,
+        )
+    };
+    let active_plan = PlanManager::new(db_path.to_path_buf())
+        .ok()
+        .and_then(|pm| pm.get_plan(session_id).ok().flatten());
+    let file_contents = read_key_file_contents(working_dir, &ranked_files, SUMMARY_FILE_CONTENTS);
+    let project_context = read_project_instructions(working_dir).map(|(_, content)| content);
+
+    let summary = generate_summary(
+        client,
+        conversation,
+        None,
+        &ranked_files,
+        &file_contents,
+        project_context.as_deref(),
+        None,
+    )
+    .await?;
+    let summary_text = summary.work_summary.clone();
+
+    let pinch_ctx = PinchContext::from_input(PinchContextInput {
+        source_session_id: session_id.to_string(),
+        source_session_title: parent_title.clone(),
+        summary,
+        ranked_files,
+        preservation_hints: None,
+        direction: None,
+        project_context,
+        key_file_contents: file_contents
+            .into_iter()
+            .take(CARRIED_FILE_CONTENTS)
+            .collect(),
+        active_plan: active_plan.as_ref().map(|p| p.to_markdown()),
+    });
+
+    let title = continuation_title(&parent_title);
+    let session_manager = SessionManager::new(Database::new(db_path)?);
+    let new_id = session_manager.create_linked_session(
+        &title,
+        session_id,
+        &pinch_ctx,
+        Some(model.as_deref().unwrap_or(&client.config().model)),
+        Some(&working_dir.to_string_lossy()),
+        target_branch.as_deref(),
+    )?;
+
+    let system_content = vec![Content::Text {
+        text: pinch_ctx.to_system_message(),
+    }];
+    session_manager.save_message(&new_id, "system", &serde_json::to_string(&system_content)?)?;
+    session_manager.save_message(&new_id, "user", &serde_json::to_string(&next_prompt)?)?;
+
+    if let Some(plan) = &active_plan {
+        match PlanManager::new(db_path.to_path_buf()) {
+            Ok(pm) => {
+                if let Err(e) = pm.save_plan_for_session(&new_id, plan) {
+                    tracing::warn!("Auto-pinch: failed to carry over plan: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Auto-pinch: failed to open plan manager: {}", e),
+        }
+    }
+
+    tracing::info!(
+        from = %session_id,
+        to = %new_id,
+        "Auto-pinch: continued in linked session"
+    );
+
+    Ok(PinchOutcome {
+        session_id: new_id,
+        title,
+        source_title: parent_title,
+        summary: summary_text,
+        conversation: vec![
+            ModelMessage {
+                role: Role::System,
+                content: system_content,
+            },
+            ModelMessage {
+                role: Role::User,
+                content: next_prompt,
+            },
+        ],
+    })
+}
+
+/// Placeholder title for a continuation session
+pub fn continuation_title(parent_title: &str) -> String {
+    format!(
+        "{} (cont.)",
+        parent_title.chars().take(45).collect::<String>()
+    )
+}
+
+/// Read the top `limit` ranked files, resolving relative paths against `working_dir`
+pub fn read_key_file_contents(
+    working_dir: &Path,
+    ranked_files: &[RankedFile],
+    limit: usize,
+) -> Vec<(String, String)> {
+    ranked_files
+        .iter()
+        .take(limit)
+        .filter_map(|file| {
+            let path = working_dir.join(&file.path);
+            std::fs::read_to_string(&path)
+                .ok()
+                .map(|content| (file.path.clone(), content))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_threshold() {
+        let policy = AutoPinchPolicy::default();
+        assert!(!policy.is_near_limit(79_999, 100_000));
+        assert!(policy.is_near_limit(80_000, 100_000));
+        assert!(!policy.is_near_limit(80_000, 0));
+        assert!(policy.pinches());
+
+        let off = AutoPinchPolicy {
+            mode: AutoPinchMode::Off,
+            ..Default::default()
+        };
+        assert!(!off.is_near_limit(100_000, 100_000));
+    }
+
+    #[test]
+    fn test_policy_deserializes_partial() {
+        let policy: AutoPinchPolicy = serde_json::from_str(r#"{"mode":"notify"}"#).unwrap();
+        assert_eq!(policy.mode, AutoPinchMode::Notify);
+        assert_eq!(policy.threshold, DEFAULT_AUTO_PINCH_THRESHOLD);
+        assert_eq!(policy.strategy, PinchStrategy::LinkedSession);
+        assert!(!policy.pinches());
+
+        let policy: AutoPinchPolicy = serde_json::from_str(r#"{"strategy":"compact"}"#).unwrap();
+        assert_eq!(policy.strategy, PinchStrategy::Compact);
+        assert!(policy.pinches());
+    }
+
+    #[test]
+    fn test_policy_validates_threshold() {
+        for threshold in [0.5, 1.0, DEFAULT_AUTO_PINCH_THRESHOLD] {
+            let policy = AutoPinchPolicy {
+                threshold,
+                ..Default::default()
+            };
+            assert!(policy.validate().is_ok(), "{} should be valid", threshold);
+        }
+        for threshold in [0.0, -0.5, 1.5, f32::NAN] {
+            let policy = AutoPinchPolicy {
+                threshold,
+                ..Default::default()
+            };
+            assert!(
+                policy.validate().is_err(),
+                "{} should be invalid",
+                threshold
+            );
+        }
+    }
+
+    #[test]
+    fn test_continuation_title() {
+        assert_eq!(continuation_title("Fix parser"), "Fix parser (cont.)");
+        assert_eq!(continuation_title(&"é".repeat(60)).chars().count(), 45 + 8);
+    }
+}
@@ -0,0 +1,288 @@
+//! Git worktree isolation for builder swarms
+//!
+//! Each builder gets its own linked worktree and branch, started from a
+//! snapshot of the working tree (uncommitted and untracked files included),
+//! so builders never see each other's half-finished edits. Once the swarm is
+//! done, every builder's changes are applied back to the working tree in
+//! order; a builder whose patch no longer applies is left on its branch for a
+//! manual merge.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use tracing::{info, warn};
+
+use crate::git::{self, GitFileDiff};
+use crate::tools::FileCheckpointer;
+
+/// Worktrees for one build, all started from the same snapshot
+pub struct BuildWorktrees {
+    repo_root: PathBuf,
+    /// Working directory relative to the repository root
+    relative_dir: PathBuf,
+    /// Snapshot commit every builder starts from
+    base: String,
+    /// Directory holding this build's worktrees
+    root_dir: PathBuf,
+    build_id: String,
+}
+
+/// A single builder's worktree
+#[derive(Debug, Clone)]
+pub struct BuilderWorktree {
+    pub task_id: String,
+    pub branch: String,
+    /// Worktree root
+    pub root: PathBuf,
+    /// The build's working directory inside the worktree
+    pub working_dir: PathBuf,
+}
+
+/// What happened to a builder's changes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeStatus {
+    /// Applied to the working tree; the branch was deleted
+    Applied,
+    /// The builder changed nothing
+    NoChanges,
+    /// The patch no longer applies; the branch was kept
+    Conflict(String),
+    /// Not applied for another reason; the branch was kept if it has commits
+    Skipped(String),
+}
+
+/// Result of merging one builder back
+#[derive(Debug, Clone)]
+pub struct BuilderMerge {
+    pub task_id: String,
+    pub branch: String,
+    pub status: MergeStatus,
+    pub files: Vec<GitFileDiff>,
+}
+
+impl BuilderMerge {
+    /// Whether the builder's branch was kept for a manual merge
+    pub fn kept_branch(&self) -> bool {
+        matches!(
+            self.status,
+            MergeStatus::Conflict(_) | MergeStatus::Skipped(_)
+        ) && !self.files.is_empty()
+    }
+
+    /// One-line diff summary, e.g. `applied, 2 files +30 -4 (src/a.rs +28 -4, ...)`
+    pub fn summary_line(&self) -> String {
+        let status = match &self.status {
+            MergeStatus::Applied => "applied".to_string(),
+            MergeStatus::NoChanges => return "no changes".to_string(),
+            MergeStatus::Conflict(_) => format!("conflict, kept on branch {}", self.branch),
+            MergeStatus::Skipped(reason) if self.files.is_empty() => {
+                return format!("not applied: {}", reason)
+            }
+            MergeStatus::Skipped(reason) => {
+                format!("not applied ({}), kept on branch {}", reason, self.branch)
+            }
+        };
+        let added: usize = self.files.iter().filter_map(|f| f.additions).sum();
+        let removed: usize = self.files.iter().filter_map(|f| f.deletions).sum();
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| match (f.additions, f.deletions) {
+                (Some(a), Some(d)) => format!("{} +{} -{}", f.path, a, d),
+                _ => format!("{} (binary)", f.path),
+            })
+            .collect();
+        format!(
+            "{}, {} file{} +{} -{} ({})",
+            status,
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+            added,
+            removed,
+            files.join(", ")
+        )
+    }
+}
+
+impl BuildWorktrees {
+    /// Snapshot the working tree for a new build
+    ///
+    /// Returns `None` when `working_dir` is not inside a git repository with
+    /// at least one commit; callers fall back to the shared tree.
+    pub fn create(working_dir: &Path) -> Result<Option<Self>> {
+        let Some(repo_root) = git::resolve_repo_root(working_dir)? else {
+            return Ok(None);
+        };
+        let base = match git::snapshot_working_tree(&repo_root) {
+            Ok(base) => base,
+            Err(e) => {
+                warn!(
+                    "Build isolation: cannot snapshot {}: {}",
+                    repo_root.display(),
+                    e
+                );
+                return Ok(None);
+            }
+        };
+        let relative_dir = relative_to(&repo_root, working_dir);
+        let build_id = uuid::Uuid::new_v4().simple().to_string()[..8].to_string();
+        let root_dir = std::env::temp_dir().join(format!("krusty-build-{}", build_id));
+        std::fs::create_dir_all(&root_dir)?;
+
+        info!(
+            repo = %repo_root.display(),
+            base = %base,
+            build_id = %build_id,
+            "Build isolation: snapshot taken"
+        );
+
+        Ok(Some(Self {
+            repo_root,
+            relative_dir,
+            base,
+            root_dir,
+            build_id,
+        }))
+    }
+
+    /// Create a builder's worktree and branch
+    pub fn add(&self, task_id: &str) -> Result<BuilderWorktree> {
+        let branch = format!("krusty/build-{}/{}", self.build_id, task_id);
+        let root = self.root_dir.join(task_id);
+        git::add_worktree(&self.repo_root, &root, &branch, &self.base)?;
+        Ok(BuilderWorktree {
+            task_id: task_id.to_string(),
+            working_dir: root.join(&self.relative_dir),
+            branch,
+            root,
+        })
+    }
+
+    /// Commit a builder's work and apply it to the working tree
+    ///
+    /// Files are snapshotted with `checkpointer` before they are written, so
+    /// the merge can be undone with the turn. Unsuccessful builders are not
+    /// applied but keep their branch.
+    pub async fn merge(
+        &self,
+        worktree: &BuilderWorktree,
+        succeeded: bool,
+        checkpointer: Option<&FileCheckpointer>,
+    ) -> BuilderMerge {
+        let mut merge = BuilderMerge {
+            task_id: worktree.task_id.clone(),
+            branch: worktree.branch.clone(),
+            status: MergeStatus::NoChanges,
+            files: Vec::new(),
+        };
+
+        let message = format!("Builder {}", worktree.task_id);
+        let prepared = git::commit_all(&worktree.root, &message).and_then(|changed| {
+            if !changed {
+                return Ok(None);
+            }
+            let files = git::diff_files(&self.repo_root, &self.base, &worktree.branch)?;
+            let patch = git::diff_patch(&self.repo_root, &self.base, &worktree.branch)?;
+            Ok(Some((files, patch)))
+        });
+
+        let patch = match prepared {
+            Ok(None) => return merge,
+            Ok(Some((files, patch))) => {
+                merge.files = files;
+                patch
+            }
+            Err(e) => {
+                merge.status = MergeStatus::Skipped(e.to_string());
+                return merge;
+            }
+        };
+
+        if !succeeded {
+            merge.status = MergeStatus::Skipped("builder failed".to_string());
+            return merge;
+        }
+        if let Err(e) = git::apply_patch(&self.repo_root, &patch, true) {
+            merge.status = MergeStatus::Conflict(e.to_string());
+            return merge;
+        }
+
+        if let Some(checkpointer) = checkpointer {
+            for file in &merge.files {
+                checkpointer
+                    .snapshot(&self.repo_root.join(&file.path))
+                    .await;
+            }
+        }
+        merge.status = match git::apply_patch(&self.repo_root, &patch, false) {
+            Ok(()) => MergeStatus::Applied,
+            Err(e) => MergeStatus::Skipped(e.to_string()),
+        };
+        merge
+    }
+
+    /// Remove the worktrees, and the branches of everything not kept
+    pub fn cleanup(&self, worktrees: &[BuilderWorktree], merges: &[BuilderMerge]) {
+        for worktree in worktrees {
+            if let Err(e) = git::remove_worktree(&self.repo_root, &worktree.root) {
+                warn!("Build isolation: failed to remove worktree: {}", e);
+            }
+            let kept = merges
+                .iter()
+                .any(|m| m.task_id == worktree.task_id && m.kept_branch());
+            if !kept {
+                if let Err(e) = git::delete_branch(&self.repo_root, &worktree.branch) {
+                    warn!("Build isolation: failed to delete branch: {}", e);
+                }
+            }
+        }
+        let _ = std::fs::remove_dir_all(&self.root_dir);
+    }
+}
+
+/// `path` relative to `root`, comparing canonical paths; empty if outside it
+fn relative_to(root: &Path, path: &Path) -> PathBuf {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let path = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    path.strip_prefix(&root)
+        .map(Path::to_path_buf)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, additions: usize, deletions: usize) -> GitFileDiff {
+        GitFileDiff {
+            path: path.to_string(),
+            additions: Some(additions),
+            deletions: Some(deletions),
+        }
+    }
+
+    #[test]
+    fn test_summary_line() {
+        let mut merge = BuilderMerge {
+            task_id: "builder-0".to_string(),
+            branch: "krusty/build-ab/builder-0".to_string(),
+            status: MergeStatus::Applied,
+            files: vec![file("src/a.rs", 28, 4), file("src/b.rs", 2, 0)],
+        };
+        assert_eq!(
+            merge.summary_line(),
+            "applied, 2 files +30 -4 (src/a.rs +28 -4, src/b.rs +2 -0)"
+        );
+        assert!(!merge.kept_branch());
+
+        merge.status = MergeStatus::Conflict("patch does not apply".to_string());
+        assert!(merge
+            .summary_line()
+            .starts_with("conflict, kept on branch krusty/build-ab/builder-0"));
+        assert!(merge.kept_branch());
+
+        merge.files.clear();
+        merge.status = MergeStatus::NoChanges;
+        assert_eq!(merge.summary_line(), "no changes");
+    }
+}
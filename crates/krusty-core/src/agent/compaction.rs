@@ -0,0 +1,415 @@
+//! In-place conversation compaction
+//!
+//! The alternative to pinching into a linked session: the session keeps its
+//! id and every stored message, but what is sent to the model replaces the
+//! oldest turns with a summary and elides large tool results that have gone
+//! stale. The view only changes when a new compaction is recorded, so the
+//! prompt-cache prefix stays stable between compactions.
+
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+use super::auto_pinch::{
+    read_key_file_contents, CONTINUE_PROMPT, RANKED_FILE_LIMIT, SUMMARY_FILE_CONTENTS,
+};
+use super::context::read_project_instructions;
+use super::summarizer::{generate_summary, SummarizationResult};
+use crate::ai::client::AiClient;
+use crate::ai::types::{Content, ModelMessage, Role};
+use crate::storage::{Compaction, CompactionStore, Database, FileActivityTracker};
+
+/// Prefix of the summary system message; it is cached with the project block
+pub const SUMMARY_PREFIX: &str = "[CONVERSATION SUMMARY";
+
+/// Most recent messages that are never summarized
+const KEEP_RECENT_MESSAGES: usize = 20;
+/// Most recent messages whose tool output is never elided
+const KEEP_RECENT_TOOL_OUTPUT: usize = 6;
+/// Tool results smaller than this are left alone
+const ELIDE_MIN_BYTES: usize = 2_000;
+
+/// Result of compacting a session
+#[derive(Debug, Clone)]
+pub struct CompactionOutcome {
+    pub compaction: Compaction,
+    /// Messages now represented by the summary
+    pub summarized_messages: usize,
+    /// Tool results replaced by a placeholder
+    pub elided_results: usize,
+}
+
+/// The conversation as sent to the model under `compaction`
+///
+/// Leading system messages (such as pinch context) are kept, the summarized
+/// range becomes a single system message, and large tool results before
+/// `pruned_through` are replaced by a short placeholder. A compaction that
+/// no longer fits the conversation is ignored.
+pub fn compacted_view<'a>(
+    conversation: &'a [ModelMessage],
+    compaction: Option<&Compaction>,
+) -> Cow<'a, [ModelMessage]> {
+    let Some(compaction) = compaction.filter(|c| applies_to(c, conversation)) else {
+        return Cow::Borrowed(conversation);
+    };
+    let summarized_through = compaction.summarized_through;
+
+    let mut view: Vec<ModelMessage> = conversation[..summarized_through]
+        .iter()
+        .filter(|m| m.role == Role::System)
+        .cloned()
+        .collect();
+    if summarized_through > 0 {
+        view.push(ModelMessage {
+            role: Role::System,
+            content: vec![Content::Text {
+                text: summary_message(&compaction.summary),
+            }],
+        });
+        // The retained turns must still open with a user message
+        if conversation
+            .get(summarized_through)
+            .is_some_and(|m| m.role == Role::Assistant)
+        {
+            view.push(ModelMessage {
+                role: Role::User,
+                content: vec![Content::Text {
+                    text: CONTINUE_PROMPT.to_string(),
+                }],
+            });
+        }
+    }
+
+    let tool_names = tool_names(conversation);
+    for (index, message) in conversation.iter().enumerate().skip(summarized_through) {
+        if index < compaction.pruned_through {
+            view.push(elide_stale_results(message, &tool_names));
+        } else {
+            view.push(message.clone());
+        }
+    }
+    Cow::Owned(view)
+}
+
+/// Summarize old turns and prune stale tool output, recording the result
+///
+/// Builds on the session's previous compaction: only messages after its
+/// summary are summarized, with the old summary folded in. Errors when there
+/// is nothing new to compact.
+pub async fn compact_session(
+    client: &AiClient,
+    db_path: &Path,
+    session_id: &str,
+    working_dir: &Path,
+    conversation: &[ModelMessage],
+    tokens_before: usize,
+) -> Result<CompactionOutcome> {
+    // Gather everything from the database up front; it can't cross an await
+    let (previous, ranked_files) = {
+        let db = Database::new(db_path)?;
+        let previous = CompactionStore::new(&db)
+            .latest(session_id)?
+            .filter(|c| applies_to(c, conversation));
+        let ranked_files = FileActivityTracker::new(&db, session_id.to_string())
+            .get_ranked_files(RANKED_FILE_LIMIT)
+            .unwrap_or_default();
+        (previous, ranked_files)
+    };
+    let (previous_through, previous_pruned) = previous
+        .as_ref()
+        .map_or((0, 0), |c| (c.summarized_through, c.pruned_through));
+
+    let boundary = compaction_boundary(conversation, KEEP_RECENT_MESSAGES);
+    let pruned_through = conversation
+        .len()
+        .saturating_sub(KEEP_RECENT_TOOL_OUTPUT)
+        .max(boundary);
+    if boundary <= previous_through && pruned_through <= previous_pruned {
+        bail!("Nothing to compact yet");
+    }
+
+    let (summary, summarized_through) = match &previous {
+        // Only the pruning moved on; keep the summary as is
+        Some(previous) if boundary <= previous_through => {
+            (previous.summary.clone(), previous.summarized_through)
+        }
+        _ => {
+            let hints = previous.as_ref().map(|c| {
+                format!(
+                    "The conversation before these messages was already summarized as \
+                     follows. Fold it into your summary.\n\n{}",
+                    c.summary
+                )
+            });
+            let file_contents =
+                read_key_file_contents(working_dir, &ranked_files, SUMMARY_FILE_CONTENTS);
+            let project_context =
+                read_project_instructions(working_dir).map(|(_, content)| content);
+            let result = generate_summary(
+                client,
+                &conversation[previous_through..boundary],
+                hints.as_deref(),
+                &ranked_files,
+                &file_contents,
+                project_context.as_deref(),
+                None,
+            )
+            .await?;
+            (format_summary(&result), boundary)
+        }
+    };
+
+    let db = Database::new(db_path)?;
+    let compaction = CompactionStore::new(&db).record(
+        session_id,
+        &summary,
+        summarized_through,
+        pruned_through,
+        tokens_before,
+    )?;
+    let elided_results = count_elided(conversation, &compaction);
+
+    tracing::info!(
+        session_id = %session_id,
+        summarized_through,
+        pruned_through,
+        elided_results,
+        "Compacted conversation in place"
+    );
+
+    Ok(CompactionOutcome {
+        compaction,
+        summarized_messages: summarized_through,
+        elided_results,
+    })
+}
+
+/// Latest compaction index at which the conversation can be cut
+///
+/// The cut keeps at least `keep_recent` messages and never lands on a
+/// message of tool results, so every kept result keeps its tool call.
+pub fn compaction_boundary(conversation: &[ModelMessage], keep_recent: usize) -> usize {
+    let max = conversation.len().saturating_sub(keep_recent.max(1));
+    (1..=max)
+        .rev()
+        .find(|&i| match conversation[i].role {
+            Role::Assistant => true,
+            Role::User => !has_tool_results(&conversation[i]),
+            _ => false,
+        })
+        .unwrap_or(0)
+}
+
+/// Tool results the view of `compaction` replaces with a placeholder
+pub fn count_elided(conversation: &[ModelMessage], compaction: &Compaction) -> usize {
+    if !applies_to(compaction, conversation) {
+        return 0;
+    }
+    conversation
+        [compaction.summarized_through.min(compaction.pruned_through)..compaction.pruned_through]
+        .iter()
+        .flat_map(|m| &m.content)
+        .filter(|c| matches!(c, Content::ToolResult { output, .. } if is_large(output)))
+        .count()
+}
+
+fn applies_to(compaction: &Compaction, conversation: &[ModelMessage]) -> bool {
+    compaction.summarized_through <= conversation.len()
+        && compaction.pruned_through <= conversation.len()
+}
+
+fn has_tool_results(message: &ModelMessage) -> bool {
+    message
+        .content
+        .iter()
+        .any(|c| matches!(c, Content::ToolResult { .. }))
+}
+
+fn summary_message(summary: &str) -> String {
+    format!(
+        "{}]\n\nEarlier messages in this session were compacted into the summary \
+         below. Treat it as established context; the most recent messages follow \
+         verbatim.\n\n{}",
+        SUMMARY_PREFIX, summary
+    )
+}
+
+fn format_summary(result: &SummarizationResult) -> String {
+    let mut summary = result.work_summary.clone();
+    for (heading, items) in [
+        ("Key decisions", &result.key_decisions),
+        ("Pending tasks", &result.pending_tasks),
+        ("Important files", &result.important_files),
+    ] {
+        if !items.is_empty() {
+            summary.push_str(&format!("\n\n## {}\n", heading));
+            for item in items {
+                summary.push_str(&format!("- {}\n", item));
+            }
+        }
+    }
+    summary.trim_end().to_string()
+}
+
+/// Tool names by tool_use id
+fn tool_names(conversation: &[ModelMessage]) -> HashMap<&str, &str> {
+    conversation
+        .iter()
+        .flat_map(|m| &m.content)
+        .filter_map(|c| match c {
+            Content::ToolUse { id, name, .. } => Some((id.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect()
+}
+
+fn output_len(output: &Value) -> usize {
+    match output {
+        Value::String(text) => text.len(),
+        other => other.to_string().len(),
+    }
+}
+
+fn is_large(output: &Value) -> bool {
+    output_len(output) >= ELIDE_MIN_BYTES
+}
+
+fn elide_stale_results(message: &ModelMessage, tool_names: &HashMap<&str, &str>) -> ModelMessage {
+    let content = message
+        .content
+        .iter()
+        .map(|block| match block {
+            Content::ToolResult {
+                tool_use_id,
+                output,
+                is_error,
+            } if is_large(output) => Content::ToolResult {
+                tool_use_id: tool_use_id.clone(),
+                output: Value::String(format!(
+                    "[Elided {} bytes of stale {} output. Run the tool again if you need it.]",
+                    output_len(output),
+                    tool_names
+                        .get(tool_use_id.as_str())
+                        .copied()
+                        .unwrap_or("tool")
+                )),
+                is_error: *is_error,
+            },
+            other => other.clone(),
+        })
+        .collect();
+    ModelMessage {
+        role: message.role.clone(),
+        content,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(role: Role, text: &str) -> ModelMessage {
+        ModelMessage {
+            role,
+            content: vec![Content::Text {
+                text: text.to_string(),
+            }],
+        }
+    }
+
+    fn tool_call(id: &str) -> ModelMessage {
+        ModelMessage {
+            role: Role::Assistant,
+            content: vec![Content::ToolUse {
+                id: id.to_string(),
+                name: "read".to_string(),
+                input: Value::Null,
+            }],
+        }
+    }
+
+    fn tool_result(id: &str, bytes: usize) -> ModelMessage {
+        ModelMessage {
+            role: Role::User,
+            content: vec![Content::ToolResult {
+                tool_use_id: id.to_string(),
+                output: Value::String("x".repeat(bytes)),
+                is_error: None,
+            }],
+        }
+    }
+
+    fn compaction(summarized_through: usize, pruned_through: usize) -> Compaction {
+        Compaction {
+            id: 1,
+            session_id: "s".to_string(),
+            summary: "did things".to_string(),
+            summarized_through,
+            pruned_through,
+            tokens_before: 0,
+            created_at: String::new(),
+        }
+    }
+
+    fn conversation() -> Vec<ModelMessage> {
+        vec![
+            text(Role::User, "start"),
+            tool_call("a"),
+            tool_result("a", 5_000),
+            tool_call("b"),
+            tool_result("b", 5_000),
+            tool_call("c"),
+            tool_result("c", 10),
+            text(Role::Assistant, "done"),
+        ]
+    }
+
+    #[test]
+    fn test_boundary_skips_tool_results() {
+        let conversation = conversation();
+        assert_eq!(compaction_boundary(&conversation, 4), 3);
+        assert_eq!(compaction_boundary(&conversation, 5), 3);
+        assert_eq!(compaction_boundary(&conversation, 6), 1);
+        assert_eq!(compaction_boundary(&conversation, 8), 0);
+    }
+
+    #[test]
+    fn test_view_summarizes_and_elides() {
+        let conversation = conversation();
+        let view = compacted_view(&conversation, Some(&compaction(3, 7)));
+
+        assert_eq!(view[0].role, Role::System);
+        assert!(
+            matches!(&view[0].content[0], Content::Text { text } if text.starts_with(SUMMARY_PREFIX))
+        );
+        // Cut lands on an assistant turn, so a user prompt is inserted
+        assert_eq!(view[1].role, Role::User);
+        assert_eq!(view.len(), 2 + conversation.len() - 3);
+
+        let outputs: Vec<&Value> = view
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|c| match c {
+                Content::ToolResult { output, .. } => Some(output),
+                _ => None,
+            })
+            .collect();
+        assert!(outputs[0]
+            .as_str()
+            .unwrap()
+            .starts_with("[Elided 5000 bytes of stale read"));
+        assert_eq!(output_len(outputs[1]), 10);
+        assert_eq!(count_elided(&conversation, &compaction(3, 7)), 1);
+    }
+
+    #[test]
+    fn test_stale_compaction_is_ignored() {
+        let conversation = conversation();
+        let view = compacted_view(&conversation, Some(&compaction(3, 20)));
+        assert!(matches!(view, Cow::Borrowed(_)));
+        assert_eq!(count_elided(&conversation, &compaction(3, 20)), 0);
+    }
+}
@@ -16,6 +16,8 @@ pub mod token_limits {
 pub mod concurrency {
     /// Maximum parallel tool executions
     pub const MAX_PARALLEL_TOOLS: usize = 100;
+    /// Maximum read-only tool calls the executor runs at once
+    pub const MAX_PARALLEL_READ_TOOLS: usize = 8;
 }
 
 /// Timeout configurations
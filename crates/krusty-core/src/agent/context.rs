@@ -1,18 +1,20 @@
 //! Context injection for the agentic loop.
 //!
-//! Builds plan, skills, and project context strings that get injected as
-//! system messages at the head of the conversation before each AI call.
-//! This ensures the AI is always aware of the active plan, available skills,
-//! and project-specific instructions.
+//! Builds project, memory, plan, skills, and agents context strings that get
+//! injected as system messages at the head of the conversation before each AI
+//! call. This ensures the AI is always aware of project-specific instructions,
+//! what it remembered from earlier sessions, the active plan, and available
+//! skills and agent types.
 
 use std::path::Path;
 
 use tokio::sync::RwLock;
 
+use crate::agent::subagent::load_agent_definitions;
 use crate::ai::types::{Content, ModelMessage, Role};
 use crate::plan::PlanManager;
 use crate::skills::SkillsManager;
-use crate::storage::WorkMode;
+use crate::storage::{Database, MemoryStore, WorkMode};
 
 /// Instruction files to search for in the working directory (priority order).
 const PROJECT_FILES: &[&str] = &[
@@ -30,23 +32,32 @@ const PROJECT_FILES: &[&str] = &[
     "gemini.md",
 ];
 
+/// Most recent memories injected into the conversation
+const MAX_INJECTED_MEMORIES: usize = 50;
+
+/// Character budget for injected memories
+const MAX_MEMORY_CONTEXT_CHARS: usize = 8000;
+
 /// Build a conversation clone with context system messages prepended.
 ///
-/// Injects plan, skills, and project context in the same order as the TUI:
-/// project → plan → skills → original conversation.
+/// Injects context in a fixed order: project → memory → plan → skills →
+/// agents → original conversation.
 pub fn inject_context(
     conversation: &[ModelMessage],
     db_path: &Path,
     session_id: &str,
     working_dir: &Path,
+    user_id: Option<&str>,
     work_mode: WorkMode,
     skills_manager: &RwLock<SkillsManager>,
 ) -> Vec<ModelMessage> {
+    let memory_ctx = build_memory_context(db_path, working_dir, user_id);
     let plan_ctx = build_plan_context(db_path, session_id, work_mode);
     let skills_ctx = build_skills_context(skills_manager);
+    let agents_ctx = build_agents_context(working_dir);
     let project_ctx = build_project_context(working_dir);
 
-    let mut injected = Vec::with_capacity(conversation.len() + 3);
+    let mut injected = Vec::with_capacity(conversation.len() + 5);
 
     if !project_ctx.is_empty() {
         injected.push(ModelMessage {
@@ -54,6 +65,12 @@ pub fn inject_context(
             content: vec![Content::Text { text: project_ctx }],
         });
     }
+    if !memory_ctx.is_empty() {
+        injected.push(ModelMessage {
+            role: Role::System,
+            content: vec![Content::Text { text: memory_ctx }],
+        });
+    }
     if !plan_ctx.is_empty() {
         injected.push(ModelMessage {
             role: Role::System,
@@ -66,11 +83,54 @@ pub fn inject_context(
             content: vec![Content::Text { text: skills_ctx }],
         });
     }
+    if !agents_ctx.is_empty() {
+        injected.push(ModelMessage {
+            role: Role::System,
+            content: vec![Content::Text { text: agents_ctx }],
+        });
+    }
 
     injected.extend_from_slice(conversation);
     injected
 }
 
+/// Build memory context from the project's saved memories.
+///
+/// Lists the most recently updated memories up to a size budget; older ones
+/// stay reachable through the `recall` tool.
+pub fn build_memory_context(db_path: &Path, working_dir: &Path, user_id: Option<&str>) -> String {
+    let working_dir = working_dir.to_string_lossy();
+    let memories = Database::new(db_path)
+        .and_then(|db| MemoryStore::new(&db).list(Some(&working_dir), user_id));
+    let memories = match memories {
+        Ok(m) if !m.is_empty() => m,
+        _ => return String::new(),
+    };
+
+    let mut context = String::from(
+        "[PROJECT MEMORY]\n\n\
+         Notes saved in earlier sessions in this project. Use `remember` to save \
+         new ones (with `replaces` to revise one), `recall` to search them and \
+         `forget` to delete ones that no longer hold.\n\n",
+    );
+    let mut shown = 0;
+    for memory in memories.iter().take(MAX_INJECTED_MEMORIES) {
+        let line = format!("- [{}] {}\n", memory.id, memory.content);
+        if shown > 0 && context.len() + line.len() > MAX_MEMORY_CONTEXT_CHARS {
+            break;
+        }
+        context.push_str(&line);
+        shown += 1;
+    }
+    if memories.len() > shown {
+        context.push_str(&format!(
+            "\n({} older memories not shown; use `recall` to search them)\n",
+            memories.len() - shown
+        ));
+    }
+    context
+}
+
 /// Build plan context from the active plan for this session.
 pub fn build_plan_context(db_path: &Path, session_id: &str, work_mode: WorkMode) -> String {
     let plan_manager = match PlanManager::new(db_path.to_path_buf()) {
@@ -188,19 +248,47 @@ pub fn build_skills_context(skills_manager: &RwLock<SkillsManager>) -> String {
     context
 }
 
+/// Build agents context listing user-defined agent types.
+pub fn build_agents_context(working_dir: &Path) -> String {
+    let definitions = load_agent_definitions(working_dir);
+    if definitions.is_empty() {
+        return String::new();
+    }
+
+    let mut context = String::from(
+        "[AVAILABLE AGENTS]\n\nUse the `task` tool to delegate work to these agents.\n\n",
+    );
+    for definition in definitions {
+        context.push_str(&format!(
+            "- **{}**: {} (tools: {})\n",
+            definition.name,
+            definition.description,
+            definition.tools.join(", ")
+        ));
+    }
+    context.push_str("\nTo use: `task(agent: \"name\", prompt: \"...\")`\n");
+    context
+}
+
 /// Build project context from instruction files in the working directory.
 ///
 /// Searches for well-known instruction files (KRAB.md, CLAUDE.md, etc.)
 /// and returns the first one found wrapped in marker tags.
 pub fn build_project_context(working_dir: &Path) -> String {
-    for filename in PROJECT_FILES {
-        let path = working_dir.join(filename);
-        if let Ok(content) = std::fs::read_to_string(&path) {
-            return format!(
-                "[PROJECT INSTRUCTIONS - {}]\n\n{}\n\n[END PROJECT INSTRUCTIONS]",
-                filename, content
-            );
-        }
+    match read_project_instructions(working_dir) {
+        Some((filename, content)) => format!(
+            "[PROJECT INSTRUCTIONS - {}]\n\n{}\n\n[END PROJECT INSTRUCTIONS]",
+            filename, content
+        ),
+        None => String::new(),
     }
-    String::new()
+}
+
+/// First instruction file found in the working directory, with its name
+pub fn read_project_instructions(working_dir: &Path) -> Option<(&'static str, String)> {
+    PROJECT_FILES.iter().find_map(|filename| {
+        std::fs::read_to_string(working_dir.join(filename))
+            .ok()
+            .map(|content| (*filename, content))
+    })
 }
@@ -1,9 +1,10 @@
 //! Tool execution for the agentic loop.
 //!
 //! Handles:
-//! - Permission-based approval workflow (supervised mode)
+//! - Permission rules and the approval workflow (supervised mode / ask rules)
 //! - Special tool dispatch (mode switch, plan tasks)
-//! - Regular tool execution via `ToolRegistry::execute()`
+//! - Regular tool execution via `ToolRegistry::execute()`, with consecutive
+//!   read-only calls run concurrently
 //! - Output truncation
 //! - Tool output streaming via `ToolOutputChunk` → `LoopEvent::ToolOutputDelta`
 
@@ -11,16 +12,21 @@ use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
+use futures::stream::{self, StreamExt};
 use tokio::sync::mpsc;
 
 use crate::ai::types::{AiToolCall, Content};
+use crate::lsp::LspManager;
 use crate::process::ProcessRegistry;
-use crate::storage::WorkMode;
+use crate::storage::{Database, WorkMode};
 use crate::tools::registry::{
-    tool_category, PermissionMode, ToolCategory, ToolContext, ToolRegistry,
+    tool_category, PermissionMode, ToolCategory, ToolContext, ToolRegistry, ToolResult,
 };
+use crate::tools::{EditorClient, FileCheckpointer};
 
+use super::constants::concurrency::MAX_PARALLEL_READ_TOOLS;
 use super::loop_events::{LoopEvent, LoopInput};
+use super::permissions::{self, PermissionDecision, PermissionPolicy, PermissionRule};
 use super::plan_handler;
 
 const MAX_TOOL_OUTPUT_CHARS: usize = 30_000;
@@ -41,50 +47,100 @@ pub(crate) async fn execute_tools(
     user_id: Option<&str>,
     permission_mode: PermissionMode,
     current_mode: WorkMode,
+    checkpointer: Option<&FileCheckpointer>,
+    lsp_manager: Option<&Arc<LspManager>>,
+    editor: Option<&Arc<dyn EditorClient>>,
     event_tx: &mpsc::UnboundedSender<LoopEvent>,
     input_rx: &mut mpsc::UnboundedReceiver<LoopInput>,
 ) -> (Vec<Content>, WorkMode) {
-    let mut work_mode = current_mode;
+    let mut env = ToolEnv {
+        tool_registry,
+        working_dir,
+        process_registry,
+        user_id,
+        work_mode: current_mode,
+        checkpointer,
+        lsp_manager,
+        editor,
+        event_tx,
+    };
     let mut results = Vec::new();
+    let mut policy = load_policy(db_path, working_dir, user_id);
+    // Allowed read-only calls waiting to run together
+    let mut read_batch: Vec<&AiToolCall> = Vec::new();
 
     for call in tool_calls {
-        let category = tool_category(&call.name);
+        // ── Permission rules + approval ────────────────────────────
+        let decision = policy.decide(permission_mode, &call.name, &call.arguments, working_dir);
 
-        // ── Supervised approval ────────────────────────────────────
-        if permission_mode == PermissionMode::Supervised && category == ToolCategory::Write {
-            let _ = event_tx.send(LoopEvent::ToolApprovalRequired {
-                id: call.id.clone(),
-                name: call.name.clone(),
-                arguments: call.arguments.clone(),
-            });
+        // Consecutive allowed read-only calls can't interfere with each other
+        if decision == PermissionDecision::Allow
+            && tool_category(&call.name) == ToolCategory::ReadOnly
+        {
+            read_batch.push(call);
+            continue;
+        }
 
-            let approved = wait_for_approval(call, event_tx, input_rx).await;
+        // Anything else runs alone, after the read-only calls before it
+        execute_read_batch(&env, std::mem::take(&mut read_batch), &mut results).await;
 
-            if !approved {
-                let denied = crate::tools::registry::ToolResult::error_with_code(
-                    "permission_denied",
-                    "Tool execution denied by user",
-                );
-                let output = truncate_output(&denied.output);
-                let _ = event_tx.send(LoopEvent::ToolDenied {
-                    id: call.id.clone(),
-                });
-                let _ = event_tx.send(LoopEvent::ToolResult {
+        let denial = match decision {
+            PermissionDecision::Allow => None,
+            PermissionDecision::Deny { rule } => {
+                tracing::info!(tool = %call.name, rule = %rule, "Tool call denied by permission rule");
+                Some(format!(
+                    "Tool execution denied by permission rule: {}",
+                    rule
+                ))
+            }
+            PermissionDecision::Ask => {
+                let _ = event_tx.send(LoopEvent::ToolApprovalRequired {
                     id: call.id.clone(),
-                    output: output.clone(),
-                    is_error: denied.is_error,
-                });
-                results.push(Content::ToolResult {
-                    tool_use_id: call.id.clone(),
-                    output: serde_json::Value::String(output),
-                    is_error: Some(denied.is_error),
+                    name: call.name.clone(),
+                    arguments: call.arguments.clone(),
                 });
-                continue;
+
+                match wait_for_approval(call, event_tx, input_rx).await {
+                    Approval::Denied => Some("Tool execution denied by user".to_string()),
+                    approval => {
+                        if approval == Approval::AlwaysAllow {
+                            let rule = PermissionRule::always_allow(
+                                &call.name,
+                                &call.arguments,
+                                working_dir,
+                            );
+                            persist_rule(db_path, &rule, user_id);
+                            let _ = event_tx
+                                .send(LoopEvent::PermissionRuleAdded { rule: rule.clone() });
+                            policy.push(rule);
+                        }
+                        let _ = event_tx.send(LoopEvent::ToolApproved {
+                            id: call.id.clone(),
+                        });
+                        None
+                    }
+                }
             }
+        };
 
-            let _ = event_tx.send(LoopEvent::ToolApproved {
+        if let Some(reason) = denial {
+            let denied =
+                crate::tools::registry::ToolResult::error_with_code("permission_denied", reason);
+            let output = truncate_output(&denied.output);
+            let _ = event_tx.send(LoopEvent::ToolDenied {
                 id: call.id.clone(),
             });
+            let _ = event_tx.send(LoopEvent::ToolResult {
+                id: call.id.clone(),
+                output: output.clone(),
+                is_error: denied.is_error,
+            });
+            results.push(Content::ToolResult {
+                tool_use_id: call.id.clone(),
+                output: serde_json::Value::String(output),
+                is_error: Some(denied.is_error),
+            });
+            continue;
         }
 
         let _ = event_tx.send(LoopEvent::ToolExecuting {
@@ -94,12 +150,12 @@ pub(crate) async fn execute_tools(
 
         // ── Mode switch tools ──────────────────────────────────────
         if call.name == "set_work_mode" || call.name == "enter_plan_mode" {
-            let switch = plan_handler::handle_mode_switch(call, session_id, db_path, work_mode);
-            work_mode = switch.next_mode;
+            let switch = plan_handler::handle_mode_switch(call, session_id, db_path, env.work_mode);
+            env.work_mode = switch.next_mode;
 
             if let Some(reason) = switch.mode_change_reason {
                 let _ = event_tx.send(LoopEvent::ModeChange {
-                    mode: work_mode.to_string(),
+                    mode: env.work_mode.to_string(),
                     reason: Some(reason),
                 });
             }
@@ -143,83 +199,179 @@ pub(crate) async fn execute_tools(
         }
 
         // ── Regular tool execution ─────────────────────────────────
-        let (output_tx, mut output_rx) =
-            mpsc::unbounded_channel::<crate::tools::registry::ToolOutputChunk>();
-
-        let forwarder_event_tx = event_tx.clone();
-        let forwarder_tool_id = call.id.clone();
-        let forwarder_tool_name = call.name.clone();
-        let forwarder_handle = tokio::spawn(async move {
-            let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
-            heartbeat_interval.tick().await;
-
-            loop {
-                tokio::select! {
-                    chunk = output_rx.recv() => {
-                        match chunk {
-                            Some(chunk) => {
-                                if !chunk.chunk.is_empty() {
-                                    let _ = forwarder_event_tx.send(LoopEvent::ToolOutputDelta {
-                                        id: forwarder_tool_id.clone(),
-                                        delta: chunk.chunk,
-                                    });
-                                }
-                                if chunk.is_complete {
-                                    break;
-                                }
+        let result = execute_regular_tool(&env, call).await;
+        results.push(finish_tool_call(event_tx, call, result));
+    }
+
+    execute_read_batch(&env, read_batch, &mut results).await;
+
+    (results, env.work_mode)
+}
+
+/// What a regular tool call needs from `execute_tools`
+struct ToolEnv<'a> {
+    tool_registry: &'a Arc<ToolRegistry>,
+    working_dir: &'a Path,
+    process_registry: &'a Arc<ProcessRegistry>,
+    user_id: Option<&'a str>,
+    work_mode: WorkMode,
+    checkpointer: Option<&'a FileCheckpointer>,
+    lsp_manager: Option<&'a Arc<LspManager>>,
+    editor: Option<&'a Arc<dyn EditorClient>>,
+    event_tx: &'a mpsc::UnboundedSender<LoopEvent>,
+}
+
+/// Run allowed read-only calls concurrently, appending results in call order
+async fn execute_read_batch(
+    env: &ToolEnv<'_>,
+    batch: Vec<&AiToolCall>,
+    results: &mut Vec<Content>,
+) {
+    if batch.len() > 1 {
+        tracing::debug!(
+            count = batch.len(),
+            "Executing read-only tool calls concurrently"
+        );
+    }
+    // Futures are built up front; `buffered` starts them in order
+    let calls: Vec<_> = batch
+        .into_iter()
+        .map(|call| execute_read_call(env, call))
+        .collect();
+    let mut finished = stream::iter(calls).buffered(MAX_PARALLEL_READ_TOOLS);
+    while let Some((call, result)) = finished.next().await {
+        results.push(finish_tool_call(env.event_tx, call, result));
+    }
+}
+
+/// Start a read-only call, announcing it when it begins
+async fn execute_read_call<'a>(
+    env: &ToolEnv<'_>,
+    call: &'a AiToolCall,
+) -> (&'a AiToolCall, ToolResult) {
+    let _ = env.event_tx.send(LoopEvent::ToolExecuting {
+        id: call.id.clone(),
+        name: call.name.clone(),
+    });
+    (call, execute_regular_tool(env, call).await)
+}
+
+/// Execute a tool through the registry, streaming its output as
+/// `ToolOutputDelta` events
+async fn execute_regular_tool(env: &ToolEnv<'_>, call: &AiToolCall) -> ToolResult {
+    let (output_tx, mut output_rx) =
+        mpsc::unbounded_channel::<crate::tools::registry::ToolOutputChunk>();
+
+    let forwarder_event_tx = env.event_tx.clone();
+    let forwarder_tool_id = call.id.clone();
+    let forwarder_tool_name = call.name.clone();
+    let forwarder_handle = tokio::spawn(async move {
+        let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+        heartbeat_interval.tick().await;
+
+        loop {
+            tokio::select! {
+                chunk = output_rx.recv() => {
+                    match chunk {
+                        Some(chunk) => {
+                            if !chunk.chunk.is_empty() {
+                                let _ = forwarder_event_tx.send(LoopEvent::ToolOutputDelta {
+                                    id: forwarder_tool_id.clone(),
+                                    delta: chunk.chunk,
+                                });
+                            }
+                            if chunk.is_complete {
+                                break;
                             }
-                            None => break,
                         }
-                    }
-                    _ = heartbeat_interval.tick() => {
-                        let _ = forwarder_event_tx.send(LoopEvent::ToolExecuting {
-                            id: forwarder_tool_id.clone(),
-                            name: forwarder_tool_name.clone(),
-                        });
+                        None => break,
                     }
                 }
+                _ = heartbeat_interval.tick() => {
+                    let _ = forwarder_event_tx.send(LoopEvent::ToolExecuting {
+                        id: forwarder_tool_id.clone(),
+                        name: forwarder_tool_name.clone(),
+                    });
+                }
             }
+        }
+    });
+
+    let ctx = ToolContext {
+        working_dir: env.working_dir.to_path_buf(),
+        process_registry: Some(env.process_registry.clone()),
+        plan_mode: env.work_mode == WorkMode::Plan,
+        user_id: env.user_id.map(ToString::to_string),
+        sandbox_root: Some(env.working_dir.to_path_buf()),
+        checkpointer: env.checkpointer.cloned(),
+        lsp_manager: env.lsp_manager.cloned(),
+        editor: env.editor.cloned(),
+        ..Default::default()
+    }
+    .with_output_stream(output_tx, call.id.clone());
+
+    let result = env
+        .tool_registry
+        .execute(&call.name, call.arguments.clone(), &ctx)
+        .await
+        .unwrap_or_else(|| {
+            ToolResult::error_with_code("unknown_tool", format!("Unknown tool: {}", call.name))
         });
 
-        let ctx = ToolContext {
-            working_dir: working_dir.to_path_buf(),
-            process_registry: Some(process_registry.clone()),
-            plan_mode: work_mode == WorkMode::Plan,
-            user_id: user_id.map(ToString::to_string),
-            sandbox_root: Some(working_dir.to_path_buf()),
-            ..Default::default()
-        }
-        .with_output_stream(output_tx, call.id.clone());
-
-        let result = tool_registry
-            .execute(&call.name, call.arguments.clone(), &ctx)
-            .await
-            .unwrap_or_else(|| {
-                crate::tools::registry::ToolResult::error_with_code(
-                    "unknown_tool",
-                    format!("Unknown tool: {}", call.name),
-                )
-            });
+    drop(ctx);
+    let _ = forwarder_handle.await;
+    result
+}
 
-        drop(ctx);
-        let _ = forwarder_handle.await;
+/// Emit a finished call's `ToolResult` event and build its result content
+fn finish_tool_call(
+    event_tx: &mpsc::UnboundedSender<LoopEvent>,
+    call: &AiToolCall,
+    result: ToolResult,
+) -> Content {
+    let output = truncate_output(&result.output);
 
-        let output = truncate_output(&result.output);
+    let _ = event_tx.send(LoopEvent::ToolResult {
+        id: call.id.clone(),
+        output: output.clone(),
+        is_error: result.is_error,
+    });
 
-        let _ = event_tx.send(LoopEvent::ToolResult {
-            id: call.id.clone(),
-            output: output.clone(),
-            is_error: result.is_error,
-        });
+    Content::ToolResult {
+        tool_use_id: call.id.clone(),
+        output: serde_json::Value::String(output),
+        is_error: if result.is_error { Some(true) } else { None },
+    }
+}
 
-        results.push(Content::ToolResult {
-            tool_use_id: call.id.clone(),
-            output: serde_json::Value::String(output),
-            is_error: if result.is_error { Some(true) } else { None },
-        });
+/// Load permission rules for this project, falling back to none on error.
+fn load_policy(db_path: &Path, working_dir: &Path, user_id: Option<&str>) -> PermissionPolicy {
+    match Database::new(db_path).and_then(|db| PermissionPolicy::load(&db, working_dir, user_id)) {
+        Ok(policy) => policy,
+        Err(e) => {
+            tracing::warn!("Failed to load permission rules: {}", e);
+            PermissionPolicy::default()
+        }
     }
+}
 
-    (results, work_mode)
+fn persist_rule(db_path: &Path, rule: &PermissionRule, user_id: Option<&str>) {
+    match Database::new(db_path) {
+        Ok(db) => {
+            if let Err(e) = permissions::save_rule(&db, rule, user_id) {
+                tracing::warn!("Failed to save permission rule: {}", e);
+            }
+        }
+        Err(e) => tracing::error!("Failed to open database while saving rule: {}", e),
+    }
+}
+
+/// User's answer to an approval prompt.
+#[derive(Debug, PartialEq)]
+enum Approval {
+    Approved,
+    AlwaysAllow,
+    Denied,
 }
 
 /// Wait for a tool approval via the LoopInput channel.
@@ -227,7 +379,7 @@ async fn wait_for_approval(
     call: &AiToolCall,
     event_tx: &mpsc::UnboundedSender<LoopEvent>,
     input_rx: &mut mpsc::UnboundedReceiver<LoopInput>,
-) -> bool {
+) -> Approval {
     let deadline = tokio::time::Instant::now() + APPROVAL_TIMEOUT;
 
     loop {
@@ -235,12 +387,17 @@ async fn wait_for_approval(
             Ok(Some(LoopInput::ToolApproval {
                 tool_call_id,
                 approved,
+                always_allow,
             })) if tool_call_id == call.id => {
-                return approved;
+                return match (approved, always_allow) {
+                    (false, _) => Approval::Denied,
+                    (true, false) => Approval::Approved,
+                    (true, true) => Approval::AlwaysAllow,
+                };
             }
-            Ok(Some(LoopInput::Cancel)) => return false,
-            Ok(Some(_)) => continue,  // ignore unrelated inputs
-            Ok(None) => return false, // channel closed
+            Ok(Some(LoopInput::Cancel)) => return Approval::Denied,
+            Ok(Some(_)) => continue,             // ignore unrelated inputs
+            Ok(None) => return Approval::Denied, // channel closed
             Err(_) => {
                 let timeout_result = crate::tools::registry::ToolResult::error_with_code(
                     "timeout",
@@ -255,7 +412,7 @@ async fn wait_for_approval(
                     output: timeout_result.output,
                     is_error: timeout_result.is_error,
                 });
-                return false;
+                return Approval::Denied;
             }
         }
     }
@@ -285,3 +442,112 @@ fn floor_char_boundary(text: &str, index: usize) -> usize {
     }
     boundary
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Sleeps, tracking how many instances run at once
+    struct SlowTool {
+        name: &'static str,
+        running: Arc<AtomicUsize>,
+        peak: Arc<AtomicUsize>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::tools::registry::Tool for SlowTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "test tool"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, params: serde_json::Value, _ctx: &ToolContext) -> ToolResult {
+            let now = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+            ToolResult::success(params["tag"].as_str().unwrap_or_default().to_string())
+        }
+    }
+
+    fn call(id: &str, name: &str) -> AiToolCall {
+        AiToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            arguments: serde_json::json!({ "tag": id }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_only_runs_execute_concurrently_in_order() {
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let registry = ToolRegistry::new();
+        for name in ["grep", "glob", "bash"] {
+            registry
+                .register(Arc::new(SlowTool {
+                    name,
+                    running: running.clone(),
+                    peak: peak.clone(),
+                }))
+                .await;
+        }
+        let registry = Arc::new(registry);
+        let dir = tempfile::tempdir().unwrap();
+        let (event_tx, mut event_rx) = mpsc::unbounded_channel();
+        let (_input_tx, mut input_rx) = mpsc::unbounded_channel();
+
+        let calls = vec![
+            call("a", "grep"),
+            call("b", "glob"),
+            call("c", "grep"),
+            call("d", "bash"),
+            call("e", "glob"),
+        ];
+        let (results, _) = execute_tools(
+            &calls,
+            &registry,
+            dir.path(),
+            &Arc::new(ProcessRegistry::new()),
+            "session",
+            &dir.path().join("krusty.db"),
+            None,
+            PermissionMode::Autonomous,
+            WorkMode::Build,
+            None,
+            None,
+            None,
+            &event_tx,
+            &mut input_rx,
+        )
+        .await;
+
+        // The three leading read-only calls overlapped; bash ran alone
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+        let ids: Vec<&str> = results
+            .iter()
+            .map(|r| match r {
+                Content::ToolResult { tool_use_id, .. } => tool_use_id.as_str(),
+                _ => panic!("expected tool result"),
+            })
+            .collect();
+        assert_eq!(ids, ["a", "b", "c", "d", "e"]);
+
+        drop(event_tx);
+        let mut result_events = Vec::new();
+        while let Some(event) = event_rx.recv().await {
+            if let LoopEvent::ToolResult { id, .. } = event {
+                result_events.push(id);
+            }
+        }
+        assert_eq!(result_events, ["a", "b", "c", "d", "e"]);
+    }
+}
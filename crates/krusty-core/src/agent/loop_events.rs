@@ -9,6 +9,7 @@
 
 use serde::Serialize;
 
+use super::permissions::PermissionRule;
 use crate::ai::types::{Citation, WebFetchContent, WebSearchResult};
 
 /// Events emitted by the agentic orchestrator.
@@ -75,9 +76,12 @@ pub enum LoopEvent {
     /// Tool was approved by user.
     ToolApproved { id: String },
 
-    /// Tool was denied by user.
+    /// Tool was denied by user or by a permission rule.
     ToolDenied { id: String },
 
+    /// User chose "always allow" and a permission rule was persisted.
+    PermissionRuleAdded { rule: PermissionRule },
+
     // ── Server-side tools (web search/fetch) ──────────────────────────
     /// Server-side tool started (web_search, web_fetch).
     ServerToolStart { id: String, name: String },
@@ -130,6 +134,41 @@ pub enum LoopEvent {
         completion_tokens: usize,
     },
 
+    /// Cost of the model call just recorded in the usage ledger, and the
+    /// session's running total. `call_cost_usd` is None for unpriced models.
+    Cost {
+        call_cost_usd: Option<f64>,
+        session_cost_usd: f64,
+    },
+
+    /// Session spending crossed its soft budget.
+    BudgetWarning { spent_usd: f64, limit_usd: f64 },
+
+    /// Session spending reached its hard budget; the loop stops.
+    BudgetExceeded { spent_usd: f64, limit_usd: f64 },
+
+    /// Context usage crossed the auto-pinch threshold.
+    ContextNearLimit {
+        tokens_used: usize,
+        context_window: usize,
+    },
+
+    /// Conversation was summarized into a linked session; the loop continues
+    /// there and later events belong to `session_id`.
+    SessionPinched {
+        previous_session_id: String,
+        session_id: String,
+        title: String,
+    },
+
+    /// Older turns were summarized and stale tool output elided in place;
+    /// the session keeps its id and its stored messages.
+    ConversationCompacted {
+        summarized_messages: usize,
+        elided_results: usize,
+        tokens_before: usize,
+    },
+
     /// Session title generated.
     TitleGenerated { title: String },
 
@@ -151,9 +190,12 @@ pub struct PlanTaskInfo {
 #[derive(Debug, Clone)]
 pub enum LoopInput {
     /// User approved or denied a tool execution.
+    ///
+    /// `always_allow` persists an allow rule matching this call.
     ToolApproval {
         tool_call_id: String,
         approved: bool,
+        always_allow: bool,
     },
 
     /// User responded to an AskUser or PlanConfirm prompt.
@@ -15,21 +15,31 @@
 //! - `LoggingHook` - Logs all tool executions
 //! - `UserHookManager` - User-configurable hooks
 //!
+//! ## Permissions
+//! - `PermissionPolicy` - Allow/deny/ask rules evaluated before tool execution
+//!
 //! ## Pinch (Context Continuation)
 //! - `PinchContext` - Structured context for session transitions
+//! - `AutoPinchPolicy` - When the orchestrator pinches near the context limit
+//! - `compaction` - In-place summaries and stale tool-output pruning
 //! - `SummarizationResult` - Output from summarization agent
 //!
 //! ## Sub-agents
 //! - `SubAgentPool` - Concurrent execution of lightweight agents
 //! - `SubAgentTask` - Task configuration for sub-agents
+//! - `AgentDefinition` - User-defined agent types from `.krusty/agents/*.md`
 //!
 //! ## Builder Swarm (Octopod)
 //! - `SharedBuildContext` - Coordination for builder agents
 //! - Type registry, file locks, conventions
+//! - `BuildWorktrees` - Per-builder git worktrees, merged back with conflict checks
 
+pub mod auto_pinch;
 pub mod build_context;
+pub mod build_worktree;
 pub mod cache;
 pub mod cancellation;
+pub mod compaction;
 pub mod constants;
 pub mod context;
 pub mod event_bus;
@@ -39,6 +49,7 @@ pub mod failure;
 pub mod hooks;
 pub mod loop_events;
 pub mod orchestrator;
+pub mod permissions;
 pub mod pinch_context;
 pub mod plan_handler;
 pub mod state;
@@ -47,16 +58,19 @@ pub mod subagent;
 pub mod summarizer;
 pub mod user_hooks;
 
+pub use auto_pinch::{AutoPinchMode, AutoPinchPolicy, PinchStrategy};
 pub use build_context::SharedBuildContext;
 pub use cancellation::AgentCancellation;
 pub use context::{
-    build_plan_context, build_project_context, build_skills_context, inject_context,
+    build_agents_context, build_memory_context, build_plan_context, build_project_context,
+    build_skills_context, inject_context, read_project_instructions,
 };
 pub use event_bus::AgentEventBus;
 pub use events::{AgentEvent, InterruptReason};
 pub use hooks::{LoggingHook, PlanModeHook, SafetyHook};
 pub use loop_events::{LoopEvent, LoopInput, PlanTaskInfo};
 pub use orchestrator::{AgenticOrchestrator, OrchestratorConfig, OrchestratorServices};
+pub use permissions::{PermissionAction, PermissionDecision, PermissionPolicy, PermissionRule};
 pub use pinch_context::{PinchContext, PinchContextInput};
 pub use state::{AgentConfig, AgentState};
 pub use summarizer::{generate_summary, SummarizationResult};
@@ -25,14 +25,22 @@ use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 
 use crate::ai::client::{AiClient, CallOptions};
-use crate::ai::title::generate_title as ai_generate_title;
+use crate::ai::models::ModelPricing;
+use crate::ai::title::{generate_pinch_title, generate_title as ai_generate_title};
 use crate::ai::types::{Content, ModelMessage, Role};
+use crate::lsp::LspManager;
 use crate::plan::PlanManager;
 use crate::process::ProcessRegistry;
 use crate::skills::SkillsManager;
-use crate::storage::{Database, SessionManager, WorkMode};
+use crate::storage::{
+    BudgetStatus, Compaction, CompactionStore, Database, MessageStore, SessionBudget,
+    SessionManager, UsageEntry, UsageLedger, WorkMode,
+};
 use crate::tools::registry::{PermissionMode, ToolRegistry};
+use crate::tools::{EditorClient, FileCheckpointer};
 
+use super::auto_pinch::{self, AutoPinchPolicy, PinchStrategy, CONTINUE_PROMPT};
+use super::compaction;
 use super::context;
 use super::executor;
 use super::failure;
@@ -55,6 +63,14 @@ pub struct OrchestratorConfig {
     /// Whether to generate a title on first AI response.
     /// Set to true for new sessions, false for resumed conversations.
     pub generate_title: bool,
+    /// Pricing for the active model, used to cost entries in the usage ledger.
+    /// None records token counts without a cost.
+    pub pricing: Option<ModelPricing>,
+    /// Context window of the active model in tokens. 0 when unknown, which
+    /// disables auto-pinch.
+    pub context_window: usize,
+    /// When to warn about, and pinch, a conversation nearing `context_window`.
+    pub auto_pinch: AutoPinchPolicy,
 }
 
 impl Default for OrchestratorConfig {
@@ -67,6 +83,9 @@ impl Default for OrchestratorConfig {
             user_id: None,
             initial_work_mode: WorkMode::default(),
             generate_title: false,
+            pricing: None,
+            context_window: 0,
+            auto_pinch: AutoPinchPolicy::default(),
         }
     }
 }
@@ -78,6 +97,10 @@ pub struct OrchestratorServices {
     pub process_registry: Arc<ProcessRegistry>,
     pub db_path: PathBuf,
     pub skills_manager: Arc<RwLock<SkillsManager>>,
+    /// Language servers to sync edits to (diagnostics on write tools)
+    pub lsp_manager: Option<Arc<LspManager>>,
+    /// Editor serving file access and terminals (ACP clients)
+    pub editor: Option<Arc<dyn EditorClient>>,
 }
 
 /// The agentic orchestrator — runs the complete AI agent loop.
@@ -119,7 +142,7 @@ impl AgenticOrchestrator {
     async fn run_inner(
         self,
         mut conversation: Vec<ModelMessage>,
-        options: CallOptions,
+        mut options: CallOptions,
         event_tx: mpsc::UnboundedSender<LoopEvent>,
         mut input_rx: mpsc::UnboundedReceiver<LoopInput>,
     ) {
@@ -129,16 +152,21 @@ impl AgenticOrchestrator {
             process_registry,
             db_path,
             skills_manager,
+            lsp_manager,
+            editor,
         } = self.services;
 
         let OrchestratorConfig {
-            session_id,
+            mut session_id,
             working_dir,
             permission_mode,
             max_iterations,
             user_id,
             initial_work_mode,
             generate_title,
+            pricing,
+            context_window,
+            auto_pinch,
         } = self.config;
 
         let mut work_mode = initial_work_mode;
@@ -146,16 +174,83 @@ impl AgenticOrchestrator {
         let mut exploration_budget_count = 0usize;
         let mut tool_failure_signatures: HashMap<String, usize> = HashMap::new();
         let mut title_generated = !generate_title;
+        let (budget, mut session_cost) = load_budget(&db_path, &session_id);
+        let mut budget_warned = false;
+        let mut context_warned = false;
+
+        // A prompt sent to a session that ended its last run near the limit
+        // opens the linked session instead, or compacts this one first
+        let stored_tokens = load_token_count(&db_path, &session_id);
+        if auto_pinch.pinches() && auto_pinch.is_near_limit(stored_tokens, context_window) {
+            match auto_pinch.strategy {
+                PinchStrategy::LinkedSession => {
+                    if let Some((prompt, history)) = conversation
+                        .split_last()
+                        .filter(|(last, history)| is_user_prompt(last) && !history.is_empty())
+                    {
+                        if let Some(pinched) = continue_in_linked_session(
+                            &ai_client,
+                            &db_path,
+                            &session_id,
+                            &working_dir,
+                            history,
+                            prompt.content.clone(),
+                            &event_tx,
+                        )
+                        .await
+                        {
+                            session_id = pinched.session_id;
+                            conversation = pinched.conversation;
+                            options.session_id = Some(session_id.clone());
+                            title_generated = true;
+                        }
+                    }
+                }
+                PinchStrategy::Compact => {
+                    compact_in_place(
+                        &ai_client,
+                        &db_path,
+                        &session_id,
+                        &working_dir,
+                        &conversation,
+                        stored_tokens,
+                        &event_tx,
+                    )
+                    .await;
+                }
+            }
+        }
+
+        // What the model sees; stored messages are never rewritten
+        let mut compaction = load_compaction(&db_path, &session_id);
+        let mut checkpointer = begin_checkpoint(&db_path, &session_id, &conversation);
 
         set_agent_state(&db_path, &session_id, "streaming");
 
         for iteration in 1..=max_iterations {
+            // Hard budget: stop before spending anything more
+            if let BudgetStatus::HardExceeded { limit_usd } = budget.check(session_cost) {
+                tracing::warn!(
+                    session_id = %session_id,
+                    spent_usd = session_cost,
+                    limit_usd,
+                    "Session hard budget reached, stopping"
+                );
+                let _ = event_tx.send(LoopEvent::BudgetExceeded {
+                    spent_usd: session_cost,
+                    limit_usd,
+                });
+                break;
+            }
+
             // Build context-injected conversation
+            let view = compaction::compacted_view(&conversation, compaction.as_ref());
             let conversation_with_context = context::inject_context(
-                &conversation,
+                &view,
                 &db_path,
                 &session_id,
                 &working_dir,
+                user_id.as_deref(),
                 work_mode,
                 &skills_manager,
             );
@@ -184,6 +279,52 @@ impl AgenticOrchestrator {
                 last_token_count = result.total_tokens;
             }
 
+            let near_limit = auto_pinch.is_near_limit(result.total_tokens, context_window);
+            if near_limit && !context_warned {
+                context_warned = true;
+                tracing::info!(
+                    session_id = %session_id,
+                    tokens_used = result.total_tokens,
+                    context_window,
+                    "Context near limit"
+                );
+                let _ = event_tx.send(LoopEvent::ContextNearLimit {
+                    tokens_used: result.total_tokens,
+                    context_window,
+                });
+            }
+
+            // Usage ledger + soft budget
+            if result.usage.prompt_tokens + result.usage.completion_tokens > 0 {
+                let call_cost_usd = pricing.as_ref().map(|p| p.cost(&result.usage));
+                let config = ai_client.config();
+                let entry = UsageEntry {
+                    session_id: &session_id,
+                    user_id: user_id.as_deref(),
+                    turn: iteration,
+                    provider: config.provider_id().storage_key(),
+                    model: &config.model,
+                    usage: &result.usage,
+                    cost_usd: call_cost_usd,
+                };
+                if let Some(total) = record_usage(&db_path, &entry) {
+                    session_cost = total;
+                    let _ = event_tx.send(LoopEvent::Cost {
+                        call_cost_usd,
+                        session_cost_usd: session_cost,
+                    });
+                }
+                if !budget_warned {
+                    if let BudgetStatus::SoftExceeded { limit_usd } = budget.check(session_cost) {
+                        budget_warned = true;
+                        let _ = event_tx.send(LoopEvent::BudgetWarning {
+                            spent_usd: session_cost,
+                            limit_usd,
+                        });
+                    }
+                }
+            }
+
             // Build and save assistant message
             let assistant_msg =
                 build_assistant_message(&result.text, &result.thinking_blocks, &result.tool_calls);
@@ -278,6 +419,9 @@ impl AgenticOrchestrator {
                         user_id.as_deref(),
                         permission_mode,
                         work_mode,
+                        checkpointer.as_ref(),
+                        lsp_manager.as_ref(),
+                        editor.as_ref(),
                         &event_tx,
                         &mut input_rx,
                     )
@@ -356,6 +500,9 @@ impl AgenticOrchestrator {
                 user_id.as_deref(),
                 permission_mode,
                 work_mode,
+                checkpointer.as_ref(),
+                lsp_manager.as_ref(),
+                editor.as_ref(),
                 &event_tx,
                 &mut input_rx,
             )
@@ -407,6 +554,55 @@ impl AgenticOrchestrator {
                 break;
             }
 
+            // Near the limit mid-task: summarize and keep going, either in a
+            // linked session or in place
+            if near_limit && auto_pinch.pinches() {
+                match auto_pinch.strategy {
+                    PinchStrategy::LinkedSession => {
+                        let next_prompt = vec![Content::Text {
+                            text: CONTINUE_PROMPT.to_string(),
+                        }];
+                        if let Some(pinched) = continue_in_linked_session(
+                            &ai_client,
+                            &db_path,
+                            &session_id,
+                            &working_dir,
+                            &conversation,
+                            next_prompt,
+                            &event_tx,
+                        )
+                        .await
+                        {
+                            update_token_count(&db_path, &session_id, last_token_count);
+                            session_id = pinched.session_id;
+                            conversation = pinched.conversation;
+                            options.session_id = Some(session_id.clone());
+                            checkpointer = begin_checkpoint(&db_path, &session_id, &conversation);
+                            compaction = None;
+                            last_token_count = 0;
+                            context_warned = false;
+                            title_generated = true;
+                        }
+                    }
+                    PinchStrategy::Compact => {
+                        if let Some(compacted) = compact_in_place(
+                            &ai_client,
+                            &db_path,
+                            &session_id,
+                            &working_dir,
+                            &conversation,
+                            last_token_count,
+                            &event_tx,
+                        )
+                        .await
+                        {
+                            compaction = Some(compacted);
+                            context_warned = false;
+                        }
+                    }
+                }
+            }
+
             set_agent_state(&db_path, &session_id, "streaming");
             let _ = event_tx.send(LoopEvent::TurnComplete {
                 turn: iteration,
@@ -425,6 +621,107 @@ impl AgenticOrchestrator {
     }
 }
 
+// ── Auto-pinch ─────────────────────────────────────────────────────────
+
+/// Pinch into a linked session, announcing it and generating its title
+///
+/// Failures are logged and leave the loop in the current session.
+async fn continue_in_linked_session(
+    ai_client: &Arc<AiClient>,
+    db_path: &Path,
+    session_id: &str,
+    working_dir: &Path,
+    conversation: &[ModelMessage],
+    next_prompt: Vec<Content>,
+    event_tx: &mpsc::UnboundedSender<LoopEvent>,
+) -> Option<auto_pinch::PinchOutcome> {
+    let outcome = match auto_pinch::pinch_session(
+        ai_client,
+        db_path,
+        session_id,
+        working_dir,
+        conversation,
+        next_prompt,
+    )
+    .await
+    {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            tracing::warn!(session_id = %session_id, "Auto-pinch failed: {}", e);
+            return None;
+        }
+    };
+
+    set_agent_state(db_path, session_id, "idle");
+    let _ = event_tx.send(LoopEvent::SessionPinched {
+        previous_session_id: session_id.to_string(),
+        session_id: outcome.session_id.clone(),
+        title: outcome.title.clone(),
+    });
+
+    let title_client = ai_client.clone();
+    let title_tx = event_tx.clone();
+    let title_db_path = db_path.to_path_buf();
+    let title_session_id = outcome.session_id.clone();
+    let source_title = outcome.source_title.clone();
+    let summary = outcome.summary.clone();
+    tokio::spawn(async move {
+        let title = generate_pinch_title(&title_client, &source_title, &summary, None).await;
+        if !title.is_empty() {
+            save_title(&title_db_path, &title_session_id, &title);
+            let _ = title_tx.send(LoopEvent::TitleGenerated { title });
+        }
+    });
+
+    Some(outcome)
+}
+
+/// Compact the session in place and announce it
+///
+/// Failures are logged and leave the conversation as it was.
+async fn compact_in_place(
+    ai_client: &Arc<AiClient>,
+    db_path: &Path,
+    session_id: &str,
+    working_dir: &Path,
+    conversation: &[ModelMessage],
+    tokens_before: usize,
+    event_tx: &mpsc::UnboundedSender<LoopEvent>,
+) -> Option<Compaction> {
+    match compaction::compact_session(
+        ai_client,
+        db_path,
+        session_id,
+        working_dir,
+        conversation,
+        tokens_before,
+    )
+    .await
+    {
+        Ok(outcome) => {
+            let _ = event_tx.send(LoopEvent::ConversationCompacted {
+                summarized_messages: outcome.summarized_messages,
+                elided_results: outcome.elided_results,
+                tokens_before,
+            });
+            Some(outcome.compaction)
+        }
+        Err(e) => {
+            tracing::warn!(session_id = %session_id, "Compaction failed: {}", e);
+            None
+        }
+    }
+}
+
+/// A user turn that carries a prompt rather than tool results
+fn is_user_prompt(message: &ModelMessage) -> bool {
+    message.role == Role::User
+        && !message
+            .content
+            .iter()
+            .any(|c| matches!(c, Content::ToolResult { .. }))
+}
+
 // ── Plan detection ─────────────────────────────────────────────────────
 
 fn handle_plan_detection(
@@ -576,6 +873,121 @@ fn set_agent_state(db_path: &Path, session_id: &str, state: &str) {
     }
 }
 
+/// Start this turn's file checkpoint
+///
+/// The prompt that started the turn is already saved, so the messages before
+/// it are the stored count minus one.
+fn begin_checkpoint(
+    db_path: &Path,
+    session_id: &str,
+    conversation: &[ModelMessage],
+) -> Option<FileCheckpointer> {
+    let message_count = match Database::new(db_path) {
+        Ok(db) => MessageStore::new(&db)
+            .get_message_count(session_id)
+            .unwrap_or(0),
+        Err(e) => {
+            tracing::error!("Failed to open database while starting checkpoint: {}", e);
+            return None;
+        }
+    };
+    let prompt = conversation
+        .last()
+        .filter(|m| matches!(m.role, Role::User))
+        .and_then(|m| {
+            m.content.iter().find_map(|c| match c {
+                Content::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+        });
+    match FileCheckpointer::begin(db_path, session_id, message_count.saturating_sub(1), prompt) {
+        Ok(checkpointer) => Some(checkpointer),
+        Err(e) => {
+            tracing::warn!(session_id = %session_id, "Failed to start checkpoint: {}", e);
+            None
+        }
+    }
+}
+
+/// Load the session budget and what the session has spent so far
+fn load_budget(db_path: &Path, session_id: &str) -> (SessionBudget, f64) {
+    let db = match Database::new(db_path) {
+        Ok(db) => db,
+        Err(e) => {
+            tracing::error!("Failed to open database while loading budget: {}", e);
+            return (SessionBudget::default(), 0.0);
+        }
+    };
+    let ledger = UsageLedger::new(&db);
+    let budget = ledger.get_budget(session_id).unwrap_or_else(|e| {
+        tracing::warn!(session_id = %session_id, "Failed to load session budget: {}", e);
+        SessionBudget::default()
+    });
+    let spent = ledger
+        .session_totals(session_id)
+        .map(|totals| totals.cost_usd)
+        .unwrap_or_else(|e| {
+            tracing::warn!(session_id = %session_id, "Failed to load session usage: {}", e);
+            0.0
+        });
+    (budget, spent)
+}
+
+/// Record a ledger entry, returning the session's updated total cost
+fn record_usage(db_path: &Path, entry: &UsageEntry<'_>) -> Option<f64> {
+    let db = match Database::new(db_path) {
+        Ok(db) => db,
+        Err(e) => {
+            tracing::error!("Failed to open database while recording usage: {}", e);
+            return None;
+        }
+    };
+    let ledger = UsageLedger::new(&db);
+    if let Err(e) = ledger.record(entry) {
+        tracing::warn!(session_id = %entry.session_id, "Failed to record usage: {}", e);
+        return None;
+    }
+    match ledger.session_totals(entry.session_id) {
+        Ok(totals) => Some(totals.cost_usd),
+        Err(e) => {
+            tracing::warn!(session_id = %entry.session_id, "Failed to total usage: {}", e);
+            None
+        }
+    }
+}
+
+/// Compaction in effect for the session, if any
+fn load_compaction(db_path: &Path, session_id: &str) -> Option<Compaction> {
+    match Database::new(db_path) {
+        Ok(db) => CompactionStore::new(&db)
+            .latest(session_id)
+            .unwrap_or_else(|e| {
+                tracing::warn!(session_id = %session_id, "Failed to load compaction: {}", e);
+                None
+            }),
+        Err(e) => {
+            tracing::error!("Failed to open database while loading compaction: {}", e);
+            None
+        }
+    }
+}
+
+/// Context size recorded at the end of the session's last run
+fn load_token_count(db_path: &Path, session_id: &str) -> usize {
+    match Database::new(db_path) {
+        Ok(db) => SessionManager::new(db)
+            .get_session(session_id)
+            .ok()
+            .flatten()
+            .and_then(|s| s.token_count)
+            .unwrap_or(0),
+        Err(e) => {
+            tracing::error!("Failed to open database while loading token count: {}", e);
+            0
+        }
+    }
+}
+
 fn update_token_count(db_path: &Path, session_id: &str, token_count: usize) {
     match Database::new(db_path) {
         Ok(db) => {
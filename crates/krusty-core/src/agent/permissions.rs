@@ -0,0 +1,796 @@
+//! Rule-based tool permission policies
+//!
+//! Declarative allow/deny/ask rules layered on top of `PermissionMode`.
+//! A rule matches a tool name (wildcards allowed) and, optionally, a pattern
+//! over the call's main argument: the command for `bash`, the path for file
+//! tools. Rules are stored per user, either globally or scoped to a project
+//! directory.
+//!
+//! ## Precedence
+//! Deny beats ask, ask beats allow. With no matching rule the mode decides:
+//! supervised asks for write tools, autonomous runs everything.
+//!
+//! ## Patterns
+//! `*` matches any run of characters (including `/`), `?` a single character.
+//! A leading `!` negates the pattern, so `deny write !src/*` blocks writes
+//! outside `src/`. Paths are matched relative to the working directory
+//! after resolving `.` and `..`; a path that ends up outside it is matched
+//! in absolute form and never satisfies a relative allow pattern.
+//!
+//! Bash commands are split into the simple commands they run (at `;`, `&&`,
+//! `||`, `|`, newlines and substitutions) and each is decided on its own:
+//! every one must be allowed, and one denied or ask part decides the whole
+//! command.
+
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::Database;
+use crate::tools::registry::{tool_category, PermissionMode, ToolCategory};
+
+/// What a matching rule does with a tool call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionAction {
+    /// Run without prompting
+    Allow,
+    /// Refuse the call
+    Deny,
+    /// Always prompt, even in autonomous mode
+    Ask,
+}
+
+impl PermissionAction {
+    /// All actions for UI display
+    pub fn all() -> &'static [PermissionAction] {
+        &[
+            PermissionAction::Allow,
+            PermissionAction::Ask,
+            PermissionAction::Deny,
+        ]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PermissionAction::Allow => "allow",
+            PermissionAction::Deny => "deny",
+            PermissionAction::Ask => "ask",
+        }
+    }
+
+    /// Parse from string representation
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "allow" => Some(PermissionAction::Allow),
+            "deny" => Some(PermissionAction::Deny),
+            "ask" => Some(PermissionAction::Ask),
+            _ => None,
+        }
+    }
+
+    /// Precedence when several rules match (higher wins)
+    fn rank(&self) -> u8 {
+        match self {
+            PermissionAction::Allow => 0,
+            PermissionAction::Ask => 1,
+            PermissionAction::Deny => 2,
+        }
+    }
+}
+
+impl std::fmt::Display for PermissionAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A single permission rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionRule {
+    /// Unique identifier
+    pub id: String,
+    pub action: PermissionAction,
+    /// Tool name pattern (`*` = any tool)
+    pub tool: String,
+    /// Pattern over the call's command or path (None = any arguments)
+    pub pattern: Option<String>,
+    /// Project directory the rule applies to (None = all projects)
+    pub working_dir: Option<String>,
+    /// When the rule was created
+    pub created_at: String,
+}
+
+impl PermissionRule {
+    /// Create a new rule
+    pub fn new(
+        action: PermissionAction,
+        tool: impl Into<String>,
+        pattern: Option<String>,
+        working_dir: Option<String>,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            action,
+            tool: tool.into(),
+            pattern: pattern.filter(|p| !p.is_empty()),
+            working_dir,
+            created_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Parse the compact `<action> <tool> [pattern]` form, e.g.
+    /// `allow bash cargo test*` or `deny write !src/*`
+    pub fn parse_spec(spec: &str, working_dir: Option<String>) -> Result<Self> {
+        let mut parts = spec.trim().splitn(3, char::is_whitespace);
+        let action = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("Expected: <allow|deny|ask> <tool> [pattern]"))?;
+        let action = PermissionAction::parse(action)
+            .ok_or_else(|| anyhow!("Unknown action '{}': use allow, deny or ask", action))?;
+        let tool = parts
+            .next()
+            .filter(|s| !s.is_empty())
+            .ok_or_else(|| anyhow!("Missing tool name"))?;
+        let pattern = parts.next().map(|p| p.trim().to_string());
+        Ok(Self::new(action, tool, pattern, working_dir))
+    }
+
+    /// Compact `<action> <tool> [pattern]` form
+    pub fn spec(&self) -> String {
+        match &self.pattern {
+            Some(pattern) => format!("{} {} {}", self.action, self.tool, pattern),
+            None => format!("{} {}", self.action, self.tool),
+        }
+    }
+
+    /// Check whether this rule applies to one subject of a call
+    ///
+    /// Rules with a pattern never match a call without subjects.
+    fn matches(&self, tool_name: &str, subject: Option<&Subject>) -> bool {
+        if !wildcard_match(&self.tool, tool_name) {
+            return false;
+        }
+        let Some(pattern) = &self.pattern else {
+            return true;
+        };
+        let Some(subject) = subject else {
+            return false;
+        };
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern.as_str()),
+        };
+        // `allow write src/*` must not cover `src/../../etc/passwd`
+        if self.action == PermissionAction::Allow && subject.outside && !pattern.starts_with('/') {
+            return false;
+        }
+        wildcard_match(pattern, &subject.text) != negated
+    }
+
+    /// Suggest an "always allow" rule for a call the user just approved
+    ///
+    /// Bash commands keep the program and subcommand of their first part
+    /// (`cargo test*`), file tools keep the exact path, other tools are
+    /// allowed outright.
+    pub fn always_allow(
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        working_dir: &Path,
+    ) -> Self {
+        let pattern = if tool_name == "bash" {
+            arguments
+                .get("command")
+                .and_then(|c| c.as_str())
+                .and_then(|c| command_segments(c).into_iter().next())
+                .map(|c| command_prefix_pattern(&c))
+        } else {
+            match call_subjects(tool_name, arguments, working_dir).as_slice() {
+                [path] => Some(path.text.clone()),
+                _ => None,
+            }
+        };
+        Self::new(
+            PermissionAction::Allow,
+            tool_name,
+            pattern,
+            Some(working_dir.to_string_lossy().into_owned()),
+        )
+    }
+}
+
+/// Outcome of evaluating a call against the policy
+#[derive(Debug, Clone, PartialEq)]
+pub enum PermissionDecision {
+    Allow,
+    Ask,
+    Deny {
+        /// The rule that denied the call, in spec form
+        rule: String,
+    },
+}
+
+/// Loaded permission rules for one project and user
+#[derive(Debug, Clone, Default)]
+pub struct PermissionPolicy {
+    rules: Vec<PermissionRule>,
+}
+
+impl PermissionPolicy {
+    pub fn new(rules: Vec<PermissionRule>) -> Self {
+        Self { rules }
+    }
+
+    /// Load global rules plus rules scoped to `working_dir`
+    ///
+    /// In multi-tenant mode rules owned by other users are skipped.
+    pub fn load(db: &Database, working_dir: &Path, user_id: Option<&str>) -> Result<Self> {
+        let dir = working_dir.to_string_lossy();
+        let rules = list_rules(db, user_id)?
+            .into_iter()
+            .filter(|r| r.working_dir.as_deref().is_none_or(|d| d == dir))
+            .collect();
+        Ok(Self { rules })
+    }
+
+    pub fn rules(&self) -> &[PermissionRule] {
+        &self.rules
+    }
+
+    /// Add a rule to the in-memory policy (see [`save_rule`] to persist)
+    pub fn push(&mut self, rule: PermissionRule) {
+        self.rules.push(rule);
+    }
+
+    /// Decide what to do with a tool call
+    pub fn decide(
+        &self,
+        mode: PermissionMode,
+        tool_name: &str,
+        arguments: &serde_json::Value,
+        working_dir: &Path,
+    ) -> PermissionDecision {
+        let category = tool_category(tool_name);
+        // Interactive tools (AskUser, plan tasks) are never gated
+        if category == ToolCategory::Interactive {
+            return PermissionDecision::Allow;
+        }
+
+        // Each subject (command part, patched file) gets its strongest rule
+        let subjects = call_subjects(tool_name, arguments, working_dir);
+        let matched: Vec<Option<&PermissionRule>> = if subjects.is_empty() {
+            vec![self.strongest_match(tool_name, None)]
+        } else {
+            subjects
+                .iter()
+                .map(|s| self.strongest_match(tool_name, Some(s)))
+                .collect()
+        };
+        let strictest = matched.iter().flatten().max_by_key(|r| r.action.rank());
+
+        match strictest {
+            Some(rule) if rule.action == PermissionAction::Deny => {
+                PermissionDecision::Deny { rule: rule.spec() }
+            }
+            Some(rule) if rule.action == PermissionAction::Ask => PermissionDecision::Ask,
+            // Allow only when every subject is covered by an allow rule
+            Some(_) if matched.iter().all(Option::is_some) => PermissionDecision::Allow,
+            _ if mode == PermissionMode::Supervised && category == ToolCategory::Write => {
+                PermissionDecision::Ask
+            }
+            _ => PermissionDecision::Allow,
+        }
+    }
+
+    /// The highest-precedence rule matching one subject of a call
+    fn strongest_match(
+        &self,
+        tool_name: &str,
+        subject: Option<&Subject>,
+    ) -> Option<&PermissionRule> {
+        self.rules
+            .iter()
+            .filter(|r| r.matches(tool_name, subject))
+            .max_by_key(|r| r.action.rank())
+    }
+}
+
+// ── Storage ────────────────────────────────────────────────────────────
+
+/// List rules visible to a user (None = single-tenant, all rules)
+pub fn list_rules(db: &Database, user_id: Option<&str>) -> Result<Vec<PermissionRule>> {
+    let conn = db.conn();
+    let mut stmt = conn.prepare(
+        "SELECT id, action, tool, pattern, working_dir, created_at
+         FROM permission_rules
+         WHERE ?1 IS NULL OR user_id = ?1 OR user_id IS NULL
+         ORDER BY created_at",
+    )?;
+    let rows = stmt.query_map(params![user_id], |row| {
+        Ok(PermissionRule {
+            id: row.get(0)?,
+            action: PermissionAction::parse(&row.get::<_, String>(1)?)
+                .unwrap_or(PermissionAction::Ask),
+            tool: row.get(2)?,
+            pattern: row.get(3)?,
+            working_dir: row.get(4)?,
+            created_at: row.get(5)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Persist a rule for a user (None = single-tenant)
+pub fn save_rule(db: &Database, rule: &PermissionRule, user_id: Option<&str>) -> Result<()> {
+    db.conn().execute(
+        "INSERT INTO permission_rules (id, action, tool, pattern, working_dir, user_id, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![
+            rule.id,
+            rule.action.as_str(),
+            rule.tool,
+            rule.pattern,
+            rule.working_dir,
+            user_id,
+            rule.created_at,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Delete a rule (in multi-tenant mode only the owner's own rules)
+///
+/// Returns whether a rule was deleted.
+pub fn delete_rule(db: &Database, id: &str, user_id: Option<&str>) -> Result<bool> {
+    let deleted = db.conn().execute(
+        "DELETE FROM permission_rules
+         WHERE id = ?1 AND (?2 IS NULL OR user_id = ?2)",
+        params![id, user_id],
+    )?;
+    Ok(deleted > 0)
+}
+
+// ── Matching ───────────────────────────────────────────────────────────
+
+/// A string a rule pattern is matched against
+#[derive(Debug, Clone, PartialEq)]
+struct Subject {
+    text: String,
+    /// A path that lies outside the working directory
+    outside: bool,
+}
+
+/// The subjects a rule pattern is matched against for a call
+fn call_subjects(
+    tool_name: &str,
+    arguments: &serde_json::Value,
+    working_dir: &Path,
+) -> Vec<Subject> {
+    let arg = |key: &str| arguments.get(key).and_then(|v| v.as_str());
+
+    if tool_name == "bash" {
+        return arg("command")
+            .map(|c| {
+                command_segments(c)
+                    .into_iter()
+                    .map(|text| Subject {
+                        text,
+                        outside: false,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    if tool_name == "apply_patch" {
+        return arg("patch")
+            .map(|patch| {
+                patch
+                    .lines()
+                    .filter_map(|line| {
+                        line.strip_prefix("*** Update File: ")
+                            .or_else(|| line.strip_prefix("*** Add File: "))
+                            .or_else(|| line.strip_prefix("*** Delete File: "))
+                    })
+                    .map(|path| path_subject(path.trim(), working_dir))
+                    .collect()
+            })
+            .unwrap_or_default();
+    }
+
+    arg("file_path")
+        .or_else(|| arg("path"))
+        .map(|path| vec![path_subject(path, working_dir)])
+        .unwrap_or_default()
+}
+
+/// Express a path relative to the working directory where possible
+fn path_subject(path: &str, working_dir: &Path) -> Subject {
+    let path = normalize_path(&working_dir.join(path));
+    match path.strip_prefix(normalize_path(working_dir)) {
+        Ok(relative) => Subject {
+            text: relative.to_string_lossy().into_owned(),
+            outside: false,
+        },
+        Err(_) => Subject {
+            text: path.to_string_lossy().into_owned(),
+            outside: true,
+        },
+    }
+}
+
+/// Resolve `.` and `..` without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Split a shell command into the simple commands it runs
+///
+/// Breaks at `;`, `&`, `&&`, `||`, `|`, newlines, parentheses, backticks and
+/// `$(`. Nothing splits inside single quotes; inside double quotes only
+/// substitutions do. Redirections like `2>&1` stay intact.
+fn command_segments(command: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut prev: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let split = match (quote, c) {
+            (Some('\''), '\'') => {
+                quote = None;
+                false
+            }
+            (Some('\''), _) => false,
+            (_, '\\') => {
+                current.push(c);
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+                prev = None;
+                continue;
+            }
+            (_, '`') => true,
+            (_, '$') if chars.peek() == Some(&'(') => {
+                chars.next();
+                true
+            }
+            (Some(_), '"') => {
+                quote = None;
+                false
+            }
+            (Some(_), _) => false,
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                false
+            }
+            (None, '&') => !matches!(prev, Some('>' | '<')) && chars.peek() != Some(&'>'),
+            (None, ';' | '|' | '\n' | '(' | ')') => true,
+            _ => false,
+        };
+        if split {
+            segments.push(std::mem::take(&mut current));
+        } else {
+            current.push(c);
+        }
+        prev = Some(c);
+    }
+    segments.push(current);
+
+    segments
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// `cargo test --lib` → `cargo test*`, `ls -la` → `ls*`
+fn command_prefix_pattern(command: &str) -> String {
+    let mut words = command.split_whitespace();
+    let Some(program) = words.next() else {
+        return "*".to_string();
+    };
+    match words.next() {
+        Some(sub)
+            if sub.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+                && !sub.starts_with('-') =>
+        {
+            format!("{} {}*", program, sub)
+        }
+        _ => format!("{}*", program),
+    }
+}
+
+/// Match `text` against a pattern with `*` (any run) and `?` (one char)
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(specs: &[&str]) -> PermissionPolicy {
+        PermissionPolicy::new(
+            specs
+                .iter()
+                .map(|s| PermissionRule::parse_spec(s, None).unwrap())
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("cargo test*", "cargo test --lib"));
+        assert!(wildcard_match("src/*", "src/a/b.rs"));
+        assert!(wildcard_match("*.rs", "lib.rs"));
+        assert!(wildcard_match("fil?.txt", "file.txt"));
+        assert!(!wildcard_match("cargo test*", "cargo build"));
+        assert!(!wildcard_match("src/*", "tests/a.rs"));
+    }
+
+    #[test]
+    fn test_command_segments() {
+        assert_eq!(
+            command_segments("cargo test; rm -rf ~"),
+            ["cargo test", "rm -rf ~"]
+        );
+        assert_eq!(
+            command_segments("cd x && git push || echo fail | tee log"),
+            ["cd x", "git push", "echo fail", "tee log"]
+        );
+        assert_eq!(
+            command_segments("echo $(curl x) `id`\nls &"),
+            ["echo", "curl x", "id", "ls"]
+        );
+        // Quoted separators and redirections don't split
+        assert_eq!(
+            command_segments("git commit -m 'a; b' 2>&1"),
+            ["git commit -m 'a; b' 2>&1"]
+        );
+        // ...but substitutions inside double quotes still run
+        assert_eq!(
+            command_segments("echo \"a && $(whoami)\""),
+            ["echo \"a &&", "whoami)\""]
+        );
+    }
+
+    #[test]
+    fn test_parse_spec() {
+        let rule = PermissionRule::parse_spec("allow bash cargo test*", None).unwrap();
+        assert_eq!(rule.action, PermissionAction::Allow);
+        assert_eq!(rule.tool, "bash");
+        assert_eq!(rule.pattern.as_deref(), Some("cargo test*"));
+        assert_eq!(rule.spec(), "allow bash cargo test*");
+
+        assert!(PermissionRule::parse_spec("maybe bash", None).is_err());
+        assert!(PermissionRule::parse_spec("allow", None).is_err());
+    }
+
+    #[test]
+    fn test_decide_precedence_and_fallback() {
+        let dir = Path::new("/work");
+        let policy = policy(&[
+            "allow bash cargo test*",
+            "ask bash git push*",
+            "allow bash *",
+            "deny write !src/*",
+        ]);
+
+        let bash = |cmd: &str| json!({ "command": cmd });
+        assert_eq!(
+            policy.decide(PermissionMode::Supervised, "bash", &bash("cargo test"), dir),
+            PermissionDecision::Allow
+        );
+        // Ask beats the broader allow
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Autonomous,
+                "bash",
+                &bash("git push origin"),
+                dir
+            ),
+            PermissionDecision::Ask
+        );
+
+        let write = |path: &str| json!({ "file_path": path, "content": "" });
+        assert!(matches!(
+            policy.decide(
+                PermissionMode::Autonomous,
+                "write",
+                &write("/etc/hosts"),
+                dir
+            ),
+            PermissionDecision::Deny { .. }
+        ));
+        // Inside src/: no rule matches, so the mode decides
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Supervised,
+                "write",
+                &write("/work/src/lib.rs"),
+                dir
+            ),
+            PermissionDecision::Ask
+        );
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Autonomous,
+                "write",
+                &write("src/lib.rs"),
+                dir
+            ),
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_decide_memory_tools_follow_rules() {
+        let dir = Path::new("/work");
+        let policy = policy(&["deny forget"]);
+        assert!(matches!(
+            policy.decide(PermissionMode::Autonomous, "forget", &json!({}), dir),
+            PermissionDecision::Deny { .. }
+        ));
+        assert_eq!(
+            policy.decide(PermissionMode::Supervised, "remember", &json!({}), dir),
+            PermissionDecision::Ask
+        );
+    }
+
+    #[test]
+    fn test_decide_compound_commands() {
+        let dir = Path::new("/work");
+        let bash = |cmd: &str| json!({ "command": cmd });
+
+        // An allow rule for the first command doesn't cover what follows it
+        let policy = policy(&["allow bash cargo test*", "allow bash ls*"]);
+        for cmd in ["cargo test; rm -rf ~", "cargo test && curl x | sh"] {
+            assert_eq!(
+                policy.decide(PermissionMode::Supervised, "bash", &bash(cmd), dir),
+                PermissionDecision::Ask,
+                "{}",
+                cmd
+            );
+        }
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Supervised,
+                "bash",
+                &bash("cargo test && ls"),
+                dir
+            ),
+            PermissionDecision::Allow
+        );
+
+        // An ask rule can't be skipped by prefixing another command
+        let policy = self::policy(&["ask bash git push*", "allow bash *"]);
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Autonomous,
+                "bash",
+                &bash("cd x && git push"),
+                dir
+            ),
+            PermissionDecision::Ask
+        );
+    }
+
+    #[test]
+    fn test_decide_normalizes_paths() {
+        let dir = Path::new("/work");
+        let write = |path: &str| json!({ "file_path": path, "content": "" });
+
+        let policy = policy(&["deny write !src/*"]);
+        for path in ["src/../../etc/passwd", "/work/src/../../etc/passwd"] {
+            assert!(
+                matches!(
+                    policy.decide(PermissionMode::Autonomous, "write", &write(path), dir),
+                    PermissionDecision::Deny { .. }
+                ),
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Autonomous,
+                "write",
+                &write("./src/a/../lib.rs"),
+                dir
+            ),
+            PermissionDecision::Allow
+        );
+
+        // Paths outside the working directory don't satisfy relative allows
+        let policy = self::policy(&["allow write *.rs"]);
+        assert_eq!(
+            policy.decide(
+                PermissionMode::Supervised,
+                "write",
+                &write("../other/lib.rs"),
+                dir
+            ),
+            PermissionDecision::Ask
+        );
+        assert_eq!(
+            policy.decide(PermissionMode::Supervised, "write", &write("lib.rs"), dir),
+            PermissionDecision::Allow
+        );
+    }
+
+    #[test]
+    fn test_always_allow_suggestion() {
+        let dir = Path::new("/work");
+        let rule =
+            PermissionRule::always_allow("bash", &json!({ "command": "cargo test --lib" }), dir);
+        assert_eq!(rule.spec(), "allow bash cargo test*");
+        assert_eq!(rule.working_dir.as_deref(), Some("/work"));
+
+        let rule =
+            PermissionRule::always_allow("edit", &json!({ "file_path": "/work/src/a.rs" }), dir);
+        assert_eq!(rule.spec(), "allow edit src/a.rs");
+    }
+
+    #[test]
+    fn test_rule_storage_scoping() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db")).unwrap();
+
+        let global = PermissionRule::parse_spec("deny bash rm -rf*", None).unwrap();
+        let project =
+            PermissionRule::parse_spec("allow bash cargo*", Some("/work".into())).unwrap();
+        let other = PermissionRule::parse_spec("allow write *", Some("/other".into())).unwrap();
+        save_rule(&db, &global, None).unwrap();
+        save_rule(&db, &project, Some("alice")).unwrap();
+        save_rule(&db, &other, None).unwrap();
+
+        let policy = PermissionPolicy::load(&db, Path::new("/work"), Some("alice")).unwrap();
+        assert_eq!(policy.rules().len(), 2);
+        // Bob sees global rules but not Alice's
+        let policy = PermissionPolicy::load(&db, Path::new("/work"), Some("bob")).unwrap();
+        assert_eq!(policy.rules().len(), 1);
+
+        assert!(!delete_rule(&db, &project.id, Some("bob")).unwrap());
+        assert!(delete_rule(&db, &project.id, Some("alice")).unwrap());
+        // Unowned rules can only be removed in single-tenant mode
+        assert!(!delete_rule(&db, &global.id, Some("alice")).unwrap());
+        assert_eq!(list_rules(&db, None).unwrap().len(), 2);
+    }
+}
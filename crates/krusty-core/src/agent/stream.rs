@@ -10,7 +10,7 @@ use std::time::Duration;
 use tokio::sync::mpsc;
 
 use crate::ai::streaming::StreamPart;
-use crate::ai::types::AiToolCall;
+use crate::ai::types::{AiToolCall, Usage};
 
 use super::loop_events::LoopEvent;
 
@@ -28,6 +28,8 @@ pub(crate) struct StreamResult {
     pub thinking_blocks: Vec<ThinkingBlock>,
     pub tool_calls: Vec<AiToolCall>,
     pub total_tokens: usize,
+    /// Final usage for the call (providers may report it in several snapshots)
+    pub usage: Usage,
 }
 
 /// Process an AI streaming response, emitting LoopEvents as chunks arrive.
@@ -41,6 +43,7 @@ pub(crate) async fn process_stream(
     let mut thinking_blocks = Vec::new();
     let mut tool_calls = Vec::new();
     let mut total_tokens = 0usize;
+    let mut call_usage = Usage::default();
 
     loop {
         let part = match tokio::time::timeout(STREAM_TIMEOUT, api_rx.recv()).await {
@@ -95,6 +98,7 @@ pub(crate) async fn process_stream(
                 });
             }
             StreamPart::Usage { usage } => {
+                merge_usage(&mut call_usage, usage);
                 total_tokens = usage.prompt_tokens + usage.completion_tokens;
                 let _ = event_tx.send(LoopEvent::Usage {
                     prompt_tokens: total_tokens,
@@ -161,5 +165,57 @@ pub(crate) async fn process_stream(
         thinking_blocks,
         tool_calls,
         total_tokens,
+        usage: call_usage,
+    }
+}
+
+/// Fold a usage snapshot into the call's running usage.
+///
+/// Snapshots are cumulative but may be partial (Anthropic reports input
+/// tokens on `message_start` and output tokens on `message_delta`), so each
+/// field keeps its largest reported value.
+fn merge_usage(acc: &mut Usage, usage: &Usage) {
+    acc.prompt_tokens = acc.prompt_tokens.max(usage.prompt_tokens);
+    acc.completion_tokens = acc.completion_tokens.max(usage.completion_tokens);
+    acc.cache_creation_input_tokens = acc
+        .cache_creation_input_tokens
+        .max(usage.cache_creation_input_tokens);
+    acc.cache_read_input_tokens = acc
+        .cache_read_input_tokens
+        .max(usage.cache_read_input_tokens);
+    acc.total_tokens = acc.prompt_tokens + acc.completion_tokens;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_usage_keeps_largest_snapshot() {
+        let mut acc = Usage::default();
+        merge_usage(
+            &mut acc,
+            &Usage {
+                prompt_tokens: 1200,
+                completion_tokens: 1,
+                total_tokens: 1201,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 1000,
+            },
+        );
+        merge_usage(
+            &mut acc,
+            &Usage {
+                prompt_tokens: 200,
+                completion_tokens: 350,
+                total_tokens: 550,
+                cache_creation_input_tokens: 0,
+                cache_read_input_tokens: 0,
+            },
+        );
+        assert_eq!(acc.prompt_tokens, 1200);
+        assert_eq!(acc.completion_tokens, 350);
+        assert_eq!(acc.cache_read_input_tokens, 1000);
+        assert_eq!(acc.total_tokens, 1550);
     }
 }
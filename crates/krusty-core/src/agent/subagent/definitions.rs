@@ -0,0 +1,284 @@
+//! User-defined sub-agent types
+//!
+//! Agent types are markdown files in `~/.krusty/agents/` (global) and
+//! `.krusty/agents/` (project). The YAML frontmatter describes the agent and
+//! the body becomes its system prompt:
+//!
+//! ```yaml
+//! ---
+//! name: reviewer
+//! description: Reviews changes for bugs and style problems
+//! tools: [glob, grep, read]
+//! model: claude-sonnet-4-5
+//! max_turns: 20
+//! ---
+//!
+//! You are a meticulous code reviewer...
+//! ```
+//!
+//! Project definitions override global ones with the same name.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+use crate::agent::constants::subagent;
+
+/// Tools a user-defined agent may be granted
+pub const AGENT_TOOLS: &[&str] = &["glob", "grep", "read", "write", "edit", "bash"];
+
+/// Tools granted when a definition lists none (read-only)
+const DEFAULT_TOOLS: &[&str] = &["glob", "grep", "read"];
+
+/// Where the agent definition comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AgentSource {
+    /// Global agents from ~/.krusty/agents/
+    Global,
+    /// Project-specific agents from .krusty/agents/
+    Project,
+}
+
+/// YAML frontmatter of an agent definition
+#[derive(Debug, Clone, Deserialize)]
+struct AgentFrontmatter {
+    name: String,
+    description: String,
+    #[serde(default)]
+    tools: Vec<String>,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    max_turns: Option<usize>,
+}
+
+/// A user-defined sub-agent type
+#[derive(Debug, Clone)]
+pub struct AgentDefinition {
+    pub name: String,
+    pub description: String,
+    /// Tools the agent may use, a subset of [`AGENT_TOOLS`]
+    pub tools: Vec<String>,
+    /// Model override; the pool's model is used when unset or when the
+    /// pool's provider does not serve it
+    pub model: Option<String>,
+    /// Turn limit, capped at the sub-agent maximum
+    pub max_turns: usize,
+    /// System prompt (the markdown body)
+    pub prompt: String,
+    pub source: AgentSource,
+    /// Path to the definition file
+    pub path: PathBuf,
+}
+
+impl AgentDefinition {
+    /// Parse a definition file's content
+    pub fn parse(content: &str, path: PathBuf, source: AgentSource) -> Result<Self> {
+        let content = content.trim();
+        if !content.starts_with("---") {
+            return Err(anyhow!(
+                "Agent definition must start with YAML frontmatter (---)"
+            ));
+        }
+        let rest = &content[3..];
+        let end_pos = rest
+            .find("\n---")
+            .ok_or_else(|| anyhow!("Missing closing frontmatter delimiter (---)"))?;
+        let frontmatter: AgentFrontmatter = serde_yaml::from_str(rest[..end_pos].trim())
+            .map_err(|e| anyhow!("Failed to parse agent frontmatter: {}", e))?;
+        let prompt = rest[end_pos + 4..].trim().to_string();
+
+        if frontmatter.name.is_empty() {
+            return Err(anyhow!("Agent name cannot be empty"));
+        }
+        if !frontmatter
+            .name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        {
+            return Err(anyhow!(
+                "Agent name must contain only lowercase letters, numbers, and hyphens"
+            ));
+        }
+        if frontmatter.description.is_empty() {
+            return Err(anyhow!("Agent description cannot be empty"));
+        }
+        if prompt.is_empty() {
+            return Err(anyhow!("Agent definition has no system prompt"));
+        }
+        if let Some(unknown) = frontmatter
+            .tools
+            .iter()
+            .find(|t| !AGENT_TOOLS.contains(&t.as_str()))
+        {
+            return Err(anyhow!(
+                "Unknown tool '{}' (available: {})",
+                unknown,
+                AGENT_TOOLS.join(", ")
+            ));
+        }
+
+        let mut tools = if frontmatter.tools.is_empty() {
+            DEFAULT_TOOLS.iter().map(|t| t.to_string()).collect()
+        } else {
+            frontmatter.tools
+        };
+        tools.dedup();
+
+        Ok(Self {
+            name: frontmatter.name,
+            description: frontmatter.description,
+            tools,
+            model: frontmatter.model.filter(|m| !m.is_empty()),
+            max_turns: frontmatter
+                .max_turns
+                .unwrap_or(subagent::MAX_TURNS)
+                .clamp(1, subagent::MAX_TURNS),
+            prompt,
+            source,
+            path,
+        })
+    }
+
+    /// Whether the agent may modify files or run commands
+    pub fn can_write(&self) -> bool {
+        self.tools
+            .iter()
+            .any(|t| matches!(t.as_str(), "write" | "edit" | "bash"))
+    }
+}
+
+/// Load all agent definitions visible from `working_dir`, sorted by name
+pub fn load_agent_definitions(working_dir: &Path) -> Vec<AgentDefinition> {
+    let global_dir = dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".krusty")
+        .join("agents");
+    let project_dir = working_dir.join(".krusty").join("agents");
+
+    let mut definitions: HashMap<String, AgentDefinition> = HashMap::new();
+    for definition in load_definitions_from_dir(&global_dir, AgentSource::Global) {
+        definitions.insert(definition.name.clone(), definition);
+    }
+    // Project definitions override global ones
+    for definition in load_definitions_from_dir(&project_dir, AgentSource::Project) {
+        definitions.insert(definition.name.clone(), definition);
+    }
+
+    let mut definitions: Vec<AgentDefinition> = definitions.into_values().collect();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions
+}
+
+/// Load the `*.md` agent definitions in a directory
+pub fn load_definitions_from_dir(dir: &Path, source: AgentSource) -> Vec<AgentDefinition> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut definitions = Vec::new();
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some("md") {
+            continue;
+        }
+        let parsed = fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|content| AgentDefinition::parse(&content, path.clone(), source));
+        match parsed {
+            Ok(definition) => {
+                debug!(
+                    "Loaded agent definition: {} from {:?}",
+                    definition.name, path
+                );
+                definitions.push(definition);
+            }
+            Err(e) => {
+                debug!("Failed to load agent definition from {:?}: {}", path, e);
+            }
+        }
+    }
+
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+    definitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_definition() {
+        let content = r#"---
+name: test-writer
+description: Writes unit tests for a module
+tools: [glob, grep, read, write, edit]
+model: claude-sonnet-4-5
+max_turns: 20
+---
+
+You write focused unit tests.
+"#;
+
+        let definition =
+            AgentDefinition::parse(content, PathBuf::from("/a.md"), AgentSource::Project).unwrap();
+        assert_eq!(definition.name, "test-writer");
+        assert_eq!(definition.tools.len(), 5);
+        assert_eq!(definition.model.as_deref(), Some("claude-sonnet-4-5"));
+        assert_eq!(definition.max_turns, 20);
+        assert_eq!(definition.prompt, "You write focused unit tests.");
+        assert!(definition.can_write());
+    }
+
+    #[test]
+    fn test_parse_defaults_to_read_only() {
+        let content =
+            "---\nname: reviewer\ndescription: Reviews code\nmax_turns: 500\n---\nReview.";
+        let definition =
+            AgentDefinition::parse(content, PathBuf::from("/a.md"), AgentSource::Global).unwrap();
+        assert_eq!(definition.tools, vec!["glob", "grep", "read"]);
+        assert_eq!(definition.max_turns, subagent::MAX_TURNS);
+        assert!(definition.model.is_none());
+        assert!(!definition.can_write());
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_definitions() {
+        let path = PathBuf::from("/a.md");
+        for content in [
+            "No frontmatter",
+            "---\nname: Bad Name\ndescription: x\n---\nPrompt",
+            "---\nname: ok\ndescription: x\ntools: [web_fetch]\n---\nPrompt",
+            "---\nname: ok\ndescription: x\n---\n",
+        ] {
+            assert!(
+                AgentDefinition::parse(content, path.clone(), AgentSource::Global).is_err(),
+                "accepted: {}",
+                content
+            );
+        }
+    }
+
+    #[test]
+    fn test_project_definitions_override_global() {
+        let dir = tempfile::tempdir().unwrap();
+        let agents = dir.path().join(".krusty").join("agents");
+        fs::create_dir_all(&agents).unwrap();
+        fs::write(
+            agents.join("doc-writer.md"),
+            "---\nname: doc-writer\ndescription: Writes docs\ntools: [read, edit]\n---\nDocument.",
+        )
+        .unwrap();
+        fs::write(agents.join("notes.txt"), "ignored").unwrap();
+
+        let loaded = load_definitions_from_dir(&agents, AgentSource::Project);
+        assert_eq!(loaded.len(), 1);
+
+        let all = load_agent_definitions(dir.path());
+        let doc_writer = all.iter().find(|d| d.name == "doc-writer").unwrap();
+        assert_eq!(doc_writer.source, AgentSource::Project);
+    }
+}
@@ -1,6 +1,6 @@
 //! Sub-agent execution loop
 //!
-//! Unified agentic loop for both explorer and builder agents.
+//! Unified agentic loop for explorer, builder, and user-defined agents.
 
 use serde_json::{json, Value};
 use std::sync::Arc;
@@ -17,7 +17,8 @@ use crate::ai::retry::{with_retry, RetryConfig};
 use crate::ai::types::{AiTool, Content, ModelMessage, Role};
 use crate::tools::registry::{ToolContext, ToolResult};
 
-use super::tools::{BuilderTools, SubAgentTools};
+use super::definitions::AgentDefinition;
+use super::tools::{BuilderTools, CustomAgentTools, SubAgentTools};
 use super::types::{
     AgentProgress, AgentProgressStatus, SubAgentApiError, SubAgentResult, SubAgentTask, ToolCall,
 };
@@ -39,6 +40,11 @@ pub(crate) trait AgentConfig: Send + Sync {
     /// Max tokens for API calls
     fn max_tokens(&self) -> usize;
 
+    /// Turn limit before the agent is forced to finish
+    fn max_turns(&self) -> usize {
+        subagent::MAX_TURNS
+    }
+
     /// Get tool definitions for AI
     fn get_ai_tools(&self) -> Vec<AiTool>;
 
@@ -226,6 +232,76 @@ impl AgentConfig for BuilderConfig {
     }
 }
 
+/// User-defined agent configuration - prompt and tools from its definition
+pub(crate) struct CustomAgentConfig {
+    task: SubAgentTask,
+    definition: Arc<AgentDefinition>,
+    tools: CustomAgentTools,
+}
+
+impl CustomAgentConfig {
+    pub fn new(task: SubAgentTask, definition: Arc<AgentDefinition>) -> Self {
+        Self {
+            task,
+            tools: CustomAgentTools::new(definition.tools.clone()),
+            definition,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl AgentConfig for CustomAgentConfig {
+    fn system_prompt(&self, _turn: usize) -> String {
+        format!(
+            "{}\n\n## Working Directory\n{}\n\n## Available Tools\n{}",
+            self.definition.prompt,
+            self.task.working_dir.display(),
+            self.definition.tools.join(", ")
+        )
+    }
+
+    fn timeout_secs(&self) -> u64 {
+        if self.definition.can_write() {
+            120
+        } else {
+            30
+        }
+    }
+
+    fn api_call_timeout(&self) -> Duration {
+        if self.definition.can_write() {
+            crate::agent::constants::timeouts::BUILDER_API_CALL
+        } else {
+            crate::agent::constants::timeouts::EXPLORER_API_CALL
+        }
+    }
+
+    fn max_tokens(&self) -> usize {
+        16384
+    }
+
+    fn max_turns(&self) -> usize {
+        self.definition.max_turns
+    }
+
+    fn get_ai_tools(&self) -> Vec<AiTool> {
+        self.tools.get_ai_tools()
+    }
+
+    async fn execute_tool(
+        &self,
+        name: &str,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Option<ToolResult> {
+        self.tools.execute(name, params, ctx).await
+    }
+
+    fn update_progress(&self, _progress: &mut AgentProgress) {}
+
+    fn cleanup(&self) {}
+}
+
 /// Unified agentic loop - replaces separate explorer/builder implementations
 pub(crate) async fn execute_agent_loop<C: AgentConfig>(
     client: &AiClient,
@@ -245,6 +321,7 @@ pub(crate) async fn execute_agent_loop<C: AgentConfig>(
     let ctx = ToolContext {
         working_dir: task.working_dir.clone(),
         timeout: Some(Duration::from_secs(config.timeout_secs())),
+        checkpointer: task.checkpointer.clone(),
         ..Default::default()
     };
 
@@ -317,11 +394,11 @@ pub(crate) async fn execute_agent_loop<C: AgentConfig>(
         turns += 1;
 
         // Enforce max turn limit to prevent infinite loops
-        if turns > subagent::MAX_TURNS {
+        if turns > config.max_turns() {
             warn!(
                 task_id = %task_id,
                 turns = turns,
-                max_turns = subagent::MAX_TURNS,
+                max_turns = config.max_turns(),
                 "Sub-agent exceeded max turns, forcing completion"
             );
             send_progress(
@@ -770,3 +847,16 @@ pub(crate) async fn execute_builder_with_progress(
     )
     .await
 }
+
+/// Execute a user-defined agent, with progress reporting if a channel is given
+pub(crate) async fn execute_custom_agent(
+    client: &AiClient,
+    task: SubAgentTask,
+    definition: Arc<AgentDefinition>,
+    model: &str,
+    cancellation: CancellationToken,
+    progress_tx: Option<mpsc::UnboundedSender<AgentProgress>>,
+) -> SubAgentResult {
+    let config = CustomAgentConfig::new(task.clone(), definition);
+    execute_agent_loop(client, &task, model, cancellation, &config, progress_tx).await
+}
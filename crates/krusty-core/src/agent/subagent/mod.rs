@@ -10,17 +10,22 @@
 //!
 //! ## Module Structure
 //! - `types`: Core data types (progress, models, tasks, results)
-//! - `tools`: Tool implementations for explorers and builders
+//! - `definitions`: User-defined agent types from `.krusty/agents/*.md`
+//! - `tools`: Tool implementations for explorers, builders and user-defined agents
 //! - `execution`: Agent loop and API communication
 
+mod definitions;
 mod execution;
 mod tools;
 mod types;
 
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::{mpsc, Semaphore};
+use tokio::task::JoinHandle;
 use tokio::time::{sleep, timeout};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, warn};
 
 /// Timeout for acquiring semaphore permit (prevents deadlock on hung agents)
@@ -34,8 +39,12 @@ use crate::agent::build_context::SharedBuildContext;
 use crate::agent::cache::SharedExploreCache;
 use crate::agent::AgentCancellation;
 use crate::ai::client::AiClient;
+use crate::ai::providers::get_provider;
 
 // Re-export public types
+pub use definitions::{
+    load_agent_definitions, load_definitions_from_dir, AgentDefinition, AgentSource, AGENT_TOOLS,
+};
 pub use tools::BuilderTools;
 pub use types::{
     AgentProgress, AgentProgressStatus, SubAgentApiError, SubAgentResult, SubAgentTask,
@@ -43,7 +52,8 @@ pub use types::{
 
 // Internal execution functions
 use execution::{
-    execute_builder_with_progress, execute_subagent_with_progress, execute_subagent_with_tools,
+    execute_builder_with_progress, execute_custom_agent, execute_subagent_with_progress,
+    execute_subagent_with_tools,
 };
 
 /// Pool for managing concurrent sub-agent execution
@@ -103,15 +113,37 @@ impl SubAgentPool {
             .unwrap_or_else(|| self.client.config().model.clone())
     }
 
+    /// Model for a user-defined agent
+    ///
+    /// A definition's model is only used when the pool's provider serves it;
+    /// otherwise the agent falls back to the pool's model.
+    fn resolve_definition_model(&self, definition: &AgentDefinition) -> String {
+        let fallback = self.resolve_model();
+        let Some(model) = definition.model.as_deref() else {
+            return fallback;
+        };
+        let provider = self.client.config().provider_id;
+        match get_provider(provider) {
+            Some(config) if !config.has_model(model) => {
+                warn!(
+                    agent = %definition.name,
+                    "Agent model '{}' is not available for {}, using '{}'",
+                    model,
+                    provider,
+                    fallback
+                );
+                fallback
+            }
+            _ => model.to_string(),
+        }
+    }
+
     /// Execute multiple sub-agent tasks concurrently with staggered spawning
     ///
     /// Agents are spawned with small delays between them to avoid rate limit storms.
     /// The stagger delay is provider-specific (lower for Anthropic, higher for others).
     pub async fn execute(&self, tasks: Vec<SubAgentTask>) -> Vec<SubAgentResult> {
         let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
-        let client = self.client.clone();
-        let cancellation = self.cancellation.clone();
-        let cache = self.cache.clone();
         let task_count = tasks.len();
         let stagger = self.stagger_delay;
 
@@ -131,94 +163,32 @@ impl SubAgentPool {
                 sleep(stagger).await;
             }
 
-            let sem = semaphore.clone();
-            let client = client.clone();
-            let cancel = cancellation.child_token();
-            let cache = cache.clone();
+            let client = self.client.clone();
+            let cancel = self.cancellation.child_token();
+            let cache = self.cache.clone();
             let task_id = task.id.clone();
             let resolved_model = self.resolve_model();
 
-            let handle = tokio::spawn(async move {
-                debug!(task_id = %task_id, "SubAgent: Acquiring semaphore permit");
-                let _permit = match timeout(SEMAPHORE_TIMEOUT, sem.acquire()).await {
-                    Ok(Ok(p)) => p,
-                    Ok(Err(e)) => {
-                        warn!(task_id = %task_id, error = %e, "SubAgent: Failed to acquire semaphore");
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!("Semaphore error: {}", e)),
-                        };
-                    }
-                    Err(_) => {
-                        warn!(task_id = %task_id, "SubAgent: Semaphore acquire timed out after {:?}", SEMAPHORE_TIMEOUT);
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!(
-                                "Semaphore acquire timed out after {:?}",
-                                SEMAPHORE_TIMEOUT
-                            )),
-                        };
-                    }
-                };
-                debug!(task_id = %task_id, "SubAgent: Got permit, checking cancellation");
-
-                if cancel.is_cancelled() {
-                    info!(task_id = %task_id, "SubAgent: Cancelled before execution");
-                    return SubAgentResult {
-                        task_id,
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some("Cancelled".to_string()),
-                    };
-                }
-
-                info!(task_id = %task_id, model = %resolved_model, "SubAgent: Starting execution");
-                let result =
-                    execute_subagent_with_tools(&client, task, &resolved_model, cancel, cache)
-                        .await;
-                info!(task_id = %result.task_id, success = result.success, "SubAgent: Execution complete");
-                result
-            });
-
-            handles.push(handle);
+            handles.push(spawn_limited(
+                semaphore.clone(),
+                cancel.clone(),
+                task_id.clone(),
+                "SubAgent",
+                async move {
+                    info!(task_id = %task_id, model = %resolved_model, "SubAgent: Starting execution");
+                    let result =
+                        execute_subagent_with_tools(&client, task, &resolved_model, cancel, cache)
+                            .await;
+                    info!(task_id = %result.task_id, success = result.success, "SubAgent: Execution complete");
+                    result
+                },
+            ));
         }
 
         info!("SubAgentPool: Waiting for {} spawned tasks", handles.len());
+        let results = collect_results(handles, "SubAgent").await;
 
-        // Collect results from all spawned tasks
-        let mut results = Vec::with_capacity(handles.len());
-        for handle in handles {
-            match handle.await {
-                Ok(result) => results.push(result),
-                Err(e) => {
-                    warn!("SubAgent task panicked: {}", e);
-                    results.push(SubAgentResult {
-                        task_id: "unknown".to_string(),
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some(format!("Task panicked: {}", e)),
-                    });
-                }
-            }
-        }
-
-        let stats = cache.stats();
+        let stats = self.cache.stats();
         info!(
             "SubAgentPool: All futures complete, {} results | {}",
             results.len(),
@@ -234,9 +204,6 @@ impl SubAgentPool {
         progress_tx: mpsc::UnboundedSender<AgentProgress>,
     ) -> Vec<SubAgentResult> {
         let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
-        let client = self.client.clone();
-        let cancellation = self.cancellation.clone();
-        let cache = self.cache.clone();
         let task_count = tasks.len();
         let stagger = self.stagger_delay;
 
@@ -256,93 +223,34 @@ impl SubAgentPool {
                 sleep(stagger).await;
             }
 
-            let sem = semaphore.clone();
-            let client = client.clone();
-            let cancel = cancellation.child_token();
-            let cache = cache.clone();
-            let task_id = task.id.clone();
+            let client = self.client.clone();
+            let cancel = self.cancellation.child_token();
+            let cache = self.cache.clone();
             let progress_tx = progress_tx.clone();
             let resolved_model = self.resolve_model();
 
-            let handle = tokio::spawn(async move {
-                let _permit = match timeout(SEMAPHORE_TIMEOUT, sem.acquire()).await {
-                    Ok(Ok(p)) => p,
-                    Ok(Err(e)) => {
-                        warn!(task_id = %task_id, error = %e, "SubAgent: Failed to acquire semaphore");
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!("Semaphore error: {}", e)),
-                        };
-                    }
-                    Err(_) => {
-                        warn!(task_id = %task_id, "SubAgent: Semaphore acquire timed out after {:?}", SEMAPHORE_TIMEOUT);
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!(
-                                "Semaphore acquire timed out after {:?}",
-                                SEMAPHORE_TIMEOUT
-                            )),
-                        };
-                    }
-                };
-
-                if cancel.is_cancelled() {
-                    return SubAgentResult {
-                        task_id,
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some("Cancelled".to_string()),
-                    };
-                }
-
-                execute_subagent_with_progress(
-                    &client,
-                    task,
-                    &resolved_model,
-                    cancel,
-                    cache,
-                    progress_tx,
-                )
-                .await
-            });
-
-            handles.push(handle);
+            handles.push(spawn_limited(
+                semaphore.clone(),
+                cancel.clone(),
+                task.id.clone(),
+                "SubAgent",
+                async move {
+                    execute_subagent_with_progress(
+                        &client,
+                        task,
+                        &resolved_model,
+                        cancel,
+                        cache,
+                        progress_tx,
+                    )
+                    .await
+                },
+            ));
         }
 
-        // Collect results
-        let mut results = Vec::with_capacity(handles.len());
-        for handle in handles {
-            match handle.await {
-                Ok(result) => results.push(result),
-                Err(e) => {
-                    warn!("SubAgent task panicked: {}", e);
-                    results.push(SubAgentResult {
-                        task_id: "unknown".to_string(),
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some(format!("Task panicked: {}", e)),
-                    });
-                }
-            }
-        }
+        let results = collect_results(handles, "SubAgent").await;
 
-        let stats = cache.stats();
+        let stats = self.cache.stats();
         info!("SubAgentPool: Complete | {}", stats);
         results
     }
@@ -355,8 +263,6 @@ impl SubAgentPool {
         progress_tx: mpsc::UnboundedSender<AgentProgress>,
     ) -> Vec<SubAgentResult> {
         let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
-        let client = self.client.clone();
-        let cancellation = self.cancellation.clone();
         let task_count = tasks.len();
         let stagger = self.stagger_delay;
 
@@ -376,94 +282,248 @@ impl SubAgentPool {
                 sleep(stagger).await;
             }
 
-            let sem = semaphore.clone();
-            let client = client.clone();
-            let cancel = cancellation.child_token();
+            let client = self.client.clone();
+            let cancel = self.cancellation.child_token();
             let context = context.clone();
-            let task_id = task.id.clone();
             let progress_tx = progress_tx.clone();
             let resolved_model = self.resolve_model();
 
-            let handle = tokio::spawn(async move {
-                let _permit = match timeout(SEMAPHORE_TIMEOUT, sem.acquire()).await {
-                    Ok(Ok(p)) => p,
-                    Ok(Err(e)) => {
-                        warn!(task_id = %task_id, error = %e, "Builder: Failed to acquire semaphore");
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!("Semaphore error: {}", e)),
-                        };
-                    }
-                    Err(_) => {
-                        warn!(task_id = %task_id, "Builder: Semaphore acquire timed out after {:?}", SEMAPHORE_TIMEOUT);
-                        return SubAgentResult {
-                            task_id,
-                            success: false,
-                            output: String::new(),
-                            files_examined: vec![],
-                            duration_ms: 0,
-                            turns_used: 0,
-                            error: Some(format!(
-                                "Semaphore acquire timed out after {:?}",
-                                SEMAPHORE_TIMEOUT
-                            )),
-                        };
-                    }
-                };
-
-                if cancel.is_cancelled() {
-                    return SubAgentResult {
-                        task_id,
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some("Cancelled".to_string()),
-                    };
-                }
-
-                execute_builder_with_progress(
-                    &client,
-                    task,
-                    &resolved_model,
-                    cancel,
-                    context,
-                    progress_tx,
-                )
-                .await
-            });
-
-            handles.push(handle);
+            handles.push(spawn_limited(
+                semaphore.clone(),
+                cancel.clone(),
+                task.id.clone(),
+                "Builder",
+                async move {
+                    execute_builder_with_progress(
+                        &client,
+                        task,
+                        &resolved_model,
+                        cancel,
+                        context,
+                        progress_tx,
+                    )
+                    .await
+                },
+            ));
         }
 
-        // Collect results
-        let mut results = Vec::with_capacity(handles.len());
-        for handle in handles {
-            match handle.await {
-                Ok(result) => results.push(result),
-                Err(e) => {
-                    warn!("Builder task panicked: {}", e);
-                    results.push(SubAgentResult {
-                        task_id: "unknown".to_string(),
-                        success: false,
-                        output: String::new(),
-                        files_examined: vec![],
-                        duration_ms: 0,
-                        turns_used: 0,
-                        error: Some(format!("Task panicked: {}", e)),
-                    });
-                }
-            }
-        }
+        let results = collect_results(handles, "Builder").await;
 
         let stats = context.stats();
         info!("SubAgentPool: Builders complete | {}", stats);
         results
     }
+
+    /// Execute tasks on user-defined agents with staggered spawning
+    ///
+    /// Each task runs on its paired definition, using the definition's model
+    /// when the pool's provider serves it.
+    pub async fn execute_defined(
+        &self,
+        tasks: Vec<(Arc<AgentDefinition>, SubAgentTask)>,
+        progress_tx: Option<mpsc::UnboundedSender<AgentProgress>>,
+    ) -> Vec<SubAgentResult> {
+        let semaphore = Arc::new(Semaphore::new(self.max_concurrency));
+        let task_count = tasks.len();
+        let stagger = self.stagger_delay;
+
+        info!(
+            count = task_count,
+            concurrency = self.max_concurrency,
+            stagger_ms = stagger.as_millis() as u64,
+            "SubAgentPool: Spawning user-defined agents with stagger"
+        );
+
+        let mut handles = Vec::with_capacity(task_count);
+
+        for (idx, (definition, task)) in tasks.into_iter().enumerate() {
+            // Stagger delay between spawns (skip first)
+            if idx > 0 && !stagger.is_zero() {
+                sleep(stagger).await;
+            }
+
+            let client = self.client.clone();
+            let cancel = self.cancellation.child_token();
+            let task_id = task.id.clone();
+            let progress_tx = progress_tx.clone();
+            let resolved_model = self.resolve_definition_model(&definition);
+
+            handles.push(spawn_limited(
+                semaphore.clone(),
+                cancel.clone(),
+                task_id.clone(),
+                "Agent",
+                async move {
+                    info!(task_id = %task_id, agent = %definition.name, model = %resolved_model, "Agent: Starting execution");
+                    execute_custom_agent(
+                        &client,
+                        task,
+                        definition,
+                        &resolved_model,
+                        cancel,
+                        progress_tx,
+                    )
+                    .await
+                },
+            ));
+        }
+
+        let results = collect_results(handles, "Agent").await;
+
+        info!("SubAgentPool: User-defined agents complete");
+        results
+    }
+}
+
+/// Spawn `run` once a concurrency permit is free, unless cancelled first
+///
+/// `label` prefixes log lines so each pool method keeps its own wording.
+fn spawn_limited<F>(
+    semaphore: Arc<Semaphore>,
+    cancel: CancellationToken,
+    task_id: String,
+    label: &'static str,
+    run: F,
+) -> JoinHandle<SubAgentResult>
+where
+    F: Future<Output = SubAgentResult> + Send + 'static,
+{
+    tokio::spawn(async move {
+        debug!(task_id = %task_id, "{}: Acquiring semaphore permit", label);
+        let _permit = match timeout(SEMAPHORE_TIMEOUT, semaphore.acquire()).await {
+            Ok(Ok(p)) => p,
+            Ok(Err(e)) => {
+                warn!(task_id = %task_id, error = %e, "{}: Failed to acquire semaphore", label);
+                return failed_result(task_id, format!("Semaphore error: {}", e));
+            }
+            Err(_) => {
+                warn!(task_id = %task_id, "{}: Semaphore acquire timed out after {:?}", label, SEMAPHORE_TIMEOUT);
+                return failed_result(
+                    task_id,
+                    format!("Semaphore acquire timed out after {:?}", SEMAPHORE_TIMEOUT),
+                );
+            }
+        };
+
+        if cancel.is_cancelled() {
+            info!(task_id = %task_id, "{}: Cancelled before execution", label);
+            return failed_result(task_id, "Cancelled");
+        }
+
+        run.await
+    })
+}
+
+/// Wait for spawned agents, turning panics into failed results
+async fn collect_results(
+    handles: Vec<JoinHandle<SubAgentResult>>,
+    label: &str,
+) -> Vec<SubAgentResult> {
+    let mut results = Vec::with_capacity(handles.len());
+    for handle in handles {
+        match handle.await {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                warn!("{} task panicked: {}", label, e);
+                results.push(failed_result(
+                    "unknown".to_string(),
+                    format!("Task panicked: {}", e),
+                ));
+            }
+        }
+    }
+    results
+}
+
+/// Result for an agent that never got to run
+fn failed_result(task_id: String, error: impl Into<String>) -> SubAgentResult {
+    SubAgentResult {
+        task_id,
+        success: false,
+        output: String::new(),
+        files_examined: vec![],
+        duration_ms: 0,
+        turns_used: 0,
+        error: Some(error.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::ai::client::AiClientConfig;
+    use crate::ai::providers::ProviderId;
+
+    fn pool(provider_id: ProviderId, model: &str) -> SubAgentPool {
+        let config = AiClientConfig {
+            model: model.to_string(),
+            provider_id,
+            ..Default::default()
+        };
+        let client = Arc::new(AiClient::new(config, "test-key".to_string()));
+        SubAgentPool::new(client, AgentCancellation::new())
+    }
+
+    fn definition(model: Option<&str>) -> AgentDefinition {
+        AgentDefinition {
+            name: "reviewer".to_string(),
+            description: "Reviews diffs".to_string(),
+            tools: vec!["read".to_string()],
+            model: model.map(str::to_string),
+            max_turns: 5,
+            prompt: "Review the diff.".to_string(),
+            source: AgentSource::Project,
+            path: PathBuf::from("reviewer.md"),
+        }
+    }
+
+    #[test]
+    fn test_definition_model_served_by_provider_is_used() {
+        let anthropic = get_provider(ProviderId::Anthropic).unwrap();
+        let default = anthropic.default_model();
+        let other = anthropic
+            .models
+            .iter()
+            .map(|m| m.id.as_str())
+            .find(|id| *id != default)
+            .unwrap();
+
+        let pool = pool(ProviderId::Anthropic, default);
+        assert_eq!(
+            pool.resolve_definition_model(&definition(Some(other))),
+            other
+        );
+        assert_eq!(pool.resolve_definition_model(&definition(None)), default);
+    }
+
+    #[test]
+    fn test_definition_model_from_another_provider_falls_back() {
+        let default = get_provider(ProviderId::Anthropic).unwrap().default_model();
+        let pool = pool(ProviderId::Anthropic, default);
+        assert_eq!(
+            pool.resolve_definition_model(&definition(Some("gpt-not-anthropic"))),
+            default
+        );
+    }
+
+    #[tokio::test]
+    async fn test_spawn_limited_skips_cancelled_tasks() {
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        let handle = spawn_limited(
+            Arc::new(Semaphore::new(1)),
+            cancel,
+            "task-1".to_string(),
+            "Test",
+            async { panic!("cancelled task should not run") },
+        );
+
+        let results = collect_results(vec![handle], "Test").await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].task_id, "task-1");
+        assert!(!results[0].success);
+        assert_eq!(results[0].error.as_deref(), Some("Cancelled"));
+    }
 }
@@ -1,6 +1,7 @@
 //! Sub-agent tool implementations
 //!
-//! Read-only tools for explorers, read-write tools for builders.
+//! Read-only tools for explorers, read-write tools for builders, and a
+//! definition-selected subset for user-defined agents.
 
 use serde_json::{json, Value};
 use std::path::PathBuf;
@@ -484,3 +485,51 @@ impl BuilderTools {
         }
     }
 }
+
+/// Tools for a user-defined agent - the subset its definition allows
+pub(crate) struct CustomAgentTools {
+    allowed: Vec<String>,
+}
+
+impl CustomAgentTools {
+    pub fn new(allowed: Vec<String>) -> Self {
+        Self { allowed }
+    }
+
+    fn tool(name: &str) -> Option<&'static dyn Tool> {
+        match name {
+            "glob" => Some(&GlobTool),
+            "grep" => Some(&GrepTool),
+            "read" => Some(&ReadTool),
+            "write" => Some(&WriteTool),
+            "edit" => Some(&EditTool),
+            "bash" => Some(&BashTool),
+            _ => None,
+        }
+    }
+
+    pub fn get_ai_tools(&self) -> Vec<AiTool> {
+        self.allowed
+            .iter()
+            .filter_map(|name| {
+                Self::tool(name).map(|tool| AiTool {
+                    name: name.clone(),
+                    description: tool.description().to_string(),
+                    input_schema: tool.parameters_schema(),
+                })
+            })
+            .collect()
+    }
+
+    pub async fn execute(
+        &self,
+        name: &str,
+        params: Value,
+        ctx: &ToolContext,
+    ) -> Option<ToolResult> {
+        if !self.allowed.iter().any(|t| t == name) {
+            return None;
+        }
+        Some(Self::tool(name)?.execute(params, ctx).await)
+    }
+}
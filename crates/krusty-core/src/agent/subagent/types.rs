@@ -8,6 +8,7 @@ use std::time::Duration;
 
 use crate::ai::retry::is_retryable_status;
 use crate::ai::retry::IsRetryable;
+use crate::tools::FileCheckpointer;
 
 /// Error type for subagent API calls that supports retry logic
 #[derive(Debug)]
@@ -129,6 +130,8 @@ pub struct SubAgentTask {
     pub plan_task_id: Option<String>,
     /// Whether thinking/reasoning is enabled for this agent
     pub thinking_enabled: bool,
+    /// Parent turn's checkpoint, so builder edits can be undone with it
+    pub checkpointer: Option<FileCheckpointer>,
 }
 
 impl SubAgentTask {
@@ -142,6 +145,7 @@ impl SubAgentTask {
             working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
             plan_task_id: None,
             thinking_enabled: false, // Default off for sub-agents
+            checkpointer: None,
         }
     }
 
@@ -165,6 +169,11 @@ impl SubAgentTask {
         self
     }
 
+    pub fn with_checkpointer(mut self, checkpointer: Option<FileCheckpointer>) -> Self {
+        self.checkpointer = checkpointer;
+        self
+    }
+
     pub(crate) fn system_prompt(&self) -> String {
         format!(
             r#"You are a codebase explorer. Your task is to systematically investigate the codebase and answer questions.
@@ -223,16 +223,15 @@ pub fn build_summarization_prompt(
                         role_str, status, preview
                     ));
                 }
-                Content::Thinking { thinking, .. } => {
+                Content::Thinking { thinking, .. }
                     // Brief thinking preview
-                    if thinking.len() > 500 {
+                    if thinking.len() > 500 => {
                         prompt.push_str(&format!(
                             "{}: [Thinking: {}...]\n\n",
                             role_str,
                             truncate_str(thinking, 500)
                         ));
                     }
-                }
                 _ => {}
             }
         }
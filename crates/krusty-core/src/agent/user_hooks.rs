@@ -884,7 +884,7 @@ mod tests {
         // Should succeed if JSON was passed correctly
         assert!(matches!(
             result,
-            UserHookResult::Continue { .. } | UserHookResult::Warn { .. }
+            UserHookResult::Continue | UserHookResult::Warn { .. }
         ));
     }
 }
@@ -53,6 +53,20 @@ impl AiClientConfig {
         }
     }
 
+    /// Get the Gemini endpoint for a model
+    ///
+    /// Gemini puts the model and method in the path:
+    /// `{base}/{model}:streamGenerateContent?alt=sse` or `{base}/{model}:generateContent`
+    pub fn google_url(&self, model: &str, stream: bool) -> String {
+        let base = self.api_url();
+        let base = base.trim_end_matches('/');
+        if stream {
+            format!("{}/{}:streamGenerateContent?alt=sse", base, model)
+        } else {
+            format!("{}/{}:generateContent", base, model)
+        }
+    }
+
     /// Get the provider ID
     pub fn provider_id(&self) -> ProviderId {
         self.provider_id
@@ -141,6 +141,11 @@ impl AiClient {
                 request = request.header("x-api-key", &self.api_key);
                 info!("Using API key authentication");
             }
+            AuthHeader::XGoogApiKey => {
+                request = request.header("x-goog-api-key", &self.api_key);
+                info!("Using Google API key authentication");
+            }
+            AuthHeader::None => {}
         }
 
         // Add Anthropic API headers if using Anthropic-compatible API
@@ -178,6 +183,10 @@ impl AiClient {
             AuthHeader::XApiKey => {
                 headers.insert("x-api-key", self.api_key.parse()?);
             }
+            AuthHeader::XGoogApiKey => {
+                headers.insert("x-goog-api-key", self.api_key.parse()?);
+            }
+            AuthHeader::None => {}
         }
 
         headers.insert("content-type", "application/json".parse()?);
@@ -183,7 +183,7 @@ impl AiClient {
             }
         });
 
-        let request = self.build_request(&self.config().api_url());
+        let request = self.build_request(&self.config().google_url(model, false));
         debug!("Google simple call to model: {}", model);
 
         let response = request.json(&body).send().await?;
@@ -497,13 +497,13 @@ impl AiClient {
     /// Cache-safe conversation call using Google format.
     async fn call_conversation_google(
         &self,
-        _model: &str,
+        model: &str,
         base_system_prompt: &str,
         conversation: &[ModelMessage],
         appended_user_message: &str,
         max_tokens: usize,
     ) -> Result<String> {
-        let format_handler = GoogleFormat::new();
+        let format_handler = GoogleFormat::for_model(model);
 
         let mut contents = format_handler.convert_messages(conversation, Some(self.provider_id()));
 
@@ -530,7 +530,7 @@ impl AiClient {
             conversation.len()
         );
 
-        let request = self.build_request(&self.config().api_url());
+        let request = self.build_request(&self.config().google_url(model, false));
         let response = request.json(&body).send().await?;
         let response = self.handle_error_response(response).await?;
 
@@ -90,16 +90,19 @@ pub(crate) fn first_text_block(content: &[Content]) -> Option<&str> {
 /// session-level (dynamic, not cached) blocks.
 ///
 /// Project context (CLAUDE.md, KRAB.md, etc.) is identified by its
-/// `[PROJECT INSTRUCTIONS` prefix and rarely changes within a session.
-/// Everything else (plan state, skills list) changes frequently and should
-/// NOT be included in the cached prefix.
+/// `[PROJECT INSTRUCTIONS` prefix and rarely changes within a session. A
+/// compaction summary only changes when the session is compacted again, so it
+/// is cached alongside. Everything else (plan state, skills list) changes
+/// frequently and should NOT be included in the cached prefix.
 pub(crate) fn partition_system_messages(messages: &[ModelMessage]) -> (String, String) {
     let mut project_context = String::new();
     let mut session_context = String::new();
 
     for message in messages.iter().filter(|m| m.role == Role::System) {
         if let Some(text) = first_text_block(&message.content) {
-            if text.starts_with("[PROJECT INSTRUCTIONS") {
+            if text.starts_with("[PROJECT INSTRUCTIONS")
+                || text.starts_with(crate::agent::compaction::SUMMARY_PREFIX)
+            {
                 if !project_context.is_empty() {
                     project_context.push_str("\n\n");
                 }
@@ -150,13 +153,11 @@ fn build_codex_user_content(content: &[Content]) -> Vec<Value> {
 
     for block in content {
         match block {
-            Content::Text { text } => {
-                if !text.is_empty() {
-                    items.push(serde_json::json!({
-                        "type": "input_text",
-                        "text": text
-                    }));
-                }
+            Content::Text { text } if !text.is_empty() => {
+                items.push(serde_json::json!({
+                    "type": "input_text",
+                    "text": text
+                }));
             }
             Content::Image { image, detail } => {
                 if let Some(image_url) = codex_image_url(image) {
@@ -170,13 +171,11 @@ fn build_codex_user_content(content: &[Content]) -> Vec<Value> {
                     items.push(item);
                 }
             }
-            Content::Thinking { thinking, .. } => {
-                if !thinking.is_empty() {
-                    items.push(serde_json::json!({
-                        "type": "input_text",
-                        "text": format!("[Thinking]\n{}\n[/Thinking]", thinking)
-                    }));
-                }
+            Content::Thinking { thinking, .. } if !thinking.is_empty() => {
+                items.push(serde_json::json!({
+                    "type": "input_text",
+                    "text": format!("[Thinking]\n{}\n[/Thinking]", thinking)
+                }));
             }
             _ => {}
         }
@@ -850,7 +849,7 @@ impl AiClient {
     ) -> Result<mpsc::UnboundedReceiver<StreamPart>> {
         info!("Using Google/Gemini format for {}", self.config().model);
 
-        let format_handler = GoogleFormat::new();
+        let format_handler = GoogleFormat::for_model(&self.config().model);
         let contents = format_handler.convert_messages(&messages, Some(self.provider_id()));
 
         let system_instruction = build_default_system_prompt(&messages, options);
@@ -872,6 +871,15 @@ impl AiClient {
             body["generationConfig"]["temperature"] = serde_json::json!(temp);
         }
 
+        let reasoning_enabled =
+            options.thinking.is_some() || options.reasoning_format == Some(ReasoningFormat::Google);
+        if let Some(thinking_config) =
+            ReasoningConfig::build(Some(ReasoningFormat::Google), reasoning_enabled, None, None)
+        {
+            body["generationConfig"]["thinkingConfig"] = thinking_config;
+            debug!("Gemini thinking enabled (dynamic budget)");
+        }
+
         // Sort tools deterministically — Gemini 2.5+ uses implicit prefix caching.
         if let Some(tools) = &options.tools {
             let mut sorted: Vec<_> = tools.to_vec();
@@ -884,9 +892,10 @@ impl AiClient {
             }
         }
 
-        debug!("Google request to: {}", self.config().api_url());
+        let url = self.config().google_url(&self.config().model, true);
+        debug!("Google request to: {}", url);
 
-        let request = self.build_request(&self.config().api_url());
+        let request = self.build_request(&url);
 
         info!("Sending Google format request...");
         let response = request.json(&body).send().await?;
@@ -1008,7 +1017,8 @@ impl AiClient {
                     }
                     debug!("DeepSeek reasoning enabled");
                 }
-                None => {}
+                // Gemini thinking lives in generationConfig (see call_streaming_google)
+                Some(ReasoningFormat::Google) | None => {}
             }
 
             // Opus 4.5 effort config
@@ -858,6 +858,9 @@ impl AiClient {
 
         // Convert messages from Anthropic to Google contents format
         let mut contents: Vec<Value> = vec![];
+        // functionResponse is matched by function name, not call ID
+        let mut tool_names: HashMap<String, String> = HashMap::new();
+        let requires_signature = model.contains("gemini-3");
 
         for msg in messages {
             let role = msg.get("role").and_then(|r| r.as_str()).unwrap_or("user");
@@ -881,12 +884,24 @@ impl AiClient {
                         Some("tool_use") => {
                             let name = item.get("name").and_then(|n| n.as_str()).unwrap_or("");
                             let input = item.get("input").cloned().unwrap_or(Value::Null);
-                            parts.push(serde_json::json!({
+                            if let Some(id) = item.get("id").and_then(|i| i.as_str()) {
+                                tool_names.insert(id.to_string(), name.to_string());
+                            }
+                            let mut part = serde_json::json!({
                                 "functionCall": {
                                     "name": name,
                                     "args": input
                                 }
-                            }));
+                            });
+                            let is_first_call =
+                                !parts.iter().any(|p| p.get("functionCall").is_some());
+                            if let Some(sig) = item.get("thought_signature") {
+                                part["thoughtSignature"] = sig.clone();
+                            } else if requires_signature && is_first_call {
+                                part["thoughtSignature"] =
+                                    serde_json::json!("skip_thought_signature_validator");
+                            }
+                            parts.push(part);
                         }
                         Some("tool_result") => {
                             let tool_use_id = item
@@ -894,9 +909,13 @@ impl AiClient {
                                 .and_then(|i| i.as_str())
                                 .unwrap_or("");
                             let output = item.get("content").and_then(|c| c.as_str()).unwrap_or("");
+                            let name = tool_names
+                                .get(tool_use_id)
+                                .map(String::as_str)
+                                .unwrap_or(tool_use_id);
                             parts.push(serde_json::json!({
                                 "functionResponse": {
-                                    "name": tool_use_id,
+                                    "name": name,
                                     "response": {
                                         "content": output
                                     }
@@ -948,7 +967,7 @@ impl AiClient {
             }]);
         }
 
-        let request = self.build_request(&self.config().api_url());
+        let request = self.build_request(&self.config().google_url(model, false));
         let response = match request.json(&body).send().await {
             Ok(r) => r,
             Err(e) => {
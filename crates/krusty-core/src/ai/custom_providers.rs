@@ -0,0 +1,481 @@
+//! User-defined providers
+//!
+//! Lets Krusty talk to self-hosted or third-party endpoints (Ollama, vLLM,
+//! LM Studio, llama.cpp, internal gateways) that speak one of the supported
+//! wire formats. Providers are declared in `~/.krusty/providers.json`:
+//!
+//! ```json
+//! {
+//!   "providers": [
+//!     {
+//!       "id": "ollama",
+//!       "name": "Ollama",
+//!       "base_url": "http://localhost:11434/v1/chat/completions",
+//!       "format": "openai",
+//!       "auth": "none",
+//!       "models_url": "http://localhost:11434/api/tags"
+//!     },
+//!     {
+//!       "id": "gateway",
+//!       "name": "Team Gateway",
+//!       "base_url": "https://llm.internal.example.com/v1/chat/completions",
+//!       "auth": "bearer",
+//!       "api_key_env": "GATEWAY_API_KEY",
+//!       "headers": { "X-Team": "platform" },
+//!       "models": [
+//!         { "id": "qwen3-coder", "display_name": "Qwen3 Coder", "context_window": 262144 }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The file is read once per process. Each entry becomes a
+//! [`ProviderId::Custom`] that appears alongside the built-in providers.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use super::models::{ApiFormat, ModelMetadata};
+use super::providers::{AuthHeader, ModelInfo, ProviderConfig, ProviderId, ReasoningFormat};
+use crate::paths;
+
+/// Contents of `providers.json`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomProvidersFile {
+    #[serde(default)]
+    pub providers: Vec<CustomProviderDef>,
+}
+
+/// A provider entry as written by the user
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomProviderDef {
+    /// Storage key (used in credentials.json, `--provider`, API routes)
+    pub id: String,
+    /// Display name (defaults to `id`)
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Short description for UI
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Full endpoint URL (e.g. `http://localhost:8000/v1/chat/completions`)
+    pub base_url: String,
+    /// Wire format: `openai` (default), `anthropic`, `openai_responses` or `google`
+    #[serde(default = "default_format")]
+    pub format: ApiFormat,
+    /// How to send the key: `bearer` (default), `x_api_key` or `none`
+    #[serde(default = "default_auth")]
+    pub auth: AuthHeader,
+    /// Environment variable to read the API key from when none is stored
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Extra headers sent with every request
+    #[serde(default)]
+    pub headers: HashMap<String, String>,
+    /// Static model list
+    #[serde(default)]
+    pub models: Vec<CustomModelDef>,
+    /// Model discovery endpoint (OpenAI `/v1/models` or Ollama `/api/tags`)
+    #[serde(default)]
+    pub models_url: Option<String>,
+    /// Whether the served models support tool calling
+    #[serde(default = "default_true")]
+    pub supports_tools: bool,
+}
+
+/// A model entry within a user-defined provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomModelDef {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default = "default_context_window")]
+    pub context_window: usize,
+    #[serde(default = "default_max_output")]
+    pub max_output: usize,
+    #[serde(default)]
+    pub reasoning: Option<ReasoningFormat>,
+    /// USD per million input tokens, for cost tracking
+    #[serde(default)]
+    pub input_price: Option<f64>,
+    /// USD per million output tokens, for cost tracking
+    #[serde(default)]
+    pub output_price: Option<f64>,
+}
+
+fn default_format() -> ApiFormat {
+    ApiFormat::OpenAI
+}
+
+fn default_auth() -> AuthHeader {
+    AuthHeader::Bearer
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_context_window() -> usize {
+    128_000
+}
+
+fn default_max_output() -> usize {
+    4096
+}
+
+/// A loaded user-defined provider
+#[derive(Debug, Clone)]
+pub struct CustomProvider {
+    /// Normalized storage key
+    pub key: String,
+    /// Provider configuration (id is `ProviderId::Custom(index)`)
+    pub config: ProviderConfig,
+}
+
+static CUSTOM_PROVIDERS: LazyLock<Vec<CustomProvider>> = LazyLock::new(|| {
+    let path = config_path();
+    match load_from_path(&path) {
+        Ok(providers) => {
+            if !providers.is_empty() {
+                info!(
+                    "Loaded {} user-defined providers from {:?}",
+                    providers.len(),
+                    path
+                );
+            }
+            providers
+        }
+        Err(e) => {
+            warn!("Failed to load {:?}: {}", path, e);
+            Vec::new()
+        }
+    }
+});
+
+/// Path of the user-defined providers file (~/.krusty/providers.json)
+pub fn config_path() -> PathBuf {
+    paths::config_dir().join("providers.json")
+}
+
+/// All user-defined providers, in file order
+pub fn all() -> &'static [CustomProvider] {
+    &CUSTOM_PROVIDERS
+}
+
+/// Get a user-defined provider by index
+pub fn get(index: u16) -> Option<&'static CustomProvider> {
+    CUSTOM_PROVIDERS.get(index as usize)
+}
+
+/// Load user-defined providers from a file (missing file = none)
+pub fn load_from_path(path: &Path) -> Result<Vec<CustomProvider>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(path)?;
+    parse(&contents)
+}
+
+/// Parse and validate a `providers.json` document
+///
+/// Entries with an empty or duplicate id, an id that shadows a built-in
+/// provider, or no base URL are skipped with a warning.
+pub fn parse(contents: &str) -> Result<Vec<CustomProvider>> {
+    let file: CustomProvidersFile = serde_json::from_str(contents)?;
+
+    // Built-in keys and legacy names resolve to the built-in first
+    let reserved: HashSet<&str> = ProviderId::builtin()
+        .flat_map(|p| std::iter::once(p.storage_key()).chain(p.legacy_name()))
+        .collect();
+
+    let mut seen = HashSet::new();
+    let mut providers = Vec::new();
+
+    for def in file.providers {
+        let key = def.id.trim().to_ascii_lowercase();
+        if key.is_empty() || def.base_url.trim().is_empty() {
+            warn!("Skipping user-defined provider with missing id or base_url");
+            continue;
+        }
+        if reserved.contains(key.as_str()) {
+            warn!(
+                "Skipping user-defined provider '{}': id is reserved for a built-in provider",
+                key
+            );
+            continue;
+        }
+        if !seen.insert(key.clone()) {
+            warn!("Skipping duplicate user-defined provider '{}'", key);
+            continue;
+        }
+        if providers.len() >= u16::MAX as usize {
+            warn!("Too many user-defined providers, ignoring the rest");
+            break;
+        }
+
+        let id = ProviderId::Custom(providers.len() as u16);
+        providers.push(CustomProvider {
+            config: build_config(id, &key, def),
+            key,
+        });
+    }
+
+    Ok(providers)
+}
+
+fn build_config(id: ProviderId, key: &str, def: CustomProviderDef) -> ProviderConfig {
+    let models = def
+        .models
+        .into_iter()
+        .map(|m| ModelInfo {
+            display_name: m.display_name.unwrap_or_else(|| m.id.clone()),
+            id: m.id,
+            context_window: m.context_window,
+            max_output: m.max_output,
+            reasoning: m.reasoning,
+            input_price: m.input_price,
+            output_price: m.output_price,
+        })
+        .collect();
+
+    ProviderConfig {
+        id,
+        name: def.name.unwrap_or_else(|| key.to_string()),
+        description: def
+            .description
+            .unwrap_or_else(|| format!("User-defined ({})", def.base_url)),
+        base_url: def.base_url.trim_end_matches('/').to_string(),
+        auth_header: def.auth,
+        models,
+        supports_tools: def.supports_tools,
+        dynamic_models: def.models_url.is_some(),
+        pricing_hint: None,
+        custom_headers: def.headers,
+        api_format: def.format,
+        api_key_env: def.api_key_env,
+        models_url: def.models_url,
+    }
+}
+
+fn model_metadata(config: &ProviderConfig, id: &str, info: Option<&ModelInfo>) -> ModelMetadata {
+    let display_name = info.map(|m| m.display_name.as_str()).unwrap_or(id);
+    let mut meta = ModelMetadata::new(id, display_name, config.id);
+    if let Some(info) = info {
+        meta = meta.with_context(info.context_window, info.max_output);
+        if let Some(format) = info.reasoning {
+            meta = meta.with_thinking(format);
+        }
+        meta = meta.with_pricing(info.input_price, info.output_price);
+    }
+    meta.supports_tools = config.supports_tools;
+    meta.api_format = config.api_format;
+    meta
+}
+
+/// Fetch the model list from a user-defined provider's `models_url`
+///
+/// Understands the OpenAI shape (`{"data": [{"id": ...}]}`, used by vLLM,
+/// LM Studio, llama.cpp and most gateways) and Ollama's `/api/tags`
+/// (`{"models": [{"name": ...}]}`). Statically declared models keep their
+/// context window and reasoning settings.
+pub async fn fetch_models(
+    provider: ProviderId,
+    api_key: Option<&str>,
+) -> Result<Vec<ModelMetadata>> {
+    let config = super::providers::get_provider(provider)
+        .ok_or_else(|| anyhow::anyhow!("Unknown provider: {}", provider))?;
+    let url = config
+        .models_url
+        .as_deref()
+        .ok_or_else(|| anyhow::anyhow!("{} has no models_url configured", provider))?;
+
+    info!("Fetching models from {} ({})", provider, url);
+
+    let mut request = Client::new().get(url);
+    match (config.auth_header, api_key.filter(|k| !k.is_empty())) {
+        (AuthHeader::Bearer, Some(key)) => {
+            request = request.header("authorization", format!("Bearer {}", key));
+        }
+        (AuthHeader::XApiKey, Some(key)) => {
+            request = request.header("x-api-key", key);
+        }
+        (AuthHeader::XGoogApiKey, Some(key)) => {
+            request = request.header("x-goog-api-key", key);
+        }
+        _ => {}
+    }
+    for (name, value) in &config.custom_headers {
+        request = request.header(name, value);
+    }
+
+    let response = request.send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        let error_text = response.text().await.unwrap_or_default();
+        return Err(anyhow::anyhow!(
+            "{} models API error: {} - {}",
+            provider,
+            status,
+            error_text
+        ));
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    let models = parse_model_list(config, &body);
+    info!("{} returned {} models", provider, models.len());
+    Ok(models)
+}
+
+/// Extract models from an OpenAI- or Ollama-style listing
+fn parse_model_list(config: &ProviderConfig, body: &serde_json::Value) -> Vec<ModelMetadata> {
+    let entries = body
+        .get("data")
+        .or_else(|| body.get("models"))
+        .and_then(|v| v.as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let id = entry
+                .get("id")
+                .or_else(|| entry.get("name"))
+                .or_else(|| entry.get("model"))
+                .and_then(|v| v.as_str())?;
+            let declared = config.models.iter().find(|m| m.id == id);
+            let mut meta = model_metadata(config, id, declared);
+            // vLLM reports the served context length
+            if declared.is_none() {
+                if let Some(len) = entry
+                    .get("max_model_len")
+                    .or_else(|| entry.get("context_length"))
+                    .and_then(|v| v.as_u64())
+                {
+                    meta.context_window = len as usize;
+                }
+            }
+            Some(meta)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r#"{
+        "providers": [
+            {
+                "id": "Ollama",
+                "base_url": "http://localhost:11434/v1/chat/completions/",
+                "auth": "none",
+                "models_url": "http://localhost:11434/api/tags"
+            },
+            {
+                "id": "gateway",
+                "name": "Team Gateway",
+                "base_url": "https://llm.example.com/v1/messages",
+                "format": "anthropic",
+                "auth": "x_api_key",
+                "api_key_env": "GATEWAY_KEY",
+                "headers": { "X-Team": "platform" },
+                "models": [
+                    { "id": "big", "display_name": "Big", "context_window": 200000, "reasoning": "anthropic" },
+                    { "id": "small" }
+                ]
+            },
+            { "id": "openai", "base_url": "http://shadow" },
+            { "id": "ollama", "base_url": "http://dup" },
+            { "id": "", "base_url": "http://empty" }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_custom_providers() {
+        let providers = parse(SAMPLE).unwrap();
+        assert_eq!(providers.len(), 2);
+
+        let ollama = &providers[0];
+        assert_eq!(ollama.key, "ollama");
+        assert_eq!(ollama.config.id, ProviderId::Custom(0));
+        assert_eq!(ollama.config.name, "ollama");
+        assert_eq!(
+            ollama.config.base_url,
+            "http://localhost:11434/v1/chat/completions"
+        );
+        assert_eq!(ollama.config.api_format, ApiFormat::OpenAI);
+        assert_eq!(ollama.config.auth_header, AuthHeader::None);
+        assert!(ollama.config.dynamic_models);
+
+        let gateway = &providers[1];
+        assert_eq!(gateway.config.id, ProviderId::Custom(1));
+        assert_eq!(gateway.config.name, "Team Gateway");
+        assert_eq!(gateway.config.api_format, ApiFormat::Anthropic);
+        assert_eq!(gateway.config.auth_header, AuthHeader::XApiKey);
+        assert_eq!(gateway.config.api_key_env.as_deref(), Some("GATEWAY_KEY"));
+        assert_eq!(gateway.config.custom_headers["X-Team"], "platform");
+        assert!(!gateway.config.dynamic_models);
+        assert_eq!(gateway.config.models.len(), 2);
+        assert_eq!(gateway.config.models[0].context_window, 200_000);
+        assert_eq!(
+            gateway.config.models[0].reasoning,
+            Some(ReasoningFormat::Anthropic)
+        );
+        assert_eq!(gateway.config.models[1].display_name, "small");
+        assert_eq!(gateway.config.default_model(), "big");
+    }
+
+    #[test]
+    fn test_parse_model_list_formats() {
+        let providers = parse(SAMPLE).unwrap();
+        let gateway = &providers[1].config;
+
+        let openai_shape = serde_json::json!({
+            "data": [
+                { "id": "big" },
+                { "id": "served", "max_model_len": 32768 }
+            ]
+        });
+        let models = parse_model_list(gateway, &openai_shape);
+        assert_eq!(models.len(), 2);
+        assert_eq!(models[0].context_window, 200_000);
+        assert!(models[0].supports_thinking);
+        assert_eq!(models[1].context_window, 32_768);
+        assert_eq!(models[1].api_format, ApiFormat::Anthropic);
+
+        let ollama_shape = serde_json::json!({
+            "models": [{ "name": "llama3.1:8b" }, { "name": "qwen2.5-coder:14b" }]
+        });
+        let models = parse_model_list(&providers[0].config, &ollama_shape);
+        let ids: Vec<_> = models.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["llama3.1:8b", "qwen2.5-coder:14b"]);
+    }
+
+    #[test]
+    fn test_builtin_ids_are_reserved() {
+        let contents = r#"{
+            "providers": [
+                { "id": "gemini", "base_url": "http://a" },
+                { "id": "OpenRouter", "base_url": "http://b" },
+                { "id": "open_a_i", "base_url": "http://c" },
+                { "id": "local", "base_url": "http://d" }
+            ]
+        }"#;
+        let providers = parse(contents).unwrap();
+        let keys: Vec<_> = providers.iter().map(|p| p.key.as_str()).collect();
+        assert_eq!(keys, vec!["local"]);
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let providers = load_from_path(Path::new("/nonexistent/providers.json")).unwrap();
+        assert!(providers.is_empty());
+    }
+}
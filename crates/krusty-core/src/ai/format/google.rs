@@ -2,21 +2,38 @@
 //!
 //! Handles conversion to Google AI API format (contents, parts, functionDeclarations).
 
+use std::collections::HashMap;
+
 use serde_json::Value;
 
 use super::{FormatHandler, RequestOptions};
 use crate::ai::providers::ProviderId;
 use crate::ai::types::{AiTool, Content, ModelMessage, Role};
 
+/// Placeholder Gemini accepts when a function call has no real thought signature
+/// (history from another provider, or thoughts that were never streamed)
+const SKIP_THOUGHT_SIGNATURE: &str = "skip_thought_signature_validator";
+
 /// Google format handler
 pub struct GoogleFormat {
     endpoint_template: String,
+    /// Gemini 3 rejects function calls in history that lack a thought signature
+    requires_thought_signatures: bool,
 }
 
 impl GoogleFormat {
     pub fn new() -> Self {
         Self {
             endpoint_template: "/v1/models/{}:streamGenerateContent".to_string(),
+            requires_thought_signatures: false,
+        }
+    }
+
+    /// Create a handler tuned for a specific Gemini model
+    pub fn for_model(model: &str) -> Self {
+        Self {
+            requires_thought_signatures: model.contains("gemini-3"),
+            ..Self::new()
         }
     }
 }
@@ -29,32 +46,54 @@ impl Default for GoogleFormat {
 
 impl FormatHandler for GoogleFormat {
     /// Convert messages to Google contents format
-    /// Note: provider_id is unused for Google format (no thinking block handling needed)
+    ///
+    /// Thinking text is never replayed; its signature is attached to the first
+    /// function call of the turn, where Gemini expects it.
+    /// Note: provider_id is unused for Google format
     fn convert_messages(
         &self,
         messages: &[ModelMessage],
         _provider_id: Option<ProviderId>,
     ) -> Vec<Value> {
+        // functionResponse is matched by name, but results only carry the call ID
+        let tool_names: HashMap<&str, &str> = messages
+            .iter()
+            .flat_map(|m| &m.content)
+            .filter_map(|c| match c {
+                Content::ToolUse { id, name, .. } => Some((id.as_str(), name.as_str())),
+                _ => None,
+            })
+            .collect();
+
         messages
             .iter()
             .filter(|m| m.role != Role::System) // System handled separately
-            .map(|m| {
+            .filter_map(|m| {
                 let role = match m.role {
                     Role::User | Role::Tool => "user", // Tool results are user role in Google format
                     Role::Assistant => "model",
                     Role::System => "user", // Should be filtered out
                 };
 
-                let parts: Vec<Value> = m
+                let mut parts: Vec<Value> = m
                     .content
                     .iter()
-                    .filter_map(convert_content_to_part)
+                    .filter_map(|c| convert_content_to_part(c, &tool_names))
                     .collect();
 
-                serde_json::json!({
+                if m.role == Role::Assistant {
+                    self.attach_thought_signature(&m.content, &mut parts);
+                }
+
+                // Gemini rejects contents with no parts (e.g. thinking-only turns)
+                if parts.is_empty() {
+                    return None;
+                }
+
+                Some(serde_json::json!({
                     "role": role,
                     "parts": parts
-                })
+                }))
             })
             .collect()
     }
@@ -119,8 +158,30 @@ impl FormatHandler for GoogleFormat {
     }
 }
 
+impl GoogleFormat {
+    /// Put the turn's thought signature on its first function call
+    fn attach_thought_signature(&self, content: &[Content], parts: &mut [Value]) {
+        let Some(call) = parts.iter_mut().find(|p| p.get("functionCall").is_some()) else {
+            return;
+        };
+        let signature = content.iter().rev().find_map(|c| match c {
+            Content::Thinking { signature, .. } if !signature.is_empty() => {
+                Some(signature.as_str())
+            }
+            _ => None,
+        });
+        match signature {
+            Some(signature) => call["thoughtSignature"] = serde_json::json!(signature),
+            None if self.requires_thought_signatures => {
+                call["thoughtSignature"] = serde_json::json!(SKIP_THOUGHT_SIGNATURE)
+            }
+            None => {}
+        }
+    }
+}
+
 /// Convert a single content block to Google parts format
-fn convert_content_to_part(content: &Content) -> Option<Value> {
+fn convert_content_to_part(content: &Content, tool_names: &HashMap<&str, &str>) -> Option<Value> {
     match content {
         Content::Text { text } => Some(serde_json::json!({"text": text})),
         Content::Image { image, .. } => {
@@ -158,15 +219,39 @@ fn convert_content_to_part(content: &Content) -> Option<Value> {
                 }
             }))
         }
+        Content::Document { source } => {
+            // PDFs go through the same inline/file data parts as images
+            if let Some(data) = &source.data {
+                Some(serde_json::json!({
+                    "inline_data": {
+                        "mime_type": source.media_type,
+                        "data": data
+                    }
+                }))
+            } else {
+                source.url.as_ref().map(|url| {
+                    serde_json::json!({
+                        "file_data": {
+                            "file_uri": url,
+                            "mime_type": source.media_type
+                        }
+                    })
+                })
+            }
+        }
         Content::ToolResult {
             tool_use_id,
             output,
             ..
         } => {
-            // Function response in user message
+            // Function response in user message, keyed by the called function's name
+            let name = tool_names
+                .get(tool_use_id.as_str())
+                .copied()
+                .unwrap_or(tool_use_id);
             Some(serde_json::json!({
                 "functionResponse": {
-                    "name": tool_use_id,
+                    "name": name,
                     "response": {
                         "content": output
                     }
@@ -176,3 +261,75 @@ fn convert_content_to_part(content: &Content) -> Option<Value> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assistant_call(signature: &str) -> ModelMessage {
+        ModelMessage {
+            role: Role::Assistant,
+            content: vec![
+                Content::Thinking {
+                    thinking: "plan".to_string(),
+                    signature: signature.to_string(),
+                },
+                Content::ToolUse {
+                    id: "google_1".to_string(),
+                    name: "read".to_string(),
+                    input: serde_json::json!({"path": "a"}),
+                },
+            ],
+        }
+    }
+
+    fn tool_result() -> ModelMessage {
+        ModelMessage {
+            role: Role::Tool,
+            content: vec![Content::ToolResult {
+                tool_use_id: "google_1".to_string(),
+                output: serde_json::json!("contents"),
+                is_error: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_function_response_uses_function_name() {
+        let contents =
+            GoogleFormat::new().convert_messages(&[assistant_call("sig"), tool_result()], None);
+        assert_eq!(contents.len(), 2);
+        let call = &contents[0]["parts"][0];
+        assert_eq!(call["functionCall"]["name"], "read");
+        assert_eq!(call["thoughtSignature"], "sig");
+        assert_eq!(contents[1]["parts"][0]["functionResponse"]["name"], "read");
+    }
+
+    #[test]
+    fn test_missing_signature_for_gemini_3() {
+        let messages = [assistant_call("")];
+        let contents = GoogleFormat::new().convert_messages(&messages, None);
+        assert!(contents[0]["parts"][0].get("thoughtSignature").is_none());
+
+        let contents =
+            GoogleFormat::for_model("gemini-3-pro-preview").convert_messages(&messages, None);
+        assert_eq!(
+            contents[0]["parts"][0]["thoughtSignature"],
+            SKIP_THOUGHT_SIGNATURE
+        );
+    }
+
+    #[test]
+    fn test_thinking_only_message_is_dropped() {
+        let messages = [ModelMessage {
+            role: Role::Assistant,
+            content: vec![Content::Thinking {
+                thinking: "hmm".to_string(),
+                signature: String::new(),
+            }],
+        }];
+        assert!(GoogleFormat::new()
+            .convert_messages(&messages, None)
+            .is_empty());
+    }
+}
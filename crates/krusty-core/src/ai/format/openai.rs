@@ -231,15 +231,13 @@ impl FormatHandler for OpenAIFormat {
                             }));
                         }
                         // Preserve thinking in tool call messages too
-                        Content::Thinking { thinking, .. } => {
-                            if !thinking.is_empty() {
-                                if !text_content.is_empty() {
-                                    text_content.push_str("\n\n");
-                                }
-                                text_content.push_str("[Thinking]\n");
-                                text_content.push_str(thinking);
-                                text_content.push_str("\n[/Thinking]\n\n");
+                        Content::Thinking { thinking, .. } if !thinking.is_empty() => {
+                            if !text_content.is_empty() {
+                                text_content.push_str("\n\n");
                             }
+                            text_content.push_str("[Thinking]\n");
+                            text_content.push_str(thinking);
+                            text_content.push_str("\n[/Thinking]\n\n");
                         }
                         _ => {}
                     }
@@ -279,23 +277,19 @@ impl FormatHandler for OpenAIFormat {
 
             for content in &msg.content {
                 match content {
-                    Content::Text { text } => {
-                        if !text.is_empty() {
-                            text_parts.push(text.clone());
-                            if role == "user" {
-                                user_parts.push(self.user_text_part(text));
-                            }
+                    Content::Text { text } if !text.is_empty() => {
+                        text_parts.push(text.clone());
+                        if role == "user" {
+                            user_parts.push(self.user_text_part(text));
                         }
                     }
                     // Preserve thinking blocks as formatted text
                     // This maintains context for reasoning models
-                    Content::Thinking { thinking, .. } => {
-                        if !thinking.is_empty() {
-                            let formatted = format!("[Thinking]\n{}\n[/Thinking]", thinking);
-                            text_parts.push(formatted.clone());
-                            if role == "user" {
-                                user_parts.push(self.user_text_part(&formatted));
-                            }
+                    Content::Thinking { thinking, .. } if !thinking.is_empty() => {
+                        let formatted = format!("[Thinking]\n{}\n[/Thinking]", thinking);
+                        text_parts.push(formatted.clone());
+                        if role == "user" {
+                            user_parts.push(self.user_text_part(&formatted));
                         }
                     }
                     Content::Image { image, detail } if role == "user" => {
@@ -109,12 +109,17 @@ pub fn normalize_google_response(response: &Value) -> Value {
                             let uuid = uuid::Uuid::new_v4().simple().to_string();
                             let id = format!("toolu_{}", &uuid[..24]);
 
-                            content.push(serde_json::json!({
+                            let mut tool_use = serde_json::json!({
                                 "type": "tool_use",
                                 "id": id,
                                 "name": name,
                                 "input": args
-                            }));
+                            });
+                            // Kept so the call can be replayed with its signature
+                            if let Some(sig) = part.get("thoughtSignature") {
+                                tool_use["thought_signature"] = sig.clone();
+                            }
+                            content.push(tool_use);
                             stop_reason = Some("tool_use");
                             stop_reason_owned = None;
                         }
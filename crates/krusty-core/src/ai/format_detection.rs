@@ -4,17 +4,23 @@
 //! Used by both ACP and TUI to route requests correctly.
 
 use super::models::ApiFormat;
-use super::providers::ProviderId;
+use super::providers::{get_provider, ProviderId};
 
 /// Detect the appropriate API format for a provider/model combination
 ///
 /// This is the canonical format detection logic used across Krusty.
 /// Provider-specific routing:
 /// - OpenAI: OpenAI chat/completions format
+/// - Gemini: Google generateContent format
+/// - User-defined providers: the format declared in providers.json
 /// - All others (OpenRouter, MiniMax, ZAi): Anthropic format
 pub fn detect_api_format(provider: ProviderId, _model: &str) -> ApiFormat {
     match provider {
         ProviderId::OpenAI => ApiFormat::OpenAI,
+        ProviderId::Gemini => ApiFormat::Google,
+        ProviderId::Custom(_) => get_provider(provider)
+            .map(|p| p.api_format)
+            .unwrap_or_default(),
         _ => ApiFormat::Anthropic,
     }
 }
@@ -31,6 +37,14 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_detect_api_format_gemini_provider() {
+        assert!(matches!(
+            detect_api_format(ProviderId::Gemini, "gemini-2.5-pro"),
+            ApiFormat::Google
+        ));
+    }
+
     #[test]
     fn test_detect_api_format_minimax_provider() {
         assert!(matches!(
@@ -1,6 +1,7 @@
 //! AI provider layer
 //!
-//! Handles communication with AI providers (MiniMax, OpenRouter, ZAi, OpenAI, etc.)
+//! Handles communication with AI providers (MiniMax, OpenRouter, ZAi, OpenAI,
+//! user-defined endpoints, etc.)
 //! Supports multiple API formats: Anthropic, OpenAI, and Google.
 
 // Modular architecture
@@ -10,6 +11,7 @@ pub mod format_detection;
 pub mod retry;
 
 // Provider-specific configuration
+pub mod custom_providers;
 pub mod glm;
 pub mod models;
 pub mod openrouter;
@@ -9,6 +9,7 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use super::providers::{ProviderId, ReasoningFormat};
+use super::types::Usage;
 
 pub type ModelsByProvider = HashMap<ProviderId, Vec<ModelMetadata>>;
 pub type OrganizedModels = (Vec<ModelMetadata>, ModelsByProvider);
@@ -23,8 +24,10 @@ pub enum ApiFormat {
     #[default]
     Anthropic,
     /// OpenAI Chat Completions API (/v1/chat/completions)
+    #[serde(alias = "openai")]
     OpenAI,
     /// OpenAI Responses API (/v1/responses) - GPT-5 models
+    #[serde(alias = "openai_responses")]
     OpenAIResponses,
     /// Google AI API (/v1/models/{model})
     Google,
@@ -59,6 +62,12 @@ pub struct ModelMetadata {
     pub input_price: Option<f64>,
     /// Output/completion price per million tokens
     pub output_price: Option<f64>,
+    /// Cache read price per million tokens (defaults to 10% of input)
+    #[serde(default)]
+    pub cache_read_price: Option<f64>,
+    /// Cache write price per million tokens (defaults to 125% of input)
+    #[serde(default)]
+    pub cache_write_price: Option<f64>,
 
     // Provider-specific metadata
     /// Sub-provider for OpenRouter models (e.g., "anthropic", "openai")
@@ -87,6 +96,8 @@ impl ModelMetadata {
             supports_vision: false,
             input_price: None,
             output_price: None,
+            cache_read_price: None,
+            cache_write_price: None,
             sub_provider: None,
             is_free: false,
             api_format: ApiFormat::default(),
@@ -107,6 +118,25 @@ impl ModelMetadata {
         self
     }
 
+    /// Builder: set input/output pricing (per million tokens)
+    pub fn with_pricing(mut self, input: Option<f64>, output: Option<f64>) -> Self {
+        self.input_price = input;
+        self.output_price = output;
+        self
+    }
+
+    /// Pricing used for cost accounting (None if input/output prices are unknown)
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        let mut pricing = ModelPricing::new(self.input_price?, self.output_price?);
+        if let Some(price) = self.cache_read_price {
+            pricing.cache_read = price;
+        }
+        if let Some(price) = self.cache_write_price {
+            pricing.cache_write = price;
+        }
+        Some(pricing)
+    }
+
     /// Get pricing tier indicator for UI
     pub fn pricing_tier(&self) -> &'static str {
         match self.input_price {
@@ -130,6 +160,43 @@ impl ModelMetadata {
     }
 }
 
+/// Per-million-token prices used to cost a model call
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    pub input: f64,
+    pub output: f64,
+    pub cache_read: f64,
+    pub cache_write: f64,
+}
+
+impl ModelPricing {
+    /// Create pricing with Anthropic-style cache multipliers
+    /// (reads at 10% of input, writes at 125%)
+    pub fn new(input: f64, output: f64) -> Self {
+        Self {
+            input,
+            output,
+            cache_read: input * 0.1,
+            cache_write: input * 1.25,
+        }
+    }
+
+    /// Cost in USD of a single call
+    ///
+    /// `prompt_tokens` includes cached tokens, so only the uncached
+    /// remainder is billed at the full input price.
+    pub fn cost(&self, usage: &Usage) -> f64 {
+        let uncached = usage
+            .prompt_tokens
+            .saturating_sub(usage.cache_read_input_tokens + usage.cache_creation_input_tokens);
+        (uncached as f64 * self.input
+            + usage.cache_read_input_tokens as f64 * self.cache_read
+            + usage.cache_creation_input_tokens as f64 * self.cache_write
+            + usage.completion_tokens as f64 * self.output)
+            / 1_000_000.0
+    }
+}
+
 /// Central model registry
 ///
 /// Thread-safe store for all models from all providers.
@@ -44,6 +44,19 @@ struct TopProvider {
 struct Pricing {
     prompt: Option<String>,
     completion: Option<String>,
+    #[serde(default)]
+    input_cache_read: Option<String>,
+    #[serde(default)]
+    input_cache_write: Option<String>,
+}
+
+impl Pricing {
+    /// Parse a per-token price string into a per-million price
+    fn per_million(price: Option<&String>) -> Option<f64> {
+        price
+            .and_then(|s| s.parse::<f64>().ok())
+            .map(|p| p * 1_000_000.0)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -178,19 +191,12 @@ fn parse_model(raw: OpenRouterModel) -> ModelMetadata {
         .unwrap_or(false);
 
     // Parse pricing (convert from per-token to per-million)
-    let input_price = raw
-        .pricing
-        .as_ref()
-        .and_then(|p| p.prompt.as_ref())
-        .and_then(|s| s.parse::<f64>().ok())
-        .map(|p| p * 1_000_000.0);
-
-    let output_price = raw
-        .pricing
-        .as_ref()
-        .and_then(|p| p.completion.as_ref())
-        .and_then(|s| s.parse::<f64>().ok())
-        .map(|p| p * 1_000_000.0);
+    let pricing = raw.pricing.as_ref();
+    let input_price = pricing.and_then(|p| Pricing::per_million(p.prompt.as_ref()));
+    let output_price = pricing.and_then(|p| Pricing::per_million(p.completion.as_ref()));
+    let cache_read_price = pricing.and_then(|p| Pricing::per_million(p.input_cache_read.as_ref()));
+    let cache_write_price =
+        pricing.and_then(|p| Pricing::per_million(p.input_cache_write.as_ref()));
 
     // Clean up display name (remove "Provider: " prefix if present)
     let display_name = raw.name.split(": ").last().unwrap_or(&raw.name).to_string();
@@ -213,6 +219,8 @@ fn parse_model(raw: OpenRouterModel) -> ModelMetadata {
         supports_vision,
         input_price,
         output_price,
+        cache_read_price,
+        cache_write_price,
         sub_provider,
         is_free,
         api_format: super::models::ApiFormat::Anthropic, // OpenRouter uses Anthropic skin
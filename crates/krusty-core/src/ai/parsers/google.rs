@@ -3,8 +3,8 @@
 use anyhow::Result;
 use serde_json::Value;
 
-use crate::ai::sse::{SseEvent, SseParser, ToolCallAccumulator};
-use crate::ai::types::FinishReason;
+use crate::ai::sse::{SseEvent, SseParser};
+use crate::ai::types::{AiToolCall, FinishReason, Usage};
 
 /// Google Gemini SSE parser
 ///
@@ -12,27 +12,37 @@ use crate::ai::types::FinishReason;
 /// ```json
 /// {"candidates": [{"content": {"parts": [{"text": "..."}], "role": "model"}, "finishReason": "STOP"}]}
 /// ```
+///
+/// A single chunk can carry thought parts, text and complete function calls,
+/// so each chunk is returned as an [`SseEvent::Batch`]. Function calls arrive
+/// whole (never as argument deltas) and are held until the finish chunk.
 pub struct GoogleParser {
-    /// Track tool calls being accumulated
-    tool_accumulators: std::sync::Mutex<std::collections::HashMap<usize, ToolCallAccumulator>>,
+    state: std::sync::Mutex<GoogleStreamState>,
+}
+
+/// Per-stream state carried across chunks
+#[derive(Default)]
+struct GoogleStreamState {
+    /// Thought text of the open thinking block, if any
+    thinking: Option<String>,
+    /// Index of the next thinking block
+    thinking_index: usize,
+    /// Function calls seen so far, completed on finish
+    tool_calls: Vec<AiToolCall>,
 }
 
 impl GoogleParser {
     pub fn new() -> Self {
         Self {
-            tool_accumulators: std::sync::Mutex::new(std::collections::HashMap::new()),
+            state: std::sync::Mutex::new(GoogleStreamState::default()),
         }
     }
 
-    /// Lock tool accumulators with proper error handling
-    fn lock_tool_accumulators(
-        &self,
-    ) -> anyhow::Result<
-        std::sync::MutexGuard<'_, std::collections::HashMap<usize, ToolCallAccumulator>>,
-    > {
-        self.tool_accumulators
+    /// Lock stream state with proper error handling
+    fn lock_state(&self) -> anyhow::Result<std::sync::MutexGuard<'_, GoogleStreamState>> {
+        self.state
             .lock()
-            .map_err(|e| anyhow::anyhow!("Tool accumulators lock poisoned: {}", e))
+            .map_err(|e| anyhow::anyhow!("Google stream state lock poisoned: {}", e))
     }
 
     /// Parse Google finish reason to our FinishReason enum
@@ -44,6 +54,98 @@ impl GoogleParser {
             _ => FinishReason::Other(reason.to_string()),
         }
     }
+
+    /// Parse `usageMetadata` (cumulative in every chunk)
+    fn parse_usage(usage: &Value) -> Option<Usage> {
+        let count = |key: &str| usage.get(key).and_then(|t| t.as_u64()).unwrap_or(0) as usize;
+        let prompt = count("promptTokenCount");
+        // Thought tokens are billed as output
+        let completion = count("candidatesTokenCount") + count("thoughtsTokenCount");
+        // Gemini 2.5+ reports implicit cache hits via cachedContentTokenCount
+        let cached = count("cachedContentTokenCount");
+        if prompt == 0 && completion == 0 {
+            return None;
+        }
+        Some(Usage {
+            prompt_tokens: prompt,
+            completion_tokens: completion,
+            total_tokens: prompt + completion,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: cached,
+        })
+    }
+
+    /// Close the open thinking block, attaching the signature Gemini sends
+    /// on the first part after the thoughts
+    fn close_thinking(state: &mut GoogleStreamState, signature: &str, events: &mut Vec<SseEvent>) {
+        if let Some(thinking) = state.thinking.take() {
+            events.push(SseEvent::ThinkingComplete {
+                index: state.thinking_index,
+                thinking,
+                signature: signature.to_string(),
+            });
+            state.thinking_index += 1;
+        }
+    }
+
+    fn parse_part(state: &mut GoogleStreamState, part: &Value, events: &mut Vec<SseEvent>) {
+        let text = part.get("text").and_then(|t| t.as_str());
+        let signature = part
+            .get("thoughtSignature")
+            .and_then(|s| s.as_str())
+            .unwrap_or("");
+
+        // Thought summary (only sent when includeThoughts is set)
+        if part.get("thought").and_then(|t| t.as_bool()) == Some(true) {
+            let Some(text) = text.filter(|t| !t.is_empty()) else {
+                return;
+            };
+            let index = state.thinking_index;
+            let thinking = state.thinking.get_or_insert_with(|| {
+                events.push(SseEvent::ThinkingStart { index });
+                String::new()
+            });
+            thinking.push_str(text);
+            events.push(SseEvent::ThinkingDelta {
+                index,
+                thinking: text.to_string(),
+            });
+            return;
+        }
+
+        Self::close_thinking(state, signature, events);
+
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            events.push(SseEvent::TextDelta(text.to_string()));
+        }
+
+        // Function call (tool use) - always delivered complete
+        if let Some(function_call) = part.get("functionCall") {
+            let name = function_call
+                .get("name")
+                .and_then(|n| n.as_str())
+                .unwrap_or("")
+                .to_string();
+            if name.is_empty() {
+                return;
+            }
+            let arguments = function_call
+                .get("args")
+                .cloned()
+                .unwrap_or_else(|| serde_json::json!({}));
+            // Gemini doesn't assign call IDs, so generate one
+            let id = format!("google_{}", uuid::Uuid::new_v4());
+            events.push(SseEvent::ToolCallStart {
+                id: id.clone(),
+                name: name.clone(),
+            });
+            state.tool_calls.push(AiToolCall {
+                id,
+                name,
+                arguments,
+            });
+        }
+    }
 }
 
 impl Default for GoogleParser {
@@ -55,103 +157,158 @@ impl Default for GoogleParser {
 #[async_trait::async_trait]
 impl SseParser for GoogleParser {
     async fn parse_event(&self, json: &Value) -> Result<SseEvent> {
+        let mut state = self.lock_state()?;
+        let mut events = Vec::new();
+
         // Google Gemini format: {"candidates": [{...}]}
-        if let Some(candidates) = json.get("candidates").and_then(|c| c.as_array()) {
-            if let Some(candidate) = candidates.first() {
-                // Check for finish reason
-                if let Some(finish_reason) = candidate.get("finishReason").and_then(|f| f.as_str())
-                {
-                    // If there's content with finish, extract it first
-                    if let Some(content) = candidate.get("content") {
-                        if let Some(parts) = content.get("parts").and_then(|p| p.as_array()) {
-                            for part in parts {
-                                // Text content
-                                if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                                    if !text.is_empty() {
-                                        // Return text delta, finish will be next chunk
-                                        return Ok(SseEvent::TextDelta(text.to_string()));
-                                    }
-                                }
-                            }
-                        }
-                    }
-                    // Return finish event
-                    return Ok(SseEvent::Finish {
+        let candidate = json
+            .get("candidates")
+            .and_then(|c| c.as_array())
+            .and_then(|c| c.first());
+
+        if let Some(candidate) = candidate {
+            if let Some(parts) = candidate
+                .get("content")
+                .and_then(|c| c.get("parts"))
+                .and_then(|p| p.as_array())
+            {
+                for part in parts {
+                    Self::parse_part(&mut state, part, &mut events);
+                }
+            }
+
+            // Finish comes with (or after) the last content; usage is final here
+            if let Some(finish_reason) = candidate.get("finishReason").and_then(|f| f.as_str()) {
+                Self::close_thinking(&mut state, "", &mut events);
+                let usage = json.get("usageMetadata").and_then(Self::parse_usage);
+                let tool_calls = std::mem::take(&mut state.tool_calls);
+                if tool_calls.is_empty() {
+                    events.push(SseEvent::Finish {
                         reason: Self::parse_finish_reason(finish_reason),
-                        usage: None,
+                        usage,
                     });
+                } else {
+                    // Gemini reports STOP even when the turn ends in function calls
+                    events.push(SseEvent::FinishWithToolCalls { tool_calls, usage });
                 }
+            }
+        }
+
+        Ok(match events.len() {
+            0 => SseEvent::Skip,
+            1 => events.remove(0),
+            _ => SseEvent::Batch(events),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
 
-                // Extract content parts
-                if let Some(content) = candidate.get("content") {
-                    if let Some(parts) = content.get("parts").and_then(|p| p.as_array()) {
-                        for part in parts {
-                            // Text content
-                            if let Some(text) = part.get("text").and_then(|t| t.as_str()) {
-                                if !text.is_empty() {
-                                    return Ok(SseEvent::TextDelta(text.to_string()));
-                                }
-                            }
-
-                            // Function call (tool use)
-                            if let Some(function_call) = part.get("functionCall") {
-                                let name = function_call
-                                    .get("name")
-                                    .and_then(|n| n.as_str())
-                                    .unwrap_or("")
-                                    .to_string();
-                                let args = function_call
-                                    .get("args")
-                                    .map(|a| serde_json::to_string(a).unwrap_or_default())
-                                    .unwrap_or_default();
-
-                                if !name.is_empty() {
-                                    // Generate a unique ID for the tool call
-                                    let id = format!("google_{}", uuid::Uuid::new_v4());
-
-                                    // Store accumulator
-                                    let mut accumulators = self.lock_tool_accumulators()?;
-                                    let index = accumulators.len();
-                                    let mut acc =
-                                        ToolCallAccumulator::new(id.clone(), name.clone());
-                                    acc.add_arguments(&args);
-                                    accumulators.insert(index, acc);
-
-                                    return Ok(SseEvent::ToolCallStart { id, name });
-                                }
-                            }
-                        }
+    fn flatten(event: SseEvent) -> Vec<SseEvent> {
+        match event {
+            SseEvent::Batch(events) => events,
+            event => vec![event],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_text_then_finish() {
+        let parser = GoogleParser::new();
+        let event = parser
+            .parse_event(&json!({
+                "candidates": [{"content": {"parts": [{"text": "Hello"}], "role": "model"}}]
+            }))
+            .await
+            .unwrap();
+        assert!(matches!(event, SseEvent::TextDelta(ref t) if t == "Hello"));
+
+        let events = flatten(
+            parser
+                .parse_event(&json!({
+                    "candidates": [{
+                        "content": {"parts": [{"text": " world"}], "role": "model"},
+                        "finishReason": "STOP"
+                    }],
+                    "usageMetadata": {
+                        "promptTokenCount": 10,
+                        "candidatesTokenCount": 5,
+                        "thoughtsTokenCount": 3
                     }
-                }
+                }))
+                .await
+                .unwrap(),
+        );
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], SseEvent::TextDelta(ref t) if t == " world"));
+        match &events[1] {
+            SseEvent::Finish { reason, usage } => {
+                assert_eq!(*reason, FinishReason::Stop);
+                let usage = usage.as_ref().unwrap();
+                assert_eq!(usage.prompt_tokens, 10);
+                assert_eq!(usage.completion_tokens, 8);
             }
+            _ => panic!("expected Finish"),
         }
+    }
+
+    #[tokio::test]
+    async fn test_thinking_and_function_calls() {
+        let parser = GoogleParser::new();
+        let events = flatten(
+            parser
+                .parse_event(&json!({
+                    "candidates": [{"content": {"parts": [
+                        {"text": "Planning", "thought": true}
+                    ]}}]
+                }))
+                .await
+                .unwrap(),
+        );
+        assert!(matches!(events[0], SseEvent::ThinkingStart { index: 0 }));
+        assert!(matches!(
+            events[1],
+            SseEvent::ThinkingDelta { index: 0, .. }
+        ));
 
-        // Check for usage metadata
-        if let Some(usage) = json.get("usageMetadata") {
-            let prompt = usage
-                .get("promptTokenCount")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0) as usize;
-            let completion = usage
-                .get("candidatesTokenCount")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0) as usize;
-            // Gemini 2.5+ reports implicit cache hits via cachedContentTokenCount
-            let cached = usage
-                .get("cachedContentTokenCount")
-                .and_then(|t| t.as_u64())
-                .unwrap_or(0) as usize;
-            if prompt > 0 || completion > 0 {
-                return Ok(SseEvent::Usage(crate::ai::types::Usage {
-                    prompt_tokens: prompt,
-                    completion_tokens: completion,
-                    total_tokens: prompt + completion,
-                    cache_creation_input_tokens: 0,
-                    cache_read_input_tokens: cached,
-                }));
+        // Final chunk carries the signature, two calls and the finish reason
+        let events = flatten(
+            parser
+                .parse_event(&json!({
+                    "candidates": [{
+                        "content": {"parts": [
+                            {"functionCall": {"name": "read", "args": {"path": "a"}}, "thoughtSignature": "sig"},
+                            {"functionCall": {"name": "read", "args": {"path": "b"}}}
+                        ]},
+                        "finishReason": "STOP"
+                    }]
+                }))
+                .await
+                .unwrap(),
+        );
+        match &events[0] {
+            SseEvent::ThinkingComplete {
+                thinking,
+                signature,
+                ..
+            } => {
+                assert_eq!(thinking, "Planning");
+                assert_eq!(signature, "sig");
             }
+            _ => panic!("expected ThinkingComplete"),
+        }
+        assert!(matches!(events[1], SseEvent::ToolCallStart { ref name, .. } if name == "read"));
+        assert!(matches!(events[2], SseEvent::ToolCallStart { .. }));
+        match &events[3] {
+            SseEvent::FinishWithToolCalls { tool_calls, .. } => {
+                assert_eq!(tool_calls.len(), 2);
+                assert_eq!(tool_calls[0].arguments["path"], "a");
+                assert_eq!(tool_calls[1].arguments["path"], "b");
+                assert_ne!(tool_calls[0].id, tool_calls[1].id);
+            }
+            _ => panic!("expected FinishWithToolCalls"),
         }
-
-        Ok(SseEvent::Skip)
     }
 }
@@ -8,7 +8,8 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::LazyLock;
 
-use crate::ai::models::ApiFormat;
+use crate::ai::custom_providers;
+use crate::ai::models::{ApiFormat, ModelPricing};
 use crate::auth::{AnthropicAuthType, OpenAIAuthType};
 
 /// ChatGPT backend API for OAuth users (Responses API)
@@ -20,9 +21,16 @@ pub const CHATGPT_RESPONSES_API: &str = "https://chatgpt.com/backend-api/codex/r
 /// This endpoint is used when authenticating with an API key.
 pub const OPENAI_CHAT_API: &str = "https://api.openai.com/v1/chat/completions";
 
+/// Gemini API models endpoint (Google AI Studio keys)
+/// Requests go to `{base}/{model}:generateContent` or `:streamGenerateContent`.
+pub const GEMINI_API_BASE: &str = "https://generativelanguage.googleapis.com/v1beta/models";
+
 /// Unique identifier for each supported provider
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
-#[serde(rename_all = "snake_case")]
+///
+/// Built-in vendors are fixed variants. Providers declared by the user in
+/// `~/.krusty/providers.json` are `Custom`, indexed into the loaded list
+/// (see [`crate::ai::custom_providers`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
 pub enum ProviderId {
     #[default]
     MiniMax,
@@ -30,19 +38,43 @@ pub enum ProviderId {
     ZAi,
     Anthropic,
     OpenAI,
+    Gemini,
+    /// User-defined provider (index into the custom provider list)
+    Custom(u16),
 }
 
+/// Built-in provider order: MiniMax first (default), then smallest to largest
+const BUILTIN_ORDER: [ProviderId; 5] = [
+    ProviderId::MiniMax,   // Default provider, always first
+    ProviderId::Anthropic, // Anthropic direct (OAuth or API key)
+    ProviderId::OpenAI,    // OpenAI direct (OAuth or API key)
+    ProviderId::Gemini,    // Google AI Studio (API key)
+    ProviderId::ZAi,       // GLM-5
+];
+
+/// All provider IDs in display order, with user-defined providers ahead of OpenRouter
+static ALL_PROVIDER_IDS: LazyLock<Vec<ProviderId>> = LazyLock::new(|| {
+    let custom = (0..custom_providers::all().len()).map(|i| ProviderId::Custom(i as u16));
+    BUILTIN_ORDER
+        .into_iter()
+        .chain(custom)
+        .chain(std::iter::once(ProviderId::OpenRouter)) // 100+ dynamic models, always last
+        .collect()
+});
+
 impl ProviderId {
     /// Get all available provider IDs
-    /// Order: MiniMax first (default), then smallest to largest, OpenRouter last
+    /// Order: MiniMax first (default), then smallest to largest, user-defined
+    /// providers, OpenRouter last
     pub fn all() -> &'static [ProviderId] {
-        &[
-            ProviderId::MiniMax,    // Default provider, always first
-            ProviderId::Anthropic,  // Anthropic direct (OAuth or API key)
-            ProviderId::OpenAI,     // OpenAI direct (OAuth or API key)
-            ProviderId::ZAi,        // GLM-5
-            ProviderId::OpenRouter, // 100+ dynamic models, always last
-        ]
+        &ALL_PROVIDER_IDS
+    }
+
+    /// Built-in providers, OpenRouter included
+    pub fn builtin() -> impl Iterator<Item = ProviderId> {
+        BUILTIN_ORDER
+            .into_iter()
+            .chain(std::iter::once(ProviderId::OpenRouter))
     }
 
     /// Get the storage key for this provider (used in credentials.json)
@@ -53,9 +85,26 @@ impl ProviderId {
             ProviderId::ZAi => "z_ai",
             ProviderId::Anthropic => "anthropic",
             ProviderId::OpenAI => "openai",
+            ProviderId::Gemini => "gemini",
+            ProviderId::Custom(index) => custom_providers::get(*index)
+                .map(|p| p.key.as_str())
+                .unwrap_or("custom"),
         }
     }
 
+    /// Look up a provider by its storage key (built-in or user-defined)
+    pub fn from_storage_key(key: &str) -> Option<ProviderId> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|p| p.storage_key().eq_ignore_ascii_case(key))
+    }
+
+    /// Check if this is a user-defined provider
+    pub fn is_custom(&self) -> bool {
+        matches!(self, ProviderId::Custom(_))
+    }
+
     /// Check if this provider supports OAuth authentication
     pub fn supports_oauth(&self) -> bool {
         matches!(self, ProviderId::OpenAI | ProviderId::Anthropic)
@@ -74,6 +123,36 @@ impl ProviderId {
             _ => vec![AuthMethod::ApiKey],
         }
     }
+
+    /// Serialized name used before user-defined providers existed
+    /// (serde `snake_case` of the variant), kept so stored files stay readable
+    pub(crate) fn legacy_name(&self) -> Option<&'static str> {
+        match self {
+            ProviderId::MiniMax => Some("mini_max"),
+            ProviderId::OpenRouter => Some("open_router"),
+            ProviderId::ZAi => Some("z_ai"),
+            ProviderId::Anthropic => Some("anthropic"),
+            ProviderId::OpenAI => Some("open_a_i"),
+            ProviderId::Gemini => Some("gemini"),
+            ProviderId::Custom(_) => None,
+        }
+    }
+}
+
+impl Serialize for ProviderId {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.legacy_name().unwrap_or_else(|| self.storage_key()))
+    }
+}
+
+impl<'de> Deserialize<'de> for ProviderId {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        ProviderId::builtin()
+            .find(|p| p.legacy_name() == Some(name.as_str()))
+            .or_else(|| ProviderId::from_storage_key(&name))
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown provider: {}", name)))
+    }
 }
 
 impl fmt::Display for ProviderId {
@@ -84,6 +163,11 @@ impl fmt::Display for ProviderId {
             ProviderId::ZAi => write!(f, "Z.ai"),
             ProviderId::Anthropic => write!(f, "Anthropic"),
             ProviderId::OpenAI => write!(f, "OpenAI"),
+            ProviderId::Gemini => write!(f, "Google Gemini"),
+            ProviderId::Custom(index) => match custom_providers::get(*index) {
+                Some(p) => write!(f, "{}", p.config.name),
+                None => write!(f, "Custom"),
+            },
         }
     }
 }
@@ -93,9 +177,17 @@ impl fmt::Display for ProviderId {
 pub enum AuthHeader {
     /// Use `x-api-key: <key>` header (Anthropic style)
     #[default]
+    #[serde(alias = "x_api_key")]
     XApiKey,
     /// Use `Authorization: Bearer <key>` header (OpenAI style)
+    #[serde(alias = "bearer")]
     Bearer,
+    /// Use `x-goog-api-key: <key>` header (Google Gemini)
+    #[serde(alias = "x_goog_api_key")]
+    XGoogApiKey,
+    /// Send no credentials (local servers such as Ollama or llama.cpp)
+    #[serde(alias = "none")]
+    None,
 }
 
 // ============================================================================
@@ -107,11 +199,17 @@ pub enum AuthHeader {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ReasoningFormat {
     /// Anthropic Claude: `thinking.budget_tokens` (see `DEFAULT_THINKING_BUDGET`)
+    #[serde(alias = "anthropic")]
     Anthropic,
     /// OpenAI o1/o3/GPT-5: `reasoning_effort: "high"`
+    #[serde(alias = "openai")]
     OpenAI,
     /// DeepSeek R1: `reasoning.enabled: true`
+    #[serde(alias = "deepseek")]
     DeepSeek,
+    /// Google Gemini: `thinkingConfig.thinkingBudget` with `includeThoughts`
+    #[serde(alias = "google")]
+    Google,
 }
 
 /// Information about a model offered by a provider
@@ -127,6 +225,12 @@ pub struct ModelInfo {
     pub max_output: usize,
     /// Reasoning/thinking support (None = not supported)
     pub reasoning: Option<ReasoningFormat>,
+    /// Input price per million tokens (None if unknown)
+    #[serde(default)]
+    pub input_price: Option<f64>,
+    /// Output price per million tokens (None if unknown)
+    #[serde(default)]
+    pub output_price: Option<f64>,
 }
 
 impl ModelInfo {
@@ -137,14 +241,34 @@ impl ModelInfo {
             context_window,
             max_output,
             reasoning: None,
+            input_price: None,
+            output_price: None,
         }
     }
 
+    /// Add list pricing (USD per million input/output tokens)
+    pub fn with_pricing(mut self, input: f64, output: f64) -> Self {
+        self.input_price = Some(input);
+        self.output_price = Some(output);
+        self
+    }
+
+    /// Pricing used for cost accounting (None if unknown)
+    pub fn pricing(&self) -> Option<ModelPricing> {
+        Some(ModelPricing::new(self.input_price?, self.output_price?))
+    }
+
     /// Add Anthropic-style extended thinking support
     pub fn with_anthropic_thinking(mut self) -> Self {
         self.reasoning = Some(ReasoningFormat::Anthropic);
         self
     }
+
+    /// Add Gemini-style thinking support
+    pub fn with_google_thinking(mut self) -> Self {
+        self.reasoning = Some(ReasoningFormat::Google);
+        self
+    }
 }
 
 /// Configuration for an AI provider
@@ -171,6 +295,15 @@ pub struct ProviderConfig {
     /// Custom headers to send with requests
     #[serde(default)]
     pub custom_headers: HashMap<String, String>,
+    /// Wire format spoken by `base_url`
+    #[serde(default)]
+    pub api_format: ApiFormat,
+    /// Environment variable holding the API key (user-defined providers)
+    #[serde(default)]
+    pub api_key_env: Option<String>,
+    /// Endpoint listing available models (OpenAI `/v1/models` or Ollama `/api/tags`)
+    #[serde(default)]
+    pub models_url: Option<String>,
 }
 
 impl ProviderConfig {
@@ -383,8 +516,17 @@ impl ProviderCapabilities {
                 web_plugins: false,
                 supports_vision: true,
             },
+            // Gemini: multimodal input; caching is implicit so no cache_control fields
+            ProviderId::Gemini => Self {
+                web_search: false,
+                web_fetch: false,
+                context_management: false,
+                prompt_caching: false,
+                web_plugins: false,
+                supports_vision: true,
+            },
             // Other providers: minimal capabilities (no vision)
-            ProviderId::ZAi | ProviderId::MiniMax => Self::default(),
+            ProviderId::ZAi | ProviderId::MiniMax | ProviderId::Custom(_) => Self::default(),
         }
     }
 }
@@ -478,6 +620,9 @@ static BUILTIN_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
             dynamic_models: true,
             pricing_hint: None,
             custom_headers: HashMap::new(),
+            api_format: ApiFormat::Anthropic,
+            api_key_env: None,
+            models_url: None,
         },
         // Z.ai - GLM Coding Plan (Anthropic-compatible endpoint)
         ProviderConfig {
@@ -491,6 +636,9 @@ static BUILTIN_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
             dynamic_models: false,
             pricing_hint: None,
             custom_headers: HashMap::new(),
+            api_format: ApiFormat::Anthropic,
+            api_key_env: None,
+            models_url: None,
         },
         // MiniMax - M2.5 (Anthropic-compatible API)
         ProviderConfig {
@@ -501,12 +649,16 @@ static BUILTIN_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
             auth_header: AuthHeader::XApiKey,
             models: vec![
                 ModelInfo::new("MiniMax-M2.5", "MiniMax M2.5", 204_800, 131_072)
-                    .with_anthropic_thinking(),
+                    .with_anthropic_thinking()
+                    .with_pricing(0.30, 1.20),
             ],
             supports_tools: true,
             dynamic_models: false,
             pricing_hint: None,
             custom_headers: HashMap::new(),
+            api_format: ApiFormat::Anthropic,
+            api_key_env: None,
+            models_url: None,
         },
         // Anthropic - Direct access with OAuth or API key (native Anthropic format)
         ProviderConfig {
@@ -517,18 +669,23 @@ static BUILTIN_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
             auth_header: AuthHeader::Bearer, // OAuth uses Bearer; API key path overrides to XApiKey
             models: vec![
                 ModelInfo::new("claude-opus-4-6", "Claude Opus 4.6", 200_000, 128_000)
-                    .with_anthropic_thinking(),
+                    .with_anthropic_thinking()
+                    .with_pricing(5.0, 25.0),
                 ModelInfo::new(
                     "claude-haiku-4-5-20251001",
                     "Claude Haiku 4.5",
                     200_000,
                     16_384,
-                ),
+                )
+                .with_pricing(1.0, 5.0),
             ],
             supports_tools: true,
             dynamic_models: false,
             pricing_hint: None,
             custom_headers: HashMap::new(),
+            api_format: ApiFormat::Anthropic,
+            api_key_env: None,
+            models_url: None,
         },
         // OpenAI - Direct access with OAuth or API key (OpenAI-compatible format)
         // Supports OAuth browser flow, device code flow, and API key authentication
@@ -548,18 +705,79 @@ static BUILTIN_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
             dynamic_models: true,
             pricing_hint: None,
             custom_headers: HashMap::new(),
+            api_format: ApiFormat::OpenAI,
+            api_key_env: None,
+            models_url: None,
+        },
+        // Google Gemini - AI Studio API key (native Gemini generateContent format)
+        // The model ID and method are appended to base_url per request
+        ProviderConfig {
+            id: ProviderId::Gemini,
+            name: "Google Gemini".to_string(),
+            description: "Gemini 2.5 Pro + Flash (API key)".to_string(),
+            base_url: GEMINI_API_BASE.to_string(),
+            auth_header: AuthHeader::XGoogApiKey,
+            models: vec![
+                // Pricing is the <=200K-token prompt tier
+                ModelInfo::new("gemini-2.5-pro", "Gemini 2.5 Pro", 1_048_576, 65_536)
+                    .with_google_thinking()
+                    .with_pricing(1.25, 10.0),
+                ModelInfo::new("gemini-2.5-flash", "Gemini 2.5 Flash", 1_048_576, 65_536)
+                    .with_google_thinking()
+                    .with_pricing(0.30, 2.50),
+                ModelInfo::new(
+                    "gemini-2.5-flash-lite",
+                    "Gemini 2.5 Flash-Lite",
+                    1_048_576,
+                    65_536,
+                )
+                .with_google_thinking()
+                .with_pricing(0.10, 0.40),
+                ModelInfo::new(
+                    "gemini-3-pro-preview",
+                    "Gemini 3 Pro (Preview)",
+                    1_048_576,
+                    65_536,
+                )
+                .with_google_thinking()
+                .with_pricing(2.0, 12.0),
+            ],
+            supports_tools: true,
+            dynamic_models: false,
+            pricing_hint: None,
+            custom_headers: HashMap::new(),
+            api_format: ApiFormat::Google,
+            api_key_env: None,
+            models_url: None,
         },
     ]
 });
 
+/// Built-in providers followed by user-defined ones
+static ALL_PROVIDERS: LazyLock<Vec<ProviderConfig>> = LazyLock::new(|| {
+    BUILTIN_PROVIDERS
+        .iter()
+        .cloned()
+        .chain(custom_providers::all().iter().map(|p| p.config.clone()))
+        .collect()
+});
+
 /// Get all built-in provider configurations (cached, no allocation)
 pub fn builtin_providers() -> &'static [ProviderConfig] {
     &BUILTIN_PROVIDERS
 }
 
+/// Get built-in and user-defined provider configurations
+pub fn all_providers() -> &'static [ProviderConfig] {
+    &ALL_PROVIDERS
+}
+
 /// Get a specific provider configuration by ID
 pub fn get_provider(id: ProviderId) -> Option<&'static ProviderConfig> {
-    BUILTIN_PROVIDERS.iter().find(|p| p.id == id)
+    match id {
+        ProviderId::Custom(index) => custom_providers::get(index).map(|p| &p.config),
+        _ => BUILTIN_PROVIDERS.iter().find(|p| p.id == id),
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -572,6 +790,7 @@ mod tests {
         assert_eq!(ProviderId::ZAi.to_string(), "Z.ai");
         assert_eq!(ProviderId::Anthropic.to_string(), "Anthropic");
         assert_eq!(ProviderId::OpenAI.to_string(), "OpenAI");
+        assert_eq!(ProviderId::Gemini.to_string(), "Google Gemini");
     }
 
     #[test]
@@ -580,17 +799,39 @@ mod tests {
         assert_eq!(ProviderId::ZAi.storage_key(), "z_ai");
         assert_eq!(ProviderId::Anthropic.storage_key(), "anthropic");
         assert_eq!(ProviderId::OpenAI.storage_key(), "openai");
+        assert_eq!(ProviderId::Gemini.storage_key(), "gemini");
+    }
+
+    #[test]
+    fn test_provider_id_serde_compat() {
+        // Legacy variant names stay stable for stored files
+        assert_eq!(
+            serde_json::to_string(&ProviderId::OpenAI).unwrap(),
+            "\"open_a_i\""
+        );
+        assert_eq!(
+            serde_json::from_str::<ProviderId>("\"mini_max\"").unwrap(),
+            ProviderId::MiniMax
+        );
+        // Storage keys are accepted too
+        assert_eq!(
+            serde_json::from_str::<ProviderId>("\"openrouter\"").unwrap(),
+            ProviderId::OpenRouter
+        );
+        assert!(serde_json::from_str::<ProviderId>("\"nope\"").is_err());
+        assert_eq!(ProviderId::from_storage_key("Z_AI"), Some(ProviderId::ZAi));
     }
 
     #[test]
     fn test_builtin_providers() {
         let providers = builtin_providers();
-        assert_eq!(providers.len(), 5);
+        assert_eq!(providers.len(), 6);
         assert!(providers.iter().any(|p| p.id == ProviderId::MiniMax));
         assert!(providers.iter().any(|p| p.id == ProviderId::OpenRouter));
         assert!(providers.iter().any(|p| p.id == ProviderId::Anthropic));
         assert!(providers.iter().any(|p| p.id == ProviderId::OpenAI));
         assert!(providers.iter().any(|p| p.id == ProviderId::ZAi));
+        assert!(providers.iter().any(|p| p.id == ProviderId::Gemini));
     }
 
     #[test]
@@ -699,6 +940,12 @@ mod tests {
         assert!(!openai.web_plugins);
         assert!(openai.supports_vision);
 
+        let gemini = ProviderCapabilities::for_provider(ProviderId::Gemini);
+        assert!(!gemini.web_search);
+        assert!(!gemini.prompt_caching);
+        assert!(!gemini.web_plugins);
+        assert!(gemini.supports_vision);
+
         let minimax = ProviderCapabilities::for_provider(ProviderId::MiniMax);
         assert!(!minimax.web_search);
         assert!(!minimax.web_plugins);
@@ -742,4 +989,18 @@ mod tests {
         assert!(provider.dynamic_models);
         assert!(!provider.models.is_empty());
     }
+
+    #[test]
+    fn test_gemini_config() {
+        let provider = get_provider(ProviderId::Gemini).unwrap();
+        assert_eq!(provider.base_url, GEMINI_API_BASE);
+        assert_eq!(provider.auth_header, AuthHeader::XGoogApiKey);
+        assert_eq!(provider.api_format, ApiFormat::Google);
+        assert_eq!(provider.default_model(), "gemini-2.5-pro");
+        assert!(provider
+            .models
+            .iter()
+            .all(|m| m.reasoning == Some(ReasoningFormat::Google)));
+        assert!(!ProviderId::Gemini.supports_oauth());
+    }
 }
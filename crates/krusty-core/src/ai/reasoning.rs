@@ -16,7 +16,8 @@ impl ReasoningConfig {
     ///
     /// Returns the JSON value to merge into the request body for the given format.
     /// For Anthropic, this is `thinking: {...}`. For OpenAI, `reasoning_effort: "high"`.
-    /// For DeepSeek, `reasoning: { enabled: true }`.
+    /// For DeepSeek, `reasoning: { enabled: true }`. For Google, the
+    /// `generationConfig.thinkingConfig` object.
     pub fn build(
         format: Option<ReasoningFormat>,
         enabled: bool,
@@ -47,6 +48,13 @@ impl ReasoningConfig {
                     "reasoning": { "enabled": true }
                 }))
             }
+            Some(ReasoningFormat::Google) => {
+                // Dynamic budget (-1) lets each Gemini model pick up to its own maximum
+                Some(json!({
+                    "includeThoughts": true,
+                    "thinkingBudget": -1
+                }))
+            }
             None => None,
         }
     }
@@ -73,7 +81,9 @@ impl ReasoningConfig {
     ) -> u32 {
         match format {
             Some(ReasoningFormat::Anthropic) => 64000,
-            Some(ReasoningFormat::OpenAI | ReasoningFormat::DeepSeek) => fallback,
+            Some(ReasoningFormat::OpenAI | ReasoningFormat::DeepSeek | ReasoningFormat::Google) => {
+                fallback
+            }
             None => {
                 if legacy_thinking_enabled {
                     64000
@@ -142,6 +152,14 @@ mod tests {
         assert_eq!(val["reasoning"]["enabled"], true);
     }
 
+    #[test]
+    fn test_build_google() {
+        let result = ReasoningConfig::build(Some(ReasoningFormat::Google), true, None, None);
+        let val = result.unwrap();
+        assert_eq!(val["includeThoughts"], true);
+        assert_eq!(val["thinkingBudget"], -1);
+    }
+
     #[test]
     fn test_build_disabled() {
         let result = ReasoningConfig::build(Some(ReasoningFormat::Anthropic), false, None, None);
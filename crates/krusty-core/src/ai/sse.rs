@@ -4,7 +4,7 @@
 
 use bytes::Bytes;
 use serde_json::Value;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{debug, info, warn};
 
@@ -193,206 +193,223 @@ impl SseStreamProcessor {
                 self.event_count, elapsed, event_type
             );
 
+            // Some formats (Gemini) carry several parts per JSON chunk
             match parser.parse_event(&json).await? {
-                SseEvent::TextDelta(text) => {
-                    debug!("  -> TextDelta: {} chars", text.len());
-                    self.stream_buffer.process_chunk(text).await;
-                }
-                SseEvent::TextDeltaWithCitations { text, citations } => {
-                    debug!(
-                        "  -> TextDeltaWithCitations: {} chars, {} citations",
-                        text.len(),
-                        citations.len()
-                    );
-                    let _ = self.tx.send(StreamPart::TextDeltaWithCitations {
-                        delta: text,
-                        citations,
-                    });
-                }
-                SseEvent::ToolCallStart { id, name } => {
-                    info!(
-                        "SSE ToolCallStart: id={}, name={} at {:?}",
-                        id, name, elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::ToolCallStart { id, name });
-                }
-                SseEvent::ToolCallDelta { id, delta } => {
-                    debug!("  -> ToolCallDelta: id={}, {} chars", id, delta.len());
-                    let _ = self.tx.send(StreamPart::ToolCallDelta { id, delta });
-                }
-                SseEvent::ToolCallComplete(tool_call) => {
-                    info!(
-                        "SSE ToolCallComplete: id={}, name={} at {:?}",
-                        tool_call.id, tool_call.name, elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::ToolCallComplete { tool_call });
-                }
-                // Server-executed tools
-                SseEvent::ServerToolStart { id, name } => {
-                    info!(
-                        "SSE ServerToolStart: id={}, name={} at {:?}",
-                        id, name, elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::ServerToolStart { id, name });
-                }
-                SseEvent::ServerToolDelta { id, delta } => {
-                    debug!("  -> ServerToolDelta: id={}, {} chars", id, delta.len());
-                    let _ = self.tx.send(StreamPart::ServerToolDelta { id, delta });
-                }
-                SseEvent::ServerToolComplete { id, name, input } => {
-                    info!(
-                        "SSE ServerToolComplete: id={}, name={} at {:?}",
-                        id, name, elapsed
-                    );
-                    let _ = self
-                        .tx
-                        .send(StreamPart::ServerToolComplete { id, name, input });
+                SseEvent::Batch(events) => {
+                    for event in events {
+                        self.dispatch_event(event, elapsed).await;
+                    }
                 }
-                SseEvent::WebSearchResults {
+                event => self.dispatch_event(event, elapsed).await,
+            }
+        } else if !data.is_empty() && !data.trim().is_empty() {
+            warn!(
+                "Failed to parse SSE JSON (event #{}): {}",
+                self.event_count, data
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Forward a single parsed event to the stream
+    async fn dispatch_event(&mut self, event: SseEvent, elapsed: Duration) {
+        match event {
+            SseEvent::TextDelta(text) => {
+                debug!("  -> TextDelta: {} chars", text.len());
+                self.stream_buffer.process_chunk(text).await;
+            }
+            SseEvent::TextDeltaWithCitations { text, citations } => {
+                debug!(
+                    "  -> TextDeltaWithCitations: {} chars, {} citations",
+                    text.len(),
+                    citations.len()
+                );
+                let _ = self.tx.send(StreamPart::TextDeltaWithCitations {
+                    delta: text,
+                    citations,
+                });
+            }
+            SseEvent::ToolCallStart { id, name } => {
+                info!(
+                    "SSE ToolCallStart: id={}, name={} at {:?}",
+                    id, name, elapsed
+                );
+                let _ = self.tx.send(StreamPart::ToolCallStart { id, name });
+            }
+            SseEvent::ToolCallDelta { id, delta } => {
+                debug!("  -> ToolCallDelta: id={}, {} chars", id, delta.len());
+                let _ = self.tx.send(StreamPart::ToolCallDelta { id, delta });
+            }
+            SseEvent::ToolCallComplete(tool_call) => {
+                info!(
+                    "SSE ToolCallComplete: id={}, name={} at {:?}",
+                    tool_call.id, tool_call.name, elapsed
+                );
+                let _ = self.tx.send(StreamPart::ToolCallComplete { tool_call });
+            }
+            // Server-executed tools
+            SseEvent::ServerToolStart { id, name } => {
+                info!(
+                    "SSE ServerToolStart: id={}, name={} at {:?}",
+                    id, name, elapsed
+                );
+                let _ = self.tx.send(StreamPart::ServerToolStart { id, name });
+            }
+            SseEvent::ServerToolDelta { id, delta } => {
+                debug!("  -> ServerToolDelta: id={}, {} chars", id, delta.len());
+                let _ = self.tx.send(StreamPart::ServerToolDelta { id, delta });
+            }
+            SseEvent::ServerToolComplete { id, name, input } => {
+                info!(
+                    "SSE ServerToolComplete: id={}, name={} at {:?}",
+                    id, name, elapsed
+                );
+                let _ = self
+                    .tx
+                    .send(StreamPart::ServerToolComplete { id, name, input });
+            }
+            SseEvent::WebSearchResults {
+                tool_use_id,
+                results,
+            } => {
+                info!(
+                    "SSE WebSearchResults: {} results for {} at {:?}",
+                    results.len(),
+                    tool_use_id,
+                    elapsed
+                );
+                let _ = self.tx.send(StreamPart::WebSearchResults {
                     tool_use_id,
                     results,
-                } => {
-                    info!(
-                        "SSE WebSearchResults: {} results for {} at {:?}",
-                        results.len(),
-                        tool_use_id,
-                        elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::WebSearchResults {
-                        tool_use_id,
-                        results,
-                    });
-                }
-                SseEvent::WebFetchResult {
+                });
+            }
+            SseEvent::WebFetchResult {
+                tool_use_id,
+                content,
+            } => {
+                info!(
+                    "SSE WebFetchResult: url={} for {} at {:?}",
+                    content.url, tool_use_id, elapsed
+                );
+                let _ = self.tx.send(StreamPart::WebFetchResult {
                     tool_use_id,
                     content,
-                } => {
-                    info!(
-                        "SSE WebFetchResult: url={} for {} at {:?}",
-                        content.url, tool_use_id, elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::WebFetchResult {
-                        tool_use_id,
-                        content,
-                    });
-                }
-                SseEvent::ServerToolError {
+                });
+            }
+            SseEvent::ServerToolError {
+                tool_use_id,
+                error_code,
+            } => {
+                warn!(
+                    "SSE ServerToolError: {} for {} at {:?}",
+                    error_code, tool_use_id, elapsed
+                );
+                let _ = self.tx.send(StreamPart::ServerToolError {
                     tool_use_id,
                     error_code,
-                } => {
-                    warn!(
-                        "SSE ServerToolError: {} for {} at {:?}",
-                        error_code, tool_use_id, elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::ServerToolError {
-                        tool_use_id,
-                        error_code,
-                    });
-                }
-                // Extended thinking
-                SseEvent::ThinkingStart { index } => {
-                    info!("SSE ThinkingStart: index={} at {:?}", index, elapsed);
-                    let _ = self.tx.send(StreamPart::ThinkingStart { index });
-                }
-                SseEvent::ThinkingDelta { index, thinking } => {
-                    debug!(
-                        "  -> ThinkingDelta: index={}, {} chars",
-                        index,
-                        thinking.len()
-                    );
-                    let _ = self.tx.send(StreamPart::ThinkingDelta { index, thinking });
-                }
-                SseEvent::SignatureDelta { index, signature } => {
-                    debug!(
-                        "  -> SignatureDelta: index={}, {} chars",
-                        index,
-                        signature.len()
-                    );
-                    let _ = self
-                        .tx
-                        .send(StreamPart::SignatureDelta { index, signature });
-                }
-                SseEvent::ThinkingComplete {
+                });
+            }
+            // Extended thinking
+            SseEvent::ThinkingStart { index } => {
+                info!("SSE ThinkingStart: index={} at {:?}", index, elapsed);
+                let _ = self.tx.send(StreamPart::ThinkingStart { index });
+            }
+            SseEvent::ThinkingDelta { index, thinking } => {
+                debug!(
+                    "  -> ThinkingDelta: index={}, {} chars",
+                    index,
+                    thinking.len()
+                );
+                let _ = self.tx.send(StreamPart::ThinkingDelta { index, thinking });
+            }
+            SseEvent::SignatureDelta { index, signature } => {
+                debug!(
+                    "  -> SignatureDelta: index={}, {} chars",
+                    index,
+                    signature.len()
+                );
+                let _ = self
+                    .tx
+                    .send(StreamPart::SignatureDelta { index, signature });
+            }
+            SseEvent::ThinkingComplete {
+                index,
+                thinking,
+                signature,
+            } => {
+                info!(
+                    "SSE ThinkingComplete: index={}, thinking={} chars, sig={} chars at {:?}",
+                    index,
+                    thinking.len(),
+                    signature.len(),
+                    elapsed
+                );
+                let _ = self.tx.send(StreamPart::ThinkingComplete {
                     index,
                     thinking,
                     signature,
-                } => {
-                    info!(
-                        "SSE ThinkingComplete: index={}, thinking={} chars, sig={} chars at {:?}",
-                        index,
-                        thinking.len(),
-                        signature.len(),
-                        elapsed
-                    );
-                    let _ = self.tx.send(StreamPart::ThinkingComplete {
-                        index,
-                        thinking,
-                        signature,
-                    });
-                }
-                SseEvent::Finish { reason, usage } => {
-                    info!(
-                        "SSE Finish: reason={:?} at {:?} ({} events, {} bytes)",
-                        reason, elapsed, self.event_count, self.bytes_received
-                    );
-                    self.stream_buffer.flush().await;
-                    // Send usage before finish if present
-                    if let Some(usage) = usage {
-                        self.emit_usage(usage, "from finish");
-                    }
-                    let _ = self.tx.send(StreamPart::Finish { reason });
-                }
-                SseEvent::FinishWithToolCalls { tool_calls, usage } => {
-                    info!(
-                        "SSE FinishWithToolCalls: {} tool calls at {:?} ({} events, {} bytes)",
-                        tool_calls.len(),
-                        elapsed,
-                        self.event_count,
-                        self.bytes_received
-                    );
-                    self.stream_buffer.flush().await;
-                    // Emit ToolCallComplete for each accumulated tool call
-                    for tool_call in tool_calls {
-                        info!(
-                            "  -> Completing tool call: id={}, name={}",
-                            tool_call.id, tool_call.name
-                        );
-                        let _ = self.tx.send(StreamPart::ToolCallComplete { tool_call });
-                    }
-                    // Send usage before finish if present
-                    if let Some(usage) = usage {
-                        self.emit_usage(usage, "from finish");
-                    }
-                    // Then send the finish signal
-                    let _ = self.tx.send(StreamPart::Finish {
-                        reason: FinishReason::ToolCalls,
-                    });
-                }
-                SseEvent::Usage(usage) => {
-                    self.emit_usage(usage, "event");
+                });
+            }
+            SseEvent::Finish { reason, usage } => {
+                info!(
+                    "SSE Finish: reason={:?} at {:?} ({} events, {} bytes)",
+                    reason, elapsed, self.event_count, self.bytes_received
+                );
+                self.stream_buffer.flush().await;
+                // Send usage before finish if present
+                if let Some(usage) = usage {
+                    self.emit_usage(usage, "from finish");
                 }
-                SseEvent::ContextEdited(metrics) => {
+                let _ = self.tx.send(StreamPart::Finish { reason });
+            }
+            SseEvent::FinishWithToolCalls { tool_calls, usage } => {
+                info!(
+                    "SSE FinishWithToolCalls: {} tool calls at {:?} ({} events, {} bytes)",
+                    tool_calls.len(),
+                    elapsed,
+                    self.event_count,
+                    self.bytes_received
+                );
+                self.stream_buffer.flush().await;
+                // Emit ToolCallComplete for each accumulated tool call
+                for tool_call in tool_calls {
                     info!(
-                        "SSE ContextEdited: cleared {} tokens ({} tool uses, {} thinking turns)",
-                        metrics.cleared_input_tokens,
-                        metrics.cleared_tool_uses,
-                        metrics.cleared_thinking_turns
+                        "  -> Completing tool call: id={}, name={}",
+                        tool_call.id, tool_call.name
                     );
-                    let _ = self.tx.send(StreamPart::ContextEdited { metrics });
+                    let _ = self.tx.send(StreamPart::ToolCallComplete { tool_call });
                 }
-                SseEvent::Skip => {
-                    // Event should be ignored
-                    debug!("  -> Skip event");
+                // Send usage before finish if present
+                if let Some(usage) = usage {
+                    self.emit_usage(usage, "from finish");
                 }
+                // Then send the finish signal
+                let _ = self.tx.send(StreamPart::Finish {
+                    reason: FinishReason::ToolCalls,
+                });
+            }
+            SseEvent::Usage(usage) => {
+                self.emit_usage(usage, "event");
+            }
+            SseEvent::ContextEdited(metrics) => {
+                info!(
+                    "SSE ContextEdited: cleared {} tokens ({} tool uses, {} thinking turns)",
+                    metrics.cleared_input_tokens,
+                    metrics.cleared_tool_uses,
+                    metrics.cleared_thinking_turns
+                );
+                let _ = self.tx.send(StreamPart::ContextEdited { metrics });
+            }
+            SseEvent::Skip => {
+                // Event should be ignored
+                debug!("  -> Skip event");
+            }
+            SseEvent::Batch(events) => {
+                // Parsers return flat batches; nested ones are not expected
+                warn!("Ignoring nested SSE batch of {} events", events.len());
             }
-        } else if !data.is_empty() && !data.trim().is_empty() {
-            warn!(
-                "Failed to parse SSE JSON (event #{}): {}",
-                self.event_count, data
-            );
         }
-
-        Ok(())
     }
 
     /// Finish processing and ensure all buffers are flushed
@@ -479,6 +496,8 @@ pub enum SseEvent {
     },
     Usage(Usage),
     ContextEdited(ContextEditingMetrics),
+    /// Several events parsed from one chunk, dispatched in order
+    Batch(Vec<SseEvent>),
     Skip,
 }
 
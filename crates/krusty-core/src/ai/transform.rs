@@ -158,7 +158,11 @@ pub fn wrap_provider_options(options: Value, provider_id: ProviderId) -> Provide
             anthropic: Some(options),
             ..Default::default()
         },
-        ProviderId::ZAi | ProviderId::MiniMax | ProviderId::OpenAI => {
+        ProviderId::Gemini => ProviderOptions {
+            google: Some(options),
+            ..Default::default()
+        },
+        ProviderId::ZAi | ProviderId::MiniMax | ProviderId::OpenAI | ProviderId::Custom(_) => {
             // For OpenAI-compatible providers (GLM, MiniMax, OpenAI)
             // Check if options contain reasoning_content (DeepSeek/MiniMax style)
             if options
@@ -6,9 +6,11 @@
 pub mod bun_runtime;
 pub mod github;
 pub mod manifest;
+pub mod slash_commands;
 pub mod types;
 pub mod wasm_host;
 
 pub use manifest::*;
+pub use slash_commands::{ExtensionSlashCommand, ExtensionSlashCommands};
 pub use wasm_host::WasmHost;
 // WasmExtension available via wasm_host module if needed
@@ -0,0 +1,260 @@
+//! Slash commands contributed by installed Zed extensions
+//!
+//! Commands are discovered from `[slash_commands.*]` in each extension's
+//! manifest without loading any WASM. An extension is instantiated the first
+//! time one of its commands completes an argument or runs. Running a command
+//! turns its output into context for the conversation.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use tokio::sync::Mutex;
+
+use crate::extensions::manifest::ExtensionManifest;
+use crate::extensions::types::LocalWorktree;
+use crate::extensions::wasm_host::{
+    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput, WasmExtension, WasmHost,
+};
+use crate::paths;
+
+/// A slash command declared by an installed extension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtensionSlashCommand {
+    pub name: String,
+    pub description: String,
+    pub requires_argument: bool,
+    extension_dir: PathBuf,
+}
+
+impl ExtensionSlashCommand {
+    fn to_wit(&self) -> SlashCommand {
+        SlashCommand {
+            name: self.name.clone(),
+            description: self.description.clone(),
+            tooltip_text: self.description.clone(),
+            requires_argument: self.requires_argument,
+        }
+    }
+}
+
+/// Slash commands from every installed extension
+pub struct ExtensionSlashCommands {
+    commands: Vec<ExtensionSlashCommand>,
+    extensions_dir: PathBuf,
+    wasm_host: OnceLock<Arc<WasmHost>>,
+    extensions: Mutex<HashMap<PathBuf, WasmExtension>>,
+}
+
+impl Default for ExtensionSlashCommands {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ExtensionSlashCommands {
+    /// Commands of the extensions installed in the default directory
+    pub fn new() -> Self {
+        Self::scan(&paths::extensions_dir())
+    }
+
+    /// Scan `extensions_dir/*/extension.toml` for slash commands
+    pub fn scan(extensions_dir: &Path) -> Self {
+        let mut commands = Vec::new();
+        if let Ok(entries) = std::fs::read_dir(extensions_dir) {
+            for entry in entries.flatten() {
+                let dir = entry.path();
+                let Ok(content) = std::fs::read_to_string(dir.join("extension.toml")) else {
+                    continue;
+                };
+                let manifest: ExtensionManifest = match toml::from_str(&content) {
+                    Ok(m) => m,
+                    Err(e) => {
+                        tracing::warn!("Skipping extension {}: {}", dir.display(), e);
+                        continue;
+                    }
+                };
+                commands.extend(manifest.slash_commands.into_iter().map(|(name, entry)| {
+                    ExtensionSlashCommand {
+                        name,
+                        description: entry.description,
+                        requires_argument: entry.requires_argument,
+                        extension_dir: dir.clone(),
+                    }
+                }));
+            }
+        }
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        commands.dedup_by(|a, b| a.name == b.name);
+
+        Self {
+            commands,
+            extensions_dir: extensions_dir.to_path_buf(),
+            wasm_host: OnceLock::new(),
+            extensions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Run commands on an existing host instead of starting one on first use
+    pub fn with_wasm_host(self, wasm_host: Arc<WasmHost>) -> Self {
+        let _ = self.wasm_host.set(wasm_host);
+        self
+    }
+
+    /// All commands, sorted by name
+    pub fn commands(&self) -> &[ExtensionSlashCommand] {
+        &self.commands
+    }
+
+    /// Look up a command by name (without the leading `/`)
+    pub fn get(&self, name: &str) -> Option<&ExtensionSlashCommand> {
+        self.commands.iter().find(|c| c.name == name)
+    }
+
+    /// Completions for the arguments typed so far
+    pub async fn complete_argument(
+        &self,
+        name: &str,
+        arguments: Vec<String>,
+    ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+        let command = self.command(name)?;
+        let extension = self.extension(&command.extension_dir).await?;
+        extension
+            .complete_slash_command_argument(command.to_wit(), arguments)
+            .await
+    }
+
+    /// Run a command against the project at `root`
+    pub async fn run(
+        &self,
+        name: &str,
+        arguments: Vec<String>,
+        root: &Path,
+    ) -> Result<SlashCommandOutput> {
+        let command = self.command(name)?;
+        if command.requires_argument && arguments.is_empty() {
+            return Err(anyhow!("/{} requires an argument", name));
+        }
+        let extension = self.extension(&command.extension_dir).await?;
+        extension
+            .run_slash_command(command.to_wit(), arguments, Some(LocalWorktree::new(root)))
+            .await
+    }
+
+    /// Replace an extension command on the first line of a prompt with its output
+    ///
+    /// Returns None when the prompt doesn't start with an extension command.
+    pub async fn expand(&self, prompt: &str, root: &Path) -> Option<Result<String>> {
+        let (first_line, rest) = prompt.split_once('\n').unwrap_or((prompt, ""));
+        let (name, arguments) = parse_invocation(first_line)?;
+        self.get(name)?;
+
+        let output = match self.run(name, arguments.clone(), root).await {
+            Ok(output) => output,
+            Err(e) => return Some(Err(e)),
+        };
+        let context = output_context(name, &arguments, &output);
+        Some(Ok(if rest.trim().is_empty() {
+            context
+        } else {
+            format!("{}\n\n{}", context, rest)
+        }))
+    }
+
+    fn command(&self, name: &str) -> Result<ExtensionSlashCommand> {
+        self.get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown extension command: /{}", name))
+    }
+
+    async fn extension(&self, extension_dir: &Path) -> Result<WasmExtension> {
+        let mut extensions = self.extensions.lock().await;
+        if let Some(extension) = extensions.get(extension_dir) {
+            return Ok(extension.clone());
+        }
+        let host = self
+            .wasm_host
+            .get_or_init(|| WasmHost::new(reqwest::Client::new(), self.extensions_dir.clone()));
+        let extension = host.load_extension_from_dir(extension_dir).await?;
+        extensions.insert(extension_dir.to_path_buf(), extension.clone());
+        Ok(extension)
+    }
+}
+
+/// Split `/name arg1 arg2` into the command name and its arguments
+pub fn parse_invocation(line: &str) -> Option<(&str, Vec<String>)> {
+    let mut parts = line.trim().strip_prefix('/')?.split_whitespace();
+    let name = parts.next()?;
+    Some((name, parts.map(str::to_string).collect()))
+}
+
+/// Wrap a command's output so the model can tell it apart from the user's words
+pub fn output_context(name: &str, arguments: &[String], output: &SlashCommandOutput) -> String {
+    let invocation = std::iter::once(format!("/{}", name))
+        .chain(arguments.iter().cloned())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "<slash_command_output command=\"{}\">\n{}\n</slash_command_output>",
+        invocation,
+        output.text.trim_end()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_extension_slash_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let ext = dir.path().join("docs");
+        std::fs::create_dir_all(&ext).unwrap();
+        std::fs::write(
+            ext.join("extension.toml"),
+            r#"
+id = "docs"
+name = "Docs"
+version = "0.1.0"
+schema_version = 1
+
+[slash_commands.rustdoc]
+description = "Insert Rust docs"
+requires_argument = true
+
+[slash_commands.now]
+description = "Insert the current time"
+"#,
+        )
+        .unwrap();
+
+        let commands = ExtensionSlashCommands::scan(dir.path());
+        let names: Vec<&str> = commands
+            .commands()
+            .iter()
+            .map(|c| c.name.as_str())
+            .collect();
+        assert_eq!(names, ["now", "rustdoc"]);
+        assert!(commands.get("rustdoc").unwrap().requires_argument);
+        assert!(!commands.get("now").unwrap().requires_argument);
+        assert!(commands.get("missing").is_none());
+    }
+
+    #[test]
+    fn test_parse_invocation_and_output_context() {
+        let (name, args) = parse_invocation("/rustdoc serde  derive").unwrap();
+        assert_eq!(name, "rustdoc");
+        assert_eq!(args, ["serde", "derive"]);
+        assert!(parse_invocation("rustdoc").is_none());
+        assert!(parse_invocation("/").is_none());
+
+        let output = SlashCommandOutput {
+            text: "docs body\n".to_string(),
+            sections: Vec::new(),
+        };
+        assert_eq!(
+            output_context(name, &args, &output),
+            "<slash_command_output command=\"/rustdoc serde derive\">\ndocs body\n</slash_command_output>"
+        );
+    }
+}
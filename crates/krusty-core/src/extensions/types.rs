@@ -4,6 +4,7 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 /// Wrapper for language server names (uses Arc<str> for efficiency)
@@ -31,3 +32,44 @@ pub trait WorktreeDelegate: Send + Sync {
     fn which(&self, binary_name: &str) -> Option<String>;
     fn shell_env(&self) -> Vec<(String, String)>;
 }
+
+/// Worktree rooted at a local directory
+pub(crate) struct LocalWorktree {
+    root: PathBuf,
+}
+
+impl LocalWorktree {
+    pub(crate) fn new(root: &Path) -> Arc<Self> {
+        Arc::new(Self {
+            root: root.to_path_buf(),
+        })
+    }
+}
+
+#[async_trait]
+impl WorktreeDelegate for LocalWorktree {
+    fn id(&self) -> u64 {
+        1
+    }
+
+    fn root_path(&self) -> String {
+        self.root.to_string_lossy().into_owned()
+    }
+
+    async fn read_text_file(&self, path: &str) -> Result<String> {
+        let path = self.root.join(path);
+        tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))
+    }
+
+    fn which(&self, binary_name: &str) -> Option<String> {
+        which::which(binary_name)
+            .ok()
+            .map(|p| p.to_string_lossy().into_owned())
+    }
+
+    fn shell_env(&self) -> Vec<(String, String)> {
+        std::env::vars().collect()
+    }
+}
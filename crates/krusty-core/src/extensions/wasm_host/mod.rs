@@ -32,7 +32,7 @@ use wasmtime::{
 use wasmtime_wasi::{self as wasi, WasiView};
 
 // Re-export WIT types for use by consumers
-pub use wit::Command;
+pub use wit::{Command, SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput};
 
 /// The WASM extension host - manages loading and running WASM extensions
 pub struct WasmHost {
@@ -414,6 +414,48 @@ impl WasmExtension {
         })
         .await?
     }
+
+    /// Get completions for a slash command's arguments
+    pub async fn complete_slash_command_argument(
+        &self,
+        command: SlashCommand,
+        arguments: Vec<String>,
+    ) -> Result<Vec<SlashCommandArgumentCompletion>> {
+        self.call(|extension, store| {
+            async move {
+                let completions = extension
+                    .call_complete_slash_command_argument(store, &command, &arguments)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(completions)
+            }
+            .boxed()
+        })
+        .await?
+    }
+
+    /// Run a slash command, optionally against a worktree
+    pub async fn run_slash_command(
+        &self,
+        command: SlashCommand,
+        arguments: Vec<String>,
+        worktree: Option<Arc<dyn WorktreeDelegate>>,
+    ) -> Result<SlashCommandOutput> {
+        self.call(|extension, store| {
+            async move {
+                let resource = worktree
+                    .map(|worktree| store.data_mut().table.push(worktree))
+                    .transpose()?;
+                let output = extension
+                    .call_run_slash_command(store, &command, &arguments, resource)
+                    .await?
+                    .map_err(|err| store.data().extension_error(err))?;
+                Ok(output)
+            }
+            .boxed()
+        })
+        .await?
+    }
 }
 
 /// Normalize a path, removing `.` and `..` components
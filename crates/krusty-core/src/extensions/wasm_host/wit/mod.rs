@@ -26,7 +26,10 @@ use wasmtime::{
     Store,
 };
 
-// Re-export the latest Command type for external use
+// Re-export the latest Command and slash command types for external use
+pub use latest::zed::extension::slash_command::{
+    SlashCommand, SlashCommandArgumentCompletion, SlashCommandOutput,
+};
 pub use latest::Command;
 
 pub fn new_linker(
@@ -241,4 +244,87 @@ impl Extension {
                 .map(|r| r.map(Into::into)),
         }
     }
+
+    pub async fn call_complete_slash_command_argument(
+        &self,
+        store: &mut Store<WasmState>,
+        command: &SlashCommand,
+        arguments: &[String],
+    ) -> Result<Result<Vec<SlashCommandArgumentCompletion>, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_6_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_5_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_4_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_3_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_2_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_1_0(ext) => {
+                ext.call_complete_slash_command_argument(store, command, arguments)
+                    .await
+            }
+            Extension::V0_0_6(_) | Extension::V0_0_4(_) | Extension::V0_0_1(_) => Ok(Err(
+                "`complete_slash_command_argument` not available prior to v0.1.0".into(),
+            )),
+        }
+    }
+
+    pub async fn call_run_slash_command(
+        &self,
+        store: &mut Store<WasmState>,
+        command: &SlashCommand,
+        arguments: &[String],
+        resource: Option<Resource<Arc<dyn WorktreeDelegate>>>,
+    ) -> Result<Result<SlashCommandOutput, String>> {
+        match self {
+            Extension::V0_8_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_6_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_5_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_4_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_3_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_2_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_1_0(ext) => {
+                ext.call_run_slash_command(store, command, arguments, resource)
+                    .await
+            }
+            Extension::V0_0_6(_) | Extension::V0_0_4(_) | Extension::V0_0_1(_) => Ok(Err(
+                "`run_slash_command` not available prior to v0.1.0".into(),
+            )),
+        }
+    }
 }
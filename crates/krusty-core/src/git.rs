@@ -1,6 +1,8 @@
 //! Lightweight git helpers shared by server and clients.
 
 use std::collections::HashMap;
+use std::ffi::OsStr;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::Command;
 use std::process::Stdio;
@@ -78,6 +80,23 @@ pub struct GitWorktreeSummary {
     pub is_current: bool,
 }
 
+/// Lines changed in one file of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GitFileDiff {
+    pub path: String,
+    /// None for binary files
+    pub additions: Option<usize>,
+    pub deletions: Option<usize>,
+}
+
+/// Identity for commits Krusty makes on its own scratch refs.
+const SCRATCH_IDENTITY: [(&str, &str); 4] = [
+    ("GIT_AUTHOR_NAME", "Krusty"),
+    ("GIT_AUTHOR_EMAIL", "krusty@localhost"),
+    ("GIT_COMMITTER_NAME", "Krusty"),
+    ("GIT_COMMITTER_EMAIL", "krusty@localhost"),
+];
+
 /// Resolve the current worktree root for a path, or `None` if path is not inside a git repo.
 pub fn resolve_repo_root(path: &Path) -> Result<Option<PathBuf>> {
     let output = Command::new("git")
@@ -260,6 +279,173 @@ pub fn checkout(path: &Path, branch: &str, create: bool, start_point: Option<&st
     Ok(())
 }
 
+/// Commit the working tree as it stands, untracked files included.
+///
+/// Uses a throwaway index, so HEAD, the index and every branch are left
+/// untouched. Returns the new commit id, parented on HEAD.
+pub fn snapshot_working_tree(repo_root: &Path) -> Result<String> {
+    let head = rev_parse(repo_root, "HEAD")?;
+    let index_path = std::env::temp_dir().join(format!("krusty-index-{}", uuid::Uuid::new_v4()));
+    let index = index_path.as_os_str();
+    let result = (|| {
+        run_git_with(
+            &["read-tree", "HEAD"],
+            repo_root,
+            &[("GIT_INDEX_FILE", index)],
+            None,
+        )?;
+        run_git_with(
+            &["add", "-A"],
+            repo_root,
+            &[("GIT_INDEX_FILE", index)],
+            None,
+        )?;
+        let tree = run_git_with(
+            &["write-tree"],
+            repo_root,
+            &[("GIT_INDEX_FILE", index)],
+            None,
+        )?;
+        let tree = String::from_utf8_lossy(&tree.stdout).trim().to_string();
+        let commit = run_git_with(
+            &[
+                "commit-tree",
+                &tree,
+                "-p",
+                &head,
+                "-m",
+                "Working tree snapshot",
+            ],
+            repo_root,
+            &scratch_identity(),
+            None,
+        )?;
+        Ok(String::from_utf8_lossy(&commit.stdout).trim().to_string())
+    })();
+    let _ = std::fs::remove_file(&index_path);
+    result
+}
+
+/// Create a linked worktree at `path` on a new branch starting at `start_point`.
+pub fn add_worktree(repo_root: &Path, path: &Path, branch: &str, start_point: &str) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git(
+        &["worktree", "add", "-b", branch, path.as_ref(), start_point],
+        repo_root,
+    )?;
+    Ok(())
+}
+
+/// Remove a linked worktree, discarding anything uncommitted in it.
+pub fn remove_worktree(repo_root: &Path, path: &Path) -> Result<()> {
+    let path = path.to_string_lossy();
+    run_git(&["worktree", "remove", "--force", path.as_ref()], repo_root)?;
+    Ok(())
+}
+
+/// Delete a local branch, merged or not.
+pub fn delete_branch(repo_root: &Path, branch: &str) -> Result<()> {
+    run_git(&["branch", "-D", branch], repo_root)?;
+    Ok(())
+}
+
+/// Commit every change in a worktree. Returns false when there was nothing to commit.
+pub fn commit_all(worktree: &Path, message: &str) -> Result<bool> {
+    run_git(&["add", "-A"], worktree)?;
+    let staged = run_git(&["diff", "--cached", "--name-only"], worktree)?;
+    if staged.stdout.iter().all(u8::is_ascii_whitespace) {
+        return Ok(false);
+    }
+    run_git_with(
+        &["commit", "--no-verify", "-m", message],
+        worktree,
+        &scratch_identity(),
+        None,
+    )?;
+    Ok(true)
+}
+
+/// Per-file line counts between two commits.
+pub fn diff_files(repo_root: &Path, from: &str, to: &str) -> Result<Vec<GitFileDiff>> {
+    let output = run_git(&["diff", "--numstat", "--no-renames", from, to], repo_root)?;
+    Ok(parse_numstat_files(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Binary-safe patch between two commits, suitable for `apply_patch`.
+pub fn diff_patch(repo_root: &Path, from: &str, to: &str) -> Result<Vec<u8>> {
+    let output = run_git(
+        &["diff", "--binary", "--full-index", "--no-renames", from, to],
+        repo_root,
+    )?;
+    Ok(output.stdout)
+}
+
+/// Apply a patch to the working tree at `repo_root`.
+///
+/// With `check` nothing is written; an error means the patch does not apply
+/// cleanly, e.g. because the files it touches changed since it was made.
+pub fn apply_patch(repo_root: &Path, patch: &[u8], check: bool) -> Result<()> {
+    let mut args = vec!["apply", "--binary", "--whitespace=nowarn"];
+    if check {
+        args.push("--check");
+    }
+    args.push("-");
+    run_git_with(&args, repo_root, &[], Some(patch))?;
+    Ok(())
+}
+
+fn rev_parse(repo_root: &Path, rev: &str) -> Result<String> {
+    let output = run_git(&["rev-parse", "--verify", rev], repo_root)?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+fn scratch_identity() -> Vec<(&'static str, &'static OsStr)> {
+    SCRATCH_IDENTITY
+        .iter()
+        .map(|(key, value)| (*key, OsStr::new(value)))
+        .collect()
+}
+
+fn run_git_with(
+    args: &[&str],
+    cwd: &Path,
+    envs: &[(&str, &OsStr)],
+    stdin: Option<&[u8]>,
+) -> Result<std::process::Output> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(cwd)
+        .envs(envs.iter().copied())
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| {
+            format!(
+                "Failed to execute git {} in {}",
+                args.join(" "),
+                cwd.display()
+            )
+        })?;
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+
+    if output.status.success() {
+        Ok(output)
+    } else {
+        let detail = command_error_detail(&output.stdout, &output.stderr);
+        Err(anyhow!("git {} failed: {}", args.join(" "), detail))
+    }
+}
+
 fn run_git(args: &[&str], cwd: &Path) -> Result<std::process::Output> {
     let output = Command::new("git")
         .args(args)
@@ -462,6 +648,23 @@ fn compute_branch_diff_summary(
     Some(parse_numstat(&stdout))
 }
 
+fn parse_numstat_files(output: &str) -> Vec<GitFileDiff> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let additions = parts.next()?.trim().parse().ok();
+            let deletions = parts.next()?.trim().parse().ok();
+            let path = parts.next()?.trim();
+            (!path.is_empty()).then(|| GitFileDiff {
+                path: path.to_string(),
+                additions,
+                deletions,
+            })
+        })
+        .collect()
+}
+
 fn parse_numstat(output: &str) -> BranchDiffSummary {
     let mut summary = BranchDiffSummary::default();
     for line in output.lines().filter(|line| !line.trim().is_empty()) {
@@ -662,6 +865,61 @@ branch refs/heads/feature-x\n";
         assert_eq!(summary.deletions, 2);
     }
 
+    #[test]
+    fn parses_numstat_files() {
+        let files = parse_numstat_files("3\t1\tsrc/a.rs\n-\t-\tlogo.png\n");
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "src/a.rs");
+        assert_eq!(files[0].additions, Some(3));
+        assert_eq!(files[1].additions, None);
+    }
+
+    #[test]
+    fn worktree_changes_apply_back_with_conflict_check() {
+        let temp = tempfile::TempDir::new().unwrap();
+        let repo = temp.path().join("repo");
+        std::fs::create_dir(&repo).unwrap();
+        run_git(&["init", "-q"], &repo).unwrap();
+        std::fs::write(repo.join("a.txt"), "one\n").unwrap();
+        run_git(&["add", "-A"], &repo).unwrap();
+        run_git_with(&["commit", "-qm", "init"], &repo, &scratch_identity(), None).unwrap();
+
+        // Uncommitted and untracked changes are part of the snapshot
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        std::fs::write(repo.join("new.txt"), "new\n").unwrap();
+        let base = snapshot_working_tree(&repo).unwrap();
+        assert_eq!(status(&repo).unwrap().unwrap().untracked, 1);
+
+        let tree = temp.path().join("wt");
+        add_worktree(&repo, &tree, "krusty/test", &base).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(tree.join("new.txt")).unwrap(),
+            "new\n"
+        );
+        std::fs::write(tree.join("a.txt"), "three\n").unwrap();
+        assert!(commit_all(&tree, "edit").unwrap());
+        assert!(!commit_all(&tree, "nothing").unwrap());
+
+        let files = diff_files(&repo, &base, "krusty/test").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].path, "a.txt");
+
+        let patch = diff_patch(&repo, &base, "krusty/test").unwrap();
+        std::fs::write(repo.join("a.txt"), "changed meanwhile\n").unwrap();
+        assert!(apply_patch(&repo, &patch, true).is_err());
+        std::fs::write(repo.join("a.txt"), "two\n").unwrap();
+        apply_patch(&repo, &patch, true).unwrap();
+        apply_patch(&repo, &patch, false).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).unwrap(),
+            "three\n"
+        );
+
+        remove_worktree(&repo, &tree).unwrap();
+        delete_branch(&repo, "krusty/test").unwrap();
+        assert!(!tree.exists());
+    }
+
     #[test]
     fn extracts_pr_number_from_branch_name() {
         assert_eq!(extract_pr_from_branch_name("pr-29"), Some(29));
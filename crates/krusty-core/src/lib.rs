@@ -5,6 +5,7 @@
 //! - Tool execution framework
 //! - Session and preference storage
 //! - MCP (Model Context Protocol) support
+//! - LSP (Language Server Protocol) client for code intelligence
 //! - ACP (Agent Client Protocol) server for editor integration
 
 pub mod acp;
@@ -14,6 +15,7 @@ pub mod auth;
 pub mod constants;
 pub mod extensions;
 pub mod git;
+pub mod lsp;
 pub mod mcp;
 pub mod paths;
 pub mod plan;
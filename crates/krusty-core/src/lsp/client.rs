@@ -0,0 +1,589 @@
+//! LSP Client
+//!
+//! Talks JSON-RPC to a single language server. A background receive loop
+//! resolves responses, answers server-initiated requests and records
+//! published diagnostics.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex as SyncMutex;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{oneshot, Mutex, Notify};
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+use super::protocol::{
+    hover_text, parse_locations, path_to_uri, Diagnostic, Location, Position,
+    PublishDiagnosticsParams,
+};
+use super::servers::LspServerSpec;
+use super::transport::LspTransport;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Servers often publish several times in quick succession (e.g. syntax
+/// errors first, type errors after); wait this long for the burst to end
+const DIAGNOSTICS_SETTLE: Duration = Duration::from_millis(300);
+
+type PendingMap = HashMap<i64, oneshot::Sender<Result<Value>>>;
+
+/// Diagnostics per document URI, tagged with the generation they arrived in
+#[derive(Default)]
+struct DiagnosticsStore {
+    generation: u64,
+    by_uri: HashMap<String, (u64, Vec<Diagnostic>)>,
+}
+
+/// A document the server currently has open
+struct OpenDocument {
+    version: i32,
+    text: String,
+}
+
+/// LSP client for one language server in one workspace
+pub struct LspClient {
+    server_id: String,
+    root: PathBuf,
+    transport: Arc<LspTransport>,
+    next_id: AtomicI64,
+    pending: Arc<SyncMutex<PendingMap>>,
+    diagnostics: Arc<SyncMutex<DiagnosticsStore>>,
+    diagnostics_changed: Arc<Notify>,
+    documents: Mutex<HashMap<String, OpenDocument>>,
+    receive_task: JoinHandle<()>,
+}
+
+impl LspClient {
+    /// Spawn a language server and complete the `initialize` handshake
+    pub async fn start(spec: &LspServerSpec, root: &Path) -> Result<Self> {
+        info!(
+            "Starting language server {} for {}",
+            spec.id,
+            root.display()
+        );
+
+        let transport = Arc::new(LspTransport::spawn(
+            &spec.command,
+            &spec.args,
+            &spec.env,
+            root,
+        )?);
+        let pending: Arc<SyncMutex<PendingMap>> = Arc::new(SyncMutex::new(HashMap::new()));
+        let diagnostics = Arc::new(SyncMutex::new(DiagnosticsStore::default()));
+        let diagnostics_changed = Arc::new(Notify::new());
+
+        let recv_transport = Arc::clone(&transport);
+        let recv_pending = Arc::clone(&pending);
+        let recv_diagnostics = Arc::clone(&diagnostics);
+        let recv_changed = Arc::clone(&diagnostics_changed);
+        let recv_id = spec.id.clone();
+
+        let receive_task = tokio::spawn(async move {
+            loop {
+                match recv_transport.receive().await {
+                    Ok(message) => {
+                        let reply = handle_message(
+                            &message,
+                            &recv_pending,
+                            &recv_diagnostics,
+                            &recv_changed,
+                        );
+                        if let Some(reply) = reply {
+                            if let Err(e) = recv_transport.send(&reply).await {
+                                warn!("LSP {} reply failed: {}", recv_id, e);
+                            }
+                        }
+                    }
+                    Err(e) => {
+                        warn!("LSP {} receive error: {}", recv_id, e);
+                        for (_, tx) in recv_pending.lock().drain() {
+                            let _ = tx.send(Err(anyhow!("Language server connection lost")));
+                        }
+                        break;
+                    }
+                }
+            }
+        });
+
+        let client = Self {
+            server_id: spec.id.clone(),
+            root: root.to_path_buf(),
+            transport,
+            next_id: AtomicI64::new(1),
+            pending,
+            diagnostics,
+            diagnostics_changed,
+            documents: Mutex::new(HashMap::new()),
+            receive_task,
+        };
+
+        client.initialize().await?;
+        Ok(client)
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        let root_uri = path_to_uri(&self.root);
+        let name = self
+            .root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "workspace".to_string());
+
+        let params = json!({
+            "processId": std::process::id(),
+            "clientInfo": { "name": "krusty", "version": env!("CARGO_PKG_VERSION") },
+            "rootUri": root_uri,
+            "workspaceFolders": [{ "uri": root_uri, "name": name }],
+            "capabilities": {
+                "workspace": {
+                    "configuration": true,
+                    "workspaceFolders": true,
+                    "workspaceEdit": { "documentChanges": true }
+                },
+                "textDocument": {
+                    "synchronization": { "didSave": true, "dynamicRegistration": false },
+                    "publishDiagnostics": { "relatedInformation": false, "versionSupport": false },
+                    "definition": { "linkSupport": true },
+                    "references": {},
+                    "hover": { "contentFormat": ["markdown", "plaintext"] },
+                    "rename": { "prepareSupport": false }
+                },
+                "window": { "workDoneProgress": true }
+            }
+        });
+        self.request("initialize", params).await?;
+        self.notify("initialized", json!({})).await?;
+        info!("Language server {} initialized", self.server_id);
+        Ok(())
+    }
+
+    /// Server id (e.g. `rust-analyzer`)
+    pub fn server_id(&self) -> &str {
+        &self.server_id
+    }
+
+    /// Workspace root the server was started for
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Check if the server process is still running
+    pub async fn is_alive(&self) -> bool {
+        !self.receive_task.is_finished() && self.transport.is_alive().await
+    }
+
+    /// Generation counter, bumped on every `publishDiagnostics`
+    pub fn diagnostics_generation(&self) -> u64 {
+        self.diagnostics.lock().generation
+    }
+
+    /// Open a document, or send its new contents if it is already open
+    ///
+    /// Returns `false` when the server already has exactly this text.
+    pub async fn sync_document(&self, path: &Path, language_id: &str, text: &str) -> Result<bool> {
+        let uri = path_to_uri(path);
+        let mut documents = self.documents.lock().await;
+
+        match documents.get_mut(&uri) {
+            Some(doc) if doc.text == text => return Ok(false),
+            Some(doc) => {
+                doc.version += 1;
+                doc.text = text.to_string();
+                let version = doc.version;
+                self.notify(
+                    "textDocument/didChange",
+                    json!({
+                        "textDocument": { "uri": uri, "version": version },
+                        "contentChanges": [{ "text": text }]
+                    }),
+                )
+                .await?;
+            }
+            None => {
+                documents.insert(
+                    uri.clone(),
+                    OpenDocument {
+                        version: 1,
+                        text: text.to_string(),
+                    },
+                );
+                self.notify(
+                    "textDocument/didOpen",
+                    json!({
+                        "textDocument": {
+                            "uri": uri,
+                            "languageId": language_id,
+                            "version": 1,
+                            "text": text
+                        }
+                    }),
+                )
+                .await?;
+            }
+        }
+
+        // The file is already on disk; servers like rust-analyzer only run
+        // their full checks on save
+        self.notify(
+            "textDocument/didSave",
+            json!({ "textDocument": { "uri": uri } }),
+        )
+        .await?;
+        Ok(true)
+    }
+
+    /// Latest diagnostics for a document, if the server has published any
+    pub fn diagnostics(&self, path: &Path) -> Option<Vec<Diagnostic>> {
+        self.diagnostics
+            .lock()
+            .by_uri
+            .get(&path_to_uri(path))
+            .map(|(_, d)| d.clone())
+    }
+
+    /// Every document with diagnostics
+    pub fn all_diagnostics(&self) -> Vec<(String, Vec<Diagnostic>)> {
+        self.diagnostics
+            .lock()
+            .by_uri
+            .iter()
+            .filter(|(_, (_, d))| !d.is_empty())
+            .map(|(uri, (_, d))| (uri.clone(), d.clone()))
+            .collect()
+    }
+
+    /// Wait for diagnostics newer than `since` for a document
+    ///
+    /// Returns whatever is stored once the burst of publishes settles, or
+    /// when `timeout` expires.
+    pub async fn wait_for_diagnostics(
+        &self,
+        path: &Path,
+        since: u64,
+        timeout: Duration,
+    ) -> Option<Vec<Diagnostic>> {
+        let uri = path_to_uri(path);
+        let deadline = Instant::now() + timeout;
+        let published_after = |since: u64| {
+            self.diagnostics
+                .lock()
+                .by_uri
+                .get(&uri)
+                .is_some_and(|(generation, _)| *generation > since)
+        };
+
+        // First publish after the change
+        loop {
+            let changed = self.diagnostics_changed.notified();
+            if published_after(since) {
+                break;
+            }
+            if tokio::time::timeout_at(deadline, changed).await.is_err() {
+                return self.diagnostics(path);
+            }
+        }
+
+        // Then let follow-up publishes land until the server goes quiet
+        while Instant::now() < deadline {
+            let changed = self.diagnostics_changed.notified();
+            let settle = (Instant::now() + DIAGNOSTICS_SETTLE).min(deadline);
+            if tokio::time::timeout_at(settle, changed).await.is_err() {
+                break;
+            }
+        }
+
+        self.diagnostics(path)
+    }
+
+    /// `textDocument/definition`
+    pub async fn definition(&self, path: &Path, position: Position) -> Result<Vec<Location>> {
+        let result = self
+            .request("textDocument/definition", position_params(path, position))
+            .await?;
+        Ok(parse_locations(result))
+    }
+
+    /// `textDocument/references`
+    pub async fn references(
+        &self,
+        path: &Path,
+        position: Position,
+        include_declaration: bool,
+    ) -> Result<Vec<Location>> {
+        let mut params = position_params(path, position);
+        params["context"] = json!({ "includeDeclaration": include_declaration });
+        let result = self.request("textDocument/references", params).await?;
+        Ok(parse_locations(result))
+    }
+
+    /// `textDocument/hover`, flattened to text
+    pub async fn hover(&self, path: &Path, position: Position) -> Result<Option<String>> {
+        let result = self
+            .request("textDocument/hover", position_params(path, position))
+            .await?;
+        Ok(hover_text(&result))
+    }
+
+    /// `textDocument/rename`, returning the raw `WorkspaceEdit`
+    pub async fn rename(&self, path: &Path, position: Position, new_name: &str) -> Result<Value> {
+        let mut params = position_params(path, position);
+        params["newName"] = json!(new_name);
+        self.request("textDocument/rename", params).await
+    }
+
+    /// Politely stop the server (`shutdown` then `exit`)
+    pub async fn shutdown(&self) {
+        if tokio::time::timeout(
+            Duration::from_secs(2),
+            self.request("shutdown", Value::Null),
+        )
+        .await
+        .is_ok()
+        {
+            let _ = self.notify("exit", Value::Null).await;
+        }
+    }
+
+    async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": method,
+            "params": params
+        });
+
+        debug!("LSP {} request [{}]: {}", self.server_id, id, method);
+
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id, tx);
+
+        if let Err(e) = self.transport.send(&message.to_string()).await {
+            self.pending.lock().remove(&id);
+            return Err(e);
+        }
+
+        match tokio::time::timeout(REQUEST_TIMEOUT, rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(anyhow!("Request cancelled")),
+            Err(_) => {
+                self.pending.lock().remove(&id);
+                Err(anyhow!(
+                    "{} request {} timed out after {}s",
+                    self.server_id,
+                    method,
+                    REQUEST_TIMEOUT.as_secs()
+                ))
+            }
+        }
+    }
+
+    async fn notify(&self, method: &str, params: Value) -> Result<()> {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        self.transport.send(&message.to_string()).await
+    }
+}
+
+impl Drop for LspClient {
+    fn drop(&mut self) {
+        // Dropping the last transport handle kills the process
+        self.receive_task.abort();
+    }
+}
+
+fn position_params(path: &Path, position: Position) -> Value {
+    json!({
+        "textDocument": { "uri": path_to_uri(path) },
+        "position": position
+    })
+}
+
+/// Handle an incoming message, returning a reply for server requests
+fn handle_message(
+    message: &str,
+    pending: &SyncMutex<PendingMap>,
+    diagnostics: &SyncMutex<DiagnosticsStore>,
+    diagnostics_changed: &Notify,
+) -> Option<String> {
+    let message: Value = match serde_json::from_str(message) {
+        Ok(v) => v,
+        Err(e) => {
+            warn!("Invalid LSP message: {}", e);
+            return None;
+        }
+    };
+    let method = message.get("method").and_then(|m| m.as_str());
+    let id = message.get("id").cloned();
+
+    match (method, id) {
+        // Response to one of our requests
+        (None, Some(id)) => {
+            let tx = id.as_i64().and_then(|id| pending.lock().remove(&id));
+            if let Some(tx) = tx {
+                let result = match message.get("error") {
+                    Some(error) => Err(anyhow!(
+                        "LSP error {}: {}",
+                        error.get("code").and_then(|c| c.as_i64()).unwrap_or(0),
+                        error
+                            .get("message")
+                            .and_then(|m| m.as_str())
+                            .unwrap_or("unknown error")
+                    )),
+                    None => Ok(message.get("result").cloned().unwrap_or(Value::Null)),
+                };
+                let _ = tx.send(result);
+            }
+            None
+        }
+        // Request from the server
+        (Some(method), Some(id)) => {
+            let reply = match server_request_result(method, message.get("params")) {
+                Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                Err((code, msg)) => json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": code, "message": msg }
+                }),
+            };
+            Some(reply.to_string())
+        }
+        // Notification
+        (Some("textDocument/publishDiagnostics"), None) => {
+            let params = message.get("params").cloned().unwrap_or(Value::Null);
+            match serde_json::from_value::<PublishDiagnosticsParams>(params) {
+                Ok(params) => {
+                    let mut store = diagnostics.lock();
+                    store.generation += 1;
+                    let generation = store.generation;
+                    store
+                        .by_uri
+                        .insert(params.uri, (generation, params.diagnostics));
+                    drop(store);
+                    diagnostics_changed.notify_waiters();
+                }
+                Err(e) => warn!("Invalid publishDiagnostics: {}", e),
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Result for a server-to-client request
+///
+/// Krusty has no settings to offer and does not apply server-driven edits,
+/// but servers stall if these requests go unanswered.
+fn server_request_result(method: &str, params: Option<&Value>) -> Result<Value, (i64, String)> {
+    match method {
+        "workspace/configuration" => {
+            let count = params
+                .and_then(|p| p.get("items"))
+                .and_then(|i| i.as_array())
+                .map_or(0, Vec::len);
+            Ok(Value::Array(vec![Value::Null; count]))
+        }
+        "window/workDoneProgress/create"
+        | "client/registerCapability"
+        | "client/unregisterCapability"
+        | "window/showMessageRequest"
+        | "workspace/diagnostic/refresh"
+        | "workspace/semanticTokens/refresh"
+        | "workspace/inlayHint/refresh"
+        | "workspace/codeLens/refresh" => Ok(Value::Null),
+        "workspace/applyEdit" => Ok(json!({
+            "applied": false,
+            "failureReason": "Client does not apply server-initiated edits"
+        })),
+        _ => Err((-32601, format!("Method not found: {}", method))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> (SyncMutex<PendingMap>, SyncMutex<DiagnosticsStore>, Notify) {
+        (
+            SyncMutex::new(HashMap::new()),
+            SyncMutex::new(DiagnosticsStore::default()),
+            Notify::new(),
+        )
+    }
+
+    #[tokio::test]
+    async fn resolves_pending_responses() {
+        let (pending, diagnostics, notify) = state();
+        let (tx, rx) = oneshot::channel();
+        pending.lock().insert(7, tx);
+
+        let reply = handle_message(
+            r#"{"jsonrpc":"2.0","id":7,"result":{"ok":true}}"#,
+            &pending,
+            &diagnostics,
+            &notify,
+        );
+        assert!(reply.is_none());
+        assert_eq!(rx.await.unwrap().unwrap(), json!({ "ok": true }));
+
+        let (tx, rx) = oneshot::channel();
+        pending.lock().insert(8, tx);
+        handle_message(
+            r#"{"jsonrpc":"2.0","id":8,"error":{"code":-32800,"message":"cancelled"}}"#,
+            &pending,
+            &diagnostics,
+            &notify,
+        );
+        assert!(rx.await.unwrap().is_err());
+    }
+
+    #[test]
+    fn answers_server_requests() {
+        let (pending, diagnostics, notify) = state();
+        let reply = handle_message(
+            r#"{"jsonrpc":"2.0","id":"c1","method":"workspace/configuration","params":{"items":[{},{}]}}"#,
+            &pending,
+            &diagnostics,
+            &notify,
+        )
+        .unwrap();
+        let reply: Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["id"], "c1");
+        assert_eq!(reply["result"], json!([null, null]));
+
+        let reply = handle_message(
+            r#"{"jsonrpc":"2.0","id":3,"method":"custom/thing"}"#,
+            &pending,
+            &diagnostics,
+            &notify,
+        )
+        .unwrap();
+        assert!(reply.contains("-32601"));
+    }
+
+    #[test]
+    fn records_published_diagnostics() {
+        let (pending, diagnostics, notify) = state();
+        handle_message(
+            r#"{"jsonrpc":"2.0","method":"textDocument/publishDiagnostics","params":{
+                "uri":"file:///a.rs",
+                "diagnostics":[{"range":{"start":{"line":0,"character":0},"end":{"line":0,"character":1}},"severity":1,"message":"boom"}]
+            }}"#,
+            &pending,
+            &diagnostics,
+            &notify,
+        );
+        let store = diagnostics.lock();
+        assert_eq!(store.generation, 1);
+        let (generation, list) = &store.by_uri["file:///a.rs"];
+        assert_eq!(*generation, 1);
+        assert_eq!(list[0].message, "boom");
+    }
+}
@@ -0,0 +1,495 @@
+//! LSP Manager
+//!
+//! Pools language servers per workspace root. Servers start lazily the first
+//! time a file of their language is touched and stay up for the process
+//! lifetime; one that fails to start is not retried. Starts run outside the
+//! pool lock, so a slow server never holds up another one.
+
+use anyhow::{anyhow, Result};
+use parking_lot::Mutex as SyncMutex;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::client::LspClient;
+use super::protocol::{uri_to_path, utf16_to_column, Diagnostic};
+use super::servers::{
+    builtin_language, builtin_server, ExtensionCatalog, LanguageInfo, LspServerSpec,
+};
+use crate::extensions::types::LocalWorktree;
+use crate::extensions::wasm_host::{WasmExtension, WasmHost};
+use crate::paths;
+
+/// Startup (spawn + initialize) budget for one server
+const START_TIMEOUT: Duration = Duration::from_secs(30);
+/// How long an edit waits for fresh diagnostics before returning without them
+const EDIT_DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(3);
+/// How long the `diagnostics` tool waits after opening a file
+const OPEN_DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(10);
+/// Cap on diagnostics reported per file
+const MAX_DIAGNOSTICS_PER_FILE: usize = 50;
+
+/// Diagnostics for one file, with the text they refer to
+#[derive(Debug, Clone)]
+pub struct FileDiagnostics {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+    text: String,
+}
+
+impl FileDiagnostics {
+    /// One JSON object per diagnostic, with 1-based line/column
+    pub fn to_json(&self, root: &Path) -> Vec<Value> {
+        let file = self
+            .path
+            .strip_prefix(root)
+            .unwrap_or(&self.path)
+            .display()
+            .to_string();
+        let lines: Vec<&str> = self.text.lines().collect();
+
+        self.diagnostics
+            .iter()
+            .take(MAX_DIAGNOSTICS_PER_FILE)
+            .map(|d| {
+                let start = d.range.start;
+                let line_text = lines.get(start.line as usize).copied().unwrap_or("");
+                let mut entry = json!({
+                    "file": file,
+                    "line": start.line + 1,
+                    "column": utf16_to_column(line_text, start.character),
+                    "severity": d.severity_label(),
+                    "message": d.message,
+                });
+                if let Some(source) = &d.source {
+                    entry["source"] = json!(source);
+                }
+                if let Some(code) = &d.code {
+                    entry["code"] = code.clone();
+                }
+                entry
+            })
+            .collect()
+    }
+}
+
+/// Flatten diagnostics for several files into one JSON array
+pub fn diagnostics_json(root: &Path, files: &[FileDiagnostics]) -> Value {
+    Value::Array(files.iter().flat_map(|f| f.to_json(root)).collect())
+}
+
+/// (workspace root, server id or language name)
+type Key = (PathBuf, String);
+
+/// Manages language servers across workspaces
+pub struct LspManager {
+    /// Running servers keyed by (workspace root, server id)
+    clients: SyncMutex<HashMap<Key, Arc<LspClient>>>,
+    /// Held while a server starts, so each server is started once
+    starting: SyncMutex<HashMap<Key, Arc<Mutex<()>>>>,
+    /// Resolved server per (workspace root, language); `None` = no server
+    resolved: SyncMutex<HashMap<Key, Option<LspServerSpec>>>,
+    /// Servers that failed to start
+    failed: SyncMutex<HashSet<Key>>,
+    extensions_dir: PathBuf,
+    catalog: OnceLock<ExtensionCatalog>,
+    wasm_host: Option<Arc<WasmHost>>,
+    extensions: Mutex<HashMap<PathBuf, WasmExtension>>,
+}
+
+impl Default for LspManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LspManager {
+    pub fn new() -> Self {
+        Self {
+            clients: SyncMutex::new(HashMap::new()),
+            starting: SyncMutex::new(HashMap::new()),
+            resolved: SyncMutex::new(HashMap::new()),
+            failed: SyncMutex::new(HashSet::new()),
+            extensions_dir: paths::extensions_dir(),
+            catalog: OnceLock::new(),
+            wasm_host: None,
+            extensions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Resolve languages and servers from installed Zed extensions too
+    pub fn with_wasm_host(mut self, wasm_host: Arc<WasmHost>) -> Self {
+        self.wasm_host = Some(wasm_host);
+        self
+    }
+
+    fn catalog(&self) -> &ExtensionCatalog {
+        self.catalog
+            .get_or_init(|| ExtensionCatalog::scan(&self.extensions_dir))
+    }
+
+    fn language(&self, path: &Path) -> Option<LanguageInfo> {
+        builtin_language(path).or_else(|| self.catalog().language(path))
+    }
+
+    /// Find a server for a language: `$PATH` first, then extensions
+    async fn resolve_server(&self, root: &Path, language: &str) -> Option<LspServerSpec> {
+        let key = (root.to_path_buf(), language.to_string());
+        if let Some(spec) = self.resolved.lock().get(&key) {
+            return spec.clone();
+        }
+
+        let spec = match builtin_server(language) {
+            Some(spec) => Some(spec),
+            None => self.extension_server(root, language).await,
+        };
+        if spec.is_none() {
+            info!("No language server found for {}", language);
+        }
+        self.resolved.lock().insert(key, spec.clone());
+        spec
+    }
+
+    async fn extension_server(&self, root: &Path, language: &str) -> Option<LspServerSpec> {
+        let server = self.catalog().server(language)?.clone();
+        let host = self.wasm_host.as_ref()?;
+
+        let extension = {
+            let mut extensions = self.extensions.lock().await;
+            match extensions.get(&server.extension_dir) {
+                Some(extension) => extension.clone(),
+                None => {
+                    let extension = host
+                        .load_extension_from_dir(&server.extension_dir)
+                        .await
+                        .map_err(|e| {
+                            warn!(
+                                "Failed to load extension {}: {}",
+                                server.extension_dir.display(),
+                                e
+                            )
+                        })
+                        .ok()?;
+                    extensions.insert(server.extension_dir.clone(), extension.clone());
+                    extension
+                }
+            }
+        };
+
+        let command = extension
+            .language_server_command(server.server_id.as_str().into(), LocalWorktree::new(root))
+            .await
+            .map_err(|e| warn!("Extension server {} unavailable: {}", server.server_id, e))
+            .ok()?;
+
+        Some(LspServerSpec {
+            id: server.server_id,
+            command: command.command,
+            args: command.args,
+            env: command.env.into_iter().collect(),
+        })
+    }
+
+    /// Running (or freshly started) server for a file
+    pub async fn client_for(
+        &self,
+        root: &Path,
+        path: &Path,
+    ) -> Option<(Arc<LspClient>, LanguageInfo)> {
+        let language = self.language(path)?;
+        let spec = self.resolve_server(root, &language.name).await?;
+        let key = (root.to_path_buf(), spec.id.clone());
+        if let Some(client) = self.running(&key).await {
+            return Some((client, language));
+        }
+
+        // Only this server's start is serialized; others start in parallel
+        let gate = Arc::clone(self.starting.lock().entry(key.clone()).or_default());
+        let _starting = gate.lock().await;
+        if self.failed.lock().contains(&key) {
+            return None;
+        }
+        if let Some(client) = self.running(&key).await {
+            return Some((client, language));
+        }
+
+        match tokio::time::timeout(START_TIMEOUT, LspClient::start(&spec, root)).await {
+            Ok(Ok(client)) => {
+                let client = Arc::new(client);
+                self.clients.lock().insert(key, Arc::clone(&client));
+                Some((client, language))
+            }
+            Ok(Err(e)) => {
+                warn!("Failed to start language server {}: {}", spec.id, e);
+                self.failed.lock().insert(key);
+                None
+            }
+            Err(_) => {
+                warn!("Language server {} did not initialize in time", spec.id);
+                self.failed.lock().insert(key);
+                None
+            }
+        }
+    }
+
+    /// The live server for a key, dropping it from the pool if it exited
+    async fn running(&self, key: &Key) -> Option<Arc<LspClient>> {
+        let client = self.clients.lock().get(key).cloned()?;
+        if client.is_alive().await {
+            return Some(client);
+        }
+        warn!("Language server {} exited; restarting", key.1);
+        let mut clients = self.clients.lock();
+        if clients.get(key).is_some_and(|c| Arc::ptr_eq(c, &client)) {
+            clients.remove(key);
+        }
+        None
+    }
+
+    /// Make sure the server has a file's current on-disk contents
+    pub async fn open(&self, root: &Path, path: &Path) -> Result<Arc<LspClient>> {
+        let (client, language) = self
+            .client_for(root, path)
+            .await
+            .ok_or_else(|| anyhow!("No language server available for {}", path.display()))?;
+        let text = tokio::fs::read_to_string(path).await?;
+        client
+            .sync_document(path, &language.language_id, &text)
+            .await?;
+        Ok(client)
+    }
+
+    /// Sync edited files and collect the errors and warnings they now have
+    ///
+    /// Files without a language server, or that come back clean, are omitted.
+    pub async fn diagnostics_after_edit(
+        &self,
+        root: &Path,
+        paths: &[PathBuf],
+    ) -> Vec<FileDiagnostics> {
+        let checks = paths
+            .iter()
+            .map(|path| self.sync_and_collect(root, path, EDIT_DIAGNOSTICS_TIMEOUT));
+        futures::future::join_all(checks)
+            .await
+            .into_iter()
+            .flatten()
+            .map(|mut file| {
+                file.diagnostics.retain(Diagnostic::is_actionable);
+                file
+            })
+            .filter(|file| !file.diagnostics.is_empty())
+            .collect()
+    }
+
+    /// Diagnostics for one file, opening it in its server if needed
+    pub async fn file_diagnostics(&self, root: &Path, path: &Path) -> Result<FileDiagnostics> {
+        if self.language(path).is_none() {
+            return Err(anyhow!(
+                "No language server available for {}",
+                path.display()
+            ));
+        }
+        self.sync_and_collect(root, path, OPEN_DIAGNOSTICS_TIMEOUT)
+            .await
+            .ok_or_else(|| anyhow!("No language server available for {}", path.display()))
+    }
+
+    /// Everything the workspace's running servers have published
+    pub async fn workspace_diagnostics(&self, root: &Path) -> Vec<FileDiagnostics> {
+        let clients: Vec<Arc<LspClient>> = self
+            .clients
+            .lock()
+            .iter()
+            .filter(|((r, _), _)| r == root)
+            .map(|(_, c)| Arc::clone(c))
+            .collect();
+
+        let mut files: Vec<FileDiagnostics> = clients
+            .iter()
+            .flat_map(|c| c.all_diagnostics())
+            .filter_map(|(uri, diagnostics)| {
+                let path = uri_to_path(&uri)?;
+                let text = std::fs::read_to_string(&path).unwrap_or_default();
+                Some(FileDiagnostics {
+                    path,
+                    diagnostics,
+                    text,
+                })
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    async fn sync_and_collect(
+        &self,
+        root: &Path,
+        path: &Path,
+        timeout: Duration,
+    ) -> Option<FileDiagnostics> {
+        let (client, language) = self.client_for(root, path).await?;
+        let text = tokio::fs::read_to_string(path).await.ok()?;
+
+        let since = client.diagnostics_generation();
+        let changed = client
+            .sync_document(path, &language.language_id, &text)
+            .await
+            .map_err(|e| warn!("LSP sync failed for {}: {}", path.display(), e))
+            .ok()?;
+        let diagnostics = if changed {
+            client.wait_for_diagnostics(path, since, timeout).await
+        } else {
+            client.diagnostics(path)
+        };
+
+        Some(FileDiagnostics {
+            path: path.to_path_buf(),
+            diagnostics: diagnostics.unwrap_or_default(),
+            text,
+        })
+    }
+
+    /// Stop every running server
+    pub async fn shutdown(&self) {
+        let clients: Vec<_> = self.clients.lock().drain().map(|(_, c)| c).collect();
+        futures::future::join_all(clients.iter().map(|c| c.shutdown())).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> (LspManager, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let manager = LspManager {
+            extensions_dir: dir.path().join("extensions"),
+            ..LspManager::new()
+        };
+        (manager, dir)
+    }
+
+    fn missing_server(id: &str) -> LspServerSpec {
+        LspServerSpec {
+            id: id.to_string(),
+            command: "/nonexistent/krusty-test-language-server".to_string(),
+            args: Vec::new(),
+            env: HashMap::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_resolution_is_cached_per_root_and_language() {
+        let (manager, dir) = manager();
+        let root = dir.path();
+
+        // Unknown languages resolve to no server, and that is remembered
+        assert!(manager.resolve_server(root, "Klingon").await.is_none());
+        assert_eq!(
+            manager
+                .resolved
+                .lock()
+                .get(&(root.to_path_buf(), "Klingon".to_string())),
+            Some(&None)
+        );
+
+        // A cached spec wins over looking on $PATH, for that root only
+        manager.resolved.lock().insert(
+            (root.to_path_buf(), "Rust".to_string()),
+            Some(missing_server("fake-rust")),
+        );
+        let spec = manager.resolve_server(root, "Rust").await.unwrap();
+        assert_eq!(spec.id, "fake-rust");
+        let other = manager
+            .resolve_server(Path::new("/elsewhere"), "Rust")
+            .await;
+        assert!(other.is_none_or(|s| s.id != "fake-rust"));
+
+        // Files without a known language never reach resolution
+        assert!(manager
+            .client_for(root, &root.join("notes.unknown-ext"))
+            .await
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_start_failure_is_not_retried() {
+        let (manager, dir) = manager();
+        let root = dir.path();
+        manager.resolved.lock().insert(
+            (root.to_path_buf(), "Rust".to_string()),
+            Some(missing_server("fake-rust")),
+        );
+        let file = root.join("main.rs");
+
+        // Concurrent first uses wait on the same start and both fail
+        let (a, b) = tokio::join!(
+            manager.client_for(root, &file),
+            manager.client_for(root, &file)
+        );
+        assert!(a.is_none() && b.is_none());
+        let key = (root.to_path_buf(), "fake-rust".to_string());
+        assert!(manager.failed.lock().contains(&key));
+        assert!(manager.clients.lock().is_empty());
+
+        // Even with a server that would start, the failure sticks
+        manager.resolved.lock().insert(
+            (root.to_path_buf(), "Rust".to_string()),
+            Some(LspServerSpec {
+                command: "true".to_string(),
+                ..missing_server("fake-rust")
+            }),
+        );
+        assert!(manager.client_for(root, &file).await.is_none());
+        assert!(manager.file_diagnostics(root, &file).await.is_err());
+    }
+
+    #[test]
+    fn test_diagnostics_json_formatting() {
+        let root = Path::new("/work");
+        let diagnostic = |line: u32, character: u32, severity: Option<u8>| -> Diagnostic {
+            serde_json::from_value(json!({
+                "range": {
+                    "start": { "line": line, "character": character },
+                    "end": { "line": line, "character": character + 1 }
+                },
+                "severity": severity,
+                "source": "rustc",
+                "code": "E0308",
+                "message": "mismatched types"
+            }))
+            .unwrap()
+        };
+        let mut diagnostics = vec![diagnostic(1, 9, Some(1)), diagnostic(0, 0, Some(2))];
+        diagnostics.extend((0..MAX_DIAGNOSTICS_PER_FILE).map(|_| diagnostic(0, 0, None)));
+        let file = FileDiagnostics {
+            path: PathBuf::from("/work/src/main.rs"),
+            diagnostics,
+            text: "fn main() {\n    let é = 1;\n}\n".to_string(),
+        };
+
+        let json = diagnostics_json(root, &[file]);
+        let entries = json.as_array().unwrap();
+        assert_eq!(entries.len(), MAX_DIAGNOSTICS_PER_FILE);
+        assert_eq!(
+            entries[0],
+            json!({
+                "file": "src/main.rs",
+                "line": 2,
+                // UTF-16 offset 9 is after `é`, the 10th character
+                "column": 10,
+                "severity": "error",
+                "message": "mismatched types",
+                "source": "rustc",
+                "code": "E0308"
+            })
+        );
+        assert_eq!(entries[1]["severity"], "warning");
+        assert_eq!(entries[1]["column"], 1);
+    }
+}
@@ -0,0 +1,21 @@
+//! LSP (Language Server Protocol) client implementation
+//!
+//! Language servers are started per workspace the first time the agent
+//! touches a file of their language: well-known servers on `$PATH` first,
+//! then servers contributed by installed Zed extensions.
+//!
+//! Edits made by `write`, `edit`, `multiedit` and `apply_patch` are synced
+//! to the servers so their diagnostics can be attached to the tool result,
+//! and the `diagnostics`, `definition`, `references`, `hover` and `rename`
+//! tools query them directly.
+
+mod client;
+mod manager;
+pub mod protocol;
+mod servers;
+mod transport;
+
+pub use client::LspClient;
+pub use manager::{diagnostics_json, FileDiagnostics, LspManager};
+pub use protocol::{Diagnostic, Location, Position};
+pub use servers::{LanguageInfo, LspServerSpec};
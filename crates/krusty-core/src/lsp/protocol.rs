@@ -0,0 +1,357 @@
+//! LSP protocol types
+//!
+//! Only the handful of structures Krusty reads or writes are modelled here;
+//! everything else stays as `serde_json::Value`.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Zero-based position; `character` counts UTF-16 code units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+impl Position {
+    /// Convert a 1-based line and character column into an LSP position
+    pub fn from_line_column(text: &str, line: u32, column: u32) -> Self {
+        let line = line.saturating_sub(1);
+        let column = column.saturating_sub(1) as usize;
+        let line_text = text.lines().nth(line as usize).unwrap_or("");
+        let character = line_text
+            .chars()
+            .take(column)
+            .map(char::len_utf16)
+            .sum::<usize>() as u32;
+        Self { line, character }
+    }
+
+    /// Byte offset of this position in `text`, clamped to the line end
+    pub fn to_offset(self, text: &str) -> usize {
+        let mut offset = 0;
+        for (index, line) in text.split_inclusive('\n').enumerate() {
+            if index == self.line as usize {
+                let content = line.trim_end_matches(['\n', '\r']);
+                return offset + utf16_to_byte(content, self.character as usize);
+            }
+            offset += line.len();
+        }
+        text.len()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Location {
+    pub uri: String,
+    pub range: Range,
+}
+
+/// A diagnostic as published by `textDocument/publishDiagnostics`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub const ERROR: u8 = 1;
+    pub const WARNING: u8 = 2;
+
+    /// `error`, `warning`, `info` or `hint` (servers may omit severity)
+    pub fn severity_label(&self) -> &'static str {
+        match self.severity {
+            Some(1) | None => "error",
+            Some(2) => "warning",
+            Some(3) => "info",
+            _ => "hint",
+        }
+    }
+
+    /// Errors and warnings; info and hints are mostly noise for the agent
+    pub fn is_actionable(&self) -> bool {
+        self.severity.is_none_or(|s| s <= Self::WARNING)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PublishDiagnosticsParams {
+    pub uri: String,
+    #[serde(default)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// `file://` URI for a path
+pub fn path_to_uri(path: &Path) -> String {
+    url::Url::from_file_path(path)
+        .map(|u| u.to_string())
+        .unwrap_or_else(|_| format!("file://{}", path.display()))
+}
+
+/// Local path for a `file://` URI
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    url::Url::parse(uri).ok()?.to_file_path().ok()
+}
+
+/// Normalize a definition/references result: `Location`, `Location[]` or `LocationLink[]`
+pub fn parse_locations(value: Value) -> Vec<Location> {
+    let items = match value {
+        Value::Array(items) => items,
+        Value::Null => return Vec::new(),
+        single => vec![single],
+    };
+
+    items
+        .into_iter()
+        .filter_map(|item| {
+            if item.get("targetUri").is_some() {
+                let uri = item.get("targetUri")?.as_str()?.to_string();
+                let range = item
+                    .get("targetSelectionRange")
+                    .or_else(|| item.get("targetRange"))?;
+                Some(Location {
+                    uri,
+                    range: serde_json::from_value(range.clone()).ok()?,
+                })
+            } else {
+                serde_json::from_value(item).ok()
+            }
+        })
+        .collect()
+}
+
+/// Flatten hover contents (`MarkupContent`, `MarkedString` or `MarkedString[]`) to text
+pub fn hover_text(value: &Value) -> Option<String> {
+    fn marked(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Object(obj) => {
+                let text = obj.get("value")?.as_str()?;
+                match obj.get("language").and_then(|l| l.as_str()) {
+                    Some(lang) => Some(format!("```{}\n{}\n```", lang, text)),
+                    None => Some(text.to_string()),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    let contents = value.get("contents")?;
+    let text = match contents {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(marked)
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+        other => marked(other)?,
+    };
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/// Text edits per document from a `WorkspaceEdit`
+///
+/// Handles both `changes` and `documentChanges`. File create/rename/delete
+/// operations are not supported and are returned as skipped descriptions.
+pub fn workspace_edit_changes(edit: &Value) -> (Vec<(String, Vec<TextEdit>)>, Vec<String>) {
+    let mut changes = Vec::new();
+    let mut skipped = Vec::new();
+
+    if let Some(document_changes) = edit.get("documentChanges").and_then(|d| d.as_array()) {
+        for change in document_changes {
+            if let Some(kind) = change.get("kind").and_then(|k| k.as_str()) {
+                skipped.push(format!(
+                    "{} {}",
+                    kind,
+                    change
+                        .get("uri")
+                        .or_else(|| change.get("oldUri"))
+                        .and_then(|u| u.as_str())
+                        .unwrap_or("?")
+                ));
+                continue;
+            }
+            let Some(uri) = change.pointer("/textDocument/uri").and_then(|u| u.as_str()) else {
+                continue;
+            };
+            let edits = change
+                .get("edits")
+                .cloned()
+                .and_then(|e| serde_json::from_value(e).ok())
+                .unwrap_or_default();
+            changes.push((uri.to_string(), edits));
+        }
+    } else if let Some(map) = edit.get("changes").and_then(|c| c.as_object()) {
+        for (uri, edits) in map {
+            let edits = serde_json::from_value(edits.clone()).unwrap_or_default();
+            changes.push((uri.clone(), edits));
+        }
+    }
+
+    (changes, skipped)
+}
+
+/// Apply text edits to a document (edits must not overlap)
+pub fn apply_text_edits(text: &str, edits: &[TextEdit]) -> String {
+    let mut resolved: Vec<(usize, usize, &str)> = edits
+        .iter()
+        .map(|e| {
+            let start = e.range.start.to_offset(text);
+            let end = e.range.end.to_offset(text).max(start);
+            (start, end, e.new_text.as_str())
+        })
+        .collect();
+    // Apply back to front so earlier offsets stay valid
+    resolved.sort_by(|a, b| b.0.cmp(&a.0).then(b.1.cmp(&a.1)));
+
+    let mut result = text.to_string();
+    for (start, end, new_text) in resolved {
+        result.replace_range(start..end, new_text);
+    }
+    result
+}
+
+/// 1-based character column for a UTF-16 offset within a line
+pub fn utf16_to_column(line: &str, utf16: u32) -> u32 {
+    let mut units = 0usize;
+    let mut column = 1u32;
+    for ch in line.chars() {
+        if units >= utf16 as usize {
+            break;
+        }
+        units += ch.len_utf16();
+        column += 1;
+    }
+    column
+}
+
+fn utf16_to_byte(line: &str, utf16: usize) -> usize {
+    let mut units = 0usize;
+    for (byte, ch) in line.char_indices() {
+        if units >= utf16 {
+            return byte;
+        }
+        units += ch.len_utf16();
+    }
+    line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn range(sl: u32, sc: u32, el: u32, ec: u32) -> Range {
+        Range {
+            start: Position {
+                line: sl,
+                character: sc,
+            },
+            end: Position {
+                line: el,
+                character: ec,
+            },
+        }
+    }
+
+    #[test]
+    fn test_position_conversions() {
+        let text = "fn main() {\n    let é = 1;\n}\n";
+        let pos = Position::from_line_column(text, 2, 10);
+        assert_eq!(
+            pos,
+            Position {
+                line: 1,
+                character: 9
+            }
+        );
+        assert_eq!(&text[pos.to_offset(text)..pos.to_offset(text) + 1], " ");
+        assert_eq!(utf16_to_column("    let é = 1;", 9), 10);
+    }
+
+    #[test]
+    fn test_parse_locations_shapes() {
+        let loc = json!({"uri": "file:///a.rs", "range": range(0, 1, 0, 2)});
+        assert_eq!(parse_locations(loc.clone()).len(), 1);
+        assert_eq!(parse_locations(json!([loc.clone(), loc])).len(), 2);
+        assert!(parse_locations(Value::Null).is_empty());
+
+        let links = parse_locations(json!([{
+            "targetUri": "file:///b.rs",
+            "targetRange": range(3, 0, 9, 1),
+            "targetSelectionRange": range(3, 4, 3, 8),
+        }]));
+        assert_eq!(links[0].uri, "file:///b.rs");
+        assert_eq!(links[0].range, range(3, 4, 3, 8));
+    }
+
+    #[test]
+    fn test_hover_text_shapes() {
+        let markup = json!({"contents": {"kind": "markdown", "value": "**x**: i32"}});
+        assert_eq!(hover_text(&markup).as_deref(), Some("**x**: i32"));
+
+        let marked = json!({"contents": [{"language": "rust", "value": "fn f()"}, "docs"]});
+        assert_eq!(
+            hover_text(&marked).as_deref(),
+            Some("```rust\nfn f()\n```\n\ndocs")
+        );
+        assert!(hover_text(&json!({"contents": ""})).is_none());
+    }
+
+    #[test]
+    fn test_workspace_edit_and_apply() {
+        let edit = json!({
+            "documentChanges": [
+                {
+                    "textDocument": {"uri": "file:///a.rs", "version": 1},
+                    "edits": [
+                        {"range": range(0, 3, 0, 6), "newText": "bar"},
+                        {"range": range(1, 4, 1, 7), "newText": "bar"},
+                    ]
+                },
+                {"kind": "rename", "oldUri": "file:///old.rs", "newUri": "file:///new.rs"}
+            ]
+        });
+        let (changes, skipped) = workspace_edit_changes(&edit);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(skipped, vec!["rename file:///old.rs".to_string()]);
+
+        let text = "fn foo() {}\n    foo();\n";
+        assert_eq!(
+            apply_text_edits(text, &changes[0].1),
+            "fn bar() {}\n    bar();\n"
+        );
+
+        let legacy =
+            json!({"changes": {"file:///a.rs": [{"range": range(0, 0, 0, 0), "newText": "x"}]}});
+        assert_eq!(workspace_edit_changes(&legacy).0[0].1.len(), 1);
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let path = PathBuf::from("/tmp/with space/a.rs");
+        let uri = path_to_uri(&path);
+        assert_eq!(uri, "file:///tmp/with%20space/a.rs");
+        assert_eq!(uri_to_path(&uri), Some(path));
+    }
+}
@@ -0,0 +1,276 @@
+//! Language server discovery
+//!
+//! Files are mapped to a language by extension. Each language has a list of
+//! well-known servers tried in order via `$PATH`; languages and servers
+//! contributed by installed Zed extensions fill in the rest.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::extensions::manifest::ExtensionManifest;
+
+/// How to launch a language server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LspServerSpec {
+    /// Stable id, used to pool one process per workspace
+    pub id: String,
+    pub command: String,
+    pub args: Vec<String>,
+    pub env: HashMap<String, String>,
+}
+
+/// Language of a file, named the way Zed extensions name it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub name: String,
+    /// LSP `languageId` sent in `didOpen`
+    pub language_id: String,
+}
+
+/// (file suffix, language name, LSP language id)
+const BUILTIN_LANGUAGES: &[(&str, &str, &str)] = &[
+    ("rs", "Rust", "rust"),
+    ("ts", "TypeScript", "typescript"),
+    ("mts", "TypeScript", "typescript"),
+    ("cts", "TypeScript", "typescript"),
+    ("tsx", "TSX", "typescriptreact"),
+    ("js", "JavaScript", "javascript"),
+    ("mjs", "JavaScript", "javascript"),
+    ("cjs", "JavaScript", "javascript"),
+    ("jsx", "JavaScript", "javascriptreact"),
+    ("py", "Python", "python"),
+    ("pyi", "Python", "python"),
+    ("go", "Go", "go"),
+    ("c", "C", "c"),
+    ("h", "C", "c"),
+    ("cc", "C++", "cpp"),
+    ("cpp", "C++", "cpp"),
+    ("cxx", "C++", "cpp"),
+    ("hpp", "C++", "cpp"),
+    ("hh", "C++", "cpp"),
+    ("zig", "Zig", "zig"),
+    ("lua", "Lua", "lua"),
+];
+
+/// (server id, languages, command, args), in order of preference
+const BUILTIN_SERVERS: &[(&str, &[&str], &str, &[&str])] = &[
+    ("rust-analyzer", &["Rust"], "rust-analyzer", &[]),
+    (
+        "typescript-language-server",
+        &["TypeScript", "TSX", "JavaScript"],
+        "typescript-language-server",
+        &["--stdio"],
+    ),
+    ("pyright", &["Python"], "pyright-langserver", &["--stdio"]),
+    (
+        "basedpyright",
+        &["Python"],
+        "basedpyright-langserver",
+        &["--stdio"],
+    ),
+    ("pylsp", &["Python"], "pylsp", &[]),
+    ("gopls", &["Go"], "gopls", &[]),
+    ("clangd", &["C", "C++"], "clangd", &[]),
+    ("zls", &["Zig"], "zls", &[]),
+    ("lua-language-server", &["Lua"], "lua-language-server", &[]),
+];
+
+fn suffix(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+}
+
+/// Built-in language for a file
+pub fn builtin_language(path: &Path) -> Option<LanguageInfo> {
+    let suffix = suffix(path)?;
+    BUILTIN_LANGUAGES
+        .iter()
+        .find(|(s, _, _)| *s == suffix)
+        .map(|(_, name, id)| LanguageInfo {
+            name: name.to_string(),
+            language_id: id.to_string(),
+        })
+}
+
+/// First built-in server for a language whose binary is on `$PATH`
+pub fn builtin_server(language: &str) -> Option<LspServerSpec> {
+    BUILTIN_SERVERS
+        .iter()
+        .filter(|(_, languages, _, _)| languages.contains(&language))
+        .find_map(|(id, _, command, args)| {
+            let command = which::which(command).ok()?;
+            Some(LspServerSpec {
+                id: id.to_string(),
+                command: command.to_string_lossy().into_owned(),
+                args: args.iter().map(|a| a.to_string()).collect(),
+                env: HashMap::new(),
+            })
+        })
+}
+
+/// A language declared by an installed extension (`languages/*/config.toml`)
+#[derive(Debug, Clone)]
+pub struct ExtensionLanguage {
+    pub name: String,
+    pub path_suffixes: Vec<String>,
+}
+
+/// A language server declared by an installed extension
+#[derive(Debug, Clone)]
+pub struct ExtensionLanguageServer {
+    pub extension_dir: PathBuf,
+    pub server_id: String,
+    pub languages: Vec<String>,
+    pub language_ids: BTreeMap<String, String>,
+}
+
+/// Languages and language servers from installed Zed extensions
+#[derive(Debug, Default)]
+pub struct ExtensionCatalog {
+    languages: Vec<ExtensionLanguage>,
+    servers: Vec<ExtensionLanguageServer>,
+}
+
+impl ExtensionCatalog {
+    /// Scan `extensions_dir/*/extension.toml`
+    pub fn scan(extensions_dir: &Path) -> Self {
+        let mut catalog = Self::default();
+        let Ok(entries) = std::fs::read_dir(extensions_dir) else {
+            return catalog;
+        };
+
+        for entry in entries.flatten() {
+            let dir = entry.path();
+            let Ok(content) = std::fs::read_to_string(dir.join("extension.toml")) else {
+                continue;
+            };
+            let manifest: ExtensionManifest = match toml::from_str(&content) {
+                Ok(m) => m,
+                Err(e) => {
+                    tracing::warn!("Skipping extension {}: {}", dir.display(), e);
+                    continue;
+                }
+            };
+
+            for (server_id, entry) in &manifest.language_servers {
+                let mut languages = entry.languages.clone();
+                languages.extend(entry.language.clone());
+                catalog.servers.push(ExtensionLanguageServer {
+                    extension_dir: dir.clone(),
+                    server_id: server_id.clone(),
+                    languages,
+                    language_ids: entry.language_ids.clone(),
+                });
+            }
+
+            let language_dirs: Vec<PathBuf> = if manifest.languages.is_empty() {
+                std::fs::read_dir(dir.join("languages"))
+                    .map(|e| e.flatten().map(|e| e.path()).collect())
+                    .unwrap_or_default()
+            } else {
+                manifest.languages.iter().map(|p| dir.join(p)).collect()
+            };
+            catalog
+                .languages
+                .extend(language_dirs.iter().filter_map(|d| read_language_config(d)));
+        }
+
+        catalog
+    }
+
+    /// Language for a file from extension-declared suffixes
+    pub fn language(&self, path: &Path) -> Option<LanguageInfo> {
+        let suffix = suffix(path)?;
+        let language = self.languages.iter().find(|l| {
+            l.path_suffixes
+                .iter()
+                .any(|s| s.eq_ignore_ascii_case(&suffix))
+        })?;
+        Some(LanguageInfo {
+            name: language.name.clone(),
+            language_id: self.language_id(&language.name),
+        })
+    }
+
+    /// First extension server for a language
+    pub fn server(&self, language: &str) -> Option<&ExtensionLanguageServer> {
+        self.servers
+            .iter()
+            .find(|s| s.languages.iter().any(|l| l == language))
+    }
+
+    fn language_id(&self, language: &str) -> String {
+        self.servers
+            .iter()
+            .find_map(|s| s.language_ids.get(language).cloned())
+            .unwrap_or_else(|| language.to_lowercase().replace(' ', ""))
+    }
+}
+
+fn read_language_config(dir: &Path) -> Option<ExtensionLanguage> {
+    let content = std::fs::read_to_string(dir.join("config.toml")).ok()?;
+    let config: toml::Value = toml::from_str(&content).ok()?;
+    Some(ExtensionLanguage {
+        name: config.get("name")?.as_str()?.to_string(),
+        path_suffixes: config
+            .get("path_suffixes")
+            .and_then(|s| s.as_array())
+            .map(|s| {
+                s.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_language() {
+        let rust = builtin_language(Path::new("src/main.rs")).unwrap();
+        assert_eq!(rust.name, "Rust");
+        assert_eq!(rust.language_id, "rust");
+        assert_eq!(
+            builtin_language(Path::new("App.TSX")).unwrap().language_id,
+            "typescriptreact"
+        );
+        assert!(builtin_language(Path::new("README.md")).is_none());
+        assert!(builtin_language(Path::new("Makefile")).is_none());
+    }
+
+    #[test]
+    fn test_extension_catalog_scan() {
+        let dir = tempfile::tempdir().unwrap();
+        let ext = dir.path().join("gleam");
+        std::fs::create_dir_all(ext.join("languages/gleam")).unwrap();
+        std::fs::write(
+            ext.join("extension.toml"),
+            r#"
+id = "gleam"
+name = "Gleam"
+version = "0.1.0"
+schema_version = 1
+
+[language_servers.gleam]
+language = "Gleam"
+language_ids = { "Gleam" = "gleam" }
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            ext.join("languages/gleam/config.toml"),
+            "name = \"Gleam\"\npath_suffixes = [\"gleam\"]\n",
+        )
+        .unwrap();
+
+        let catalog = ExtensionCatalog::scan(dir.path());
+        let language = catalog.language(Path::new("src/app.gleam")).unwrap();
+        assert_eq!(language.name, "Gleam");
+        assert_eq!(language.language_id, "gleam");
+        assert_eq!(catalog.server("Gleam").unwrap().server_id, "gleam");
+        assert!(catalog.server("Rust").is_none());
+    }
+}
@@ -0,0 +1,185 @@
+//! LSP stdio transport
+//!
+//! Language servers speak JSON-RPC over stdio with a `Content-Length` header
+//! in front of every message (unlike MCP's newline-delimited JSON).
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Stdio;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+
+/// Refuse frames larger than this rather than allocating unbounded memory
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+
+/// Stdio transport to one language server process
+pub struct LspTransport {
+    stdin: Mutex<ChildStdin>,
+    stdout: Mutex<BufReader<ChildStdout>>,
+    child: Mutex<Child>,
+}
+
+impl LspTransport {
+    /// Spawn a language server process
+    pub fn spawn(
+        command: &str,
+        args: &[String],
+        env: &HashMap<String, String>,
+        working_dir: &Path,
+    ) -> Result<Self> {
+        tracing::info!("Spawning language server: {} {:?}", command, args);
+
+        let mut cmd = Command::new(command);
+        cmd.args(args)
+            .envs(env)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            // Servers log heavily to stderr; an undrained pipe would stall them
+            .stderr(Stdio::null())
+            .current_dir(working_dir)
+            .kill_on_drop(true);
+
+        let mut child = cmd.spawn().map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                anyhow!("Language server not found: {}", command)
+            } else {
+                anyhow!("Failed to spawn {}: {}", command, e)
+            }
+        })?;
+
+        let stdin = child.stdin.take().ok_or_else(|| anyhow!("No stdin"))?;
+        let stdout = child.stdout.take().ok_or_else(|| anyhow!("No stdout"))?;
+
+        Ok(Self {
+            stdin: Mutex::new(stdin),
+            stdout: Mutex::new(BufReader::new(stdout)),
+            child: Mutex::new(child),
+        })
+    }
+
+    /// Send one JSON-RPC message
+    pub async fn send(&self, message: &str) -> Result<()> {
+        let mut stdin = self.stdin.lock().await;
+        stdin.write_all(&encode_frame(message)).await?;
+        stdin.flush().await?;
+        tracing::trace!("LSP sent: {}", message);
+        Ok(())
+    }
+
+    /// Receive the next JSON-RPC message
+    pub async fn receive(&self) -> Result<String> {
+        let mut stdout = self.stdout.lock().await;
+        match read_frame(&mut *stdout).await? {
+            Some(message) => {
+                tracing::trace!("LSP received: {}", message);
+                Ok(message)
+            }
+            None => {
+                let mut child = self.child.lock().await;
+                match child.try_wait() {
+                    Ok(Some(status)) => Err(anyhow!("Language server exited with {}", status)),
+                    _ => Err(anyhow!("Language server closed stdout unexpectedly")),
+                }
+            }
+        }
+    }
+
+    /// Check if the process is still running
+    pub async fn is_alive(&self) -> bool {
+        let mut child = self.child.lock().await;
+        matches!(child.try_wait(), Ok(None))
+    }
+}
+
+/// Prefix a message with its `Content-Length` header
+pub(crate) fn encode_frame(message: &str) -> Vec<u8> {
+    let mut frame = format!("Content-Length: {}\r\n\r\n", message.len()).into_bytes();
+    frame.extend_from_slice(message.as_bytes());
+    frame
+}
+
+/// Read one framed message, or `None` at a clean end of stream
+pub(crate) async fn read_frame<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<String>> {
+    let mut content_length: Option<usize> = None;
+    let mut saw_header = false;
+
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).await? == 0 {
+            return if saw_header {
+                Err(anyhow!("Stream ended inside an LSP header"))
+            } else {
+                Ok(None)
+            };
+        }
+
+        let line = line.trim_end_matches(['\r', '\n']);
+        if line.is_empty() {
+            if saw_header {
+                break;
+            }
+            continue;
+        }
+        saw_header = true;
+
+        if let Some((name, value)) = line.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = Some(value.trim().parse()?);
+            }
+        }
+    }
+
+    let length = content_length.ok_or_else(|| anyhow!("LSP message without Content-Length"))?;
+    if length > MAX_FRAME_BYTES {
+        return Err(anyhow!("LSP message too large: {} bytes", length));
+    }
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(String::from_utf8(body)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn frames_round_trip() {
+        let mut stream = encode_frame(r#"{"jsonrpc":"2.0","id":1}"#);
+        stream.extend(encode_frame(r#"{"method":"é"}"#));
+        let mut reader = BufReader::new(stream.as_slice());
+
+        assert_eq!(
+            read_frame(&mut reader).await.unwrap().as_deref(),
+            Some(r#"{"jsonrpc":"2.0","id":1}"#)
+        );
+        assert_eq!(
+            read_frame(&mut reader).await.unwrap().as_deref(),
+            Some(r#"{"method":"é"}"#)
+        );
+        assert!(read_frame(&mut reader).await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn tolerates_extra_headers() {
+        let body = r#"{"id":2}"#;
+        let raw = format!(
+            "Content-Type: application/vscode-jsonrpc; charset=utf-8\r\ncontent-length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        let mut reader = BufReader::new(raw.as_bytes());
+        assert_eq!(
+            read_frame(&mut reader).await.unwrap().as_deref(),
+            Some(body)
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_length() {
+        let mut reader = BufReader::new("X-Other: 1\r\n\r\n{}".as_bytes());
+        assert!(read_frame(&mut reader).await.is_err());
+    }
+}
@@ -1,59 +1,106 @@
-//! MCP Client for local stdio servers
+//! MCP Client
 //!
-//! Handles JSON-RPC communication with a single MCP server.
-//! Uses a background receive loop to avoid race conditions.
+//! Handles JSON-RPC communication with a single MCP server, local (stdio)
+//! or remote (HTTP). Uses a background receive loop to avoid race conditions.
 
 use anyhow::{anyhow, Result};
-use serde_json::Value;
+use parking_lot::Mutex as SyncMutex;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::Path;
 use std::sync::atomic::{AtomicI64, Ordering};
 use std::sync::Arc;
 use tokio::sync::{mpsc, oneshot, RwLock};
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
 
 use super::config::McpServerConfig;
+use super::http::{HttpTransport, SessionExpired};
 use super::protocol::{
-    ClientCapabilities, ClientInfo, InitializeParams, InitializeResult, McpRequest, McpResponse,
-    McpToolDef, McpToolResult, ToolCallParams, ToolCallResult, ToolsListResult,
+    ClientCapabilities, ClientInfo, InitializeParams, InitializeResult, McpPrompt, McpRequest,
+    McpResource, McpResourceContents, McpResourceTemplate, McpResponse, McpToolDef, McpToolResult,
+    ProgressParams, PromptGetResult, ResourceReadResult, ServerCapabilities, ToolCallParams,
+    ToolCallResult, ToolsListResult,
 };
-use super::transport::StdioTransport;
+use super::transport::{StdioTransport, Transport};
 
 const PROTOCOL_VERSION: &str = "2024-11-05";
 const REQUEST_TIMEOUT_SECS: u64 = 30;
+/// Upper bound on pages fetched for one paginated list
+const MAX_LIST_PAGES: usize = 50;
+
+type PendingMap = HashMap<i64, oneshot::Sender<Result<Value>>>;
+type ProgressMap = HashMap<String, mpsc::UnboundedSender<ProgressParams>>;
+
+/// A `notifications/*/list_changed` from the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ListChanged {
+    Tools,
+    Resources,
+    Prompts,
+}
 
-/// MCP client for a local server
+/// MCP client for one server
 pub struct McpClient {
     name: String,
-    transport: Arc<StdioTransport>,
+    transport: Arc<Transport>,
     next_id: AtomicI64,
     /// Pending request handlers
-    pending: Arc<RwLock<HashMap<i64, oneshot::Sender<Result<Value>>>>>,
+    pending: Arc<RwLock<PendingMap>>,
+    /// Progress listeners for in-flight requests, keyed by progress token
+    progress: Arc<SyncMutex<ProgressMap>>,
+    /// List-changed notifications, taken once by the manager
+    list_changed_rx: SyncMutex<Option<mpsc::UnboundedReceiver<ListChanged>>>,
+    /// Capabilities from the initialize response
+    capabilities: RwLock<ServerCapabilities>,
     /// Cached tools
     tools: RwLock<Vec<McpToolDef>>,
+    /// Cached resources and resource templates
+    resources: RwLock<Vec<McpResource>>,
+    resource_templates: RwLock<Vec<McpResourceTemplate>>,
+    /// Cached prompts
+    prompts: RwLock<Vec<McpPrompt>>,
     /// Shutdown signal
     shutdown_tx: Option<mpsc::Sender<()>>,
 }
 
 impl McpClient {
-    /// Connect to a local MCP server
+    /// Connect to an MCP server
     pub async fn connect(name: &str, config: &McpServerConfig, working_dir: &Path) -> Result<Self> {
-        let McpServerConfig::Local { command, args, env } = config else {
-            return Err(anyhow!("McpClient only handles local servers"));
-        };
-
         info!("Connecting to MCP server: {}", name);
 
-        let transport = Arc::new(StdioTransport::spawn(command, args, env, working_dir).await?);
+        let transport = match config {
+            McpServerConfig::Local { command, args, env } => Transport::Stdio(Box::new(
+                StdioTransport::spawn(command, args, env, working_dir).await?,
+            )),
+            McpServerConfig::Remote {
+                url,
+                authorization_token,
+                headers,
+                legacy_sse,
+            } => Transport::Http(
+                HttpTransport::connect(
+                    name,
+                    url,
+                    authorization_token.clone(),
+                    headers,
+                    *legacy_sse,
+                )
+                .await?,
+            ),
+        };
+        let transport = Arc::new(transport);
 
-        let pending: Arc<RwLock<HashMap<i64, oneshot::Sender<Result<Value>>>>> =
-            Arc::new(RwLock::new(HashMap::new()));
+        let pending: Arc<RwLock<PendingMap>> = Arc::new(RwLock::new(HashMap::new()));
+        let progress: Arc<SyncMutex<ProgressMap>> = Arc::new(SyncMutex::new(HashMap::new()));
+        let (list_changed_tx, list_changed_rx) = mpsc::unbounded_channel();
 
         let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
 
         // Start background receive loop
         let recv_transport = Arc::clone(&transport);
         let recv_pending = Arc::clone(&pending);
+        let recv_progress = Arc::clone(&progress);
         let recv_name = name.to_string();
 
         tokio::spawn(async move {
@@ -66,7 +113,14 @@ impl McpClient {
                     result = recv_transport.receive() => {
                         match result {
                             Ok(message) => {
-                                if let Err(e) = handle_message(&message, &recv_pending).await {
+                                if let Err(e) = handle_message(
+                                    &message,
+                                    &recv_pending,
+                                    &recv_progress,
+                                    &list_changed_tx,
+                                )
+                                .await
+                                {
                                     error!("MCP {} message error: {}", recv_name, e);
                                 }
                             }
@@ -90,7 +144,13 @@ impl McpClient {
             transport,
             next_id: AtomicI64::new(1),
             pending,
+            progress,
+            list_changed_rx: SyncMutex::new(Some(list_changed_rx)),
+            capabilities: RwLock::new(ServerCapabilities::default()),
             tools: RwLock::new(Vec::new()),
+            resources: RwLock::new(Vec::new()),
+            resource_templates: RwLock::new(Vec::new()),
+            prompts: RwLock::new(Vec::new()),
             shutdown_tx: Some(shutdown_tx),
         };
 
@@ -112,7 +172,7 @@ impl McpClient {
 
         debug!("Sending initialize request to {}", self.name);
         let result: InitializeResult = self
-            .request("initialize", Some(serde_json::to_value(params)?))
+            .request_once("initialize", Some(serde_json::to_value(params)?))
             .await
             .map_err(|e| {
                 error!("MCP {} initialize failed: {}", self.name, e);
@@ -124,8 +184,11 @@ impl McpClient {
             self.name, result.protocol_version
         );
 
+        *self.capabilities.write().await = result.capabilities.clone();
+
         // Send initialized notification
         self.notify("notifications/initialized", None).await?;
+        self.transport.on_initialized(&result.protocol_version);
 
         Ok(result)
     }
@@ -151,8 +214,19 @@ impl McpClient {
         Ok(result.tools)
     }
 
-    /// Call a tool
-    pub async fn call_tool(&self, name: &str, arguments: Value) -> Result<McpToolResult> {
+    /// Call a tool, forwarding `notifications/progress` for it to `progress`
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+        progress: Option<mpsc::UnboundedSender<ProgressParams>>,
+    ) -> Result<McpToolResult> {
+        let token = progress.map(|tx| {
+            let token = format!("krusty-{}", self.next_id.fetch_add(1, Ordering::SeqCst));
+            self.progress.lock().insert(token.clone(), tx);
+            token
+        });
+
         let params = ToolCallParams {
             name: name.to_string(),
             arguments: if arguments.is_null() {
@@ -160,13 +234,17 @@ impl McpClient {
             } else {
                 Some(arguments)
             },
+            meta: token.as_ref().map(|t| json!({ "progressToken": t })),
         };
 
-        let result: ToolCallResult = self
-            .request("tools/call", Some(serde_json::to_value(params)?))
-            .await?;
+        let result = self
+            .request::<ToolCallResult>("tools/call", Some(serde_json::to_value(params)?))
+            .await;
+        if let Some(token) = token {
+            self.progress.lock().remove(&token);
+        }
 
-        Ok(result.into())
+        Ok(result?.into())
     }
 
     /// Get cached tools
@@ -174,6 +252,88 @@ impl McpClient {
         self.tools.read().await.clone()
     }
 
+    /// Fetch resources and resource templates, if the server offers them
+    pub async fn list_resources(&self) -> Result<Vec<McpResource>> {
+        if self.capabilities.read().await.resources.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let resources: Vec<McpResource> =
+            self.list_paginated("resources/list", "resources").await?;
+        // Templates are optional even for servers with resources
+        let templates: Vec<McpResourceTemplate> = self
+            .list_paginated("resources/templates/list", "resourceTemplates")
+            .await
+            .unwrap_or_else(|e| {
+                debug!("MCP {} has no resource templates: {}", self.name, e);
+                Vec::new()
+            });
+        info!(
+            "MCP {} has {} resources, {} templates",
+            self.name,
+            resources.len(),
+            templates.len()
+        );
+
+        *self.resources.write().await = resources.clone();
+        *self.resource_templates.write().await = templates;
+        Ok(resources)
+    }
+
+    /// Read a resource by URI
+    pub async fn read_resource(&self, uri: &str) -> Result<Vec<McpResourceContents>> {
+        let result: ResourceReadResult = self
+            .request("resources/read", Some(json!({ "uri": uri })))
+            .await?;
+        Ok(result.contents)
+    }
+
+    /// Get cached resources
+    pub async fn get_resources(&self) -> Vec<McpResource> {
+        self.resources.read().await.clone()
+    }
+
+    /// Get cached resource templates
+    pub async fn get_resource_templates(&self) -> Vec<McpResourceTemplate> {
+        self.resource_templates.read().await.clone()
+    }
+
+    /// Fetch prompts, if the server offers them
+    pub async fn list_prompts(&self) -> Result<Vec<McpPrompt>> {
+        if self.capabilities.read().await.prompts.is_none() {
+            return Ok(Vec::new());
+        }
+
+        let prompts: Vec<McpPrompt> = self.list_paginated("prompts/list", "prompts").await?;
+        info!("MCP {} has {} prompts", self.name, prompts.len());
+
+        *self.prompts.write().await = prompts.clone();
+        Ok(prompts)
+    }
+
+    /// Render a prompt with the given arguments
+    pub async fn get_prompt(
+        &self,
+        name: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<PromptGetResult> {
+        self.request(
+            "prompts/get",
+            Some(json!({ "name": name, "arguments": arguments })),
+        )
+        .await
+    }
+
+    /// Get cached prompts
+    pub async fn get_prompts(&self) -> Vec<McpPrompt> {
+        self.prompts.read().await.clone()
+    }
+
+    /// Take the receiver for the server's list-changed notifications
+    pub(crate) fn take_list_changed(&self) -> Option<mpsc::UnboundedReceiver<ListChanged>> {
+        self.list_changed_rx.lock().take()
+    }
+
     /// Get server name
     pub fn name(&self) -> &str {
         &self.name
@@ -185,10 +345,55 @@ impl McpClient {
     }
 
     /// Send a request and wait for response
+    ///
+    /// If a remote server has dropped our session, re-initialize and retry once.
     async fn request<R: for<'de> serde::Deserialize<'de>>(
         &self,
         method: &str,
         params: Option<Value>,
+    ) -> Result<R> {
+        match self.request_once(method, params.clone()).await {
+            Err(e) if e.is::<SessionExpired>() => {
+                info!("MCP {} session expired, re-initializing", self.name);
+                self.transport.reset_session();
+                self.initialize().await?;
+                self.request_once(method, params).await
+            }
+            result => result,
+        }
+    }
+
+    /// Fetch every page of a cursor-paginated list
+    async fn list_paginated<T: DeserializeOwned>(&self, method: &str, key: &str) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        for _ in 0..MAX_LIST_PAGES {
+            let params = cursor.as_ref().map(|c| json!({ "cursor": c }));
+            let mut page: Value = self.request(method, params).await?;
+            if let Some(list) = page.get_mut(key).map(Value::take) {
+                items.extend(serde_json::from_value::<Vec<T>>(list)?);
+            }
+            cursor = page
+                .get("nextCursor")
+                .and_then(|c| c.as_str())
+                .map(str::to_string);
+            if cursor.is_none() {
+                return Ok(items);
+            }
+        }
+
+        warn!(
+            "MCP {} {} exceeded {} pages; truncating",
+            self.name, method, MAX_LIST_PAGES
+        );
+        Ok(items)
+    }
+
+    async fn request_once<R: for<'de> serde::Deserialize<'de>>(
+        &self,
+        method: &str,
+        params: Option<Value>,
     ) -> Result<R> {
         let id = self.next_id.fetch_add(1, Ordering::SeqCst);
         let request = McpRequest::new(id, method, params);
@@ -201,7 +406,10 @@ impl McpClient {
         self.pending.write().await.insert(id, tx);
 
         // Send request
-        self.transport.send(&json).await?;
+        if let Err(e) = self.transport.send(&json).await {
+            self.pending.write().await.remove(&id);
+            return Err(e);
+        }
 
         // Wait for response with timeout
         let result =
@@ -253,7 +461,9 @@ impl Drop for McpClient {
 /// Handle an incoming message (called by receive loop)
 async fn handle_message(
     message: &str,
-    pending: &RwLock<HashMap<i64, oneshot::Sender<Result<Value>>>>,
+    pending: &RwLock<PendingMap>,
+    progress: &SyncMutex<ProgressMap>,
+    list_changed: &mpsc::UnboundedSender<ListChanged>,
 ) -> Result<()> {
     let response: McpResponse = serde_json::from_str(message)?;
 
@@ -271,10 +481,77 @@ async fn handle_message(
     }
 
     // Handle notifications (server → client)
-    if let Some(method) = &response.method {
-        debug!("MCP notification: {}", method);
-        // We don't need to handle notifications currently
+    let Some(method) = &response.method else {
+        return Ok(());
+    };
+    debug!("MCP notification: {}", method);
+    match method.as_str() {
+        "notifications/progress" => {
+            let params: ProgressParams =
+                serde_json::from_value(response.params.unwrap_or(Value::Null))?;
+            let token = match &params.progress_token {
+                Value::String(token) => token.clone(),
+                other => other.to_string(),
+            };
+            if let Some(tx) = progress.lock().get(&token) {
+                let _ = tx.send(params);
+            }
+        }
+        "notifications/tools/list_changed" => {
+            let _ = list_changed.send(ListChanged::Tools);
+        }
+        "notifications/resources/list_changed" => {
+            let _ = list_changed.send(ListChanged::Resources);
+        }
+        "notifications/prompts/list_changed" => {
+            let _ = list_changed.send(ListChanged::Prompts);
+        }
+        _ => {}
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn routes_progress_to_listener_by_token() {
+        let pending = RwLock::new(HashMap::new());
+        let progress = SyncMutex::new(HashMap::new());
+        let (list_tx, _list_rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        progress.lock().insert("krusty-7".to_string(), tx);
+
+        let message = r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":"krusty-7","progress":2,"total":4,"message":"indexing"}}"#;
+        handle_message(message, &pending, &progress, &list_tx)
+            .await
+            .unwrap();
+
+        let update = rx.try_recv().unwrap();
+        assert_eq!(update.describe(), "[2/4] indexing");
+    }
+
+    #[tokio::test]
+    async fn forwards_list_changed_notifications() {
+        let pending = RwLock::new(HashMap::new());
+        let progress = SyncMutex::new(HashMap::new());
+        let (list_tx, mut list_rx) = mpsc::unbounded_channel();
+
+        for method in [
+            "notifications/tools/list_changed",
+            "notifications/resources/list_changed",
+            "notifications/prompts/list_changed",
+        ] {
+            let message = format!(r#"{{"jsonrpc":"2.0","method":"{}"}}"#, method);
+            handle_message(&message, &pending, &progress, &list_tx)
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(list_rx.try_recv().unwrap(), ListChanged::Tools);
+        assert_eq!(list_rx.try_recv().unwrap(), ListChanged::Resources);
+        assert_eq!(list_rx.try_recv().unwrap(), ListChanged::Prompts);
+    }
+}
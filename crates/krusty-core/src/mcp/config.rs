@@ -1,11 +1,13 @@
 //! MCP configuration parsing
 //!
 //! Parses .mcp.json files. Supports two server types:
-//! - Local (stdio): Spawns a local process, we act as MCP client
-//! - Remote (url): Passed to Anthropic API's MCP Connector
+//! - Local (stdio): Spawns a local process
+//! - Remote (url): Connects over Streamable HTTP, or legacy HTTP+SSE
+//!
+//! Krusty is the MCP client for both, so their tools work with every provider.
 
 use anyhow::{Context, Result};
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use std::collections::HashMap;
 use std::path::Path;
 
@@ -29,13 +31,15 @@ pub enum McpServerConfigRaw {
         #[serde(default)]
         env: HashMap<String, String>,
     },
-    /// Remote server (passed to Anthropic MCP Connector API)
+    /// Remote server (Streamable HTTP, or legacy HTTP+SSE)
     Remote {
         #[serde(rename = "type")]
-        server_type: String, // Must be "url"
+        server_type: String, // "url"/"http" (auto-detect) or "sse" (legacy)
         url: String,
         #[serde(default)]
         authorization_token: Option<String>,
+        #[serde(default)]
+        headers: HashMap<String, String>,
     },
 }
 
@@ -48,24 +52,16 @@ pub enum McpServerConfig {
         args: Vec<String>,
         env: HashMap<String, String>,
     },
-    /// Remote server - passed to Anthropic API's MCP Connector
+    /// Remote server - we connect over HTTP
     Remote {
         url: String,
         authorization_token: Option<String>,
+        headers: HashMap<String, String>,
+        /// Use the legacy HTTP+SSE transport without trying Streamable HTTP
+        legacy_sse: bool,
     },
 }
 
-/// Remote server config for Anthropic API
-#[derive(Debug, Clone, Serialize)]
-pub struct RemoteMcpServer {
-    #[serde(rename = "type")]
-    pub server_type: String,
-    pub url: String,
-    pub name: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub authorization_token: Option<String>,
-}
-
 impl McpServerConfig {
     pub fn is_local(&self) -> bool {
         matches!(self, McpServerConfig::Local { .. })
@@ -78,7 +74,10 @@ impl McpServerConfig {
     pub fn transport_type(&self) -> &'static str {
         match self {
             McpServerConfig::Local { .. } => "stdio",
-            McpServerConfig::Remote { .. } => "remote",
+            McpServerConfig::Remote {
+                legacy_sse: true, ..
+            } => "sse",
+            McpServerConfig::Remote { .. } => "http",
         }
     }
 }
@@ -154,17 +153,24 @@ impl McpConfig {
                     }
                 }
                 McpServerConfigRaw::Remote {
+                    server_type,
                     url,
                     authorization_token,
-                    ..
+                    headers,
                 } => {
                     let token = match authorization_token {
                         Some(t) => Some(expand_env_var(t).await),
                         None => None,
                     };
+                    let mut expanded_headers = HashMap::new();
+                    for (k, v) in headers {
+                        expanded_headers.insert(k.clone(), expand_env_var(v).await);
+                    }
                     McpServerConfig::Remote {
                         url: url.clone(),
                         authorization_token: token,
+                        headers: expanded_headers,
+                        legacy_sse: server_type.eq_ignore_ascii_case("sse"),
                     }
                 }
             };
@@ -172,31 +178,6 @@ impl McpConfig {
         }
         result
     }
-
-    /// Get remote servers formatted for Anthropic API's MCP Connector
-    pub async fn remote_servers_for_api(&self) -> Vec<RemoteMcpServer> {
-        let mut result = Vec::new();
-        for (name, raw) in &self.mcp_servers {
-            if let McpServerConfigRaw::Remote {
-                url,
-                authorization_token,
-                ..
-            } = raw
-            {
-                let token = match authorization_token {
-                    Some(t) => Some(expand_env_var(t).await),
-                    None => None,
-                };
-                result.push(RemoteMcpServer {
-                    server_type: "url".to_string(),
-                    url: url.clone(),
-                    name: name.clone(),
-                    authorization_token: token,
-                });
-            }
-        }
-        result
-    }
 }
 
 /// Expand ${VAR} environment variables, with fallback to credentials store
@@ -259,6 +240,7 @@ fn credential_key_for_env(env_name: &str) -> Option<&'static str> {
         "MINIMAX_API_KEY" => Some("minimax"),
         "OPENROUTER_API_KEY" => Some("openrouter"),
         "OPENAI_API_KEY" => Some("openai"),
+        "GEMINI_API_KEY" => Some("gemini"),
         _ => None,
     }
 }
@@ -351,6 +333,28 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_parse_sse_server_with_headers() {
+        let json = r#"{
+            "mcpServers": {
+                "legacy": {
+                    "type": "sse",
+                    "url": "https://mcp.example.com/sse",
+                    "headers": {"X-Team": "krusty"}
+                }
+            }
+        }"#;
+
+        let config: McpConfig = serde_json::from_str(json).unwrap();
+        let servers = config.servers().await;
+        let server = servers.get("legacy").unwrap();
+        assert_eq!(server.transport_type(), "sse");
+        let McpServerConfig::Remote { headers, .. } = server else {
+            panic!("expected remote server");
+        };
+        assert_eq!(headers.get("X-Team").map(String::as_str), Some("krusty"));
+    }
+
     #[tokio::test]
     async fn test_expand_env_var() {
         // Test that direct values pass through
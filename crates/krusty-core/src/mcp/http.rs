@@ -0,0 +1,658 @@
+//! MCP Streamable HTTP transport, with legacy HTTP+SSE fallback
+//!
+//! Streamable HTTP: every message is POSTed to the server URL and the reply
+//! is either a JSON body or an SSE stream of messages. The server may assign
+//! a session (`Mcp-Session-Id`) that is echoed on later requests, and may push
+//! server-initiated messages on an optional GET SSE stream.
+//!
+//! Legacy HTTP+SSE (protocol 2024-11-05): a GET SSE stream first announces,
+//! in an `endpoint` event, the URL to POST messages to; all replies arrive on
+//! that stream. It is used when a server rejects the initial POST, or when
+//! the server is configured with `"type": "sse"`.
+//!
+//! Both kinds of SSE stream reconnect with backoff. When the server forgets
+//! our session the transport reports [`SessionExpired`] so the client can
+//! re-initialize.
+
+use anyhow::{anyhow, Result};
+use futures::StreamExt;
+use parking_lot::Mutex as SyncMutex;
+use reqwest::header::{ACCEPT, CONTENT_TYPE};
+use reqwest::{RequestBuilder, Response, StatusCode};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, Mutex, Notify};
+use tokio::task::JoinHandle;
+
+use super::keys::McpAuth;
+
+const SESSION_HEADER: &str = "mcp-session-id";
+const PROTOCOL_HEADER: &str = "mcp-protocol-version";
+const EVENT_STREAM: &str = "text/event-stream";
+const INCOMING_BUFFER: usize = 256;
+/// How long a legacy server has to announce its POST endpoint
+const ENDPOINT_TIMEOUT: Duration = Duration::from_secs(15);
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// The server no longer recognizes our session; re-initialize and retry
+#[derive(Debug, thiserror::Error)]
+#[error("MCP session expired")]
+pub struct SessionExpired;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Mode {
+    /// Not known yet; the first POST decides
+    Detect,
+    Streamable,
+    /// POST to the endpoint announced on the legacy SSE stream
+    LegacySse {
+        endpoint: String,
+    },
+}
+
+/// State shared with the background stream readers
+struct Shared {
+    name: String,
+    url: String,
+    client: reqwest::Client,
+    headers: HashMap<String, String>,
+    auth: McpAuth,
+    mode: SyncMutex<Mode>,
+    session_id: SyncMutex<Option<String>>,
+    protocol_version: SyncMutex<Option<String>>,
+    expired: AtomicBool,
+    closed: AtomicBool,
+    closed_notify: Notify,
+    incoming: mpsc::Sender<String>,
+}
+
+/// HTTP transport for remote MCP servers
+pub struct HttpTransport {
+    shared: Arc<Shared>,
+    incoming_rx: Mutex<mpsc::Receiver<String>>,
+    /// Readers of SSE responses to POSTs
+    response_tasks: SyncMutex<Vec<JoinHandle<()>>>,
+    /// GET stream: server push (streamable) or the whole session (legacy)
+    listener: SyncMutex<Option<JoinHandle<()>>>,
+}
+
+impl HttpTransport {
+    /// Create a transport for `url`; nothing is sent until the first message
+    ///
+    /// With `legacy_sse` the HTTP+SSE transport is used from the start.
+    pub async fn connect(
+        name: &str,
+        url: &str,
+        authorization_token: Option<String>,
+        headers: &HashMap<String, String>,
+        legacy_sse: bool,
+    ) -> Result<Self> {
+        tracing::info!("Connecting to remote MCP server {} at {}", name, url);
+        reqwest::Url::parse(url).map_err(|e| anyhow!("Invalid MCP server URL {}: {}", url, e))?;
+
+        let (incoming, incoming_rx) = mpsc::channel(INCOMING_BUFFER);
+        let transport = Self {
+            shared: Arc::new(Shared {
+                name: name.to_string(),
+                url: url.to_string(),
+                client: reqwest::Client::new(),
+                headers: headers.clone(),
+                auth: McpAuth::new(name, authorization_token),
+                mode: SyncMutex::new(Mode::Detect),
+                session_id: SyncMutex::new(None),
+                protocol_version: SyncMutex::new(None),
+                expired: AtomicBool::new(false),
+                closed: AtomicBool::new(false),
+                closed_notify: Notify::new(),
+                incoming,
+            }),
+            incoming_rx: Mutex::new(incoming_rx),
+            response_tasks: SyncMutex::new(Vec::new()),
+            listener: SyncMutex::new(None),
+        };
+
+        if legacy_sse {
+            transport.open_legacy_stream().await?;
+        }
+        Ok(transport)
+    }
+
+    /// Send a JSON-RPC message
+    pub async fn send(&self, message: &str) -> Result<()> {
+        let shared = &self.shared;
+        if shared.closed.load(Ordering::SeqCst) {
+            return Err(anyhow!("MCP server {} connection closed", shared.name));
+        }
+        if shared.expired.load(Ordering::SeqCst) {
+            return Err(SessionExpired.into());
+        }
+
+        let mode = shared.mode.lock().clone();
+        if let Mode::LegacySse { endpoint } = mode {
+            return self.send_legacy(&endpoint, message).await;
+        }
+
+        let response = shared.post(&shared.url, message).await?;
+        let status = response.status();
+        tracing::debug!("MCP {} POST -> {}", shared.name, status);
+
+        if mode == Mode::Detect
+            && matches!(
+                status,
+                StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+            )
+        {
+            tracing::info!(
+                "MCP {} rejected Streamable HTTP ({}), falling back to HTTP+SSE",
+                shared.name,
+                status
+            );
+            let endpoint = self.open_legacy_stream().await?;
+            return self.send_legacy(&endpoint, message).await;
+        }
+
+        if status == StatusCode::NOT_FOUND && shared.session_id.lock().is_some() {
+            shared.expired.store(true, Ordering::SeqCst);
+            return Err(SessionExpired.into());
+        }
+        if !status.is_success() {
+            return Err(error_for_status(&shared.name, response).await);
+        }
+
+        if mode == Mode::Detect {
+            *shared.mode.lock() = Mode::Streamable;
+        }
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            *shared.session_id.lock() = Some(session_id.to_string());
+        }
+
+        if status == StatusCode::ACCEPTED {
+            return Ok(());
+        }
+        if is_event_stream(&response) {
+            let shared = Arc::clone(shared);
+            let task = tokio::spawn(async move {
+                if let Err(e) = read_events(response, &shared, |_| {}).await {
+                    tracing::warn!("MCP {} response stream ended: {}", shared.name, e);
+                }
+            });
+            let mut tasks = self.response_tasks.lock();
+            tasks.retain(|t| !t.is_finished());
+            tasks.push(task);
+        } else {
+            let body = response.text().await?;
+            shared.push_messages(&body).await;
+        }
+        Ok(())
+    }
+
+    /// Receive the next JSON-RPC message from any of the server's streams
+    pub async fn receive(&self) -> Result<String> {
+        let mut rx = self.incoming_rx.lock().await;
+        loop {
+            // Register for the close signal before checking the flag
+            let closed = self.shared.closed_notify.notified();
+            tokio::pin!(closed);
+            closed.as_mut().enable();
+            if self.shared.closed.load(Ordering::SeqCst) {
+                return Err(anyhow!("MCP server {} connection lost", self.shared.name));
+            }
+            tokio::select! {
+                message = rx.recv() => {
+                    return message.ok_or_else(|| anyhow!("MCP transport closed"));
+                }
+                _ = &mut closed => continue,
+            }
+        }
+    }
+
+    /// Whether the transport can still reach the server
+    pub fn is_alive(&self) -> bool {
+        !self.shared.closed.load(Ordering::SeqCst)
+    }
+
+    /// Record the negotiated protocol version and open the server push stream
+    pub fn on_initialized(&self, protocol_version: &str) {
+        *self.shared.protocol_version.lock() = Some(protocol_version.to_string());
+
+        if *self.shared.mode.lock() == Mode::Streamable {
+            let shared = Arc::clone(&self.shared);
+            self.set_listener(tokio::spawn(listen_streamable(shared)));
+        }
+    }
+
+    /// Forget the expired session so the client can initialize a new one
+    pub fn reset_session(&self) {
+        *self.shared.session_id.lock() = None;
+        self.shared.expired.store(false, Ordering::SeqCst);
+    }
+
+    async fn send_legacy(&self, endpoint: &str, message: &str) -> Result<()> {
+        let response = self.shared.post(endpoint, message).await?;
+        if !response.status().is_success() {
+            return Err(error_for_status(&self.shared.name, response).await);
+        }
+        // Replies arrive on the SSE stream
+        Ok(())
+    }
+
+    /// Open the legacy SSE stream and wait for its POST endpoint
+    async fn open_legacy_stream(&self) -> Result<String> {
+        let (endpoint_tx, endpoint_rx) = oneshot::channel();
+        let shared = Arc::clone(&self.shared);
+        self.set_listener(tokio::spawn(listen_legacy(shared, endpoint_tx)));
+
+        match tokio::time::timeout(ENDPOINT_TIMEOUT, endpoint_rx).await {
+            Ok(Ok(Ok(endpoint))) => Ok(endpoint),
+            Ok(Ok(Err(e))) => Err(e),
+            Ok(Err(_)) => Err(anyhow!("SSE stream closed before announcing an endpoint")),
+            Err(_) => Err(anyhow!(
+                "Server did not announce an SSE endpoint within {}s",
+                ENDPOINT_TIMEOUT.as_secs()
+            )),
+        }
+    }
+
+    fn set_listener(&self, task: JoinHandle<()>) {
+        if let Some(old) = self.listener.lock().replace(task) {
+            old.abort();
+        }
+    }
+}
+
+impl Drop for HttpTransport {
+    fn drop(&mut self) {
+        self.shared.closed.store(true, Ordering::SeqCst);
+        if let Some(listener) = self.listener.lock().take() {
+            listener.abort();
+        }
+        for task in self.response_tasks.lock().drain(..) {
+            task.abort();
+        }
+
+        // Tell the server it can drop the session (best effort)
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let shared = Arc::clone(&self.shared);
+        runtime.spawn(async move {
+            if shared.session_id.lock().is_none() {
+                return;
+            }
+            let request = shared.decorate(shared.client.delete(&shared.url)).await;
+            if let Err(e) = request.send().await {
+                tracing::debug!("MCP {} session DELETE failed: {}", shared.name, e);
+            }
+        });
+    }
+}
+
+impl Shared {
+    /// Add configured headers, bearer token, session id and protocol version
+    async fn decorate(&self, mut request: RequestBuilder) -> RequestBuilder {
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+        if let Some(token) = self.auth.bearer().await {
+            request = request.bearer_auth(token);
+        }
+        if let Some(session_id) = self.session_id.lock().clone() {
+            request = request.header(SESSION_HEADER, session_id);
+        }
+        if let Some(version) = self.protocol_version.lock().clone() {
+            request = request.header(PROTOCOL_HEADER, version);
+        }
+        request
+    }
+
+    /// POST a message, refreshing the OAuth token once on 401
+    async fn post(&self, url: &str, message: &str) -> Result<Response> {
+        let mut retried = false;
+        loop {
+            let request = self
+                .client
+                .post(url)
+                .header(CONTENT_TYPE, "application/json")
+                .header(ACCEPT, format!("application/json, {}", EVENT_STREAM))
+                .body(message.to_string());
+            let response = self.decorate(request).await.send().await?;
+            if response.status() == StatusCode::UNAUTHORIZED
+                && !retried
+                && self.auth.refresh().await
+            {
+                retried = true;
+                continue;
+            }
+            return Ok(response);
+        }
+    }
+
+    /// Open a GET SSE stream, with `Last-Event-ID` when resuming
+    async fn get_stream(&self, last_event_id: Option<&str>) -> Result<Response> {
+        let mut request = self.client.get(&self.url).header(ACCEPT, EVENT_STREAM);
+        if let Some(id) = last_event_id {
+            request = request.header("last-event-id", id);
+        }
+        Ok(self.decorate(request).await.send().await?)
+    }
+
+    /// Queue a JSON-RPC message or batch for the client's receive loop
+    async fn push_messages(&self, data: &str) {
+        let data = data.trim();
+        if data.is_empty() {
+            return;
+        }
+        let messages = match serde_json::from_str::<Vec<serde_json::Value>>(data) {
+            Ok(batch) => batch.iter().map(|m| m.to_string()).collect(),
+            Err(_) => vec![data.to_string()],
+        };
+        for message in messages {
+            tracing::debug!("MCP {} received: {}", self.name, message);
+            if self.incoming.send(message).await.is_err() {
+                return;
+            }
+        }
+    }
+
+    fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.closed_notify.notify_waiters();
+    }
+}
+
+/// Follow the optional server push stream of a Streamable HTTP session
+async fn listen_streamable(shared: Arc<Shared>) {
+    let mut last_event_id: Option<String> = None;
+    let mut attempts = 0;
+
+    while !shared.closed.load(Ordering::SeqCst) {
+        match shared.get_stream(last_event_id.as_deref()).await {
+            Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => {
+                tracing::debug!("MCP {} has no server push stream", shared.name);
+                return;
+            }
+            Ok(response) if response.status() == StatusCode::NOT_FOUND => {
+                shared.expired.store(true, Ordering::SeqCst);
+                return;
+            }
+            Ok(response) if response.status().is_success() && is_event_stream(&response) => {
+                let result = read_events(response, &shared, |event| {
+                    if event.id.is_some() {
+                        last_event_id = event.id.clone();
+                    }
+                    attempts = 0;
+                })
+                .await;
+                if let Err(e) = result {
+                    tracing::debug!("MCP {} push stream dropped: {}", shared.name, e);
+                }
+            }
+            Ok(response) => {
+                tracing::debug!(
+                    "MCP {} push stream refused: {}",
+                    shared.name,
+                    response.status()
+                );
+                return;
+            }
+            Err(e) => tracing::debug!("MCP {} push stream failed: {}", shared.name, e),
+        }
+
+        attempts += 1;
+        if attempts > MAX_RECONNECT_ATTEMPTS {
+            tracing::warn!("MCP {} push stream gave up reconnecting", shared.name);
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY * attempts).await;
+    }
+}
+
+/// Run a legacy HTTP+SSE session, reconnecting when the stream drops
+///
+/// The first announced endpoint is sent on `endpoint_tx`. A reconnect starts
+/// a new server-side session, so it marks the old one expired.
+async fn listen_legacy(shared: Arc<Shared>, endpoint_tx: oneshot::Sender<Result<String>>) {
+    let mut endpoint_tx = Some(endpoint_tx);
+    let mut attempts = 0;
+
+    while !shared.closed.load(Ordering::SeqCst) {
+        let failure = match shared.get_stream(None).await {
+            Ok(response) if response.status().is_success() => {
+                let result = read_events(response, &shared, |event| {
+                    if event.event.as_deref() == Some("endpoint") {
+                        attempts = 0;
+                        match resolve_endpoint(&shared.url, &event.data) {
+                            Ok(endpoint) => {
+                                set_legacy_endpoint(&shared, endpoint, &mut endpoint_tx)
+                            }
+                            Err(e) => {
+                                tracing::warn!("MCP {}: {}", shared.name, e);
+                                if let Some(tx) = endpoint_tx.take() {
+                                    let _ = tx.send(Err(e));
+                                }
+                            }
+                        }
+                    }
+                })
+                .await;
+                result.err().map(|e| e.to_string())
+            }
+            Ok(response) => Some(format!("HTTP {}", response.status())),
+            Err(e) => Some(e.to_string()),
+        };
+        tracing::debug!(
+            "MCP {} SSE stream ended: {}",
+            shared.name,
+            failure.as_deref().unwrap_or("closed by server")
+        );
+
+        // Never connected: report the failure instead of retrying
+        if let Some(tx) = endpoint_tx.take() {
+            let _ = tx.send(Err(anyhow!(
+                "Failed to open SSE stream: {}",
+                failure.as_deref().unwrap_or("closed by server")
+            )));
+            shared.close();
+            return;
+        }
+
+        attempts += 1;
+        if attempts > MAX_RECONNECT_ATTEMPTS {
+            tracing::warn!("MCP {} SSE stream gave up reconnecting", shared.name);
+            shared.close();
+            return;
+        }
+        tokio::time::sleep(RECONNECT_DELAY * attempts).await;
+    }
+}
+
+fn set_legacy_endpoint(
+    shared: &Shared,
+    endpoint: String,
+    endpoint_tx: &mut Option<oneshot::Sender<Result<String>>>,
+) {
+    tracing::debug!("MCP {} SSE endpoint: {}", shared.name, endpoint);
+    *shared.mode.lock() = Mode::LegacySse {
+        endpoint: endpoint.clone(),
+    };
+    match endpoint_tx.take() {
+        Some(tx) => {
+            let _ = tx.send(Ok(endpoint));
+        }
+        None => shared.expired.store(true, Ordering::SeqCst),
+    }
+}
+
+/// Resolve an announced endpoint relative to the server URL
+///
+/// Endpoints on another origin are rejected: requests to them would carry
+/// the server's authorization token and headers.
+fn resolve_endpoint(base: &str, endpoint: &str) -> Result<String> {
+    let base = reqwest::Url::parse(base)?;
+    let url = base.join(endpoint.trim())?;
+    if url.origin() != base.origin() {
+        return Err(anyhow!(
+            "SSE endpoint {} is not on the server's origin {}",
+            url,
+            base.origin().ascii_serialization()
+        ));
+    }
+    Ok(url.to_string())
+}
+
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with(EVENT_STREAM))
+}
+
+async fn error_for_status(name: &str, response: Response) -> anyhow::Error {
+    let status = response.status();
+    if status == StatusCode::UNAUTHORIZED {
+        return anyhow!(
+            "MCP server {} requires authorization: set authorization_token in .mcp.json or add a token to {}",
+            name,
+            crate::paths::mcp_keys_path().display()
+        );
+    }
+    let body = response.text().await.unwrap_or_default();
+    anyhow!("MCP server {} returned {}: {}", name, status, body.trim())
+}
+
+/// Read an SSE response, queueing `message` events for the client
+///
+/// `on_event` sees every event, e.g. to track ids or the legacy endpoint.
+async fn read_events(
+    response: Response,
+    shared: &Shared,
+    mut on_event: impl FnMut(&SseEvent),
+) -> Result<()> {
+    let mut decoder = SseDecoder::default();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        for event in decoder.feed(&chunk?) {
+            on_event(&event);
+            if matches!(event.event.as_deref(), None | Some("message")) {
+                shared.push_messages(&event.data).await;
+            }
+        }
+        if shared.closed.load(Ordering::SeqCst) {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// One server-sent event
+#[derive(Debug, Default, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    data: String,
+    id: Option<String>,
+}
+
+/// Incremental SSE decoder that copes with events split across chunks
+#[derive(Default)]
+struct SseDecoder {
+    buffer: Vec<u8>,
+    current: SseEvent,
+    has_data: bool,
+}
+
+impl SseDecoder {
+    fn feed(&mut self, chunk: &[u8]) -> Vec<SseEvent> {
+        self.buffer.extend_from_slice(chunk);
+        let mut events = Vec::new();
+
+        while let Some(newline) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=newline).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            if line.is_empty() {
+                if self.has_data || self.current.event.is_some() {
+                    events.push(std::mem::take(&mut self.current));
+                }
+                self.has_data = false;
+                continue;
+            }
+            if line.starts_with(':') {
+                continue;
+            }
+
+            let (field, value) = line.split_once(':').unwrap_or((line, ""));
+            let value = value.strip_prefix(' ').unwrap_or(value);
+            match field {
+                "event" => self.current.event = Some(value.to_string()),
+                "id" => self.current.id = Some(value.to_string()),
+                "data" => {
+                    if self.has_data {
+                        self.current.data.push('\n');
+                    }
+                    self.current.data.push_str(value);
+                    self.has_data = true;
+                }
+                _ => {}
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_decoder_split_chunks() {
+        let mut decoder = SseDecoder::default();
+        assert!(decoder.feed(b"event: endpoint\r\nda").is_empty());
+        let events =
+            decoder.feed(b"ta: /messages?session=1\r\n\r\n: ping\n\nid: 7\ndata: {\"a\":\n");
+        assert_eq!(
+            events,
+            vec![SseEvent {
+                event: Some("endpoint".to_string()),
+                data: "/messages?session=1".to_string(),
+                id: None,
+            }]
+        );
+
+        let events = decoder.feed(b"data: 1}\n\n");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].data, "{\"a\":\n1}");
+        assert_eq!(events[0].id.as_deref(), Some("7"));
+    }
+
+    #[test]
+    fn test_resolve_endpoint() {
+        let base = "https://mcp.example.com/sse";
+        assert_eq!(
+            resolve_endpoint(base, "/messages?s=1").unwrap(),
+            "https://mcp.example.com/messages?s=1"
+        );
+        assert_eq!(
+            resolve_endpoint(base, "https://mcp.example.com:443/m").unwrap(),
+            "https://mcp.example.com/m"
+        );
+        // Another scheme, host or port would receive the server's credentials
+        for endpoint in [
+            "https://other.example.com/m",
+            "http://mcp.example.com/m",
+            "https://mcp.example.com:8443/m",
+            "//other.example.com/m",
+        ] {
+            assert!(resolve_endpoint(base, endpoint).is_err(), "{}", endpoint);
+        }
+    }
+}
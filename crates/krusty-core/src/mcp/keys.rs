@@ -0,0 +1,219 @@
+//! Credentials for remote MCP servers
+//!
+//! A server's bearer token comes from its `authorization_token` in .mcp.json
+//! or, failing that, from `~/.krusty/tokens/mcp_keys.json`, keyed by server
+//! name. Entries there are either a plain token string or an OAuth token that
+//! is refreshed when it expires or the server answers 401:
+//!
+//! ```json
+//! {
+//!   "linear": "lin_api_...",
+//!   "notion": {
+//!     "access_token": "...",
+//!     "refresh_token": "...",
+//!     "expires_at": 1767225600,
+//!     "token_url": "https://mcp.notion.com/token",
+//!     "client_id": "..."
+//!   }
+//! }
+//! ```
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+/// Refresh OAuth tokens this many seconds before they expire
+const EXPIRY_MARGIN_SECS: u64 = 60;
+
+/// A stored MCP server credential
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum McpKey {
+    Token(String),
+    OAuth(McpOAuthToken),
+}
+
+/// OAuth token for an MCP server, with what is needed to refresh it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpOAuthToken {
+    pub access_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refresh_token: Option<String>,
+    /// Unix timestamp when the access token expires (if known)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+}
+
+impl McpOAuthToken {
+    fn expires_soon(&self) -> bool {
+        self.expires_at
+            .is_some_and(|at| now_secs() + EXPIRY_MARGIN_SECS >= at)
+    }
+
+    fn can_refresh(&self) -> bool {
+        self.refresh_token.is_some() && self.token_url.is_some()
+    }
+}
+
+#[derive(Deserialize)]
+struct RefreshResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    expires_in: Option<u64>,
+}
+
+/// Resolves the bearer token for one remote server
+pub struct McpAuth {
+    server: String,
+    /// `authorization_token` from .mcp.json; takes precedence when set
+    configured: Option<String>,
+    stored: RwLock<Option<McpKey>>,
+}
+
+impl McpAuth {
+    pub fn new(server: &str, configured: Option<String>) -> Self {
+        let configured = configured.filter(|t| !t.is_empty());
+        let stored = if configured.is_none() {
+            load_keys().remove(server)
+        } else {
+            None
+        };
+        Self {
+            server: server.to_string(),
+            configured,
+            stored: RwLock::new(stored),
+        }
+    }
+
+    /// Current bearer token, refreshing an expiring OAuth token first
+    pub async fn bearer(&self) -> Option<String> {
+        if let Some(token) = &self.configured {
+            return Some(token.clone());
+        }
+
+        let needs_refresh = matches!(
+            &*self.stored.read().await,
+            Some(McpKey::OAuth(t)) if t.expires_soon() && t.can_refresh()
+        );
+        if needs_refresh {
+            self.refresh().await;
+        }
+
+        match &*self.stored.read().await {
+            Some(McpKey::Token(token)) => Some(token.clone()),
+            Some(McpKey::OAuth(t)) => Some(t.access_token.clone()),
+            None => None,
+        }
+    }
+
+    /// Try to refresh the stored OAuth token after the server rejected it
+    ///
+    /// Returns true if a new token is available for a retry.
+    pub async fn refresh(&self) -> bool {
+        if self.configured.is_some() {
+            return false;
+        }
+
+        let mut stored = self.stored.write().await;
+        let Some(McpKey::OAuth(token)) = &*stored else {
+            return false;
+        };
+        match refresh_token(token).await {
+            Ok(refreshed) => {
+                tracing::info!("Refreshed OAuth token for MCP server {}", self.server);
+                if let Err(e) = save_key(&self.server, &McpKey::OAuth(refreshed.clone())) {
+                    tracing::warn!("Failed to save refreshed MCP token: {}", e);
+                }
+                *stored = Some(McpKey::OAuth(refreshed));
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Failed to refresh OAuth token for MCP server {}: {}",
+                    self.server,
+                    e
+                );
+                false
+            }
+        }
+    }
+}
+
+async fn refresh_token(token: &McpOAuthToken) -> Result<McpOAuthToken> {
+    let (Some(refresh_token), Some(token_url)) = (&token.refresh_token, &token.token_url) else {
+        return Err(anyhow!("No refresh token or token_url stored"));
+    };
+
+    let mut form = vec![
+        ("grant_type", "refresh_token"),
+        ("refresh_token", refresh_token.as_str()),
+    ];
+    if let Some(client_id) = &token.client_id {
+        form.push(("client_id", client_id.as_str()));
+    }
+
+    let response = reqwest::Client::new()
+        .post(token_url)
+        .form(&form)
+        .send()
+        .await
+        .context("Failed to send token refresh request")?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(anyhow!("Token refresh failed ({}): {}", status, body));
+    }
+
+    let refreshed: RefreshResponse = response
+        .json()
+        .await
+        .context("Failed to parse token refresh response")?;
+    Ok(McpOAuthToken {
+        access_token: refreshed.access_token,
+        refresh_token: refreshed
+            .refresh_token
+            .or_else(|| token.refresh_token.clone()),
+        expires_at: refreshed.expires_in.map(|secs| now_secs() + secs),
+        token_url: token.token_url.clone(),
+        client_id: token.client_id.clone(),
+    })
+}
+
+fn load_keys() -> HashMap<String, McpKey> {
+    let path = crate::paths::mcp_keys_path();
+    let Ok(content) = std::fs::read_to_string(&path) else {
+        return HashMap::new();
+    };
+    serde_json::from_str(&content).unwrap_or_else(|e| {
+        tracing::warn!("Failed to parse {:?}: {}", path, e);
+        HashMap::new()
+    })
+}
+
+fn save_key(server: &str, key: &McpKey) -> Result<()> {
+    let path = crate::paths::mcp_keys_path();
+    let mut keys = load_keys();
+    keys.insert(server.to_string(), key.clone());
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&path, serde_json::to_string_pretty(&keys)?)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+fn now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
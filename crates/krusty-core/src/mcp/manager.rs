@@ -1,19 +1,24 @@
-//! MCP Manager - manages local MCP server connections
+//! MCP Manager - manages MCP server connections
 //!
-//! Simple manager for local stdio servers. Remote servers are handled
-//! by passing them to the Anthropic API's MCP Connector.
+//! Connects to local stdio servers and remote HTTP servers alike, so their
+//! tools can be registered for every provider. Resources and prompts are
+//! cached per server, and list changes announced by a server are refetched
+//! and broadcast as [`McpEvent`]s.
 
 use anyhow::Result;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::sync::{Arc, Weak};
+use tokio::sync::{broadcast, mpsc, RwLock};
 use tracing::{info, warn};
 
-use super::client::McpClient;
-use super::config::{McpConfig, McpServerConfig, RemoteMcpServer};
-use super::protocol::{McpToolDef, McpToolResult};
+use super::client::{ListChanged, McpClient};
+use super::config::{McpConfig, McpServerConfig};
+use super::protocol::{
+    McpPrompt, McpResource, McpResourceContents, McpResourceTemplate, McpToolDef, McpToolResult,
+    ProgressParams, PromptGetResult,
+};
 
 /// Server status
 #[derive(Debug, Clone, PartialEq)]
@@ -33,11 +38,19 @@ impl std::fmt::Display for McpServerStatus {
     }
 }
 
+/// Change announced by a connected server, after the cache was refreshed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum McpEvent {
+    ToolsChanged { server: String },
+    ResourcesChanged { server: String },
+    PromptsChanged { server: String },
+}
+
 /// Server info for UI
 #[derive(Debug, Clone)]
 pub struct McpServerInfo {
     pub name: String,
-    pub server_type: String, // "stdio" or "remote"
+    pub server_type: String, // "stdio", "http" or "sse"
     pub status: McpServerStatus,
     pub tool_count: usize,
     pub tools: Vec<McpToolDef>,
@@ -46,14 +59,14 @@ pub struct McpServerInfo {
 
 /// MCP Manager
 pub struct McpManager {
-    /// Connected local clients
+    /// Connected clients
     clients: RwLock<HashMap<String, Arc<McpClient>>>,
     /// Server configurations
     configs: RwLock<HashMap<String, McpServerConfig>>,
-    /// Remote servers (for API)
-    remote_servers: RwLock<Vec<RemoteMcpServer>>,
     /// Working directory
     working_dir: PathBuf,
+    /// List-change events for subscribers
+    events: broadcast::Sender<McpEvent>,
 }
 
 impl McpManager {
@@ -61,11 +74,16 @@ impl McpManager {
         Self {
             clients: RwLock::new(HashMap::new()),
             configs: RwLock::new(HashMap::new()),
-            remote_servers: RwLock::new(Vec::new()),
             working_dir,
+            events: broadcast::channel(64).0,
         }
     }
 
+    /// Subscribe to tool, resource and prompt list changes
+    pub fn subscribe(&self) -> broadcast::Receiver<McpEvent> {
+        self.events.subscribe()
+    }
+
     /// Load configuration from .mcp.json
     pub async fn load_config(&self) -> Result<()> {
         let config = McpConfig::load(&self.working_dir).await?;
@@ -73,9 +91,6 @@ impl McpManager {
         let mut configs = self.configs.write().await;
         *configs = config.servers().await;
 
-        // Store remote servers for API
-        *self.remote_servers.write().await = config.remote_servers_for_api().await;
-
         let local_count = configs.values().filter(|c| c.is_local()).count();
         let remote_count = configs.values().filter(|c| c.is_remote()).count();
 
@@ -87,13 +102,19 @@ impl McpManager {
         Ok(())
     }
 
-    /// Connect to all local servers in parallel
+    /// Add a server configuration alongside those from .mcp.json
+    ///
+    /// Used for servers supplied at runtime, such as by an ACP client.
+    pub async fn add_server(&self, name: String, config: McpServerConfig) {
+        self.configs.write().await.insert(name, config);
+    }
+
+    /// Connect to all servers in parallel
     pub async fn connect_all(&self) -> Result<()> {
         let configs: Vec<_> = {
             let configs = self.configs.read().await;
             configs
                 .iter()
-                .filter(|(_, c)| c.is_local())
                 .map(|(n, c)| (n.clone(), c.clone()))
                 .collect()
         };
@@ -102,10 +123,7 @@ impl McpManager {
             return Ok(());
         }
 
-        info!(
-            "Connecting to {} local MCP servers in parallel",
-            configs.len()
-        );
+        info!("Connecting to {} MCP servers in parallel", configs.len());
 
         // Connect to all servers in parallel
         let connect_futures: Vec<_> = configs
@@ -130,7 +148,7 @@ impl McpManager {
         Ok(())
     }
 
-    /// Connect to a specific local server
+    /// Connect to a specific server
     pub async fn connect(&self, name: &str) -> Result<()> {
         let config = {
             let configs = self.configs.read().await;
@@ -141,13 +159,6 @@ impl McpManager {
             return Err(anyhow::anyhow!("Unknown server: {}", name));
         };
 
-        if config.is_remote() {
-            return Err(anyhow::anyhow!(
-                "Server {} is remote - handled by Anthropic API",
-                name
-            ));
-        }
-
         // Disconnect first if already connected
         self.disconnect(name).await;
 
@@ -157,10 +168,23 @@ impl McpManager {
         // Initialize
         client.initialize().await?;
 
-        // Get tools
+        // Get tools, then resources and prompts (optional, so only warn)
         client.list_tools().await?;
+        if let Err(e) = client.list_resources().await {
+            warn!("Failed to list resources for MCP server {}: {}", name, e);
+        }
+        if let Err(e) = client.list_prompts().await {
+            warn!("Failed to list prompts for MCP server {}: {}", name, e);
+        }
 
         let client = Arc::new(client);
+        if let Some(rx) = client.take_list_changed() {
+            tokio::spawn(watch_list_changes(
+                Arc::downgrade(&client),
+                rx,
+                self.events.clone(),
+            ));
+        }
         self.clients.write().await.insert(name.to_string(), client);
 
         info!("Connected to MCP server: {}", name);
@@ -174,7 +198,15 @@ impl McpManager {
         }
     }
 
-    /// Get all tools from connected local servers
+    /// Disconnect from every server, stopping local server processes
+    pub async fn disconnect_all(&self) {
+        let names: Vec<String> = self.clients.read().await.keys().cloned().collect();
+        for name in names {
+            self.disconnect(&name).await;
+        }
+    }
+
+    /// Get all tools from connected servers
     pub async fn get_all_tools(&self) -> Vec<(String, McpToolDef)> {
         let clients = self.clients.read().await;
         let mut tools = Vec::new();
@@ -188,19 +220,83 @@ impl McpManager {
         tools
     }
 
-    /// Call a tool on a local server
+    /// Call a tool on a connected server
+    ///
+    /// Progress notifications for the call are sent to `progress` if given.
     pub async fn call_tool(
         &self,
         server: &str,
         tool: &str,
         arguments: Value,
+        progress: Option<mpsc::UnboundedSender<ProgressParams>>,
     ) -> Result<McpToolResult> {
+        let client = self.require_client(server).await?;
+        client.call_tool(tool, arguments, progress).await
+    }
+
+    /// Get all resources from connected servers
+    pub async fn get_all_resources(&self) -> Vec<(String, McpResource)> {
+        let clients = self.clients.read().await;
+        let mut resources = Vec::new();
+
+        for (name, client) in clients.iter() {
+            for resource in client.get_resources().await {
+                resources.push((name.clone(), resource));
+            }
+        }
+
+        resources
+    }
+
+    /// Get all resource templates from connected servers
+    pub async fn get_all_resource_templates(&self) -> Vec<(String, McpResourceTemplate)> {
+        let clients = self.clients.read().await;
+        let mut templates = Vec::new();
+
+        for (name, client) in clients.iter() {
+            for template in client.get_resource_templates().await {
+                templates.push((name.clone(), template));
+            }
+        }
+
+        templates
+    }
+
+    /// Read a resource from a connected server
+    pub async fn read_resource(&self, server: &str, uri: &str) -> Result<Vec<McpResourceContents>> {
+        let client = self.require_client(server).await?;
+        client.read_resource(uri).await
+    }
+
+    /// Get all prompts from connected servers
+    pub async fn get_all_prompts(&self) -> Vec<(String, McpPrompt)> {
         let clients = self.clients.read().await;
-        let client = clients
-            .get(server)
-            .ok_or_else(|| anyhow::anyhow!("Server not connected: {}", server))?;
+        let mut prompts = Vec::new();
+
+        for (name, client) in clients.iter() {
+            for prompt in client.get_prompts().await {
+                prompts.push((name.clone(), prompt));
+            }
+        }
+
+        prompts
+    }
 
-        client.call_tool(tool, arguments).await
+    /// Render a prompt from a connected server
+    pub async fn get_prompt(
+        &self,
+        server: &str,
+        prompt: &str,
+        arguments: HashMap<String, String>,
+    ) -> Result<PromptGetResult> {
+        let client = self.require_client(server).await?;
+        client.get_prompt(prompt, arguments).await
+    }
+
+    async fn require_client(&self, server: &str) -> Result<Arc<McpClient>> {
+        self.get_client(server)
+            .await
+            .ok_or_else(|| anyhow::anyhow!("Server not connected: {}", server))
     }
 
     /// Get server info for UI
@@ -211,25 +307,25 @@ impl McpManager {
         let mut servers = Vec::new();
 
         for (name, config) in configs.iter() {
-            let (status, tool_count, tools, error) = if config.is_local() {
-                if let Some(client) = clients.get(name) {
-                    let t = client.get_tools().await;
-                    if client.is_alive().await {
-                        (McpServerStatus::Connected, t.len(), t, None)
-                    } else {
-                        (
-                            McpServerStatus::Error("Process died".to_string()),
-                            0,
-                            Vec::new(),
-                            Some("Process died".to_string()),
-                        )
-                    }
+            let (status, tool_count, tools, error) = if let Some(client) = clients.get(name) {
+                let t = client.get_tools().await;
+                if client.is_alive().await {
+                    (McpServerStatus::Connected, t.len(), t, None)
                 } else {
-                    (McpServerStatus::Disconnected, 0, Vec::new(), None)
+                    let reason = if config.is_local() {
+                        "Process died"
+                    } else {
+                        "Connection lost"
+                    };
+                    (
+                        McpServerStatus::Error(reason.to_string()),
+                        0,
+                        Vec::new(),
+                        Some(reason.to_string()),
+                    )
                 }
             } else {
-                // Remote servers are always "connected" (handled by API)
-                (McpServerStatus::Connected, 0, Vec::new(), None)
+                (McpServerStatus::Disconnected, 0, Vec::new(), None)
             };
 
             servers.push(McpServerInfo {
@@ -246,11 +342,6 @@ impl McpManager {
         servers
     }
 
-    /// Get remote servers for Anthropic API
-    pub async fn get_remote_servers(&self) -> Vec<RemoteMcpServer> {
-        self.remote_servers.read().await.clone()
-    }
-
     /// Check if any servers are configured
     pub async fn has_servers(&self) -> bool {
         !self.configs.read().await.is_empty()
@@ -261,3 +352,45 @@ impl McpManager {
         self.clients.read().await.get(name).cloned()
     }
 }
+
+/// Refresh a client's cached lists when the server announces a change
+///
+/// Ends when the client is dropped (its receive loop closes the channel).
+async fn watch_list_changes(
+    client: Weak<McpClient>,
+    mut rx: mpsc::UnboundedReceiver<ListChanged>,
+    events: broadcast::Sender<McpEvent>,
+) {
+    while let Some(change) = rx.recv().await {
+        let Some(client) = client.upgrade() else {
+            break;
+        };
+        let server = client.name().to_string();
+        let event = match change {
+            ListChanged::Tools => client
+                .list_tools()
+                .await
+                .map(|_| McpEvent::ToolsChanged { server }),
+            ListChanged::Resources => client
+                .list_resources()
+                .await
+                .map(|_| McpEvent::ResourcesChanged { server }),
+            ListChanged::Prompts => client
+                .list_prompts()
+                .await
+                .map(|_| McpEvent::PromptsChanged { server }),
+        };
+        match event {
+            Ok(event) => {
+                info!("MCP list changed: {:?}", event);
+                let _ = events.send(event);
+            }
+            Err(e) => warn!(
+                "Failed to refresh {:?} list for MCP server {}: {}",
+                change,
+                client.name(),
+                e
+            ),
+        }
+    }
+}
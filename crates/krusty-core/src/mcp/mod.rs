@@ -2,18 +2,25 @@
 //!
 //! Supports two types of MCP servers:
 //! - Local (stdio): We spawn the process and act as MCP client
-//! - Remote (url): Passed to Anthropic API's MCP Connector
+//! - Remote (url): We connect over Streamable HTTP, or legacy HTTP+SSE
 //!
-//! Local servers are managed here. Remote servers are passed to the API.
+//! Tools from both are registered in the `ToolRegistry` for every provider.
+//! Resources and prompts are cached per server for `@` mentions and slash
+//! commands, and progress notifications stream into tool output.
 
 mod client;
 mod config;
+mod http;
+mod keys;
 mod manager;
 mod protocol;
 pub mod tool;
 mod transport;
 
-pub use config::{McpConfig, McpServerConfig, RemoteMcpServer};
-pub use manager::{McpManager, McpServerInfo, McpServerStatus};
-pub use protocol::{McpContent, McpToolDef, McpToolResult};
+pub use config::{McpConfig, McpServerConfig};
+pub use manager::{McpEvent, McpManager, McpServerInfo, McpServerStatus};
+pub use protocol::{
+    format_resource_contents, McpContent, McpPrompt, McpPromptArgument, McpResource,
+    McpResourceContents, McpResourceTemplate, McpToolDef, McpToolResult, PromptGetResult,
+};
 pub use tool::McpTool;
@@ -4,6 +4,7 @@
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
 /// JSON-RPC request
 #[derive(Debug, Serialize)]
@@ -37,9 +38,9 @@ pub struct McpResponse {
     /// For notifications
     #[serde(default)]
     pub method: Option<String>,
-    /// Notification params (protocol field, not directly used)
+    /// Notification params
     #[serde(default)]
-    pub _params: Option<Value>,
+    pub params: Option<Value>,
 }
 
 /// JSON-RPC error
@@ -181,6 +182,9 @@ pub struct ToolCallParams {
     pub name: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub arguments: Option<Value>,
+    /// Request metadata (carries the progress token)
+    #[serde(rename = "_meta", skip_serializing_if = "Option::is_none")]
+    pub meta: Option<Value>,
 }
 
 /// Tool call result (from server)
@@ -201,6 +205,212 @@ impl From<ToolCallResult> for McpToolResult {
     }
 }
 
+/// Resource from resources/list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResource {
+    pub uri: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+/// Resource template from resources/templates/list (RFC 6570 URI template)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResourceTemplate {
+    pub uri_template: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+}
+
+/// One item of a resources/read result; text or base64 blob
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpResourceContents {
+    pub uri: String,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub text: Option<String>,
+    #[serde(default)]
+    pub blob: Option<String>,
+}
+
+/// Resources read response
+#[derive(Debug, Deserialize)]
+pub struct ResourceReadResult {
+    pub contents: Vec<McpResourceContents>,
+}
+
+/// Prompt from prompts/list
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+impl McpPrompt {
+    /// Bind slash-command input to the prompt's arguments
+    ///
+    /// `key=value` words set named arguments; other words fill the remaining
+    /// arguments in order, with any extra words joined onto the last one.
+    pub fn bind_arguments(&self, input: &str) -> Result<HashMap<String, String>, String> {
+        let mut bound = HashMap::new();
+        let mut positional = Vec::new();
+        for word in input.split_whitespace() {
+            match word.split_once('=') {
+                Some((key, value)) if self.arguments.iter().any(|a| a.name == key) => {
+                    bound.insert(key.to_string(), value.to_string());
+                }
+                _ => positional.push(word),
+            }
+        }
+
+        let open: Vec<&str> = self
+            .arguments
+            .iter()
+            .map(|a| a.name.as_str())
+            .filter(|name| !bound.contains_key(*name))
+            .collect();
+        if !positional.is_empty() && open.is_empty() {
+            return Err(format!("Prompt {} takes no more arguments", self.name));
+        }
+        for (i, name) in open.iter().enumerate() {
+            if i + 1 == open.len() && positional.len() > i {
+                bound.insert(name.to_string(), positional[i..].join(" "));
+            } else if let Some(word) = positional.get(i) {
+                bound.insert(name.to_string(), word.to_string());
+            }
+        }
+
+        match self
+            .arguments
+            .iter()
+            .find(|a| a.required && !bound.contains_key(&a.name))
+        {
+            Some(missing) => Err(format!("Missing argument: {}", missing.name)),
+            None => Ok(bound),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpPromptArgument {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Message in a prompts/get result
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct McpPromptMessage {
+    pub role: String,
+    /// Text, image or embedded resource content block
+    pub content: Value,
+}
+
+impl McpPromptMessage {
+    /// Text of the message; embedded resources contribute their text
+    pub fn text(&self) -> String {
+        match self.content.get("type").and_then(|t| t.as_str()) {
+            Some("text") => self.content["text"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string(),
+            Some("resource") => {
+                let resource = &self.content["resource"];
+                resource["text"]
+                    .as_str()
+                    .or_else(|| resource["uri"].as_str())
+                    .unwrap_or_default()
+                    .to_string()
+            }
+            Some(other) => format!("[{}]", other),
+            None => String::new(),
+        }
+    }
+}
+
+/// Prompts get response
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PromptGetResult {
+    #[serde(default)]
+    pub description: Option<String>,
+    pub messages: Vec<McpPromptMessage>,
+}
+
+impl PromptGetResult {
+    /// Flatten the prompt into a single user message
+    pub fn to_text(&self) -> String {
+        self.messages
+            .iter()
+            .map(McpPromptMessage::text)
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// Progress notification params
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressParams {
+    pub progress_token: Value,
+    pub progress: f64,
+    #[serde(default)]
+    pub total: Option<f64>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+impl ProgressParams {
+    /// One-line description for tool output streams
+    pub fn describe(&self) -> String {
+        let amount = match self.total {
+            Some(total) if total > 0.0 => format!("{}/{}", self.progress, total),
+            _ => self.progress.to_string(),
+        };
+        match &self.message {
+            Some(message) => format!("[{}] {}", amount, message),
+            None => format!("[{}]", amount),
+        }
+    }
+}
+
+/// Format resource contents as context for the model
+pub fn format_resource_contents(contents: &[McpResourceContents]) -> String {
+    let mut formatted = String::new();
+    for item in contents {
+        if !formatted.is_empty() {
+            formatted.push_str("\n\n");
+        }
+        match (&item.text, &item.blob) {
+            (Some(text), _) => formatted.push_str(text),
+            (None, Some(blob)) => formatted.push_str(&format!(
+                "[Binary resource {} ({}, {} bytes base64)]",
+                item.uri,
+                item.mime_type
+                    .as_deref()
+                    .unwrap_or("application/octet-stream"),
+                blob.len()
+            )),
+            (None, None) => formatted.push_str(&format!("[Empty resource {}]", item.uri)),
+        }
+    }
+    formatted
+}
+
 /// Format MCP tool result for display
 pub fn format_mcp_result(result: &McpToolResult) -> String {
     let mut formatted = String::new();
@@ -212,3 +422,49 @@ pub fn format_mcp_result(result: &McpToolResult) -> String {
     }
     formatted
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompt(args: &[(&str, bool)]) -> McpPrompt {
+        McpPrompt {
+            name: "review".to_string(),
+            description: None,
+            arguments: args
+                .iter()
+                .map(|(name, required)| McpPromptArgument {
+                    name: name.to_string(),
+                    description: None,
+                    required: *required,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn bind_arguments_fills_in_order_and_joins_the_rest() {
+        let prompt = prompt(&[("pr", true), ("focus", false)]);
+        let bound = prompt.bind_arguments("42 error handling").unwrap();
+        assert_eq!(bound["pr"], "42");
+        assert_eq!(bound["focus"], "error handling");
+    }
+
+    #[test]
+    fn bind_arguments_accepts_named_values() {
+        let prompt = prompt(&[("pr", true), ("focus", false)]);
+        let bound = prompt.bind_arguments("focus=tests 7").unwrap();
+        assert_eq!(bound["pr"], "7");
+        assert_eq!(bound["focus"], "tests");
+    }
+
+    #[test]
+    fn bind_arguments_reports_missing_required() {
+        let prompt = prompt(&[("pr", true)]);
+        assert_eq!(
+            prompt.bind_arguments("").unwrap_err(),
+            "Missing argument: pr"
+        );
+        assert!(self::prompt(&[]).bind_arguments("extra").is_err());
+    }
+}
@@ -2,17 +2,22 @@
 //!
 //! Wraps MCP tools as our Tool trait for seamless integration.
 //!
+//! Progress notifications from the server stream into the tool's output, and
+//! `spawn_tool_sync` keeps the registry current when a server's tools change.
+//!
 //! NOTE: MCP tools execute on external servers and bypass Krusty's sandbox.
 //! When sandbox_root is configured, a warning is logged for visibility.
 
 use async_trait::async_trait;
 use serde_json::{json, Value};
 use std::sync::Arc;
-use tracing::warn;
+use tokio::sync::{broadcast, mpsc};
+use tracing::{info, warn};
 
-use super::manager::McpManager;
-use super::protocol::{format_mcp_result, McpToolDef};
-use crate::tools::registry::{Tool, ToolContext, ToolResult};
+use super::manager::{McpEvent, McpManager};
+use super::protocol::{format_mcp_result, McpToolDef, ProgressParams};
+use crate::tools::registry::{Tool, ToolContext, ToolOutputChunk, ToolResult};
+use crate::tools::ToolRegistry;
 
 fn sanitize_schema(schema: &Value) -> Value {
     let mut normalized = match schema {
@@ -134,11 +139,36 @@ impl Tool for McpTool {
             );
         }
 
-        match self
+        // Stream progress notifications as tool output lines
+        let (progress_tx, forwarder) = match (&ctx.output_tx, &ctx.tool_use_id) {
+            (Some(output_tx), Some(tool_use_id)) => {
+                let (tx, mut rx) = mpsc::unbounded_channel::<ProgressParams>();
+                let output_tx = output_tx.clone();
+                let tool_use_id = tool_use_id.clone();
+                let forwarder = tokio::spawn(async move {
+                    while let Some(progress) = rx.recv().await {
+                        let _ = output_tx.send(ToolOutputChunk {
+                            tool_use_id: tool_use_id.clone(),
+                            chunk: format!("{}\n", progress.describe()),
+                            is_complete: false,
+                            exit_code: None,
+                        });
+                    }
+                });
+                (Some(tx), Some(forwarder))
+            }
+            _ => (None, None),
+        };
+
+        let result = self
             .manager
-            .call_tool(&self.server_name, &self.tool_name, params)
-            .await
-        {
+            .call_tool(&self.server_name, &self.tool_name, params, progress_tx)
+            .await;
+        if let Some(forwarder) = forwarder {
+            let _ = forwarder.await;
+        }
+
+        match result {
             Ok(result) => {
                 let output = format_mcp_result(&result);
                 let metadata = Some(json!({
@@ -184,7 +214,7 @@ impl Tool for McpTool {
 }
 
 /// Register all MCP tools from connected servers
-pub async fn register_mcp_tools(manager: Arc<McpManager>, registry: &crate::tools::ToolRegistry) {
+pub async fn register_mcp_tools(manager: Arc<McpManager>, registry: &ToolRegistry) {
     let tools = manager.get_all_tools().await;
 
     for (server_name, tool_def) in tools {
@@ -193,6 +223,25 @@ pub async fn register_mcp_tools(manager: Arc<McpManager>, registry: &crate::tool
     }
 }
 
+/// Re-register MCP tools whenever a server reports `tools/list_changed`
+pub fn spawn_tool_sync(manager: Arc<McpManager>, registry: Arc<ToolRegistry>) {
+    let mut events = manager.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(McpEvent::ToolsChanged { server }) => {
+                    info!("MCP server {} changed its tools, re-registering", server);
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+            registry.unregister_by_prefix("mcp__").await;
+            register_mcp_tools(manager.clone(), &registry).await;
+        }
+    });
+}
+
 #[cfg(test)]
 mod tests {
     use super::sanitize_schema;
@@ -1,7 +1,7 @@
-//! MCP stdio transport
+//! MCP transports
 //!
-//! Simple stdio transport for local MCP servers. Uses newline-delimited JSON.
-//! Each message is a JSON object followed by a newline.
+//! Local servers use stdio with newline-delimited JSON: each message is a
+//! JSON object followed by a newline. Remote servers use HTTP (see `http`).
 
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
@@ -11,6 +11,54 @@ use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, ChildStdin, ChildStdout, Command};
 use tokio::sync::Mutex;
 
+use super::http::HttpTransport;
+
+/// Transport to one MCP server
+pub enum Transport {
+    Stdio(Box<StdioTransport>),
+    Http(HttpTransport),
+}
+
+impl Transport {
+    /// Send a JSON-RPC message
+    pub async fn send(&self, message: &str) -> Result<()> {
+        match self {
+            Transport::Stdio(t) => t.send(message).await,
+            Transport::Http(t) => t.send(message).await,
+        }
+    }
+
+    /// Receive the next JSON-RPC message
+    pub async fn receive(&self) -> Result<String> {
+        match self {
+            Transport::Stdio(t) => t.receive().await,
+            Transport::Http(t) => t.receive().await,
+        }
+    }
+
+    /// Check if the server is still reachable
+    pub async fn is_alive(&self) -> bool {
+        match self {
+            Transport::Stdio(t) => t.is_alive().await,
+            Transport::Http(t) => t.is_alive(),
+        }
+    }
+
+    /// Called once `initialize` has completed
+    pub fn on_initialized(&self, protocol_version: &str) {
+        if let Transport::Http(t) = self {
+            t.on_initialized(protocol_version);
+        }
+    }
+
+    /// Drop an expired remote session before re-initializing
+    pub fn reset_session(&self) {
+        if let Transport::Http(t) = self {
+            t.reset_session();
+        }
+    }
+}
+
 /// Stdio transport for MCP servers
 pub struct StdioTransport {
     stdin: Mutex<ChildStdin>,
@@ -199,7 +199,7 @@ impl PlanManager {
         }
 
         // Sort by creation date, newest first
-        plans.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        plans.sort_by_key(|p| std::cmp::Reverse(p.created_at));
 
         Ok(plans)
     }
@@ -0,0 +1,640 @@
+//! Server accounts, API tokens and device pairing
+//!
+//! Passwords are stored as Argon2 hashes in the `users` table. Bearer tokens
+//! and browser sessions share `auth_tokens`; only the SHA-256 of a token
+//! secret is stored, so a leaked database does not leak usable tokens.
+
+use anyhow::{anyhow, Result};
+use argon2::password_hash::{rand_core::OsRng, PasswordHash, SaltString};
+use argon2::{Argon2, PasswordHasher, PasswordVerifier};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand::{Rng, RngCore};
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::database::Database;
+
+/// Prefix of every token secret, so leaked tokens are easy to spot
+const TOKEN_PREFIX: &str = "krusty_";
+/// Pairing code alphabet (no 0/O or 1/I to avoid misreading)
+const PAIRING_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+const PAIRING_CODE_LEN: usize = 8;
+/// Shortest password accepted for a login
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+/// What a token is allowed to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenScope {
+    /// Read-only API access (GET requests)
+    Read,
+    /// Mutating API access: chat, tools, file writes
+    Write,
+    /// Interactive terminal (PTY websocket)
+    Terminal,
+    /// Manage tokens and pair devices
+    Admin,
+}
+
+impl TokenScope {
+    pub const ALL: [TokenScope; 4] = [Self::Read, Self::Write, Self::Terminal, Self::Admin];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Terminal => "terminal",
+            Self::Admin => "admin",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "read" => Some(Self::Read),
+            "write" => Some(Self::Write),
+            "terminal" => Some(Self::Terminal),
+            "admin" => Some(Self::Admin),
+            _ => None,
+        }
+    }
+}
+
+fn scopes_to_string(scopes: &[TokenScope]) -> String {
+    scopes
+        .iter()
+        .map(TokenScope::as_str)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn scopes_from_string(s: &str) -> Vec<TokenScope> {
+    s.split(',').filter_map(TokenScope::parse).collect()
+}
+
+/// How a token is presented
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenKind {
+    /// `Authorization: Bearer` API token
+    Api,
+    /// Browser session cookie (PWA login or device pairing)
+    Session,
+}
+
+impl TokenKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Api => "api",
+            Self::Session => "session",
+        }
+    }
+}
+
+/// A user that can sign in to the server
+#[derive(Debug, Clone, Serialize)]
+pub struct UserAccount {
+    pub id: String,
+    pub email: String,
+    pub display_name: Option<String>,
+    pub created_at: String,
+}
+
+/// Token metadata (the secret is only returned once, at creation)
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthToken {
+    pub id: String,
+    pub user_id: String,
+    pub kind: TokenKind,
+    pub name: Option<String>,
+    pub scopes: Vec<TokenScope>,
+    pub created_at: String,
+    pub expires_at: Option<String>,
+    pub last_used_at: Option<String>,
+}
+
+impl AuthToken {
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+fn token_from_row(row: &Row<'_>) -> rusqlite::Result<AuthToken> {
+    let kind: String = row.get(2)?;
+    let scopes: String = row.get(4)?;
+    Ok(AuthToken {
+        id: row.get(0)?,
+        user_id: row.get(1)?,
+        kind: if kind == "session" {
+            TokenKind::Session
+        } else {
+            TokenKind::Api
+        },
+        name: row.get(3)?,
+        scopes: scopes_from_string(&scopes),
+        created_at: row.get(5)?,
+        expires_at: row.get(6)?,
+        last_used_at: row.get(7)?,
+    })
+}
+
+fn user_from_row(row: &Row<'_>) -> rusqlite::Result<UserAccount> {
+    Ok(UserAccount {
+        id: row.get(0)?,
+        email: row.get(1)?,
+        display_name: row.get(2)?,
+        created_at: row.get(3)?,
+    })
+}
+
+fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn is_expired(expires_at: Option<&str>) -> bool {
+    expires_at
+        .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+        .is_some_and(|t| t < Utc::now())
+}
+
+fn normalize_pairing_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+/// SQLite-backed account store
+pub struct AccountStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> AccountStore<'a> {
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Number of users that have a password set
+    pub fn login_count(&self) -> Result<usize> {
+        let count: i64 = self.db.conn().query_row(
+            "SELECT COUNT(*) FROM users WHERE password_hash IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        Ok(count as usize)
+    }
+
+    /// Create a user with a password
+    pub fn create_user(
+        &self,
+        email: &str,
+        password: &str,
+        display_name: Option<&str>,
+    ) -> Result<UserAccount> {
+        let email = email.trim().to_ascii_lowercase();
+        if !email.contains('@') {
+            return Err(anyhow!("Invalid email address"));
+        }
+        check_password(password)?;
+        let password_hash = hash_password(password)?;
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+
+        self.db.conn().execute(
+            "INSERT INTO users (id, email, display_name, password_hash, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            params![id, email, display_name, password_hash, now],
+        )?;
+
+        Ok(UserAccount {
+            id,
+            email,
+            display_name: display_name.map(String::from),
+            created_at: now,
+        })
+    }
+
+    /// Users that have a password set, oldest first
+    pub fn list_logins(&self) -> Result<Vec<UserAccount>> {
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, email, display_name, created_at FROM users
+             WHERE password_hash IS NOT NULL ORDER BY created_at",
+        )?;
+        let users = stmt
+            .query_map([], user_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(users)
+    }
+
+    /// Get a user by id
+    pub fn get_user(&self, user_id: &str) -> Result<Option<UserAccount>> {
+        let user = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT id, email, display_name, created_at FROM users WHERE id = ?1",
+                [user_id],
+                user_from_row,
+            )
+            .optional()?;
+        Ok(user)
+    }
+
+    /// Check an email and password, returning the user on success
+    pub fn verify_password(&self, email: &str, password: &str) -> Result<Option<UserAccount>> {
+        let email = email.trim().to_ascii_lowercase();
+        let row = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT id, email, display_name, created_at, password_hash
+                 FROM users WHERE email = ?1 AND password_hash IS NOT NULL",
+                [&email],
+                |row| Ok((user_from_row(row)?, row.get::<_, String>(4)?)),
+            )
+            .optional()?;
+
+        let Some((user, hash)) = row else {
+            // Hash anyway so unknown emails take as long as wrong passwords
+            let _ = hash_password(password);
+            return Ok(None);
+        };
+
+        let parsed =
+            PasswordHash::new(&hash).map_err(|e| anyhow!("Invalid password hash: {}", e))?;
+        if Argon2::default()
+            .verify_password(password.as_bytes(), &parsed)
+            .is_err()
+        {
+            return Ok(None);
+        }
+
+        self.db.conn().execute(
+            "UPDATE users SET last_login_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), user.id],
+        )?;
+        Ok(Some(user))
+    }
+
+    /// Replace a user's password
+    pub fn set_password(&self, user_id: &str, password: &str) -> Result<bool> {
+        check_password(password)?;
+        let password_hash = hash_password(password)?;
+        let rows = self.db.conn().execute(
+            "UPDATE users SET password_hash = ?1, updated_at = ?2 WHERE id = ?3",
+            params![password_hash, Utc::now().to_rfc3339(), user_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Issue a token, returning its metadata and the secret (shown once)
+    pub fn create_token(
+        &self,
+        user_id: &str,
+        kind: TokenKind,
+        name: Option<&str>,
+        scopes: &[TokenScope],
+        ttl: Option<Duration>,
+    ) -> Result<(AuthToken, String)> {
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let secret = format!("{}{}", TOKEN_PREFIX, URL_SAFE_NO_PAD.encode(bytes));
+
+        let token = AuthToken {
+            id: uuid::Uuid::new_v4().to_string(),
+            user_id: user_id.to_string(),
+            kind,
+            name: name.map(String::from),
+            scopes: scopes.to_vec(),
+            created_at: Utc::now().to_rfc3339(),
+            expires_at: ttl.map(|ttl| (Utc::now() + ttl).to_rfc3339()),
+            last_used_at: None,
+        };
+
+        self.db.conn().execute(
+            "INSERT INTO auth_tokens
+                (id, user_id, kind, name, token_hash, scopes, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                token.id,
+                token.user_id,
+                kind.as_str(),
+                token.name,
+                hash_secret(&secret),
+                scopes_to_string(scopes),
+                token.created_at,
+                token.expires_at
+            ],
+        )?;
+
+        Ok((token, secret))
+    }
+
+    /// Look up a token secret of the given kind
+    ///
+    /// Expired tokens are deleted and rejected. Updates `last_used_at`.
+    pub fn authenticate(&self, secret: &str, kind: TokenKind) -> Result<Option<AuthToken>> {
+        if !secret.starts_with(TOKEN_PREFIX) {
+            return Ok(None);
+        }
+        let conn = self.db.conn();
+        let token = conn
+            .query_row(
+                "SELECT id, user_id, kind, name, scopes, created_at, expires_at, last_used_at
+                 FROM auth_tokens WHERE token_hash = ?1 AND kind = ?2",
+                params![hash_secret(secret), kind.as_str()],
+                token_from_row,
+            )
+            .optional()?;
+
+        let Some(token) = token else {
+            return Ok(None);
+        };
+        if is_expired(token.expires_at.as_deref()) {
+            conn.execute("DELETE FROM auth_tokens WHERE id = ?1", [&token.id])?;
+            return Ok(None);
+        }
+
+        conn.execute(
+            "UPDATE auth_tokens SET last_used_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), token.id],
+        )?;
+        Ok(Some(token))
+    }
+
+    /// List a user's tokens, newest first
+    pub fn list_tokens(&self, user_id: &str) -> Result<Vec<AuthToken>> {
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, user_id, kind, name, scopes, created_at, expires_at, last_used_at
+             FROM auth_tokens WHERE user_id = ?1 ORDER BY created_at DESC",
+        )?;
+        let tokens = stmt
+            .query_map([user_id], token_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(tokens)
+    }
+
+    /// Revoke one of a user's tokens by id
+    pub fn revoke_token(&self, user_id: &str, token_id: &str) -> Result<bool> {
+        let rows = self.db.conn().execute(
+            "DELETE FROM auth_tokens WHERE id = ?1 AND user_id = ?2",
+            params![token_id, user_id],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Revoke a token by its secret (logout)
+    pub fn revoke_secret(&self, secret: &str) -> Result<bool> {
+        let rows = self.db.conn().execute(
+            "DELETE FROM auth_tokens WHERE token_hash = ?1",
+            [hash_secret(secret)],
+        )?;
+        Ok(rows > 0)
+    }
+
+    /// Create a single-use pairing code for signing in another device
+    pub fn create_pairing_code(
+        &self,
+        user_id: &str,
+        scopes: &[TokenScope],
+        ttl: Duration,
+    ) -> Result<(String, String)> {
+        let mut rng = rand::thread_rng();
+        let code: String = (0..PAIRING_CODE_LEN)
+            .map(|_| PAIRING_ALPHABET[rng.gen_range(0..PAIRING_ALPHABET.len())] as char)
+            .collect();
+        let now = Utc::now();
+        let expires_at = (now + ttl).to_rfc3339();
+
+        let conn = self.db.conn();
+        conn.execute(
+            "DELETE FROM pairing_codes WHERE expires_at < ?1",
+            [now.to_rfc3339()],
+        )?;
+        conn.execute(
+            "INSERT INTO pairing_codes (code_hash, user_id, scopes, created_at, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                hash_secret(&code),
+                user_id,
+                scopes_to_string(scopes),
+                now.to_rfc3339(),
+                expires_at
+            ],
+        )?;
+
+        Ok((code, expires_at))
+    }
+
+    /// Redeem a pairing code for a session token on the new device
+    pub fn claim_pairing_code(
+        &self,
+        code: &str,
+        device_name: Option<&str>,
+        session_ttl: Duration,
+    ) -> Result<Option<(AuthToken, String)>> {
+        let code_hash = hash_secret(&normalize_pairing_code(code));
+        let pairing = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT user_id, scopes, expires_at FROM pairing_codes WHERE code_hash = ?1",
+                [&code_hash],
+                |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                    ))
+                },
+            )
+            .optional()?;
+
+        // Single use, whether or not it has expired
+        self.db.conn().execute(
+            "DELETE FROM pairing_codes WHERE code_hash = ?1",
+            [&code_hash],
+        )?;
+
+        let Some((user_id, scopes, expires_at)) = pairing else {
+            return Ok(None);
+        };
+        if is_expired(Some(&expires_at)) {
+            return Ok(None);
+        }
+
+        let name = device_name.unwrap_or("Paired device");
+        self.create_token(
+            &user_id,
+            TokenKind::Session,
+            Some(name),
+            &scopes_from_string(&scopes),
+            Some(session_ttl),
+        )
+        .map(Some)
+    }
+}
+
+/// Reject passwords shorter than [`MIN_PASSWORD_LEN`] characters
+fn check_password(password: &str) -> Result<()> {
+    if password.chars().count() < MIN_PASSWORD_LEN {
+        return Err(anyhow!(
+            "Password must be at least {} characters",
+            MIN_PASSWORD_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn hash_password(password: &str) -> Result<String> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| anyhow!("Failed to hash password: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn create_test_db() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db")).unwrap();
+        (db, temp_dir)
+    }
+
+    #[test]
+    fn test_password_login() {
+        let (db, _temp) = create_test_db();
+        let store = AccountStore::new(&db);
+        assert_eq!(store.login_count().unwrap(), 0);
+
+        assert!(store.create_user("a@b.c", "short", None).is_err());
+        let user = store
+            .create_user("Me@Example.com", "correct horse", Some("Me"))
+            .unwrap();
+        assert_eq!(user.email, "me@example.com");
+        assert_eq!(store.login_count().unwrap(), 1);
+
+        assert!(store
+            .verify_password("me@example.com", "wrong password")
+            .unwrap()
+            .is_none());
+        let found = store
+            .verify_password("ME@example.com", "correct horse")
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, user.id);
+    }
+
+    #[test]
+    fn test_password_minimum_length() {
+        let (db, _temp) = create_test_db();
+        let store = AccountStore::new(&db);
+
+        let err = store.create_user("a@b.c", "1234567", None).unwrap_err();
+        assert!(err.to_string().contains("at least 8 characters"));
+        assert_eq!(store.login_count().unwrap(), 0);
+
+        let user = store.create_user("a@b.c", "12345678", None).unwrap();
+        assert!(store.set_password(&user.id, "short").is_err());
+        assert!(store.set_password(&user.id, "longer password").unwrap());
+        assert!(store
+            .verify_password("a@b.c", "longer password")
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_tokens_scopes_and_expiry() {
+        let (db, _temp) = create_test_db();
+        let store = AccountStore::new(&db);
+        let user = store.create_user("a@b.c", "password123", None).unwrap();
+
+        let (token, secret) = store
+            .create_token(
+                &user.id,
+                TokenKind::Api,
+                Some("ci"),
+                &[TokenScope::Read],
+                None,
+            )
+            .unwrap();
+        let found = store
+            .authenticate(&secret, TokenKind::Api)
+            .unwrap()
+            .unwrap();
+        assert_eq!(found.id, token.id);
+        assert!(found.has_scope(TokenScope::Read));
+        assert!(!found.has_scope(TokenScope::Write));
+
+        // Wrong kind and unknown secrets are rejected
+        assert!(store
+            .authenticate(&secret, TokenKind::Session)
+            .unwrap()
+            .is_none());
+        assert!(store
+            .authenticate("krusty_nope", TokenKind::Api)
+            .unwrap()
+            .is_none());
+
+        let (_, expired) = store
+            .create_token(
+                &user.id,
+                TokenKind::Api,
+                None,
+                &TokenScope::ALL,
+                Some(Duration::seconds(-1)),
+            )
+            .unwrap();
+        assert!(store
+            .authenticate(&expired, TokenKind::Api)
+            .unwrap()
+            .is_none());
+        assert_eq!(store.list_tokens(&user.id).unwrap().len(), 1);
+
+        assert!(store.revoke_token(&user.id, &token.id).unwrap());
+        assert!(store
+            .authenticate(&secret, TokenKind::Api)
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_pairing_code_single_use() {
+        let (db, _temp) = create_test_db();
+        let store = AccountStore::new(&db);
+        let user = store.create_user("a@b.c", "password123", None).unwrap();
+
+        let (code, _) = store
+            .create_pairing_code(&user.id, &TokenScope::ALL, Duration::minutes(10))
+            .unwrap();
+        assert_eq!(code.len(), PAIRING_CODE_LEN);
+
+        // Codes are case-insensitive and may be typed with separators
+        let typed = format!("{}-{}", &code[..4], &code[4..]).to_lowercase();
+        let (token, secret) = store
+            .claim_pairing_code(&typed, Some("Phone"), Duration::days(30))
+            .unwrap()
+            .unwrap();
+        assert_eq!(token.kind, TokenKind::Session);
+        assert!(store
+            .authenticate(&secret, TokenKind::Session)
+            .unwrap()
+            .is_some());
+
+        assert!(store
+            .claim_pairing_code(&code, None, Duration::days(30))
+            .unwrap()
+            .is_none());
+    }
+}
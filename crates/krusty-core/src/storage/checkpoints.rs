@@ -0,0 +1,204 @@
+//! File checkpoint storage
+//!
+//! Each agent turn gets a checkpoint; write tools record the contents a file
+//! had before its first modification in that turn. Restoring to turn N
+//! replays the earliest snapshot of every file touched in turns >= N.
+
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use super::database::Database;
+
+/// A checkpoint taken at the start of an agent turn
+#[derive(Debug, Clone, Serialize)]
+pub struct Checkpoint {
+    pub id: i64,
+    pub session_id: String,
+    /// 1-based turn number within the session
+    pub turn: usize,
+    /// Messages in the conversation before this turn's prompt
+    pub message_count: usize,
+    /// Preview of the prompt that started the turn
+    pub prompt: Option<String>,
+    pub created_at: String,
+    /// Files modified during this turn
+    pub files: Vec<String>,
+}
+
+/// Pre-edit contents of one file
+#[derive(Debug, Clone)]
+pub struct FileSnapshot {
+    pub path: String,
+    /// None when the file did not exist before the edit
+    pub content: Option<Vec<u8>>,
+}
+
+const PROMPT_PREVIEW_CHARS: usize = 80;
+
+fn checkpoint_from_row(row: &Row<'_>) -> rusqlite::Result<Checkpoint> {
+    Ok(Checkpoint {
+        id: row.get(0)?,
+        session_id: row.get(1)?,
+        turn: row.get::<_, i64>(2)? as usize,
+        message_count: row.get::<_, i64>(3)? as usize,
+        prompt: row.get(4)?,
+        created_at: row.get(5)?,
+        files: Vec::new(),
+    })
+}
+
+/// SQLite-backed checkpoint store
+pub struct CheckpointStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> CheckpointStore<'a> {
+    /// Create a new checkpoint store with database reference
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Start a checkpoint for the next turn of a session
+    pub fn create(
+        &self,
+        session_id: &str,
+        message_count: usize,
+        prompt: Option<&str>,
+    ) -> Result<Checkpoint> {
+        let conn = self.db.conn();
+        let turn: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(turn), 0) + 1 FROM checkpoints WHERE session_id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        let prompt = prompt.map(|p| {
+            let line = p.lines().next().unwrap_or_default().trim();
+            if line.chars().count() > PROMPT_PREVIEW_CHARS {
+                let head: String = line.chars().take(PROMPT_PREVIEW_CHARS - 3).collect();
+                format!("{}...", head)
+            } else {
+                line.to_string()
+            }
+        });
+        let created_at = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO checkpoints (session_id, turn, message_count, prompt, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![session_id, turn, message_count as i64, prompt, created_at],
+        )?;
+        Ok(Checkpoint {
+            id: conn.last_insert_rowid(),
+            session_id: session_id.to_string(),
+            turn: turn as usize,
+            message_count,
+            prompt,
+            created_at,
+            files: Vec::new(),
+        })
+    }
+
+    /// Record a file's pre-edit contents (first snapshot per turn wins)
+    ///
+    /// Returns whether a snapshot was stored.
+    pub fn record_file(
+        &self,
+        checkpoint_id: i64,
+        path: &str,
+        content: Option<&[u8]>,
+    ) -> Result<bool> {
+        let inserted = self.db.conn().execute(
+            "INSERT OR IGNORE INTO checkpoint_files (checkpoint_id, path, content)
+             VALUES (?1, ?2, ?3)",
+            params![checkpoint_id, path, content],
+        )?;
+        Ok(inserted > 0)
+    }
+
+    /// Whether a file already has a snapshot in this checkpoint
+    pub fn has_file(&self, checkpoint_id: i64, path: &str) -> Result<bool> {
+        let found = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT 1 FROM checkpoint_files WHERE checkpoint_id = ?1 AND path = ?2",
+                params![checkpoint_id, path],
+                |_| Ok(()),
+            )
+            .optional()?;
+        Ok(found.is_some())
+    }
+
+    /// List a session's checkpoints, oldest first
+    pub fn list(&self, session_id: &str) -> Result<Vec<Checkpoint>> {
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(
+            "SELECT id, session_id, turn, message_count, prompt, created_at
+             FROM checkpoints WHERE session_id = ?1 ORDER BY turn",
+        )?;
+        let mut checkpoints = stmt
+            .query_map([session_id], checkpoint_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let mut files_stmt = conn
+            .prepare("SELECT path FROM checkpoint_files WHERE checkpoint_id = ?1 ORDER BY path")?;
+        for checkpoint in &mut checkpoints {
+            checkpoint.files = files_stmt
+                .query_map([checkpoint.id], |row| row.get(0))?
+                .collect::<rusqlite::Result<Vec<String>>>()?;
+        }
+        Ok(checkpoints)
+    }
+
+    /// Get a single checkpoint by turn
+    pub fn get(&self, session_id: &str, turn: usize) -> Result<Option<Checkpoint>> {
+        Ok(self.list(session_id)?.into_iter().find(|c| c.turn == turn))
+    }
+
+    /// Most recent checkpoint that modified at least one file
+    pub fn latest_with_files(&self, session_id: &str) -> Result<Option<Checkpoint>> {
+        Ok(self
+            .list(session_id)?
+            .into_iter()
+            .rev()
+            .find(|c| !c.files.is_empty()))
+    }
+
+    /// Earliest snapshot of every file modified in turns >= `turn`
+    ///
+    /// Writing these back returns the files to their state before `turn`.
+    pub fn snapshots_since(&self, session_id: &str, turn: usize) -> Result<Vec<FileSnapshot>> {
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(
+            "SELECT f.path, f.content
+             FROM checkpoint_files f
+             JOIN checkpoints c ON c.id = f.checkpoint_id
+             WHERE c.session_id = ?1 AND c.turn >= ?2
+               AND c.turn = (
+                   SELECT MIN(c2.turn) FROM checkpoint_files f2
+                   JOIN checkpoints c2 ON c2.id = f2.checkpoint_id
+                   WHERE c2.session_id = ?1 AND c2.turn >= ?2 AND f2.path = f.path
+               )
+             ORDER BY f.path",
+        )?;
+        let snapshots = stmt
+            .query_map(params![session_id, turn as i64], |row| {
+                Ok(FileSnapshot {
+                    path: row.get(0)?,
+                    content: row.get(1)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(snapshots)
+    }
+
+    /// Delete checkpoints for turns >= `turn` (after restoring them)
+    pub fn delete_since(&self, session_id: &str, turn: usize) -> Result<usize> {
+        let deleted = self.db.conn().execute(
+            "DELETE FROM checkpoints WHERE session_id = ?1 AND turn >= ?2",
+            params![session_id, turn as i64],
+        )?;
+        Ok(deleted)
+    }
+}
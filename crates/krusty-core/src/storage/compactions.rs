@@ -0,0 +1,129 @@
+//! Conversation compaction storage
+//!
+//! A compaction replaces a session's oldest messages with a summary when the
+//! conversation is sent to the model. Message rows are never touched; the
+//! indices below refer to positions in the session's stored message order.
+
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use super::database::Database;
+
+/// An in-place compaction of a session's conversation
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Compaction {
+    pub id: i64,
+    pub session_id: String,
+    /// Markdown summary of the replaced messages
+    pub summary: String,
+    /// Messages before this index are replaced by `summary`
+    pub summarized_through: usize,
+    /// Large tool results before this index are elided
+    pub pruned_through: usize,
+    /// Context tokens in use when the compaction was made
+    pub tokens_before: usize,
+    pub created_at: String,
+}
+
+/// SQLite-backed compaction store
+pub struct CompactionStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> CompactionStore<'a> {
+    /// Create a new compaction store with database reference
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Record a compaction; it supersedes any earlier one for the session
+    pub fn record(
+        &self,
+        session_id: &str,
+        summary: &str,
+        summarized_through: usize,
+        pruned_through: usize,
+        tokens_before: usize,
+    ) -> Result<Compaction> {
+        let conn = self.db.conn();
+        let created_at = Utc::now().to_rfc3339();
+        conn.execute(
+            "INSERT INTO session_compactions
+                (session_id, summary, summarized_through, pruned_through, tokens_before, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                session_id,
+                summary,
+                summarized_through as i64,
+                pruned_through as i64,
+                tokens_before as i64,
+                created_at
+            ],
+        )?;
+        Ok(Compaction {
+            id: conn.last_insert_rowid(),
+            session_id: session_id.to_string(),
+            summary: summary.to_string(),
+            summarized_through,
+            pruned_through,
+            tokens_before,
+            created_at,
+        })
+    }
+
+    /// The compaction currently in effect for a session
+    pub fn latest(&self, session_id: &str) -> Result<Option<Compaction>> {
+        let compaction = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT id, session_id, summary, summarized_through, pruned_through,
+                        tokens_before, created_at
+                 FROM session_compactions WHERE session_id = ?1
+                 ORDER BY id DESC LIMIT 1",
+                [session_id],
+                |row| {
+                    Ok(Compaction {
+                        id: row.get(0)?,
+                        session_id: row.get(1)?,
+                        summary: row.get(2)?,
+                        summarized_through: row.get::<_, i64>(3)? as usize,
+                        pruned_through: row.get::<_, i64>(4)? as usize,
+                        tokens_before: row.get::<_, i64>(5)? as usize,
+                        created_at: row.get(6)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(compaction)
+    }
+
+    /// Copy the latest compaction within the first `keep` messages to a fork
+    pub fn copy_within(&self, session_id: &str, fork_id: &str, keep: usize) -> Result<bool> {
+        let copied = self.db.conn().execute(
+            "INSERT INTO session_compactions
+                (session_id, summary, summarized_through, pruned_through, tokens_before, created_at)
+             SELECT ?1, summary, summarized_through, pruned_through, tokens_before, created_at
+             FROM session_compactions
+             WHERE session_id = ?2 AND summarized_through <= ?3 AND pruned_through <= ?3
+             ORDER BY id DESC LIMIT 1",
+            params![fork_id, session_id, keep as i64],
+        )?;
+        Ok(copied > 0)
+    }
+
+    /// Drop compactions that reach past the first `keep` messages
+    ///
+    /// Called when a session is rewound, since their indices no longer
+    /// point at the messages they were made from.
+    pub fn discard_beyond(&self, session_id: &str, keep: usize) -> Result<usize> {
+        let deleted = self.db.conn().execute(
+            "DELETE FROM session_compactions
+             WHERE session_id = ?1 AND (summarized_through > ?2 OR pruned_through > ?2)",
+            params![session_id, keep as i64],
+        )?;
+        Ok(deleted)
+    }
+}
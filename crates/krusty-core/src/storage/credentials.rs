@@ -9,7 +9,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 
-use crate::ai::providers::ProviderId;
+use crate::ai::providers::{get_provider, AuthHeader, ProviderId};
 use crate::auth::{try_refresh_oauth_token_blocking, OAuthTokenStore};
 use crate::paths;
 
@@ -140,6 +140,33 @@ impl CredentialStore {
         self.keys.contains_key(provider.storage_key())
     }
 
+    /// Get the API key for a provider, including user-defined fallbacks
+    ///
+    /// Stored keys win. User-defined providers may instead read the key from
+    /// their `api_key_env` variable, and keyless ones (`auth: none`) resolve
+    /// to an empty key.
+    pub fn get_api_key(&self, provider: &ProviderId) -> Option<String> {
+        if let Some(key) = self.get(provider) {
+            return Some(key.clone());
+        }
+
+        let config = get_provider(*provider)?;
+        if let Some(key) = config
+            .api_key_env
+            .as_deref()
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|key| !key.is_empty())
+        {
+            return Some(key);
+        }
+        (config.auth_header == AuthHeader::None).then(String::new)
+    }
+
+    /// Check if a provider can be used with an API key (stored or implicit)
+    pub fn has_api_key(&self, provider: &ProviderId) -> bool {
+        self.get_api_key(provider).is_some()
+    }
+
     /// Get all providers with stored API keys
     pub fn configured_providers(&self) -> Vec<ProviderId> {
         ProviderId::all()
@@ -160,8 +187,8 @@ impl CredentialStore {
     /// Returns the credential string suitable for use in Authorization headers.
     pub fn get_auth(&self, provider: &ProviderId) -> Option<String> {
         // Try API key first
-        if let Some(key) = self.get(provider) {
-            return Some(key.clone());
+        if let Some(key) = self.get_api_key(provider) {
+            return Some(key);
         }
 
         // Try OAuth token for providers that support it
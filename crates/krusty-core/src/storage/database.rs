@@ -7,7 +7,7 @@ use std::sync::{Arc, Mutex};
 use tracing::info;
 
 /// Current schema version
-const SCHEMA_VERSION: i32 = 16;
+const SCHEMA_VERSION: i32 = 24;
 
 /// Shared database handle for connection reuse
 ///
@@ -617,6 +617,200 @@ impl Database {
             self.set_schema_version_tx(&tx, 16)?;
         }
 
+        if current_version < 17 {
+            info!("Running migration 17: Usage ledger and session budgets");
+            tx.execute_batch(
+                r#"
+                -- One row per model call with token counts and computed cost
+                CREATE TABLE IF NOT EXISTS usage_ledger (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    user_id TEXT,
+                    turn INTEGER NOT NULL,
+                    provider TEXT NOT NULL,
+                    model TEXT NOT NULL,
+                    prompt_tokens INTEGER NOT NULL DEFAULT 0,
+                    completion_tokens INTEGER NOT NULL DEFAULT 0,
+                    cache_read_tokens INTEGER NOT NULL DEFAULT 0,
+                    cache_write_tokens INTEGER NOT NULL DEFAULT 0,
+                    cost_usd REAL,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_usage_ledger_session
+                    ON usage_ledger(session_id);
+                CREATE INDEX IF NOT EXISTS idx_usage_ledger_user
+                    ON usage_ledger(user_id, created_at);
+
+                -- Optional per-session spending limits in USD
+                ALTER TABLE sessions ADD COLUMN budget_soft_usd REAL;
+                ALTER TABLE sessions ADD COLUMN budget_hard_usd REAL;
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 17)?;
+        }
+
+        if current_version < 18 {
+            info!("Running migration 18: Tool permission rules");
+            tx.execute_batch(
+                r#"
+                -- Allow/deny/ask rules; working_dir NULL = applies to all projects
+                CREATE TABLE IF NOT EXISTS permission_rules (
+                    id TEXT PRIMARY KEY,
+                    action TEXT NOT NULL,
+                    tool TEXT NOT NULL,
+                    pattern TEXT,
+                    working_dir TEXT,
+                    user_id TEXT,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_permission_rules_user
+                    ON permission_rules(user_id);
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 18)?;
+        }
+
+        if current_version < 19 {
+            info!("Running migration 19: File checkpoints");
+            tx.execute_batch(
+                r#"
+                -- One checkpoint per agent turn. No FK to sessions: ACP sessions
+                -- checkpoint without a persisted session row.
+                CREATE TABLE IF NOT EXISTS checkpoints (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL,
+                    turn INTEGER NOT NULL,
+                    message_count INTEGER NOT NULL,
+                    prompt TEXT,
+                    created_at TEXT NOT NULL,
+                    UNIQUE(session_id, turn)
+                );
+
+                -- Pre-edit file contents; content NULL = file did not exist
+                CREATE TABLE IF NOT EXISTS checkpoint_files (
+                    checkpoint_id INTEGER NOT NULL,
+                    path TEXT NOT NULL,
+                    content BLOB,
+                    PRIMARY KEY (checkpoint_id, path),
+                    FOREIGN KEY (checkpoint_id) REFERENCES checkpoints(id) ON DELETE CASCADE
+                );
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 19)?;
+        }
+
+        if current_version < 20 {
+            info!("Running migration 20: Server accounts, tokens and device pairing");
+            tx.execute_batch(
+                r#"
+                -- Argon2 PHC string; NULL for users without a login
+                ALTER TABLE users ADD COLUMN password_hash TEXT;
+
+                -- Bearer API tokens and browser session cookies (SHA-256 of secret)
+                CREATE TABLE IF NOT EXISTS auth_tokens (
+                    id TEXT PRIMARY KEY,
+                    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    kind TEXT NOT NULL,
+                    name TEXT,
+                    token_hash TEXT NOT NULL UNIQUE,
+                    scopes TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    expires_at TEXT,
+                    last_used_at TEXT
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_auth_tokens_user ON auth_tokens(user_id);
+
+                -- Short-lived single-use codes for signing in new devices
+                CREATE TABLE IF NOT EXISTS pairing_codes (
+                    code_hash TEXT PRIMARY KEY,
+                    user_id TEXT NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+                    scopes TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    expires_at TEXT NOT NULL
+                );
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 20)?;
+        }
+
+        if current_version < 21 {
+            info!("Running migration 21: In-place conversation compactions");
+            tx.execute_batch(
+                r#"
+                -- Summaries that replace a session's oldest messages when it is
+                -- sent to the model; the messages themselves are kept
+                CREATE TABLE IF NOT EXISTS session_compactions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id TEXT NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+                    summary TEXT NOT NULL,
+                    summarized_through INTEGER NOT NULL,
+                    pruned_through INTEGER NOT NULL,
+                    tokens_before INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_session_compactions_session
+                    ON session_compactions(session_id, id);
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 21)?;
+        }
+
+        if current_version < 22 {
+            info!("Running migration 22: Session forks");
+            tx.execute_batch(
+                r#"
+                -- Number of parent messages a forked session was started with;
+                -- NULL for root sessions and pinch continuations
+                ALTER TABLE sessions ADD COLUMN fork_point INTEGER;
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 22)?;
+        }
+
+        if current_version < 23 {
+            info!("Running migration 23: Message full-text search");
+            tx.execute_batch(
+                r#"
+                -- Searchable text of each message, keyed by messages.id
+                CREATE VIRTUAL TABLE IF NOT EXISTS message_search USING fts5(
+                    text,
+                    tools,
+                    paths,
+                    session_id UNINDEXED,
+                    tokenize = 'porter unicode61'
+                );
+                "#,
+            )?;
+            let indexed = super::search::index_messages(&tx, None)?;
+            info!("Indexed {} existing messages for search", indexed);
+            self.set_schema_version_tx(&tx, 23)?;
+        }
+
+        if current_version < 24 {
+            info!("Running migration 24: Project memories");
+            tx.execute_batch(
+                r#"
+                -- Notes the agent keeps across sessions, per project and user
+                CREATE TABLE IF NOT EXISTS memories (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    working_dir TEXT NOT NULL,
+                    user_id TEXT,
+                    content TEXT NOT NULL,
+                    created_at TEXT NOT NULL,
+                    updated_at TEXT NOT NULL
+                );
+
+                CREATE INDEX IF NOT EXISTS idx_memories_project
+                    ON memories(working_dir, user_id);
+                "#,
+            )?;
+            self.set_schema_version_tx(&tx, 24)?;
+        }
+
         tx.commit()?;
 
         info!("Migrations complete");
@@ -26,7 +26,7 @@ mod tests {
 
         // Database should initialize with schema_version table
         let version = db.get_schema_version();
-        assert_eq!(version, 16, "Expected current schema version to be 16");
+        assert_eq!(version, 24, "Expected current schema version to be 24");
     }
 
     #[test]
@@ -147,7 +147,7 @@ mod tests {
         let version = db.get_schema_version();
 
         // After all migrations, version should be current
-        assert_eq!(version, 16, "Expected final schema version");
+        assert_eq!(version, 24, "Expected final schema version");
     }
 
     #[test]
@@ -332,4 +332,142 @@ mod tests {
         assert!(columns.contains(&"last_failure_reason".to_string()));
         assert!(columns.contains(&"failure_count".to_string()));
     }
+
+    #[test]
+    fn test_usage_ledger_totals() {
+        use crate::ai::types::Usage;
+        use crate::storage::{SessionManager, UsageEntry, UsageLedger};
+
+        let (db, _temp) = create_test_db();
+        let sessions = SessionManager::new(db);
+        let session_id = sessions
+            .create_session("Usage", Some("claude-opus-4-6"), None)
+            .expect("Failed to create session");
+        let ledger = UsageLedger::new(sessions.db());
+
+        let usage = Usage {
+            prompt_tokens: 1000,
+            completion_tokens: 200,
+            total_tokens: 1200,
+            cache_creation_input_tokens: 0,
+            cache_read_input_tokens: 800,
+        };
+        for (model, cost_usd) in [("opus", Some(0.5)), ("opus", Some(0.25)), ("local", None)] {
+            ledger
+                .record(&UsageEntry {
+                    session_id: &session_id,
+                    user_id: None,
+                    turn: 1,
+                    provider: "anthropic",
+                    model,
+                    usage: &usage,
+                    cost_usd,
+                })
+                .expect("Failed to record usage");
+        }
+
+        let totals = ledger.session_totals(&session_id).unwrap();
+        assert_eq!(totals.calls, 3);
+        assert_eq!(totals.prompt_tokens, 3000);
+        assert_eq!(totals.cache_read_tokens, 2400);
+        assert_eq!(totals.unpriced_calls, 1);
+        assert!((totals.cost_usd - 0.75).abs() < 1e-9);
+
+        let by_model = ledger.session_usage_by_model(&session_id).unwrap();
+        assert_eq!(by_model.len(), 2);
+        assert_eq!(by_model[0].model, "opus");
+        assert_eq!(by_model[0].totals.calls, 2);
+
+        assert_eq!(ledger.user_totals(None, None).unwrap().calls, 3);
+        assert_eq!(ledger.user_totals(Some("other"), None).unwrap().calls, 0);
+    }
+
+    #[test]
+    fn test_session_budget_roundtrip() {
+        use crate::storage::{BudgetStatus, SessionBudget, SessionManager, UsageLedger};
+
+        let (db, _temp) = create_test_db();
+        let sessions = SessionManager::new(db);
+        let session_id = sessions
+            .create_session("Budget", None, None)
+            .expect("Failed to create session");
+        let ledger = UsageLedger::new(sessions.db());
+
+        assert!(ledger.get_budget(&session_id).unwrap().is_empty());
+
+        let budget = SessionBudget {
+            soft_usd: Some(1.0),
+            hard_usd: Some(2.0),
+        };
+        ledger.set_budget(&session_id, &budget).unwrap();
+        assert_eq!(ledger.get_budget(&session_id).unwrap(), budget);
+        assert!(ledger.set_budget("missing", &budget).is_err());
+
+        assert_eq!(budget.check(0.5), BudgetStatus::Within);
+        assert_eq!(
+            budget.check(1.5),
+            BudgetStatus::SoftExceeded { limit_usd: 1.0 }
+        );
+        assert_eq!(
+            budget.check(2.0),
+            BudgetStatus::HardExceeded { limit_usd: 2.0 }
+        );
+    }
+
+    #[test]
+    fn test_session_compactions_roundtrip() {
+        use crate::storage::{CompactionStore, SessionManager};
+
+        let (db, _temp) = create_test_db();
+        let sessions = SessionManager::new(db);
+        let session_id = sessions
+            .create_session("Compaction", None, None)
+            .expect("Failed to create session");
+        let store = CompactionStore::new(sessions.db());
+
+        assert!(store.latest(&session_id).unwrap().is_none());
+        store.record(&session_id, "first", 4, 6, 90_000).unwrap();
+        let latest = store
+            .record(&session_id, "second", 10, 14, 150_000)
+            .unwrap();
+        assert_eq!(store.latest(&session_id).unwrap(), Some(latest));
+
+        // Rewinding past the newest compaction falls back to the older one
+        sessions.truncate_messages(&session_id, 12).unwrap();
+        let latest = store.latest(&session_id).unwrap().unwrap();
+        assert_eq!(latest.summary, "first");
+        assert_eq!(latest.summarized_through, 4);
+        assert_eq!(latest.pruned_through, 6);
+
+        sessions.delete_session(&session_id).unwrap();
+        assert!(store.latest(&session_id).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_delete_session_clears_checkpoints() {
+        use crate::storage::{CheckpointStore, SessionManager};
+
+        let (db, _temp) = create_test_db();
+        let sessions = SessionManager::new(db);
+        let session_id = sessions
+            .create_session("Checkpoints", None, None)
+            .expect("Failed to create session");
+        let store = CheckpointStore::new(sessions.db());
+        let checkpoint = store.create(&session_id, 0, Some("edit")).unwrap();
+        store
+            .record_file(checkpoint.id, "/work/src/lib.rs", Some(b"v1"))
+            .unwrap();
+
+        // Checkpoints have no FK to sessions, so deletion must clear them
+        sessions.delete_session(&session_id).unwrap();
+        assert!(store.list(&session_id).unwrap().is_empty());
+        let files: i64 = sessions
+            .db()
+            .conn()
+            .query_row("SELECT COUNT(*) FROM checkpoint_files", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(files, 0);
+    }
 }
@@ -0,0 +1,817 @@
+//! Session export and import
+//!
+//! A session is exported as a [`SessionBundle`]: its metadata, every message
+//! (tool calls, tool results and thinking included), its plan and its file
+//! activity. The bundle serializes to self-contained JSON that can be
+//! imported on another machine, and renders to a Markdown or HTML transcript.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::Utc;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::database::Database;
+use super::plans::PlanStore;
+use super::sessions::{SessionManager, WorkMode};
+use crate::ai::types::Content;
+use crate::plan::{PlanFile, PlanStatus};
+
+/// Identifies a Krusty session bundle
+pub const BUNDLE_FORMAT: &str = "krusty-session";
+
+/// Current bundle version; newer bundles are rejected on import
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// Tool output longer than this is shortened in transcripts
+const TRANSCRIPT_OUTPUT_LIMIT: usize = 4000;
+
+/// Export formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Re-importable JSON bundle
+    Json,
+    /// Readable Markdown transcript
+    Markdown,
+    /// Static HTML page
+    Html,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Html => "html",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "application/json",
+            ExportFormat::Markdown => "text/markdown; charset=utf-8",
+            ExportFormat::Html => "text/html; charset=utf-8",
+        }
+    }
+
+    /// Render a bundle in this format
+    pub fn render(&self, bundle: &SessionBundle) -> Result<String> {
+        match self {
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(bundle)?),
+            ExportFormat::Markdown => Ok(render_markdown(bundle)),
+            ExportFormat::Html => Ok(render_html(bundle)),
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "md" | "markdown" => Ok(ExportFormat::Markdown),
+            "html" => Ok(ExportFormat::Html),
+            _ => Err(format!(
+                "Unknown export format '{}' (expected json, markdown or html)",
+                s
+            )),
+        }
+    }
+}
+
+/// A session with everything needed to recreate it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionBundle {
+    pub format: String,
+    pub version: u32,
+    pub exported_at: String,
+    pub session: BundleSession,
+    pub messages: Vec<BundleMessage>,
+    #[serde(default)]
+    pub plan: Option<BundlePlan>,
+    #[serde(default)]
+    pub file_activity: Vec<BundleFileActivity>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleSession {
+    pub title: String,
+    pub created_at: String,
+    pub updated_at: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+    #[serde(default)]
+    pub work_mode: WorkMode,
+    #[serde(default)]
+    pub target_branch: Option<String>,
+    #[serde(default)]
+    pub token_count: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleMessage {
+    pub role: String,
+    /// Content blocks as stored (normally a `Vec<Content>` array)
+    pub content: Value,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundlePlan {
+    pub status: String,
+    /// Plan in its markdown file format
+    pub markdown: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleFileActivity {
+    pub file_path: String,
+    pub read_count: usize,
+    pub write_count: usize,
+    pub edit_count: usize,
+    pub last_accessed: String,
+    pub user_referenced: bool,
+}
+
+/// Export a session as a bundle
+pub fn export_session(db: &Database, session_id: &str) -> Result<SessionBundle> {
+    let conn = db.conn();
+    let session = conn
+        .query_row(
+            "SELECT title, created_at, updated_at, model, working_dir, work_mode,
+                    target_branch, token_count
+             FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| {
+                let work_mode: String = row.get(5)?;
+                Ok(BundleSession {
+                    title: row.get(0)?,
+                    created_at: row.get(1)?,
+                    updated_at: row.get(2)?,
+                    model: row.get(3)?,
+                    working_dir: row.get(4)?,
+                    work_mode: work_mode.parse().unwrap_or_default(),
+                    target_branch: row.get(6)?,
+                    token_count: row.get::<_, Option<i64>>(7)?.map(|c| c as usize),
+                })
+            },
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => anyhow!("Session {} not found", session_id),
+            e => e.into(),
+        })?;
+
+    let mut stmt = conn.prepare(
+        "SELECT role, content, created_at FROM messages WHERE session_id = ?1 ORDER BY id",
+    )?;
+    let messages = stmt
+        .query_map([session_id], |row| {
+            let content: String = row.get(1)?;
+            Ok(BundleMessage {
+                role: row.get(0)?,
+                // Legacy plain-text rows are kept as JSON strings
+                content: serde_json::from_str(&content).unwrap_or(Value::String(content)),
+                created_at: row.get(2)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    let plan = PlanStore::new(db)
+        .get_plan_for_session(session_id)?
+        .map(|plan| BundlePlan {
+            status: plan.status.to_string(),
+            markdown: plan.to_markdown(),
+        });
+
+    let mut stmt = conn.prepare(
+        "SELECT file_path, read_count, write_count, edit_count, last_accessed, user_referenced
+         FROM file_activity WHERE session_id = ?1 ORDER BY file_path",
+    )?;
+    let file_activity = stmt
+        .query_map([session_id], |row| {
+            Ok(BundleFileActivity {
+                file_path: row.get(0)?,
+                read_count: row.get::<_, i64>(1)? as usize,
+                write_count: row.get::<_, i64>(2)? as usize,
+                edit_count: row.get::<_, i64>(3)? as usize,
+                last_accessed: row.get(4)?,
+                user_referenced: row.get::<_, i64>(5)? != 0,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+
+    Ok(SessionBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at: Utc::now().to_rfc3339(),
+        session,
+        messages,
+        plan,
+        file_activity,
+    })
+}
+
+/// Parse and validate a JSON bundle
+pub fn parse_bundle(json: &str) -> Result<SessionBundle> {
+    let bundle: SessionBundle =
+        serde_json::from_str(json).map_err(|e| anyhow!("Invalid session bundle: {}", e))?;
+    if bundle.format != BUNDLE_FORMAT {
+        bail!("Not a Krusty session bundle (format '{}')", bundle.format);
+    }
+    if bundle.version > BUNDLE_VERSION {
+        bail!(
+            "Session bundle version {} is newer than supported ({}); update Krusty",
+            bundle.version,
+            BUNDLE_VERSION
+        );
+    }
+    Ok(bundle)
+}
+
+/// Import a bundle as a new session and return its ID
+///
+/// `working_dir` replaces the exported directory (paths rarely match across
+/// machines); `user_id` sets the owner in multi-tenant mode.
+pub fn import_session(
+    db: &Database,
+    bundle: &SessionBundle,
+    working_dir: Option<&str>,
+    user_id: Option<&str>,
+) -> Result<String> {
+    let id = uuid::Uuid::new_v4().to_string();
+    let session = &bundle.session;
+    let working_dir = working_dir.or(session.working_dir.as_deref());
+
+    let tx = db.conn().unchecked_transaction()?;
+    tx.execute(
+        "INSERT INTO sessions (id, title, created_at, updated_at, model, working_dir, user_id,
+                               work_mode, target_branch, token_count)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            id,
+            session.title,
+            session.created_at,
+            session.updated_at,
+            session.model,
+            working_dir,
+            user_id,
+            session.work_mode.to_string(),
+            session.target_branch,
+            session.token_count.map(|c| c as i64),
+        ],
+    )?;
+
+    for message in &bundle.messages {
+        let content = match &message.content {
+            Value::String(text) => text.clone(),
+            content => content.to_string(),
+        };
+        tx.execute(
+            "INSERT INTO messages (session_id, role, content, created_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![id, message.role, content, message.created_at],
+        )?;
+    }
+
+    if let Some(plan) = &bundle.plan {
+        let mut plan_file = PlanFile::from_markdown(&plan.markdown)
+            .map_err(|e| anyhow!("Invalid plan in bundle: {}", e))?;
+        if let Ok(status) = plan.status.parse::<PlanStatus>() {
+            plan_file.status = status;
+        }
+        PlanStore::new(db).upsert_plan(&id, &plan_file)?;
+    }
+
+    for activity in &bundle.file_activity {
+        tx.execute(
+            "INSERT OR REPLACE INTO file_activity
+                (session_id, file_path, read_count, write_count, edit_count, last_accessed,
+                 user_referenced)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                activity.file_path,
+                activity.read_count as i64,
+                activity.write_count as i64,
+                activity.edit_count as i64,
+                activity.last_accessed,
+                activity.user_referenced as i64,
+            ],
+        )?;
+    }
+
+    super::search::MessageSearch::new(db).index_session(&id)?;
+    tx.commit()?;
+
+    tracing::info!(
+        session_id = %id,
+        messages = bundle.messages.len(),
+        "Session imported"
+    );
+    Ok(id)
+}
+
+impl SessionManager {
+    /// Export a session as a bundle
+    pub fn export_session(&self, session_id: &str) -> Result<SessionBundle> {
+        export_session(self.db(), session_id)
+    }
+
+    /// Import a bundle as a new session
+    pub fn import_session(
+        &self,
+        bundle: &SessionBundle,
+        working_dir: Option<&str>,
+        user_id: Option<&str>,
+    ) -> Result<String> {
+        import_session(self.db(), bundle, working_dir, user_id)
+    }
+}
+
+// =============================================================================
+// Transcripts
+// =============================================================================
+
+/// One rendered step of a transcript
+enum Entry {
+    Text(String),
+    Thinking(String),
+    ToolCall {
+        name: String,
+        input: Value,
+        result: Option<(String, bool)>,
+    },
+    Attachment(&'static str),
+}
+
+/// A message reduced to what a transcript shows
+struct Turn<'a> {
+    role: &'a str,
+    created_at: &'a str,
+    entries: Vec<Entry>,
+}
+
+/// Group messages into transcript turns, pairing tool calls with results
+///
+/// Messages holding only tool results are folded into the calls they answer.
+fn transcript_turns(bundle: &SessionBundle) -> Vec<Turn<'_>> {
+    let parsed: Vec<Vec<Content>> = bundle
+        .messages
+        .iter()
+        .map(|m| match &m.content {
+            Value::String(text) => vec![Content::Text { text: text.clone() }],
+            content => serde_json::from_value(content.clone()).unwrap_or_default(),
+        })
+        .collect();
+
+    let mut results: HashMap<String, (String, bool)> = HashMap::new();
+    for content in parsed.iter().flatten() {
+        if let Content::ToolResult {
+            tool_use_id,
+            output,
+            is_error,
+        } = content
+        {
+            let output = match output {
+                Value::String(s) => s.clone(),
+                other => pretty_json(other),
+            };
+            results.insert(tool_use_id.clone(), (output, is_error.unwrap_or(false)));
+        }
+    }
+
+    let mut turns = Vec::new();
+    for (message, content) in bundle.messages.iter().zip(parsed) {
+        let entries: Vec<Entry> = content
+            .into_iter()
+            .filter_map(|c| match c {
+                // Filler messages keep user/assistant alternation
+                Content::Text { text } if text.trim() == "." => None,
+                Content::Text { text } => Some(Entry::Text(text)),
+                Content::Thinking { thinking, .. } => Some(Entry::Thinking(thinking)),
+                Content::ToolUse { id, name, input } => Some(Entry::ToolCall {
+                    result: results.remove(&id),
+                    name,
+                    input,
+                }),
+                Content::Image { .. } => Some(Entry::Attachment("image")),
+                Content::Document { .. } => Some(Entry::Attachment("document")),
+                Content::ToolResult { .. } | Content::RedactedThinking { .. } => None,
+            })
+            .collect();
+        if !entries.is_empty() {
+            turns.push(Turn {
+                role: &message.role,
+                created_at: &message.created_at,
+                entries,
+            });
+        }
+    }
+    turns
+}
+
+fn role_label(role: &str) -> &str {
+    match role {
+        "user" => "User",
+        "assistant" => "Assistant",
+        "system" => "System",
+        other => other,
+    }
+}
+
+/// Shorten long tool output for transcripts
+fn clip(output: &str) -> String {
+    match output.char_indices().nth(TRANSCRIPT_OUTPUT_LIMIT) {
+        Some((cut, _)) => format!(
+            "{}\n… ({} more characters)",
+            &output[..cut],
+            output[cut..].chars().count()
+        ),
+        None => output.to_string(),
+    }
+}
+
+/// A Markdown code fence longer than any backtick run in `content`
+fn fence(content: &str) -> String {
+    let longest = content.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    "`".repeat(longest.max(2) + 1)
+}
+
+fn pretty_json(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default()
+}
+
+/// Render a readable Markdown transcript
+pub fn render_markdown(bundle: &SessionBundle) -> String {
+    let session = &bundle.session;
+    let mut out = String::new();
+    let _ = writeln!(out, "# {}\n", session.title);
+    if let Some(model) = &session.model {
+        let _ = writeln!(out, "- **Model:** {}", model);
+    }
+    if let Some(dir) = &session.working_dir {
+        let _ = writeln!(out, "- **Directory:** `{}`", dir);
+    }
+    let _ = writeln!(out, "- **Created:** {}", session.created_at);
+    let _ = writeln!(out, "- **Messages:** {}", bundle.messages.len());
+
+    if let Some(plan) = &bundle.plan {
+        let _ = writeln!(out, "\n## Plan ({})\n", plan.status);
+        // Nest the plan's headings under this section
+        for line in plan.markdown.lines() {
+            if line.starts_with('#') {
+                let _ = writeln!(out, "##{}", line);
+            } else {
+                let _ = writeln!(out, "{}", line);
+            }
+        }
+    }
+
+    if !bundle.file_activity.is_empty() {
+        out.push_str("\n## Files\n\n| File | Reads | Edits | Writes |\n|---|---|---|---|\n");
+        for file in &bundle.file_activity {
+            let _ = writeln!(
+                out,
+                "| `{}` | {} | {} | {} |",
+                file.file_path, file.read_count, file.edit_count, file.write_count
+            );
+        }
+    }
+
+    out.push_str("\n## Transcript\n");
+    for turn in transcript_turns(bundle) {
+        let _ = writeln!(out, "\n### {}\n", role_label(turn.role));
+        for entry in &turn.entries {
+            match entry {
+                Entry::Text(text) => {
+                    let _ = writeln!(out, "{}\n", text.trim());
+                }
+                Entry::Thinking(thinking) => {
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>Thinking</summary>\n\n{}\n\n</details>\n",
+                        thinking.trim()
+                    );
+                }
+                Entry::ToolCall {
+                    name,
+                    input,
+                    result,
+                } => {
+                    let input = pretty_json(input);
+                    let f = fence(&input);
+                    let _ = writeln!(out, "**Tool: `{}`**\n\n{}json\n{}\n{}\n", name, f, input, f);
+                    if let Some((output, is_error)) = result {
+                        let output = clip(output);
+                        let f = fence(&output);
+                        let summary = if *is_error { "Error" } else { "Result" };
+                        let _ = writeln!(
+                            out,
+                            "<details><summary>{}</summary>\n\n{}\n{}\n{}\n\n</details>\n",
+                            summary, f, output, f
+                        );
+                    }
+                }
+                Entry::Attachment(kind) => {
+                    let _ = writeln!(out, "_[{} attached]_\n", kind);
+                }
+            }
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = "\
+body{font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',sans-serif;max-width:860px;\
+margin:2rem auto;padding:0 1rem;color:#1f2328;background:#fff;line-height:1.5}\
+h1{margin-bottom:.25rem}.meta{color:#59636e;margin:0 0 1.5rem;padding:0;list-style:none}\
+.turn{border-left:3px solid #d1d9e0;padding:.25rem 1rem;margin:1rem 0}\
+.turn.user{border-color:#0969da}.turn.assistant{border-color:#8250df}\
+.role{font-weight:600;font-size:.85rem;text-transform:uppercase;color:#59636e}\
+.time{font-weight:normal;margin-left:.5rem}\
+.text{white-space:pre-wrap;word-wrap:break-word}\
+pre{background:#f6f8fa;padding:.75rem;border-radius:6px;overflow-x:auto;font-size:.85rem}\
+details{margin:.5rem 0}summary{cursor:pointer;color:#59636e}\
+.tool{font-family:ui-monospace,monospace;font-weight:600}.error summary{color:#cf222e}\
+table{border-collapse:collapse}td,th{border:1px solid #d1d9e0;padding:.25rem .75rem}";
+
+/// Render a self-contained HTML page
+pub fn render_html(bundle: &SessionBundle) -> String {
+    let session = &bundle.session;
+    let mut out = String::new();
+    let title = escape_html(&session.title);
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title}</title>\n<style>{HTML_STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<ul class=\"meta\">\n"
+    );
+    if let Some(model) = &session.model {
+        let _ = writeln!(out, "<li>Model: {}</li>", escape_html(model));
+    }
+    if let Some(dir) = &session.working_dir {
+        let _ = writeln!(out, "<li>Directory: <code>{}</code></li>", escape_html(dir));
+    }
+    let _ = writeln!(
+        out,
+        "<li>Created: {}</li>\n<li>Messages: {}</li>\n</ul>",
+        escape_html(&session.created_at),
+        bundle.messages.len()
+    );
+
+    if let Some(plan) = &bundle.plan {
+        let _ = writeln!(
+            out,
+            "<details open><summary>Plan ({})</summary>\n<pre>{}</pre>\n</details>",
+            escape_html(&plan.status),
+            escape_html(&plan.markdown)
+        );
+    }
+
+    if !bundle.file_activity.is_empty() {
+        out.push_str(
+            "<details><summary>Files</summary>\n<table>\n\
+             <tr><th>File</th><th>Reads</th><th>Edits</th><th>Writes</th></tr>\n",
+        );
+        for file in &bundle.file_activity {
+            let _ = writeln!(
+                out,
+                "<tr><td><code>{}</code></td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&file.file_path),
+                file.read_count,
+                file.edit_count,
+                file.write_count
+            );
+        }
+        out.push_str("</table>\n</details>\n");
+    }
+
+    for turn in transcript_turns(bundle) {
+        let _ = writeln!(
+            out,
+            "<section class=\"turn {}\">\n<div class=\"role\">{}<span class=\"time\">{}</span></div>",
+            escape_html(turn.role),
+            escape_html(role_label(turn.role)),
+            escape_html(turn.created_at.get(..16).unwrap_or(turn.created_at))
+        );
+        for entry in &turn.entries {
+            match entry {
+                Entry::Text(text) => {
+                    let _ = writeln!(
+                        out,
+                        "<div class=\"text\">{}</div>",
+                        escape_html(text.trim())
+                    );
+                }
+                Entry::Thinking(thinking) => {
+                    let _ = writeln!(
+                        out,
+                        "<details><summary>Thinking</summary>\n<div class=\"text\">{}</div>\n</details>",
+                        escape_html(thinking.trim())
+                    );
+                }
+                Entry::ToolCall {
+                    name,
+                    input,
+                    result,
+                } => {
+                    let _ = writeln!(
+                        out,
+                        "<div class=\"tool\">{}</div>\n<pre>{}</pre>",
+                        escape_html(name),
+                        escape_html(&pretty_json(input))
+                    );
+                    if let Some((output, is_error)) = result {
+                        let (class, summary) = if *is_error {
+                            (" class=\"error\"", "Error")
+                        } else {
+                            ("", "Result")
+                        };
+                        let _ = writeln!(
+                            out,
+                            "<details{}><summary>{}</summary>\n<pre>{}</pre>\n</details>",
+                            class,
+                            summary,
+                            escape_html(&clip(output))
+                        );
+                    }
+                }
+                Entry::Attachment(kind) => {
+                    let _ = writeln!(out, "<p><em>[{} attached]</em></p>", kind);
+                }
+            }
+        }
+        out.push_str("</section>\n");
+    }
+
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn setup() -> (SessionManager, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db")).unwrap();
+        (SessionManager::new(db), temp_dir)
+    }
+
+    fn sample_session(manager: &SessionManager) -> String {
+        let id = manager
+            .create_session("Fix <ws> bug", Some("model-a"), Some("/src/app"))
+            .unwrap();
+        let messages = [
+            (
+                "user",
+                json!([{"type": "text", "text": "Fix the websocket"}]),
+            ),
+            (
+                "assistant",
+                json!([
+                    {"type": "thinking", "thinking": "Check the ping", "signature": "sig"},
+                    {"type": "tool_use", "id": "t1", "name": "read", "input": {"file_path": "src/ws.rs"}}
+                ]),
+            ),
+            (
+                "user",
+                json!([{"type": "tool_result", "tool_use_id": "t1", "output": "fn ping() {}"}]),
+            ),
+            (
+                "assistant",
+                json!([{"type": "text", "text": "Added the ping"}]),
+            ),
+        ];
+        for (role, content) in messages {
+            manager
+                .save_message(&id, role, &content.to_string())
+                .unwrap();
+        }
+        let mut plan = PlanFile::new("Websocket fix");
+        plan.status = PlanStatus::InProgress;
+        PlanStore::new(manager.db())
+            .upsert_plan(&id, &plan)
+            .unwrap();
+        manager
+            .db()
+            .conn()
+            .execute(
+                "INSERT INTO file_activity (session_id, file_path, read_count, write_count,
+                                            edit_count, last_accessed, user_referenced)
+                 VALUES (?1, 'src/ws.rs', 1, 0, 2, ?2, 1)",
+                params![id, Utc::now().to_rfc3339()],
+            )
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let (source, _temp) = setup();
+        let id = sample_session(&source);
+        let json = ExportFormat::Json
+            .render(&source.export_session(&id).unwrap())
+            .unwrap();
+
+        let (target, _temp2) = setup();
+        let bundle = parse_bundle(&json).unwrap();
+        let imported = target
+            .import_session(&bundle, Some("/elsewhere"), None)
+            .unwrap();
+
+        let info = target.get_session(&imported).unwrap().unwrap();
+        assert_eq!(info.title, "Fix <ws> bug");
+        assert_eq!(info.model.as_deref(), Some("model-a"));
+        assert_eq!(info.working_dir.as_deref(), Some("/elsewhere"));
+        assert_eq!(
+            target.load_session_messages(&imported).unwrap(),
+            source.load_session_messages(&id).unwrap()
+        );
+        assert_eq!(
+            PlanStore::new(target.db())
+                .get_plan_for_session(&imported)
+                .unwrap()
+                .unwrap()
+                .title,
+            "Websocket fix"
+        );
+
+        let reexported = target.export_session(&imported).unwrap();
+        assert_eq!(reexported.file_activity.len(), 1);
+        assert_eq!(reexported.file_activity[0].edit_count, 2);
+        assert!(reexported.file_activity[0].user_referenced);
+
+        // Imported messages are searchable
+        let hits = super::super::MessageSearch::new(target.db())
+            .search("websocket", None, None, 10)
+            .unwrap();
+        assert_eq!(hits[0].session_id, imported);
+    }
+
+    #[test]
+    fn test_parse_bundle_rejects_foreign_and_newer_bundles() {
+        assert!(parse_bundle("{}").is_err());
+        let (manager, _temp) = setup();
+        let id = sample_session(&manager);
+        let mut bundle = manager.export_session(&id).unwrap();
+        bundle.version = BUNDLE_VERSION + 1;
+        assert!(parse_bundle(&serde_json::to_string(&bundle).unwrap()).is_err());
+        bundle.version = BUNDLE_VERSION;
+        bundle.format = "other".to_string();
+        assert!(parse_bundle(&serde_json::to_string(&bundle).unwrap()).is_err());
+        assert!(manager.export_session("missing").is_err());
+    }
+
+    #[test]
+    fn test_transcripts_pair_tool_calls_with_results() {
+        let (manager, _temp) = setup();
+        let id = sample_session(&manager);
+        let bundle = manager.export_session(&id).unwrap();
+
+        let markdown = render_markdown(&bundle);
+        assert!(markdown.starts_with("# Fix <ws> bug\n"));
+        assert!(markdown.contains("**Tool: `read`**"));
+        assert!(markdown.contains("<details><summary>Result</summary>\n\n```\nfn ping() {}\n```"));
+        assert!(markdown.contains("<details><summary>Thinking</summary>"));
+        assert!(markdown.contains("| `src/ws.rs` | 1 | 2 | 0 |"));
+        // The tool-result message is folded into the call, not its own turn
+        assert_eq!(markdown.matches("### User").count(), 1);
+
+        let html = render_html(&bundle);
+        assert!(html.contains("<title>Fix &lt;ws&gt; bug</title>"));
+        assert!(html.contains("<div class=\"tool\">read</div>"));
+        assert!(!html.contains("<ws>"));
+    }
+
+    #[test]
+    fn test_fence_outgrows_backticks() {
+        assert_eq!(fence("plain"), "```");
+        assert_eq!(fence("has ```` inside"), "`````");
+    }
+}
@@ -0,0 +1,262 @@
+//! Persistent project memory
+//!
+//! Short notes the agent (or the user) keeps across sessions: conventions,
+//! decisions, gotchas. Memories are scoped to a project directory and, in
+//! multi-tenant mode, to the user who created them.
+
+use anyhow::{bail, Result};
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::Serialize;
+
+use super::database::Database;
+
+/// Longest memory accepted, in characters
+pub const MAX_MEMORY_CHARS: usize = 1000;
+
+/// A saved memory
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Memory {
+    pub id: i64,
+    /// Project directory the memory belongs to
+    pub working_dir: String,
+    pub content: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl Memory {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            id: row.get(0)?,
+            working_dir: row.get(1)?,
+            content: row.get(2)?,
+            created_at: row.get(3)?,
+            updated_at: row.get(4)?,
+        })
+    }
+}
+
+const MEMORY_COLUMNS: &str = "id, working_dir, content, created_at, updated_at";
+
+/// SQLite-backed memory storage
+///
+/// `user_id` filters follow the other multi-tenant stores: `None` sees every
+/// memory, `Some` only that user's own.
+pub struct MemoryStore<'a> {
+    db: &'a Database,
+}
+
+impl<'a> MemoryStore<'a> {
+    /// Create a new memory store with database reference
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Save a memory for a project
+    ///
+    /// Saving text that is already remembered refreshes the existing memory
+    /// instead of adding a duplicate.
+    pub fn add(&self, working_dir: &str, user_id: Option<&str>, content: &str) -> Result<Memory> {
+        let content = validate(content)?;
+        let now = Utc::now().to_rfc3339();
+        let conn = self.db.conn();
+
+        let existing: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM memories
+                 WHERE working_dir = ?1 AND user_id IS ?2 AND content = ?3",
+                params![working_dir, user_id, content],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let id = match existing {
+            Some(id) => {
+                conn.execute(
+                    "UPDATE memories SET updated_at = ?1 WHERE id = ?2",
+                    params![now, id],
+                )?;
+                id
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO memories (working_dir, user_id, content, created_at, updated_at)
+                     VALUES (?1, ?2, ?3, ?4, ?4)",
+                    params![working_dir, user_id, content, now],
+                )?;
+                conn.last_insert_rowid()
+            }
+        };
+
+        self.get(id, user_id)?
+            .ok_or_else(|| anyhow::anyhow!("Failed to fetch saved memory"))
+    }
+
+    /// Get a memory by ID
+    pub fn get(&self, id: i64, user_id: Option<&str>) -> Result<Option<Memory>> {
+        let memory = self
+            .db
+            .conn()
+            .query_row(
+                &format!(
+                    "SELECT {} FROM memories WHERE id = ?1 AND (?2 IS NULL OR user_id = ?2)",
+                    MEMORY_COLUMNS
+                ),
+                params![id, user_id],
+                Memory::from_row,
+            )
+            .optional()?;
+        Ok(memory)
+    }
+
+    /// List a project's memories (or every project's), most recently updated first
+    pub fn list(&self, working_dir: Option<&str>, user_id: Option<&str>) -> Result<Vec<Memory>> {
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM memories
+             WHERE (?1 IS NULL OR working_dir = ?1) AND (?2 IS NULL OR user_id = ?2)
+             ORDER BY updated_at DESC, id DESC",
+            MEMORY_COLUMNS
+        ))?;
+        let memories = stmt
+            .query_map(params![working_dir, user_id], Memory::from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(memories)
+    }
+
+    /// Find a project's memories containing every word of `query`
+    ///
+    /// Matching is case-insensitive; more recently updated memories come first.
+    pub fn recall(
+        &self,
+        working_dir: &str,
+        user_id: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<Memory>> {
+        let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let memories = self
+            .list(Some(working_dir), user_id)?
+            .into_iter()
+            .filter(|m| {
+                let content = m.content.to_lowercase();
+                words.iter().all(|w| content.contains(w.as_str()))
+            })
+            .take(limit)
+            .collect();
+        Ok(memories)
+    }
+
+    /// Replace a memory's text
+    ///
+    /// Returns whether a memory was updated.
+    pub fn update(&self, id: i64, user_id: Option<&str>, content: &str) -> Result<bool> {
+        let content = validate(content)?;
+        let updated = self.db.conn().execute(
+            "UPDATE memories SET content = ?1, updated_at = ?2
+             WHERE id = ?3 AND (?4 IS NULL OR user_id = ?4)",
+            params![content, Utc::now().to_rfc3339(), id, user_id],
+        )?;
+        Ok(updated > 0)
+    }
+
+    /// Delete a memory
+    ///
+    /// Returns whether a memory was deleted.
+    pub fn delete(&self, id: i64, user_id: Option<&str>) -> Result<bool> {
+        let deleted = self.db.conn().execute(
+            "DELETE FROM memories WHERE id = ?1 AND (?2 IS NULL OR user_id = ?2)",
+            params![id, user_id],
+        )?;
+        Ok(deleted > 0)
+    }
+}
+
+/// Trim memory text and enforce the length limit
+fn validate(content: &str) -> Result<&str> {
+    let content = content.trim();
+    if content.is_empty() {
+        bail!("Memory cannot be empty");
+    }
+    let chars = content.chars().count();
+    if chars > MAX_MEMORY_CHARS {
+        bail!(
+            "Memory is {} characters; keep it under {}",
+            chars,
+            MAX_MEMORY_CHARS
+        );
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn setup() -> (Database, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db")).unwrap();
+        (db, temp_dir)
+    }
+
+    #[test]
+    fn test_memories_are_scoped_to_project_and_user() {
+        let (db, _temp) = setup();
+        let store = MemoryStore::new(&db);
+
+        let first = store
+            .add("/app", None, "  Run tests with cargo nextest  ")
+            .unwrap();
+        assert_eq!(first.content, "Run tests with cargo nextest");
+        store.add("/other", None, "Uses pnpm").unwrap();
+        store
+            .add("/app", Some("alice"), "Alice prefers tabs")
+            .unwrap();
+
+        // Saving the same text again refreshes rather than duplicates
+        let again = store
+            .add("/app", None, "Run tests with cargo nextest")
+            .unwrap();
+        assert_eq!(again.id, first.id);
+
+        assert_eq!(store.list(Some("/app"), None).unwrap().len(), 2);
+        assert_eq!(store.list(None, None).unwrap().len(), 3);
+        let alice = store.list(Some("/app"), Some("alice")).unwrap();
+        assert_eq!(alice.len(), 1);
+        assert_eq!(alice[0].content, "Alice prefers tabs");
+
+        // Other users can neither see nor change a user's memories
+        assert!(store.get(alice[0].id, Some("bob")).unwrap().is_none());
+        assert!(!store.delete(alice[0].id, Some("bob")).unwrap());
+        assert!(store.delete(alice[0].id, Some("alice")).unwrap());
+    }
+
+    #[test]
+    fn test_recall_and_update() {
+        let (db, _temp) = setup();
+        let store = MemoryStore::new(&db);
+        let memory = store
+            .add("/app", None, "The API server listens on port 8080")
+            .unwrap();
+        store.add("/app", None, "Migrations live in db/").unwrap();
+
+        let hits = store.recall("/app", None, "api PORT", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, memory.id);
+        assert_eq!(store.recall("/app", None, "", 10).unwrap().len(), 2);
+        assert!(store
+            .recall("/elsewhere", None, "port", 10)
+            .unwrap()
+            .is_empty());
+
+        assert!(store
+            .update(memory.id, None, "The API server listens on port 9090")
+            .unwrap());
+        assert!(store.recall("/app", None, "8080", 10).unwrap().is_empty());
+        assert!(store.update(memory.id, None, "   ").is_err());
+        assert!(store
+            .add("/app", None, &"x".repeat(MAX_MEMORY_CHARS + 1))
+            .is_err());
+    }
+}
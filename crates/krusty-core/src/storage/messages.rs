@@ -4,9 +4,10 @@
 
 use anyhow::Result;
 use chrono::Utc;
-use rusqlite::params;
+use rusqlite::{params, OptionalExtension};
 
 use super::database::Database;
+use super::search::MessageSearch;
 
 /// Message persistence store
 pub struct MessageStore<'a> {
@@ -29,6 +30,8 @@ impl<'a> MessageStore<'a> {
              VALUES (?1, ?2, ?3, ?4)",
             params![session_id, role, content_json, now],
         )?;
+        let message_id = self.db.conn().last_insert_rowid();
+        MessageSearch::new(self.db).index_message(message_id, session_id, content_json)?;
 
         // Update session timestamp
         self.db.conn().execute(
@@ -98,22 +101,29 @@ impl<'a> MessageStore<'a> {
         content_json: &str,
     ) -> Result<()> {
         let now = Utc::now().to_rfc3339();
-        let affected = self.db.conn().execute(
-            "UPDATE messages SET content = ?1
-             WHERE id = (
-                 SELECT id FROM messages
-                 WHERE session_id = ?2 AND role = ?3
-                 ORDER BY id DESC LIMIT 1
-             )",
-            params![content_json, session_id, role],
-        )?;
-        if affected == 0 {
+        let message_id: Option<i64> = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT id FROM messages
+                 WHERE session_id = ?1 AND role = ?2
+                 ORDER BY id DESC LIMIT 1",
+                params![session_id, role],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let Some(message_id) = message_id else {
             anyhow::bail!(
                 "No {} message found to update in session {}",
                 role,
                 session_id
             );
-        }
+        };
+        self.db.conn().execute(
+            "UPDATE messages SET content = ?1 WHERE id = ?2",
+            params![content_json, message_id],
+        )?;
+        MessageSearch::new(self.db).index_message(message_id, session_id, content_json)?;
         self.db.conn().execute(
             "UPDATE sessions SET updated_at = ?1 WHERE id = ?2",
             params![now, session_id],
@@ -121,12 +131,28 @@ impl<'a> MessageStore<'a> {
         Ok(())
     }
 
+    /// Keep the first `keep` messages of a session and delete the rest
+    ///
+    /// Returns the number of messages deleted.
+    pub fn truncate_messages(&self, session_id: &str, keep: usize) -> Result<usize> {
+        let deleted = self.db.conn().execute(
+            "DELETE FROM messages
+             WHERE session_id = ?1 AND id NOT IN (
+                 SELECT id FROM messages WHERE session_id = ?1 ORDER BY id LIMIT ?2
+             )",
+            params![session_id, keep as i64],
+        )?;
+        MessageSearch::new(self.db).prune_session(session_id)?;
+        Ok(deleted)
+    }
+
     /// Delete all messages for a session
     /// Called automatically when session is deleted via CASCADE
     pub fn delete_session_messages(&self, session_id: &str) -> Result<()> {
         self.db
             .conn()
             .execute("DELETE FROM messages WHERE session_id = ?1", [session_id])?;
+        MessageSearch::new(self.db).prune_session(session_id)?;
         Ok(())
     }
 }
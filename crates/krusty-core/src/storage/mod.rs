@@ -6,28 +6,47 @@
 //! - User preferences
 //! - File activity tracking for context
 //! - API credentials
+//! - Token usage ledger and session budgets
+//! - File checkpoints for undo/rewind
+//! - In-place conversation compactions
+//! - Full-text search across sessions
+//! - Persistent project memory
+//! - Session export and import
+//! - Server accounts, API tokens and device pairing
 
 use std::time::{SystemTime, UNIX_EPOCH};
 
+pub mod accounts;
 mod agent_state;
 mod block_ui;
+mod checkpoints;
+mod compactions;
 pub mod credentials;
 mod database;
 #[cfg(test)]
 mod database_tests;
+pub mod export;
 mod file_activity;
+mod memories;
 mod messages;
 mod plans;
 mod preferences;
 pub mod push_delivery_attempts;
 pub mod push_subscriptions;
+mod search;
 mod sessions;
+mod usage;
 
+pub use accounts::{AccountStore, AuthToken, TokenKind, TokenScope, UserAccount, MIN_PASSWORD_LEN};
 pub use agent_state::AgentState;
 pub use block_ui::BlockUiState;
+pub use checkpoints::{Checkpoint, CheckpointStore, FileSnapshot};
+pub use compactions::{Compaction, CompactionStore};
 pub use credentials::CredentialStore;
 pub use database::{Database, SharedDatabase};
+pub use export::{ExportFormat, SessionBundle};
 pub use file_activity::{FileActivityTracker, RankedFile};
+pub use memories::{Memory, MemoryStore, MAX_MEMORY_CHARS};
 pub use messages::MessageStore;
 pub use plans::{PlanStore, PlanSummary};
 pub use preferences::Preferences;
@@ -35,7 +54,9 @@ pub use push_delivery_attempts::{
     PushDeliveryAttempt, PushDeliveryAttemptInput, PushDeliveryAttemptStore, PushDeliverySummary,
 };
 pub use push_subscriptions::{PushSubscription, PushSubscriptionStore};
+pub use search::{MessageSearch, SearchHit};
 pub use sessions::{SessionInfo, SessionManager, WorkMode};
+pub use usage::{BudgetStatus, ModelUsage, SessionBudget, UsageEntry, UsageLedger, UsageTotals};
 
 /// Get current Unix timestamp in seconds
 #[inline]
@@ -3,6 +3,7 @@
 use anyhow::Result;
 use rusqlite::params;
 
+use crate::agent::AutoPinchPolicy;
 use crate::ai::models::ModelMetadata;
 use crate::tools::git_identity::GitIdentity;
 
@@ -174,4 +175,59 @@ impl Preferences {
         let json = serde_json::to_string(identity)?;
         self.set("git_identity", &json)
     }
+
+    /// Get the auto-pinch policy (defaults to pinching at 80% of the window)
+    ///
+    /// An out-of-range threshold is replaced by the default.
+    pub fn get_auto_pinch_policy(&self) -> AutoPinchPolicy {
+        let mut policy: AutoPinchPolicy = self
+            .get("auto_pinch")
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default();
+        if let Err(e) = policy.validate() {
+            tracing::warn!("Ignoring stored auto-pinch threshold: {}", e);
+            policy.threshold = AutoPinchPolicy::default().threshold;
+        }
+        policy
+    }
+
+    /// Save the auto-pinch policy, rejecting an out-of-range threshold
+    pub fn set_auto_pinch_policy(&self, policy: &AutoPinchPolicy) -> Result<()> {
+        policy.validate()?;
+        let json = serde_json::to_string(policy)?;
+        self.set("auto_pinch", &json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_auto_pinch_threshold_out_of_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let prefs = Preferences::new(Database::new(&temp_dir.path().join("test.db")).unwrap());
+
+        let policy = AutoPinchPolicy {
+            threshold: 1.5,
+            ..Default::default()
+        };
+        assert!(prefs.set_auto_pinch_policy(&policy).is_err());
+
+        // Hand-edited values load with the default threshold
+        prefs
+            .set("auto_pinch", r#"{"mode":"notify","threshold":0}"#)
+            .unwrap();
+        let loaded = prefs.get_auto_pinch_policy();
+        assert_eq!(loaded.mode, crate::agent::AutoPinchMode::Notify);
+        assert_eq!(loaded.threshold, AutoPinchPolicy::default().threshold);
+
+        let policy = AutoPinchPolicy {
+            threshold: 0.6,
+            ..Default::default()
+        };
+        prefs.set_auto_pinch_policy(&policy).unwrap();
+        assert_eq!(prefs.get_auto_pinch_policy(), policy);
+    }
 }
@@ -0,0 +1,293 @@
+//! Full-text search across sessions
+//!
+//! Messages are indexed in the `message_search` FTS5 table (keyed by message
+//! id) as they are saved or updated: their text, the names of the tools they
+//! call and the file paths those calls touch. Tool outputs are not indexed.
+
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::database::Database;
+
+/// Tool input fields that hold file paths
+const PATH_FIELDS: &[&str] = &["file_path", "path", "notebook_path"];
+
+/// A message matching a search
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_title: String,
+    pub working_dir: Option<String>,
+    pub message_id: i64,
+    /// Position of the message in its session (0-based)
+    pub message_index: usize,
+    pub role: String,
+    /// Excerpt around the match
+    pub snippet: String,
+    pub created_at: String,
+}
+
+/// FTS5 index over session messages
+pub struct MessageSearch<'a> {
+    db: &'a Database,
+}
+
+impl<'a> MessageSearch<'a> {
+    /// Create a new search index with database reference
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Index (or re-index) a message
+    pub fn index_message(
+        &self,
+        message_id: i64,
+        session_id: &str,
+        content_json: &str,
+    ) -> Result<()> {
+        index_message(self.db.conn(), message_id, session_id, content_json)
+    }
+
+    /// Re-index every message of a session
+    pub fn index_session(&self, session_id: &str) -> Result<usize> {
+        index_messages(self.db.conn(), Some(session_id))
+    }
+
+    /// Drop index entries whose messages no longer exist
+    pub fn prune_session(&self, session_id: &str) -> Result<usize> {
+        let deleted = self.db.conn().execute(
+            "DELETE FROM message_search
+             WHERE session_id = ?1
+               AND rowid NOT IN (SELECT id FROM messages WHERE session_id = ?1)",
+            [session_id],
+        )?;
+        Ok(deleted)
+    }
+
+    /// Search messages, best matches first
+    ///
+    /// Every word of `query` must match (prefix matches count). Results are
+    /// limited to sessions in `working_dir` and owned by `user_id` when set.
+    pub fn search(
+        &self,
+        query: &str,
+        working_dir: Option<&str>,
+        user_id: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let Some(query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        let mut stmt = self.db.conn().prepare(
+            "SELECT m.session_id, s.title, s.working_dir, m.id,
+                    (SELECT COUNT(*) FROM messages p
+                     WHERE p.session_id = m.session_id AND p.id < m.id),
+                    m.role, snippet(message_search, -1, '', '', '…', 24), m.created_at
+             FROM message_search
+             JOIN messages m ON m.id = message_search.rowid
+             JOIN sessions s ON s.id = m.session_id
+             WHERE message_search MATCH ?1
+               AND (?2 IS NULL OR s.working_dir = ?2)
+               AND (?3 IS NULL OR s.user_id = ?3)
+             ORDER BY rank
+             LIMIT ?4",
+        )?;
+        let hits = stmt
+            .query_map(params![query, working_dir, user_id, limit as i64], |row| {
+                Ok(SearchHit {
+                    session_id: row.get(0)?,
+                    session_title: row.get(1)?,
+                    working_dir: row.get(2)?,
+                    message_id: row.get(3)?,
+                    message_index: row.get::<_, i64>(4)? as usize,
+                    role: row.get(5)?,
+                    snippet: row.get(6)?,
+                    created_at: row.get(7)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(hits)
+    }
+}
+
+/// Index a message on a raw connection (also used by the migration backfill)
+pub(crate) fn index_message(
+    conn: &Connection,
+    message_id: i64,
+    session_id: &str,
+    content_json: &str,
+) -> Result<()> {
+    conn.execute("DELETE FROM message_search WHERE rowid = ?1", [message_id])?;
+    let (text, tools, paths) = extract_searchable(content_json);
+    if text.is_empty() && tools.is_empty() && paths.is_empty() {
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO message_search (rowid, text, tools, paths, session_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![message_id, text, tools, paths, session_id],
+    )?;
+    Ok(())
+}
+
+/// Index all messages, or those of one session
+pub(crate) fn index_messages(conn: &Connection, session_id: Option<&str>) -> Result<usize> {
+    let mut stmt = conn.prepare(
+        "SELECT id, session_id, content FROM messages
+         WHERE ?1 IS NULL OR session_id = ?1 ORDER BY id",
+    )?;
+    let messages = stmt
+        .query_map([session_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    for (id, session_id, content) in &messages {
+        index_message(conn, *id, session_id, content)?;
+    }
+    Ok(messages.len())
+}
+
+/// Pull the text, tool names and file paths out of a message's content JSON
+///
+/// Content is a JSON array of blocks; anything else is indexed as plain text.
+fn extract_searchable(content_json: &str) -> (String, String, String) {
+    let Ok(Value::Array(blocks)) = serde_json::from_str::<Value>(content_json) else {
+        return (content_json.to_string(), String::new(), String::new());
+    };
+
+    let mut text = Vec::new();
+    let mut tools = Vec::new();
+    let mut paths = Vec::new();
+    for block in &blocks {
+        match block.get("type").and_then(Value::as_str) {
+            Some("text") => {
+                if let Some(t) = block.get("text").and_then(Value::as_str) {
+                    text.push(t);
+                }
+            }
+            Some("tool_use") => {
+                if let Some(name) = block.get("name").and_then(Value::as_str) {
+                    tools.push(name);
+                }
+                if let Some(input) = block.get("input") {
+                    paths.extend(
+                        PATH_FIELDS
+                            .iter()
+                            .filter_map(|field| input.get(*field).and_then(Value::as_str)),
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    (text.join("\n"), tools.join(" "), paths.join("\n"))
+}
+
+/// Turn free text into an FTS5 query: every word quoted, as a prefix
+///
+/// Quoting keeps punctuation in user input from being read as FTS5 syntax.
+fn fts_query(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SessionManager;
+    use tempfile::TempDir;
+
+    fn setup() -> (SessionManager, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::new(&temp_dir.path().join("test.db")).unwrap();
+        (SessionManager::new(db), temp_dir)
+    }
+
+    #[test]
+    fn test_extract_searchable() {
+        let content = r#"[
+            {"type": "text", "text": "Fix the websocket reconnect"},
+            {"type": "tool_use", "id": "t1", "name": "edit", "input": {"file_path": "src/ws.rs"}},
+            {"type": "tool_result", "tool_use_id": "t1", "output": "ignored"}
+        ]"#;
+        let (text, tools, paths) = extract_searchable(content);
+        assert_eq!(text, "Fix the websocket reconnect");
+        assert_eq!(tools, "edit");
+        assert_eq!(paths, "src/ws.rs");
+        assert_eq!(extract_searchable("plain").0, "plain");
+    }
+
+    #[test]
+    fn test_fts_query_quotes_terms() {
+        assert_eq!(
+            fts_query(r#"websocket "bug" OR"#).as_deref(),
+            Some(r#""websocket"* "bug"* "OR"*"#)
+        );
+        assert!(fts_query("  \"\" ").is_none());
+    }
+
+    #[test]
+    fn test_search_tracks_saved_and_updated_messages() {
+        let (manager, _temp) = setup();
+        let a = manager.create_session("Sockets", None, Some("/a")).unwrap();
+        let b = manager.create_session("Other", None, Some("/b")).unwrap();
+        let text = |t: &str| serde_json::json!([{"type": "text", "text": t}]).to_string();
+
+        manager.save_message(&a, "user", &text("hello")).unwrap();
+        manager
+            .save_message(
+                &a,
+                "assistant",
+                &text("The websocket bug was a missing ping"),
+            )
+            .unwrap();
+        manager
+            .save_message(&b, "user", &text("websockets elsewhere"))
+            .unwrap();
+
+        let search = MessageSearch::new(manager.db());
+        let hits = search.search("websocket ping", None, None, 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, a);
+        assert_eq!(hits[0].session_title, "Sockets");
+        assert_eq!(hits[0].message_index, 1);
+        assert_eq!(hits[0].role, "assistant");
+
+        assert_eq!(search.search("websocket", None, None, 10).unwrap().len(), 2);
+        assert_eq!(
+            search.search("websocket", Some("/b"), None, 10).unwrap()[0].session_id,
+            b
+        );
+
+        // Updates replace the indexed text
+        manager
+            .update_last_message(&a, "assistant", &text("Rewrote the heartbeat"))
+            .unwrap();
+        assert!(search.search("ping", None, None, 10).unwrap().is_empty());
+        assert_eq!(search.search("heartbeat", None, None, 10).unwrap().len(), 1);
+
+        // Rewound and deleted messages drop out of the index
+        manager.truncate_messages(&a, 1).unwrap();
+        assert!(search
+            .search("heartbeat", None, None, 10)
+            .unwrap()
+            .is_empty());
+        manager.delete_session(&b).unwrap();
+        assert!(search
+            .search("websocket", None, None, 10)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -11,21 +11,21 @@ use super::database::Database;
 use crate::agent::PinchContext;
 
 const LIST_SESSIONS_SQL_ALL: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              ORDER BY updated_at DESC";
 const LIST_SESSIONS_SQL_BY_DIR: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              WHERE working_dir = ?1
              ORDER BY updated_at DESC";
 const LIST_SESSIONS_SQL_BY_USER: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              WHERE user_id = ?1
              ORDER BY updated_at DESC";
 const LIST_SESSIONS_SQL_BY_DIR_AND_USER: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              WHERE working_dir = ?1 AND user_id = ?2
              ORDER BY updated_at DESC";
@@ -36,12 +36,12 @@ const LIST_SESSION_DIRS_SQL_BY_USER: &str = "SELECT DISTINCT working_dir FROM se
                  WHERE working_dir IS NOT NULL AND user_id = ?1
                  ORDER BY working_dir";
 const LIST_SESSIONS_BY_DIRECTORY_SQL: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              WHERE working_dir IS NOT NULL
              ORDER BY working_dir, updated_at DESC";
 const GET_SESSION_SQL: &str =
-    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch
+    "SELECT id, title, updated_at, token_count, parent_session_id, working_dir, user_id, work_mode, model, target_branch, fork_point
              FROM sessions
              WHERE id = ?1";
 
@@ -52,8 +52,10 @@ pub struct SessionInfo {
     pub title: String,
     pub updated_at: DateTime<Utc>,
     pub token_count: Option<usize>,
-    /// Parent session ID for linked sessions (pinch)
+    /// Parent session ID for linked sessions (pinch) and forks
     pub parent_session_id: Option<String>,
+    /// Parent messages this session was forked with (None unless forked)
+    pub fork_point: Option<usize>,
     /// Working directory for this session
     pub working_dir: Option<String>,
     /// User ID for multi-tenant isolation
@@ -227,6 +229,7 @@ impl SessionManager {
         let work_mode_raw: String = row.get(7)?;
         let model: Option<String> = row.get(8)?;
         let target_branch: Option<String> = row.get(9)?;
+        let fork_point: Option<i64> = row.get(10)?;
 
         Ok(SessionInfo {
             id: row.get(0)?,
@@ -236,6 +239,7 @@ impl SessionManager {
                 .unwrap_or_else(|_| Utc::now()),
             token_count: token_count.map(|t| t as usize),
             parent_session_id: row.get(4)?,
+            fork_point: fork_point.map(|n| n as usize),
             working_dir: row.get(5)?,
             user_id: row.get(6)?,
             work_mode: work_mode_raw.parse().unwrap_or_default(),
@@ -442,10 +446,17 @@ impl SessionManager {
             params![session_id],
         )?;
 
+        // Clear file checkpoints (their file snapshots cascade)
+        self.db.conn().execute(
+            "DELETE FROM checkpoints WHERE session_id = ?1",
+            params![session_id],
+        )?;
+
         // Messages will be deleted via ON DELETE CASCADE
         self.db
             .conn()
             .execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+        super::search::MessageSearch::new(&self.db).prune_session(session_id)?;
 
         tracing::info!(session_id = %session_id, "Session deleted from database");
         Ok(())
@@ -477,6 +488,14 @@ impl SessionManager {
         super::messages::MessageStore::new(&self.db).load_session_messages(session_id)
     }
 
+    /// Drop every message after the first `keep` (conversation rewind)
+    pub fn truncate_messages(&self, session_id: &str, keep: usize) -> Result<usize> {
+        let deleted =
+            super::messages::MessageStore::new(&self.db).truncate_messages(session_id, keep)?;
+        super::compactions::CompactionStore::new(&self.db).discard_beyond(session_id, keep)?;
+        Ok(deleted)
+    }
+
     /// Generate a title from the first message content
     /// Truncates at word boundaries for cleaner display
     /// Uses char-based indexing for UTF-8 safety
@@ -555,10 +574,10 @@ impl SessionManager {
         let id = uuid::Uuid::new_v4().to_string();
         let now = Utc::now().to_rfc3339();
 
-        // Create new session with parent reference
+        // Create new session with parent reference, owned by the parent's user
         self.db.conn().execute(
-            "INSERT INTO sessions (id, title, created_at, updated_at, model, working_dir, parent_session_id, target_branch)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            "INSERT INTO sessions (id, title, created_at, updated_at, model, working_dir, parent_session_id, target_branch, user_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, (SELECT user_id FROM sessions WHERE id = ?7))",
             params![
                 id,
                 title,
@@ -593,6 +612,70 @@ impl SessionManager {
         Ok(id)
     }
 
+    // =========================================================================
+    // Forks
+    // =========================================================================
+
+    /// Fork a session, starting the fork with its first `message_count` messages
+    ///
+    /// `None` forks the whole conversation. The fork is a child of the source
+    /// with `fork_point` set and continues independently of it. A compaction
+    /// that only covers the copied messages is carried over.
+    pub fn fork_session(
+        &self,
+        source_id: &str,
+        message_count: Option<usize>,
+        title: Option<&str>,
+        model: Option<&str>,
+    ) -> Result<String> {
+        let source = self
+            .get_session(source_id)?
+            .ok_or_else(|| anyhow::anyhow!("Session {} not found", source_id))?;
+        let total = super::messages::MessageStore::new(&self.db).get_message_count(source_id)?;
+        let keep = message_count.unwrap_or(total);
+        if keep > total {
+            anyhow::bail!(
+                "Cannot fork at message {}: the session has {} messages",
+                keep,
+                total
+            );
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+        let title = title
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{} (fork)", source.title));
+
+        let tx = self.db.conn().unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO sessions (id, title, created_at, updated_at, model, working_dir,
+                                   parent_session_id, fork_point, user_id, workspace_id,
+                                   codebase_id, work_mode, target_branch)
+             SELECT ?1, ?2, ?3, ?3, COALESCE(?4, model), working_dir, id, ?5, user_id,
+                    workspace_id, codebase_id, work_mode, target_branch
+             FROM sessions WHERE id = ?6",
+            params![id, title, now, model, keep as i64, source_id],
+        )?;
+        tx.execute(
+            "INSERT INTO messages (session_id, role, content, created_at, tool_calls)
+             SELECT ?1, role, content, created_at, tool_calls
+             FROM messages WHERE session_id = ?2 ORDER BY id LIMIT ?3",
+            params![id, source_id, keep as i64],
+        )?;
+        super::compactions::CompactionStore::new(&self.db).copy_within(source_id, &id, keep)?;
+        super::search::MessageSearch::new(&self.db).index_session(&id)?;
+        tx.commit()?;
+
+        tracing::info!(
+            source = %source_id,
+            fork = %id,
+            messages = keep,
+            "Session forked"
+        );
+        Ok(id)
+    }
+
     // =========================================================================
     // Agent State Tracking (for background execution)
     // =========================================================================
@@ -1142,4 +1225,99 @@ mod tests {
         let state = manager.get_agent_state(&session_id).unwrap();
         assert!(state.last_event_at.is_some(), "Should have last_event_at");
     }
+
+    #[test]
+    fn test_linked_session_inherits_owner() {
+        use crate::agent::{PinchContext, SummarizationResult};
+
+        let (db, _temp) = create_test_db();
+        create_test_user(&db, "user-123");
+        let manager = SessionManager::new(db);
+
+        let parent = manager
+            .create_session_for_user("Parent", None, Some("/tmp"), Some("user-123"))
+            .expect("Failed to create session");
+        let pinch_ctx = PinchContext::new(
+            parent.clone(),
+            "Parent".to_string(),
+            SummarizationResult::default(),
+            Vec::new(),
+            None,
+            None,
+            None,
+            Vec::new(),
+            None,
+        );
+        let child = manager
+            .create_linked_session("Parent (cont.)", &parent, &pinch_ctx, None, None, None)
+            .expect("Failed to create linked session");
+
+        let info = manager.get_session(&child).unwrap().unwrap();
+        assert_eq!(info.parent_session_id.as_deref(), Some(parent.as_str()));
+        assert_eq!(info.user_id.as_deref(), Some("user-123"));
+        assert!(manager
+            .verify_session_ownership(&child, Some("user-123"))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_fork_session_copies_history_and_diverges() {
+        use crate::storage::CompactionStore;
+
+        let (db, _temp) = create_test_db();
+        create_test_user(&db, "user-123");
+        let manager = SessionManager::new(db);
+
+        let source = manager
+            .create_session_for_user("Source", Some("model-a"), Some("/tmp"), Some("user-123"))
+            .expect("Failed to create session");
+        for (role, text) in [("user", "one"), ("assistant", "two"), ("user", "three")] {
+            manager.save_message(&source, role, text).unwrap();
+        }
+        let compactions = CompactionStore::new(manager.db());
+        compactions.record(&source, "summary", 1, 1, 100).unwrap();
+        compactions.record(&source, "later", 3, 3, 200).unwrap();
+
+        let fork = manager
+            .fork_session(&source, Some(2), None, Some("model-b"))
+            .expect("Failed to fork session");
+        let info = manager.get_session(&fork).unwrap().unwrap();
+        assert_eq!(info.title, "Source (fork)");
+        assert_eq!(info.parent_session_id.as_deref(), Some(source.as_str()));
+        assert_eq!(info.fork_point, Some(2));
+        assert_eq!(info.model.as_deref(), Some("model-b"));
+        assert_eq!(info.user_id.as_deref(), Some("user-123"));
+        assert_eq!(info.working_dir.as_deref(), Some("/tmp"));
+
+        let messages = manager.load_session_messages(&fork).unwrap();
+        assert_eq!(
+            messages,
+            vec![
+                ("user".to_string(), "one".to_string()),
+                ("assistant".to_string(), "two".to_string())
+            ]
+        );
+        // Only the compaction within the fork point carries over
+        let compaction = compactions.latest(&fork).unwrap().unwrap();
+        assert_eq!(compaction.summary, "summary");
+
+        // Both branches continue independently
+        manager.save_message(&fork, "user", "fork turn").unwrap();
+        assert_eq!(manager.load_session_messages(&source).unwrap().len(), 3);
+        assert_eq!(manager.load_session_messages(&fork).unwrap().len(), 3);
+
+        let whole = manager
+            .fork_session(&source, None, Some("Copy"), None)
+            .unwrap();
+        let info = manager.get_session(&whole).unwrap().unwrap();
+        assert_eq!(info.fork_point, Some(3));
+        assert_eq!(info.model.as_deref(), Some("model-a"));
+        assert!(manager.fork_session(&source, Some(4), None, None).is_err());
+        assert!(manager
+            .get_session(&source)
+            .unwrap()
+            .unwrap()
+            .fork_point
+            .is_none());
+    }
 }
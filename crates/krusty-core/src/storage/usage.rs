@@ -0,0 +1,220 @@
+//! Token usage ledger
+//!
+//! Records prompt, completion and cache tokens for every model call along
+//! with the computed cost, and stores optional per-session spending budgets.
+
+use anyhow::Result;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+
+use super::database::Database;
+use crate::ai::types::Usage;
+
+/// A single model call to record
+#[derive(Debug, Clone)]
+pub struct UsageEntry<'a> {
+    pub session_id: &'a str,
+    pub user_id: Option<&'a str>,
+    /// Orchestrator iteration within the session's current run
+    pub turn: usize,
+    pub provider: &'a str,
+    pub model: &'a str,
+    pub usage: &'a Usage,
+    /// None when pricing for the model is unknown
+    pub cost_usd: Option<f64>,
+}
+
+/// Aggregated token counts and cost
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageTotals {
+    pub calls: usize,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub cache_read_tokens: usize,
+    pub cache_write_tokens: usize,
+    pub cost_usd: f64,
+    /// Calls recorded without pricing (not included in `cost_usd`)
+    pub unpriced_calls: usize,
+}
+
+/// Usage totals for one provider/model pair
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelUsage {
+    pub provider: String,
+    pub model: String,
+    #[serde(flatten)]
+    pub totals: UsageTotals,
+}
+
+/// Optional spending limits for a session, in USD
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionBudget {
+    /// Warn once spending reaches this amount
+    pub soft_usd: Option<f64>,
+    /// Stop the agent once spending reaches this amount
+    pub hard_usd: Option<f64>,
+}
+
+/// Result of checking spending against a [`SessionBudget`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BudgetStatus {
+    Within,
+    SoftExceeded { limit_usd: f64 },
+    HardExceeded { limit_usd: f64 },
+}
+
+impl SessionBudget {
+    /// True when neither limit is set
+    pub fn is_empty(&self) -> bool {
+        self.soft_usd.is_none() && self.hard_usd.is_none()
+    }
+
+    /// Check spending against the limits (hard limit takes precedence)
+    pub fn check(&self, spent_usd: f64) -> BudgetStatus {
+        if let Some(limit_usd) = self.hard_usd.filter(|limit| spent_usd >= *limit) {
+            return BudgetStatus::HardExceeded { limit_usd };
+        }
+        if let Some(limit_usd) = self.soft_usd.filter(|limit| spent_usd >= *limit) {
+            return BudgetStatus::SoftExceeded { limit_usd };
+        }
+        BudgetStatus::Within
+    }
+}
+
+const TOTALS_COLUMNS: &str = "COUNT(*),
+     COALESCE(SUM(prompt_tokens), 0),
+     COALESCE(SUM(completion_tokens), 0),
+     COALESCE(SUM(cache_read_tokens), 0),
+     COALESCE(SUM(cache_write_tokens), 0),
+     COALESCE(SUM(cost_usd), 0.0),
+     COALESCE(SUM(cost_usd IS NULL), 0)";
+
+/// Read a [`TOTALS_COLUMNS`] projection starting at `offset`
+fn totals_from_row(row: &Row<'_>, offset: usize) -> rusqlite::Result<UsageTotals> {
+    let count =
+        |i: usize| -> rusqlite::Result<usize> { Ok(row.get::<_, i64>(offset + i)? as usize) };
+    Ok(UsageTotals {
+        calls: count(0)?,
+        prompt_tokens: count(1)?,
+        completion_tokens: count(2)?,
+        cache_read_tokens: count(3)?,
+        cache_write_tokens: count(4)?,
+        cost_usd: row.get(offset + 5)?,
+        unpriced_calls: count(6)?,
+    })
+}
+
+/// SQLite-backed usage ledger
+pub struct UsageLedger<'a> {
+    db: &'a Database,
+}
+
+impl<'a> UsageLedger<'a> {
+    /// Create a new usage ledger with database reference
+    pub fn new(db: &'a Database) -> Self {
+        Self { db }
+    }
+
+    /// Record one model call
+    pub fn record(&self, entry: &UsageEntry<'_>) -> Result<()> {
+        self.db.conn().execute(
+            "INSERT INTO usage_ledger (session_id, user_id, turn, provider, model,
+                 prompt_tokens, completion_tokens, cache_read_tokens, cache_write_tokens,
+                 cost_usd, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                entry.session_id,
+                entry.user_id,
+                entry.turn as i64,
+                entry.provider,
+                entry.model,
+                entry.usage.prompt_tokens as i64,
+                entry.usage.completion_tokens as i64,
+                entry.usage.cache_read_input_tokens as i64,
+                entry.usage.cache_creation_input_tokens as i64,
+                entry.cost_usd,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Totals across every call in a session
+    pub fn session_totals(&self, session_id: &str) -> Result<UsageTotals> {
+        let sql = format!("SELECT {TOTALS_COLUMNS} FROM usage_ledger WHERE session_id = ?1");
+        let totals = self
+            .db
+            .conn()
+            .query_row(&sql, [session_id], |row| totals_from_row(row, 0))?;
+        Ok(totals)
+    }
+
+    /// Per-model totals for a session, most expensive first
+    pub fn session_usage_by_model(&self, session_id: &str) -> Result<Vec<ModelUsage>> {
+        let sql = format!(
+            "SELECT provider, model, {TOTALS_COLUMNS}
+             FROM usage_ledger WHERE session_id = ?1
+             GROUP BY provider, model
+             ORDER BY SUM(cost_usd) DESC, provider, model"
+        );
+        let conn = self.db.conn();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map([session_id], |row| {
+                Ok(ModelUsage {
+                    provider: row.get(0)?,
+                    model: row.get(1)?,
+                    totals: totals_from_row(row, 2)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(rows)
+    }
+
+    /// Totals for a user across all sessions (None = single-tenant usage)
+    ///
+    /// `since` is an RFC 3339 timestamp; only calls at or after it are counted.
+    pub fn user_totals(&self, user_id: Option<&str>, since: Option<&str>) -> Result<UsageTotals> {
+        let sql = format!(
+            "SELECT {TOTALS_COLUMNS} FROM usage_ledger
+             WHERE user_id IS ?1 AND (?2 IS NULL OR created_at >= ?2)"
+        );
+        let totals = self
+            .db
+            .conn()
+            .query_row(&sql, params![user_id, since], |row| totals_from_row(row, 0))?;
+        Ok(totals)
+    }
+
+    /// Get the spending budget for a session
+    pub fn get_budget(&self, session_id: &str) -> Result<SessionBudget> {
+        let budget = self
+            .db
+            .conn()
+            .query_row(
+                "SELECT budget_soft_usd, budget_hard_usd FROM sessions WHERE id = ?1",
+                [session_id],
+                |row| {
+                    Ok(SessionBudget {
+                        soft_usd: row.get(0)?,
+                        hard_usd: row.get(1)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(budget.unwrap_or_default())
+    }
+
+    /// Set (or clear, with None limits) the spending budget for a session
+    pub fn set_budget(&self, session_id: &str, budget: &SessionBudget) -> Result<()> {
+        let updated = self.db.conn().execute(
+            "UPDATE sessions SET budget_soft_usd = ?1, budget_hard_usd = ?2 WHERE id = ?3",
+            params![budget.soft_usd, budget.hard_usd, session_id],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Session not found: {}", session_id);
+        }
+        Ok(())
+    }
+}
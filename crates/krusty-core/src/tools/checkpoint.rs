@@ -0,0 +1,257 @@
+//! File checkpoints for write tools
+//!
+//! The agent loop starts a checkpoint at the beginning of every turn and puts a
+//! [`FileCheckpointer`] in the [`ToolContext`](super::ToolContext). Write tools
+//! call `ctx.checkpoint_file(path)` before modifying a file, so its prior
+//! contents can be diffed against or restored later (`/undo`, `/rewind`).
+
+use std::future::Future;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use similar::TextDiff;
+use tracing::warn;
+
+use crate::storage::{CheckpointStore, Database};
+
+/// Files larger than this are not snapshotted (10 MB, same as the write limit)
+const MAX_SNAPSHOT_SIZE: usize = 10 * 1024 * 1024;
+
+/// Records pre-edit file contents into the current turn's checkpoint
+#[derive(Debug, Clone)]
+pub struct FileCheckpointer {
+    db_path: PathBuf,
+    checkpoint_id: i64,
+}
+
+impl FileCheckpointer {
+    pub fn new(db_path: PathBuf, checkpoint_id: i64) -> Self {
+        Self {
+            db_path,
+            checkpoint_id,
+        }
+    }
+
+    /// Start a checkpoint for a new turn
+    ///
+    /// `message_count` is the number of conversation messages before the
+    /// turn's prompt, used when rewinding the conversation.
+    pub fn begin(
+        db_path: &Path,
+        session_id: &str,
+        message_count: usize,
+        prompt: Option<&str>,
+    ) -> Result<Self> {
+        let db = Database::new(db_path)?;
+        let checkpoint = CheckpointStore::new(&db).create(session_id, message_count, prompt)?;
+        Ok(Self::new(db_path.to_path_buf(), checkpoint.id))
+    }
+
+    /// Snapshot a file before it is modified
+    ///
+    /// Only the first snapshot per file and turn is kept. Failures are logged,
+    /// never surfaced: a missing checkpoint must not block the edit.
+    pub async fn snapshot(&self, path: &Path) {
+        self.snapshot_with(path, || tokio::fs::read(path)).await;
+    }
+
+    /// Snapshot a file, reading its current contents with `read`
+    ///
+    /// For callers whose writes don't go to disk (an editor buffer), so the
+    /// snapshot holds what the write actually replaces.
+    pub async fn snapshot_with<F, Fut>(&self, path: &Path, read: F)
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = std::io::Result<Vec<u8>>>,
+    {
+        let key = path.to_string_lossy();
+        if self.with_store(|store| store.has_file(self.checkpoint_id, &key)) != Some(false) {
+            return;
+        }
+
+        let content = match read().await {
+            Ok(bytes) if bytes.len() > MAX_SNAPSHOT_SIZE => {
+                warn!(
+                    "Checkpoint: skipping {} ({} bytes exceeds snapshot limit)",
+                    key,
+                    bytes.len()
+                );
+                return;
+            }
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                warn!("Checkpoint: failed to read {}: {}", key, e);
+                return;
+            }
+        };
+
+        self.with_store(|store| store.record_file(self.checkpoint_id, &key, content.as_deref()));
+    }
+
+    /// Run a store operation, logging failures (the connection is not held
+    /// across awaits, as `Database` is not `Sync`)
+    fn with_store<T>(&self, f: impl FnOnce(&CheckpointStore<'_>) -> Result<T>) -> Option<T> {
+        let db = Database::new(&self.db_path)
+            .map_err(|e| warn!("Checkpoint: failed to open database: {}", e))
+            .ok()?;
+        f(&CheckpointStore::new(&db))
+            .map_err(|e| warn!("Checkpoint: {}", e))
+            .ok()
+    }
+}
+
+/// Outcome of restoring files to a checkpoint
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RestoreSummary {
+    /// Turn the files were rewound to (its changes are undone too)
+    pub turn: usize,
+    /// Messages before that turn's prompt, for rewinding the conversation
+    pub message_count: usize,
+    /// Files written back with their earlier contents
+    pub restored: Vec<String>,
+    /// Files removed because they did not exist before
+    pub deleted: Vec<String>,
+    /// Files that could not be restored, with the error
+    pub failed: Vec<String>,
+}
+
+/// Restore every file modified in turns >= `turn` to its state before `turn`
+///
+/// Checkpoints for those turns are dropped once all files are restored; on
+/// partial failure they are kept so the restore can be retried.
+pub fn restore_files(db: &Database, session_id: &str, turn: usize) -> Result<RestoreSummary> {
+    let store = CheckpointStore::new(db);
+    let checkpoint = store
+        .get(session_id, turn)?
+        .ok_or_else(|| anyhow!("No checkpoint for turn {}", turn))?;
+
+    let mut summary = RestoreSummary {
+        turn,
+        message_count: checkpoint.message_count,
+        ..Default::default()
+    };
+
+    for snapshot in store.snapshots_since(session_id, turn)? {
+        let path = Path::new(&snapshot.path);
+        let result = match &snapshot.content {
+            Some(content) => path
+                .parent()
+                .map_or(Ok(()), std::fs::create_dir_all)
+                .and_then(|_| std::fs::write(path, content))
+                .map(|_| summary.restored.push(snapshot.path.clone())),
+            None if path.exists() => {
+                std::fs::remove_file(path).map(|_| summary.deleted.push(snapshot.path.clone()))
+            }
+            None => Ok(()),
+        };
+        if let Err(e) = result {
+            summary.failed.push(format!("{}: {}", snapshot.path, e));
+        }
+    }
+
+    if summary.failed.is_empty() {
+        store.delete_since(session_id, turn)?;
+    }
+    Ok(summary)
+}
+
+/// Unified diff of one file between a checkpoint and the working tree
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiff {
+    pub path: String,
+    pub diff: String,
+}
+
+/// Diff the files changed since `turn` began against their current contents
+///
+/// Files that are unchanged (e.g. already restored) are omitted.
+pub fn diff_files(db: &Database, session_id: &str, turn: usize) -> Result<Vec<FileDiff>> {
+    let store = CheckpointStore::new(db);
+    if store.get(session_id, turn)?.is_none() {
+        return Err(anyhow!("No checkpoint for turn {}", turn));
+    }
+
+    let mut diffs = Vec::new();
+    for snapshot in store.snapshots_since(session_id, turn)? {
+        let old = snapshot
+            .content
+            .as_deref()
+            .map(String::from_utf8_lossy)
+            .unwrap_or_default();
+        let new = std::fs::read(&snapshot.path)
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default();
+        if old == new {
+            continue;
+        }
+
+        let diff = TextDiff::from_lines(old.as_ref(), new.as_str())
+            .unified_diff()
+            .context_radius(3)
+            .header(&snapshot.path, &snapshot.path)
+            .to_string();
+        diffs.push(FileDiff {
+            path: snapshot.path,
+            diff,
+        });
+    }
+    Ok(diffs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SessionManager;
+
+    #[tokio::test]
+    async fn test_snapshot_restore_roundtrip() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let sessions = SessionManager::new(Database::new(&db_path).unwrap());
+        let session_id = sessions.create_session("Checkpoints", None, None).unwrap();
+
+        let edited = temp_dir.path().join("src/lib.rs");
+        let created = temp_dir.path().join("src/new.rs");
+        std::fs::create_dir_all(edited.parent().unwrap()).unwrap();
+        std::fs::write(&edited, "v1\n").unwrap();
+
+        // Turn 1 edits lib.rs twice; only the first snapshot is kept
+        let turn1 = FileCheckpointer::begin(&db_path, &session_id, 0, Some("edit")).unwrap();
+        turn1.snapshot(&edited).await;
+        std::fs::write(&edited, "v2\n").unwrap();
+        turn1.snapshot(&edited).await;
+        std::fs::write(&edited, "v3\n").unwrap();
+
+        // Turn 2 creates a new file
+        let turn2 = FileCheckpointer::begin(&db_path, &session_id, 2, Some("add")).unwrap();
+        turn2.snapshot(&created).await;
+        std::fs::write(&created, "new\n").unwrap();
+
+        let db = sessions.db();
+        let checkpoints = CheckpointStore::new(db).list(&session_id).unwrap();
+        assert_eq!(checkpoints.len(), 2);
+        assert_eq!(checkpoints[0].files.len(), 1);
+
+        let diffs = diff_files(db, &session_id, 1).unwrap();
+        assert_eq!(diffs.len(), 2);
+        assert!(diffs.iter().any(|d| d.diff.contains("-v1")));
+
+        // Undo turn 2 only
+        let summary = restore_files(db, &session_id, 2).unwrap();
+        assert_eq!(summary.deleted.len(), 1);
+        assert!(!created.exists());
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "v3\n");
+
+        // Rewind to before turn 1
+        let summary = restore_files(db, &session_id, 1).unwrap();
+        assert_eq!(summary.restored.len(), 1);
+        assert_eq!(summary.message_count, 0);
+        assert_eq!(std::fs::read_to_string(&edited).unwrap(), "v1\n");
+        assert!(CheckpointStore::new(db)
+            .list(&session_id)
+            .unwrap()
+            .is_empty());
+    }
+}
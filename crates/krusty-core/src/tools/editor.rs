@@ -0,0 +1,62 @@
+//! Editor-side file and terminal access
+//!
+//! When Krusty runs inside an editor (over ACP), the editor can serve file
+//! reads and writes from its open buffers and run commands in its own
+//! terminals. Tools prefer the editor when it offers a capability and fall
+//! back to local execution otherwise.
+
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+/// Result of a command run in an editor terminal
+#[derive(Debug, Clone)]
+pub struct EditorCommandOutput {
+    /// Combined stdout/stderr as captured by the terminal
+    pub output: String,
+    /// Exit code, or None if the command was killed by a signal
+    pub exit_code: Option<i32>,
+    /// Whether the command was killed for exceeding its timeout
+    pub timed_out: bool,
+}
+
+/// The editor could not create a terminal, so the command never ran
+///
+/// This is the only `run_command` failure after which running the command
+/// locally is safe; later failures may come after the command already ran.
+#[derive(Debug, thiserror::Error)]
+#[error("Editor could not create a terminal: {0}")]
+pub struct TerminalUnavailable(pub String);
+
+/// File and terminal operations served by the editor hosting the agent
+#[async_trait]
+pub trait EditorClient: Send + Sync {
+    /// Whether the editor serves file reads (including unsaved buffers)
+    fn can_read_files(&self) -> bool;
+
+    /// Whether the editor applies file writes (landing in its undo stack)
+    fn can_write_files(&self) -> bool;
+
+    /// Whether the editor runs commands in its own terminals
+    fn can_run_commands(&self) -> bool;
+
+    /// Read a text file as the editor sees it
+    async fn read_text_file(&self, path: &Path) -> anyhow::Result<String>;
+
+    /// Write a text file through the editor
+    async fn write_text_file(&self, path: &Path, content: &str) -> anyhow::Result<()>;
+
+    /// Run a shell command in an editor terminal and wait for it to finish
+    ///
+    /// `tool_use_id` lets the editor show the terminal inside the tool call.
+    /// Fails with [`TerminalUnavailable`] if the terminal couldn't be created.
+    async fn run_command(
+        &self,
+        command: &str,
+        cwd: &Path,
+        env: Vec<(String, String)>,
+        timeout: Duration,
+        tool_use_id: Option<&str>,
+    ) -> anyhow::Result<EditorCommandOutput>;
+}
@@ -134,12 +134,20 @@ impl Tool for ApplyPatchTool {
             files_deleted.len()
         );
 
-        ToolResult::success_data(json!({
+        let mut data = json!({
             "message": msg,
             "files_modified": files_modified,
             "files_created": files_created,
             "files_deleted": files_deleted,
-        }))
+        });
+        let touched: Vec<_> = files_modified
+            .iter()
+            .chain(&files_created)
+            .map(|p| ctx.resolve_path(p))
+            .collect();
+        ctx.attach_diagnostics(&mut data, &touched).await;
+
+        ToolResult::success_data(data)
     }
 }
 
@@ -309,7 +317,7 @@ async fn apply_update(path: &str, chunks: &[Chunk], ctx: &ToolContext) -> Result
     }
 
     // Apply replacements in reverse order to preserve indices
-    replacements.sort_by(|a, b| b.0.cmp(&a.0));
+    replacements.sort_by_key(|r| std::cmp::Reverse(r.0));
 
     for (start, end, new_lines) in replacements {
         let end = end.min(lines.len());
@@ -324,6 +332,7 @@ async fn apply_update(path: &str, chunks: &[Chunk], ctx: &ToolContext) -> Result
         new_content
     };
 
+    ctx.checkpoint_file(&resolved).await;
     fs::write(&resolved, &final_content)
         .await
         .map_err(|e| format!("Failed to write: {}", e))?;
@@ -343,6 +352,7 @@ async fn apply_add(path: &str, content: &str, ctx: &ToolContext) -> Result<(), S
             .map_err(|e| format!("Failed to create directories: {}", e))?;
     }
 
+    ctx.checkpoint_file(&resolved).await;
     fs::write(&resolved, content)
         .await
         .map_err(|e| format!("Failed to write new file: {}", e))?;
@@ -355,6 +365,7 @@ async fn apply_delete(path: &str, ctx: &ToolContext) -> Result<(), String> {
         .sandboxed_resolve(path)
         .map_err(|e| format!("Path error: {}", e))?;
 
+    ctx.checkpoint_file(&resolved).await;
     fs::remove_file(&resolved)
         .await
         .map_err(|e| format!("Failed to delete: {}", e))?;
@@ -14,7 +14,7 @@ use tokio::time::{sleep, timeout};
 
 use crate::tools::registry::{Tool, ToolOutputChunk};
 use crate::tools::truncation;
-use crate::tools::{parse_params, ToolContext, ToolResult};
+use crate::tools::{parse_params, TerminalUnavailable, ToolContext, ToolResult};
 
 const MAX_OUTPUT_LINES: usize = 2000;
 const MAX_OUTPUT_BYTES: usize = 50_000; // 50KB
@@ -176,16 +176,23 @@ fn build_shell_command(command: &str, ctx: &ToolContext) -> Command {
         c
     };
 
-    cmd.env("NO_COLOR", "1");
+    cmd.envs(shell_env(ctx));
+    cmd.current_dir(&ctx.working_dir);
+    cmd
+}
 
+/// Environment variables set for every command
+fn shell_env(ctx: &ToolContext) -> Vec<(String, String)> {
+    let mut env = vec![("NO_COLOR".to_string(), "1".to_string())];
     if let Some(ref identity) = ctx.git_identity {
-        for (key, val) in identity.env_vars() {
-            cmd.env(key, val);
-        }
+        env.extend(
+            identity
+                .env_vars()
+                .into_iter()
+                .map(|(key, val)| (key.to_string(), val.to_string())),
+        );
     }
-
-    cmd.current_dir(&ctx.working_dir);
-    cmd
+    env
 }
 
 fn configure_foreground_process_group(cmd: &mut Command) {
@@ -227,9 +234,9 @@ async fn join_reader_with_timeout(mut handle: tokio::task::JoinHandle<()>) {
         .is_err()
     {
         handle.abort();
+        // A finished handle must not be polled again
+        let _ = handle.await;
     }
-
-    let _ = handle.await;
 }
 
 #[cfg(unix)]
@@ -383,6 +390,23 @@ async fn execute_foreground(
         });
     }
 
+    command_result(
+        combined_output,
+        exit_code,
+        killed,
+        timed_out,
+        timeout_duration,
+    )
+}
+
+/// Build the tool result for a finished foreground command
+fn command_result(
+    combined_output: String,
+    exit_code: i32,
+    killed: bool,
+    timed_out: bool,
+    timeout_duration: Duration,
+) -> ToolResult {
     let processed = process_output(combined_output);
     let metadata = Some(json!({
         "exit_code": exit_code,
@@ -547,6 +571,42 @@ impl Tool for BashTool {
             }
         }
 
+        let timeout_ms = params.timeout.unwrap_or(30_000).min(600_000);
+        let timeout_duration = Duration::from_millis(timeout_ms);
+
+        // Run in the editor's terminal when it offers one
+        if let Some(editor) = ctx.editor.as_ref().filter(|e| e.can_run_commands()) {
+            let env = shell_env(ctx);
+            match editor
+                .run_command(
+                    &effective_command,
+                    &ctx.working_dir,
+                    env,
+                    timeout_duration,
+                    ctx.tool_use_id.as_deref(),
+                )
+                .await
+            {
+                Ok(out) => {
+                    let exit_code = out.exit_code.unwrap_or(-1);
+                    return command_result(
+                        out.output,
+                        exit_code,
+                        out.timed_out,
+                        out.timed_out,
+                        timeout_duration,
+                    );
+                }
+                // Nothing ran yet, so running locally can't run it twice
+                Err(e) if e.is::<TerminalUnavailable>() => {
+                    tracing::warn!("Editor terminal failed, running locally: {}", e);
+                }
+                Err(e) => {
+                    return ToolResult::error(format!("Editor terminal failed: {}", e));
+                }
+            }
+        }
+
         // Foreground execution with bounded output capture.
         let mut cmd = build_shell_command(&effective_command, ctx);
         configure_foreground_process_group(&mut cmd);
@@ -555,9 +615,6 @@ impl Tool for BashTool {
         cmd.stdout(Stdio::piped());
         cmd.stderr(Stdio::piped());
 
-        let timeout_ms = params.timeout.unwrap_or(30_000).min(600_000);
-        let timeout_duration = Duration::from_millis(timeout_ms);
-
         let stream = match (ctx.output_tx.as_ref(), ctx.tool_use_id.as_ref()) {
             (Some(tx), Some(id)) => Some(StreamContext {
                 output_tx: tx.clone(),
@@ -643,6 +700,75 @@ mod tests {
         assert!(parsed.is_none());
     }
 
+    /// Editor whose terminal fails either before or after the command runs
+    struct FailingTerminal {
+        created: bool,
+    }
+
+    #[async_trait]
+    impl crate::tools::EditorClient for FailingTerminal {
+        fn can_read_files(&self) -> bool {
+            false
+        }
+
+        fn can_write_files(&self) -> bool {
+            false
+        }
+
+        fn can_run_commands(&self) -> bool {
+            true
+        }
+
+        async fn read_text_file(&self, _path: &std::path::Path) -> anyhow::Result<String> {
+            anyhow::bail!("reads not supported")
+        }
+
+        async fn write_text_file(
+            &self,
+            _path: &std::path::Path,
+            _content: &str,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!("writes not supported")
+        }
+
+        async fn run_command(
+            &self,
+            _command: &str,
+            _cwd: &std::path::Path,
+            _env: Vec<(String, String)>,
+            _timeout: Duration,
+            _tool_use_id: Option<&str>,
+        ) -> anyhow::Result<crate::tools::EditorCommandOutput> {
+            if self.created {
+                anyhow::bail!("terminal output unavailable")
+            }
+            Err(TerminalUnavailable("terminals disabled".to_string()).into())
+        }
+    }
+
+    #[tokio::test]
+    async fn editor_terminal_falls_back_only_before_creation() {
+        let dir = tempfile::tempdir().unwrap();
+        let marker = dir.path().join("ran");
+        let params = json!({ "command": "touch ran" });
+        let ctx = |created| {
+            ToolContext {
+                working_dir: dir.path().to_path_buf(),
+                ..Default::default()
+            }
+            .with_editor(Arc::new(FailingTerminal { created }))
+        };
+
+        // The command may already have run in the editor: don't rerun it
+        let result = BashTool.execute(params.clone(), &ctx(true)).await;
+        assert!(result.is_error);
+        assert!(!marker.exists());
+
+        let result = BashTool.execute(params, &ctx(false)).await;
+        assert!(!result.is_error, "{}", result.output);
+        assert!(marker.exists());
+    }
+
     #[test]
     fn bounded_output_buffer_keeps_recent_lines() {
         let mut buffer = BoundedOutputBuffer::new(3, 1024);
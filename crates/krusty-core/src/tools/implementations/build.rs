@@ -2,6 +2,8 @@
 //!
 //! This tool spawns a team of Opus agents that work together to build code.
 //! Builders coordinate via SharedBuildContext to share types, modules, and file locks.
+//! By default each builder works in its own git worktree (see `BuildWorktrees`)
+//! and the results are applied back once the swarm finishes.
 
 use async_trait::async_trait;
 use serde::Deserialize;
@@ -9,6 +11,7 @@ use serde_json::{json, Value};
 use std::sync::Arc;
 use tracing::{debug, info, warn};
 
+use crate::agent::build_worktree::{BuildWorktrees, BuilderMerge, BuilderWorktree, MergeStatus};
 use crate::agent::subagent::{SubAgentPool, SubAgentTask};
 use crate::agent::{AgentCancellation, SharedBuildContext};
 use crate::ai::client::AiClient;
@@ -30,6 +33,21 @@ impl BuildTool {
     }
 }
 
+/// Where builders make their edits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Isolation {
+    /// One git worktree and branch per builder, merged back at the end
+    #[default]
+    Worktree,
+    /// Everyone edits the working tree, coordinated by file locks
+    Shared,
+}
+
+const ISOLATION_NOTE: &str = "\n\nISOLATION: You work in your own git worktree. Other builders' \
+     files will NOT appear while you work; rely on [SHARED TYPES] and assume their modules \
+     exist. Your changes are merged into the project when the build finishes.";
+
 #[derive(Deserialize)]
 struct Params {
     /// The overall build goal/requirements
@@ -51,6 +69,10 @@ struct Params {
     /// Index i maps to components[i]
     #[serde(default)]
     task_ids: Option<Vec<String>>,
+
+    /// Worktree per builder (default) or the shared working tree
+    #[serde(default)]
+    isolation: Isolation,
 }
 
 #[async_trait]
@@ -69,7 +91,9 @@ impl Tool for BuildTool {
          2-3 for tightly coupled components (shared files), \
          5-10 for independent components (separate files). \
          Default: matches component count (natural parallelism). \
-         Builders coordinate via file locking - more concurrency is fine if components don't share files."
+         Builders coordinate via file locking - more concurrency is fine if components don't share files. \
+         By default each builder gets its own git worktree and the changes are merged back at the end, \
+         with conflicts left on a branch; set isolation to 'shared' to edit the working tree directly."
     }
 
     fn parameters_schema(&self) -> Value {
@@ -95,6 +119,11 @@ impl Tool for BuildTool {
                     "description": "Max parallel builders. Default: component count. Use 2-3 for tightly coupled code (shared files), 5-10 for independent modules.",
                     "minimum": 1,
                     "maximum": 20
+                },
+                "isolation": {
+                    "type": "string",
+                    "enum": ["worktree", "shared"],
+                    "description": "'worktree' (default): each builder edits its own git worktree, merged back when all finish. 'shared': all builders edit the working tree, coordinated by file locks. Outside a git repository builders always share."
                 }
             },
             "required": ["prompt"],
@@ -194,6 +223,19 @@ impl Tool for BuildTool {
             );
         }
 
+        // Move each builder into its own worktree
+        let isolation = match params.isolation {
+            Isolation::Worktree => isolate_builders(&ctx.working_dir, &mut tasks),
+            Isolation::Shared => None,
+        };
+        // Builders in the shared tree checkpoint their own writes; isolated
+        // ones are checkpointed by repo path when their worktree is merged
+        if isolation.is_none() {
+            for task in &mut tasks {
+                task.checkpointer = ctx.checkpointer.clone();
+            }
+        }
+
         info!("Build tool: Created {} builder tasks", tasks.len());
         for (i, task) in tasks.iter().enumerate() {
             debug!("Builder {}: id={}, name={}", i, task.id, task.name);
@@ -221,6 +263,22 @@ impl Tool for BuildTool {
 
         info!("Build tool: Kraken returned {} results", results.len());
 
+        // Apply each builder's worktree back, in builder order
+        let mut merges: Vec<BuilderMerge> = Vec::new();
+        if let Some((worktrees, builder_trees)) = &isolation {
+            for tree in builder_trees {
+                let succeeded = results
+                    .iter()
+                    .any(|r| r.task_id == tree.task_id && r.success);
+                merges.push(
+                    worktrees
+                        .merge(tree, succeeded, ctx.checkpointer.as_ref())
+                        .await,
+                );
+            }
+            worktrees.cleanup(builder_trees, &merges);
+        }
+
         // Get final stats from context
         let stats = context.stats();
 
@@ -236,6 +294,9 @@ impl Tool for BuildTool {
                 output.push_str(&format!("\n## Builder: {}\n", result.task_id));
                 output.push_str(&result.output);
                 output.push('\n');
+                if let Some(merge) = merges.iter().find(|m| m.task_id == result.task_id) {
+                    output.push_str(&format!("**Diff**: {}\n", merge.summary_line()));
+                }
             } else if let Some(err) = &result.error {
                 errors.push(format!("{}: {}", result.task_id, err));
             }
@@ -279,6 +340,30 @@ impl Tool for BuildTool {
             }
         }
 
+        if isolation.is_some() {
+            let applied = merges
+                .iter()
+                .filter(|m| m.status == MergeStatus::Applied)
+                .count();
+            summary.push_str(&format!(
+                "\n**Isolation**: git worktrees, {}/{} builders applied",
+                applied,
+                merges.len()
+            ));
+            for merge in merges.iter().filter(|m| m.kept_branch()) {
+                let reason = match &merge.status {
+                    MergeStatus::Conflict(detail) | MergeStatus::Skipped(detail) => detail.as_str(),
+                    _ => "",
+                };
+                summary.push_str(&format!(
+                    "\n- `{}` kept on branch `{}` for a manual merge: {}",
+                    merge.task_id,
+                    merge.branch,
+                    reason.lines().next().unwrap_or_default()
+                ));
+            }
+        }
+
         output.push_str(&summary);
 
         if !errors.is_empty() {
@@ -292,3 +377,42 @@ impl Tool for BuildTool {
         }
     }
 }
+
+/// Give every task its own worktree; `None` (shared tree) if that fails
+fn isolate_builders(
+    working_dir: &std::path::Path,
+    tasks: &mut [SubAgentTask],
+) -> Option<(BuildWorktrees, Vec<BuilderWorktree>)> {
+    let worktrees = match BuildWorktrees::create(working_dir) {
+        Ok(Some(worktrees)) => worktrees,
+        Ok(None) => {
+            info!("Build tool: not a git repository, builders share the working tree");
+            return None;
+        }
+        Err(e) => {
+            warn!("Build tool: worktree isolation unavailable: {}", e);
+            return None;
+        }
+    };
+
+    let mut builder_trees = Vec::with_capacity(tasks.len());
+    for task in tasks.iter() {
+        match worktrees.add(&task.id) {
+            Ok(tree) => builder_trees.push(tree),
+            Err(e) => {
+                warn!(
+                    "Build tool: failed to create worktree for {}: {}",
+                    task.id, e
+                );
+                worktrees.cleanup(&builder_trees, &[]);
+                return None;
+            }
+        }
+    }
+
+    for (task, tree) in tasks.iter_mut().zip(&builder_trees) {
+        task.working_dir = tree.working_dir.clone();
+        task.prompt.push_str(ISOLATION_NOTE);
+    }
+    Some((worktrees, builder_trees))
+}
@@ -3,7 +3,6 @@
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tokio::fs;
 
 use similar::TextDiff;
 
@@ -80,7 +79,7 @@ impl Tool for EditTool {
             return ToolResult::error(format!("File not found: {}", path.display()));
         }
 
-        let content = match fs::read_to_string(&path).await {
+        let content = match ctx.read_text(&path).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(format!("Failed to read file: {}", e)),
         };
@@ -98,13 +97,16 @@ impl Tool for EditTool {
             let new_content = content.replace(&params.old_string, &params.new_string);
             let diff = generate_compact_diff(&content, &new_content, &path);
 
-            match fs::write(&path, &new_content).await {
+            ctx.checkpoint_file(&path).await;
+            match ctx.write_text(&path, &new_content).await {
                 Ok(_) => {
-                    let data = json!({
+                    let mut data = json!({
                         "message": format!("Replaced {} occurrence(s)", count),
                         "replacements": count,
                         "file_path": path.display().to_string()
                     });
+                    ctx.attach_diagnostics(&mut data, std::slice::from_ref(&path))
+                        .await;
 
                     ToolResult::success_data_with(data, Vec::new(), Some(diff), None)
                 }
@@ -141,7 +143,8 @@ impl Tool for EditTool {
 
                     let diff = generate_compact_diff(&content, &new_content, &path);
 
-                    match fs::write(&path, &new_content).await {
+                    ctx.checkpoint_file(&path).await;
+                    match ctx.write_text(&path, &new_content).await {
                         Ok(_) => {
                             let mut msg = "Replaced 1 occurrence".to_string();
                             let mut warnings = Vec::new();
@@ -150,12 +153,14 @@ impl Tool for EditTool {
                                 warnings.push(format!("Used fuzzy matching pass {}", m.pass));
                             }
 
-                            let data = json!({
+                            let mut data = json!({
                                 "message": msg,
                                 "replacements": 1,
                                 "file_path": path.display().to_string(),
                                 "match_pass": m.pass
                             });
+                            ctx.attach_diagnostics(&mut data, std::slice::from_ref(&path))
+                                .await;
 
                             ToolResult::success_data_with(data, warnings, Some(diff), None)
                         }
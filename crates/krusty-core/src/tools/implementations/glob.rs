@@ -92,7 +92,7 @@ impl Tool for GlobTool {
             })
             .collect();
 
-        files.sort_by(|a, b| b.1.cmp(&a.1));
+        files.sort_by_key(|f| std::cmp::Reverse(f.1));
 
         let matches: Vec<String> = files
             .iter()
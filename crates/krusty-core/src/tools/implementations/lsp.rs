@@ -0,0 +1,501 @@
+//! LSP tools - diagnostics, definition, references, hover and rename
+//!
+//! Thin wrappers over `LspManager`. Positions are 1-based line/column, the
+//! same numbering the `read` tool shows.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs;
+
+use crate::lsp::protocol::{
+    apply_text_edits, uri_to_path, utf16_to_column, workspace_edit_changes, Location,
+};
+use crate::lsp::{diagnostics_json, LspClient, LspManager, Position};
+use crate::tools::registry::Tool;
+use crate::tools::{parse_params, ToolContext, ToolResult};
+
+/// Cap on locations returned by `definition`/`references`
+const MAX_LOCATIONS: usize = 200;
+
+#[derive(Deserialize)]
+struct PositionParams {
+    file_path: String,
+    line: u32,
+    column: u32,
+}
+
+fn position_schema(extra: Value) -> Value {
+    let mut schema = json!({
+        "type": "object",
+        "properties": {
+            "file_path": {
+                "type": "string",
+                "description": "Path to the file containing the symbol"
+            },
+            "line": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "1-based line number of the symbol"
+            },
+            "column": {
+                "type": "integer",
+                "minimum": 1,
+                "description": "1-based column of any character within the symbol"
+            }
+        },
+        "required": ["file_path", "line", "column"],
+        "additionalProperties": false
+    });
+    if let Value::Object(extra) = extra {
+        for (key, value) in extra {
+            schema["properties"][&key] = value;
+        }
+    }
+    schema
+}
+
+/// Open the file in its language server and convert the position
+async fn locate(
+    manager: &LspManager,
+    ctx: &ToolContext,
+    params: &PositionParams,
+) -> Result<(Arc<LspClient>, PathBuf, Position), ToolResult> {
+    let path = ctx
+        .sandboxed_resolve(&params.file_path)
+        .map_err(|e| ToolResult::error(format!("Access denied: {}", e)))?;
+    let text = fs::read_to_string(&path)
+        .await
+        .map_err(|e| ToolResult::error(format!("Failed to read file: {}", e)))?;
+    let client = manager
+        .open(&ctx.working_dir, &path)
+        .await
+        .map_err(|e| ToolResult::error_with_code("lsp_unavailable", e))?;
+    let position = Position::from_line_column(&text, params.line, params.column);
+    Ok((client, path, position))
+}
+
+/// Render locations with 1-based positions and the source line they point at
+async fn locations_json(root: &Path, locations: &[Location]) -> Vec<Value> {
+    let mut files: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut out = Vec::new();
+
+    for location in locations.iter().take(MAX_LOCATIONS) {
+        let Some(path) = uri_to_path(&location.uri) else {
+            continue;
+        };
+        if !files.contains_key(&path) {
+            let text = fs::read_to_string(&path).await.ok();
+            files.insert(path.clone(), text);
+        }
+        let start = location.range.start;
+        let line_text = files[&path]
+            .as_deref()
+            .and_then(|t| t.lines().nth(start.line as usize))
+            .unwrap_or("");
+
+        out.push(json!({
+            "file": path.strip_prefix(root).unwrap_or(&path).display().to_string(),
+            "line": start.line + 1,
+            "column": utf16_to_column(line_text, start.character),
+            "text": line_text.trim(),
+        }));
+    }
+    out
+}
+
+pub struct DiagnosticsTool {
+    manager: Arc<LspManager>,
+}
+
+impl DiagnosticsTool {
+    pub fn new(manager: Arc<LspManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[derive(Deserialize)]
+struct DiagnosticsParams {
+    file_path: Option<String>,
+}
+
+#[async_trait]
+impl Tool for DiagnosticsTool {
+    fn name(&self) -> &str {
+        "diagnostics"
+    }
+
+    fn description(&self) -> &str {
+        "Get compiler/type-checker errors and warnings from the language server. With file_path, opens that file and returns its diagnostics; without, returns everything reported so far in the workspace."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "file_path": {
+                    "type": "string",
+                    "description": "File to check (omit for all known workspace diagnostics)"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<DiagnosticsParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+
+        let files = match params.file_path {
+            Some(file_path) => {
+                let path = match ctx.sandboxed_resolve(&file_path) {
+                    Ok(p) => p,
+                    Err(e) => return ToolResult::error(format!("Access denied: {}", e)),
+                };
+                match self.manager.file_diagnostics(&ctx.working_dir, &path).await {
+                    Ok(file) => vec![file],
+                    Err(e) => return ToolResult::error_with_code("lsp_unavailable", e),
+                }
+            }
+            None => self.manager.workspace_diagnostics(&ctx.working_dir).await,
+        };
+
+        let diagnostics = diagnostics_json(&ctx.working_dir, &files);
+        let count = diagnostics.as_array().map_or(0, Vec::len);
+        ToolResult::success_data(json!({
+            "message": if count == 0 {
+                "No diagnostics reported".to_string()
+            } else {
+                format!("{} diagnostic(s)", count)
+            },
+            "count": count,
+            "diagnostics": diagnostics,
+        }))
+    }
+}
+
+pub struct DefinitionTool {
+    manager: Arc<LspManager>,
+}
+
+impl DefinitionTool {
+    pub fn new(manager: Arc<LspManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl Tool for DefinitionTool {
+    fn name(&self) -> &str {
+        "definition"
+    }
+
+    fn description(&self) -> &str {
+        "Go to definition: find where the symbol at a file position is defined, using the language server."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        position_schema(Value::Null)
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<PositionParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let (client, path, position) = match locate(&self.manager, ctx, &params).await {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+
+        match client.definition(&path, position).await {
+            Ok(locations) => {
+                let locations = locations_json(&ctx.working_dir, &locations).await;
+                ToolResult::success_data(json!({
+                    "message": format!("{} definition(s)", locations.len()),
+                    "locations": locations,
+                }))
+            }
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+pub struct ReferencesTool {
+    manager: Arc<LspManager>,
+}
+
+impl ReferencesTool {
+    pub fn new(manager: Arc<LspManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[derive(Deserialize)]
+struct ReferencesParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    #[serde(default = "default_true")]
+    include_declaration: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[async_trait]
+impl Tool for ReferencesTool {
+    fn name(&self) -> &str {
+        "references"
+    }
+
+    fn description(&self) -> &str {
+        "Find all references to the symbol at a file position, using the language server. More precise than grep for renamed, shadowed or common identifiers."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        position_schema(json!({
+            "include_declaration": {
+                "type": "boolean",
+                "description": "Include the declaration itself (default: true)"
+            }
+        }))
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<ReferencesParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let (client, path, position) = match locate(&self.manager, ctx, &params.position).await {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+
+        match client
+            .references(&path, position, params.include_declaration)
+            .await
+        {
+            Ok(locations) => {
+                let total = locations.len();
+                let locations = locations_json(&ctx.working_dir, &locations).await;
+                ToolResult::success_data(json!({
+                    "message": format!("{} reference(s)", total),
+                    "count": total,
+                    "truncated": total > locations.len(),
+                    "locations": locations,
+                }))
+            }
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+pub struct HoverTool {
+    manager: Arc<LspManager>,
+}
+
+impl HoverTool {
+    pub fn new(manager: Arc<LspManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl Tool for HoverTool {
+    fn name(&self) -> &str {
+        "hover"
+    }
+
+    fn description(&self) -> &str {
+        "Show the type signature and documentation of the symbol at a file position, using the language server."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        position_schema(Value::Null)
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<PositionParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let (client, path, position) = match locate(&self.manager, ctx, &params).await {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+
+        match client.hover(&path, position).await {
+            Ok(Some(contents)) => ToolResult::success_data(json!({ "contents": contents })),
+            Ok(None) => ToolResult::success_data(json!({
+                "message": "No hover information at this position",
+                "contents": null,
+            })),
+            Err(e) => ToolResult::error(e),
+        }
+    }
+}
+
+pub struct RenameTool {
+    manager: Arc<LspManager>,
+}
+
+impl RenameTool {
+    pub fn new(manager: Arc<LspManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[derive(Deserialize)]
+struct RenameParams {
+    #[serde(flatten)]
+    position: PositionParams,
+    new_name: String,
+}
+
+#[async_trait]
+impl Tool for RenameTool {
+    fn name(&self) -> &str {
+        "rename"
+    }
+
+    fn description(&self) -> &str {
+        "Rename the symbol at a file position across the workspace, using the language server. Edits every file that refers to it."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        position_schema(json!({
+            "new_name": {
+                "type": "string",
+                "description": "The new name for the symbol"
+            }
+        }))
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<RenameParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        if params.new_name.trim().is_empty() {
+            return ToolResult::invalid_parameters("new_name must not be empty");
+        }
+        let (client, path, position) = match locate(&self.manager, ctx, &params.position).await {
+            Ok(found) => found,
+            Err(e) => return e,
+        };
+
+        let edit = match client.rename(&path, position, &params.new_name).await {
+            Ok(edit) => edit,
+            Err(e) => return ToolResult::error(e),
+        };
+        let (changes, skipped) = workspace_edit_changes(&edit);
+        if changes.is_empty() {
+            return ToolResult::error("Language server returned no edits for this rename");
+        }
+
+        // Validate every target before touching anything
+        let mut targets = Vec::new();
+        for (uri, edits) in changes {
+            let Some(target) = uri_to_path(&uri) else {
+                return ToolResult::error(format!("Unsupported URI in rename edit: {}", uri));
+            };
+            if !ctx.is_path_allowed(&target) {
+                return ToolResult::error(format!(
+                    "Access denied: rename would edit {} outside workspace",
+                    target.display()
+                ));
+            }
+            targets.push((target, edits));
+        }
+
+        let mut files = Vec::new();
+        let mut edit_count = 0;
+        for (target, edits) in &targets {
+            let text = match fs::read_to_string(target).await {
+                Ok(t) => t,
+                Err(e) => {
+                    return ToolResult::error(format!("Failed to read {}: {}", target.display(), e))
+                }
+            };
+            ctx.checkpoint_file(target).await;
+            if let Err(e) = fs::write(target, apply_text_edits(&text, edits)).await {
+                return ToolResult::error(format!("Failed to write {}: {}", target.display(), e));
+            }
+            edit_count += edits.len();
+            files.push(target.clone());
+        }
+
+        let mut data = json!({
+            "message": format!("Renamed to '{}': {} edit(s) in {} file(s)", params.new_name, edit_count, files.len()),
+            "files_modified": files
+                .iter()
+                .map(|f| f.strip_prefix(&ctx.working_dir).unwrap_or(f).display().to_string())
+                .collect::<Vec<_>>(),
+            "edits": edit_count,
+        });
+        let diagnostics = self
+            .manager
+            .diagnostics_after_edit(&ctx.working_dir, &files)
+            .await;
+        if !diagnostics.is_empty() {
+            data["diagnostics"] = diagnostics_json(&ctx.working_dir, &diagnostics);
+        }
+
+        let warnings = skipped
+            .into_iter()
+            .map(|op| format!("Skipped unsupported file operation: {}", op))
+            .collect();
+        ToolResult::success_data_with(data, warnings, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::protocol::path_to_uri;
+
+    #[tokio::test]
+    async fn test_locations_json_formatting() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("src/lib.rs");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "fn a() {}\n    let é = b();\n").unwrap();
+
+        let location = |path: &Path, line: u32, character: u32| -> Location {
+            serde_json::from_value(json!({
+                "uri": path_to_uri(path),
+                "range": {
+                    "start": { "line": line, "character": character },
+                    "end": { "line": line, "character": character + 1 }
+                }
+            }))
+            .unwrap()
+        };
+        let locations = vec![
+            location(&file, 1, 12),
+            location(&dir.path().join("gone.rs"), 0, 0),
+            Location {
+                uri: "untitled:1".to_string(),
+                ..location(&file, 0, 0)
+            },
+        ];
+
+        let json = locations_json(dir.path(), &locations).await;
+        assert_eq!(json.len(), 2);
+        assert_eq!(
+            json[0],
+            json!({ "file": "src/lib.rs", "line": 2, "column": 13, "text": "let é = b();" })
+        );
+        // Unreadable files still report the position
+        assert_eq!(
+            json[1],
+            json!({ "file": "gone.rs", "line": 1, "column": 1, "text": "" })
+        );
+    }
+}
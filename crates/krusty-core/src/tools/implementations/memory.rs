@@ -0,0 +1,329 @@
+//! Memory tools - Project notes that persist across sessions
+//!
+//! - remember: Save a note (or revise an existing one)
+//! - recall: Search saved notes
+//! - forget: Delete a note that no longer holds
+//!
+//! Memories are scoped to the working directory and the current user. The
+//! most recent ones are also injected into every conversation as context.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::storage::{Database, Memory, MemoryStore, MAX_MEMORY_CHARS};
+use crate::tools::registry::Tool;
+use crate::tools::{parse_params, ToolContext, ToolResult};
+
+const DEFAULT_RECALL_LIMIT: usize = 20;
+const MAX_RECALL_LIMIT: usize = 100;
+
+fn memory_json(memory: &Memory) -> Value {
+    json!({
+        "id": memory.id,
+        "content": memory.content,
+        "updated": memory.updated_at.get(..10).unwrap_or(&memory.updated_at),
+    })
+}
+
+// ============================================================================
+// Remember
+// ============================================================================
+
+pub struct RememberTool {
+    db_path: PathBuf,
+}
+
+impl RememberTool {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+}
+
+#[derive(Deserialize)]
+struct RememberParams {
+    content: String,
+    #[serde(default)]
+    replaces: Option<i64>,
+}
+
+#[async_trait]
+impl Tool for RememberTool {
+    fn name(&self) -> &str {
+        "remember"
+    }
+
+    fn description(&self) -> &str {
+        "Save a short note about this project for future sessions: conventions, \
+         commands, decisions, pitfalls, user preferences. Keep each memory to one \
+         self-contained fact. Pass `replaces` with a memory ID to revise an \
+         outdated memory instead of adding a new one."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "content": {
+                    "type": "string",
+                    "description": "The fact to remember, e.g. 'Integration tests need `docker compose up db` first'"
+                },
+                "replaces": {
+                    "type": "integer",
+                    "description": "ID of an existing memory to overwrite"
+                }
+            },
+            "required": ["content"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<RememberParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let content = params.content.trim();
+        if content.is_empty() {
+            return ToolResult::invalid_parameters("content cannot be empty");
+        }
+        if content.chars().count() > MAX_MEMORY_CHARS {
+            return ToolResult::invalid_parameters(format!(
+                "content must be under {} characters; save one fact per memory",
+                MAX_MEMORY_CHARS
+            ));
+        }
+
+        let working_dir = ctx.working_dir.to_string_lossy();
+        let user_id = ctx.user_id.as_deref();
+        let saved = Database::new(&self.db_path).and_then(|db| {
+            let store = MemoryStore::new(&db);
+            match params.replaces {
+                Some(id) => {
+                    let owned = store
+                        .get(id, user_id)?
+                        .filter(|m| m.working_dir == working_dir);
+                    if owned.is_none() || !store.update(id, user_id, content)? {
+                        anyhow::bail!("Memory {} not found in this project", id);
+                    }
+                    store
+                        .get(id, user_id)?
+                        .ok_or_else(|| anyhow::anyhow!("Memory {} not found", id))
+                }
+                None => store.add(&working_dir, user_id, content),
+            }
+        });
+
+        match saved {
+            Ok(memory) => ToolResult::success_data(json!({
+                "saved": memory_json(&memory),
+                "replaced": params.replaces.is_some(),
+            })),
+            Err(e) => ToolResult::error(format!("Failed to save memory: {}", e)),
+        }
+    }
+}
+
+// ============================================================================
+// Recall
+// ============================================================================
+
+pub struct RecallTool {
+    db_path: PathBuf,
+}
+
+impl RecallTool {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+}
+
+#[derive(Deserialize)]
+struct RecallParams {
+    #[serde(default)]
+    query: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[async_trait]
+impl Tool for RecallTool {
+    fn name(&self) -> &str {
+        "recall"
+    }
+
+    fn description(&self) -> &str {
+        "Search the notes saved with `remember` for this project. Every word of \
+         the query must appear in a memory (case-insensitive). Omit the query to \
+         list all memories, most recently updated first."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to search for, e.g. 'test database'"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of memories (default: 20, max: 100)"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<RecallParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        let query = params.query.unwrap_or_default();
+        let limit = params
+            .limit
+            .unwrap_or(DEFAULT_RECALL_LIMIT)
+            .clamp(1, MAX_RECALL_LIMIT);
+
+        let working_dir = ctx.working_dir.to_string_lossy();
+        let memories = Database::new(&self.db_path).and_then(|db| {
+            MemoryStore::new(&db).recall(&working_dir, ctx.user_id.as_deref(), &query, limit)
+        });
+        let memories = match memories {
+            Ok(memories) => memories,
+            Err(e) => return ToolResult::error(format!("Failed to recall memories: {}", e)),
+        };
+
+        let results: Vec<Value> = memories.iter().map(memory_json).collect();
+        ToolResult::success_data(json!({
+            "query": query,
+            "count": results.len(),
+            "memories": results,
+        }))
+    }
+}
+
+// ============================================================================
+// Forget
+// ============================================================================
+
+pub struct ForgetTool {
+    db_path: PathBuf,
+}
+
+impl ForgetTool {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+}
+
+#[derive(Deserialize)]
+struct ForgetParams {
+    id: i64,
+}
+
+#[async_trait]
+impl Tool for ForgetTool {
+    fn name(&self) -> &str {
+        "forget"
+    }
+
+    fn description(&self) -> &str {
+        "Delete a project memory that is wrong or no longer applies, by its ID."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "description": "ID of the memory to delete"
+                }
+            },
+            "required": ["id"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<ForgetParams>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+
+        let working_dir = ctx.working_dir.to_string_lossy();
+        let user_id = ctx.user_id.as_deref();
+        let deleted = Database::new(&self.db_path).and_then(|db| {
+            let store = MemoryStore::new(&db);
+            let in_project = store
+                .get(params.id, user_id)?
+                .is_some_and(|m| m.working_dir == working_dir);
+            Ok(in_project && store.delete(params.id, user_id)?)
+        });
+
+        match deleted {
+            Ok(true) => ToolResult::success_data(json!({ "deleted": params.id })),
+            Ok(false) => {
+                ToolResult::error(format!("Memory {} not found in this project", params.id))
+            }
+            Err(e) => ToolResult::error(format!("Failed to delete memory: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remember_recall_forget() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let ctx = ToolContext {
+            working_dir: PathBuf::from("/project"),
+            ..Default::default()
+        };
+        let data = |result: ToolResult| {
+            assert!(!result.is_error, "{}", result.output);
+            serde_json::from_str::<Value>(&result.output).unwrap()["data"].clone()
+        };
+
+        let remember = RememberTool::new(db_path.clone());
+        let saved = data(
+            remember
+                .execute(json!({"content": "Run migrations with just db"}), &ctx)
+                .await,
+        );
+        let id = saved["saved"]["id"].as_i64().unwrap();
+        data(
+            remember
+                .execute(
+                    json!({"content": "Run migrations with `just migrate`", "replaces": id}),
+                    &ctx,
+                )
+                .await,
+        );
+
+        let recall = RecallTool::new(db_path.clone());
+        let found = data(recall.execute(json!({"query": "migrate"}), &ctx).await);
+        assert_eq!(found["count"], 1);
+        assert_eq!(found["memories"][0]["id"], id);
+
+        // Memories of other projects are out of reach
+        let elsewhere = ToolContext {
+            working_dir: PathBuf::from("/elsewhere"),
+            ..Default::default()
+        };
+        let found = data(recall.execute(json!({}), &elsewhere).await);
+        assert_eq!(found["count"], 0);
+        let forget = ForgetTool::new(db_path);
+        assert!(forget.execute(json!({"id": id}), &elsewhere).await.is_error);
+
+        data(forget.execute(json!({"id": id}), &ctx).await);
+        let found = data(recall.execute(json!({}), &ctx).await);
+        assert_eq!(found["count"], 0);
+    }
+}
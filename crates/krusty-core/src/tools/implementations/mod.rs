@@ -10,9 +10,13 @@
 //! - glob: Find files by pattern
 //! - list: List directory contents
 //! - apply_patch: Multi-file patch application
+//! - diagnostics/definition/references/hover/rename: Language server queries
 //! - processes: Manage background processes
+//! - session_search: Search past conversations
+//! - remember/recall/forget: Persistent project memory
 //! - explore: Spawn parallel sub-agents for deep codebase exploration
 //! - build: Spawn parallel Opus builder agents (The Kraken)
+//! - task: Delegate work to user-defined sub-agents (.krusty/agents)
 //! - skill: Invoke skills for specialized instructions
 //! - ask_user: Interactive user prompts (handled by UI)
 //! - task_complete: Mark plan tasks as complete with result (handled by UI)
@@ -32,13 +36,17 @@ pub mod explore;
 pub mod glob;
 pub mod grep;
 pub mod list;
+pub mod lsp;
+pub mod memory;
 pub mod multiedit;
 pub mod plan_mode;
 pub mod processes;
 pub mod read;
+pub mod session_search;
 pub mod set_dependency;
 pub mod set_work_mode;
 pub mod skill;
+pub mod task;
 pub mod task_complete;
 pub mod task_start;
 pub mod write;
@@ -53,21 +61,27 @@ pub use explore::ExploreTool;
 pub use glob::GlobTool;
 pub use grep::GrepTool;
 pub use list::ListTool;
+pub use lsp::{DefinitionTool, DiagnosticsTool, HoverTool, ReferencesTool, RenameTool};
+pub use memory::{ForgetTool, RecallTool, RememberTool};
 pub use multiedit::MultiEditTool;
 pub use plan_mode::EnterPlanModeTool;
 pub use processes::ProcessesTool;
 pub use read::ReadTool;
+pub use session_search::SessionSearchTool;
 pub use set_dependency::SetDependencyTool;
 pub use set_work_mode::SetWorkModeTool;
 pub use skill::SkillTool;
+pub use task::TaskTool;
 pub use task_complete::TaskCompleteTool;
 pub use task_start::TaskStartTool;
 pub use write::WriteTool;
 
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use crate::agent::AgentCancellation;
 use crate::ai::client::AiClient;
+use crate::lsp::LspManager;
 use crate::tools::registry::ToolRegistry;
 
 /// Register all built-in tools (except explore which needs client)
@@ -92,24 +106,39 @@ pub async fn register_all_tools(registry: &ToolRegistry) {
     registry.register(Arc::new(EnterPlanModeTool)).await;
 }
 
-/// Register tools for ACP (excludes TUI-only tools)
-///
-/// Excludes:
-/// - AskUserQuestionTool (requires TUI interaction)
-/// - TaskCompleteTool (requires TUI plan mode)
-/// - EnterPlanModeTool (requires TUI plan mode)
-/// - SkillTool (requires skills manager setup)
-pub async fn register_acp_tools(registry: &ToolRegistry) {
-    registry.register(Arc::new(ReadTool)).await;
-    registry.register(Arc::new(WriteTool)).await;
-    registry.register(Arc::new(EditTool)).await;
-    registry.register(Arc::new(MultiEditTool)).await;
-    registry.register(Arc::new(BashTool)).await;
-    registry.register(Arc::new(GrepTool)).await;
-    registry.register(Arc::new(GlobTool)).await;
-    registry.register(Arc::new(ListTool)).await;
-    registry.register(Arc::new(ApplyPatchTool)).await;
-    registry.register(Arc::new(ProcessesTool)).await;
+/// Register the language server tools
+pub async fn register_lsp_tools(registry: &ToolRegistry, manager: Arc<LspManager>) {
+    registry
+        .register(Arc::new(DiagnosticsTool::new(manager.clone())))
+        .await;
+    registry
+        .register(Arc::new(DefinitionTool::new(manager.clone())))
+        .await;
+    registry
+        .register(Arc::new(ReferencesTool::new(manager.clone())))
+        .await;
+    registry
+        .register(Arc::new(HoverTool::new(manager.clone())))
+        .await;
+    registry.register(Arc::new(RenameTool::new(manager))).await;
+}
+
+/// Register the session search tool (requires the session database)
+pub async fn register_session_search_tool(registry: &ToolRegistry, db_path: PathBuf) {
+    registry
+        .register(Arc::new(SessionSearchTool::new(db_path)))
+        .await;
+}
+
+/// Register the project memory tools (require the session database)
+pub async fn register_memory_tools(registry: &ToolRegistry, db_path: PathBuf) {
+    registry
+        .register(Arc::new(RememberTool::new(db_path.clone())))
+        .await;
+    registry
+        .register(Arc::new(RecallTool::new(db_path.clone())))
+        .await;
+    registry.register(Arc::new(ForgetTool::new(db_path))).await;
 }
 
 /// Register the explore tool (requires AI client)
@@ -137,3 +166,16 @@ pub async fn register_build_tool(
         .register(Arc::new(BuildTool::new(client, cancellation)))
         .await;
 }
+
+/// Register the task tool (user-defined sub-agents)
+///
+/// Call this after authentication when the client is available.
+pub async fn register_task_tool(
+    registry: &ToolRegistry,
+    client: Arc<AiClient>,
+    cancellation: AgentCancellation,
+) {
+    registry
+        .register(Arc::new(TaskTool::new(client, cancellation)))
+        .await;
+}
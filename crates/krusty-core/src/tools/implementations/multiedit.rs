@@ -4,7 +4,6 @@ use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::{json, Value};
 use similar::TextDiff;
-use tokio::fs;
 
 use crate::tools::matching;
 use crate::tools::registry::Tool;
@@ -92,7 +91,7 @@ impl Tool for MultiEditTool {
             return ToolResult::error(format!("File not found: {}", path.display()));
         }
 
-        let original = match fs::read_to_string(&path).await {
+        let original = match ctx.read_text(&path).await {
             Ok(c) => c,
             Err(e) => return ToolResult::error(format!("Failed to read file: {}", e)),
         };
@@ -141,20 +140,23 @@ impl Tool for MultiEditTool {
         // Generate diff before writing
         let diff = generate_compact_diff(&original, &content, &path);
 
-        match fs::write(&path, &content).await {
+        ctx.checkpoint_file(&path).await;
+        match ctx.write_text(&path, &content).await {
             Ok(_) => {
                 let mut msg = format!("Applied {}/{} edits", applied, total);
                 if !errors.is_empty() {
                     msg.push_str(&format!(" ({} failed)", errors.len()));
                 }
 
-                let data = json!({
+                let mut data = json!({
                     "message": msg,
                     "edits_applied": applied,
                     "edits_total": total,
                     "file_path": path.display().to_string(),
                     "partial": !errors.is_empty()
                 });
+                ctx.attach_diagnostics(&mut data, std::slice::from_ref(&path))
+                    .await;
 
                 // Partial success still writes the file, so keep success with warnings.
                 ToolResult::success_data_with(data, errors, Some(diff), None)
@@ -101,9 +101,13 @@ impl Tool for ReadTool {
             ));
         }
 
-        let content = match fs::read(&path).await {
-            Ok(bytes) => bytes,
-            Err(e) => return ToolResult::error(format!("Failed to read file: {}", e)),
+        // The editor's view includes unsaved changes
+        let content = match ctx.read_from_editor(&path).await {
+            Some(text) => text.into_bytes(),
+            None => match fs::read(&path).await {
+                Ok(bytes) => bytes,
+                Err(e) => return ToolResult::error(format!("Failed to read file: {}", e)),
+            },
         };
 
         // Check for binary
@@ -0,0 +1,159 @@
+//! Session search tool - Recall prior work from past conversations
+//!
+//! Searches the full-text index over saved messages (text, tool names and
+//! file paths). Sessions in the current working directory are searched unless
+//! `all_directories` is set.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::storage::{Database, MessageSearch};
+use crate::tools::registry::Tool;
+use crate::tools::{parse_params, ToolContext, ToolResult};
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+pub struct SessionSearchTool {
+    db_path: PathBuf,
+}
+
+impl SessionSearchTool {
+    pub fn new(db_path: PathBuf) -> Self {
+        Self { db_path }
+    }
+}
+
+#[derive(Deserialize)]
+struct Params {
+    query: String,
+    #[serde(default)]
+    all_directories: bool,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[async_trait]
+impl Tool for SessionSearchTool {
+    fn name(&self) -> &str {
+        "session_search"
+    }
+
+    fn description(&self) -> &str {
+        "Search past conversations to recall prior work, decisions and fixes. \
+         Matches message text, tool names and file paths; every word must match. \
+         Searches sessions in the current directory unless all_directories is true. \
+         Returns excerpts with their session, date and message position."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "Words to search for, e.g. 'websocket reconnect'"
+                },
+                "all_directories": {
+                    "type": "boolean",
+                    "description": "Search sessions from every directory (default: false)"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Maximum number of results (default: 10, max: 50)"
+                }
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<Params>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+        if params.query.trim().is_empty() {
+            return ToolResult::invalid_parameters("query cannot be empty");
+        }
+
+        let working_dir = ctx.working_dir.to_string_lossy().into_owned();
+        let working_dir = (!params.all_directories).then_some(working_dir.as_str());
+        let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let hits = Database::new(&self.db_path).and_then(|db| {
+            MessageSearch::new(&db).search(
+                &params.query,
+                working_dir,
+                ctx.user_id.as_deref(),
+                limit,
+            )
+        });
+        let hits = match hits {
+            Ok(hits) => hits,
+            Err(e) => return ToolResult::error(format!("Session search failed: {}", e)),
+        };
+
+        let results: Vec<Value> = hits
+            .iter()
+            .map(|hit| {
+                json!({
+                    "session_id": hit.session_id,
+                    "session_title": hit.session_title,
+                    "working_dir": hit.working_dir,
+                    "message_index": hit.message_index,
+                    "role": hit.role,
+                    "date": hit.created_at.get(..10).unwrap_or(&hit.created_at),
+                    "excerpt": hit.snippet,
+                })
+            })
+            .collect();
+
+        ToolResult::success_data(json!({
+            "query": params.query,
+            "count": results.len(),
+            "results": results,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::SessionManager;
+
+    #[tokio::test]
+    async fn test_searches_current_directory_by_default() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+        let manager = SessionManager::new(Database::new(&db_path).unwrap());
+        let text = |t: &str| json!([{"type": "text", "text": t}]).to_string();
+        for dir in ["/project", "/elsewhere"] {
+            let id = manager
+                .create_session("Websocket fix", None, Some(dir))
+                .unwrap();
+            manager
+                .save_message(&id, "assistant", &text("Added a websocket heartbeat"))
+                .unwrap();
+        }
+
+        let tool = SessionSearchTool::new(db_path);
+        let ctx = ToolContext {
+            working_dir: PathBuf::from("/project"),
+            ..Default::default()
+        };
+
+        let result = tool.execute(json!({"query": "heartbeat"}), &ctx).await;
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["data"]["count"], 1);
+        assert_eq!(output["data"]["results"][0]["working_dir"], "/project");
+
+        let result = tool
+            .execute(json!({"query": "heartbeat", "all_directories": true}), &ctx)
+            .await;
+        let output: Value = serde_json::from_str(&result.output).unwrap();
+        assert_eq!(output["data"]["count"], 2);
+    }
+}
@@ -0,0 +1,183 @@
+//! Task tool - Delegate work to user-defined sub-agents
+//!
+//! Agent types are defined in `.krusty/agents/*.md` (project) and
+//! `~/.krusty/agents/*.md` (global). Each task runs on its agent's system
+//! prompt, tool set, model and turn limit through `SubAgentPool`.
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::agent::subagent::{load_agent_definitions, AgentDefinition, SubAgentPool, SubAgentTask};
+use crate::agent::AgentCancellation;
+use crate::ai::client::AiClient;
+use crate::tools::registry::Tool;
+use crate::tools::{parse_params, ToolContext, ToolResult};
+
+/// Task tool for dispatching work to user-defined agents
+pub struct TaskTool {
+    client: Arc<AiClient>,
+    cancellation: AgentCancellation,
+}
+
+impl TaskTool {
+    pub fn new(client: Arc<AiClient>, cancellation: AgentCancellation) -> Self {
+        Self {
+            client,
+            cancellation,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Params {
+    /// Agent type for a single task
+    #[serde(default)]
+    agent: Option<String>,
+
+    /// Instructions for a single task
+    #[serde(default)]
+    prompt: Option<String>,
+
+    /// Several tasks to run in parallel
+    #[serde(default)]
+    tasks: Option<Vec<TaskSpec>>,
+}
+
+#[derive(Deserialize)]
+struct TaskSpec {
+    agent: String,
+    prompt: String,
+}
+
+#[async_trait]
+impl Tool for TaskTool {
+    fn name(&self) -> &str {
+        "task"
+    }
+
+    fn description(&self) -> &str {
+        "Delegate work to a user-defined sub-agent (see [AVAILABLE AGENTS]). \
+         Each agent type has its own instructions, tools, and model, defined in \
+         .krusty/agents/*.md. Pass 'agent' and 'prompt' for one task, or 'tasks' to run \
+         several in parallel. The prompt must be self-contained: the agent does not see \
+         this conversation. Returns each agent's final report."
+    }
+
+    fn parameters_schema(&self) -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "agent": {
+                    "type": "string",
+                    "description": "Name of the agent type to run"
+                },
+                "prompt": {
+                    "type": "string",
+                    "description": "Complete, self-contained instructions for the agent"
+                },
+                "tasks": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "agent": {"type": "string"},
+                            "prompt": {"type": "string"}
+                        },
+                        "required": ["agent", "prompt"]
+                    },
+                    "description": "Several tasks to run in parallel, instead of agent/prompt"
+                }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    async fn execute(&self, params: Value, ctx: &ToolContext) -> ToolResult {
+        let params = match parse_params::<Params>(params) {
+            Ok(p) => p,
+            Err(e) => return e,
+        };
+
+        let specs = match (params.tasks, params.agent, params.prompt) {
+            (Some(tasks), None, None) if !tasks.is_empty() => tasks,
+            (None, Some(agent), Some(prompt)) => vec![TaskSpec { agent, prompt }],
+            _ => {
+                return ToolResult::invalid_parameters(
+                    "Provide either 'agent' and 'prompt', or a non-empty 'tasks' list",
+                )
+            }
+        };
+
+        let definitions = load_agent_definitions(&ctx.working_dir);
+        if definitions.is_empty() {
+            return ToolResult::error(
+                "No agent types are defined. Add markdown definitions to .krusty/agents/ \
+                 or ~/.krusty/agents/",
+            );
+        }
+        let definitions: Vec<Arc<AgentDefinition>> =
+            definitions.into_iter().map(Arc::new).collect();
+
+        let mut tasks = Vec::with_capacity(specs.len());
+        for (i, spec) in specs.into_iter().enumerate() {
+            let Some(definition) = definitions.iter().find(|d| d.name == spec.agent) else {
+                let available: Vec<&str> = definitions.iter().map(|d| d.name.as_str()).collect();
+                return ToolResult::invalid_parameters(format!(
+                    "Unknown agent '{}'. Available: {}",
+                    spec.agent,
+                    available.join(", ")
+                ));
+            };
+            let task = SubAgentTask::new(format!("{}-{}", definition.name, i), spec.prompt)
+                .with_name(definition.name.clone())
+                .with_working_dir(ctx.working_dir.clone())
+                .with_checkpointer(ctx.checkpointer.clone());
+            tasks.push((definition.clone(), task));
+        }
+
+        info!("Task tool: dispatching {} task(s)", tasks.len());
+        let pool = SubAgentPool::new(self.client.clone(), self.cancellation.clone())
+            .with_concurrency(tasks.len())
+            .with_override_model(ctx.current_model.clone());
+        let results = pool
+            .execute_defined(tasks, ctx.explore_progress_tx.clone())
+            .await;
+
+        let mut output = String::new();
+        let mut errors: Vec<String> = Vec::new();
+        let mut total_turns = 0;
+        let mut total_duration_ms = 0u64;
+
+        for result in &results {
+            if result.success {
+                output.push_str(&format!("\n## Agent: {}\n", result.task_id));
+                output.push_str(&result.output);
+                output.push('\n');
+            } else if let Some(err) = &result.error {
+                warn!("Task tool: {} failed: {}", result.task_id, err);
+                errors.push(format!("{}: {}", result.task_id, err));
+            }
+            total_turns += result.turns_used;
+            total_duration_ms += result.duration_ms;
+        }
+
+        output.push_str(&format!(
+            "\n---\n**Summary**: {} agents, {} turns total, {}ms",
+            results.len(),
+            total_turns,
+            total_duration_ms
+        ));
+        if !errors.is_empty() {
+            output.push_str("\n**Errors**: ");
+            output.push_str(&errors.join(", "));
+        }
+
+        ToolResult {
+            output,
+            is_error: results.iter().all(|r| !r.success),
+        }
+    }
+}
@@ -76,11 +76,13 @@ impl Tool for WriteTool {
 
         // Read existing content for diff (before writing)
         let old_content = if path.is_file() {
-            fs::read_to_string(&path).await.ok()
+            ctx.read_text(&path).await.ok()
         } else {
             None
         };
 
+        ctx.checkpoint_file(&path).await;
+
         // Create parent directories if needed
         if let Some(parent) = path.parent().filter(|p| !p.exists()) {
             info!("Write tool: creating parent directory {:?}", parent);
@@ -89,11 +91,11 @@ impl Tool for WriteTool {
             }
         }
 
-        match fs::write(&path, &params.content).await {
+        match ctx.write_text(&path, &params.content).await {
             Ok(_) => {
                 let line_count = params.content.lines().count();
 
-                let data = match &old_content {
+                let mut data = match &old_content {
                     Some(_) => json!({
                         "message": format!("Successfully overwrote file ({} lines)", line_count),
                         "bytes_written": params.content.len(),
@@ -119,6 +121,9 @@ impl Tool for WriteTool {
                     None
                 };
 
+                ctx.attach_diagnostics(&mut data, std::slice::from_ref(&path))
+                    .await;
+
                 ToolResult::success_data_with(data, Vec::new(), diff, None)
             }
             Err(e) => ToolResult::error(format!("Failed to write file: {}", e)),
@@ -2,6 +2,8 @@
 //!
 //! Provides the tool registry and all built-in tool implementations.
 
+pub mod checkpoint;
+pub mod editor;
 pub mod git_identity;
 pub mod image;
 pub mod implementations;
@@ -10,11 +12,14 @@ pub mod path_utils;
 pub mod registry;
 pub mod truncation;
 
+pub use checkpoint::FileCheckpointer;
+pub use editor::{EditorClient, EditorCommandOutput, TerminalUnavailable};
 pub use git_identity::{GitIdentity, GitIdentityMode};
 pub use image::{
     is_image_extension, is_supported_file, load_from_clipboard_rgba, load_from_path, load_from_url,
 };
 pub use implementations::{
-    register_acp_tools, register_all_tools, register_build_tool, register_explore_tool,
+    register_all_tools, register_build_tool, register_explore_tool, register_lsp_tools,
+    register_memory_tools, register_session_search_tool, register_task_tool,
 };
 pub use registry::{parse_params, ToolContext, ToolOutputChunk, ToolRegistry, ToolResult};
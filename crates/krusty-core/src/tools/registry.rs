@@ -14,9 +14,12 @@ use serde::{Deserialize, Serialize};
 use crate::agent::hooks::{HookResult, PostToolHook, PreToolHook};
 use crate::agent::subagent::AgentProgress;
 use crate::ai::types::AiTool;
+use crate::lsp::LspManager;
 use crate::mcp::McpManager;
 use crate::process::ProcessRegistry;
 use crate::skills::SkillsManager;
+use crate::tools::checkpoint::FileCheckpointer;
+use crate::tools::editor::EditorClient;
 use crate::tools::git_identity::GitIdentity;
 
 /// Tool category for permission checking.
@@ -42,11 +45,13 @@ pub enum PermissionMode {
 /// Categorize a tool by name.
 pub fn tool_category(name: &str) -> ToolCategory {
     match name {
-        "read" | "glob" | "grep" | "list" | "web_search" | "web_fetch" | "explore" => {
+        "read" | "glob" | "grep" | "list" | "web_search" | "web_fetch" | "explore"
+        | "diagnostics" | "definition" | "references" | "hover" | "session_search" | "recall" => {
             ToolCategory::ReadOnly
         }
         "AskUserQuestion" | "PlanConfirm" | "enter_plan_mode" | "set_work_mode" | "task_start"
         | "task_complete" | "add_subtask" | "set_dependency" => ToolCategory::Interactive,
+        // Everything else, including `remember`/`forget` (persistent memory)
         _ => ToolCategory::Write,
     }
 }
@@ -202,6 +207,8 @@ pub struct ToolContext {
     pub process_registry: Option<Arc<ProcessRegistry>>,
     pub skills_manager: Option<Arc<RwLock<SkillsManager>>>,
     pub mcp_manager: Option<Arc<McpManager>>,
+    /// Language servers kept in sync with file edits
+    pub lsp_manager: Option<Arc<LspManager>>,
     /// Optional per-call timeout override
     pub timeout: Option<Duration>,
     /// Channel for streaming output (used by bash tool)
@@ -218,6 +225,10 @@ pub struct ToolContext {
     pub current_model: Option<String>,
     /// Git identity for commit attribution
     pub git_identity: Option<GitIdentity>,
+    /// Current turn's file checkpoint (write tools snapshot files into it)
+    pub checkpointer: Option<FileCheckpointer>,
+    /// Editor serving file reads/writes and terminals (ACP clients)
+    pub editor: Option<Arc<dyn EditorClient>>,
 }
 
 impl Default for ToolContext {
@@ -229,6 +240,7 @@ impl Default for ToolContext {
             process_registry: None,
             skills_manager: None,
             mcp_manager: None,
+            lsp_manager: None,
             timeout: None,
             output_tx: None,
             tool_use_id: None,
@@ -237,6 +249,8 @@ impl Default for ToolContext {
             build_progress_tx: None,
             current_model: None,
             git_identity: None,
+            checkpointer: None,
+            editor: None,
         }
     }
 }
@@ -272,6 +286,12 @@ impl ToolContext {
         self
     }
 
+    /// Add LSP manager to context
+    pub fn with_lsp_manager(mut self, lsp_manager: Arc<LspManager>) -> Self {
+        self.lsp_manager = Some(lsp_manager);
+        self
+    }
+
     /// Add skills manager to context
     pub fn with_skills_manager(mut self, skills_manager: Arc<RwLock<SkillsManager>>) -> Self {
         self.skills_manager = Some(skills_manager);
@@ -313,6 +333,96 @@ impl ToolContext {
         self
     }
 
+    /// Record files into the current turn's checkpoint
+    pub fn with_checkpointer(mut self, checkpointer: FileCheckpointer) -> Self {
+        self.checkpointer = Some(checkpointer);
+        self
+    }
+
+    /// Route file and terminal access through the hosting editor
+    pub fn with_editor(mut self, editor: Arc<dyn EditorClient>) -> Self {
+        self.editor = Some(editor);
+        self
+    }
+
+    /// Read a file through the editor, if it serves reads
+    ///
+    /// Returns None when there is no such editor or the read failed, in which
+    /// case the caller reads from disk.
+    pub async fn read_from_editor(&self, path: &std::path::Path) -> Option<String> {
+        let editor = self.editor.as_ref().filter(|e| e.can_read_files())?;
+        match editor.read_text_file(path).await {
+            Ok(content) => Some(content),
+            Err(e) => {
+                tracing::warn!(
+                    "Editor read of {} failed, reading locally: {}",
+                    path.display(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Read a text file, preferring the editor's view of it
+    pub async fn read_text(&self, path: &std::path::Path) -> std::io::Result<String> {
+        match self.read_from_editor(path).await {
+            Some(content) => Ok(content),
+            None => tokio::fs::read_to_string(path).await,
+        }
+    }
+
+    /// Write a text file, through the editor when it applies writes
+    pub async fn write_text(&self, path: &std::path::Path, content: &str) -> std::io::Result<()> {
+        if let Some(editor) = self.editor.as_ref().filter(|e| e.can_write_files()) {
+            match editor.write_text_file(path, content).await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    tracing::warn!(
+                        "Editor write of {} failed, writing locally: {}",
+                        path.display(),
+                        e
+                    );
+                }
+            }
+        }
+        tokio::fs::write(path, content).await
+    }
+
+    /// Snapshot a file's current contents before a write tool modifies it
+    ///
+    /// Prefers the editor's view, as that is what [`Self::write_text`] replaces.
+    pub async fn checkpoint_file(&self, path: &std::path::Path) {
+        if let Some(checkpointer) = &self.checkpointer {
+            checkpointer
+                .snapshot_with(path, || async {
+                    match self.read_from_editor(path).await {
+                        Some(content) => Ok(content.into_bytes()),
+                        None => tokio::fs::read(path).await,
+                    }
+                })
+                .await;
+        }
+    }
+
+    /// Sync edited files to their language servers and attach the errors and
+    /// warnings they now report to a write tool's result `data`
+    pub async fn attach_diagnostics(&self, data: &mut Value, paths: &[std::path::PathBuf]) {
+        let Some(lsp) = &self.lsp_manager else {
+            return;
+        };
+        let files = lsp.diagnostics_after_edit(&self.working_dir, paths).await;
+        if files.is_empty() {
+            return;
+        }
+        if let Some(data) = data.as_object_mut() {
+            data.insert(
+                "diagnostics".to_string(),
+                crate::lsp::diagnostics_json(&self.working_dir, &files),
+            );
+        }
+    }
+
     /// Resolve a path relative to working directory (absolute paths pass through)
     pub fn resolve_path(&self, path: &str) -> std::path::PathBuf {
         let p = std::path::PathBuf::from(path);
@@ -480,6 +590,20 @@ impl ToolRegistry {
         tools.insert(name, tool);
     }
 
+    /// Copy this registry's tools and hooks into a new registry
+    ///
+    /// Tools registered on the copy (such as one session's MCP tools) don't
+    /// reach the original.
+    pub async fn fork(&self) -> Self {
+        let tools = self.tools.read().await.clone();
+        Self {
+            tools: Arc::new(RwLock::new(tools)),
+            default_timeout: self.default_timeout,
+            pre_hooks: self.pre_hooks.clone(),
+            post_hooks: self.post_hooks.clone(),
+        }
+    }
+
     /// Add a pre-execution hook
     pub fn add_pre_hook(&mut self, hook: Arc<dyn PreToolHook>) {
         self.pre_hooks.push(hook);
@@ -822,4 +946,104 @@ mod tests {
         assert_eq!(parsed["error"]["code"], "blocked_by_policy");
         assert_eq!(parsed["error"]["message"], "blocked for test");
     }
+
+    #[tokio::test]
+    async fn test_fork_keeps_hooks_but_not_later_tools() {
+        let mut registry = ToolRegistry::new();
+        registry.add_pre_hook(Arc::new(AlwaysBlockHook));
+        let fork = registry.fork().await;
+        fork.register(Arc::new(TestTool)).await;
+
+        assert!(registry.get("test_tool").await.is_none());
+        let result = fork
+            .execute("test_tool", json!({}), &create_test_context())
+            .await
+            .unwrap();
+        assert!(result.is_error);
+    }
+
+    /// Serves reads from an in-memory buffer and refuses writes
+    struct BufferEditor;
+
+    #[async_trait]
+    impl EditorClient for BufferEditor {
+        fn can_read_files(&self) -> bool {
+            true
+        }
+
+        fn can_write_files(&self) -> bool {
+            false
+        }
+
+        fn can_run_commands(&self) -> bool {
+            false
+        }
+
+        async fn read_text_file(&self, _path: &std::path::Path) -> anyhow::Result<String> {
+            Ok("unsaved buffer".to_string())
+        }
+
+        async fn write_text_file(
+            &self,
+            _path: &std::path::Path,
+            _content: &str,
+        ) -> anyhow::Result<()> {
+            anyhow::bail!("writes not supported")
+        }
+
+        async fn run_command(
+            &self,
+            _command: &str,
+            _cwd: &std::path::Path,
+            _env: Vec<(String, String)>,
+            _timeout: Duration,
+            _tool_use_id: Option<&str>,
+        ) -> anyhow::Result<crate::tools::EditorCommandOutput> {
+            anyhow::bail!("terminals not supported")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_editor_file_access_falls_back_to_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("file.txt");
+        let ctx = create_test_context().with_editor(Arc::new(BufferEditor));
+
+        // Writes the editor doesn't offer go to disk
+        ctx.write_text(&path, "on disk").await.unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "on disk");
+
+        // Reads prefer the editor's view
+        assert_eq!(ctx.read_text(&path).await.unwrap(), "unsaved buffer");
+        let local = create_test_context();
+        assert_eq!(local.read_text(&path).await.unwrap(), "on disk");
+    }
+
+    #[tokio::test]
+    async fn test_checkpoint_snapshots_editor_buffer() {
+        use crate::storage::{CheckpointStore, Database, SessionManager};
+
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("test.db");
+        let sessions = SessionManager::new(Database::new(&db_path).unwrap());
+        let session_id = sessions.create_session("Checkpoints", None, None).unwrap();
+        let path = dir.path().join("file.txt");
+        std::fs::write(&path, "on disk").unwrap();
+
+        let checkpointer = FileCheckpointer::begin(&db_path, &session_id, 0, None).unwrap();
+        let ctx = create_test_context()
+            .with_editor(Arc::new(BufferEditor))
+            .with_checkpointer(checkpointer);
+        ctx.checkpoint_file(&path).await;
+
+        // The snapshot holds the buffer a write through the editor replaces
+        let snapshots = CheckpointStore::new(sessions.db())
+            .snapshots_since(&session_id, 1)
+            .unwrap();
+        assert_eq!(snapshots.len(), 1);
+        assert_eq!(
+            snapshots[0].content.as_deref(),
+            Some(&b"unsaved buffer"[..])
+        );
+    }
 }
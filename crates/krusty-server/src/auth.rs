@@ -1,26 +1,96 @@
-//! Lightweight auth middleware for self-host deployments.
+//! Authentication middleware for self-host deployments.
 //!
-//! This keeps request-level user context optional:
-//! - No auth headers => single-tenant local mode.
-//! - `X-User-Id` + optional `X-Workspace-Dir` => scoped multi-user mode.
+//! Requests are authenticated by, in order:
+//! - `Authorization: Bearer <token>` API tokens (scoped, optionally expiring)
+//! - the `krusty_session` cookie set by PWA login or device pairing
+//!
+//! Unauthenticated requests are served in single-tenant local mode only when
+//! they come straight from loopback. Remote (or proxied, e.g. Tailscale serve)
+//! requests without credentials are refused unless the server was started
+//! with unauthenticated remote access explicitly enabled.
+//!
+//! Failed password logins and pairing-code claims are counted per client IP;
+//! too many lock that client out for a while ([`LoginThrottle`]).
 
 use axum::{
     async_trait,
-    extract::{ConnectInfo, FromRequestParts, Request, State},
-    http::{request::Parts, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, OriginalUri, Request, State},
+    http::{header, request::Parts, HeaderMap, Method, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
+    Json,
 };
-use std::net::SocketAddr;
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 
+use krusty_core::storage::{AccountStore, AuthToken, Database, TokenKind, TokenScope};
+
+use crate::error::ApiError;
 use crate::AppState;
 
+/// Cookie holding the browser session token
+pub const SESSION_COOKIE: &str = "krusty_session";
+
+/// Endpoints reachable without credentials (sign-in and pairing)
+const PUBLIC_PATHS: [&str; 3] = [
+    "/api/auth/status",
+    "/api/auth/login",
+    "/api/auth/pair/claim",
+];
+
+/// Failed logins or pairing claims a client may make before being locked out
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+/// How long a lockout lasts, and how long failed attempts are remembered
+const LOCKOUT_DURATION: Duration = Duration::from_secs(15 * 60);
+
+/// Per-IP lockout for password and pairing-code guessing
+///
+/// Requests through a local reverse proxy share its address, so a lockout
+/// there applies to every client behind the proxy.
+#[derive(Debug, Default)]
+pub struct LoginThrottle {
+    /// Failure count and time of the latest failure, per client
+    failures: Mutex<HashMap<IpAddr, (u32, Instant)>>,
+}
+
+impl LoginThrottle {
+    /// Time left on the client's lockout, if it is locked out
+    pub fn locked_out(&self, ip: IpAddr) -> Option<Duration> {
+        let failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        let (count, last) = failures.get(&ip)?;
+        let elapsed = last.elapsed();
+        (*count >= MAX_FAILED_ATTEMPTS && elapsed < LOCKOUT_DURATION)
+            .then(|| LOCKOUT_DURATION - elapsed)
+    }
+
+    pub fn record_failure(&self, ip: IpAddr) {
+        let mut failures = self.failures.lock().unwrap_or_else(|e| e.into_inner());
+        failures.retain(|_, (_, last)| last.elapsed() < LOCKOUT_DURATION);
+        let entry = failures.entry(ip).or_insert((0, Instant::now()));
+        entry.0 += 1;
+        entry.1 = Instant::now();
+    }
+
+    pub fn record_success(&self, ip: IpAddr) {
+        self.failures
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&ip);
+    }
+}
+
 /// User context attached to request extensions by middleware.
 #[derive(Debug, Clone)]
 pub struct AuthenticatedUser {
     pub user_id: Option<String>,
     pub home_dir: Option<PathBuf>,
+    /// What this request may do (all scopes in local mode)
+    pub scopes: Vec<TokenScope>,
+    /// Id of the token or session used, if any
+    pub token_id: Option<String>,
 }
 
 impl AuthenticatedUser {
@@ -28,8 +98,23 @@ impl AuthenticatedUser {
         Self {
             user_id: None,
             home_dir: None,
+            scopes: TokenScope::ALL.to_vec(),
+            token_id: None,
+        }
+    }
+
+    fn from_token(token: AuthToken) -> Self {
+        Self {
+            user_id: Some(token.user_id),
+            home_dir: None,
+            scopes: token.scopes,
+            token_id: Some(token.id),
         }
     }
+
+    pub fn has_scope(&self, scope: TokenScope) -> bool {
+        self.scopes.contains(&scope)
+    }
 }
 
 /// Extractor for routes that want user context.
@@ -52,37 +137,192 @@ where
     }
 }
 
-/// Middleware that attaches optional user info to request extensions.
+/// Middleware that authenticates the request and enforces token scopes.
 pub async fn auth_middleware(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     mut request: Request,
     next: Next,
 ) -> Response {
-    let mut user = AuthenticatedUser::local();
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    let is_public = PUBLIC_PATHS.contains(&path.as_str());
+    let remote = is_remote(&addr, request.headers());
 
-    if let Some(user_id) = request
-        .headers()
-        .get("X-User-Id")
-        .and_then(|v| v.to_str().ok())
-        .filter(|v| !v.trim().is_empty())
-    {
-        user.user_id = Some(user_id.to_string());
-        user.home_dir = request
-            .headers()
-            .get("X-Workspace-Dir")
-            .and_then(|v| v.to_str().ok())
-            .map(PathBuf::from)
-            .or_else(|| Some((*state.working_dir).clone()));
-    }
-
-    if !addr.ip().is_loopback() {
-        tracing::debug!(
-            "External request from {} accepted in self-host mode",
-            addr.ip()
-        );
+    let token = match authenticate(&state, request.headers()) {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("Failed to authenticate request: {}", e);
+            return reject(StatusCode::INTERNAL_SERVER_ERROR, "Authentication failed");
+        }
+    };
+
+    let user = match token {
+        Some(token) => AuthenticatedUser::from_token(token),
+        None if !remote => AuthenticatedUser::local(),
+        None if state.allow_unauthenticated_remote => {
+            tracing::debug!(
+                "Unauthenticated remote request from {} accepted (explicitly enabled)",
+                addr.ip()
+            );
+            AuthenticatedUser::local()
+        }
+        None if is_public => return next.run(request).await,
+        None => {
+            tracing::debug!("Refused unauthenticated remote request from {}", addr.ip());
+            return reject(StatusCode::UNAUTHORIZED, "Authentication required");
+        }
+    };
+
+    // Auth endpoints check their own permissions
+    if !path.starts_with("/api/auth/") {
+        let required = required_scope(&path, request.method());
+        if !user.has_scope(required) {
+            return reject(
+                StatusCode::FORBIDDEN,
+                &format!("Token is missing the '{}' scope", required.as_str()),
+            );
+        }
     }
 
     request.extensions_mut().insert(user);
     next.run(request).await
 }
+
+/// Scope a request needs, from its path and method
+fn required_scope(path: &str, method: &Method) -> TokenScope {
+    if path.starts_with("/ws/") {
+        TokenScope::Terminal
+    } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS) {
+        TokenScope::Read
+    } else {
+        TokenScope::Write
+    }
+}
+
+/// Whether a request did not come directly from this machine
+///
+/// Reverse proxies (Tailscale serve, nginx) connect over loopback, so
+/// forwarding headers also mark a request as remote.
+pub fn is_remote(addr: &SocketAddr, headers: &HeaderMap) -> bool {
+    !addr.ip().is_loopback()
+        || ["x-forwarded-for", "forwarded", "tailscale-user-login"]
+            .iter()
+            .any(|h| headers.contains_key(*h))
+}
+
+/// Resolve the bearer token or session cookie on a request
+fn authenticate(state: &AppState, headers: &HeaderMap) -> anyhow::Result<Option<AuthToken>> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .map(str::trim);
+    let cookie = session_cookie(headers);
+    if bearer.is_none() && cookie.is_none() {
+        return Ok(None);
+    }
+
+    let db = Database::new(&state.db_path)?;
+    let store = AccountStore::new(&db);
+    if let Some(secret) = bearer {
+        return store.authenticate(secret, TokenKind::Api);
+    }
+    match cookie {
+        Some(secret) => store.authenticate(&secret, TokenKind::Session),
+        None => Ok(None),
+    }
+}
+
+/// Session token from the request's cookies
+pub fn session_cookie(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+fn reject(status: StatusCode, message: &str) -> Response {
+    let code = match status {
+        StatusCode::UNAUTHORIZED => "UNAUTHORIZED",
+        StatusCode::FORBIDDEN => "FORBIDDEN",
+        _ => "INTERNAL_ERROR",
+    };
+    (
+        status,
+        Json(ApiError {
+            error: message.to_string(),
+            code: code.to_string(),
+        }),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn test_required_scope() {
+        assert_eq!(
+            required_scope("/api/sessions", &Method::GET),
+            TokenScope::Read
+        );
+        assert_eq!(
+            required_scope("/api/chat", &Method::POST),
+            TokenScope::Write
+        );
+        assert_eq!(
+            required_scope("/ws/terminal", &Method::GET),
+            TokenScope::Terminal
+        );
+    }
+
+    #[test]
+    fn test_proxied_loopback_is_remote() {
+        let loopback: SocketAddr = "127.0.0.1:5000".parse().unwrap();
+        let lan: SocketAddr = "192.168.1.20:5000".parse().unwrap();
+        let mut headers = HeaderMap::new();
+        assert!(!is_remote(&loopback, &headers));
+        assert!(is_remote(&lan, &headers));
+
+        headers.insert("x-forwarded-for", HeaderValue::from_static("100.64.0.2"));
+        assert!(is_remote(&loopback, &headers));
+    }
+
+    #[test]
+    fn test_login_throttle_locks_out_after_failures() {
+        let throttle = LoginThrottle::default();
+        let attacker: IpAddr = "192.168.1.20".parse().unwrap();
+        let other: IpAddr = "192.168.1.21".parse().unwrap();
+
+        for _ in 0..MAX_FAILED_ATTEMPTS - 1 {
+            throttle.record_failure(attacker);
+        }
+        assert!(throttle.locked_out(attacker).is_none());
+        throttle.record_failure(attacker);
+        assert!(throttle.locked_out(attacker).is_some());
+        assert!(throttle.locked_out(other).is_none());
+
+        throttle.record_success(attacker);
+        assert!(throttle.locked_out(attacker).is_none());
+    }
+
+    #[test]
+    fn test_session_cookie_parsing() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; krusty_session=krusty_abc; other=1"),
+        );
+        assert_eq!(session_cookie(&headers).as_deref(), Some("krusty_abc"));
+    }
+}
@@ -0,0 +1,270 @@
+//! Sequence-numbered event logs for running chat sessions
+//!
+//! The orchestrator bridge appends every `AgenticEvent` of a run to the
+//! session's log instead of writing to a single SSE sender. Any number of
+//! clients can follow a log from a given sequence number, replaying what they
+//! missed before receiving live events, so a dropped connection can resume.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::response::sse::Event;
+use tokio::sync::{mpsc, Notify};
+use tokio_stream::wrappers::ReceiverStream;
+
+/// Events kept per session; older ones are dropped first
+const MAX_EVENTS: usize = 4096;
+/// How long a finished run's log stays available for reconnecting clients
+const FINISHED_RETENTION: Duration = Duration::from_secs(15 * 60);
+const FOLLOW_CHANNEL_BUFFER: usize = 256;
+
+pub type EventLogMap = HashMap<String, Arc<EventLog>>;
+
+/// One serialized event with its sequence number
+#[derive(Debug, Clone)]
+pub struct LoggedEvent {
+    pub seq: u64,
+    pub data: Arc<str>,
+}
+
+#[derive(Debug)]
+struct LogInner {
+    events: VecDeque<LoggedEvent>,
+    next_seq: u64,
+    finished_at: Option<Instant>,
+}
+
+/// Bounded, sequence-numbered event log for one agent run
+#[derive(Debug)]
+pub struct EventLog {
+    inner: Mutex<LogInner>,
+    notify: Notify,
+}
+
+impl EventLog {
+    /// Create a log whose first event gets sequence number `first_seq`
+    ///
+    /// Runs of the same session continue the previous run's numbering so
+    /// clients can keep using their last seen `after` value.
+    pub fn new(first_seq: u64) -> Self {
+        Self {
+            inner: Mutex::new(LogInner {
+                events: VecDeque::new(),
+                next_seq: first_seq.max(1),
+                finished_at: None,
+            }),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Append an event and wake followers, returning its sequence number
+    pub fn push(&self, data: String) -> u64 {
+        let seq = {
+            let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            let seq = inner.next_seq;
+            inner.next_seq += 1;
+            if inner.events.len() >= MAX_EVENTS {
+                inner.events.pop_front();
+            }
+            inner.events.push_back(LoggedEvent {
+                seq,
+                data: data.into(),
+            });
+            seq
+        };
+        self.notify.notify_waiters();
+        seq
+    }
+
+    /// Mark the run as finished; followers end once they have caught up
+    pub fn finish(&self) {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finished_at = Some(Instant::now());
+        self.notify.notify_waiters();
+    }
+
+    /// Guard that finishes the log when dropped
+    ///
+    /// Held by the task producing a run's events, so followers still end if
+    /// that task panics or is aborted.
+    pub fn finish_on_drop(self: &Arc<Self>) -> FinishGuard {
+        FinishGuard(Arc::clone(self))
+    }
+
+    /// Sequence number of the most recent event (0 if none)
+    pub fn last_seq(&self) -> u64 {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .next_seq
+            - 1
+    }
+
+    /// Whether the run is still producing events
+    pub fn is_running(&self) -> bool {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finished_at
+            .is_none()
+    }
+
+    /// Retained events after `after`, and whether the run has finished
+    ///
+    /// If `after` is older than the oldest retained event, replay starts at
+    /// the oldest one available.
+    pub fn since(&self, after: u64) -> (Vec<LoggedEvent>, bool) {
+        let inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        let events = inner
+            .events
+            .iter()
+            .filter(|e| e.seq > after)
+            .cloned()
+            .collect();
+        (events, inner.finished_at.is_some())
+    }
+
+    fn expired(&self) -> bool {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .finished_at
+            .is_some_and(|t| t.elapsed() > FINISHED_RETENTION)
+    }
+
+    /// Stream events after `after` as SSE, then follow live output until the
+    /// run finishes or the client disconnects
+    pub fn follow(self: Arc<Self>, after: u64) -> ReceiverStream<Result<Event, Infallible>> {
+        let (tx, rx) = mpsc::channel(FOLLOW_CHANNEL_BUFFER);
+
+        tokio::spawn(async move {
+            let mut last = after;
+            loop {
+                // Register for wakeups before reading so no push is missed
+                let notified = self.notify.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+
+                let (events, finished) = self.since(last);
+                for event in events {
+                    last = event.seq;
+                    let sse = Event::default()
+                        .id(event.seq.to_string())
+                        .data(event.data.as_ref());
+                    if tx.send(Ok(sse)).await.is_err() {
+                        return;
+                    }
+                }
+                if finished {
+                    return;
+                }
+
+                tokio::select! {
+                    _ = &mut notified => {}
+                    _ = tx.closed() => return,
+                }
+            }
+        });
+
+        ReceiverStream::new(rx)
+    }
+}
+
+/// Finishes its log when dropped (see [`EventLog::finish_on_drop`])
+pub struct FinishGuard(Arc<EventLog>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.finish();
+    }
+}
+
+/// Start a new log for a session's run, replacing the previous one
+///
+/// Also drops finished logs past their retention period.
+pub fn start_run(logs: &mut EventLogMap, session_id: &str) -> Arc<EventLog> {
+    logs.retain(|_, log| !log.expired());
+    let first_seq = logs.get(session_id).map_or(1, |log| log.last_seq() + 1);
+    let log = Arc::new(EventLog::new(first_seq));
+    logs.insert(session_id.to_string(), Arc::clone(&log));
+    log
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[test]
+    fn test_since_and_bounds() {
+        let log = EventLog::new(1);
+        for i in 0..MAX_EVENTS + 10 {
+            log.push(format!("{{\"n\":{}}}", i));
+        }
+        assert_eq!(log.last_seq(), (MAX_EVENTS + 10) as u64);
+
+        let (events, finished) = log.since(0);
+        assert_eq!(events.len(), MAX_EVENTS);
+        assert_eq!(events[0].seq, 11);
+        assert!(!finished);
+
+        let (events, _) = log.since(log.last_seq() - 2);
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn test_runs_continue_numbering() {
+        let mut logs = EventLogMap::new();
+        let first = start_run(&mut logs, "s1");
+        first.push("a".into());
+        first.push("b".into());
+        first.finish();
+
+        let second = start_run(&mut logs, "s1");
+        assert_eq!(second.push("c".into()), 3);
+        assert!(second.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_follow_replays_then_streams_live() {
+        let log = Arc::new(EventLog::new(1));
+        log.push("a".into());
+        log.push("b".into());
+
+        let mut stream = Arc::clone(&log).follow(1);
+        let log_writer = Arc::clone(&log);
+        tokio::spawn(async move {
+            log_writer.push("c".into());
+            log_writer.finish();
+        });
+
+        let mut received = 0;
+        while let Some(Ok(_)) = stream.next().await {
+            received += 1;
+        }
+        assert_eq!(received, 2);
+    }
+
+    #[tokio::test]
+    async fn test_follower_ends_when_run_is_aborted() {
+        let log = Arc::new(EventLog::new(1));
+        let finish = log.finish_on_drop();
+        let run = tokio::spawn(async move {
+            let _finish = finish;
+            std::future::pending::<()>().await;
+        });
+        log.push("a".into());
+
+        let mut stream = Arc::clone(&log).follow(0);
+        assert!(stream.next().await.is_some());
+        run.abort();
+
+        let end = tokio::time::timeout(Duration::from_secs(5), stream.next()).await;
+        assert!(matches!(end, Ok(None)), "follower should end after abort");
+        assert!(!log.is_running());
+    }
+}
@@ -31,9 +31,13 @@ use krusty_core::agent::{
     UserPreToolHook,
 };
 use krusty_core::ai::client::{AiClient, AiClientConfig};
+use krusty_core::ai::custom_providers;
+use krusty_core::ai::format_detection::detect_api_format;
 use krusty_core::ai::models::{create_model_registry, ModelMetadata, SharedModelRegistry};
-use krusty_core::ai::providers::{builtin_providers, get_provider, ProviderId};
+use krusty_core::ai::providers::{all_providers, get_provider, ProviderId};
 use krusty_core::constants;
+use krusty_core::extensions::ExtensionSlashCommands;
+use krusty_core::lsp::LspManager;
 use krusty_core::mcp::McpManager;
 use krusty_core::paths;
 use krusty_core::process::ProcessRegistry;
@@ -41,7 +45,8 @@ use krusty_core::skills::SkillsManager;
 use krusty_core::storage::credentials::CredentialStore;
 use krusty_core::storage::Database;
 use krusty_core::tools::implementations::{
-    register_all_tools, register_build_tool, register_explore_tool,
+    register_all_tools, register_build_tool, register_explore_tool, register_lsp_tools,
+    register_memory_tools, register_session_search_tool, register_task_tool,
 };
 use krusty_core::tools::registry::ToolRegistry;
 
@@ -51,6 +56,7 @@ type SessionInputMap =
     HashMap<String, tokio::sync::mpsc::UnboundedSender<krusty_core::agent::LoopInput>>;
 pub mod auth;
 pub mod error;
+pub mod event_log;
 pub mod push;
 pub mod routes;
 pub mod types;
@@ -74,6 +80,8 @@ pub struct ServerConfig {
     pub port: u16,
     /// Working directory for file/tools APIs.
     pub working_dir: PathBuf,
+    /// Serve remote requests without credentials (trusted networks only).
+    pub allow_unauthenticated_remote: bool,
 }
 
 impl Default for ServerConfig {
@@ -81,6 +89,7 @@ impl Default for ServerConfig {
         Self {
             port: 3000,
             working_dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            allow_unauthenticated_remote: false,
         }
     }
 }
@@ -89,6 +98,8 @@ impl Default for ServerConfig {
 #[derive(Clone)]
 pub struct AppState {
     pub server_port: u16,
+    /// Whether remote requests without credentials are allowed.
+    pub allow_unauthenticated_remote: bool,
     pub db_path: Arc<PathBuf>,
     pub working_dir: Arc<PathBuf>,
     pub ai_client: Option<Arc<AiClient>>,
@@ -97,6 +108,10 @@ pub struct AppState {
     pub model_registry: SharedModelRegistry,
     pub credential_store: Arc<RwLock<CredentialStore>>,
     pub mcp_manager: Arc<McpManager>,
+    /// Language servers, pooled per workspace and started on first use.
+    pub lsp_manager: Arc<LspManager>,
+    /// Slash commands contributed by installed extensions.
+    pub slash_commands: Arc<ExtensionSlashCommands>,
     pub hook_manager: Arc<RwLock<UserHookManager>>,
     pub skills_manager: Arc<RwLock<SkillsManager>>,
     pub cancellation: AgentCancellation,
@@ -104,10 +119,14 @@ pub struct AppState {
     pub session_locks: Arc<RwLock<SessionLockMap>>,
     /// Active orchestrator input channels for tool approvals / cancellation.
     pub session_inputs: Arc<RwLock<SessionInputMap>>,
+    /// Sequence-numbered agent event logs, so clients can resume streams.
+    pub event_logs: Arc<RwLock<event_log::EventLogMap>>,
     /// Web Push notification service (None if VAPID init failed).
     pub push_service: Option<Arc<push::PushService>>,
     /// Active OAuth flows keyed by provider storage key.
     pub oauth_flows: Arc<Mutex<HashMap<String, routes::oauth::OAuthFlowState>>>,
+    /// Lockouts for clients guessing passwords or pairing codes.
+    pub login_throttle: Arc<auth::LoginThrottle>,
 }
 
 /// Build an AI client from configured credentials and env overrides.
@@ -129,6 +148,9 @@ pub fn create_ai_client(credentials: &CredentialStore) -> Option<AiClient> {
             ProviderId::ZAi => "Z_AI_API_KEY",
             ProviderId::Anthropic => "ANTHROPIC_API_KEY",
             ProviderId::OpenAI => "OPENAI_API_KEY",
+            ProviderId::Gemini => "GEMINI_API_KEY",
+            // User-defined providers resolve their env var in get_auth
+            ProviderId::Custom(_) => return None,
         };
         std::env::var(env_key).ok()
     });
@@ -162,6 +184,7 @@ pub fn create_ai_client(credentials: &CredentialStore) -> Option<AiClient> {
                 return None;
             }
         };
+        let api_format = detect_api_format(provider, &model);
         (
             AiClientConfig {
                 model,
@@ -169,7 +192,7 @@ pub fn create_ai_client(credentials: &CredentialStore) -> Option<AiClient> {
                 base_url: Some(provider_cfg.base_url.clone()),
                 auth_header: provider_cfg.auth_header,
                 provider_id: provider,
-                api_format: Default::default(),
+                api_format,
                 custom_headers: provider_cfg.custom_headers.clone(),
             },
             api_key,
@@ -181,13 +204,14 @@ pub fn create_ai_client(credentials: &CredentialStore) -> Option<AiClient> {
 
 /// Initialize models in the shared registry.
 async fn initialize_models(registry: &SharedModelRegistry, credentials: &CredentialStore) {
-    for provider in builtin_providers() {
+    for provider in all_providers() {
         let models: Vec<ModelMetadata> = provider
             .models
             .iter()
             .map(|m| {
                 let mut model = ModelMetadata::new(&m.id, &m.display_name, provider.id)
-                    .with_context(m.context_window, m.max_output);
+                    .with_context(m.context_window, m.max_output)
+                    .with_pricing(m.input_price, m.output_price);
 
                 if let Some(reasoning) = m.reasoning {
                     model = model.with_thinking(reasoning);
@@ -210,6 +234,28 @@ async fn initialize_models(registry: &SharedModelRegistry, credentials: &Credent
             Err(e) => tracing::warn!("Failed to fetch OpenRouter models: {}", e),
         }
     }
+
+    for provider in all_providers() {
+        if provider.models_url.is_some() {
+            refresh_custom_models(registry, credentials, provider.id).await;
+        }
+    }
+}
+
+/// Replace a user-defined provider's models with its discovery endpoint listing.
+pub(crate) async fn refresh_custom_models(
+    registry: &SharedModelRegistry,
+    credentials: &CredentialStore,
+    provider: ProviderId,
+) {
+    let Some(api_key) = credentials.get_api_key(&provider) else {
+        return;
+    };
+    match custom_providers::fetch_models(provider, Some(&api_key)).await {
+        Ok(models) if !models.is_empty() => registry.set_models(provider, models).await,
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to fetch {} models: {}", provider, e),
+    }
 }
 
 /// Build the Axum router with all routes and embedded PWA assets.
@@ -243,11 +289,17 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
     let tool_registry = Arc::new(tool_registry_inner);
     register_all_tools(&tool_registry).await;
 
-    // Register sub-agent tools (explore + build) if AI client is available
+    let lsp_manager = Arc::new(LspManager::new());
+    register_lsp_tools(&tool_registry, lsp_manager.clone()).await;
+    register_session_search_tool(&tool_registry, db_path.clone()).await;
+    register_memory_tools(&tool_registry, db_path.clone()).await;
+
+    // Register sub-agent tools (explore + build + task) if AI client is available
     if let Some(ref client) = ai_client {
         register_explore_tool(&tool_registry, client.clone(), cancellation.clone()).await;
         register_build_tool(&tool_registry, client.clone(), cancellation.clone()).await;
-        tracing::info!("Registered explore and build sub-agent tools");
+        register_task_tool(&tool_registry, client.clone(), cancellation.clone()).await;
+        tracing::info!("Registered explore, build and task sub-agent tools");
     }
 
     let model_registry = create_model_registry();
@@ -262,6 +314,7 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
     }
     // Register MCP tools so they're visible to the AI
     krusty_core::mcp::tool::register_mcp_tools(mcp_manager.clone(), &tool_registry).await;
+    krusty_core::mcp::tool::spawn_tool_sync(mcp_manager.clone(), tool_registry.clone());
     let mcp_tool_count = tool_registry.get_ai_tools().await.len();
     tracing::info!("Tool registry initialized with {} tools", mcp_tool_count);
 
@@ -279,6 +332,7 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
 
     let state = AppState {
         server_port: config.port,
+        allow_unauthenticated_remote: config.allow_unauthenticated_remote,
         db_path: Arc::new(db_path),
         working_dir: Arc::new(config.working_dir.clone()),
         ai_client,
@@ -287,6 +341,8 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
         model_registry,
         credential_store,
         mcp_manager,
+        lsp_manager,
+        slash_commands: Arc::new(ExtensionSlashCommands::new()),
         hook_manager,
         skills_manager: Arc::new(RwLock::new(SkillsManager::with_defaults(
             &config.working_dir,
@@ -294,8 +350,10 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
         cancellation,
         session_locks: Arc::new(RwLock::new(HashMap::new())),
         session_inputs: Arc::new(RwLock::new(HashMap::new())),
+        event_logs: Arc::new(RwLock::new(HashMap::new())),
         push_service,
         oauth_flows: Arc::new(Mutex::new(HashMap::new())),
+        login_throttle: Arc::new(auth::LoginThrottle::default()),
     };
 
     let cors = CorsLayer::new()
@@ -307,11 +365,18 @@ pub async fn build_router(config: &ServerConfig) -> anyhow::Result<(Router, AppS
             Method::PUT,
             Method::DELETE,
         ])
-        .allow_headers(Any);
+        .allow_headers(Any)
+        .expose_headers([header::HeaderName::from_static("x-session-id")]);
 
     let app = Router::new()
         .route("/health", get(health))
-        .route("/ws/terminal", get(ws::terminal::handler))
+        .route(
+            "/ws/terminal",
+            get(ws::terminal::handler).layer(middleware::from_fn_with_state(
+                state.clone(),
+                auth::auth_middleware,
+            )),
+        )
         .nest(
             "/api",
             routes::api_router().layer(middleware::from_fn_with_state(
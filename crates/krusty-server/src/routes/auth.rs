@@ -0,0 +1,348 @@
+//! Account, token and device pairing endpoints
+
+use std::net::{IpAddr, SocketAddr};
+
+use axum::{
+    extract::{ConnectInfo, Path, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{AppendHeaders, IntoResponse},
+    routing::{delete, get, post},
+    Json, Router,
+};
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+
+use krusty_core::storage::{AccountStore, AuthToken, Database, TokenKind, TokenScope, UserAccount};
+
+use crate::auth::{is_remote, session_cookie, CurrentUser, SESSION_COOKIE};
+use crate::error::AppError;
+use crate::AppState;
+
+/// Browser sessions last 30 days
+const SESSION_TTL_DAYS: i64 = 30;
+/// Pairing codes are valid for 10 minutes
+const PAIRING_TTL_MINUTES: i64 = 10;
+
+/// Build the auth router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/status", get(status))
+        .route("/setup", post(setup))
+        .route("/login", post(login))
+        .route("/logout", post(logout))
+        .route("/tokens", get(list_tokens).post(create_token))
+        .route("/tokens/:id", delete(revoke_token))
+        .route("/pair", post(create_pairing_code))
+        .route("/pair/claim", post(claim_pairing_code))
+}
+
+#[derive(Serialize)]
+pub struct AuthStatusResponse {
+    /// Whether the request carries valid credentials or is local
+    pub authenticated: bool,
+    /// Signed-in user (None in local single-tenant mode)
+    pub user: Option<UserAccount>,
+    /// No login exists yet; create one with `POST /auth/setup`
+    pub setup_required: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CredentialsRequest {
+    pub email: String,
+    pub password: String,
+    pub display_name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateTokenRequest {
+    pub name: String,
+    /// Defaults to read + write
+    pub scopes: Option<Vec<TokenScope>>,
+    /// Omit for a token that never expires
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct CreateTokenResponse {
+    #[serde(flatten)]
+    pub token: AuthToken,
+    /// The bearer secret; only returned here
+    pub secret: String,
+}
+
+#[derive(Serialize)]
+pub struct PairingCodeResponse {
+    pub code: String,
+    pub expires_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct ClaimPairingRequest {
+    pub code: String,
+    pub device_name: Option<String>,
+}
+
+/// Whether the current request is signed in, and whether setup is needed
+async fn status(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> Result<Json<AuthStatusResponse>, AppError> {
+    let db = Database::new(&state.db_path)?;
+    let store = AccountStore::new(&db);
+    let account = match user.as_ref().and_then(|u| u.0.user_id.as_deref()) {
+        Some(user_id) => store.get_user(user_id)?,
+        None => None,
+    };
+    Ok(Json(AuthStatusResponse {
+        authenticated: user.is_some(),
+        user: account,
+        setup_required: store.login_count()? == 0,
+    }))
+}
+
+/// Create the first login; only allowed locally and before any login exists
+async fn setup(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    user: Option<CurrentUser>,
+    headers: HeaderMap,
+    Json(req): Json<CredentialsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    // Unauthenticated remote access also gets local mode, so check the peer
+    if is_remote(&addr, &headers) || user.as_ref().is_none_or(|u| u.0.user_id.is_some()) {
+        return Err(AppError::BadRequest(
+            "Setup must be done from the server machine".to_string(),
+        ));
+    }
+
+    let db = Database::new(&state.db_path)?;
+    let store = AccountStore::new(&db);
+    if store.login_count()? > 0 {
+        return Err(AppError::Conflict("A login already exists".to_string()));
+    }
+    let account = store
+        .create_user(&req.email, &req.password, req.display_name.as_deref())
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    start_session(&store, &account, &headers, Some("Browser"))
+}
+
+/// Sign in with email and password, setting the session cookie
+async fn login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<CredentialsRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    check_throttle(&state, addr.ip())?;
+    let db = Database::new(&state.db_path)?;
+    let store = AccountStore::new(&db);
+    let Some(account) = store.verify_password(&req.email, &req.password)? else {
+        tracing::warn!("Failed login for {} from {}", req.email, addr.ip());
+        state.login_throttle.record_failure(addr.ip());
+        return Err(AppError::Unauthorized(
+            "Invalid email or password".to_string(),
+        ));
+    };
+    state.login_throttle.record_success(addr.ip());
+
+    start_session(&store, &account, &headers, Some("Browser"))
+}
+
+/// Revoke the current session and clear its cookie
+async fn logout(State(state): State<AppState>, headers: HeaderMap) -> impl IntoResponse {
+    if let Some(secret) = session_cookie(&headers) {
+        if let Ok(db) = Database::new(&state.db_path) {
+            if let Err(e) = AccountStore::new(&db).revoke_secret(&secret) {
+                tracing::warn!("Failed to revoke session: {}", e);
+            }
+        }
+    }
+    (
+        AppendHeaders([(header::SET_COOKIE, clear_cookie())]),
+        StatusCode::NO_CONTENT,
+    )
+}
+
+/// List the signed-in user's tokens and sessions
+async fn list_tokens(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> Result<Json<Vec<AuthToken>>, AppError> {
+    let user_id = require_admin(user.as_ref())?;
+    let db = Database::new(&state.db_path)?;
+    Ok(Json(AccountStore::new(&db).list_tokens(&user_id)?))
+}
+
+/// Create a bearer API token
+async fn create_token(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CreateTokenRequest>,
+) -> Result<(StatusCode, Json<CreateTokenResponse>), AppError> {
+    let user_id = require_admin(user.as_ref())?;
+    let name = req.name.trim();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "Token name cannot be empty".to_string(),
+        ));
+    }
+    let scopes = req
+        .scopes
+        .unwrap_or_else(|| vec![TokenScope::Read, TokenScope::Write]);
+    if scopes.is_empty() {
+        return Err(AppError::BadRequest(
+            "Token needs at least one scope".to_string(),
+        ));
+    }
+    let ttl = match req.expires_in_days {
+        Some(days) if days <= 0 => {
+            return Err(AppError::BadRequest(
+                "expires_in_days must be positive".to_string(),
+            ))
+        }
+        Some(days) => Some(Duration::days(days)),
+        None => None,
+    };
+
+    let db = Database::new(&state.db_path)?;
+    let (token, secret) =
+        AccountStore::new(&db).create_token(&user_id, TokenKind::Api, Some(name), &scopes, ttl)?;
+    Ok((
+        StatusCode::CREATED,
+        Json(CreateTokenResponse { token, secret }),
+    ))
+}
+
+/// Revoke a token or session
+async fn revoke_token(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let user_id = require_admin(user.as_ref())?;
+    let db = Database::new(&state.db_path)?;
+    if !AccountStore::new(&db).revoke_token(&user_id, &id)? {
+        return Err(AppError::NotFound(format!("Token {} not found", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Create a short-lived code for signing in a new device
+async fn create_pairing_code(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+) -> Result<Json<PairingCodeResponse>, AppError> {
+    let user_id = require_admin(user.as_ref())?;
+    let db = Database::new(&state.db_path)?;
+    let (code, expires_at) = AccountStore::new(&db).create_pairing_code(
+        &user_id,
+        &TokenScope::ALL,
+        Duration::minutes(PAIRING_TTL_MINUTES),
+    )?;
+    Ok(Json(PairingCodeResponse { code, expires_at }))
+}
+
+/// Redeem a pairing code, signing this device in
+async fn claim_pairing_code(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(req): Json<ClaimPairingRequest>,
+) -> Result<impl IntoResponse, AppError> {
+    check_throttle(&state, addr.ip())?;
+    let db = Database::new(&state.db_path)?;
+    let store = AccountStore::new(&db);
+    let claimed = store.claim_pairing_code(
+        &req.code,
+        req.device_name.as_deref(),
+        Duration::days(SESSION_TTL_DAYS),
+    )?;
+    let Some((token, secret)) = claimed else {
+        tracing::warn!("Invalid pairing code from {}", addr.ip());
+        state.login_throttle.record_failure(addr.ip());
+        return Err(AppError::Unauthorized(
+            "Invalid or expired pairing code".to_string(),
+        ));
+    };
+
+    state.login_throttle.record_success(addr.ip());
+
+    let account = store
+        .get_user(&token.user_id)?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+    Ok((
+        AppendHeaders([(header::SET_COOKIE, session_cookie_header(&secret, &headers))]),
+        Json(account),
+    ))
+}
+
+/// Refuse sign-in attempts from a client that failed too many times
+fn check_throttle(state: &AppState, ip: IpAddr) -> Result<(), AppError> {
+    match state.login_throttle.locked_out(ip) {
+        Some(remaining) => Err(AppError::TooManyRequests(format!(
+            "Too many failed attempts; try again in {} minutes",
+            remaining.as_secs().div_ceil(60)
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// The signed-in user's id, if the request may manage tokens
+fn require_admin(user: Option<&CurrentUser>) -> Result<String, AppError> {
+    let user = user
+        .map(|u| &u.0)
+        .ok_or_else(|| AppError::Unauthorized("Authentication required".to_string()))?;
+    let user_id = user
+        .user_id
+        .clone()
+        .ok_or_else(|| AppError::Unauthorized("Sign in to manage tokens".to_string()))?;
+    if !user.has_scope(TokenScope::Admin) {
+        return Err(AppError::Forbidden(
+            "Token is missing the 'admin' scope".to_string(),
+        ));
+    }
+    Ok(user_id)
+}
+
+/// Issue a browser session for `account` and set its cookie
+fn start_session(
+    store: &AccountStore<'_>,
+    account: &UserAccount,
+    headers: &HeaderMap,
+    name: Option<&str>,
+) -> Result<impl IntoResponse, AppError> {
+    let (_, secret) = store.create_token(
+        &account.id,
+        TokenKind::Session,
+        name,
+        &TokenScope::ALL,
+        Some(Duration::days(SESSION_TTL_DAYS)),
+    )?;
+    Ok((
+        AppendHeaders([(header::SET_COOKIE, session_cookie_header(&secret, headers))]),
+        Json(account.clone()),
+    ))
+}
+
+fn session_cookie_header(secret: &str, headers: &HeaderMap) -> String {
+    // Tailscale serve and other TLS proxies report the original scheme
+    let secure = headers
+        .get("x-forwarded-proto")
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|proto| proto.eq_ignore_ascii_case("https"));
+    format!(
+        "{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}{}",
+        SESSION_COOKIE,
+        secret,
+        Duration::days(SESSION_TTL_DAYS).num_seconds(),
+        if secure { "; Secure" } else { "" }
+    )
+}
+
+fn clear_cookie() -> String {
+    format!(
+        "{}=; Path=/; HttpOnly; SameSite=Strict; Max-Age=0",
+        SESSION_COOKIE
+    )
+}
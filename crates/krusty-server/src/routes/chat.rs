@@ -6,15 +6,19 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use axum::{
-    extract::State,
-    response::sse::{Event, KeepAlive, Sse},
-    routing::post,
+    extract::{Path as UrlPath, Query, State},
+    http::{header::HeaderName, HeaderMap},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse,
+    },
+    routing::{get, post},
     Json, Router,
 };
 use futures::stream::Stream;
+use serde::Deserialize;
 use serde_json::json;
-use tokio::sync::{mpsc, Mutex, OwnedMutexGuard};
-use tokio_stream::wrappers::ReceiverStream;
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 use krusty_core::agent::plan_handler::parse_plan_confirm_choice;
 use krusty_core::agent::{
@@ -26,19 +30,21 @@ use krusty_core::ai::client::{
 use krusty_core::ai::providers::ProviderId;
 use krusty_core::ai::types::{Content, ImageContent, ModelMessage, Role, ThinkingConfig};
 use krusty_core::plan::PlanManager;
-use krusty_core::storage::{Database, WorkMode};
+use krusty_core::storage::{Database, Preferences, WorkMode};
 use krusty_core::tools::registry::PermissionMode;
 use krusty_core::SessionManager;
 
 use crate::auth::CurrentUser;
 use crate::error::AppError;
+use crate::event_log;
 use crate::push::{PushEventType, PushPayload, PushService};
 use crate::types::{
     AgenticEvent, ChatRequest, ContentBlock, ThinkingLevel, ToolApprovalRequest, ToolResultRequest,
 };
 use crate::AppState;
 
-const SSE_CHANNEL_BUFFER: usize = 256;
+/// Response header carrying the session id, so clients can resume the stream
+const SESSION_ID_HEADER: HeaderName = HeaderName::from_static("x-session-id");
 const SESSION_LOCK_MAX_ENTRIES: usize = 1000;
 const SESSION_LOCK_MAX_AGE: Duration = Duration::from_secs(3600);
 
@@ -47,6 +53,14 @@ pub fn router() -> Router<AppState> {
         .route("/", post(chat))
         .route("/tool-result", post(tool_result))
         .route("/tool-approval", post(tool_approval))
+        .route("/:session_id/events", get(session_events))
+}
+
+/// Query params for following a session's event stream
+#[derive(Debug, Deserialize)]
+pub struct EventsQuery {
+    /// Replay events with a sequence number greater than this
+    pub after: Option<u64>,
 }
 
 struct ChatSessionContext {
@@ -215,6 +229,8 @@ async fn setup_chat_session(
             let role = match role_str.as_str() {
                 "user" => Role::User,
                 "assistant" => Role::Assistant,
+                // Pinch context; also keeps indices aligned with compactions
+                "system" => Role::System,
                 _ => return None,
             };
             serde_json::from_str(&content_json)
@@ -263,7 +279,7 @@ async fn chat(
     State(state): State<AppState>,
     user: Option<CurrentUser>,
     Json(req): Json<ChatRequest>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let user_id = user.as_ref().and_then(|u| u.0.user_id.clone());
     let default_working_dir = user
         .as_ref()
@@ -322,7 +338,20 @@ async fn chat(
         }
     }
 
-    let user_content = build_user_content(&req.message, &req.content);
+    // Extension slash commands are replaced by their output before the AI sees them
+    let mut message = req.message;
+    let mut content_blocks = req.content;
+    if let Some(result) = state
+        .slash_commands
+        .expand(&message, &ctx.working_dir)
+        .await
+    {
+        message =
+            result.map_err(|e| AppError::BadRequest(format!("Slash command failed: {}", e)))?;
+        content_blocks.retain(|block| !matches!(block, ContentBlock::Text { .. }));
+    }
+
+    let user_content = build_user_content(&message, &content_blocks);
     let user_content_json = serde_json::to_string(&user_content)?;
 
     ctx.conversation.push(ModelMessage {
@@ -346,7 +375,7 @@ async fn tool_result(
     State(state): State<AppState>,
     user: Option<CurrentUser>,
     Json(req): Json<ToolResultRequest>,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+) -> Result<impl IntoResponse, AppError> {
     let mut ctx = setup_chat_session(
         &state,
         user.as_ref(),
@@ -457,6 +486,50 @@ async fn tool_result(
     start_orchestrator_sse(&state, ctx, work_mode, PermissionMode::Autonomous, false).await
 }
 
+/// Attach to a session's running (or recently finished) agent loop
+///
+/// Replays events after `after` (or the `Last-Event-ID` header), then
+/// follows live output until the run finishes.
+async fn session_events(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    UrlPath(session_id): UrlPath<String>,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    {
+        let db = Database::new(&state.db_path)?;
+        let session_manager = SessionManager::new(db);
+        let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+        if !session_manager.verify_session_ownership(&session_id, user_id)? {
+            return Err(AppError::NotFound(format!(
+                "Session {} not found",
+                session_id
+            )));
+        }
+    }
+
+    let log = state
+        .event_logs
+        .read()
+        .await
+        .get(&session_id)
+        .cloned()
+        .ok_or_else(|| AppError::NotFound("No event stream for this session".into()))?;
+
+    let after = query
+        .after
+        .or_else(|| {
+            headers
+                .get("last-event-id")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+        })
+        .unwrap_or(0);
+
+    Ok(Sse::new(log.follow(after)).keep_alive(KeepAlive::default()))
+}
+
 async fn tool_approval(
     State(state): State<AppState>,
     Json(req): Json<ToolApprovalRequest>,
@@ -468,6 +541,7 @@ async fn tool_approval(
     let _ = sender.send(LoopInput::ToolApproval {
         tool_call_id: req.tool_call_id,
         approved: req.approved,
+        always_allow: req.always_allow,
     });
     Ok(Json(json!({"status": "ok"})))
 }
@@ -480,8 +554,21 @@ async fn start_orchestrator_sse(
     work_mode: WorkMode,
     permission_mode: PermissionMode,
     generate_title: bool,
-) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
-    let (sse_tx, sse_rx) = mpsc::channel::<Result<Event, Infallible>>(SSE_CHANNEL_BUFFER);
+) -> Result<impl IntoResponse, AppError> {
+    let model = state
+        .model_registry
+        .get_model(&ctx.ai_client.config().model)
+        .await;
+    let pricing = model.as_ref().and_then(|m| m.pricing());
+    let context_window = model.map(|m| m.context_window).unwrap_or(0);
+    let auto_pinch = {
+        let db = Database::new(&state.db_path)?;
+        match ctx.user_id.as_deref() {
+            Some(user_id) => Preferences::for_user(db, user_id),
+            None => Preferences::new(db),
+        }
+        .get_auto_pinch_policy()
+    };
 
     let services = OrchestratorServices {
         ai_client: ctx.ai_client,
@@ -489,6 +576,8 @@ async fn start_orchestrator_sse(
         process_registry: Arc::clone(&state.process_registry),
         db_path: (*state.db_path).clone(),
         skills_manager: Arc::clone(&state.skills_manager),
+        lsp_manager: Some(Arc::clone(&state.lsp_manager)),
+        editor: None,
     };
 
     let config = OrchestratorConfig {
@@ -498,6 +587,9 @@ async fn start_orchestrator_sse(
         user_id: ctx.user_id.clone(),
         initial_work_mode: work_mode,
         generate_title,
+        pricing,
+        context_window,
+        auto_pinch,
         ..Default::default()
     };
 
@@ -505,13 +597,22 @@ async fn start_orchestrator_sse(
     let (mut event_rx, input_tx) = orchestrator.run(ctx.conversation, ctx.options);
 
     // Store input channel for tool approvals
-    let session_id = ctx.session_id;
+    let mut session_id = ctx.session_id;
     {
         let mut inputs = state.session_inputs.write().await;
         inputs.insert(session_id.clone(), input_tx);
     }
 
+    // Events go to the session's log; this response is its first follower
+    let log = {
+        let mut logs = state.event_logs.write().await;
+        event_log::start_run(&mut logs, &session_id)
+    };
+    let stream = Arc::clone(&log).follow(log.last_seq());
+    let headers = [(SESSION_ID_HEADER, session_id.clone())];
+
     let session_inputs = Arc::clone(&state.session_inputs);
+    let event_logs = Arc::clone(&state.event_logs);
     let push_service = state.push_service.clone();
     let user_id = ctx.user_id;
     let db_path = Arc::clone(&state.db_path);
@@ -519,12 +620,34 @@ async fn start_orchestrator_sse(
 
     tokio::spawn(async move {
         let _guard = guard;
+        // Followers must end even if this task panics or is aborted
+        let finish_log = log.finish_on_drop();
         let mut awaiting_input = false;
         let mut had_error = false;
 
         while let Some(loop_event) = event_rx.recv().await {
             let is_finished = matches!(loop_event, LoopEvent::Finished { .. });
 
+            // The run moved to a linked session: route approvals and
+            // reconnects for the new id to this run
+            if let LoopEvent::SessionPinched {
+                session_id: new_session_id,
+                ..
+            } = &loop_event
+            {
+                {
+                    let mut inputs = session_inputs.write().await;
+                    if let Some(sender) = inputs.remove(&session_id) {
+                        inputs.insert(new_session_id.clone(), sender);
+                    }
+                }
+                event_logs
+                    .write()
+                    .await
+                    .insert(new_session_id.clone(), Arc::clone(&log));
+                session_id = new_session_id.clone();
+            }
+
             if matches!(loop_event, LoopEvent::AwaitingInput { .. }) {
                 awaiting_input = true;
                 fire_push(
@@ -545,14 +668,18 @@ async fn start_orchestrator_sse(
             }
 
             let agentic_event: AgenticEvent = loop_event.into();
-            if let Ok(sse_event) = Event::default().json_data(&agentic_event) {
-                let _ = sse_tx.send(Ok(sse_event)).await;
+            match serde_json::to_string(&agentic_event) {
+                Ok(data) => {
+                    log.push(data);
+                }
+                Err(e) => tracing::warn!("Failed to serialize event: {}", e),
             }
 
             if is_finished {
                 break;
             }
         }
+        drop(finish_log);
 
         // Fire push notification based on how the loop ended
         if !awaiting_input {
@@ -589,8 +716,7 @@ async fn start_orchestrator_sse(
         inputs.remove(&session_id);
     });
 
-    let stream = ReceiverStream::new(sse_rx);
-    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+    Ok((headers, Sse::new(stream).keep_alive(KeepAlive::default())))
 }
 
 // ── Helpers ──────────────────────────────────────────────────────────
@@ -0,0 +1,92 @@
+//! Extension slash command endpoints
+//!
+//! Running a command happens through the chat endpoint: a message starting
+//! with `/name args` is replaced by the command's output.
+
+use axum::{
+    extract::State,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::error::AppError;
+use crate::AppState;
+
+/// Build the commands router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_commands))
+        .route("/complete", post(complete_argument))
+}
+
+/// Slash command info for API response
+#[derive(Serialize)]
+pub struct CommandResponse {
+    pub name: String,
+    pub description: String,
+    pub requires_argument: bool,
+}
+
+#[derive(Deserialize)]
+pub struct CompleteArgumentRequest {
+    /// Command name, without the leading `/`
+    pub command: String,
+    /// Arguments typed so far
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Argument completion for API response
+#[derive(Serialize)]
+pub struct CompletionResponse {
+    pub label: String,
+    pub new_text: String,
+    /// Whether accepting the completion should run the command
+    pub run_command: bool,
+}
+
+/// List slash commands from installed extensions
+async fn list_commands(State(state): State<AppState>) -> Json<Vec<CommandResponse>> {
+    let commands = state
+        .slash_commands
+        .commands()
+        .iter()
+        .map(|c| CommandResponse {
+            name: c.name.clone(),
+            description: c.description.clone(),
+            requires_argument: c.requires_argument,
+        })
+        .collect();
+    Json(commands)
+}
+
+/// Complete a slash command's argument
+async fn complete_argument(
+    State(state): State<AppState>,
+    Json(req): Json<CompleteArgumentRequest>,
+) -> Result<Json<Vec<CompletionResponse>>, AppError> {
+    if state.slash_commands.get(&req.command).is_none() {
+        return Err(AppError::NotFound(format!(
+            "Command /{} not found",
+            req.command
+        )));
+    }
+
+    let completions = state
+        .slash_commands
+        .complete_argument(&req.command, req.args)
+        .await
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    Ok(Json(
+        completions
+            .into_iter()
+            .map(|c| CompletionResponse {
+                label: c.label,
+                new_text: c.new_text,
+                run_command: c.run_command,
+            })
+            .collect(),
+    ))
+}
@@ -39,7 +39,7 @@ async fn list_providers(State(state): State<AppState>) -> Json<Vec<ProviderStatu
         .map(|id| ProviderStatus {
             id: id.storage_key().to_string(),
             name: id.to_string(),
-            configured: store.has_key(id),
+            configured: store.has_api_key(id),
             has_oauth: oauth_store.has_token(id),
             supports_oauth: id.supports_oauth(),
         })
@@ -59,7 +59,7 @@ async fn get_provider(
     Ok(Json(ProviderStatus {
         id: provider_id.storage_key().to_string(),
         name: provider_id.to_string(),
-        configured: store.has_key(&provider_id),
+        configured: store.has_api_key(&provider_id),
         has_oauth: oauth_store.has_token(&provider_id),
         supports_oauth: provider_id.supports_oauth(),
     }))
@@ -98,6 +98,12 @@ async fn set_credential(
                 Err(e) => tracing::warn!("Failed to refresh OpenRouter models: {}", e),
             }
         });
+    } else if provider_id.is_custom() {
+        let registry = state.model_registry.clone();
+        let store = state.credential_store.read().await.clone();
+        tokio::spawn(async move {
+            crate::refresh_custom_models(&registry, &store, provider_id).await;
+        });
     }
 
     let oauth_store = krusty_core::auth::OAuthTokenStore::load().unwrap_or_default();
@@ -129,10 +135,15 @@ async fn delete_credential(
     }
 
     let oauth_store = krusty_core::auth::OAuthTokenStore::load().unwrap_or_default();
+    let configured = state
+        .credential_store
+        .read()
+        .await
+        .has_api_key(&provider_id);
     Ok(Json(ProviderStatus {
         id: provider_id.storage_key().to_string(),
         name: provider_id.to_string(),
-        configured: false,
+        configured,
         has_oauth: oauth_store.has_token(&provider_id),
         supports_oauth: provider_id.supports_oauth(),
     }))
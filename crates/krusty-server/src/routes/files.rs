@@ -307,7 +307,7 @@ async fn browse_directories(
     }
 
     // Sort alphabetically
-    directories.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+    directories.sort_by_key(|a| a.name.to_lowercase());
 
     Ok(Json(BrowseResponse {
         current: canonical_current.display().to_string(),
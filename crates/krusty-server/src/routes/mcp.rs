@@ -5,9 +5,13 @@ use axum::{
     routing::{get, post},
     Json, Router,
 };
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-use krusty_core::mcp::McpServerStatus;
+use krusty_core::mcp::{
+    format_resource_contents, McpPromptArgument, McpResourceContents, McpServerStatus,
+    PromptGetResult,
+};
 
 use crate::error::AppError;
 use crate::AppState;
@@ -20,6 +24,10 @@ pub fn router() -> Router<AppState> {
         .route("/:name/connect", post(connect_server))
         .route("/:name/disconnect", post(disconnect_server))
         .route("/:name/tools", get(list_tools))
+        .route("/resources", get(list_resources))
+        .route("/resources/read", post(read_resource))
+        .route("/prompts", get(list_prompts))
+        .route("/prompts/get", post(get_prompt))
 }
 
 /// Keep AI-visible MCP tools in sync with current connected MCP servers.
@@ -188,3 +196,157 @@ async fn list_tools(
             .collect(),
     ))
 }
+
+/// MCP resource or resource template, for `@` mentions
+#[derive(Serialize)]
+pub struct McpResourceResponse {
+    pub server: String,
+    /// Resource URI, or the URI template for templates
+    pub uri: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub mime_type: Option<String>,
+    pub is_template: bool,
+}
+
+/// List resources and resource templates from connected servers
+async fn list_resources(State(state): State<AppState>) -> Json<Vec<McpResourceResponse>> {
+    let resources = state.mcp_manager.get_all_resources().await;
+    let templates = state.mcp_manager.get_all_resource_templates().await;
+
+    let response = resources
+        .into_iter()
+        .map(|(server, r)| McpResourceResponse {
+            server,
+            uri: r.uri,
+            name: r.name,
+            description: r.description,
+            mime_type: r.mime_type,
+            is_template: false,
+        })
+        .chain(
+            templates
+                .into_iter()
+                .map(|(server, t)| McpResourceResponse {
+                    server,
+                    uri: t.uri_template,
+                    name: t.name,
+                    description: t.description,
+                    mime_type: t.mime_type,
+                    is_template: true,
+                }),
+        )
+        .collect();
+
+    Json(response)
+}
+
+#[derive(Deserialize)]
+pub struct ReadResourceRequest {
+    pub server: String,
+    pub uri: String,
+}
+
+/// Resource contents, plus the text form used as message context
+#[derive(Serialize)]
+pub struct ReadResourceResponse {
+    pub contents: Vec<McpResourceContents>,
+    pub text: String,
+}
+
+/// Read a resource from a connected server
+async fn read_resource(
+    State(state): State<AppState>,
+    Json(req): Json<ReadResourceRequest>,
+) -> Result<Json<ReadResourceResponse>, AppError> {
+    let contents = state
+        .mcp_manager
+        .read_resource(&req.server, &req.uri)
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Failed to read {}: {}", req.uri, e)))?;
+
+    Ok(Json(ReadResourceResponse {
+        text: format_resource_contents(&contents),
+        contents,
+    }))
+}
+
+/// MCP prompt, offered as a slash command
+#[derive(Serialize)]
+pub struct McpPromptResponse {
+    pub server: String,
+    pub name: String,
+    /// Slash command that runs the prompt
+    pub command: String,
+    pub description: Option<String>,
+    pub arguments: Vec<McpPromptArgument>,
+}
+
+/// List prompts from connected servers
+async fn list_prompts(State(state): State<AppState>) -> Json<Vec<McpPromptResponse>> {
+    let prompts = state.mcp_manager.get_all_prompts().await;
+
+    Json(
+        prompts
+            .into_iter()
+            .map(|(server, p)| McpPromptResponse {
+                command: format!("/mcp__{}__{}", server, p.name),
+                server,
+                name: p.name,
+                description: p.description,
+                arguments: p.arguments,
+            })
+            .collect(),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct GetPromptRequest {
+    pub server: String,
+    pub name: String,
+    /// Named arguments
+    #[serde(default)]
+    pub arguments: Option<HashMap<String, String>>,
+    /// Raw slash-command input, bound to arguments when `arguments` is absent
+    #[serde(default)]
+    pub input: Option<String>,
+}
+
+/// Rendered prompt, plus the text to send as a user message
+#[derive(Serialize)]
+pub struct GetPromptResponse {
+    #[serde(flatten)]
+    pub prompt: PromptGetResult,
+    pub text: String,
+}
+
+/// Render a prompt from a connected server
+async fn get_prompt(
+    State(state): State<AppState>,
+    Json(req): Json<GetPromptRequest>,
+) -> Result<Json<GetPromptResponse>, AppError> {
+    let arguments = match req.arguments {
+        Some(arguments) => arguments,
+        None => {
+            let prompts = state.mcp_manager.get_all_prompts().await;
+            let (_, prompt) = prompts
+                .into_iter()
+                .find(|(server, p)| *server == req.server && p.name == req.name)
+                .ok_or_else(|| AppError::NotFound(format!("Prompt {} not found", req.name)))?;
+            prompt
+                .bind_arguments(req.input.as_deref().unwrap_or_default())
+                .map_err(AppError::BadRequest)?
+        }
+    };
+
+    let prompt = state
+        .mcp_manager
+        .get_prompt(&req.server, &req.name, arguments)
+        .await
+        .map_err(|e| AppError::BadGateway(format!("Failed to get prompt {}: {}", req.name, e)))?;
+
+    Ok(Json(GetPromptResponse {
+        text: prompt.to_text(),
+        prompt,
+    }))
+}
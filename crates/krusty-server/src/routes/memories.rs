@@ -0,0 +1,128 @@
+//! Project memory endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::get,
+    Json, Router,
+};
+use serde::Deserialize;
+
+use krusty_core::storage::{Database, Memory, MemoryStore};
+
+use crate::auth::CurrentUser;
+use crate::error::AppError;
+use crate::AppState;
+
+/// Build the memories router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_memories).post(create_memory))
+        .route(
+            "/:id",
+            get(get_memory).patch(update_memory).delete(delete_memory),
+        )
+}
+
+#[derive(Deserialize)]
+pub struct ListMemoriesQuery {
+    /// Only memories of this project
+    pub working_dir: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateMemoryRequest {
+    /// Project directory the memory belongs to
+    pub working_dir: String,
+    pub content: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMemoryRequest {
+    pub content: String,
+}
+
+/// List memories visible to the current user, most recently updated first
+async fn list_memories(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Query(query): Query<ListMemoriesQuery>,
+) -> Result<Json<Vec<Memory>>, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    let memories = MemoryStore::new(&db).list(query.working_dir.as_deref(), user_id)?;
+    Ok(Json(memories))
+}
+
+/// Get a memory
+async fn get_memory(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<Json<Memory>, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    MemoryStore::new(&db)
+        .get(id, user_id)?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Memory {} not found", id)))
+}
+
+/// Save a memory for a project
+async fn create_memory(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CreateMemoryRequest>,
+) -> Result<(StatusCode, Json<Memory>), AppError> {
+    let working_dir = req.working_dir.trim();
+    if working_dir.is_empty() {
+        return Err(AppError::BadRequest(
+            "working_dir cannot be empty".to_string(),
+        ));
+    }
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    let memory = MemoryStore::new(&db)
+        .add(working_dir, user_id, &req.content)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(memory)))
+}
+
+/// Replace a memory's text
+async fn update_memory(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<i64>,
+    Json(req): Json<UpdateMemoryRequest>,
+) -> Result<Json<Memory>, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    let store = MemoryStore::new(&db);
+    if store.get(id, user_id)?.is_none() {
+        return Err(AppError::NotFound(format!("Memory {} not found", id)));
+    }
+    store
+        .update(id, user_id, &req.content)
+        .map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    store
+        .get(id, user_id)?
+        .map(Json)
+        .ok_or_else(|| AppError::NotFound(format!("Memory {} not found", id)))
+}
+
+/// Delete a memory
+async fn delete_memory(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    if !MemoryStore::new(&db).delete(id, user_id)? {
+        return Err(AppError::NotFound(format!("Memory {} not found", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -4,14 +4,18 @@ use axum::Router;
 
 use crate::AppState;
 
+mod auth;
 mod chat;
+mod commands;
 mod credentials;
 mod files;
 mod git;
 mod hooks;
 mod mcp;
+mod memories;
 mod models;
 pub mod oauth;
+mod permissions;
 mod ports;
 mod preview_settings;
 mod processes;
@@ -24,6 +28,7 @@ pub fn api_router() -> Router<AppState> {
     Router::new()
         .nest("/sessions", sessions::router())
         .nest("/chat", chat::router())
+        .nest("/commands", commands::router())
         .nest("/models", models::router())
         .nest("/tools", tools::router())
         .nest("/git", git::router())
@@ -34,7 +39,10 @@ pub fn api_router() -> Router<AppState> {
         .nest("/ports", ports::router())
         .nest("/settings/preview", preview_settings::router())
         .nest("/hooks", hooks::router())
+        .nest("/permissions", permissions::router())
+        .nest("/memories", memories::router())
         .nest("/push", push::router())
+        .nest("/auth", auth::router())
         .nest("/auth/oauth", oauth::router())
         .merge(Router::new())
 }
@@ -0,0 +1,109 @@
+//! Tool permission rule endpoints
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    routing::{delete, get},
+    Json, Router,
+};
+use serde::Deserialize;
+
+use krusty_core::agent::permissions::{self, PermissionAction, PermissionRule};
+use krusty_core::storage::Database;
+
+use crate::auth::CurrentUser;
+use crate::error::AppError;
+use crate::AppState;
+
+/// Build the permissions router
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/", get(list_rules).post(create_rule))
+        .route("/:id", delete(delete_rule))
+}
+
+#[derive(Deserialize)]
+pub struct ListRulesQuery {
+    /// Only rules that apply to this project (global + project-scoped)
+    pub working_dir: Option<String>,
+}
+
+/// Request to create a rule, either as fields or as a compact spec
+/// (`allow bash cargo test*`)
+#[derive(Deserialize)]
+pub struct CreateRuleRequest {
+    pub spec: Option<String>,
+    pub action: Option<String>,
+    pub tool: Option<String>,
+    pub pattern: Option<String>,
+    /// Project directory (omit for a global rule)
+    pub working_dir: Option<String>,
+}
+
+/// List permission rules visible to the current user
+async fn list_rules(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Query(query): Query<ListRulesQuery>,
+) -> Result<Json<Vec<PermissionRule>>, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    let mut rules = permissions::list_rules(&db, user_id)?;
+    if let Some(dir) = query.working_dir.as_deref() {
+        rules.retain(|r| r.working_dir.as_deref().is_none_or(|d| d == dir));
+    }
+    Ok(Json(rules))
+}
+
+/// Create a permission rule
+async fn create_rule(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Json(req): Json<CreateRuleRequest>,
+) -> Result<(StatusCode, Json<PermissionRule>), AppError> {
+    let working_dir = req
+        .working_dir
+        .map(|d| d.trim().to_string())
+        .filter(|d| !d.is_empty());
+
+    let rule = match req.spec {
+        Some(spec) => PermissionRule::parse_spec(&spec, working_dir)
+            .map_err(|e| AppError::BadRequest(e.to_string()))?,
+        None => {
+            let action = req
+                .action
+                .as_deref()
+                .and_then(PermissionAction::parse)
+                .ok_or_else(|| {
+                    AppError::BadRequest("action must be one of: allow, deny, ask".to_string())
+                })?;
+            let tool = req
+                .tool
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .ok_or_else(|| AppError::BadRequest("tool cannot be empty".to_string()))?;
+            PermissionRule::new(action, tool, req.pattern, working_dir)
+        }
+    };
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    permissions::save_rule(&db, &rule, user_id)
+        .map_err(|e| AppError::Internal(format!("Failed to save rule: {}", e)))?;
+
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+/// Delete a permission rule
+async fn delete_rule(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, AppError> {
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let db = Database::new(&state.db_path)?;
+    if !permissions::delete_rule(&db, &id, user_id)? {
+        return Err(AppError::NotFound(format!("Rule {} not found", id)));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -481,7 +481,7 @@ async fn probe_ports_previewability(
         return HashMap::new();
     }
 
-    stream::iter(ports.into_iter())
+    stream::iter(ports)
         .map(|port| async move { (port, probe_previewable_port(port, timeout).await) })
         .buffer_unordered(PORT_PROBE_CONCURRENCY)
         .collect::<Vec<(u16, ProbeResult)>>()
@@ -2,7 +2,8 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::IntoResponse,
     routing::{get, post},
     Json, Router,
 };
@@ -11,13 +12,20 @@ use serde::Deserialize;
 use krusty_core::agent::pinch_context::{PinchContext, PinchContextInput};
 use krusty_core::agent::summarizer::{generate_summary, SummarizationResult};
 use krusty_core::ai::types::{Content, ModelMessage, Role};
-use krusty_core::{storage::Database, SessionManager};
+use krusty_core::storage::export::parse_bundle;
+use krusty_core::storage::{
+    Checkpoint, CheckpointStore, Database, ExportFormat, MessageSearch, SearchHit, SessionBudget,
+    UsageLedger,
+};
+use krusty_core::tools::checkpoint::{self, FileDiff};
+use krusty_core::SessionManager;
 
 use crate::auth::CurrentUser;
 use crate::error::AppError;
 use crate::types::{
-    CreateSessionRequest, MessageResponse, PinchRequest, PinchResponse, SessionResponse,
-    SessionStateResponse, SessionWithMessagesResponse, UpdateSessionRequest,
+    CreateSessionRequest, ForkSessionRequest, MessageResponse, PinchRequest, PinchResponse,
+    RestoreCheckpointRequest, RestoreCheckpointResponse, SessionResponse, SessionStateResponse,
+    SessionUsageResponse, SessionWithMessagesResponse, UpdateSessionRequest,
 };
 use crate::AppState;
 
@@ -28,6 +36,31 @@ pub struct ListSessionsQuery {
     pub working_dir: Option<String>,
 }
 
+/// Query params for searching messages
+#[derive(Debug, Deserialize)]
+pub struct SearchSessionsQuery {
+    /// Search text; every word must match
+    pub q: String,
+    /// Only search sessions in this working directory
+    pub working_dir: Option<String>,
+    /// Maximum number of results (default 20, max 100)
+    pub limit: Option<usize>,
+}
+
+/// Query params for exporting a session
+#[derive(Debug, Deserialize)]
+pub struct ExportSessionQuery {
+    /// `json` (default, re-importable), `markdown` or `html`
+    pub format: Option<String>,
+}
+
+/// Query params for importing a session bundle
+#[derive(Debug, Deserialize)]
+pub struct ImportSessionQuery {
+    /// Working directory for the imported session (defaults to the exported one)
+    pub working_dir: Option<String>,
+}
+
 /// Query params for retrieving a session with messages (pagination)
 #[derive(Debug, Deserialize)]
 pub struct GetSessionQuery {
@@ -42,6 +75,8 @@ pub fn router() -> Router<AppState> {
     Router::new()
         .route("/", get(list_sessions).post(create_session))
         .route("/directories", get(list_directories))
+        .route("/search", get(search_sessions))
+        .route("/import", post(import_session))
         .route(
             "/:id",
             get(get_session)
@@ -49,7 +84,16 @@ pub fn router() -> Router<AppState> {
                 .delete(delete_session),
         )
         .route("/:id/state", get(get_session_state))
+        .route(
+            "/:id/usage",
+            get(get_session_usage).put(update_session_budget),
+        )
         .route("/:id/pinch", post(pinch_session))
+        .route("/:id/fork", post(fork_session))
+        .route("/:id/export", get(export_session))
+        .route("/:id/checkpoints", get(list_checkpoints))
+        .route("/:id/checkpoints/:turn/diff", get(diff_checkpoint))
+        .route("/:id/checkpoints/:turn/restore", post(restore_checkpoint))
 }
 
 /// List all sessions, optionally filtered by working directory
@@ -82,6 +126,25 @@ async fn list_directories(
     Ok(Json(directories))
 }
 
+/// Full-text search over the messages of all sessions, best matches first
+async fn search_sessions(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Query(query): Query<SearchSessionsQuery>,
+) -> Result<Json<Vec<SearchHit>>, AppError> {
+    if query.q.trim().is_empty() {
+        return Err(AppError::BadRequest("Search query is empty".to_string()));
+    }
+
+    let db = Database::new(&state.db_path)?;
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let limit = query.limit.unwrap_or(20).clamp(1, 100);
+    let hits =
+        MessageSearch::new(&db).search(&query.q, query.working_dir.as_deref(), user_id, limit)?;
+
+    Ok(Json(hits))
+}
+
 /// Create a new session
 async fn create_session(
     State(state): State<AppState>,
@@ -297,6 +360,249 @@ async fn get_session_state(
     }))
 }
 
+/// Get token usage, cost and budget for a session
+async fn get_session_usage(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<Json<SessionUsageResponse>, AppError> {
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    let ledger = UsageLedger::new(session_manager.db());
+    Ok(Json(SessionUsageResponse {
+        totals: ledger.session_totals(&id)?,
+        by_model: ledger.session_usage_by_model(&id)?,
+        budget: ledger.get_budget(&id)?,
+        id,
+    }))
+}
+
+/// Set or clear a session's spending budget (null clears a limit)
+async fn update_session_budget(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+    Json(budget): Json<SessionBudget>,
+) -> Result<Json<SessionUsageResponse>, AppError> {
+    let invalid = |limit: Option<f64>| limit.is_some_and(|usd| !usd.is_finite() || usd <= 0.0);
+    if invalid(budget.soft_usd) || invalid(budget.hard_usd) {
+        return Err(AppError::BadRequest(
+            "Budget limits must be positive amounts in USD".to_string(),
+        ));
+    }
+
+    {
+        let db = Database::new(&state.db_path)?;
+        let session_manager = SessionManager::new(db);
+
+        let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+        if !session_manager.verify_session_ownership(&id, user_id)? {
+            return Err(AppError::NotFound(format!("Session {} not found", id)));
+        }
+
+        UsageLedger::new(session_manager.db()).set_budget(&id, &budget)?;
+    }
+
+    get_session_usage(State(state), user, Path(id)).await
+}
+
+/// List a session's file checkpoints, oldest first
+async fn list_checkpoints(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<Checkpoint>>, AppError> {
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    Ok(Json(CheckpointStore::new(session_manager.db()).list(&id)?))
+}
+
+/// Diff files changed since a checkpoint against their current contents
+async fn diff_checkpoint(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path((id, turn)): Path<(String, usize)>,
+) -> Result<Json<Vec<FileDiff>>, AppError> {
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    let diffs = checkpoint::diff_files(session_manager.db(), &id, turn)
+        .map_err(|e| AppError::NotFound(e.to_string()))?;
+    Ok(Json(diffs))
+}
+
+/// Restore files (and optionally the conversation) to before a turn
+async fn restore_checkpoint(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path((id, turn)): Path<(String, usize)>,
+    Json(req): Json<RestoreCheckpointRequest>,
+) -> Result<Json<RestoreCheckpointResponse>, AppError> {
+    if state.session_inputs.read().await.contains_key(&id) {
+        return Err(AppError::Conflict(
+            "Cannot restore a checkpoint while the agent is running".to_string(),
+        ));
+    }
+
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    let store = CheckpointStore::new(session_manager.db());
+    if store.get(&id, turn)?.is_none() {
+        return Err(AppError::NotFound(format!(
+            "No checkpoint for turn {}",
+            turn
+        )));
+    }
+
+    let summary = checkpoint::restore_files(session_manager.db(), &id, turn)?;
+    let messages_removed = if req.conversation && summary.failed.is_empty() {
+        session_manager.truncate_messages(&id, summary.message_count)?
+    } else {
+        0
+    };
+
+    Ok(Json(RestoreCheckpointResponse {
+        summary,
+        messages_removed,
+    }))
+}
+
+/// Fork a session into a new child that shares its first messages
+async fn fork_session(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+    Json(req): Json<ForkSessionRequest>,
+) -> Result<(StatusCode, Json<SessionResponse>), AppError> {
+    if state.session_inputs.read().await.contains_key(&id) {
+        return Err(AppError::Conflict(
+            "Cannot fork a session while the agent is running".to_string(),
+        ));
+    }
+
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    let total = session_manager.load_session_messages(&id)?.len();
+    if let Some(count) = req.message_count.filter(|&count| count > total) {
+        return Err(AppError::BadRequest(format!(
+            "Cannot fork at message {}: the session has {} messages",
+            count, total
+        )));
+    }
+
+    let title = req
+        .title
+        .as_deref()
+        .map(str::trim)
+        .filter(|t| !t.is_empty());
+    let model = req
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|m| !m.is_empty());
+    let fork_id = session_manager.fork_session(&id, req.message_count, title, model)?;
+
+    let fork = session_manager
+        .get_session(&fork_id)?
+        .ok_or_else(|| AppError::Internal("Failed to fetch forked session".to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(fork.into())))
+}
+
+/// Export a session as a JSON bundle, Markdown transcript or HTML page
+async fn export_session(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Path(id): Path<String>,
+    Query(query): Query<ExportSessionQuery>,
+) -> Result<impl IntoResponse, AppError> {
+    let format: ExportFormat = query
+        .format
+        .as_deref()
+        .unwrap_or("json")
+        .parse()
+        .map_err(AppError::BadRequest)?;
+
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    if !session_manager.verify_session_ownership(&id, user_id)? {
+        return Err(AppError::NotFound(format!("Session {} not found", id)));
+    }
+
+    let body = format.render(&session_manager.export_session(&id)?)?;
+    let disposition = format!(
+        "attachment; filename=\"krusty-session-{}.{}\"",
+        id.get(..8).unwrap_or(&id),
+        format.extension()
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        body,
+    ))
+}
+
+/// Import a JSON session bundle as a new session
+async fn import_session(
+    State(state): State<AppState>,
+    user: Option<CurrentUser>,
+    Query(query): Query<ImportSessionQuery>,
+    body: String,
+) -> Result<(StatusCode, Json<SessionResponse>), AppError> {
+    let bundle = parse_bundle(&body).map_err(|e| AppError::BadRequest(e.to_string()))?;
+
+    let db = Database::new(&state.db_path)?;
+    let session_manager = SessionManager::new(db);
+
+    let user_id = user.as_ref().and_then(|u| u.0.user_id.as_deref());
+    let working_dir = query
+        .working_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty());
+    let session_id = session_manager.import_session(&bundle, working_dir, user_id)?;
+
+    let session = session_manager
+        .get_session(&session_id)?
+        .ok_or_else(|| AppError::Internal("Failed to fetch imported session".to_string()))?;
+
+    Ok((StatusCode::CREATED, Json(session.into())))
+}
+
 /// Pinch a session - create a child session with summarized context
 async fn pinch_session(
     State(state): State<AppState>,
@@ -1,6 +1,8 @@
 //! Request and response types for the API
 
-use krusty_core::storage::{SessionInfo, WorkMode};
+use krusty_core::agent::PermissionRule;
+use krusty_core::storage::{ModelUsage, SessionBudget, SessionInfo, UsageTotals, WorkMode};
+use krusty_core::tools::checkpoint::RestoreSummary;
 use krusty_core::tools::registry::PermissionMode;
 use serde::{de, Deserialize, Deserializer, Serialize};
 
@@ -33,6 +35,17 @@ pub struct PinchRequest {
     pub direction: Option<String>,
 }
 
+/// Fork a session at an earlier message
+#[derive(Deserialize)]
+pub struct ForkSessionRequest {
+    /// Messages to carry into the fork (default: all of them)
+    pub message_count: Option<usize>,
+    /// Title for the fork (default: "<source title> (fork)")
+    pub title: Option<String>,
+    /// Model for the fork (default: the source session's model)
+    pub model: Option<String>,
+}
+
 #[derive(Serialize)]
 pub struct PinchResponse {
     /// The new child session
@@ -52,6 +65,8 @@ pub struct SessionResponse {
     pub updated_at: String,
     pub token_count: Option<usize>,
     pub parent_session_id: Option<String>,
+    /// Parent messages the session was forked with (null unless forked)
+    pub fork_point: Option<usize>,
     pub working_dir: Option<String>,
     pub mode: WorkMode,
     pub model: Option<String>,
@@ -66,6 +81,7 @@ impl From<SessionInfo> for SessionResponse {
             updated_at: s.updated_at.to_rfc3339(),
             token_count: s.token_count,
             parent_session_id: s.parent_session_id,
+            fork_point: s.fork_point,
             working_dir: s.working_dir,
             mode: s.work_mode,
             model: s.model,
@@ -95,6 +111,35 @@ pub struct SessionStateResponse {
     pub mode: WorkMode,
 }
 
+/// Token usage and cost for a session
+#[derive(Serialize)]
+pub struct SessionUsageResponse {
+    /// Session ID
+    pub id: String,
+    /// Totals across all model calls
+    pub totals: UsageTotals,
+    /// Totals per provider/model, most expensive first
+    pub by_model: Vec<ModelUsage>,
+    /// Spending limits (null when unset)
+    pub budget: SessionBudget,
+}
+
+/// Restore files (and optionally the conversation) to a checkpoint
+#[derive(Deserialize)]
+pub struct RestoreCheckpointRequest {
+    /// Also drop the messages from the checkpoint's turn onward
+    #[serde(default)]
+    pub conversation: bool,
+}
+
+#[derive(Serialize)]
+pub struct RestoreCheckpointResponse {
+    #[serde(flatten)]
+    pub summary: RestoreSummary,
+    /// Messages removed from the conversation (0 unless requested)
+    pub messages_removed: usize,
+}
+
 #[derive(Serialize)]
 pub struct MessageResponse {
     pub role: String,
@@ -257,6 +302,9 @@ pub struct ToolApprovalRequest {
     pub session_id: String,
     pub tool_call_id: String,
     pub approved: bool,
+    /// Persist an allow rule for matching calls in this project
+    #[serde(default)]
+    pub always_allow: bool,
 }
 
 // ============================================================================
@@ -519,6 +567,32 @@ pub enum AgenticEvent {
         prompt_tokens: usize,
         completion_tokens: usize,
     },
+    /// Cost of the last model call and the session total (USD)
+    Cost {
+        call_cost_usd: Option<f64>,
+        session_cost_usd: f64,
+    },
+    /// Session spend crossed its soft budget
+    BudgetWarning { spent_usd: f64, limit_usd: f64 },
+    /// Session spend reached its hard budget; the loop stopped
+    BudgetExceeded { spent_usd: f64, limit_usd: f64 },
+    /// Context usage crossed the auto-pinch threshold
+    ContextNearLimit {
+        tokens_used: usize,
+        context_window: usize,
+    },
+    /// The run continues in a linked session created by auto-pinch
+    SessionPinched {
+        previous_session_id: String,
+        session_id: String,
+        title: String,
+    },
+    /// Older turns were summarized in place; the session id is unchanged
+    ConversationCompacted {
+        summarized_messages: usize,
+        elided_results: usize,
+        tokens_before: usize,
+    },
     /// Agentic loop finished
     Finish { session_id: String },
     /// Session title updated (from Haiku)
@@ -531,8 +605,10 @@ pub enum AgenticEvent {
     },
     /// Tool was approved by user
     ToolApproved { id: String },
-    /// Tool was denied by user
+    /// Tool was denied by user or by a permission rule
     ToolDenied { id: String },
+    /// An "always allow" answer saved a permission rule
+    PermissionRuleAdded { rule: PermissionRule },
     /// Error occurred
     Error { error: String },
 }
@@ -589,6 +665,7 @@ impl From<krusty_core::agent::LoopEvent> for AgenticEvent {
             },
             LoopEvent::ToolApproved { id } => Self::ToolApproved { id },
             LoopEvent::ToolDenied { id } => Self::ToolDenied { id },
+            LoopEvent::PermissionRuleAdded { rule } => Self::PermissionRuleAdded { rule },
             // Server-side tool events — pass through as tool execution events
             LoopEvent::ServerToolStart { id, name } => Self::ToolExecuting { id, name },
             LoopEvent::ServerToolComplete { id, name } => Self::ToolResult {
@@ -640,6 +717,52 @@ impl From<krusty_core::agent::LoopEvent> for AgenticEvent {
                 prompt_tokens,
                 completion_tokens,
             },
+            LoopEvent::Cost {
+                call_cost_usd,
+                session_cost_usd,
+            } => Self::Cost {
+                call_cost_usd,
+                session_cost_usd,
+            },
+            LoopEvent::BudgetWarning {
+                spent_usd,
+                limit_usd,
+            } => Self::BudgetWarning {
+                spent_usd,
+                limit_usd,
+            },
+            LoopEvent::BudgetExceeded {
+                spent_usd,
+                limit_usd,
+            } => Self::BudgetExceeded {
+                spent_usd,
+                limit_usd,
+            },
+            LoopEvent::ContextNearLimit {
+                tokens_used,
+                context_window,
+            } => Self::ContextNearLimit {
+                tokens_used,
+                context_window,
+            },
+            LoopEvent::SessionPinched {
+                previous_session_id,
+                session_id,
+                title,
+            } => Self::SessionPinched {
+                previous_session_id,
+                session_id,
+                title,
+            },
+            LoopEvent::ConversationCompacted {
+                summarized_messages,
+                elided_results,
+                tokens_before,
+            } => Self::ConversationCompacted {
+                summarized_messages,
+                elided_results,
+                tokens_before,
+            },
             LoopEvent::TitleGenerated { title } => Self::TitleUpdate { title },
             LoopEvent::Finished { session_id } => Self::Finish { session_id },
             LoopEvent::Error { error } => Self::Error { error },